@@ -0,0 +1,72 @@
+// Jelly wobble for fruit (see src/wobble.rs). The quad is drawn bigger than
+// the fruit, so the fruit has room to bulge. At rest it's a plain square of
+// `material.color`, the way the sprite was.
+
+#import bevy_sprite::{
+    mesh2d_functions as mesh_functions,
+    mesh2d_vertex_output::VertexOutput,
+    mesh2d_view_bindings::view,
+}
+
+#ifdef TONEMAP_IN_SHADER
+#import bevy_core_pipeline::tonemapping
+#endif
+
+struct WobbleMaterial {
+    color: vec4<f32>,
+    // The fruit's size at rest
+    size: vec2<f32>,
+    // 0 with no player near, 1 with one touching
+    proximity: f32,
+    // How far through the collection pop, from 0 to 1 (gone)
+    pop: f32,
+    // Game seconds, and an offset so fruit side by side don't wobble in step
+    time: f32,
+    phase: f32,
+};
+
+@group(2) @binding(0) var<uniform> material: WobbleMaterial;
+
+// How much bigger than the fruit the quad is
+const MARGIN: f32 = 1.5;
+
+struct Vertex {
+    @builtin(instance_index) instance_index: u32,
+    @location(0) position: vec3<f32>,
+    @location(2) uv: vec2<f32>,
+};
+
+// Squash and stretch: wider as it gets shorter, bouncing while a player is
+// near and flattening out as it pops
+@vertex
+fn vertex(vertex: Vertex) -> VertexOutput {
+    let squash = sin(material.time * 9.0 + material.phase) * 0.06 * material.proximity + material.pop * 0.35;
+    let position = vertex.position.xy * material.size * MARGIN * vec2<f32>(1.0 + squash, 1.0 - squash);
+
+    var out: VertexOutput;
+    let world_from_local = mesh_functions::get_world_from_local(vertex.instance_index);
+    out.world_position = mesh_functions::mesh2d_position_local_to_world(world_from_local, vec4<f32>(position, vertex.position.z, 1.0));
+    out.position = mesh_functions::mesh2d_position_world_to_clip(out.world_position);
+    out.uv = vertex.uv;
+    return out;
+}
+
+// The square's edges ripple, the more the closer a player is, and it fades
+// out as it pops
+@fragment
+fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+    // From the fruit's middle, where its square at rest runs from -0.5 to 0.5
+    let p = (in.uv - 0.5) * MARGIN;
+    let ripple = material.proximity * 0.06 + material.pop * 0.1;
+    let edge = 0.5 + ripple * sin(atan2(p.y, p.x) * 4.0 + material.time * 7.0 + material.phase);
+    if max(abs(p.x), abs(p.y)) > edge {
+        discard;
+    }
+
+    var color = material.color;
+    color.a *= 1.0 - material.pop;
+#ifdef TONEMAP_IN_SHADER
+    color = tonemapping::tone_mapping(color, view.color_grading);
+#endif
+    return color;
+}
@@ -0,0 +1,89 @@
+// Benchmarks for the heavier core routines: level generation, the
+// reachability and validation checks, and the collision routine and broad
+// phase against a stress world (see `rustbevy_core::stress`).
+//
+// `cargo bench -p rustbevy_core --bench core` runs them all; criterion keeps the last run
+// under `target/criterion` and reports how each one moved since.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use rustbevy_core::config::{PLAYER_SIZE, PLAYER_SPAWN};
+use rustbevy_core::difficulty::DifficultyCurve;
+use rustbevy_core::generation::generate_platform_layout;
+use rustbevy_core::level::Level;
+use rustbevy_core::physics::{resolve_platform_collisions, Body, JumpTimers, Tuning};
+use rustbevy_core::reachability::{fruit_reachable, reachable_from, JumpProfile};
+use rustbevy_core::spatial::SpatialGrid;
+use rustbevy_core::stress::{stress_level, stress_platforms, STRESS_PLATFORMS};
+use rustbevy_core::validation::validate_level;
+use rustbevy_core::world::WorldBounds;
+
+const SEED: u64 = 7;
+// A level late enough to have a bit of everything
+const LEVEL: u32 = 15;
+const DT: f32 = 1.0 / 60.0;
+
+fn profile() -> JumpProfile {
+    JumpProfile::new(&Tuning::default(), PLAYER_SIZE)
+}
+
+fn generation(c: &mut Criterion) {
+    let curve = DifficultyCurve::default();
+    let layout = curve.layout(curve.base(LEVEL));
+    let profile = profile();
+    c.bench_function("generation/platform_layout", |b| b.iter(|| generate_platform_layout(black_box(SEED), &layout, WorldBounds::CLASSIC)));
+    c.bench_function("generation/level", |b| b.iter(|| Level::generate(black_box(SEED), LEVEL, curve.base(LEVEL), &profile, &curve)));
+    c.bench_function("generation/stress_platforms", |b| b.iter(|| stress_platforms(black_box(SEED), STRESS_PLATFORMS, WorldBounds::CLASSIC)));
+}
+
+fn reachability(c: &mut Criterion) {
+    let profile = profile();
+    let curve = DifficultyCurve::default();
+    let level = Level::generate(SEED, LEVEL, curve.base(LEVEL), &profile, &curve);
+    let stress = stress_level(SEED);
+    let far_corner = stress.bonus_fruit.last().copied().unwrap_or(PLAYER_SPAWN);
+    c.bench_function("reachability/level", |b| b.iter(|| reachable_from(black_box(&level.platforms), 0, &profile)));
+    c.bench_function("reachability/stress", |b| b.iter(|| reachable_from(black_box(&stress.platforms), 0, &profile)));
+    c.bench_function("reachability/stress_fruit", |b| b.iter(|| fruit_reachable(black_box(&stress.platforms), far_corner, &profile)));
+    c.bench_function("validation/level", |b| b.iter(|| validate_level(black_box(&level), PLAYER_SPAWN, &profile)));
+    c.bench_function("validation/stress", |b| b.iter(|| validate_level(black_box(&stress), PLAYER_SPAWN, &profile)));
+}
+
+// A player falling onto the middle of the stress world, resolved against every
+// platform, as before the broad phase, and against the broad phase's candidates
+fn collision(c: &mut Criterion) {
+    let platforms = stress_platforms(SEED, STRESS_PLATFORMS, WorldBounds::CLASSIC);
+    let jumps = JumpTimers::default();
+    let target = platforms[platforms.len() / 2];
+    let body = Body { x: target.x, y: target.y + PLAYER_SIZE / 2.0, velocity_x: 200.0, velocity_y: -600.0, ..Body::at_spawn() };
+    let mut grid = SpatialGrid::default();
+    for (index, platform) in platforms.iter().enumerate() {
+        grid.insert(index, (platform.x, platform.y), (platform.width / 2.0, platform.half_extent_y()));
+    }
+    c.bench_function("collision/every_platform", |b| {
+        b.iter(|| {
+            let mut body = body;
+            resolve_platform_collisions(&mut body, black_box(&platforms), &jumps, DT);
+            body
+        })
+    });
+    c.bench_function("collision/broad_phase", |b| {
+        b.iter(|| {
+            let mut body = body;
+            let reach = |speed: f32| body.size * 1.5 + speed.abs() * DT;
+            let near: Vec<_> = grid.query((body.x, body.y), (reach(body.velocity_x), reach(body.velocity_y))).into_iter().map(|index| platforms[index]).collect();
+            resolve_platform_collisions(&mut body, black_box(&near), &jumps, DT);
+            body
+        })
+    });
+    c.bench_function("collision/grid_rebuild", |b| {
+        b.iter(|| {
+            grid.clear();
+            for (index, platform) in platforms.iter().enumerate() {
+                grid.insert(index, (platform.x, platform.y), (platform.width / 2.0, platform.half_extent_y()));
+            }
+        })
+    });
+}
+
+criterion_group!(benches, generation, reachability, collision);
+criterion_main!(benches);
@@ -0,0 +1,76 @@
+// Optional assists for players who want an easier time. Runs played with any
+// assist on are marked as assisted on the results screens.
+
+use crate::autorun::{ControlScheme, AUTO_RUN_SPEED};
+
+pub const ASSIST_JUMP_MULTIPLIER: f32 = 1.25;
+pub const ASSIST_GAME_SPEED: f32 = 0.7;
+// Lives lost on one level before the level skip assist offers to skip it
+pub const LEVEL_SKIP_FAILED_ATTEMPTS: u32 = 2;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Assists {
+    pub higher_jump: bool,
+    pub slow_motion: bool,
+    pub infinite_lives: bool,
+    // Spikes and lava don't cost a life
+    pub harmless_hazards: bool,
+    // Highlight the platforms one jump away from where the player stands
+    pub reachable_hints: bool,
+    // Show where a jump from where the player stands would go
+    pub jump_arc: bool,
+    // Skip a level after failing it a few times
+    pub level_skip: bool,
+    // Auto-run with one or two switches instead of the usual keys
+    pub controls: ControlScheme,
+}
+
+impl Assists {
+    pub fn any(self) -> bool {
+        self.higher_jump || self.slow_motion || self.infinite_lives || self.harmless_hazards || self.reachable_hints || self.jump_arc || self.level_skip || self.controls.auto_run()
+    }
+
+    pub fn jump_speed(self, base_jump_speed: f32) -> f32 {
+        if self.higher_jump {
+            base_jump_speed * ASSIST_JUMP_MULTIPLIER
+        } else {
+            base_jump_speed
+        }
+    }
+
+    pub fn player_speed(self, base_player_speed: f32) -> f32 {
+        if self.controls.auto_run() {
+            base_player_speed * AUTO_RUN_SPEED
+        } else {
+            base_player_speed
+        }
+    }
+
+    // Multiplier for how fast game time passes
+    pub fn game_speed(self) -> f32 {
+        if self.slow_motion {
+            ASSIST_GAME_SPEED
+        } else {
+            1.0
+        }
+    }
+
+    // Names of the assists that are on, for results screens
+    pub fn labels(self) -> Vec<&'static str> {
+        [
+            (self.higher_jump, "Higher jump"),
+            (self.slow_motion, "Slower game speed"),
+            (self.infinite_lives, "Infinite lives"),
+            (self.harmless_hazards, "No hazard damage"),
+            (self.reachable_hints, "Reachable platform hints"),
+            (self.jump_arc, "Jump arc preview"),
+            (self.level_skip, "Level skip"),
+            (self.controls == ControlScheme::TwoButton, "Two-button controls"),
+            (self.controls == ControlScheme::OneSwitch, "One-switch controls"),
+        ]
+        .into_iter()
+        .filter(|(on, _)| *on)
+        .map(|(_, label)| label)
+        .collect()
+    }
+}
@@ -0,0 +1,98 @@
+// Auto-run controls for players who can press only one or two switches.
+//
+// The player runs on its own and turns around on request. In one-switch mode
+// the switch jumps and holding it turns the player around (once per hold); in
+// two-button mode the switch only jumps and a second button turns. Running is
+// slower than usual so there is time to react, and levels are checked against
+// that slower run (see `JumpProfile::with_auto_run`).
+
+// Fraction of the normal run speed
+pub const AUTO_RUN_SPEED: f32 = 0.7;
+// How long the switch is held before the player turns around
+pub const TURN_HOLD_SECONDS: f32 = 0.35;
+// How long a player running across a platform needs to react to it
+pub const AUTO_RUN_REACTION_SECONDS: f32 = 0.4;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ControlScheme {
+    #[default]
+    Standard,
+    TwoButton,
+    OneSwitch,
+}
+
+impl ControlScheme {
+    pub fn next(self) -> Self {
+        match self {
+            Self::Standard => Self::TwoButton,
+            Self::TwoButton => Self::OneSwitch,
+            Self::OneSwitch => Self::Standard,
+        }
+    }
+
+    // For the assists line on the main menu
+    pub fn description(self) -> &'static str {
+        match self {
+            Self::Standard => "standard",
+            Self::TwoButton => "two-button (auto-run, jump button jumps, down turns)",
+            Self::OneSwitch => "one-switch (auto-run, press to jump, hold to turn)",
+        }
+    }
+
+    pub fn auto_run(self) -> bool {
+        self != Self::Standard
+    }
+}
+
+// Which way an auto-running player is heading, and how long the switch has
+// been held
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AutoRun {
+    direction: f32,
+    held: f32,
+    turned: bool,
+    turn_was_held: bool,
+}
+
+impl Default for AutoRun {
+    fn default() -> Self {
+        Self { direction: 1.0, held: 0.0, turned: false, turn_was_held: false }
+    }
+}
+
+impl AutoRun {
+    // The horizontal input for this step: -1 or 1. `switch_held` is the jump
+    // switch, `turn_held` the second button (only read in two-button mode).
+    pub fn update(&mut self, scheme: ControlScheme, switch_held: bool, turn_held: bool, dt: f32) -> f32 {
+        match scheme {
+            ControlScheme::Standard => {}
+            ControlScheme::TwoButton => {
+                if turn_held && !self.turn_was_held {
+                    self.turn();
+                }
+            }
+            ControlScheme::OneSwitch => {
+                if switch_held {
+                    self.held += dt;
+                    if self.held >= TURN_HOLD_SECONDS && !self.turned {
+                        self.turn();
+                        self.turned = true;
+                    }
+                } else {
+                    self.held = 0.0;
+                    self.turned = false;
+                }
+            }
+        }
+        self.turn_was_held = turn_held;
+        self.direction
+    }
+
+    pub fn turn(&mut self) {
+        self.direction = -self.direction;
+    }
+
+    pub fn direction(&self) -> f32 {
+        self.direction
+    }
+}
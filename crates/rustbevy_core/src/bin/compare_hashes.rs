@@ -0,0 +1,61 @@
+// Desync finder.
+//
+// Compares two state hash logs (written by the game's desync probe, F11, from
+// two runs of one replay or from two peers on one seed) and reports the first
+// tick and stage where they disagree.
+//
+// Usage: compare_hashes <log> <log>
+//
+// Exit codes: 0 = logs match, 1 = logs diverge, 2 = bad arguments or log file.
+
+use std::process::ExitCode;
+
+use rustbevy_core::snapshot::{Divergence, HashLog};
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let [left_path, right_path] = args.as_slice() else {
+        eprintln!("usage: compare_hashes <log> <log>");
+        return ExitCode::from(2);
+    };
+
+    let (left, right) = match (read_log(left_path), read_log(right_path)) {
+        (Ok(left), Ok(right)) => (left, right),
+        (Err(message), _) | (_, Err(message)) => {
+            eprintln!("{}", message);
+            return ExitCode::from(2);
+        }
+    };
+
+    match left.first_divergence(&right) {
+        None => {
+            println!("MATCH ({} hashes)", left.entries.len());
+            ExitCode::SUCCESS
+        }
+        Some(Divergence::Hash { tick, stage, last_match }) => {
+            match last_match {
+                Some(last_match) => println!("DIVERGED at tick {}: state matches after '{}' but not after '{}'", tick, last_match, stage),
+                None => println!("DIVERGED at tick {}: state differs at its first stage '{}' (changed between ticks)", tick, stage),
+            }
+            ExitCode::from(1)
+        }
+        Some(Divergence::Length { matched }) => {
+            println!(
+                "DIVERGED: the first {} hashes match, then one log ends ({} vs {} hashes)",
+                matched,
+                left.entries.len(),
+                right.entries.len()
+            );
+            ExitCode::from(1)
+        }
+        Some(Divergence::Stage { tick, left, right }) => {
+            eprintln!("logs hash different stages at tick {} ('{}' vs '{}'); were they recorded by the same build?", tick, left, right);
+            ExitCode::from(2)
+        }
+    }
+}
+
+fn read_log(path: &str) -> Result<HashLog, String> {
+    let text = std::fs::read_to_string(path).map_err(|error| format!("could not read {}: {}", path, error))?;
+    HashLog::parse(&text).map_err(|error| format!("invalid hash log {}: {}", path, error))
+}
@@ -0,0 +1,74 @@
+// Headless run validator.
+//
+// Re-simulates a recorded replay with the same simulation code the game uses
+// and checks that the claimed level and time actually happen.
+//
+// Usage: validate_run <replay file> [--seed <u64>]
+//
+// Exit codes: 0 = claim verified, 1 = claim rejected, 2 = bad arguments or replay file.
+
+use std::process::ExitCode;
+
+use rustbevy_core::replay::Replay;
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let (path, seed_override) = match parse_args(&args) {
+        Ok(parsed) => parsed,
+        Err(message) => {
+            eprintln!("{}", message);
+            eprintln!("usage: validate_run <replay file> [--seed <u64>]");
+            return ExitCode::from(2);
+        }
+    };
+
+    let text = match std::fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(error) => {
+            eprintln!("could not read {}: {}", path, error);
+            return ExitCode::from(2);
+        }
+    };
+    let mut replay = match Replay::parse(&text) {
+        Ok(replay) => replay,
+        Err(error) => {
+            eprintln!("invalid replay {}: {}", path, error);
+            return ExitCode::from(2);
+        }
+    };
+    if let Some(seed) = seed_override {
+        replay.seed = seed;
+    }
+
+    let outcome = replay.simulate();
+    let claimed_seconds = replay.claimed_ticks as f32 * replay.dt;
+    let simulated_seconds = outcome.ticks as f32 * replay.dt;
+    println!("seed:      {}", replay.seed);
+    println!("claimed:   level {} in {} ticks ({:.2}s)", replay.claimed_level, replay.claimed_ticks, claimed_seconds);
+    println!("simulated: level {} in {} ticks ({:.2}s), {} lives left", outcome.level, outcome.ticks, simulated_seconds, outcome.lives);
+
+    if outcome.level == replay.claimed_level && outcome.ticks == replay.claimed_ticks {
+        println!("VALID");
+        ExitCode::SUCCESS
+    } else {
+        println!("REJECTED");
+        ExitCode::from(1)
+    }
+}
+
+fn parse_args(args: &[String]) -> Result<(String, Option<u64>), String> {
+    let mut path = None;
+    let mut seed = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--seed" => {
+                let value = args.next().ok_or("--seed needs a value")?;
+                seed = Some(value.parse().map_err(|_| format!("invalid seed '{}'", value))?);
+            }
+            other if path.is_none() => path = Some(other.to_string()),
+            other => return Err(format!("unexpected argument '{}'", other)),
+        }
+    }
+    Ok((path.ok_or("missing replay file")?, seed))
+}
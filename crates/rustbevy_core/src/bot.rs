@@ -0,0 +1,87 @@
+// A simple computer-controlled player for attract mode and demos.
+//
+// The bot picks a platform to head for, walks towards it and jumps when the
+// target is above it or it is about to run off the edge of the platform it is
+// standing on. It produces the same `SimInput` a human would, so it runs on the
+// regular physics step.
+
+use crate::generation::PlatformSpec;
+use crate::physics::{Body, SimInput};
+use crate::rng::SeededRng;
+
+// Give up on a target that can't be reached after this long
+const RETARGET_SECONDS: f32 = 4.0;
+// How close to a platform edge the bot gets before it jumps
+const EDGE_MARGIN: f32 = 15.0;
+
+pub struct Bot {
+    rng: SeededRng,
+    target: Option<usize>,
+    target_seconds: f32,
+}
+
+impl Bot {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: SeededRng::new(seed),
+            target: None,
+            target_seconds: 0.0,
+        }
+    }
+
+    // Decides the buttons to hold for the next tick
+    pub fn think(&mut self, body: &Body, platforms: &[PlatformSpec], dt: f32) -> SimInput {
+        if platforms.is_empty() {
+            return SimInput::default();
+        }
+
+        let standing_on = platforms.iter().position(|platform| stands_on(body, platform));
+        self.target_seconds += dt;
+        let reached = self.target.is_some() && self.target == standing_on;
+        if self.target.is_none() || reached || self.target_seconds > RETARGET_SECONDS {
+            self.pick_target(platforms.len(), standing_on);
+        }
+
+        let Some(target) = self.target.and_then(|index| platforms.get(index)) else {
+            return SimInput::default();
+        };
+        let dx = target.x - body.x;
+        let input_direction = if dx.abs() > 10.0 { dx.signum() } else { 0.0 };
+
+        let feet = body.y - body.size / 2.0;
+        let target_above = target.y + target.height / 2.0 > feet + 5.0;
+        let near_edge = standing_on.is_some_and(|index| {
+            let platform = &platforms[index];
+            let edge = platform.x + input_direction * platform.width / 2.0;
+            input_direction != 0.0 && (edge - body.x) * input_direction < EDGE_MARGIN
+        });
+
+        SimInput {
+            left: input_direction < 0.0,
+            right: input_direction > 0.0,
+            jump: body.grounded && (target_above || near_edge),
+            up: false,
+            down: false,
+            throw: false,
+        }
+    }
+
+    fn pick_target(&mut self, platform_count: usize, standing_on: Option<usize>) {
+        self.target_seconds = 0.0;
+        if platform_count < 2 {
+            self.target = Some(0);
+            return;
+        }
+        let mut index = self.rng.below(platform_count);
+        if Some(index) == standing_on {
+            index = (index + 1) % platform_count;
+        }
+        self.target = Some(index);
+    }
+}
+
+fn stands_on(body: &Body, platform: &PlatformSpec) -> bool {
+    let feet = body.y - body.size / 2.0;
+    let top = platform.y + platform.height / 2.0;
+    body.grounded && (body.x - platform.x).abs() <= platform.width / 2.0 + body.size / 2.0 && (feet - top).abs() < 6.0
+}
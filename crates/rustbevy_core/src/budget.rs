@@ -0,0 +1,53 @@
+// Entity budgets: caps on how many of each kind of non-essential entity may
+// exist at once, so a burst of effects or a runaway spawner can't push frame
+// times up on a slow machine. When a category goes over its cap the
+// lowest-priority entities are dropped first, and among equals the newest, so
+// what is already on screen stays put and the extra spawns are what go.
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BudgetCategory {
+    Particles,
+    Enemies,
+    Decorations,
+}
+
+impl BudgetCategory {
+    pub const ALL: [Self; 3] = [Self::Particles, Self::Enemies, Self::Decorations];
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Budget {
+    pub particles: usize,
+    pub enemies: usize,
+    pub decorations: usize,
+}
+
+impl Default for Budget {
+    // Room for the biggest celebration, a crowded level and the menu backdrop
+    fn default() -> Self {
+        Self { particles: 500, enemies: 24, decorations: 80 }
+    }
+}
+
+impl Budget {
+    pub fn cap(&self, category: BudgetCategory) -> usize {
+        match category {
+            BudgetCategory::Particles => self.particles,
+            BudgetCategory::Enemies => self.enemies,
+            BudgetCategory::Decorations => self.decorations,
+        }
+    }
+}
+
+// Which of `entries` (priority, spawn order) to drop to get down to `cap`, as
+// indices into `entries`. Higher priorities are kept; a later spawn order is
+// a newer entity.
+pub fn over_budget(entries: &[(u8, u64)], cap: usize) -> Vec<usize> {
+    if entries.len() <= cap {
+        return Vec::new();
+    }
+    let mut order: Vec<usize> = (0..entries.len()).collect();
+    order.sort_by_key(|&index| (entries[index].0, std::cmp::Reverse(entries[index].1)));
+    order.truncate(entries.len() - cap);
+    order
+}
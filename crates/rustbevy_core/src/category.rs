@@ -0,0 +1,107 @@
+// Speedrun categories for Classic runs. A categorized run is timed from its
+// start until it reaches FINISH_LEVEL; each category has its own rules on top
+// of that and keeps its own best times.
+
+pub const FINISH_LEVEL: u32 = 10;
+// Best times kept per category
+pub const BEST_TIMES_KEPT: usize = 5;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum RunCategory {
+    #[default]
+    Any,
+    // Every fruit and every coin
+    Full,
+    // No modifiers drafted
+    Low,
+}
+
+impl RunCategory {
+    pub const ALL: [RunCategory; 3] = [RunCategory::Any, RunCategory::Full, RunCategory::Low];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Any => "Any%",
+            Self::Full => "100%",
+            Self::Low => "Low%",
+        }
+    }
+
+    pub fn rules(self) -> &'static str {
+        match self {
+            Self::Any => "anything goes",
+            Self::Full => "every fruit and coin",
+            Self::Low => "skip every draft",
+        }
+    }
+
+    // Name used in the best times file
+    pub fn key(self) -> &'static str {
+        match self {
+            Self::Any => "any",
+            Self::Full => "full",
+            Self::Low => "low",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|category| category.key() == key)
+    }
+
+    pub fn next(self) -> Self {
+        let index = Self::ALL.iter().position(|category| *category == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    // Why a run no longer counts for this category, if it doesn't
+    pub fn broken_by(self, stats: &RunStats) -> Option<&'static str> {
+        if stats.started_mid_run {
+            return Some("the run didn't start at level 1");
+        }
+        match self {
+            Self::Any => None,
+            Self::Full => (stats.coins_missed > 0).then_some("a coin was left behind"),
+            Self::Low => (stats.modifiers_picked > 0).then_some("a modifier was drafted"),
+        }
+    }
+}
+
+// What a run has done so far, as far as the categories care
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct RunStats {
+    pub seconds: f32,
+    pub modifiers_picked: u32,
+    pub coins_missed: u32,
+    pub levels_skipped: u32,
+    pub started_mid_run: bool,
+    pub finished: bool,
+}
+
+// Fastest finishes of one category, quickest first
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BestTimes {
+    pub times: Vec<f32>,
+}
+
+impl BestTimes {
+    // Adds a finish; returns its place (1 = new best) if it made the table
+    pub fn record(&mut self, seconds: f32) -> Option<usize> {
+        let place = self.times.iter().position(|time| seconds < *time).unwrap_or(self.times.len());
+        if place >= BEST_TIMES_KEPT {
+            return None;
+        }
+        self.times.insert(place, seconds);
+        self.times.truncate(BEST_TIMES_KEPT);
+        Some(place + 1)
+    }
+
+    pub fn best(&self) -> Option<f32> {
+        self.times.first().copied()
+    }
+}
+
+// `m:ss.cc`
+pub fn format_time(seconds: f32) -> String {
+    let centiseconds = (seconds.max(0.0) * 100.0).round() as u32;
+    format!("{}:{:02}.{:02}", centiseconds / 6000, centiseconds / 100 % 60, centiseconds % 100)
+}
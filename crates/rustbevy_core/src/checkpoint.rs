@@ -0,0 +1,67 @@
+// Checkpoints in Classic levels. Levels span several screens, so falling near
+// the far end would otherwise mean crossing the whole level again. Generated
+// levels get a checkpoint flag in each screen-sized part of the level away from
+// the start; touching one makes it where the player respawns after losing a
+// life, until the level is done.
+
+use crate::config::{PLATFORM_HEIGHT, WINDOW_HEIGHT, WINDOW_WIDTH};
+use crate::generation::{PlatformSpec, STARTING_PLATFORM};
+use crate::physics::Body;
+use crate::world::WorldBounds;
+
+pub const CHECKPOINT_WIDTH: f32 = 10.0;
+pub const CHECKPOINT_HEIGHT: f32 = 60.0;
+// The player drops back in this far above the checkpoint's platform, like the
+// level's own spawn point above the starting platform
+const RESPAWN_HEIGHT: f32 = 100.0;
+
+// Where a checkpoint's flag stands on `platform`: the middle of its top
+pub fn checkpoint_on(platform: &PlatformSpec) -> (f32, f32) {
+    (platform.x, platform.y + PLATFORM_HEIGHT / 2.0)
+}
+
+// Where a player touching the checkpoint at `position` respawns
+pub fn respawn_point(position: (f32, f32)) -> (f32, f32) {
+    (position.0, position.1 - PLATFORM_HEIGHT / 2.0 + RESPAWN_HEIGHT)
+}
+
+// Whether `body` touches the flag standing at `position`
+pub fn touches_checkpoint(body: &Body, position: (f32, f32)) -> bool {
+    let center_y = position.1 + CHECKPOINT_HEIGHT / 2.0;
+    (body.x - position.0).abs() < (body.size + CHECKPOINT_WIDTH) / 2.0 && (body.y - center_y).abs() < (body.size + CHECKPOINT_HEIGHT) / 2.0
+}
+
+// One checkpoint per screen-sized part of `bounds` without the starting
+// platform in it, on the platform nearest that part's centre. Platforms listed
+// in `taken` (indices into `platforms`) are left out.
+pub fn place_checkpoints(platforms: &[PlatformSpec], taken: &[usize], bounds: WorldBounds) -> Vec<(f32, f32)> {
+    let columns = (bounds.width / WINDOW_WIDTH).round().max(1.0) as usize;
+    let rows = (bounds.height / WINDOW_HEIGHT).round().max(1.0) as usize;
+    let cell = |x: f32, y: f32| {
+        let column = (((x + bounds.half_width()) / WINDOW_WIDTH) as usize).min(columns - 1);
+        let row = (((y + bounds.half_height()) / WINDOW_HEIGHT) as usize).min(rows - 1);
+        (column, row)
+    };
+    let start = cell(STARTING_PLATFORM.x, STARTING_PLATFORM.y);
+    let mut checkpoints = Vec::new();
+    for row in 0..rows {
+        for column in 0..columns {
+            if (column, row) == start {
+                continue;
+            }
+            let center_x = -bounds.half_width() + (column as f32 + 0.5) * WINDOW_WIDTH;
+            let center_y = -bounds.half_height() + (row as f32 + 0.5) * WINDOW_HEIGHT;
+            let nearest = platforms
+                .iter()
+                .enumerate()
+                .filter(|(index, platform)| !taken.contains(index) && cell(platform.x, platform.y) == (column, row))
+                .map(|(_, platform)| platform)
+                .min_by(|a, b| {
+                    let distance = |platform: &PlatformSpec| (platform.x - center_x).hypot(platform.y - center_y);
+                    distance(a).total_cmp(&distance(b))
+                });
+            checkpoints.extend(nearest.map(checkpoint_on));
+        }
+    }
+    checkpoints
+}
@@ -0,0 +1,118 @@
+// Things the player can pick up in a Classic level. Every level has its fruit,
+// which finishes the level (see `fruit`); from POWER_UP_MIN_LEVEL on, a few
+// power-ups lie around it too. Which power-ups show up is weighted by level:
+// speed boosts are common throughout, double jump tokens, shields and (from
+// level 4) helper platforms get more likely as the levels get harder, and
+// extra lives stay rare.
+
+use crate::config::{FRUIT_SIZE, PLATFORM_HEIGHT};
+use crate::generation::{PlatformSpec, STARTING_PLATFORM};
+use crate::rng::SeededRng;
+use crate::status::StatusEffect;
+
+pub const POWER_UP_MIN_LEVEL: u32 = 2;
+const HELPER_PLATFORM_MIN_LEVEL: u32 = 4;
+// How long a timed power-up lasts; picking up another one tops it back up
+pub const POWER_UP_SECONDS: f32 = 10.0;
+// Most power-ups a level has
+const MAX_POWER_UPS: usize = 3;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Collectible {
+    Fruit,
+    SpeedBoost,
+    ExtraLife,
+    DoubleJumpToken,
+    Shield,
+    HelperPlatform,
+}
+
+impl Collectible {
+    pub const POWER_UPS: [Collectible; 5] = [
+        Collectible::SpeedBoost,
+        Collectible::ExtraLife,
+        Collectible::DoubleJumpToken,
+        Collectible::Shield,
+        Collectible::HelperPlatform,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Fruit => "Fruit",
+            Self::SpeedBoost => "Speed boost",
+            Self::ExtraLife => "Extra life",
+            Self::DoubleJumpToken => "Double jump",
+            Self::Shield => "Shield",
+            Self::HelperPlatform => "Helper platforms",
+        }
+    }
+
+    // The timed effect picking this up puts on the player
+    pub fn effect(self) -> Option<StatusEffect> {
+        match self {
+            Self::SpeedBoost => Some(StatusEffect::SpeedBoost),
+            Self::DoubleJumpToken => Some(StatusEffect::DoubleJump),
+            Self::Shield => Some(StatusEffect::Shield),
+            Self::Fruit | Self::ExtraLife | Self::HelperPlatform => None,
+        }
+    }
+
+    pub fn extra_lives(self) -> u32 {
+        u32::from(self == Self::ExtraLife)
+    }
+
+    // How likely this is to be a level's power-up, against the other power-ups
+    pub fn spawn_weight(self, level: u32) -> u32 {
+        match self {
+            Self::Fruit => 0,
+            Self::SpeedBoost => 6,
+            Self::ExtraLife => 1,
+            Self::DoubleJumpToken => 2 + level.min(12) / 2,
+            Self::Shield => level.saturating_sub(POWER_UP_MIN_LEVEL).min(8),
+            Self::HelperPlatform => level.saturating_sub(HELPER_PLATFORM_MIN_LEVEL - 1).min(3),
+        }
+    }
+}
+
+// How many power-ups level `level` has
+pub fn power_up_count(level: u32) -> usize {
+    if level < POWER_UP_MIN_LEVEL {
+        return 0;
+    }
+    (1 + (level - POWER_UP_MIN_LEVEL) as usize / 4).min(MAX_POWER_UPS)
+}
+
+fn pick_power_up(rng: &mut SeededRng, level: u32) -> Collectible {
+    let total: u32 = Collectible::POWER_UPS.iter().map(|power_up| power_up.spawn_weight(level)).sum();
+    let mut roll = rng.below(total as usize) as u32;
+    for power_up in Collectible::POWER_UPS {
+        let weight = power_up.spawn_weight(level);
+        if roll < weight {
+            return power_up;
+        }
+        roll -= weight;
+    }
+    Collectible::SpeedBoost
+}
+
+// A level's power-ups, each on top of its own platform. The starting platform
+// and the platforms listed in `taken` (indices into `platforms`) are left out.
+pub fn place_power_ups(platforms: &[PlatformSpec], taken: &[usize], seed: u64, level: u32) -> Vec<(Collectible, (f32, f32))> {
+    let mut rng = SeededRng::new(seed);
+    let mut free: Vec<&PlatformSpec> = platforms
+        .iter()
+        .enumerate()
+        .filter(|(index, platform)| !taken.contains(index) && **platform != STARTING_PLATFORM)
+        .map(|(_, platform)| platform)
+        .collect();
+    let mut power_ups = Vec::new();
+    for _ in 0..power_up_count(level) {
+        if free.is_empty() {
+            break;
+        }
+        let platform = free.swap_remove(rng.below(free.len()));
+        let position = (platform.x, platform.y + PLATFORM_HEIGHT / 2.0 + FRUIT_SIZE / 2.0);
+        power_ups.push((pick_power_up(&mut rng, level), position));
+    }
+    power_ups
+}
@@ -0,0 +1,305 @@
+// Long-term collection goals: trophies for milestones across all Classic
+// runs and daily challenges, the player skins they unlock and a tally of the
+// rare fruit collected. The game keeps it between sessions and shows it in
+// the trophy room.
+//
+// Skins aren't only a color: each runs and jumps a little differently (see
+// `Skin::tuning`), a few percent either way so the levels stay in reach.
+
+use crate::fruit::FruitKind;
+use crate::physics::Tuning;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Trophy {
+    FirstSteps,
+    Climber,
+    Summit,
+    Harvest,
+    Blessing,
+    Daredevil,
+    Catcher,
+    Regular,
+    Stargazer,
+    HighScorer,
+}
+
+impl Trophy {
+    pub const ALL: [Self; 10] = [
+        Self::FirstSteps,
+        Self::Climber,
+        Self::Summit,
+        Self::Harvest,
+        Self::Blessing,
+        Self::Daredevil,
+        Self::Catcher,
+        Self::Regular,
+        Self::Stargazer,
+        Self::HighScorer,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::FirstSteps => "First Steps",
+            Self::Climber => "Climber",
+            Self::Summit => "Summit",
+            Self::Harvest => "Harvest",
+            Self::Blessing => "Blessing",
+            Self::Daredevil => "Daredevil",
+            Self::Catcher => "Catcher",
+            Self::Regular => "Regular",
+            Self::Stargazer => "Stargazer",
+            Self::HighScorer => "High Scorer",
+        }
+    }
+
+    // How it's earned
+    pub fn goal(self) -> &'static str {
+        match self {
+            Self::FirstSteps => "Reach level 5",
+            Self::Climber => "Reach level 10",
+            Self::Summit => "Reach level 20",
+            Self::Harvest => "Collect 100 fruit",
+            Self::Blessing => "Collect a blessed fruit",
+            Self::Daredevil => "Collect 5 cursed fruit",
+            Self::Catcher => "Catch 3 fleeing fruit",
+            Self::Regular => "Play 7 days in a row",
+            Self::Stargazer => "Finish 10 daily challenges",
+            Self::HighScorer => "Score 5000 points in a run",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|trophy| trophy.name() == name)
+    }
+
+    fn earned(self, collection: &Collection) -> bool {
+        match self {
+            Self::FirstSteps => collection.best_level >= 5,
+            Self::Climber => collection.best_level >= 10,
+            Self::Summit => collection.best_level >= 20,
+            Self::Harvest => collection.fruit >= 100,
+            Self::Blessing => collection.rare_fruit(FruitKind::Blessed) >= 1,
+            Self::Daredevil => collection.rare_fruit(FruitKind::Cursed) >= 5,
+            Self::Catcher => collection.rare_fruit(FruitKind::Fleeing) >= 3,
+            Self::Regular => collection.best_streak >= 7,
+            Self::Stargazer => collection.daily_completed >= 10,
+            Self::HighScorer => collection.best_score >= 5000,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Skin {
+    #[default]
+    Classic,
+    Moss,
+    Sunset,
+    Gold,
+    Aurora,
+    Starlight,
+    Comet,
+}
+
+impl Skin {
+    pub const ALL: [Self; 7] = [Self::Classic, Self::Moss, Self::Sunset, Self::Gold, Self::Aurora, Self::Starlight, Self::Comet];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Classic => "Classic",
+            Self::Moss => "Moss",
+            Self::Sunset => "Sunset",
+            Self::Gold => "Gold",
+            Self::Aurora => "Aurora",
+            Self::Starlight => "Starlight",
+            Self::Comet => "Comet",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|skin| skin.name() == name)
+    }
+
+    // The player's color, as sRGB
+    pub fn color(self) -> (f32, f32, f32) {
+        match self {
+            Self::Classic => (0.0, 0.5, 1.0),
+            Self::Moss => (0.3, 0.7, 0.35),
+            Self::Sunset => (0.95, 0.45, 0.3),
+            Self::Gold => (1.0, 0.82, 0.25),
+            Self::Aurora => (0.45, 0.95, 0.75),
+            Self::Starlight => (0.85, 0.8, 1.0),
+            Self::Comet => (1.0, 0.35, 0.6),
+        }
+    }
+
+    // Run and jump speeds, as multiples of the classic skin's
+    pub fn speed(self) -> f32 {
+        match self {
+            Self::Classic | Self::Gold => 1.0,
+            Self::Moss => 0.95,
+            Self::Sunset => 1.08,
+            Self::Aurora => 1.04,
+            Self::Starlight => 0.97,
+            Self::Comet => 1.1,
+        }
+    }
+
+    pub fn jump(self) -> f32 {
+        match self {
+            Self::Classic | Self::Gold => 1.0,
+            Self::Moss => 1.05,
+            Self::Sunset => 0.97,
+            Self::Aurora => 1.02,
+            Self::Starlight => 1.06,
+            Self::Comet => 0.98,
+        }
+    }
+
+    // Whether it plays any differently from the classic skin
+    pub fn changes_physics(self) -> bool {
+        self.speed() != 1.0 || self.jump() != 1.0
+    }
+
+    pub fn tuning(self, tuning: Tuning) -> Tuning {
+        Tuning {
+            player_speed: tuning.player_speed * self.speed(),
+            jump_speed: tuning.jump_speed * self.jump(),
+            ..tuning
+        }
+    }
+
+    // The trophy that unlocks it; the classic skin is always there
+    pub fn unlocked_by(self) -> Option<Trophy> {
+        match self {
+            Self::Classic => None,
+            Self::Moss => Some(Trophy::FirstSteps),
+            Self::Sunset => Some(Trophy::Climber),
+            Self::Gold => Some(Trophy::Summit),
+            Self::Aurora => Some(Trophy::Regular),
+            Self::Starlight => Some(Trophy::Stargazer),
+            Self::Comet => Some(Trophy::HighScorer),
+        }
+    }
+}
+
+const RARE_FRUIT: [FruitKind; 3] = [FruitKind::Blessed, FruitKind::Cursed, FruitKind::Fleeing];
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Collection {
+    // Highest Classic level reached
+    pub best_level: u32,
+    // Every fruit that finished a level
+    pub fruit: u32,
+    // Rare fruit collected, in `RARE_FRUIT` order
+    rare_fruit: [u32; 3],
+    // Longest daily streak and daily challenges finished
+    pub best_streak: u32,
+    pub daily_completed: u32,
+    // Best Classic score
+    pub best_score: u32,
+    pub trophies: Vec<Trophy>,
+}
+
+impl Collection {
+    pub fn rare_fruit(&self, kind: FruitKind) -> u32 {
+        RARE_FRUIT.iter().position(|&rare| rare == kind).map_or(0, |index| self.rare_fruit[index])
+    }
+
+    pub fn has(&self, trophy: Trophy) -> bool {
+        self.trophies.contains(&trophy)
+    }
+
+    pub fn unlocked(&self, skin: Skin) -> bool {
+        skin.unlocked_by().is_none_or(|trophy| self.has(trophy))
+    }
+
+    // A level was reached; returns the trophies that earned
+    pub fn record_level(&mut self, level: u32) -> Vec<Trophy> {
+        self.best_level = self.best_level.max(level);
+        self.award()
+    }
+
+    // A run reached `score`; returns the trophies that earned
+    pub fn record_score(&mut self, score: u32) -> Vec<Trophy> {
+        self.best_score = self.best_score.max(score);
+        self.award()
+    }
+
+    // A fruit of `kind` finished a level; returns the trophies that earned
+    pub fn record_fruit(&mut self, kind: FruitKind) -> Vec<Trophy> {
+        self.fruit += 1;
+        if let Some(index) = RARE_FRUIT.iter().position(|&rare| rare == kind) {
+            self.rare_fruit[index] += 1;
+        }
+        self.award()
+    }
+
+    // The daily challenges' streak and tally changed; returns the trophies
+    // that earned
+    pub fn record_daily(&mut self, best_streak: u32, completed: u32) -> Vec<Trophy> {
+        self.best_streak = self.best_streak.max(best_streak);
+        self.daily_completed = self.daily_completed.max(completed);
+        self.award()
+    }
+
+    fn award(&mut self) -> Vec<Trophy> {
+        let earned: Vec<Trophy> = Trophy::ALL.into_iter().filter(|&trophy| !self.has(trophy) && trophy.earned(self)).collect();
+        self.trophies.extend(&earned);
+        earned
+    }
+
+    // One `key value` line per entry
+    pub fn to_text(&self) -> String {
+        let mut lines = vec![
+            format!("best_level {}", self.best_level),
+            format!("fruit {}", self.fruit),
+            format!("best_streak {}", self.best_streak),
+            format!("daily_completed {}", self.daily_completed),
+            format!("best_score {}", self.best_score),
+        ];
+        for (kind, count) in RARE_FRUIT.iter().zip(self.rare_fruit) {
+            lines.push(format!("{} {}", rare_fruit_key(*kind), count));
+        }
+        lines.extend(self.trophies.iter().map(|trophy| format!("trophy {}", trophy.name())));
+        lines.join("\n")
+    }
+
+    // Lines that don't parse are skipped; trophies are checked again, so a
+    // hand-edited tally still earns them
+    pub fn from_text(text: &str) -> Self {
+        let mut collection = Self::default();
+        for line in text.lines() {
+            let Some((key, value)) = line.split_once(' ') else {
+                continue;
+            };
+            match key {
+                "best_level" => collection.best_level = value.parse().unwrap_or(collection.best_level),
+                "fruit" => collection.fruit = value.parse().unwrap_or(collection.fruit),
+                "best_streak" => collection.best_streak = value.parse().unwrap_or(collection.best_streak),
+                "daily_completed" => collection.daily_completed = value.parse().unwrap_or(collection.daily_completed),
+                "best_score" => collection.best_score = value.parse().unwrap_or(collection.best_score),
+                "trophy" => {
+                    if let Some(trophy) = Trophy::from_name(value).filter(|&trophy| !collection.has(trophy)) {
+                        collection.trophies.push(trophy);
+                    }
+                }
+                _ => {
+                    if let Some(index) = RARE_FRUIT.iter().position(|&kind| rare_fruit_key(kind) == key) {
+                        collection.rare_fruit[index] = value.parse().unwrap_or(0);
+                    }
+                }
+            }
+        }
+        collection.award();
+        collection
+    }
+}
+
+fn rare_fruit_key(kind: FruitKind) -> &'static str {
+    match kind {
+        FruitKind::Blessed => "blessed",
+        FruitKind::Cursed => "cursed",
+        FruitKind::Fleeing => "fleeing",
+        FruitKind::Normal => "normal",
+    }
+}
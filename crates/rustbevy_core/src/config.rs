@@ -0,0 +1,31 @@
+// Tuning constants shared by the game and the headless simulation
+
+pub const WINDOW_WIDTH: f32 = 1200.0;
+pub const WINDOW_HEIGHT: f32 = 800.0;
+// Classic levels span several screens; the camera follows the player around
+pub const WORLD_WIDTH: f32 = WINDOW_WIDTH * 3.0;
+pub const WORLD_HEIGHT: f32 = WINDOW_HEIGHT * 2.0;
+pub const PLAYER_SPEED: f32 = 300.0;
+pub const AIR_CONTROL: f32 = 1.0; // 1.0 = full control in air, 0.5 = half control, etc.
+pub const JUMP_SPEED: f32 = 700.0; // Increased from 500.0 for higher jumps
+pub const GRAVITY: f32 = 2000.0;
+// GRAVITY is scaled by where the player is in a jump: rising, hanging near the
+// top (moving up or down slower than APEX_SPEED), falling, and falling with
+// down held (fast-fall)
+pub const RISE_GRAVITY_SCALE: f32 = 1.0;
+pub const APEX_GRAVITY_SCALE: f32 = 0.5;
+pub const APEX_SPEED: f32 = 100.0;
+pub const FALL_GRAVITY_SCALE: f32 = 1.3;
+pub const FAST_FALL_GRAVITY_SCALE: f32 = 2.2;
+// Nothing falls faster, so a fall can't carry the smallest player through a
+// platform in one physics step
+pub const MAX_FALL_SPEED: f32 = 2400.0;
+// Physics steps per second, in the game and in replays
+pub const PHYSICS_HZ: f32 = 60.0;
+
+pub const PLAYER_SIZE: f32 = 50.0; // Player is 50x50
+pub const PLATFORM_HEIGHT: f32 = 20.0;
+pub const FRUIT_SIZE: f32 = 25.0;
+pub const FRUIT_PICKUP_DISTANCE: f32 = 30.0;
+pub const PLAYER_SPAWN: (f32, f32) = (0.0, 200.0);
+pub const ENEMY_CHASE_RADIUS: f32 = 180.0; // Enemies chase a player closer than this
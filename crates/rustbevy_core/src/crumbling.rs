@@ -0,0 +1,64 @@
+// Crumbling platforms. From the difficulty curve's `crumbling_from_level` on a
+// few platforms give way a moment after the player lands on them, then come
+// back a few seconds later. Standing still on one is never safe, but running
+// across it is.
+
+use crate::generation::PlatformSpec;
+use crate::rng::SeededRng;
+
+// How long a platform holds once stood on
+pub const CRUMBLE_SECONDS: f32 = 0.6;
+// How long a fallen platform stays gone
+pub const RETURN_SECONDS: f32 = 3.0;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CrumbleState {
+    Solid,
+    // Seconds left before it falls
+    Crumbling(f32),
+    // Seconds left before it comes back
+    Fallen(f32),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CrumblingPlatform {
+    // Index of the platform in its level
+    pub platform: usize,
+    pub state: CrumbleState,
+}
+
+impl CrumblingPlatform {
+    pub fn new(platform: usize) -> Self {
+        Self { platform, state: CrumbleState::Solid }
+    }
+
+    // Advances the platform by `dt`; `stood_on` starts it crumbling. Once it
+    // starts it falls whether or not the player stays.
+    pub fn update(&mut self, stood_on: bool, dt: f32) {
+        self.state = match self.state {
+            CrumbleState::Solid if stood_on => CrumbleState::Crumbling(CRUMBLE_SECONDS),
+            CrumbleState::Solid => CrumbleState::Solid,
+            CrumbleState::Crumbling(left) if left > dt => CrumbleState::Crumbling(left - dt),
+            CrumbleState::Crumbling(_) => CrumbleState::Fallen(RETURN_SECONDS),
+            CrumbleState::Fallen(left) if left > dt => CrumbleState::Fallen(left - dt),
+            CrumbleState::Fallen(_) => CrumbleState::Solid,
+        };
+    }
+
+    // Whether the player can stand on it
+    pub fn solid(&self) -> bool {
+        !matches!(self.state, CrumbleState::Fallen(_))
+    }
+}
+
+// Up to `count` crumbling platforms: never the starting platform (the first
+// one) or any platform listed in `keep_solid`
+pub fn place_crumbling_platforms(platforms: &[PlatformSpec], seed: u64, count: usize, keep_solid: &[usize]) -> Vec<CrumblingPlatform> {
+    let mut candidates: Vec<usize> = (1..platforms.len()).filter(|index| !keep_solid.contains(index)).collect();
+    let mut rng = SeededRng::new(seed);
+    let mut crumbling = Vec::new();
+    while crumbling.len() < count && !candidates.is_empty() {
+        crumbling.push(CrumblingPlatform::new(candidates.swap_remove(rng.below(candidates.len()))));
+    }
+    crumbling
+}
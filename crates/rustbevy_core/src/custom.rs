@@ -0,0 +1,121 @@
+// Custom games: Classic runs generated from the player's own numbers instead
+// of the usual difficulty curve. How many platforms a screen has and how wide
+// they are, how many hazards and enemies a level gets, and maybe one theme
+// for them all. Each is picked from a short list of steps, and every level of
+// the run uses the same numbers from level 1 on. Gaps, moving platforms and everything else still follow the
+// curve the game was started with.
+
+use crate::difficulty::DifficultyCurve;
+use crate::theme::LevelTheme;
+
+// Fewest to most platforms per screen
+const PLATFORM_COUNTS: [(usize, usize); 5] = [(3, 5), (4, 7), (6, 10), (8, 12), (10, 14)];
+// Narrowest to widest platform
+const PLATFORM_WIDTHS: [(f32, f32); 5] = [(60.0, 110.0), (90.0, 160.0), (120.0, 220.0), (160.0, 280.0), (220.0, 360.0)];
+pub const MAX_ENEMIES: usize = 6;
+
+// How thick spikes and lava are on the ground
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum HazardDensity {
+    None,
+    Light,
+    #[default]
+    Medium,
+    Heavy,
+}
+
+impl HazardDensity {
+    const ALL: [Self; 4] = [Self::None, Self::Light, Self::Medium, Self::Heavy];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::None => "None",
+            Self::Light => "Light",
+            Self::Medium => "Medium",
+            Self::Heavy => "Heavy",
+        }
+    }
+
+    // The share of platforms with spikes, and the lava pools along the bottom
+    fn spikes_and_lava(self) -> (f32, usize) {
+        match self {
+            Self::None => (0.0, 0),
+            Self::Light => (0.1, 1),
+            Self::Medium => (0.2, 2),
+            Self::Heavy => (0.35, 3),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CustomGame {
+    pub platforms_per_screen: (usize, usize),
+    pub platform_widths: (f32, f32),
+    pub hazards: HazardDensity,
+    pub enemies: usize,
+    // The theme of every level; they take turns as usual without one
+    pub theme: Option<LevelTheme>,
+}
+
+impl Default for CustomGame {
+    fn default() -> Self {
+        let curve = DifficultyCurve::default();
+        Self { platforms_per_screen: curve.platforms_per_screen, platform_widths: curve.platform_widths, hazards: HazardDensity::default(), enemies: 2, theme: None }
+    }
+}
+
+// The step after (`steps` > 0) or before `current` in `steps`, wrapping
+// around; a value that isn't one of them goes to the first
+fn cycle<T: Copy + PartialEq>(steps: &[T], current: T, by: i32) -> T {
+    let index = steps.iter().position(|&step| step == current).map_or(0, |index| (index as i32 + by).rem_euclid(steps.len() as i32) as usize);
+    steps[index]
+}
+
+impl CustomGame {
+    pub fn step_platform_count(&mut self, by: i32) {
+        self.platforms_per_screen = cycle(&PLATFORM_COUNTS, self.platforms_per_screen, by);
+    }
+
+    pub fn step_platform_widths(&mut self, by: i32) {
+        self.platform_widths = cycle(&PLATFORM_WIDTHS, self.platform_widths, by);
+    }
+
+    pub fn step_hazards(&mut self, by: i32) {
+        self.hazards = cycle(&HazardDensity::ALL, self.hazards, by);
+    }
+
+    pub fn step_enemies(&mut self, by: i32) {
+        self.enemies = (self.enemies as i32 + by).rem_euclid(MAX_ENEMIES as i32 + 1) as usize;
+    }
+
+    // None first, then each theme
+    pub fn step_theme(&mut self, by: i32) {
+        let index = self.theme.map_or(0, |theme| LevelTheme::ALL.iter().position(|&other| other == theme).map_or(0, |index| index + 1));
+        let index = (index as i32 + by).rem_euclid(LevelTheme::ALL.len() as i32 + 1) as usize;
+        self.theme = index.checked_sub(1).map(|index| LevelTheme::ALL[index]);
+    }
+
+    // `base` with these numbers in place of its own. Platforms don't thin out
+    // or narrow as levels get harder, and the hazards and enemies are all
+    // there from the first level.
+    pub fn curve(&self, base: &DifficultyCurve) -> DifficultyCurve {
+        let (spike_share, lava_pools) = self.hazards.spikes_and_lava();
+        DifficultyCurve {
+            platforms_per_screen: self.platforms_per_screen,
+            fewer_platforms: 0.0,
+            platform_widths: self.platform_widths,
+            width_shrink: 0.0,
+            enemies_from_level: 1,
+            levels_per_enemy: 0,
+            max_enemies: self.enemies,
+            spikes_from_level: 1,
+            levels_per_spikes: 0,
+            max_spike_share: spike_share,
+            lava_from_level: 1,
+            levels_per_lava: 0,
+            max_lava_pools: lava_pools,
+            theme: self.theme,
+            ..*base
+        }
+    }
+}
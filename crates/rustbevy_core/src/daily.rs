@@ -0,0 +1,194 @@
+// Daily challenges. Each day (UTC) brings two objectives picked from the
+// day's number, the same for everyone, with progress made in Classic runs
+// that day. Playing on consecutive days keeps a streak going. Finished
+// challenges and long streaks earn trophies and skins in the collection.
+
+use crate::fruit::FruitKind;
+use crate::rng::SeededRng;
+
+pub const SECONDS_PER_DAY: u64 = 86_400;
+pub const OBJECTIVES_PER_DAY: usize = 2;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Objective {
+    CollectFruit(u32),
+    ClearLevels(u32),
+    // Levels cleared without losing a life on them
+    FlawlessLevels(u32),
+    // In a single run
+    ReachLevel(u32),
+    RareFruit(u32),
+}
+
+impl Objective {
+    pub fn target(self) -> u32 {
+        match self {
+            Self::CollectFruit(count)
+            | Self::ClearLevels(count)
+            | Self::FlawlessLevels(count)
+            | Self::ReachLevel(count)
+            | Self::RareFruit(count) => count,
+        }
+    }
+
+    pub fn description(self) -> String {
+        match self {
+            Self::CollectFruit(count) => format!("Collect {} fruit", count),
+            Self::ClearLevels(count) => format!("Clear {} levels", count),
+            Self::FlawlessLevels(count) => format!("Clear {} levels without losing a life", count),
+            Self::ReachLevel(level) => format!("Reach level {}", level),
+            Self::RareFruit(1) => "Collect a rare fruit".to_string(),
+            Self::RareFruit(count) => format!("Collect {} rare fruit", count),
+        }
+    }
+
+    // How far `event` takes it, from `count`
+    fn advance(self, count: u32, event: DailyEvent) -> u32 {
+        match (self, event) {
+            (Self::CollectFruit(_), DailyEvent::Fruit(_)) => count + 1,
+            (Self::RareFruit(_), DailyEvent::Fruit(kind)) if kind != FruitKind::Normal => count + 1,
+            (Self::ClearLevels(_), DailyEvent::LevelCleared { .. }) => count + 1,
+            (Self::FlawlessLevels(_), DailyEvent::LevelCleared { flawless: true, .. }) => count + 1,
+            (Self::ReachLevel(_), DailyEvent::LevelCleared { reached, .. }) => count.max(reached),
+            _ => count,
+        }
+    }
+
+    fn to_text(self) -> String {
+        let name = match self {
+            Self::CollectFruit(_) => "collect_fruit",
+            Self::ClearLevels(_) => "clear_levels",
+            Self::FlawlessLevels(_) => "flawless_levels",
+            Self::ReachLevel(_) => "reach_level",
+            Self::RareFruit(_) => "rare_fruit",
+        };
+        format!("{} {}", name, self.target())
+    }
+
+    fn from_text(name: &str, target: u32) -> Option<Self> {
+        Some(match name {
+            "collect_fruit" => Self::CollectFruit(target),
+            "clear_levels" => Self::ClearLevels(target),
+            "flawless_levels" => Self::FlawlessLevels(target),
+            "reach_level" => Self::ReachLevel(target),
+            "rare_fruit" => Self::RareFruit(target),
+            _ => return None,
+        })
+    }
+}
+
+// Something that happened in a Classic run
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DailyEvent {
+    // The fruit that finishes a level
+    Fruit(FruitKind),
+    // `reached` is the new level
+    LevelCleared { reached: u32, flawless: bool },
+}
+
+// The objectives of day `day` (days since the Unix epoch), all different kinds
+pub fn objectives_for(day: u64) -> Vec<Objective> {
+    let mut rng = SeededRng::new(day.wrapping_mul(0x9E37_79B9_7F4A_7C15));
+    let mut pool = vec![
+        Objective::CollectFruit(5 + 5 * rng.below(2) as u32),
+        Objective::ClearLevels(3 + 2 * rng.below(3) as u32),
+        Objective::FlawlessLevels(2 + rng.below(3) as u32),
+        Objective::ReachLevel(5 + 3 * rng.below(3) as u32),
+        Objective::RareFruit(1 + rng.below(2) as u32),
+    ];
+    let mut picked = Vec::new();
+    while picked.len() < OBJECTIVES_PER_DAY && !pool.is_empty() {
+        picked.push(pool.swap_remove(rng.below(pool.len())));
+    }
+    picked
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ObjectiveProgress {
+    pub objective: Objective,
+    pub count: u32,
+}
+
+impl ObjectiveProgress {
+    pub fn done(&self) -> bool {
+        self.count >= self.objective.target()
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DailyChallenges {
+    // The day the objectives are for
+    pub day: u64,
+    pub objectives: Vec<ObjectiveProgress>,
+    // Consecutive days played, ending with the last one
+    pub streak: u32,
+    pub best_streak: u32,
+    // Objectives finished, over all days
+    pub completed: u32,
+}
+
+impl DailyChallenges {
+    // Moves on to `day` when it's a new one, with fresh objectives; a streak
+    // goes on if the last day played was the day before. Returns true on the
+    // first call of a day.
+    pub fn visit(&mut self, day: u64) -> bool {
+        if day == self.day && !self.objectives.is_empty() {
+            return false;
+        }
+        self.streak = if self.streak > 0 && day == self.day + 1 { self.streak + 1 } else { 1 };
+        self.best_streak = self.best_streak.max(self.streak);
+        self.day = day;
+        self.objectives = objectives_for(day).into_iter().map(|objective| ObjectiveProgress { objective, count: 0 }).collect();
+        true
+    }
+
+    // Counts `event` towards today's objectives; returns the ones it finished
+    pub fn record(&mut self, event: DailyEvent) -> Vec<Objective> {
+        let mut finished = Vec::new();
+        for progress in &mut self.objectives {
+            if progress.done() {
+                continue;
+            }
+            progress.count = progress.objective.advance(progress.count, event);
+            if progress.done() {
+                finished.push(progress.objective);
+            }
+        }
+        self.completed += finished.len() as u32;
+        finished
+    }
+
+    // `day streak best_streak completed` on the first line, then one
+    // `objective target count` line per objective
+    pub fn to_text(&self) -> String {
+        let mut lines = vec![format!("{} {} {} {}", self.day, self.streak, self.best_streak, self.completed)];
+        lines.extend(self.objectives.iter().map(|progress| format!("{} {}", progress.objective.to_text(), progress.count)));
+        lines.join("\n")
+    }
+
+    // Anything that doesn't parse is left at its default
+    pub fn from_text(text: &str) -> Self {
+        let mut lines = text.lines();
+        let mut challenges = Self::default();
+        let header: Vec<u64> = lines.next().unwrap_or_default().split_whitespace().filter_map(|value| value.parse().ok()).collect();
+        if let [day, streak, best_streak, completed] = header[..] {
+            challenges.day = day;
+            challenges.streak = streak as u32;
+            challenges.best_streak = best_streak as u32;
+            challenges.completed = completed as u32;
+        }
+        for line in lines {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let [name, target, count] = fields[..] else {
+                continue;
+            };
+            let (Ok(target), Ok(count)) = (target.parse(), count.parse()) else {
+                continue;
+            };
+            if let Some(objective) = Objective::from_text(name, target) {
+                challenges.objectives.push(ObjectiveProgress { objective, count });
+            }
+        }
+        challenges
+    }
+}
@@ -0,0 +1,373 @@
+// Level difficulty: the base curve every run follows, plus the optional
+// adaptive (rubber-banding) adjustment on top of it. `DifficultyCurve` holds
+// every number that makes later levels harder, so the scaling can be tuned in
+// one place.
+
+use crate::config::PLAYER_SIZE;
+use crate::physics::Tuning;
+use crate::reachability::JumpProfile;
+use crate::theme::LevelTheme;
+
+// How far adaptive difficulty may stray from the base curve, either way
+pub const ADAPTIVE_MAX_OFFSET: f32 = 0.25;
+pub const ADAPTIVE_STEP: f32 = 0.1;
+// Deaths on one level before the next levels get easier
+pub const DEATHS_BEFORE_EASING: u32 = 2;
+// Clearing a level this fast without dying makes the next levels harder
+pub const FAST_CLEAR_SECONDS: f32 = 12.0;
+
+// How a level's number and layout difficulty (0.0-1.0) turn into what the
+// generator builds
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DifficultyCurve {
+    // Layout difficulty gained each level, and where the base curve levels off
+    pub per_level: f32,
+    pub max_base: f32,
+    // Platforms per screen on the easiest levels, fewest to most
+    pub platforms_per_screen: (usize, usize),
+    // Platforms per screen dropped from `platforms_per_screen` at difficulty 1.0
+    pub fewer_platforms: f32,
+    // Narrowest and widest platform on the easiest levels
+    pub platform_widths: (f32, f32),
+    // How much narrower platforms get at difficulty 1.0
+    pub width_shrink: f32,
+    // Smallest edge-to-edge gap between platforms at about the same height, as
+    // a share of the longest jump, at difficulty 0.0 and 1.0
+    pub gap_share: (f32, f32),
+    pub enemies_from_level: u32,
+    // One more enemy every this many levels, up to `max_enemies`; 0 brings
+    // them all in at once
+    pub levels_per_enemy: u32,
+    pub max_enemies: usize,
+    // One more moving platform each level, up to this share of the platforms
+    pub moving_from_level: u32,
+    pub max_moving_share: f32,
+    // Chance, out of 100, that a platform is one-way
+    pub one_way_from_level: u32,
+    pub one_way_odds: usize,
+    // Chance, out of 100, that a platform with nothing on it is a ramp
+    pub slopes_from_level: u32,
+    pub slope_odds: usize,
+    // One more crumbling platform every two levels, up to this share
+    pub crumbling_from_level: u32,
+    pub max_crumbling_share: f32,
+    // One more trap platform every `levels_per_trap` levels, up to this share
+    pub traps_from_level: u32,
+    pub levels_per_trap: u32,
+    pub max_trap_share: f32,
+    // One more spiked platform every `levels_per_spikes` levels (0: all at
+    // once), up to this share
+    pub spikes_from_level: u32,
+    pub levels_per_spikes: u32,
+    pub max_spike_share: f32,
+    // One more lava pool along the bottom every `levels_per_lava` levels (0:
+    // all at once), up to `max_lava_pools`
+    pub lava_from_level: u32,
+    pub levels_per_lava: u32,
+    pub max_lava_pools: usize,
+    // One more conveyor platform every `levels_per_conveyor` levels, up to this share
+    pub conveyors_from_level: u32,
+    pub levels_per_conveyor: u32,
+    pub max_conveyor_share: f32,
+    // One more wind zone every `levels_per_wind` levels, up to `max_wind_zones`
+    pub wind_from_level: u32,
+    pub levels_per_wind: u32,
+    pub max_wind_zones: usize,
+    // One more pair of portals every `levels_per_portal` levels, up to `max_portal_pairs`
+    pub portals_from_level: u32,
+    pub levels_per_portal: u32,
+    pub max_portal_pairs: usize,
+    // Each theme in turn for `levels_per_theme` levels, unless `theme` keeps
+    // one for every level
+    pub levels_per_theme: u32,
+    pub theme: Option<LevelTheme>,
+    // One more bonus fruit every `levels_per_bonus_fruit` levels, up to
+    // `max_bonus_fruit`; `bonus_fruit_bias` is how strongly they lean toward
+    // hard-to-reach platforms once `hard_fruit_level` is reached
+    pub bonus_fruit_from_level: u32,
+    pub levels_per_bonus_fruit: u32,
+    pub max_bonus_fruit: usize,
+    pub bonus_fruit_bias: f32,
+    pub hard_fruit_level: u32,
+    // From `fruit_goal_from_level` on a level takes two fruit to finish, then
+    // one more every `levels_per_goal_fruit` levels, up to `max_fruit_goal`
+    pub fruit_goal_from_level: u32,
+    pub levels_per_goal_fruit: u32,
+    pub max_fruit_goal: usize,
+    // From `doors_from_level` on every `levels_per_door`th level (0: every
+    // level) is finished through an exit door instead of by its fruit
+    pub doors_from_level: u32,
+    pub levels_per_door: u32,
+}
+
+impl Default for DifficultyCurve {
+    fn default() -> Self {
+        Self {
+            per_level: 0.05,
+            max_base: 0.5,
+            platforms_per_screen: (6, 10),
+            fewer_platforms: 2.0,
+            platform_widths: (120.0, 220.0),
+            width_shrink: 0.4,
+            gap_share: (0.3, 0.6),
+            enemies_from_level: 3,
+            levels_per_enemy: 3,
+            max_enemies: 3,
+            moving_from_level: 5,
+            max_moving_share: 0.4,
+            one_way_from_level: 3,
+            one_way_odds: 25,
+            slopes_from_level: 2,
+            slope_odds: 20,
+            crumbling_from_level: 7,
+            max_crumbling_share: 0.25,
+            traps_from_level: 9,
+            levels_per_trap: 3,
+            max_trap_share: 0.2,
+            spikes_from_level: 4,
+            levels_per_spikes: 2,
+            max_spike_share: 0.2,
+            lava_from_level: 6,
+            levels_per_lava: 4,
+            max_lava_pools: 3,
+            conveyors_from_level: 8,
+            levels_per_conveyor: 3,
+            max_conveyor_share: 0.2,
+            wind_from_level: 10,
+            levels_per_wind: 4,
+            max_wind_zones: 3,
+            portals_from_level: 11,
+            levels_per_portal: 6,
+            max_portal_pairs: 2,
+            levels_per_theme: 5,
+            theme: None,
+            bonus_fruit_from_level: 2,
+            levels_per_bonus_fruit: 4,
+            max_bonus_fruit: 3,
+            bonus_fruit_bias: 4.0,
+            hard_fruit_level: 12,
+            fruit_goal_from_level: 5,
+            levels_per_goal_fruit: 4,
+            max_fruit_goal: 5,
+            doors_from_level: 6,
+            levels_per_door: 3,
+        }
+    }
+}
+
+// What the platform generator takes from the curve
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LayoutParams {
+    // Platforms per screen before `fewer_platforms`, fewest to most
+    pub platforms_per_screen: (usize, usize),
+    // Platforms per screen taken off those
+    pub fewer_platforms: usize,
+    // Narrowest and widest platform before `width_scale`
+    pub widths: (f32, f32),
+    // Applied to those widths
+    pub width_scale: f32,
+    // Smallest gap between the edges of platforms at about the same height
+    pub min_gap: f32,
+}
+
+// How far apart two platforms' edges can be and still be jumped across with
+// the default tuning: the run speed over a full jump's airtime, plus the
+// player's width (it can stand half over either edge)
+pub fn longest_jump() -> f32 {
+    JumpProfile::new(&Tuning::default(), PLAYER_SIZE).jump_distance() + PLAYER_SIZE
+}
+
+impl DifficultyCurve {
+    // 0.0 on level 1, rising by `per_level` each level to `max_base`
+    pub fn base(&self, level: u32) -> f32 {
+        (level.saturating_sub(1) as f32 * self.per_level).min(self.max_base)
+    }
+
+    pub fn layout(&self, difficulty: f32) -> LayoutParams {
+        let difficulty = difficulty.clamp(0.0, 1.0);
+        let (easy_gap, hard_gap) = self.gap_share;
+        LayoutParams {
+            platforms_per_screen: self.platforms_per_screen,
+            fewer_platforms: (difficulty * self.fewer_platforms).round() as usize,
+            widths: self.platform_widths,
+            width_scale: 1.0 - self.width_shrink * difficulty,
+            min_gap: longest_jump() * (easy_gap + (hard_gap - easy_gap) * difficulty),
+        }
+    }
+
+    pub fn enemies(&self, level: u32) -> usize {
+        if level < self.enemies_from_level {
+            return 0;
+        }
+        if self.levels_per_enemy == 0 {
+            return self.max_enemies;
+        }
+        ((level / self.levels_per_enemy) as usize).min(self.max_enemies)
+    }
+
+    pub fn moving(&self, level: u32, platform_count: usize) -> usize {
+        if level < self.moving_from_level {
+            return 0;
+        }
+        let most = (platform_count as f32 * self.max_moving_share) as usize;
+        ((level - self.moving_from_level + 1) as usize).min(most)
+    }
+
+    pub fn one_way_odds(&self, level: u32) -> usize {
+        if level < self.one_way_from_level {
+            return 0;
+        }
+        self.one_way_odds
+    }
+
+    pub fn slope_odds(&self, level: u32) -> usize {
+        if level < self.slopes_from_level {
+            return 0;
+        }
+        self.slope_odds
+    }
+
+    pub fn bonus_fruit(&self, level: u32) -> usize {
+        if level < self.bonus_fruit_from_level {
+            return 0;
+        }
+        ((level - self.bonus_fruit_from_level) as usize / self.levels_per_bonus_fruit.max(1) as usize + 1).min(self.max_bonus_fruit)
+    }
+
+    // How many fruit finish a level, the level's own fruit included
+    pub fn fruit_goal(&self, level: u32) -> usize {
+        if level < self.fruit_goal_from_level {
+            return 1;
+        }
+        ((level - self.fruit_goal_from_level) as usize / self.levels_per_goal_fruit.max(1) as usize + 2).min(self.max_fruit_goal.max(1))
+    }
+
+    // Whether `level` is finished through an exit door. Its keys take the
+    // place of the fruit it would have had besides its own, at least one.
+    pub fn door(&self, level: u32) -> bool {
+        if level < self.doors_from_level {
+            return false;
+        }
+        self.levels_per_door == 0 || (level - self.doors_from_level).is_multiple_of(self.levels_per_door)
+    }
+
+    // How much more likely the hardest spot is to get a bonus fruit than the
+    // easiest: not at all on level 1, `bonus_fruit_bias` times from
+    // `hard_fruit_level` on
+    pub fn fruit_bias(&self, level: u32) -> f32 {
+        let progress = (level.saturating_sub(1) as f32 / self.hard_fruit_level.max(2).saturating_sub(1) as f32).min(1.0);
+        progress * self.bonus_fruit_bias
+    }
+
+    pub fn crumbling(&self, level: u32, platform_count: usize) -> usize {
+        if level < self.crumbling_from_level {
+            return 0;
+        }
+        let most = (platform_count as f32 * self.max_crumbling_share) as usize;
+        ((level - self.crumbling_from_level) as usize / 2 + 1).min(most)
+    }
+
+    pub fn traps(&self, level: u32, platform_count: usize) -> usize {
+        if level < self.traps_from_level {
+            return 0;
+        }
+        let most = (platform_count as f32 * self.max_trap_share) as usize;
+        ((level - self.traps_from_level) as usize / self.levels_per_trap.max(1) as usize + 1).min(most)
+    }
+
+    pub fn spikes(&self, level: u32, platform_count: usize) -> usize {
+        if level < self.spikes_from_level {
+            return 0;
+        }
+        let most = (platform_count as f32 * self.max_spike_share) as usize;
+        if self.levels_per_spikes == 0 {
+            return most;
+        }
+        ((level - self.spikes_from_level) as usize / self.levels_per_spikes as usize + 1).min(most)
+    }
+
+    pub fn lava(&self, level: u32) -> usize {
+        if level < self.lava_from_level {
+            return 0;
+        }
+        if self.levels_per_lava == 0 {
+            return self.max_lava_pools;
+        }
+        ((level - self.lava_from_level) as usize / self.levels_per_lava as usize + 1).min(self.max_lava_pools)
+    }
+
+    pub fn conveyors(&self, level: u32, platform_count: usize) -> usize {
+        if level < self.conveyors_from_level {
+            return 0;
+        }
+        let most = (platform_count as f32 * self.max_conveyor_share) as usize;
+        ((level - self.conveyors_from_level) as usize / self.levels_per_conveyor.max(1) as usize + 1).min(most)
+    }
+
+    pub fn wind(&self, level: u32) -> usize {
+        if level < self.wind_from_level {
+            return 0;
+        }
+        ((level - self.wind_from_level) as usize / self.levels_per_wind.max(1) as usize + 1).min(self.max_wind_zones)
+    }
+
+    pub fn theme(&self, level: u32) -> LevelTheme {
+        self.theme.unwrap_or_else(|| {
+            let band = level.saturating_sub(1) / self.levels_per_theme.max(1);
+            LevelTheme::ALL[band as usize % LevelTheme::ALL.len()]
+        })
+    }
+
+    pub fn portals(&self, level: u32) -> usize {
+        if level < self.portals_from_level {
+            return 0;
+        }
+        ((level - self.portals_from_level) as usize / self.levels_per_portal.max(1) as usize + 1).min(self.max_portal_pairs)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct AdaptiveDifficulty {
+    pub enabled: bool,
+    pub offset: f32,
+    pub deaths_this_level: u32,
+    pub level_seconds: f32,
+}
+
+impl AdaptiveDifficulty {
+    pub fn new(enabled: bool) -> Self {
+        Self { enabled, ..Self::default() }
+    }
+
+    pub fn tick(&mut self, dt: f32) {
+        self.level_seconds += dt;
+    }
+
+    pub fn record_death(&mut self) {
+        self.deaths_this_level += 1;
+        if self.enabled && self.deaths_this_level >= DEATHS_BEFORE_EASING {
+            self.nudge(-ADAPTIVE_STEP);
+            self.deaths_this_level = 0;
+        }
+    }
+
+    // Call when the fruit is collected, before the next level is generated
+    pub fn record_clear(&mut self) {
+        let flawless = self.deaths_this_level == 0;
+        if self.enabled && flawless && self.level_seconds < FAST_CLEAR_SECONDS {
+            self.nudge(ADAPTIVE_STEP);
+        }
+        self.deaths_this_level = 0;
+        self.level_seconds = 0.0;
+    }
+
+    fn nudge(&mut self, amount: f32) {
+        self.offset = (self.offset + amount).clamp(-ADAPTIVE_MAX_OFFSET, ADAPTIVE_MAX_OFFSET);
+    }
+
+    // Difficulty used to generate `level`, between 0.0 (easiest) and 1.0
+    pub fn difficulty(&self, curve: &DifficultyCurve, level: u32) -> f32 {
+        let offset = if self.enabled { self.offset } else { 0.0 };
+        (curve.base(level) + offset).clamp(0.0, 1.0)
+    }
+}
@@ -0,0 +1,132 @@
+// Enemies. From the difficulty curve's `enemies_from_level` on a few of them
+// stand on random platforms, walking back and forth along their platform until the player
+// comes within their chase radius where they can see them (solid platforms
+// block the view, one-way ones don't), then running at the player. The edge
+// sensor stops them at the edge of the ground they walk on, and they never
+// leave the platform they were placed on. Landing on one's head defeats it,
+// bouncing the player back up for ENEMY_STOMP_SCORE points; touching it any
+// other way costs a life, just like falling.
+
+use crate::config::{ENEMY_CHASE_RADIUS, JUMP_SPEED};
+use crate::generation::{PlatformSpec, STARTING_PLATFORM};
+use crate::physics::{edge_ahead, line_of_sight, Body};
+use crate::rng::SeededRng;
+use crate::zones::{clear_of, SafeZone, Zone};
+
+pub const ENEMY_SIZE: f32 = 30.0;
+pub const ENEMY_PATROL_SPEED: f32 = 60.0;
+pub const ENEMY_CHASE_SPEED: f32 = 140.0;
+pub const ENEMY_STOMP_SCORE: u32 = 50;
+// How fast a stomp sends the player back up
+pub const STOMP_BOUNCE_SPEED: f32 = JUMP_SPEED * 0.6;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EnemyBehavior {
+    Patrol,
+    Chase,
+}
+
+// One enemy and the platform it was placed on
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct EnemyAi {
+    pub x: f32,
+    pub y: f32,
+    pub behavior: EnemyBehavior,
+    pub chase_radius: f32,
+    left: f32,
+    right: f32,
+    direction: f32,
+}
+
+impl EnemyAi {
+    // An enemy standing in the middle of `platform`
+    pub fn on_platform(platform: &PlatformSpec) -> Self {
+        let reach = (platform.width - ENEMY_SIZE).max(0.0) / 2.0;
+        Self {
+            x: platform.x,
+            y: platform.y + platform.height / 2.0 + ENEMY_SIZE / 2.0,
+            behavior: EnemyBehavior::Patrol,
+            chase_radius: ENEMY_CHASE_RADIUS,
+            left: platform.x - reach,
+            right: platform.x + reach,
+            direction: 1.0,
+        }
+    }
+
+    // Moves the enemy for `dt` seconds; `player` is the player's centre, if
+    // there is a player, and `platforms` the solid platforms it can walk on
+    // and that can hide the player. A patrol turns around at an edge and a
+    // chase waits there.
+    pub fn update(&mut self, player: Option<(f32, f32)>, platforms: &[PlatformSpec], dt: f32) {
+        let close = player.filter(|player| {
+            (player.0 - self.x).hypot(player.1 - self.y) <= self.chase_radius && line_of_sight((self.x, self.y), *player, platforms)
+        });
+        match close {
+            Some(player) => {
+                self.behavior = EnemyBehavior::Chase;
+                let step = ENEMY_CHASE_SPEED * dt;
+                let offset = (player.0 - self.x).clamp(-step, step);
+                if !edge_ahead(&self.body(), offset.signum(), platforms) {
+                    self.x += offset;
+                }
+            }
+            None => {
+                self.behavior = EnemyBehavior::Patrol;
+                let at_end = if self.direction > 0.0 { self.x >= self.right } else { self.x <= self.left };
+                if at_end || edge_ahead(&self.body(), self.direction, platforms) {
+                    self.direction = -self.direction;
+                }
+                if !edge_ahead(&self.body(), self.direction, platforms) {
+                    self.x += self.direction * ENEMY_PATROL_SPEED * dt;
+                }
+            }
+        }
+        self.x = self.x.clamp(self.left, self.right);
+    }
+
+    pub fn touches(&self, body: &Body) -> bool {
+        let reach = (body.size + ENEMY_SIZE) / 2.0;
+        (body.x - self.x).abs() < reach && (body.y - self.y).abs() < reach
+    }
+
+    // Whether `body` is coming down on the enemy's head rather than running
+    // into its side: falling, with its feet still above the enemy's middle
+    pub fn stomped_by(&self, body: &Body) -> bool {
+        self.touches(body) && body.velocity_y < 0.0 && body.y - body.size / 2.0 >= self.y
+    }
+
+    // The enemy as a standing body, for the edge sensor
+    fn body(&self) -> Body {
+        Body { x: self.x, y: self.y, size: ENEMY_SIZE, grounded: true, ..Body::at_spawn() }
+    }
+
+    // Whether nowhere along its walk comes into any of the `safe` zones
+    pub fn clear_of(&self, safe: &[SafeZone]) -> bool {
+        let reach = ENEMY_SIZE / 2.0;
+        !safe.iter().any(|zone| zone.reaches(self.left - reach, self.right + reach, self.y))
+    }
+}
+
+// Sends a player who stomped an enemy back up
+pub fn stomp_bounce(body: &mut Body) {
+    body.velocity_y = STOMP_BOUNCE_SPEED;
+    body.grounded = false;
+}
+
+// Up to `count` enemies on random platforms wide enough to walk on, with
+// their centre in one of `zones` and clear of the `safe` zones: never the
+// starting platform and at most one per platform
+pub fn place_enemies(platforms: &[PlatformSpec], seed: u64, count: usize, zones: &[Zone], safe: &[SafeZone]) -> Vec<EnemyAi> {
+    let mut candidates: Vec<&PlatformSpec> = platforms
+        .iter()
+        .filter(|platform| **platform != STARTING_PLATFORM && platform.width >= ENEMY_SIZE * 2.0)
+        .filter(|platform| zones.iter().any(|zone| zone.contains((platform.x, platform.y))) && clear_of(platform, safe))
+        .collect();
+    let mut rng = SeededRng::new(seed.wrapping_mul(211));
+    let mut enemies = Vec::new();
+    while enemies.len() < count && !candidates.is_empty() {
+        let index = rng.below(candidates.len());
+        enemies.push(EnemyAi::on_platform(candidates.swap_remove(index)));
+    }
+    enemies
+}
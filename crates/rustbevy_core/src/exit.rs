@@ -0,0 +1,46 @@
+// Exit doors, the other way to finish a Classic level. On a door level (see
+// the difficulty curve's `doors_from_level`) the level's fruit makes way for
+// a locked door standing where it would have been, and the rest of the fruit
+// to collect for keys lying where they would have been. The door opens once
+// every key is held, and walking into the open door finishes the level. A
+// level file can have a door and keys of its own instead of its fruit.
+
+use crate::config::FRUIT_SIZE;
+use crate::physics::Body;
+
+pub const DOOR_WIDTH: f32 = 48.0;
+pub const DOOR_HEIGHT: f32 = 72.0;
+
+// A locked door standing at `door` (the middle of its bottom edge), and the
+// keys that open it, by their centres. Keys are fruit-sized, so they go on
+// the same spots fruit do and are picked up the same way.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExitDoor {
+    pub door: (f32, f32),
+    pub keys: Vec<(f32, f32)>,
+}
+
+impl ExitDoor {
+    // A door where a fruit centred at `fruit` would be, opened by `keys`
+    pub fn at_fruit_spot(fruit: (f32, f32), keys: Vec<(f32, f32)>) -> Self {
+        Self { door: (fruit.0, fruit.1 - FRUIT_SIZE / 2.0), keys }
+    }
+
+    // Where a fruit standing in the doorway would be, for checking the door
+    // can be reached the way fruit is
+    pub fn fruit_spot(&self) -> (f32, f32) {
+        (self.door.0, self.door.1 + FRUIT_SIZE / 2.0)
+    }
+
+    // Whether holding `keys_held` keys opens the door
+    pub fn opens_with(&self, keys_held: usize) -> bool {
+        keys_held >= self.keys.len()
+    }
+}
+
+// Whether `body` has walked into the door standing at `door`: its middle is
+// in the doorway, so brushing the frame doesn't count
+pub fn enters_door(body: &Body, door: (f32, f32)) -> bool {
+    let center_y = door.1 + DOOR_HEIGHT / 2.0;
+    (body.x - door.0).abs() < DOOR_WIDTH / 2.0 && (body.y - center_y).abs() < (body.size + DOOR_HEIGHT) / 2.0
+}
@@ -0,0 +1,104 @@
+// Fleeing fruit. When the player gets close, the fruit hops over to a
+// neighboring platform it could reach itself (an edge of the reachability
+// graph) that the player can also get to, as far from the player as it can.
+// It only has a few hops in it; after that it stays put and can be collected.
+
+use crate::config::{FRUIT_SIZE, PLATFORM_HEIGHT};
+use crate::generation::PlatformSpec;
+use crate::reachability::{reachable_platforms, JumpProfile};
+
+// How close the player can get before the fruit runs
+pub const FLEE_RADIUS: f32 = 130.0;
+pub const FLEE_HOPS: u32 = 3;
+pub const HOP_SECONDS: f32 = 0.4;
+// Peak height of the hop arc above the straight line between platforms
+const HOP_ARC_HEIGHT: f32 = 60.0;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FleeingFruit {
+    platforms: Vec<PlatformSpec>,
+    reached: Vec<bool>,
+    profile: JumpProfile,
+    platform: usize,
+    hops_left: u32,
+    // Where the current hop started and how far along it is
+    hop: Option<((f32, f32), f32)>,
+}
+
+// Where a fruit sits on top of `platform`
+fn resting_position(platform: &PlatformSpec) -> (f32, f32) {
+    (platform.x, platform.y + PLATFORM_HEIGHT / 2.0 + FRUIT_SIZE / 2.0)
+}
+
+impl FleeingFruit {
+    // A fleeing fruit at `position`, which has to be on top of one of the
+    // platforms. The first platform is the starting platform.
+    pub fn new(platforms: &[PlatformSpec], position: (f32, f32), profile: JumpProfile) -> Option<Self> {
+        let platform = platforms.iter().position(|platform| {
+            let on_top = (platform.y + platform.height / 2.0 + FRUIT_SIZE / 2.0 - position.1).abs() < 1.0;
+            on_top && (platform.x - position.0).abs() <= platform.width / 2.0
+        })?;
+        Some(Self {
+            platforms: platforms.to_vec(),
+            reached: reachable_platforms(platforms, &profile),
+            profile,
+            platform,
+            hops_left: FLEE_HOPS,
+            hop: None,
+        })
+    }
+
+    pub fn hops_left(&self) -> u32 {
+        self.hops_left
+    }
+
+    // The fruit can't be grabbed while it is in the air
+    pub fn catchable(&self) -> bool {
+        self.hop.is_none()
+    }
+
+    // Where the fruit is now, part way along its hop if it is hopping
+    pub fn position(&self) -> (f32, f32) {
+        let to = resting_position(&self.platforms[self.platform]);
+        match self.hop {
+            Some((from, elapsed)) => {
+                let t = (elapsed / HOP_SECONDS).min(1.0);
+                let arc = 4.0 * t * (1.0 - t) * HOP_ARC_HEIGHT;
+                (from.0 + (to.0 - from.0) * t, from.1 + (to.1 - from.1) * t + arc)
+            }
+            None => to,
+        }
+    }
+
+    // The platform to hop to when the player is at `player`: a neighbor the
+    // player can reach, as far away from them as possible
+    fn escape_platform(&self, player: (f32, f32)) -> Option<usize> {
+        let from = &self.platforms[self.platform];
+        let distance = |platform: &PlatformSpec| (platform.x - player.0).hypot(platform.y - player.1);
+        (1..self.platforms.len())
+            .filter(|&to| to != self.platform && self.reached[to] && self.profile.can_hop(from, &self.platforms[to]))
+            .max_by(|&a, &b| distance(&self.platforms[a]).total_cmp(&distance(&self.platforms[b])))
+    }
+
+    // Moves the fruit along for `dt` seconds; starts a hop when the player is
+    // too close. Returns true when a new hop starts.
+    pub fn update(&mut self, player: (f32, f32), dt: f32) -> bool {
+        if let Some((from, elapsed)) = self.hop {
+            let elapsed = elapsed + dt;
+            self.hop = (elapsed < HOP_SECONDS).then_some((from, elapsed));
+            return false;
+        }
+
+        let (x, y) = self.position();
+        if self.hops_left == 0 || (x - player.0).hypot(y - player.1) > FLEE_RADIUS {
+            return false;
+        }
+        let Some(to) = self.escape_platform(player) else {
+            return false;
+        };
+        self.hop = Some(((x, y), 0.0));
+        self.platform = to;
+        self.hops_left -= 1;
+        true
+    }
+}
@@ -0,0 +1,129 @@
+// Fruit variants. Most fruit is plain, but now and then a level's fruit is
+// blessed (an extra life, placed on the most dangerous platform), cursed
+// (double score, but the player gets a temporary debuff) or, at higher
+// levels, fleeing (it hops away when the player gets close, see `flee`).
+
+use crate::config::{FRUIT_SIZE, PLATFORM_HEIGHT};
+use crate::generation::{fruit_position, fruit_spot, fruit_spots, too_close, PlatformSpec, STARTING_PLATFORM};
+use crate::rng::SeededRng;
+use crate::status::StatusEffect;
+
+pub const FRUIT_SCORE: u32 = 100;
+pub const CURSE_SECONDS: f32 = 8.0;
+// One fruit in this many is blessed, one in this many is cursed, and from
+// FLEEING_MIN_LEVEL on one in this many flees
+const VARIANT_ODDS: usize = 12;
+pub const FLEEING_MIN_LEVEL: u32 = 4;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FruitKind {
+    #[default]
+    Normal,
+    Blessed,
+    Cursed,
+    Fleeing,
+}
+
+impl FruitKind {
+    pub fn score(self) -> u32 {
+        match self {
+            Self::Cursed => FRUIT_SCORE * 2,
+            _ => FRUIT_SCORE,
+        }
+    }
+
+    pub fn extra_lives(self) -> u32 {
+        u32::from(self == Self::Blessed)
+    }
+}
+
+// Which variant the fruit of a level is. Level 1 always has a plain fruit.
+pub fn fruit_kind(seed: u64, level: u32) -> FruitKind {
+    if level <= 1 {
+        return FruitKind::Normal;
+    }
+    let mut rng = SeededRng::new(seed.wrapping_mul(131));
+    match rng.below(VARIANT_ODDS) {
+        0 => FruitKind::Blessed,
+        1 => FruitKind::Cursed,
+        2 if level >= FLEEING_MIN_LEVEL => FruitKind::Fleeing,
+        _ => FruitKind::Normal,
+    }
+}
+
+// The debuff a cursed fruit puts on the player
+pub fn curse_effect(seed: u64) -> StatusEffect {
+    let mut rng = SeededRng::new(seed.wrapping_mul(17));
+    StatusEffect::CURSES[rng.below(StatusEffect::CURSES.len())]
+}
+
+// How hard a platform is to land on: narrow and far from the start is hard
+fn danger(platform: &PlatformSpec) -> f32 {
+    let distance = (platform.x - STARTING_PLATFORM.x).hypot(platform.y - STARTING_PLATFORM.y);
+    distance / platform.width
+}
+
+// The platform that is hardest to land on
+pub fn most_dangerous_platform(platforms: &[PlatformSpec]) -> Option<&PlatformSpec> {
+    platforms
+        .iter()
+        .filter(|platform| **platform != STARTING_PLATFORM)
+        .max_by(|a, b| danger(a).total_cmp(&danger(b)))
+}
+
+// Where a blessed fruit sits: on top of the most dangerous platform
+pub fn blessed_fruit_position(platforms: &[PlatformSpec]) -> Option<(f32, f32)> {
+    most_dangerous_platform(platforms).map(|platform| (platform.x, platform.y + PLATFORM_HEIGHT / 2.0 + FRUIT_SIZE / 2.0))
+}
+
+// Kind and position of a level's fruit. `seed` is the level's fruit seed;
+// `platforms` is a generated layout, starting platform first.
+pub fn place_fruit(platforms: &[PlatformSpec], seed: u64, level: u32) -> Option<(FruitKind, (f32, f32))> {
+    let kind = fruit_kind(seed, level);
+    let position = if kind == FruitKind::Blessed {
+        blessed_fruit_position(platforms)
+    } else {
+        fruit_position(&fruit_spots(platforms, 0), seed)
+    };
+    position.map(|position| (kind, position))
+}
+
+// Up to `count` bonus fruit for a generated layout (starting platform first),
+// each on its own platform and never on the start or the platforms in `taken`.
+// Harder platforms are picked `bias` times more often than the easiest ones
+// (see `DifficultyCurve::fruit_bias`), and no two fruit are closer than the
+// generator keeps two platforms with a gap of `min_gap`.
+pub fn place_bonus_fruit(platforms: &[PlatformSpec], taken: &[usize], seed: u64, count: usize, bias: f32, min_gap: f32) -> Vec<(f32, f32)> {
+    pick_fruit_spots(platforms, taken, SeededRng::new(seed.wrapping_mul(191)), count, bias, min_gap)
+}
+
+// The `count` plain fruit a level needs collected besides its own fruit (see
+// `DifficultyCurve::fruit_goal`), placed the way bonus fruit are but evenly
+// over the platforms left
+pub fn place_goal_fruit(platforms: &[PlatformSpec], taken: &[usize], seed: u64, count: usize, min_gap: f32) -> Vec<(f32, f32)> {
+    pick_fruit_spots(platforms, taken, SeededRng::new(seed.wrapping_mul(229)), count, 0.0, min_gap)
+}
+
+fn pick_fruit_spots(platforms: &[PlatformSpec], taken: &[usize], mut rng: SeededRng, count: usize, bias: f32, min_gap: f32) -> Vec<(f32, f32)> {
+    let mut candidates: Vec<usize> = (1..platforms.len()).filter(|index| !taken.contains(index)).collect();
+    let hardest = candidates.iter().map(|&index| danger(&platforms[index])).fold(0.0, f32::max);
+    let weight = |index: usize| 1.0 + bias * danger(&platforms[index]) / hardest.max(f32::EPSILON);
+    let mut fruit = Vec::new();
+    while fruit.len() < count && !candidates.is_empty() {
+        let total: f32 = candidates.iter().map(|&index| weight(index)).sum();
+        let mut roll = rng.fraction() * total;
+        let mut picked = candidates.len() - 1;
+        for (slot, &index) in candidates.iter().enumerate() {
+            roll -= weight(index);
+            if roll < 0.0 {
+                picked = slot;
+                break;
+            }
+        }
+        let spot = fruit_spot(&platforms[candidates.swap_remove(picked)]);
+        if fruit.iter().all(|&other| !too_close(spot, FRUIT_SIZE, other, FRUIT_SIZE, min_gap)) {
+            fruit.push(spot);
+        }
+    }
+    fruit
+}
@@ -0,0 +1,307 @@
+// Seeded level generation: platform layouts and fruit placement
+
+use crate::config::{FRUIT_SIZE, PLATFORM_HEIGHT, PLAYER_SIZE, PLAYER_SPAWN};
+use crate::difficulty::LayoutParams;
+use crate::rng::SeededRng;
+use crate::world::WorldBounds;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PlatformSpec {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    // Can be jumped up through from below; only landing on it from above
+    // collides (see `physics::resolve_platform_collisions`)
+    pub one_way: bool,
+    // How much higher the top gets for each pixel to the right: 0.0 is flat,
+    // 1.0 a 45° ramp up to the right. A sloped platform is a slab `height`
+    // thick whose top still passes through the middle of where a flat one's
+    // would be.
+    pub slope: f32,
+}
+
+impl PlatformSpec {
+    // How high the top is above `x`, or above the nearer end when `x` is past one
+    pub fn top_at(&self, x: f32) -> f32 {
+        let half_width = self.width / 2.0;
+        self.y + self.height / 2.0 + self.slope * (x - self.x).clamp(-half_width, half_width)
+    }
+
+    // Half the height of the box around it, ends and all
+    pub fn half_extent_y(&self) -> f32 {
+        self.height / 2.0 + self.slope.abs() * self.width / 2.0
+    }
+}
+
+pub const STARTING_PLATFORM: PlatformSpec = PlatformSpec { x: 0.0, y: 100.0, width: 200.0, height: PLATFORM_HEIGHT, one_way: false, slope: 0.0 };
+
+// A gentle ramp and the steepest slope (45°) there is
+pub const GENTLE_SLOPE: f32 = 0.25;
+pub const STEEP_SLOPE: f32 = 1.0;
+// How much higher a generated ramp's high end is than its low end, at most;
+// steep ones are shortened to keep to it
+const MAX_RAMP_RISE: f32 = 80.0;
+
+const MIN_PLATFORM_DISTANCE: f32 = 80.0; // Minimum distance between platform centres
+pub const MIN_VERTICAL_GAP: f32 = 60.0; // Minimum vertical space for jumping
+
+// Whether two things `a_width` and `b_width` wide, centred at `a` and `b`, are
+// too close together for one level: side by side with less than `min_gap`
+// between their edges and not far enough apart vertically to jump between,
+// or just too close overall
+pub fn too_close(a: (f32, f32), a_width: f32, b: (f32, f32), b_width: f32, min_gap: f32) -> bool {
+    let distance_x = (a.0 - b.0).abs();
+    let distance_y = (a.1 - b.1).abs();
+    let required_horizontal_gap = a_width / 2.0 + b_width / 2.0 + min_gap;
+    (distance_x < required_horizontal_gap && distance_y < MIN_VERTICAL_GAP) || (distance_x * distance_x + distance_y * distance_y).sqrt() < MIN_PLATFORM_DISTANCE
+}
+
+// Generates the platform layout for a seed, spread over `bounds`. The starting
+// platform is always first. Harder `layout`s (see `DifficultyCurve::layout`)
+// have fewer and narrower platforms, further apart.
+pub fn generate_platform_layout(seed: u64, layout: &LayoutParams, bounds: WorldBounds) -> Vec<PlatformSpec> {
+    // Always ensure there's a starting platform near the player first
+    let mut platforms = vec![STARTING_PLATFORM];
+    let mut rng = SeededRng::new(seed);
+
+    // Generate random platforms per screen (6-10 normally) with proper spacing
+    let (fewest, most) = layout.platforms_per_screen;
+    let per_screen = (fewest + rng.below(most.saturating_sub(fewest) + 1)).saturating_sub(layout.fewer_platforms).max(1);
+    let num_platforms = (per_screen as f32 * bounds.screens()).round() as usize;
+    let mut attempts = 0;
+    let max_attempts = num_platforms * 10; // Limit attempts to prevent infinite loops
+
+    while platforms.len() < num_platforms + 1 && attempts < max_attempts {
+        attempts += 1;
+
+        // Generate random position and size
+        let width = rng.range(layout.widths.0, layout.widths.1) * layout.width_scale; // Width between 120-220 on the easiest levels, normally
+        let x = (rng.fraction() - 0.5) * (bounds.width - width - 100.0);
+        let y = (rng.fraction() - 0.5) * (bounds.height - 150.0);
+
+        // Check if this position is valid (enough space from other platforms)
+        let mut valid_position = !platforms
+            .iter()
+            .any(|existing| too_close((x, y), width, (existing.x, existing.y), existing.width, layout.min_gap));
+
+        // Don't place platforms too close to starting area
+        if x.abs() < 120.0 && (y - STARTING_PLATFORM.y).abs() < 70.0 {
+            valid_position = false;
+        }
+        // Or across the spawn point, where the player appears above it
+        if (x - PLAYER_SPAWN.0).abs() < (width + PLAYER_SIZE) / 2.0 && (y - PLAYER_SPAWN.1).abs() < PLAYER_SIZE + PLATFORM_HEIGHT {
+            valid_position = false;
+        }
+
+        // Keep platforms reasonably within bounds
+        if x.abs() > bounds.half_width() - width / 2.0 - 50.0 || y.abs() > bounds.half_height() - 100.0 {
+            valid_position = false;
+        }
+
+        if valid_position {
+            platforms.push(PlatformSpec { x, y, width, height: PLATFORM_HEIGHT, one_way: false, slope: 0.0 });
+        }
+    }
+
+    platforms
+}
+
+// Makes some of a generated level's platforms one-way, each with a chance of
+// `odds` out of 100. The starting platform and the ones in `keep_solid`
+// (moving platforms, say) never are.
+pub fn mark_one_way_platforms(platforms: &mut [PlatformSpec], keep_solid: &[usize], seed: u64, odds: usize) {
+    if odds == 0 {
+        return;
+    }
+    let mut rng = SeededRng::new(seed);
+    for (index, platform) in platforms.iter_mut().enumerate().skip(1) {
+        // Rolled for every platform so one kept solid doesn't shift the rest
+        let roll = rng.below(100);
+        platform.one_way = roll < odds && !keep_solid.contains(&index);
+    }
+}
+
+// Tilts some of a generated level's platforms into ramps, each with a chance
+// of `odds` out of 100: mostly gentle ones, some steep (and shortened to keep
+// the rise to `MAX_RAMP_RISE`), either way up. The starting platform, the
+// ones in `keep_flat` (anything standing on them expects a flat top) and ones
+// with another platform close above or below never are.
+pub fn tilt_platforms(platforms: &mut [PlatformSpec], keep_flat: &[usize], seed: u64, odds: usize) {
+    if odds == 0 {
+        return;
+    }
+    let mut rng = SeededRng::new(seed);
+    for index in 1..platforms.len() {
+        // Rolled for every platform so one kept flat doesn't shift the rest
+        let (roll, steep, direction) = (rng.below(100), rng.below(4) == 0, if rng.below(2) == 0 { -1.0 } else { 1.0 });
+        if roll >= odds || keep_flat.contains(&index) {
+            continue;
+        }
+        let slope = direction * if steep { STEEP_SLOPE } else { GENTLE_SLOPE };
+        let tilted = PlatformSpec { width: platforms[index].width.min(MAX_RAMP_RISE / slope.abs()), slope, ..platforms[index] };
+        let crowded = platforms.iter().enumerate().any(|(other_index, other)| {
+            other_index != index
+                && (other.x - tilted.x).abs() < (other.width + tilted.width) / 2.0 + PLAYER_SIZE
+                && (other.y - tilted.y).abs() < other.half_extent_y() + tilted.half_extent_y() + PLAYER_SIZE
+        });
+        if !crowded {
+            platforms[index] = tilted;
+        }
+    }
+}
+
+// A level's platforms as generated, and which one the player starts on. The
+// fruit and anything else placed on the level goes on these spots, so
+// placement never depends on what happens to be spawned at the moment.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LevelLayout {
+    pub platforms: Vec<PlatformSpec>,
+    // Index of the starting platform in `platforms`
+    pub start: usize,
+}
+
+impl LevelLayout {
+    // A generated layout: the starting platform comes first
+    pub fn generated(platforms: Vec<PlatformSpec>) -> Self {
+        Self { platforms, start: 0 }
+    }
+
+    // A layout whose player spawns at `spawn`; the start is the highest
+    // platform under it (the first one if nothing is)
+    pub fn with_spawn(platforms: Vec<PlatformSpec>, spawn: (f32, f32)) -> Self {
+        let start = platforms
+            .iter()
+            .enumerate()
+            .filter(|(_, platform)| (platform.x - spawn.0).abs() <= platform.width / 2.0 && platform.y <= spawn.1)
+            .max_by(|(_, a), (_, b)| a.y.total_cmp(&b.y))
+            .map_or(0, |(index, _)| index);
+        Self { platforms, start }
+    }
+
+    // Where a fruit could sit: centred on top of every platform but the start
+    pub fn fruit_spots(&self) -> Vec<(f32, f32)> {
+        fruit_spots(&self.platforms, self.start)
+    }
+}
+
+// Where a fruit could sit on each of `platforms` but the one at `start`
+pub fn fruit_spots(platforms: &[PlatformSpec], start: usize) -> Vec<(f32, f32)> {
+    platforms
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| *index != start)
+        .map(|(_, platform)| fruit_spot(platform))
+        .collect()
+}
+
+// Where a fruit sits on `platform`: centred on top of it
+pub fn fruit_spot(platform: &PlatformSpec) -> (f32, f32) {
+    (platform.x, platform.y + platform.height / 2.0 + FRUIT_SIZE / 2.0)
+}
+
+// Picks one of `spots` (see `fruit_spots`) for the fruit
+pub fn fruit_position(spots: &[(f32, f32)], seed: u64) -> Option<(f32, f32)> {
+    if spots.is_empty() {
+        return None; // No platforms available for fruit placement
+    }
+    let mut rng = SeededRng::new(seed.wrapping_mul(73));
+    Some(spots[rng.below(spots.len())])
+}
+
+// Seeds used for a level of a seeded run, matching what the game does
+pub fn level_seed(run_seed: u64, level: u32) -> u64 {
+    if level <= 1 {
+        run_seed
+    } else {
+        run_seed.wrapping_add(level as u64 * 1000)
+    }
+}
+
+pub fn fruit_seed(run_seed: u64, level: u32) -> u64 {
+    if level <= 1 {
+        run_seed.wrapping_add(99)
+    } else {
+        level_seed(run_seed, level).wrapping_add(42)
+    }
+}
+
+// Seed for the modifier draft offered on reaching `level`
+pub fn draft_seed(run_seed: u64, level: u32) -> u64 {
+    level_seed(run_seed, level).wrapping_add(7)
+}
+
+// Seed for where a level's enemies stand
+pub fn enemy_seed(run_seed: u64, level: u32) -> u64 {
+    level_seed(run_seed, level).wrapping_add(13)
+}
+
+// Seed for what a level's platforms are made of
+pub fn surface_seed(run_seed: u64, level: u32) -> u64 {
+    level_seed(run_seed, level).wrapping_add(19)
+}
+
+// Seed for which of a level's platforms move
+pub fn moving_seed(run_seed: u64, level: u32) -> u64 {
+    level_seed(run_seed, level).wrapping_add(17)
+}
+
+// Seed for a level's power-ups
+pub fn power_up_seed(run_seed: u64, level: u32) -> u64 {
+    level_seed(run_seed, level).wrapping_add(23)
+}
+
+// Seed for which of a level's platforms are one-way
+pub fn one_way_seed(run_seed: u64, level: u32) -> u64 {
+    level_seed(run_seed, level).wrapping_add(29)
+}
+
+// Seed for which of a level's platforms crumble
+pub fn crumbling_seed(run_seed: u64, level: u32) -> u64 {
+    level_seed(run_seed, level).wrapping_add(31)
+}
+
+// Seed for which of a level's platforms are traps, and what kind
+pub fn trap_seed(run_seed: u64, level: u32) -> u64 {
+    level_seed(run_seed, level).wrapping_add(37)
+}
+
+// Seed for where a level's ladders and ropes go
+pub fn ladder_seed(run_seed: u64, level: u32) -> u64 {
+    level_seed(run_seed, level).wrapping_add(41)
+}
+
+// Seed for where a level's spikes and lava pools go
+pub fn hazard_seed(run_seed: u64, level: u32) -> u64 {
+    level_seed(run_seed, level).wrapping_add(43)
+}
+
+// Seed for where a level's wind zones blow
+pub fn wind_seed(run_seed: u64, level: u32) -> u64 {
+    level_seed(run_seed, level).wrapping_add(61)
+}
+
+// Seed for where a level's portals go
+pub fn portal_seed(run_seed: u64, level: u32) -> u64 {
+    level_seed(run_seed, level).wrapping_add(71)
+}
+
+// Seed for which of a level's platforms are conveyor belts
+pub fn conveyor_seed(run_seed: u64, level: u32) -> u64 {
+    level_seed(run_seed, level).wrapping_add(67)
+}
+
+// Seed for which of a level's platforms are ramps
+pub fn slope_seed(run_seed: u64, level: u32) -> u64 {
+    level_seed(run_seed, level).wrapping_add(53)
+}
+
+// Seed for how a level's platforms are dressed up (see `look`)
+pub fn look_seed(run_seed: u64, level: u32) -> u64 {
+    level_seed(run_seed, level).wrapping_add(59)
+}
+
+// Seed for a level's name and flavor text
+pub fn name_seed(run_seed: u64, level: u32) -> u64 {
+    level_seed(run_seed, level).wrapping_add(47)
+}
@@ -0,0 +1,150 @@
+// Hazard tiles: spikes along one end of a platform's top, and lava pools
+// along the bottom of the level. Touching either costs a life, like an enemy
+// or a fall. A spiked platform always keeps most of its top clear, and
+// spikes that would leave the fruit out of reach without landing on them
+// are left out (see `clear_footing`).
+
+use crate::config::PLAYER_SIZE;
+use crate::generation::PlatformSpec;
+use crate::physics::Body;
+use crate::rng::SeededRng;
+use crate::world::WorldBounds;
+
+pub const SPIKE_HEIGHT: f32 = 14.0;
+pub const LAVA_DEPTH: f32 = 40.0;
+// Only platforms this wide get spikes, and the spikes cover this share of
+// them (short of half, so there's always room for the player to land)
+const MIN_SPIKED_WIDTH: f32 = 110.0;
+const SPIKED_SHARE: (f32, f32) = (0.3, 0.4);
+const LAVA_WIDTH: (f32, f32) = (300.0, 900.0);
+// Tries at fitting a lava pool in before giving up on it
+const LAVA_ATTEMPTS: usize = 10;
+// A body has to be this far into a hazard to be hurt, so grazing a corner
+// doesn't cost a life
+const GRACE: f32 = 4.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HazardKind {
+    Spikes,
+    Lava,
+}
+
+impl HazardKind {
+    pub const ALL: [Self; 2] = [Self::Spikes, Self::Lava];
+
+    // The name used in level files
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Spikes => "spikes",
+            Self::Lava => "lava",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.name().eq_ignore_ascii_case(name))
+    }
+}
+
+// A hazard's box, by its centre
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HazardTile {
+    pub kind: HazardKind,
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl HazardTile {
+    // Spikes `width` wide on top of `platform`, at its left end (`side` -1.0)
+    // or its right end (1.0)
+    pub fn spikes_on(platform: &PlatformSpec, width: f32, side: f32) -> Self {
+        Self {
+            kind: HazardKind::Spikes,
+            x: platform.x + side * (platform.width - width) / 2.0,
+            y: platform.y + platform.height / 2.0 + SPIKE_HEIGHT / 2.0,
+            width,
+            height: SPIKE_HEIGHT,
+        }
+    }
+
+    pub fn touches(&self, body: &Body) -> bool {
+        let reach_x = (self.width + body.size) / 2.0 - GRACE;
+        let reach_y = (self.height + body.size) / 2.0 - GRACE;
+        (body.x - self.x).abs() < reach_x && (body.y - self.y).abs() < reach_y
+    }
+
+    // Whether these spikes sit on top of `platform`
+    fn on(&self, platform: &PlatformSpec) -> bool {
+        self.kind == HazardKind::Spikes
+            && (self.y - self.height / 2.0 - (platform.y + platform.height / 2.0)).abs() < 1.0
+            && (self.x - platform.x).abs() < platform.width / 2.0
+    }
+
+    // The platform-like box the hazard takes up, for the safe zone checks
+    pub fn spec(&self) -> PlatformSpec {
+        PlatformSpec { x: self.x, y: self.y, width: self.width, height: self.height, one_way: false, slope: 0.0 }
+    }
+}
+
+// Spikes on up to `count` platforms wide enough for them: never the starting
+// platform (the first one) or any platform listed in `keep_clear`
+pub fn place_spikes(platforms: &[PlatformSpec], seed: u64, count: usize, keep_clear: &[usize]) -> Vec<HazardTile> {
+    let mut candidates: Vec<usize> =
+        (1..platforms.len()).filter(|index| !keep_clear.contains(index) && platforms[*index].width >= MIN_SPIKED_WIDTH).collect();
+    let mut rng = SeededRng::new(seed);
+    let mut spikes = Vec::new();
+    while spikes.len() < count && !candidates.is_empty() {
+        let platform = &platforms[candidates.swap_remove(rng.below(candidates.len()))];
+        let width = (platform.width * rng.range(SPIKED_SHARE.0, SPIKED_SHARE.1)).round();
+        let side = if rng.below(2) == 0 { -1.0 } else { 1.0 };
+        spikes.push(HazardTile::spikes_on(platform, width, side));
+    }
+    spikes
+}
+
+// Up to `count` lava pools along the bottom of `bounds`, each kept at least a
+// player's height below every platform above it
+pub fn place_lava(platforms: &[PlatformSpec], seed: u64, count: usize, bounds: WorldBounds) -> Vec<HazardTile> {
+    let mut rng = SeededRng::new(seed.wrapping_mul(7));
+    let mut pools: Vec<HazardTile> = Vec::new();
+    let top = -bounds.half_height() + LAVA_DEPTH;
+    for _ in 0..count {
+        for _ in 0..LAVA_ATTEMPTS {
+            let width = rng.range(LAVA_WIDTH.0, LAVA_WIDTH.1).min(bounds.width);
+            let x = rng.range(-bounds.half_width() + width / 2.0, bounds.half_width() - width / 2.0);
+            let pool = HazardTile { kind: HazardKind::Lava, x, y: top - LAVA_DEPTH / 2.0, width, height: LAVA_DEPTH };
+            let overlaps_x = |left: f32, right: f32| left < pool.x + pool.width / 2.0 && right > pool.x - pool.width / 2.0;
+            let under_platform = platforms.iter().any(|platform| {
+                overlaps_x(platform.x - platform.width / 2.0, platform.x + platform.width / 2.0)
+                    && platform.y - platform.height / 2.0 - PLAYER_SIZE < top
+            });
+            let overlaps_pool = pools.iter().any(|other| overlaps_x(other.x - other.width / 2.0, other.x + other.width / 2.0));
+            if !under_platform && !overlaps_pool {
+                pools.push(pool);
+                break;
+            }
+        }
+    }
+    pools
+}
+
+// `platforms` with their spiked ends cut off: where a player can land
+// without touching spikes, for checking the level can still be finished
+pub fn clear_footing(platforms: &[PlatformSpec], hazards: &[HazardTile]) -> Vec<PlatformSpec> {
+    platforms
+        .iter()
+        .map(|platform| {
+            let mut clear = *platform;
+            for spikes in hazards.iter().filter(|hazard| hazard.on(platform)) {
+                let (left, right) = (clear.x - clear.width / 2.0, clear.x + clear.width / 2.0);
+                let (spikes_left, spikes_right) = (spikes.x - spikes.width / 2.0, spikes.x + spikes.width / 2.0);
+                // Keep the wider of the two clear stretches either side
+                let (left, right) = if spikes_left - left >= right - spikes_right { (left, spikes_left.max(left)) } else { (spikes_right.min(right), right) };
+                clear.x = (left + right) / 2.0;
+                clear.width = right - left;
+            }
+            clear
+        })
+        .collect()
+}
@@ -0,0 +1,52 @@
+// Helper platforms. Picking up the helper platform power-up lets the player
+// put a short-lived platform right under their feet in mid-air (down + jump),
+// a couple of times before the level ends. The platform collides like any
+// other until it runs out.
+
+use crate::config::PLATFORM_HEIGHT;
+use crate::generation::PlatformSpec;
+use crate::physics::Body;
+
+// Platforms one pickup gives; any left over are lost when the level ends
+pub const HELPER_PLATFORM_USES: u32 = 2;
+pub const HELPER_PLATFORM_SECONDS: f32 = 3.0;
+pub const HELPER_PLATFORM_WIDTH: f32 = 90.0;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HelperPlatform {
+    pub spec: PlatformSpec,
+    pub seconds_left: f32,
+}
+
+impl HelperPlatform {
+    // A platform whose top is at the feet of `body`
+    pub fn beneath(body: &Body) -> Self {
+        let feet = body.y - body.size / 2.0;
+        Self {
+            spec: PlatformSpec {
+                x: body.x,
+                y: feet - PLATFORM_HEIGHT / 2.0,
+                width: HELPER_PLATFORM_WIDTH,
+                height: PLATFORM_HEIGHT,
+                one_way: false,
+                slope: 0.0,
+            },
+            seconds_left: HELPER_PLATFORM_SECONDS,
+        }
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        self.seconds_left -= dt;
+    }
+
+    pub fn expired(&self) -> bool {
+        self.seconds_left <= 0.0
+    }
+}
+
+// Whether this tick's input puts a helper platform down: down + a fresh jump
+// press while airborne, with a use left. It replaces the jump (an air jump
+// included).
+pub fn summons_helper(body: &Body, jump_pressed: bool, down_held: bool, uses: u32) -> bool {
+    jump_pressed && down_held && !body.grounded && uses > 0
+}
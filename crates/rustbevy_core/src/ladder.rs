@@ -0,0 +1,144 @@
+// Ladders and ropes. Generated levels put a few between platforms further
+// apart vertically than a comfortable jump, and hand-made levels can place
+// their own. A player on one holding up or down grabs it: no gravity and no
+// platform collisions, just climbing straight up or down it (slower on a
+// rope). Climbing off the top leaves them standing on the upper platform and
+// off the bottom on the lower one; a jump lets go.
+
+use crate::generation::PlatformSpec;
+use crate::physics::{Body, JumpTimers, SimInput, Tuning};
+use crate::rng::SeededRng;
+
+pub const LADDER_WIDTH: f32 = 28.0;
+pub const ROPE_WIDTH: f32 = 8.0;
+pub const CLIMB_SPEED: f32 = 180.0;
+pub const ROPE_CLIMB_SPEED: f32 = 130.0;
+// Generated levels get at most this many
+pub const MAX_LADDERS: usize = 2;
+// Platforms closer than this (top to top) are left to jumping
+const MIN_LADDER_RISE: f32 = 100.0;
+const MAX_LADDER_RISE: f32 = 450.0;
+// Slack at the ends, so feet resting on a platform count as at the end
+const GRAB_MARGIN: f32 = 1.0;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Ladder {
+    pub x: f32,
+    // Where the player's feet are at the ends: the tops of the platforms it
+    // joins
+    pub bottom: f32,
+    pub top: f32,
+    pub rope: bool,
+}
+
+impl Ladder {
+    // From the top of `lower` to the top of `upper`, at `x`
+    pub fn between(lower: &PlatformSpec, upper: &PlatformSpec, x: f32, rope: bool) -> Self {
+        Self { x, bottom: lower.y + lower.height / 2.0, top: upper.y + upper.height / 2.0, rope }
+    }
+
+    pub fn width(&self) -> f32 {
+        if self.rope {
+            ROPE_WIDTH
+        } else {
+            LADDER_WIDTH
+        }
+    }
+
+    pub fn climb_speed(&self) -> f32 {
+        if self.rope {
+            ROPE_CLIMB_SPEED
+        } else {
+            CLIMB_SPEED
+        }
+    }
+
+    // Whether `body` is on the ladder: its middle within a ladder's width of
+    // it (a rope is drawn thinner but is as easy to grab) and its feet between
+    // the ends
+    pub fn reaches(&self, body: &Body) -> bool {
+        let feet = body.y - body.size / 2.0;
+        (body.x - self.x).abs() <= LADDER_WIDTH / 2.0 && feet >= self.bottom - GRAB_MARGIN && feet <= self.top + GRAB_MARGIN
+    }
+
+    // Whether `body` grabs the ladder this step: on it and holding up with
+    // room to climb up, or down with room to climb down
+    pub fn grabbed_by(&self, body: &Body, input: SimInput) -> bool {
+        let feet = body.y - body.size / 2.0;
+        self.reaches(body) && ((input.up && feet < self.top - GRAB_MARGIN) || (input.down && feet > self.bottom + GRAB_MARGIN))
+    }
+}
+
+// One step on `ladder`. Returns whether the body is still climbing: a jump
+// lets go (with the jump) unless up is held too, as the up keys are also jump
+// keys, and so does climbing off either end or no longer being on the ladder
+// (something moved the body off it). The ladder moves the body itself, so it
+// has no velocity while it climbs.
+pub fn climb(body: &mut Body, jumps: &mut JumpTimers, ladder: &Ladder, input: SimInput, jump_pressed: bool, tuning: &Tuning, dt: f32) -> bool {
+    if !ladder.reaches(body) {
+        return false;
+    }
+    body.grounded = false;
+    body.wall = None;
+    *jumps = JumpTimers { air_jumps_left: tuning.air_jumps, ..JumpTimers::default() };
+    if jump_pressed && !input.up {
+        body.velocity_x = input.horizontal() * tuning.player_speed;
+        body.velocity_y = tuning.jump_speed;
+        jumps.jumped = true;
+        return false;
+    }
+    let direction = f32::from(input.up as u8) - f32::from(input.down as u8);
+    let half_size = body.size / 2.0;
+    let feet = body.y - half_size + direction * ladder.climb_speed() * dt;
+    body.x = ladder.x;
+    body.velocity_x = 0.0;
+    body.velocity_y = 0.0;
+    body.y = feet.clamp(ladder.bottom, ladder.top) + half_size;
+    feet > ladder.bottom && feet < ladder.top
+}
+
+// Up to `count` ladders (some of them ropes), each from the top of a platform
+// to one above it that overlaps it enough to fit one, with no other platform
+// in the way. Platforms listed in `keep_clear` (moving, crumbling or trap
+// platforms) get none.
+pub fn place_ladders(platforms: &[PlatformSpec], seed: u64, count: usize, keep_clear: &[usize]) -> Vec<Ladder> {
+    let mut rng = SeededRng::new(seed.wrapping_mul(211));
+    let mut candidates: Vec<(usize, usize)> = Vec::new();
+    for lower in (0..platforms.len()).filter(|index| !keep_clear.contains(index)) {
+        for upper in (0..platforms.len()).filter(|index| !keep_clear.contains(index)) {
+            let rise = platforms[upper].y - platforms[lower].y;
+            if (MIN_LADDER_RISE..=MAX_LADDER_RISE).contains(&rise) && overlap(&platforms[lower], &platforms[upper]).is_some() {
+                candidates.push((lower, upper));
+            }
+        }
+    }
+    let mut ladders: Vec<Ladder> = Vec::new();
+    while ladders.len() < count && !candidates.is_empty() {
+        let (lower, upper) = candidates.swap_remove(rng.below(candidates.len()));
+        let Some((left, right)) = overlap(&platforms[lower], &platforms[upper]) else {
+            continue;
+        };
+        let rope = rng.below(3) == 0;
+        let ladder = Ladder::between(&platforms[lower], &platforms[upper], rng.range(left, right), rope);
+        let in_the_way = platforms.iter().enumerate().any(|(index, platform)| {
+            index != lower
+                && index != upper
+                && (platform.x - ladder.x).abs() < (platform.width + LADDER_WIDTH) / 2.0
+                && platform.y + platform.height / 2.0 > ladder.bottom
+                && platform.y - platform.height / 2.0 < ladder.top
+        });
+        let crowded = ladders.iter().any(|other| (other.x - ladder.x).abs() < LADDER_WIDTH * 2.0 && other.bottom < ladder.top && ladder.bottom < other.top);
+        if !in_the_way && !crowded {
+            ladders.push(ladder);
+        }
+    }
+    ladders
+}
+
+// The span of x a ladder between `lower` and `upper` can stand at: over both,
+// a ladder's width in from their ends
+fn overlap(lower: &PlatformSpec, upper: &PlatformSpec) -> Option<(f32, f32)> {
+    let left = (lower.x - lower.width / 2.0).max(upper.x - upper.width / 2.0) + LADDER_WIDTH;
+    let right = (lower.x + lower.width / 2.0).min(upper.x + upper.width / 2.0) - LADDER_WIDTH;
+    (left < right).then_some((left, right))
+}
@@ -0,0 +1,511 @@
+// A complete level: the platform layout (some of it one-way, some ramps) plus its fruit
+// (more than one on later levels) or exit door and keys, and bonus fruit, power-ups, enemies, moving, crumbling, trap and
+// conveyor platforms, ladders, spikes, lava, wind, portals and checkpoints, checked
+// to be finishable with the player's current jump profile. How much of each a
+// level gets comes from the `DifficultyCurve`. Enemies start inside the
+// level's spawn zones, and no hazard is left in a safe zone (see `zones`).
+
+use crate::collectible::{place_power_ups, Collectible};
+use crate::checkpoint::{checkpoint_on, place_checkpoints};
+use crate::config::{FRUIT_SIZE, PLAYER_SPAWN};
+use crate::crumbling::{place_crumbling_platforms, CrumblingPlatform};
+use crate::difficulty::DifficultyCurve;
+use crate::enemy::{place_enemies, EnemyAi, ENEMY_SIZE};
+use crate::exit::ExitDoor;
+use crate::fruit::{place_bonus_fruit, place_fruit, place_goal_fruit, FruitKind};
+use crate::generation::{
+    conveyor_seed, crumbling_seed, enemy_seed, fruit_seed, generate_platform_layout, hazard_seed, ladder_seed, level_seed, mark_one_way_platforms, moving_seed, one_way_seed,
+    portal_seed, power_up_seed, slope_seed, surface_seed, tilt_platforms, trap_seed, wind_seed, PlatformSpec,
+};
+use crate::hazard::{clear_footing, place_lava, place_spikes, HazardKind, HazardTile};
+use crate::ladder::{place_ladders, Ladder, MAX_LADDERS};
+use crate::moving::{place_moving_platforms, PlatformMotion, PlatformPath};
+use crate::portal::{place_portals, PortalPair, PORTAL_HEIGHT};
+use crate::reachability::{fruit_reachable, holds_fruit, reachable_from, JumpProfile};
+use crate::surface::{platform_surfaces, Surface};
+use crate::theme::LevelTheme;
+use crate::trap::{place_traps, TrapPlatform};
+use crate::wind::{place_conveyors, place_wind, Conveyor, Wind};
+use crate::world::WorldBounds;
+use crate::zones::{clear_of, enemy_spawn_zones, safe_zones, SafeZone, Zone};
+
+// Layouts tried before settling for one that may not be finishable. Levels span
+// several screens, so the fruit is out of reach more often than on one screen.
+const MAX_LAYOUT_ATTEMPTS: u64 = 16;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Level {
+    pub platforms: Vec<PlatformSpec>,
+    // What each platform is made of, in the same order
+    pub surfaces: Vec<Surface>,
+    // The fruit that finishes the level
+    pub fruit: Option<(FruitKind, (f32, f32))>,
+    // Plain fruit that have to be collected along with it
+    pub goal_fruit: Vec<(f32, f32)>,
+    // The door that finishes the level instead, on levels without fruit to collect
+    pub exit: Option<ExitDoor>,
+    // Plain fruit worth points that don't finish the level
+    pub bonus_fruit: Vec<(f32, f32)>,
+    pub power_ups: Vec<(Collectible, (f32, f32))>,
+    pub enemies: Vec<EnemyAi>,
+    pub moving: Vec<PlatformMotion>,
+    pub crumbling: Vec<CrumblingPlatform>,
+    pub traps: Vec<TrapPlatform>,
+    pub conveyors: Vec<Conveyor>,
+    pub ladders: Vec<Ladder>,
+    // Spikes and lava pools
+    pub hazards: Vec<HazardTile>,
+    pub winds: Vec<Wind>,
+    pub portals: Vec<PortalPair>,
+    // Where each checkpoint flag stands
+    pub checkpoints: Vec<(f32, f32)>,
+    // Where enemies may start; a hand-made level without any lets them start anywhere
+    pub enemy_zones: Vec<Zone>,
+    // Around the spawn point and each checkpoint, kept free of hazards
+    pub safe_zones: Vec<SafeZone>,
+    pub theme: LevelTheme,
+}
+
+// Whether something centred at `position` and `size` tall stands on `platform`
+fn stands_on(platform: &PlatformSpec, position: (f32, f32), size: f32) -> bool {
+    let bottom = position.1 - size / 2.0;
+    (platform.x - position.0).abs() <= platform.width / 2.0 && (bottom - (platform.y + platform.height / 2.0)).abs() < 1.0
+}
+
+// Platforms that move, leaving the ones with fruit or an enemy on them still
+fn moving_platforms(platforms: &[PlatformSpec], fruit: &[(f32, f32)], enemies: &[EnemyAi], seed: u64, count: usize) -> Vec<PlatformMotion> {
+    let keep_still: Vec<usize> = (0..platforms.len())
+        .filter(|&index| {
+            let platform = &platforms[index];
+            fruit.iter().any(|&position| stands_on(platform, position, FRUIT_SIZE))
+                || enemies.iter().any(|enemy| stands_on(platform, (enemy.x, enemy.y), ENEMY_SIZE))
+        })
+        .collect();
+    place_moving_platforms(platforms, seed, count, &keep_still)
+}
+
+// Platforms that crumble, leaving the ones with fruit or an enemy on them,
+// the moving ones and the one-way ones solid
+fn crumbling_platforms(
+    platforms: &[PlatformSpec],
+    fruit: &[(f32, f32)],
+    enemies: &[EnemyAi],
+    moving: &[PlatformMotion],
+    seed: u64,
+    count: usize,
+) -> Vec<CrumblingPlatform> {
+    let keep_solid: Vec<usize> = (0..platforms.len())
+        .filter(|&index| {
+            let platform = &platforms[index];
+            platform.one_way
+                || fruit.iter().any(|&position| stands_on(platform, position, FRUIT_SIZE))
+                || enemies.iter().any(|enemy| stands_on(platform, (enemy.x, enemy.y), ENEMY_SIZE))
+                || moving.iter().any(|motion| motion.platform == index)
+        })
+        .collect();
+    place_crumbling_platforms(platforms, seed, count, &keep_solid)
+}
+
+// Trap platforms, leaving the ones with fruit or an enemy on them, the moving,
+// crumbling and one-way ones plain
+fn trap_platforms(
+    platforms: &[PlatformSpec],
+    fruit: &[(f32, f32)],
+    enemies: &[EnemyAi],
+    moving: &[PlatformMotion],
+    crumbling: &[CrumblingPlatform],
+    seed: u64,
+    count: usize,
+) -> Vec<TrapPlatform> {
+    let keep_plain: Vec<usize> = (0..platforms.len())
+        .filter(|&index| {
+            let platform = &platforms[index];
+            platform.one_way
+                || fruit.iter().any(|&position| stands_on(platform, position, FRUIT_SIZE))
+                || enemies.iter().any(|enemy| stands_on(platform, (enemy.x, enemy.y), ENEMY_SIZE))
+                || moving.iter().any(|motion| motion.platform == index)
+                || crumbling.iter().any(|crumbling| crumbling.platform == index)
+        })
+        .collect();
+    place_traps(platforms, seed, count, &keep_plain)
+}
+
+// Conveyor platforms, leaving the ones with fruit or an enemy on them, the
+// moving, crumbling, trap and one-way ones plain
+#[allow(clippy::too_many_arguments)]
+fn conveyor_platforms(
+    platforms: &[PlatformSpec],
+    fruit: &[(f32, f32)],
+    enemies: &[EnemyAi],
+    moving: &[PlatformMotion],
+    crumbling: &[CrumblingPlatform],
+    traps: &[TrapPlatform],
+    seed: u64,
+    count: usize,
+) -> Vec<Conveyor> {
+    let keep_plain: Vec<usize> = (0..platforms.len())
+        .filter(|&index| {
+            let platform = &platforms[index];
+            platform.one_way
+                || fruit.iter().any(|&position| stands_on(platform, position, FRUIT_SIZE))
+                || enemies.iter().any(|enemy| stands_on(platform, (enemy.x, enemy.y), ENEMY_SIZE))
+                || moving.iter().any(|motion| motion.platform == index)
+                || crumbling.iter().any(|crumbling| crumbling.platform == index)
+                || traps.iter().any(|trap| trap.platform == index)
+        })
+        .collect();
+    place_conveyors(platforms, seed, count, &keep_plain)
+}
+
+// Power-ups for a level, kept off the fruit's platforms and the moving,
+// crumbling and trap ones
+fn power_up_platforms(
+    platforms: &[PlatformSpec],
+    fruit: &[(f32, f32)],
+    moving: &[PlatformMotion],
+    crumbling: &[CrumblingPlatform],
+    traps: &[TrapPlatform],
+    seed: u64,
+    level: u32,
+) -> Vec<(Collectible, (f32, f32))> {
+    let taken: Vec<usize> = (0..platforms.len())
+        .filter(|&index| {
+            fruit.iter().any(|&position| stands_on(&platforms[index], position, FRUIT_SIZE))
+                || moving.iter().any(|motion| motion.platform == index)
+                || crumbling.iter().any(|crumbling| crumbling.platform == index)
+                || traps.iter().any(|trap| trap.platform == index)
+        })
+        .collect();
+    place_power_ups(platforms, &taken, seed, level)
+}
+
+// Ladders between a level's platforms, kept off the moving, crumbling and
+// trap ones
+fn ladders_between(
+    platforms: &[PlatformSpec],
+    moving: &[PlatformMotion],
+    crumbling: &[CrumblingPlatform],
+    traps: &[TrapPlatform],
+    seed: u64,
+) -> Vec<Ladder> {
+    let keep_clear: Vec<usize> = (0..platforms.len())
+        .filter(|&index| {
+            moving.iter().any(|motion| motion.platform == index)
+                || crumbling.iter().any(|crumbling| crumbling.platform == index)
+                || traps.iter().any(|trap| trap.platform == index)
+        })
+        .collect();
+    place_ladders(platforms, seed, MAX_LADDERS, &keep_clear)
+}
+
+// Spikes and lava for a level. Spikes are kept off the platforms with
+// anything else on them or at the end of a ladder, and the one-way, moving,
+// crumbling, trap and conveyor ones.
+#[allow(clippy::too_many_arguments)]
+fn hazards_for(
+    platforms: &[PlatformSpec],
+    pickups: &[(f32, f32)],
+    checkpoints: &[(f32, f32)],
+    enemies: &[EnemyAi],
+    moving: &[PlatformMotion],
+    crumbling: &[CrumblingPlatform],
+    traps: &[TrapPlatform],
+    conveyors: &[Conveyor],
+    ladders: &[Ladder],
+    seed: u64,
+    counts: (usize, usize),
+) -> Vec<HazardTile> {
+    let keep_clear: Vec<usize> = (0..platforms.len())
+        .filter(|&index| {
+            let platform = &platforms[index];
+            let top = platform.y + platform.height / 2.0;
+            platform.one_way
+                || pickups.iter().any(|&position| stands_on(platform, position, FRUIT_SIZE))
+                || checkpoints.iter().any(|&position| stands_on(platform, position, 0.0))
+                || enemies.iter().any(|enemy| stands_on(platform, (enemy.x, enemy.y), ENEMY_SIZE))
+                || moving.iter().any(|motion| motion.platform == index)
+                || crumbling.iter().any(|crumbling| crumbling.platform == index)
+                || traps.iter().any(|trap| trap.platform == index)
+                || conveyors.iter().any(|conveyor| conveyor.platform == index)
+                || ladders.iter().any(|ladder| {
+                    (ladder.x - platform.x).abs() <= platform.width / 2.0 && ((ladder.bottom - top).abs() < 1.0 || (ladder.top - top).abs() < 1.0)
+                })
+        })
+        .collect();
+    let mut hazards = place_spikes(platforms, seed, counts.0, &keep_clear);
+    hazards.extend(place_lava(platforms, seed, counts.1, WorldBounds::CLASSIC));
+    hazards
+}
+
+// The platforms of a level that stay flat: the one-way ones and the ones with
+// anything on them or at the end of a ladder, and the moving, crumbling, trap
+// and conveyor ones
+#[allow(clippy::too_many_arguments)]
+fn flat_platforms(
+    platforms: &[PlatformSpec],
+    pickups: &[(f32, f32)],
+    checkpoints: &[(f32, f32)],
+    enemies: &[EnemyAi],
+    moving: &[PlatformMotion],
+    crumbling: &[CrumblingPlatform],
+    traps: &[TrapPlatform],
+    conveyors: &[Conveyor],
+    ladders: &[Ladder],
+    hazards: &[HazardTile],
+) -> Vec<usize> {
+    (0..platforms.len())
+        .filter(|&index| {
+            let platform = &platforms[index];
+            let top = platform.y + platform.height / 2.0;
+            platform.one_way
+                || pickups.iter().any(|&position| stands_on(platform, position, FRUIT_SIZE))
+                || checkpoints.iter().any(|&position| stands_on(platform, position, 0.0))
+                || enemies.iter().any(|enemy| stands_on(platform, (enemy.x, enemy.y), ENEMY_SIZE))
+                || hazards.iter().any(|hazard| hazard.kind == HazardKind::Spikes && stands_on(platform, (hazard.x, hazard.y), hazard.height))
+                || moving.iter().any(|motion| motion.platform == index)
+                || crumbling.iter().any(|crumbling| crumbling.platform == index)
+                || traps.iter().any(|trap| trap.platform == index)
+                || conveyors.iter().any(|conveyor| conveyor.platform == index)
+                || ladders.iter().any(|ladder| {
+                    (ladder.x - platform.x).abs() <= platform.width / 2.0 && ((ladder.bottom - top).abs() < 1.0 || (ladder.top - top).abs() < 1.0)
+                })
+        })
+        .collect()
+}
+
+// Checkpoints for a level, kept off the platforms with fruit or an enemy
+// on them and the moving, crumbling, trap and conveyor ones
+fn checkpoint_platforms(
+    platforms: &[PlatformSpec],
+    fruit: &[(f32, f32)],
+    enemies: &[EnemyAi],
+    moving: &[PlatformMotion],
+    crumbling: &[CrumblingPlatform],
+    traps: &[TrapPlatform],
+    conveyors: &[Conveyor],
+) -> Vec<(f32, f32)> {
+    let taken: Vec<usize> = (0..platforms.len())
+        .filter(|&index| {
+            let platform = &platforms[index];
+            fruit.iter().any(|&position| stands_on(platform, position, FRUIT_SIZE))
+                || enemies.iter().any(|enemy| stands_on(platform, (enemy.x, enemy.y), ENEMY_SIZE))
+                || moving.iter().any(|motion| motion.platform == index)
+                || crumbling.iter().any(|crumbling| crumbling.platform == index)
+                || traps.iter().any(|trap| trap.platform == index)
+                || conveyors.iter().any(|conveyor| conveyor.platform == index)
+        })
+        .collect();
+    place_checkpoints(platforms, &taken, WorldBounds::CLASSIC)
+}
+
+impl Level {
+    // How many fruit finish the level; none when a door does
+    pub fn fruit_goal(&self) -> u32 {
+        (usize::from(self.fruit.is_some()) + self.goal_fruit.len()) as u32
+    }
+
+    // Where every fruit to collect is, the level's own fruit first, then the
+    // door's keys and the door itself
+    fn goal_positions(&self) -> impl Iterator<Item = (f32, f32)> + '_ {
+        let door = self.exit.iter().flat_map(|exit| exit.keys.iter().copied().chain([exit.fruit_spot()]));
+        self.fruit.map(|(_, position)| position).into_iter().chain(self.goal_fruit.iter().copied()).chain(door)
+    }
+
+    // Whether the player can get to every fruit to collect, or to the door
+    // and its keys, jumping about the plain layout
+    pub fn finishable(&self, profile: &JumpProfile) -> bool {
+        (self.fruit.is_some() || self.exit.is_some()) && self.goal_positions().all(|position| fruit_reachable(&self.platforms, position, profile))
+    }
+
+    // Takes out the enemies, crumbling platforms, traps, conveyors, wind and
+    // portals that reach into a safe zone. Checkpoints are placed after the hazards,
+    // so this is where their zones are enforced.
+    fn clear_safe_zones(&mut self) {
+        let safe = &self.safe_zones;
+        let platforms = &self.platforms;
+        let clear = |index: usize| platforms.get(index).is_none_or(|platform| clear_of(platform, safe));
+        self.enemies.retain(|enemy| enemy.clear_of(safe));
+        self.crumbling.retain(|crumbling| clear(crumbling.platform));
+        self.traps.retain(|trap| clear(trap.platform));
+        self.conveyors.retain(|conveyor| clear(conveyor.platform));
+        self.winds.retain(|wind| !safe.iter().any(|zone| wind.reaches(zone)));
+        self.portals.retain(|pair| !safe.iter().any(|zone| pair.reaches(zone)));
+        self.hazards.retain(|hazard| clear_of(&hazard.spec(), safe));
+    }
+
+    // Takes out spikes, the last placed first, until every fruit to collect
+    // can be reached without landing on any
+    fn clear_spikes_in_the_way(&mut self, profile: &JumpProfile) {
+        let goal: Vec<(f32, f32)> = self.goal_positions().collect();
+        while !goal.iter().all(|&fruit| fruit_reachable(&clear_footing(&self.platforms, &self.hazards), fruit, profile)) {
+            let Some(last) = self.hazards.iter().rposition(|hazard| hazard.kind == HazardKind::Spikes) else {
+                break;
+            };
+            self.hazards.remove(last);
+        }
+    }
+
+    // Level `level` of the run seeded with `run_seed`. When a fruit to collect
+    // can't be reached, the layout is regenerated from the next seed; the first
+    // reachable layout is normally the plain seeded one. `difficulty` shapes
+    // the layout; the level number decides the rest through `curve`. A door
+    // level is worked out as a fruit level first, its fruit and the fruit to
+    // collect with it becoming the door and its keys at the end.
+    pub fn generate(run_seed: u64, level: u32, difficulty: f32, profile: &JumpProfile, curve: &DifficultyCurve) -> Self {
+        let fruit_seed = fruit_seed(run_seed, level);
+        let layout = curve.layout(difficulty);
+        let enemy_zones = enemy_spawn_zones(WorldBounds::CLASSIC);
+        let spawn_zone = safe_zones(PLAYER_SPAWN, &[]);
+        let mut fallback = None;
+        for attempt in 0..MAX_LAYOUT_ATTEMPTS {
+            let mut platforms = generate_platform_layout(level_seed(run_seed, level).wrapping_add(attempt), &layout, WorldBounds::CLASSIC);
+            let door = curve.door(level);
+            // A door stands where a plain fruit would
+            let fruit = place_fruit(&platforms, fruit_seed, level).map(|(kind, position)| (if door { FruitKind::Normal } else { kind }, position));
+            let fruit_platform: Vec<usize> =
+                (0..platforms.len()).filter(|&index| fruit.is_some_and(|(_, position)| stands_on(&platforms[index], position, FRUIT_SIZE))).collect();
+            // The rest of the fruit to collect go on platforms the player can
+            // reach in the plain layout
+            let reached = reachable_from(&platforms, 0, profile);
+            let goal_taken: Vec<usize> = (0..platforms.len()).filter(|&index| fruit_platform.contains(&index) || !reached[index]).collect();
+            let goal_count = if door { curve.fruit_goal(level).max(2) } else { curve.fruit_goal(level) };
+            let goal_fruit = place_goal_fruit(&platforms, &goal_taken, fruit_seed, goal_count - 1, layout.min_gap);
+            let goal_platforms: Vec<usize> = (0..platforms.len())
+                .filter(|&index| fruit_platform.contains(&index) || goal_fruit.iter().any(|&position| holds_fruit(&platforms[index], position)))
+                .collect();
+            let bonus_count = curve.bonus_fruit(level);
+            let bonus_fruit = place_bonus_fruit(&platforms, &goal_platforms, fruit_seed, bonus_count, curve.fruit_bias(level), layout.min_gap);
+            let all_fruit: Vec<(f32, f32)> =
+                fruit.map(|(_, position)| position).into_iter().chain(goal_fruit.iter().copied()).chain(bonus_fruit.iter().copied()).collect();
+            let enemies = place_enemies(&platforms, enemy_seed(run_seed, level), curve.enemies(level), &enemy_zones, &spawn_zone);
+            // A fleeing fruit hops between platforms as they were placed, so
+            // its levels keep every platform still
+            let moving_count = if fruit.is_some_and(|(kind, _)| kind == FruitKind::Fleeing) { 0 } else { curve.moving(level, platforms.len()) };
+            let moving = moving_platforms(&platforms, &all_fruit, &enemies, moving_seed(run_seed, level), moving_count);
+            let moving_indices: Vec<usize> = moving.iter().map(|motion| motion.platform).collect();
+            mark_one_way_platforms(&mut platforms, &moving_indices, one_way_seed(run_seed, level), curve.one_way_odds(level));
+            let crumbling_count = curve.crumbling(level, platforms.len());
+            let crumbling = crumbling_platforms(&platforms, &all_fruit, &enemies, &moving, crumbling_seed(run_seed, level), crumbling_count);
+            let trap_count = curve.traps(level, platforms.len());
+            let traps = trap_platforms(&platforms, &all_fruit, &enemies, &moving, &crumbling, trap_seed(run_seed, level), trap_count);
+            let conveyor_count = curve.conveyors(level, platforms.len());
+            let conveyors = conveyor_platforms(&platforms, &all_fruit, &enemies, &moving, &crumbling, &traps, conveyor_seed(run_seed, level), conveyor_count);
+            let surfaces = platform_surfaces(surface_seed(run_seed, level), platforms.len());
+            let power_ups = power_up_platforms(&platforms, &all_fruit, &moving, &crumbling, &traps, power_up_seed(run_seed, level), level);
+            let checkpoints = checkpoint_platforms(&platforms, &all_fruit, &enemies, &moving, &crumbling, &traps, &conveyors);
+            let ladders = ladders_between(&platforms, &moving, &crumbling, &traps, ladder_seed(run_seed, level));
+            let pickups: Vec<(f32, f32)> = all_fruit.iter().copied().chain(power_ups.iter().map(|&(_, position)| position)).collect();
+            let hazard_counts = (curve.spikes(level, platforms.len()), curve.lava(level));
+            let hazards =
+                hazards_for(&platforms, &pickups, &checkpoints, &enemies, &moving, &crumbling, &traps, &conveyors, &ladders, hazard_seed(run_seed, level), hazard_counts);
+            let winds = place_wind(wind_seed(run_seed, level), curve.wind(level), WorldBounds::CLASSIC);
+            let mut keep_flat = flat_platforms(&platforms, &pickups, &checkpoints, &enemies, &moving, &crumbling, &traps, &conveyors, &ladders, &hazards);
+            // Portals stand where nothing else does, and their platforms stay flat too
+            let portals = place_portals(&platforms, portal_seed(run_seed, level), curve.portals(level), &keep_flat);
+            keep_flat.extend((0..platforms.len()).filter(|&index| {
+                portals.iter().any(|pair| [pair.a, pair.b].into_iter().any(|end| stands_on(&platforms[index], end, PORTAL_HEIGHT)))
+            }));
+            // Ramps go in last, where nothing placed on the level stands
+            tilt_platforms(&mut platforms, &keep_flat, slope_seed(run_seed, level), curve.slope_odds(level));
+            // Bonus fruit out of reach is left out rather than rerolling the level
+            let bonus_fruit = bonus_fruit.into_iter().filter(|&position| fruit_reachable(&platforms, position, profile)).collect();
+            let safe_zones = safe_zones(PLAYER_SPAWN, &checkpoints);
+            let (fruit, goal_fruit, exit) = match fruit {
+                Some((_, position)) if door => (None, Vec::new(), Some(ExitDoor::at_fruit_spot(position, goal_fruit))),
+                _ => (fruit, goal_fruit, None),
+            };
+            let mut candidate = Self {
+                platforms,
+                surfaces,
+                fruit,
+                goal_fruit,
+                exit,
+                bonus_fruit,
+                power_ups,
+                enemies,
+                moving,
+                crumbling,
+                traps,
+                conveyors,
+                ladders,
+                hazards,
+                winds,
+                portals,
+                checkpoints,
+                enemy_zones: enemy_zones.clone(),
+                safe_zones,
+                theme: curve.theme(level),
+            };
+            candidate.clear_safe_zones();
+            if candidate.finishable(profile) {
+                candidate.clear_spikes_in_the_way(profile);
+                return candidate;
+            }
+            fallback.get_or_insert(candidate);
+        }
+        fallback.expect("at least one layout attempt")
+    }
+
+    // A hand-made level with a plain fruit at `fruit`, or the door `exit` in its place (and no power-ups), an enemy on each of
+    // the platforms listed in `enemy_platforms`, a checkpoint on each of the
+    // ones in `checkpoint_platforms` and the platforms in `moving` following
+    // their paths (all indices into `platforms`), plus `ladders`, `hazards` and `portals`. `surfaces` goes with
+    // `platforms`; missing ones are stone. Enemies outside `enemy_zones` (when
+    // there are any), and enemies and portals too close to `spawn` or a
+    // checkpoint, are left out. The theme is left for whoever plays the level
+    // to pick.
+    #[allow(clippy::too_many_arguments)]
+    pub fn handmade(
+        platforms: Vec<PlatformSpec>,
+        mut surfaces: Vec<Surface>,
+        fruit: Option<(f32, f32)>,
+        exit: Option<ExitDoor>,
+        spawn: (f32, f32),
+        enemy_platforms: &[usize],
+        enemy_zones: Vec<Zone>,
+        checkpoint_platforms: &[usize],
+        moving: Vec<(usize, PlatformPath)>,
+        ladders: Vec<Ladder>,
+        hazards: Vec<HazardTile>,
+        portals: Vec<PortalPair>,
+    ) -> Self {
+        surfaces.resize(platforms.len(), Surface::Stone);
+        let enemies = enemy_platforms
+            .iter()
+            .filter_map(|&index| platforms.get(index))
+            .filter(|platform| enemy_zones.is_empty() || enemy_zones.iter().any(|zone| zone.contains((platform.x, platform.y))))
+            .map(EnemyAi::on_platform)
+            .collect();
+        let checkpoints: Vec<(f32, f32)> = checkpoint_platforms.iter().filter_map(|&index| platforms.get(index)).map(checkpoint_on).collect();
+        let safe_zones = safe_zones(spawn, &checkpoints);
+        let moving = moving
+            .into_iter()
+            .filter_map(|(index, path)| platforms.get(index).map(|platform| PlatformMotion::new(index, platform, path)))
+            .collect();
+        let mut level = Self {
+            // A door takes the fruit's place
+            fruit: fruit.filter(|_| exit.is_none()).map(|position| (FruitKind::Normal, position)),
+            goal_fruit: Vec::new(),
+            exit,
+            bonus_fruit: Vec::new(),
+            power_ups: Vec::new(),
+            platforms,
+            surfaces,
+            enemies,
+            moving,
+            crumbling: Vec::new(),
+            traps: Vec::new(),
+            conveyors: Vec::new(),
+            ladders,
+            hazards,
+            winds: Vec::new(),
+            portals,
+            checkpoints,
+            enemy_zones,
+            safe_zones,
+            theme: LevelTheme::default(),
+        };
+        level.clear_safe_zones();
+        level
+    }
+}
@@ -0,0 +1,61 @@
+// Rendering-free game logic shared by the game and the headless tools.
+//
+// Everything here is plain Rust on plain numbers (plus a seeded ChaCha RNG): level generation from a seed,
+// fruit placement, the player physics step and the classic-mode rules. This
+// crate deliberately does not depend on Bevy; the game's systems are thin
+// wrappers around these functions, so a run can be re-simulated exactly
+// without a window, GPU or audio device (see `src/bin/validate_run.rs`).
+
+pub mod assists;
+pub mod autorun;
+pub mod bot;
+pub mod budget;
+pub mod category;
+pub mod checkpoint;
+pub mod collection;
+pub mod collectible;
+pub mod config;
+pub mod crumbling;
+pub mod custom;
+pub mod daily;
+pub mod difficulty;
+pub mod enemy;
+pub mod exit;
+pub mod flee;
+pub mod fruit;
+pub mod generation;
+pub mod hazard;
+pub mod helper;
+pub mod ladder;
+pub mod level;
+pub mod look;
+pub mod mixer;
+pub mod modifiers;
+pub mod moving;
+pub mod naming;
+pub mod mutators;
+pub mod physics;
+pub mod portal;
+pub mod projectile;
+pub mod reachability;
+pub mod rescue;
+pub mod rng;
+pub mod replay;
+pub mod rewind;
+pub mod rules;
+pub mod score;
+pub mod snapshot;
+pub mod spatial;
+pub mod splits;
+pub mod status;
+pub mod stress;
+pub mod summary;
+pub mod surface;
+pub mod theme;
+pub mod tower;
+pub mod trap;
+pub mod validation;
+pub mod visual;
+pub mod wind;
+pub mod world;
+pub mod zones;
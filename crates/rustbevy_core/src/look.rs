@@ -0,0 +1,61 @@
+// How a platform is dressed up. Each platform's color is nudged a little off
+// its surface's, it may get a trim along its edges, and a wide one may be
+// split into tiles, so a level reads as less of a grid of identical bars.
+// A look comes from the level's `look_seed` and the platform's index, so a
+// level looks the same every time it's played, and nothing in the rules ever
+// reads it.
+
+use crate::rng::SeededRng;
+
+// The width of one tile of a tiled platform
+pub const TILE_WIDTH: f32 = 40.0;
+// Platforms with fewer tiles than this stay untiled; a pattern on a short one reads as noise
+const MIN_PATTERN_TILES: u32 = 3;
+// Largest turn of a platform's hue, in degrees, and largest change in its lightness
+const MAX_HUE_SHIFT: f32 = 8.0;
+const MAX_LIGHTNESS_SHIFT: f32 = 0.04;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EdgeTrim {
+    Plain,
+    // A lighter strip along the top
+    Lip,
+    // Darker blocks at both ends
+    Caps,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TilePattern {
+    Plain,
+    // Thin lines between the tiles
+    Seams,
+    // Every other tile darker
+    Checker,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PlatformLook {
+    pub hue_shift: f32,
+    pub lightness_shift: f32,
+    pub edge: EdgeTrim,
+    pub pattern: TilePattern,
+    // How many tiles the platform is split into, for the pattern
+    pub tiles: u32,
+}
+
+impl PlatformLook {
+    // The look of the platform at `index` in a level, `width` pixels wide
+    pub fn new(seed: u64, index: usize, width: f32) -> Self {
+        let mut rng = SeededRng::new(seed.wrapping_mul(131).wrapping_add(index as u64));
+        let hue_shift = rng.range(-MAX_HUE_SHIFT, MAX_HUE_SHIFT);
+        let lightness_shift = rng.range(-MAX_LIGHTNESS_SHIFT, MAX_LIGHTNESS_SHIFT);
+        let edge = [EdgeTrim::Plain, EdgeTrim::Lip, EdgeTrim::Caps][rng.below(3)];
+        let tiles = ((width / TILE_WIDTH).round() as u32).max(1);
+        let pattern = if tiles < MIN_PATTERN_TILES {
+            TilePattern::Plain
+        } else {
+            [TilePattern::Plain, TilePattern::Seams, TilePattern::Checker][rng.below(3)]
+        };
+        Self { hue_shift, lightness_shift, edge, pattern, tiles }
+    }
+}
@@ -0,0 +1,77 @@
+// Volume envelopes for the game's audio buses. Important sounds (a hit, a
+// 1-up, a level jingle) and dialogue duck a bus: its gain falls to the duck's
+// level over the attack time, stays there for the hold time and comes back up
+// over the release time. Overlapping ducks keep the bus at the lowest level
+// any of them asked for until the last one lets go.
+
+// How far and how fast a bus ducks for one sound
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Duck {
+    // Gain while ducked, 0.0-1.0
+    pub gain: f32,
+    // Seconds to fall to `gain`
+    pub attack: f32,
+    // Seconds to stay there
+    pub hold: f32,
+    // Seconds to come back up to full
+    pub release: f32,
+}
+
+impl Duck {
+    // Losing a life or taking a hit
+    pub const HIT: Duck = Duck { gain: 0.35, attack: 0.05, hold: 0.5, release: 0.6 };
+    // A 1-up or a level jingle, which is music itself
+    pub const JINGLE: Duck = Duck { gain: 0.25, attack: 0.08, hold: 0.9, release: 0.8 };
+    // Dialogue, held for as long as it's on screen
+    pub const DIALOGUE: Duck = Duck { gain: 0.5, attack: 0.25, hold: 0.1, release: 0.7 };
+}
+
+// The gain of one bus, 1.0 when nothing ducks it
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BusEnvelope {
+    gain: f32,
+    // Where the held ducks want the gain
+    target: f32,
+    hold: f32,
+    attack: f32,
+    release: f32,
+}
+
+impl Default for BusEnvelope {
+    fn default() -> Self {
+        Self { gain: 1.0, target: 1.0, hold: 0.0, attack: 0.0, release: 0.0 }
+    }
+}
+
+impl BusEnvelope {
+    pub fn gain(&self) -> f32 {
+        self.gain
+    }
+
+    pub fn ducked(&self) -> bool {
+        self.gain < 1.0
+    }
+
+    pub fn duck(&mut self, duck: Duck) {
+        self.target = if self.hold > 0.0 { self.target.min(duck.gain) } else { duck.gain };
+        self.hold = self.hold.max(duck.hold);
+        self.attack = duck.attack;
+        self.release = self.release.max(duck.release);
+    }
+
+    // Moves the gain along the envelope by `dt` seconds
+    pub fn update(&mut self, dt: f32) {
+        if self.hold > 0.0 {
+            self.hold = (self.hold - dt).max(0.0);
+            // Falling at a rate that gets from full to the target in `attack`
+            let rate = (1.0 - self.target) / self.attack.max(f32::EPSILON);
+            self.gain = (self.gain - rate * dt).max(self.target);
+            return;
+        }
+        let rate = (1.0 - self.target) / self.release.max(f32::EPSILON);
+        self.gain = (self.gain + rate * dt).min(1.0);
+        if self.gain >= 1.0 {
+            *self = Self::default();
+        }
+    }
+}
@@ -0,0 +1,122 @@
+// Roguelike run modifiers. Every few levels the player drafts one of three
+// random modifiers; picked modifiers stack for the rest of the run.
+
+use crate::physics::Tuning;
+use crate::rng::SeededRng;
+
+pub const DRAFT_EVERY_LEVELS: u32 = 3;
+pub const DRAFT_CHOICES: usize = 3;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Modifier {
+    Springy,
+    Sturdy,
+    Greedy,
+    Swift,
+    Floaty,
+    Cloud,
+}
+
+impl Modifier {
+    pub const ALL: [Modifier; 6] = [Modifier::Springy, Modifier::Sturdy, Modifier::Greedy, Modifier::Swift, Modifier::Floaty, Modifier::Cloud];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Springy => "Springy Boots",
+            Self::Sturdy => "Sturdy Heart",
+            Self::Greedy => "Greedy Hands",
+            Self::Swift => "Swift Feet",
+            Self::Floaty => "Feather Fall",
+            Self::Cloud => "Cloud Step",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|modifier| modifier.name() == name)
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Self::Springy => "Jump 15% faster, but gravity is 15% stronger",
+            Self::Sturdy => "One extra life, but you run 10% slower",
+            Self::Greedy => "Grab fruit from 50% further away, but levels get harder",
+            Self::Swift => "Run 15% faster, but steer 20% less in the air",
+            Self::Floaty => "Gravity is 15% weaker, but you jump 8% slower",
+            Self::Cloud => "Jump once more in mid-air, but you run 10% slower",
+        }
+    }
+
+    // Name used in replay files
+    pub fn key(self) -> &'static str {
+        match self {
+            Self::Springy => "springy",
+            Self::Sturdy => "sturdy",
+            Self::Greedy => "greedy",
+            Self::Swift => "swift",
+            Self::Floaty => "floaty",
+            Self::Cloud => "cloud",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|modifier| modifier.key() == key)
+    }
+
+    pub fn extra_lives(self) -> u32 {
+        u32::from(self == Self::Sturdy)
+    }
+}
+
+// Whether reaching `level` offers a draft (levels 4, 7, 10, ...)
+pub fn draft_offered(level: u32) -> bool {
+    level > 1 && (level - 1).is_multiple_of(DRAFT_EVERY_LEVELS)
+}
+
+// The three different modifiers offered by a draft
+pub fn draft_choices(seed: u64) -> [Modifier; DRAFT_CHOICES] {
+    let mut pool = Modifier::ALL.to_vec();
+    let mut rng = SeededRng::new(seed.wrapping_mul(31).wrapping_add(7));
+    let mut choices = [Modifier::Springy; DRAFT_CHOICES];
+    for choice in choices.iter_mut() {
+        let index = rng.below(pool.len());
+        *choice = pool.remove(index);
+    }
+    choices
+}
+
+// Modifiers picked so far this run
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ModifierStack {
+    pub picked: Vec<Modifier>,
+}
+
+impl ModifierStack {
+    fn count(&self, modifier: Modifier) -> i32 {
+        self.picked.iter().filter(|picked| **picked == modifier).count() as i32
+    }
+
+    // Movement tuning with every picked modifier applied on top of `base`
+    pub fn tuning(&self, base: Tuning) -> Tuning {
+        let springy = self.count(Modifier::Springy);
+        let sturdy = self.count(Modifier::Sturdy);
+        let greedy = self.count(Modifier::Greedy);
+        let swift = self.count(Modifier::Swift);
+        let floaty = self.count(Modifier::Floaty);
+        let cloud = self.count(Modifier::Cloud);
+        Tuning {
+            player_speed: base.player_speed * 0.9f32.powi(sturdy + cloud) * 1.15f32.powi(swift),
+            air_control: base.air_control * 0.8f32.powi(swift),
+            jump_speed: base.jump_speed * 1.15f32.powi(springy) * 0.92f32.powi(floaty),
+            gravity: base.gravity * 1.15f32.powi(springy) * 0.85f32.powi(floaty),
+            gravity_scales: base.gravity_scales,
+            pickup_distance: base.pickup_distance * 1.5f32.powi(greedy),
+            air_jumps: base.air_jumps + cloud as u32,
+            traction: base.traction,
+        }
+    }
+
+    // Added on top of the level's generation difficulty
+    pub fn extra_difficulty(&self) -> f32 {
+        self.count(Modifier::Greedy) as f32 * 0.15
+    }
+}
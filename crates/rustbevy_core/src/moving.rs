@@ -0,0 +1,121 @@
+// Moving platforms. From the difficulty curve's `moving_from_level` on a few
+// platforms sway back and forth (or up and down) around where they were generated, and hand-made
+// levels can send a platform around a loop of waypoints. A player standing on
+// a moving platform is carried along with it: whoever the last collision check
+// left standing on it (see `RunSimulation::standing_on`) moves with it.
+//
+// Reachability is checked with every platform where it was placed, so the
+// swing is kept small enough that a jump made near that spot still lands.
+
+use std::f32::consts::TAU;
+
+use crate::generation::PlatformSpec;
+use crate::physics::Body;
+use crate::rng::SeededRng;
+
+const MIN_SWING: f32 = 40.0;
+const MAX_SWING: f32 = 90.0;
+const MIN_PERIOD: f32 = 2.5;
+const MAX_PERIOD: f32 = 4.5;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum PlatformPath {
+    // Swings `amplitude` either side of where the platform was placed, once
+    // every `period` seconds
+    Sine { horizontal: bool, amplitude: f32, period: f32 },
+    // Travels through these offsets from where the platform was placed at
+    // `speed`, then back to the start
+    Waypoints { points: Vec<(f32, f32)>, speed: f32 },
+}
+
+// One moving platform of a level and how far along its path it is
+#[derive(Clone, Debug, PartialEq)]
+pub struct PlatformMotion {
+    // Index of the platform in its level
+    pub platform: usize,
+    origin: (f32, f32),
+    path: PlatformPath,
+    elapsed: f32,
+}
+
+impl PlatformMotion {
+    pub fn new(platform: usize, spec: &PlatformSpec, path: PlatformPath) -> Self {
+        Self {
+            platform,
+            origin: (spec.x, spec.y),
+            path,
+            elapsed: 0.0,
+        }
+    }
+
+    // Where the platform's centre is now
+    pub fn position(&self) -> (f32, f32) {
+        let offset = match &self.path {
+            PlatformPath::Sine { horizontal, amplitude, period } => {
+                let swing = amplitude * (TAU * self.elapsed / period).sin();
+                if *horizontal {
+                    (swing, 0.0)
+                } else {
+                    (0.0, swing)
+                }
+            }
+            PlatformPath::Waypoints { points, speed } => waypoint_offset(points, *speed, self.elapsed),
+        };
+        (self.origin.0 + offset.0, self.origin.1 + offset.1)
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        self.elapsed += dt;
+    }
+}
+
+// Offset `elapsed` seconds along the loop from the start through `points` and back
+fn waypoint_offset(points: &[(f32, f32)], speed: f32, elapsed: f32) -> (f32, f32) {
+    let mut stops = vec![(0.0, 0.0)];
+    stops.extend_from_slice(points);
+    let leg = |index: usize| (stops[index], stops[(index + 1) % stops.len()]);
+    let length = |(from, to): ((f32, f32), (f32, f32))| (to.0 - from.0).hypot(to.1 - from.1);
+    let total: f32 = (0..stops.len()).map(|index| length(leg(index))).sum();
+    if total <= 0.0 || speed <= 0.0 {
+        return (0.0, 0.0);
+    }
+
+    let mut distance = (elapsed * speed) % total;
+    for index in 0..stops.len() {
+        let (from, to) = leg(index);
+        let leg_length = length((from, to));
+        if distance <= leg_length && leg_length > 0.0 {
+            let t = distance / leg_length;
+            return (from.0 + (to.0 - from.0) * t, from.1 + (to.1 - from.1) * t);
+        }
+        distance -= leg_length;
+    }
+    (0.0, 0.0)
+}
+
+// Moves `body`, standing on a platform that just went from `before` to
+// `after`, along with it
+pub fn carry(body: &mut Body, before: &PlatformSpec, after: &PlatformSpec) {
+    body.x += after.x - before.x;
+    body.y += after.y - before.y;
+}
+
+// Up to `count` swinging platforms: never the starting platform (the first
+// one) or any platform listed in `keep_still`
+pub fn place_moving_platforms(platforms: &[PlatformSpec], seed: u64, count: usize, keep_still: &[usize]) -> Vec<PlatformMotion> {
+    let mut candidates: Vec<usize> = (1..platforms.len()).filter(|index| !keep_still.contains(index)).collect();
+    let mut rng = SeededRng::new(seed.wrapping_mul(157));
+    let mut motions = Vec::new();
+    while motions.len() < count && !candidates.is_empty() {
+        let index = candidates.swap_remove(rng.below(candidates.len()));
+        let horizontal = rng.below(2) == 0;
+        let mut amplitude = rng.range(MIN_SWING, MAX_SWING);
+        if !horizontal {
+            // Platforms stack closer vertically than they sit side by side
+            amplitude /= 2.0;
+        }
+        let period = rng.range(MIN_PERIOD, MAX_PERIOD);
+        motions.push(PlatformMotion::new(index, &platforms[index], PlatformPath::Sine { horizontal, amplitude, period }));
+    }
+    motions
+}
@@ -0,0 +1,156 @@
+// Challenge mutators for Classic mode. Unlike assists they don't make a run
+// easier, just different; they are picked on the main menu. The game speed
+// goes both ways: slower for players who need the time, faster for a
+// challenge.
+
+use crate::config::PLAYER_SIZE;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MirrorMode {
+    #[default]
+    Off,
+    Mirrored,
+    // Mirrored, with left and right swapped so the keys match the screen
+    MirroredSwapped,
+}
+
+impl MirrorMode {
+    pub fn next(self) -> Self {
+        match self {
+            Self::Off => Self::Mirrored,
+            Self::Mirrored => Self::MirroredSwapped,
+            Self::MirroredSwapped => Self::Off,
+        }
+    }
+
+    pub fn mirrored(self) -> bool {
+        self != Self::Off
+    }
+
+    pub fn swaps_inputs(self) -> bool {
+        self == Self::MirroredSwapped
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Off => "OFF",
+            Self::Mirrored => "ON",
+            Self::MirroredSwapped => "ON (inputs swapped)",
+        }
+    }
+}
+
+// Size of the player's sprite and collider
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PlayerScale {
+    #[default]
+    Normal,
+    Tiny,
+    Big,
+}
+
+impl PlayerScale {
+    pub fn next(self) -> Self {
+        match self {
+            Self::Normal => Self::Tiny,
+            Self::Tiny => Self::Big,
+            Self::Big => Self::Normal,
+        }
+    }
+
+    pub fn factor(self) -> f32 {
+        match self {
+            Self::Normal => 1.0,
+            Self::Tiny => 0.5,
+            Self::Big => 1.5,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Normal => "normal",
+            Self::Tiny => "tiny (0.5x)",
+            Self::Big => "big (1.5x)",
+        }
+    }
+}
+
+// How fast the whole game runs, in percent of normal speed. Every physics
+// step is the same length at any speed; there are just more or fewer of them
+// a second. Like any other mutator it keeps a run from being recorded: sped
+// up, a frame can take more than one physics step, which a replay can't be
+// sure to play back the same.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GameSpeed(u32);
+
+impl GameSpeed {
+    pub const NORMAL: Self = Self(100);
+    pub const MIN_PERCENT: u32 = 50;
+    pub const MAX_PERCENT: u32 = 150;
+    pub const STEP_PERCENT: u32 = 10;
+
+    // A step faster (`steps` > 0) or slower, within the limits
+    pub fn step(self, steps: i32) -> Self {
+        let percent = (self.0 as i32 + steps * Self::STEP_PERCENT as i32).clamp(Self::MIN_PERCENT as i32, Self::MAX_PERCENT as i32);
+        Self(percent as u32)
+    }
+
+    pub fn percent(self) -> u32 {
+        self.0
+    }
+
+    // Multiplier for how fast game time passes
+    pub fn factor(self) -> f32 {
+        self.0 as f32 / 100.0
+    }
+}
+
+impl Default for GameSpeed {
+    fn default() -> Self {
+        Self::NORMAL
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Mutators {
+    pub mirror: MirrorMode,
+    pub scale: PlayerScale,
+    pub speed: GameSpeed,
+}
+
+impl Mutators {
+    pub fn any(self) -> bool {
+        self.mirror.mirrored() || self.scale != PlayerScale::Normal || self.speed != GameSpeed::NORMAL
+    }
+
+    // Side of the player's square collider
+    pub fn player_size(self) -> f32 {
+        PLAYER_SIZE * self.scale.factor()
+    }
+
+    // Horizontal input as the player means it on the (possibly mirrored) screen
+    pub fn horizontal_input(self, horizontal: f32) -> f32 {
+        if self.mirror.swaps_inputs() {
+            -horizontal
+        } else {
+            horizontal
+        }
+    }
+
+    // Names of the mutators that are on, for the HUD and the results screen
+    pub fn labels(self) -> Vec<String> {
+        let mut labels = Vec::new();
+        if self.mirror.mirrored() {
+            labels.push("Mirror mode".to_string());
+        }
+        match self.scale {
+            PlayerScale::Normal => {}
+            PlayerScale::Tiny => labels.push("Tiny player".to_string()),
+            PlayerScale::Big => labels.push("Big player".to_string()),
+        }
+        if self.speed != GameSpeed::NORMAL {
+            labels.push(format!("Game speed {}%", self.speed.percent()));
+        }
+        labels
+    }
+}
@@ -0,0 +1,52 @@
+// Level names and flavor text. Each level of a run gets a name like "Windy
+// Gray Steps #4821" and a line of flavor text, both worked out from the run
+// seed and level number alone, so the same seed always gives the same names
+// and a name is enough to tell two runs' levels apart when sharing them.
+
+use crate::generation::name_seed;
+use crate::rng::SeededRng;
+
+const MOODS: [&str; 16] = [
+    "Windy", "Quiet", "Crooked", "Sunny", "Misty", "Restless", "Hollow", "Drowsy",
+    "Brittle", "Humming", "Lonely", "Tangled", "Gentle", "Stormy", "Dusty", "Whispering",
+];
+const COLORS: [&str; 12] = ["Gray", "Amber", "Violet", "Green", "Crimson", "Silver", "Golden", "Blue", "Rust", "Ivory", "Teal", "Ash"];
+const PLACES: [&str; 14] = [
+    "Steps", "Ledges", "Heights", "Hollow", "Climb", "Terraces", "Cliffs", "Spires",
+    "Bluffs", "Perches", "Gardens", "Ridge", "Stairs", "Rafters",
+];
+const FLAVOR: [&str; 16] = [
+    "The wind here remembers every jump.",
+    "Somebody left the fruit up there on purpose.",
+    "The platforms hum when nobody is looking.",
+    "Old climbers say the third ledge is lucky.",
+    "Watch your step; the ground is further than it looks.",
+    "Even the enemies took the long way around.",
+    "A quiet place, until you start jumping.",
+    "The view from the top is said to be worth it.",
+    "Nobody remembers building these steps.",
+    "Every ledge here has a story. Most end in a fall.",
+    "The air smells faintly of ripe fruit.",
+    "Take it slow, or don't. The fruit will wait.",
+    "The stones are warm from a thousand landings.",
+    "Legend says the fruit was never meant to be reached.",
+    "Listen closely and you can hear the ledges creak.",
+    "Mind the gaps; they certainly mind you.",
+];
+
+// Level `level` of the run seeded with `run_seed`'s name, as
+// "<mood> <color> <place> #<number>"
+pub fn level_name(run_seed: u64, level: u32) -> String {
+    let mut rng = SeededRng::new(name_seed(run_seed, level));
+    let mood = MOODS[rng.below(MOODS.len())];
+    let color = COLORS[rng.below(COLORS.len())];
+    let place = PLACES[rng.below(PLACES.len())];
+    let number = 1000 + rng.below(9000);
+    format!("{} {} {} #{}", mood, color, place, number)
+}
+
+// A line of flavor text to go with the level's name
+pub fn flavor_text(run_seed: u64, level: u32) -> &'static str {
+    let mut rng = SeededRng::new(name_seed(run_seed, level).wrapping_mul(3));
+    FLAVOR[rng.below(FLAVOR.len())]
+}
@@ -0,0 +1,574 @@
+// Player physics step on plain numbers. The game's Bevy systems copy their
+// components into a `Body`, run these functions and copy the result back.
+// The edge sensor (`edge_ahead`, `over_edge`) is here too, for anything that
+// walks on platforms: enemies turn around with it and the player teeters.
+// So are the raycasts (`raycast`, `raycast_all`, `cast_ray`) the sensor and
+// enemy sight lines are built on. Sloped platforms (see `PlatformSpec::slope`)
+// are collided with where the body stands over them, and cast against as the
+// slanted slabs they are.
+
+use crate::config::{
+    AIR_CONTROL, APEX_GRAVITY_SCALE, APEX_SPEED, FALL_GRAVITY_SCALE, FAST_FALL_GRAVITY_SCALE, FRUIT_PICKUP_DISTANCE, GRAVITY, JUMP_SPEED,
+    MAX_FALL_SPEED, PLAYER_SIZE, PLAYER_SPAWN, PLAYER_SPEED, RISE_GRAVITY_SCALE,
+};
+use crate::generation::PlatformSpec;
+use crate::world::WorldBounds;
+
+const GROUNDED_TOLERANCE: f32 = 5.0;
+// A jump still works this long after walking off a ledge
+pub const COYOTE_SECONDS: f32 = 0.1;
+// A jump pressed this long before landing happens on landing
+pub const JUMP_BUFFER_SECONDS: f32 = 0.12;
+// Down + jump lets the body fall through one-way platforms for this long
+pub const DROP_THROUGH_SECONDS: f32 = 0.2;
+// How far below a one-way platform's top the feet may have been and still land on it
+const ONE_WAY_TOLERANCE: f32 = 2.0;
+// How close to a platform's side the body counts as touching it
+const WALL_TOLERANCE: f32 = 2.0;
+// Fastest fall while pressing into a wall
+pub const WALL_SLIDE_SPEED: f32 = 120.0;
+// A wall jump's lift, as a share of a normal jump, and its push away from the
+// wall, as a share of the run speed
+pub const WALL_JUMP_LIFT: f32 = 0.9;
+pub const WALL_JUMP_PUSH: f32 = 1.2;
+// How long the push away from the wall holds before the player steers again
+pub const WALL_JUMP_PUSH_SECONDS: f32 = 0.15;
+// How far past a body's side, and below its feet, the edge sensor probes
+pub const EDGE_PROBE_AHEAD: f32 = 2.0;
+pub const EDGE_PROBE_DEPTH: f32 = 4.0;
+// How much slower walking up a 45° slope is, and faster walking down one;
+// gentler slopes change the speed less
+pub const SLOPE_SPEED_CHANGE: f32 = 0.25;
+
+// How a player moves. `Tuning::default()` is the normal game feel; assists and
+// run modifiers adjust it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Tuning {
+    pub player_speed: f32,
+    pub air_control: f32,
+    pub jump_speed: f32,
+    pub gravity: f32,
+    pub gravity_scales: GravityScales,
+    pub pickup_distance: f32,
+    // Extra jumps in mid-air before landing again
+    pub air_jumps: u32,
+    // How fast the player's speed on the ground catches up with where they're
+    // steering, in run speeds a second; infinite is at once
+    pub traction: f32,
+}
+
+impl Default for Tuning {
+    fn default() -> Self {
+        Self {
+            player_speed: PLAYER_SPEED,
+            air_control: AIR_CONTROL,
+            jump_speed: JUMP_SPEED,
+            gravity: GRAVITY,
+            gravity_scales: GravityScales::default(),
+            pickup_distance: FRUIT_PICKUP_DISTANCE,
+            air_jumps: 0,
+            traction: f32::INFINITY,
+        }
+    }
+}
+
+// What `Tuning::gravity` is multiplied by in each part of a jump (see `gravity`)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GravityScales {
+    pub rise: f32,
+    pub apex: f32,
+    pub fall: f32,
+    pub fast_fall: f32,
+}
+
+impl Default for GravityScales {
+    fn default() -> Self {
+        Self { rise: RISE_GRAVITY_SCALE, apex: APEX_GRAVITY_SCALE, fall: FALL_GRAVITY_SCALE, fast_fall: FAST_FALL_GRAVITY_SCALE }
+    }
+}
+
+// Buttons held during one simulation tick
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SimInput {
+    pub left: bool,
+    pub right: bool,
+    pub jump: bool,
+    // Climbs up a ladder; down climbs down one
+    pub up: bool,
+    pub down: bool,
+    // Tosses a seed (see `projectile`)
+    pub throw: bool,
+}
+
+impl SimInput {
+    pub fn horizontal(self) -> f32 {
+        let mut horizontal = 0.0;
+        if self.left {
+            horizontal -= 1.0;
+        }
+        if self.right {
+            horizontal += 1.0;
+        }
+        horizontal
+    }
+}
+
+// Position/velocity/grounded state of one player body. `size` is the side of
+// its square collider. `wall` is the side (-1.0 left, 1.0 right) of a
+// platform the body touched in mid-air at the last collision check, and
+// `slope` the slope of the platform it stood on (0.0 in the air).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Body {
+    pub x: f32,
+    pub y: f32,
+    pub velocity_x: f32,
+    pub velocity_y: f32,
+    pub grounded: bool,
+    pub wall: Option<f32>,
+    pub slope: f32,
+    pub size: f32,
+}
+
+impl Body {
+    pub fn at_spawn() -> Self {
+        Self {
+            x: PLAYER_SPAWN.0,
+            y: PLAYER_SPAWN.1,
+            velocity_x: 0.0,
+            velocity_y: 0.0,
+            grounded: false,
+            wall: None,
+            slope: 0.0,
+            size: PLAYER_SIZE,
+        }
+    }
+
+    pub fn with_size(self, size: f32) -> Self {
+        Self { size, ..self }
+    }
+}
+
+// Jump timing carried from tick to tick
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct JumpTimers {
+    // Seconds since the body last stood on something
+    pub since_grounded: f32,
+    // Seconds a jump pressed in the air stays queued
+    pub buffered: f32,
+    // Left the ground by jumping, so there's no coyote time
+    pub jumped: bool,
+    pub air_jumps_left: u32,
+    // Seconds left falling through one-way platforms after a drop
+    pub dropping: f32,
+    // Seconds left of a wall jump's push, while the input doesn't steer
+    pub pushed_off: f32,
+    // Down held in mid-air, which pulls the body down harder once it stops rising
+    pub fast_fall: bool,
+}
+
+impl Default for JumpTimers {
+    fn default() -> Self {
+        // Spawning in the air doesn't count as walking off a ledge
+        Self { since_grounded: f32::INFINITY, buffered: 0.0, jumped: false, air_jumps_left: 0, dropping: 0.0, pushed_off: 0.0, fast_fall: false }
+    }
+}
+
+impl JumpTimers {
+    fn can_ground_jump(&self, body: &Body) -> bool {
+        body.grounded || (!self.jumped && self.since_grounded <= COYOTE_SECONDS)
+    }
+}
+
+// Horizontal input and jumping for one tick of `dt` seconds. `jump_pressed` is
+// the press edge, not the held state; with `down_held` on the ground it drops
+// through one-way platforms instead of jumping, and in mid-air it fast-falls.
+// Pressing into a wall in mid-air slides down it slowly, and jumping off it
+// pushes away diagonally. Returns true when a jump started.
+pub fn apply_input(
+    body: &mut Body,
+    jumps: &mut JumpTimers,
+    horizontal_input: f32,
+    jump_pressed: bool,
+    down_held: bool,
+    dt: f32,
+    tuning: &Tuning,
+) -> bool {
+    // Apply horizontal movement with air control, unless a wall jump is still
+    // carrying the body away from the wall
+    jumps.pushed_off = (jumps.pushed_off - dt).max(0.0);
+    if jumps.pushed_off <= 0.0 {
+        let movement_multiplier = if body.grounded { slope_speed(body.slope, horizontal_input) } else { tuning.air_control };
+        let target = horizontal_input * tuning.player_speed * movement_multiplier;
+        body.velocity_x = if body.grounded && tuning.traction.is_finite() {
+            let most = tuning.traction * tuning.player_speed * dt;
+            body.velocity_x + (target - body.velocity_x).clamp(-most, most)
+        } else {
+            target
+        };
+    }
+    let wall = body.wall.filter(|_| !body.grounded);
+    if wall.is_some_and(|side| horizontal_input * side > 0.0) {
+        body.velocity_y = body.velocity_y.max(-WALL_SLIDE_SPEED);
+    }
+
+    jumps.fast_fall = down_held && !body.grounded;
+    if body.grounded {
+        jumps.since_grounded = 0.0;
+        jumps.jumped = false;
+        jumps.air_jumps_left = tuning.air_jumps;
+    } else {
+        jumps.since_grounded += dt;
+    }
+    jumps.dropping = (jumps.dropping - dt).max(0.0);
+    if jump_pressed && down_held && body.grounded {
+        jumps.dropping = DROP_THROUGH_SECONDS;
+        jumps.buffered = 0.0;
+        return false;
+    }
+    jumps.buffered = if jump_pressed { JUMP_BUFFER_SECONDS } else { (jumps.buffered - dt).max(0.0) };
+    if jumps.buffered <= 0.0 {
+        return false;
+    }
+
+    // From the ground (or just off it), or a fresh press spending an air jump;
+    // a queued press waits for the landing instead of using one up
+    if jumps.can_ground_jump(body) {
+        jumps.jumped = true;
+    } else if let Some(side) = wall {
+        body.velocity_x = -side * tuning.player_speed * WALL_JUMP_PUSH;
+        body.velocity_y = tuning.jump_speed * WALL_JUMP_LIFT;
+        jumps.jumped = true;
+        jumps.pushed_off = WALL_JUMP_PUSH_SECONDS;
+        jumps.buffered = 0.0;
+        return true;
+    } else if jump_pressed && jumps.air_jumps_left > 0 {
+        jumps.air_jumps_left -= 1;
+    } else {
+        return false;
+    }
+    body.velocity_y = tuning.jump_speed;
+    jumps.buffered = 0.0;
+    true
+}
+
+// What the run speed is multiplied by walking `direction` on a `slope`: less
+// than 1.0 uphill, more downhill
+pub fn slope_speed(slope: f32, direction: f32) -> f32 {
+    1.0 - SLOPE_SPEED_CHANGE * (slope * direction).clamp(-1.0, 1.0)
+}
+
+// Gravity's pull on the body right now: lighter around the top of a jump for
+// a moment of hang time, heavier on the way down, and heaviest fast-falling
+pub fn gravity(body: &Body, jumps: &JumpTimers, tuning: &Tuning) -> f32 {
+    let scales = tuning.gravity_scales;
+    let scale = if jumps.fast_fall && body.velocity_y < APEX_SPEED {
+        scales.fast_fall
+    } else if !body.grounded && body.velocity_y.abs() < APEX_SPEED {
+        scales.apex
+    } else if body.velocity_y > 0.0 {
+        scales.rise
+    } else {
+        scales.fall
+    };
+    tuning.gravity * scale
+}
+
+pub fn apply_gravity(body: &mut Body, jumps: &JumpTimers, dt: f32, tuning: &Tuning) {
+    body.velocity_y = (body.velocity_y - gravity(body, jumps, tuning) * dt).max(-MAX_FALL_SPEED);
+}
+
+pub fn integrate(body: &mut Body, dt: f32) {
+    body.x += body.velocity_x * dt;
+    body.y += body.velocity_y * dt;
+}
+
+// Pushes the body out of any platform it overlaps and updates grounded and
+// the wall it touches. `dt` is the tick the body just moved for; while
+// `jumps.dropping`, one-way platforms are passed through. A sloped platform
+// is treated as the part of it under the body's middle, as thick as ever, and
+// a body walking down one is kept on it rather than stepping off into the air.
+pub fn resolve_platform_collisions(body: &mut Body, platforms: &[PlatformSpec], jumps: &JumpTimers, dt: f32) {
+    let half_size = body.size / 2.0;
+    body.grounded = false;
+    body.wall = None;
+    body.slope = 0.0;
+    // Where the body was before this tick's move
+    let previous_x = body.x - body.velocity_x * dt;
+    let previous_y = body.y - body.velocity_y * dt;
+
+    for platform in platforms {
+        // Player bounds
+        let player_left = body.x - half_size;
+        let player_right = body.x + half_size;
+        let player_bottom = body.y - half_size;
+        let player_top = body.y + half_size;
+
+        // Platform bounds - use the actual platform size, and on a slope the
+        // height of the part under the body
+        let platform_left = platform.x - platform.width / 2.0;
+        let platform_right = platform.x + platform.width / 2.0;
+        let platform_top = platform.top_at(body.x);
+        let platform_bottom = platform_top - platform.height;
+
+        // A one-way platform only holds a body coming down onto it: falling,
+        // with its feet above the top before this tick's move
+        if platform.one_way {
+            let previous_bottom = player_bottom - body.velocity_y * dt;
+            if jumps.dropping > 0.0 || body.velocity_y > 0.0 || previous_bottom < platform.top_at(previous_x) - ONE_WAY_TOLERANCE {
+                continue;
+            }
+        }
+
+        // Check for collision
+        if player_right > platform_left
+            && player_left < platform_right
+            && player_top > platform_bottom
+            && player_bottom < platform_top
+        {
+            // Determine collision direction and resolve
+            let overlap_x = f32::min(player_right - platform_left, platform_right - player_left);
+            let overlap_y = f32::min(player_top - platform_bottom, platform_top - player_bottom);
+            // Walked into its side while level with it: a wall, however little
+            // of it is in the way (a gap lower than the body isn't a ceiling
+            // to squeeze under)
+            let entered_from_side = (previous_x + half_size <= platform_left || previous_x - half_size >= platform_right)
+                && previous_y + half_size > platform_bottom
+                && previous_y - half_size < platform_top;
+
+            if (overlap_x < overlap_y || entered_from_side) && !platform.one_way {
+                // Horizontal collision
+                if body.x < platform.x {
+                    // Player is on the left
+                    body.x = platform_left - half_size;
+                } else {
+                    // Player is on the right
+                    body.x = platform_right + half_size;
+                }
+                body.velocity_x = 0.0;
+            } else if previous_y < platform_top - platform.height / 2.0 && !platform.one_way {
+                // Player came up from below (hitting from below). Judged
+                // from before the move, since a fast fall can carry the body
+                // past the platform's middle in one tick.
+                body.y = platform_bottom - half_size;
+                body.velocity_y = 0.0;
+            } else {
+                // Player is above platform (landing on top)
+                body.y = platform_top + half_size;
+                if body.velocity_y <= 0.0 {
+                    // Only stop downward velocity
+                    body.velocity_y = 0.0;
+                }
+                body.grounded = true;
+                body.slope = platform.slope;
+            }
+        }
+
+        // Walking down a slope leaves the feet above it by up to this much
+        // each tick
+        let slope_drop = (platform.slope * body.velocity_x * dt).abs();
+        let feet = body.y - half_size;
+        if platform.slope != 0.0
+            && player_right > platform_left
+            && player_left < platform_right
+            && feet > platform_top
+            && feet <= platform_top + GROUNDED_TOLERANCE + slope_drop
+            && body.velocity_y <= 0.0
+        {
+            body.y = platform_top + half_size;
+            body.velocity_y = 0.0;
+            body.grounded = true;
+            body.slope = platform.slope;
+        }
+
+        // Additional grounded check - more lenient for jumping
+        if player_right > platform_left
+            && player_left < platform_right
+            && player_bottom <= platform_top + GROUNDED_TOLERANCE
+            && player_bottom >= platform_top - GROUNDED_TOLERANCE
+            && body.velocity_y <= 0.0
+        {
+            body.grounded = true;
+            body.slope = platform.slope;
+        }
+    }
+
+    if !body.grounded {
+        body.wall = platforms.iter().filter(|platform| !platform.one_way).find_map(|platform| wall_side(body, platform));
+    }
+}
+
+// Which side of `body` the side of `platform` is on, if they touch. Only
+// touching corners doesn't count.
+fn wall_side(body: &Body, platform: &PlatformSpec) -> Option<f32> {
+    let half_size = body.size / 2.0;
+    let middle = platform.top_at(body.x) - platform.height / 2.0;
+    if (body.y - middle).abs() >= half_size + platform.height / 2.0 - WALL_TOLERANCE {
+        return None;
+    }
+    let gap_left = (body.x - half_size) - (platform.x + platform.width / 2.0);
+    let gap_right = (platform.x - platform.width / 2.0) - (body.x + half_size);
+    if gap_left.abs() <= WALL_TOLERANCE {
+        Some(-1.0)
+    } else if gap_right.abs() <= WALL_TOLERANCE {
+        Some(1.0)
+    } else {
+        None
+    }
+}
+
+// Keeps the player between the level's left and right edges
+pub fn keep_in_world(body: &mut Body, bounds: WorldBounds) {
+    body.x = bounds.clamp_x(body.x, body.size);
+}
+
+// Whether `body` is standing on `platform`
+pub fn stood_on(body: &Body, platform: &PlatformSpec) -> bool {
+    let feet = body.y - body.size / 2.0;
+    let top = platform.top_at(body.x);
+    body.grounded && (body.x - platform.x).abs() < (platform.width + body.size) / 2.0 && (feet - top).abs() <= GROUNDED_TOLERANCE
+}
+
+// Whether `point` is inside one of `platforms`
+pub fn ground_at(point: (f32, f32), platforms: &[PlatformSpec]) -> bool {
+    platforms.iter().any(|platform| {
+        (point.0 - platform.x).abs() <= platform.width / 2.0 && (point.1 - (platform.top_at(point.0) - platform.height / 2.0)).abs() <= platform.height / 2.0
+    })
+}
+
+// Which platforms stop a ray
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RayMask {
+    pub solid: bool,
+    pub one_way: bool,
+}
+
+impl RayMask {
+    pub const ALL: RayMask = RayMask { solid: true, one_way: true };
+    // One-way platforms are see-through, so sight lines pass them
+    pub const SOLID: RayMask = RayMask { solid: true, one_way: false };
+
+    fn stops_at(self, platform: &PlatformSpec) -> bool {
+        if platform.one_way {
+            self.one_way
+        } else {
+            self.solid
+        }
+    }
+}
+
+// Where a ray went into a platform: which one (its index in the platforms
+// cast against), how far along the segment (0.0 at the start, 1.0 at the
+// end), the point and the normal of the side it went in through. A segment
+// that starts inside a platform hits it at 0.0, with no normal.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RayHit {
+    pub platform: usize,
+    pub fraction: f32,
+    pub point: (f32, f32),
+    pub normal: (f32, f32),
+}
+
+// Where the segment from `from` to `to` goes into `platform`, if it does. A
+// sloped platform is straightened out first, shifting every point down by how
+// much the slope raises it; the segment stays a segment, hit at the same
+// fraction, and only the normals of the top and bottom need tilting back.
+fn segment_into(from: (f32, f32), to: (f32, f32), platform: &PlatformSpec) -> Option<(f32, (f32, f32))> {
+    let straighten = |point: (f32, f32)| (point.0, point.1 - platform.slope * (point.0 - platform.x));
+    let (from, to) = (straighten(from), straighten(to));
+    let axes = [
+        (from.0, to.0 - from.0, platform.x, platform.width / 2.0),
+        (from.1, to.1 - from.1, platform.y, platform.height / 2.0),
+    ];
+    let (mut enter, mut exit) = (0.0_f32, 1.0_f32);
+    let mut normal = (0.0, 0.0);
+    for (axis, (start, delta, centre, half)) in axes.into_iter().enumerate() {
+        if delta.abs() < f32::EPSILON {
+            // Parallel to this pair of sides: in between them the whole way, or never
+            if (start - centre).abs() > half {
+                return None;
+            }
+            continue;
+        }
+        let (a, b) = ((centre - half - start) / delta, (centre + half - start) / delta);
+        let (near, far) = (a.min(b), a.max(b));
+        if near > enter {
+            enter = near;
+            normal = if axis == 0 { (-delta.signum(), 0.0) } else { (0.0, -delta.signum()) };
+        }
+        exit = exit.min(far);
+        if enter > exit {
+            return None;
+        }
+    }
+    if normal.1 != 0.0 {
+        let length = platform.slope.hypot(1.0);
+        normal = (-platform.slope * normal.1 / length, normal.1 / length);
+    }
+    Some((enter, normal))
+}
+
+// Every platform in `mask` the segment from `from` to `to` goes into, nearest first
+pub fn raycast_all(from: (f32, f32), to: (f32, f32), platforms: &[PlatformSpec], mask: RayMask) -> Vec<RayHit> {
+    let mut hits: Vec<RayHit> = platforms
+        .iter()
+        .enumerate()
+        .filter(|(_, platform)| mask.stops_at(platform))
+        .filter_map(|(index, platform)| {
+            segment_into(from, to, platform).map(|(fraction, normal)| RayHit {
+                platform: index,
+                fraction,
+                point: (from.0 + (to.0 - from.0) * fraction, from.1 + (to.1 - from.1) * fraction),
+                normal,
+            })
+        })
+        .collect();
+    hits.sort_by(|a, b| a.fraction.total_cmp(&b.fraction));
+    hits
+}
+
+// The first platform in `mask` the segment from `from` to `to` goes into
+pub fn raycast(from: (f32, f32), to: (f32, f32), platforms: &[PlatformSpec], mask: RayMask) -> Option<RayHit> {
+    raycast_all(from, to, platforms, mask).into_iter().next()
+}
+
+// A ray from `origin` along `direction` (any length but zero), `distance` long
+pub fn cast_ray(origin: (f32, f32), direction: (f32, f32), distance: f32, platforms: &[PlatformSpec], mask: RayMask) -> Option<RayHit> {
+    let length = direction.0.hypot(direction.1);
+    let to = (origin.0 + direction.0 / length * distance, origin.1 + direction.1 / length * distance);
+    raycast(origin, to, platforms, mask)
+}
+
+// Whether nothing solid stands between `from` and `to`
+pub fn line_of_sight(from: (f32, f32), to: (f32, f32), platforms: &[PlatformSpec]) -> bool {
+    raycast(from, to, platforms, RayMask::SOLID).is_none()
+}
+
+// Edge sensor: probes down from a little past the side of `body` facing
+// `direction` (-1.0 left, 1.0 right), from its feet to a little below them.
+// True when there's nothing to stand on there, so walking on steps off an
+// edge.
+pub fn edge_ahead(body: &Body, direction: f32, platforms: &[PlatformSpec]) -> bool {
+    let probe = (body.x + direction * (body.size / 2.0 + EDGE_PROBE_AHEAD), body.y - body.size / 2.0);
+    cast_ray(probe, (0.0, -1.0), EDGE_PROBE_DEPTH, platforms, RayMask::ALL).is_none()
+}
+
+// Whether `body` is standing with its middle out over an edge, held up by
+// the corner of its collider
+pub fn over_edge(body: &Body, platforms: &[PlatformSpec]) -> bool {
+    body.grounded && !ground_at((body.x, body.y - body.size / 2.0 - EDGE_PROBE_DEPTH), platforms)
+}
+
+pub fn fell_out_of_world(body: &Body, bounds: WorldBounds) -> bool {
+    bounds.below(body.y)
+}
+
+// Pickup reach grows and shrinks with the player's collider
+pub fn pickup_reach(body: &Body, tuning: &Tuning) -> f32 {
+    tuning.pickup_distance + (body.size - PLAYER_SIZE) / 2.0
+}
+
+pub fn touches_fruit(body: &Body, fruit: (f32, f32), tuning: &Tuning) -> bool {
+    let dx = body.x - fruit.0;
+    let dy = body.y - fruit.1;
+    (dx * dx + dy * dy).sqrt() < pickup_reach(body, tuning)
+}
@@ -0,0 +1,117 @@
+// Teleporter portals. From the difficulty curve's `portals_from_level` on, a
+// level gets a pair or two of linked portals standing on platforms far apart;
+// a level file can put its own anywhere. Walking or falling into one end puts
+// the player at the other straight away, moving just as fast, or turned by a
+// quarter turn or more when the pair is set up that way. Coming out of a
+// portal doesn't send the player straight back: the way out only re-arms
+// once they've been clear of every portal for `PORTAL_COOLDOWN`.
+
+use crate::generation::PlatformSpec;
+use crate::physics::Body;
+use crate::rng::SeededRng;
+use crate::zones::SafeZone;
+
+pub const PORTAL_WIDTH: f32 = 40.0;
+pub const PORTAL_HEIGHT: f32 = 80.0;
+// Seconds clear of every portal before one works again
+pub const PORTAL_COOLDOWN: f32 = 0.3;
+// How far apart the two platforms of a generated pair are at least
+const MIN_PORTAL_DISTANCE: f32 = 600.0;
+// Tries at finding a far enough platform for the second end
+const PORTAL_ATTEMPTS: usize = 10;
+
+// Two linked portals, by their centres. Going in at `a` turns the player's
+// velocity `quarter_turns` quarter turns anticlockwise on the way out of `b`;
+// going in at `b` turns it back.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PortalPair {
+    pub a: (f32, f32),
+    pub b: (f32, f32),
+    pub quarter_turns: u8,
+}
+
+// One end of a pair, and where it leads
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Portal {
+    pub x: f32,
+    pub y: f32,
+    pub exit: (f32, f32),
+    pub quarter_turns: u8,
+}
+
+impl PortalPair {
+    pub fn ends(&self) -> [Portal; 2] {
+        let turns = self.quarter_turns % 4;
+        [
+            Portal { x: self.a.0, y: self.a.1, exit: self.b, quarter_turns: turns },
+            Portal { x: self.b.0, y: self.b.1, exit: self.a, quarter_turns: (4 - turns) % 4 },
+        ]
+    }
+
+    // Whether either end is inside `zone`
+    pub fn reaches(&self, zone: &SafeZone) -> bool {
+        [self.a, self.b].iter().any(|&(x, y)| (x - zone.x).hypot(y - zone.y) < zone.radius)
+    }
+}
+
+impl Portal {
+    // Whether the middle of `body` is inside the portal
+    pub fn touches(&self, body: &Body) -> bool {
+        (body.x - self.x).abs() < PORTAL_WIDTH / 2.0 && (body.y - self.y).abs() < PORTAL_HEIGHT / 2.0
+    }
+
+    // Puts `body` at the other end, turning its velocity as the pair says
+    pub fn send(&self, body: &mut Body) {
+        (body.x, body.y) = self.exit;
+        for _ in 0..self.quarter_turns {
+            (body.velocity_x, body.velocity_y) = (-body.velocity_y, body.velocity_x);
+        }
+        body.grounded = false;
+    }
+}
+
+// Sends `body` through the portal end it has walked into, if they've re-armed.
+// `cooldown` counts down only while the body is clear of every portal, so
+// standing in the one it came out of keeps it from going back. Returns
+// whether it went through.
+pub fn teleport(body: &mut Body, portals: &[Portal], cooldown: &mut f32, dt: f32) -> bool {
+    let entered = portals.iter().find(|portal| portal.touches(body));
+    match entered {
+        None => {
+            *cooldown = (*cooldown - dt).max(0.0);
+            false
+        }
+        Some(portal) if *cooldown <= 0.0 => {
+            portal.send(body);
+            *cooldown = PORTAL_COOLDOWN;
+            true
+        }
+        Some(_) => false,
+    }
+}
+
+// Where a portal standing on the middle of `platform` is
+fn portal_on(platform: &PlatformSpec) -> (f32, f32) {
+    (platform.x, platform.y + platform.height / 2.0 + PORTAL_HEIGHT / 2.0)
+}
+
+// Up to `count` pairs, each end on its own platform and the two ends of a
+// pair far apart: never on the starting platform (the first one) or any
+// platform listed in `keep_clear`. Generated pairs don't turn the player.
+pub fn place_portals(platforms: &[PlatformSpec], seed: u64, count: usize, keep_clear: &[usize]) -> Vec<PortalPair> {
+    let mut candidates: Vec<usize> = (1..platforms.len()).filter(|index| !keep_clear.contains(index)).collect();
+    let mut rng = SeededRng::new(seed.wrapping_mul(17));
+    let mut portals = Vec::new();
+    while portals.len() < count && candidates.len() >= 2 {
+        let first = candidates.swap_remove(rng.below(candidates.len()));
+        let far = (0..PORTAL_ATTEMPTS)
+            .map(|_| rng.below(candidates.len()))
+            .find(|&index| (platforms[candidates[index]].x - platforms[first].x).abs() >= MIN_PORTAL_DISTANCE);
+        let Some(index) = far else {
+            continue;
+        };
+        let second = candidates.swap_remove(index);
+        portals.push(PortalPair { a: portal_on(&platforms[first]), b: portal_on(&platforms[second]), quarter_turns: 0 });
+    }
+    portals
+}
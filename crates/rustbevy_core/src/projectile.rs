@@ -0,0 +1,122 @@
+// Thrown seeds. The Classic player can toss a seed the way they face: it
+// flies forward and up, falls under its own gravity, and is gone once it
+// hits a solid platform (one-way ones let it through) or leaves the world.
+// A seed that hits an enemy defeats it. The pouch holds a few seeds and grows
+// one back every couple of seconds.
+
+use crate::enemy::{EnemyAi, ENEMY_SIZE};
+use crate::generation::PlatformSpec;
+use crate::physics::Body;
+use crate::world::WorldBounds;
+
+pub const SEED_SIZE: f32 = 10.0;
+pub const MAX_SEEDS: u32 = 3;
+pub const SEED_REGROW_SECONDS: f32 = 2.0;
+const THROW_SPEED_X: f32 = 600.0;
+const THROW_SPEED_Y: f32 = 150.0;
+const SEED_GRAVITY: f32 = 900.0;
+// Slower than this doesn't turn the player around, so standing still keeps
+// the last facing (as the sprite does)
+const MIN_FACING_SPEED: f32 = 10.0;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Projectile {
+    pub x: f32,
+    pub y: f32,
+    pub velocity_x: f32,
+    pub velocity_y: f32,
+}
+
+impl Projectile {
+    // A seed leaving the front of `body`, `facing` -1.0 (left) or 1.0 (right)
+    pub fn thrown(body: &Body, facing: f32) -> Self {
+        Self {
+            x: body.x + facing * (body.size + SEED_SIZE) / 2.0,
+            y: body.y,
+            velocity_x: facing * THROW_SPEED_X,
+            velocity_y: THROW_SPEED_Y,
+        }
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        self.velocity_y -= SEED_GRAVITY * dt;
+        self.x += self.velocity_x * dt;
+        self.y += self.velocity_y * dt;
+    }
+
+    // Whether the seed has hit one of the solid `platforms`
+    pub fn hits_platform(&self, platforms: &[PlatformSpec]) -> bool {
+        platforms.iter().filter(|platform| !platform.one_way).any(|platform| {
+            (self.x - platform.x).abs() < (platform.width + SEED_SIZE) / 2.0 && (self.y - platform.y).abs() < (platform.height + SEED_SIZE) / 2.0
+        })
+    }
+
+    pub fn hits(&self, enemy: &EnemyAi) -> bool {
+        let reach = (SEED_SIZE + ENEMY_SIZE) / 2.0;
+        (self.x - enemy.x).abs() < reach && (self.y - enemy.y).abs() < reach
+    }
+
+    pub fn out_of(&self, bounds: WorldBounds) -> bool {
+        self.x.abs() > bounds.half_width() + SEED_SIZE || self.y < -bounds.half_height() - SEED_SIZE
+    }
+}
+
+// The player's seeds and which way they face
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SeedPouch {
+    pub seeds: u32,
+    // Seconds until the next seed grows back
+    regrow: f32,
+    pub facing: f32,
+}
+
+impl Default for SeedPouch {
+    fn default() -> Self {
+        Self { seeds: MAX_SEEDS, regrow: SEED_REGROW_SECONDS, facing: 1.0 }
+    }
+}
+
+impl SeedPouch {
+    // Grows seeds back and turns to where `body` is running
+    pub fn update(&mut self, body: &Body, dt: f32) {
+        if body.velocity_x.abs() >= MIN_FACING_SPEED {
+            self.facing = body.velocity_x.signum();
+        }
+        if self.seeds >= MAX_SEEDS {
+            self.regrow = SEED_REGROW_SECONDS;
+            return;
+        }
+        self.regrow -= dt;
+        if self.regrow <= 0.0 {
+            self.seeds += 1;
+            self.regrow += SEED_REGROW_SECONDS;
+        }
+    }
+
+    // A seed thrown from `body`, if there's one left
+    pub fn throw(&mut self, body: &Body) -> Option<Projectile> {
+        if self.seeds == 0 {
+            return None;
+        }
+        self.seeds -= 1;
+        Some(Projectile::thrown(body, self.facing))
+    }
+
+    // How far the next seed is from growing back, 0 to 1
+    pub fn regrow_progress(&self) -> f32 {
+        1.0 - self.regrow / SEED_REGROW_SECONDS
+    }
+}
+
+// Moves `projectiles` for `dt` seconds, dropping the ones that hit a solid
+// platform or leave `bounds` and, with the enemy, the ones that hit an enemy
+pub fn update_projectiles(projectiles: &mut Vec<Projectile>, enemies: &mut Vec<EnemyAi>, platforms: &[PlatformSpec], bounds: WorldBounds, dt: f32) {
+    projectiles.retain_mut(|projectile| {
+        projectile.update(dt);
+        if let Some(index) = enemies.iter().position(|enemy| projectile.hits(enemy)) {
+            enemies.remove(index);
+            return false;
+        }
+        !projectile.hits_platform(platforms) && !projectile.out_of(bounds)
+    });
+}
@@ -0,0 +1,241 @@
+// Checks that a generated level can actually be finished: that the fruit's
+// platform can be reached from the starting platform by jumping and falling
+// with a given jump profile. Used to reject layouts that a changed player
+// (bigger, smaller, different tuning) couldn't clear.
+
+use crate::autorun::AUTO_RUN_REACTION_SECONDS;
+use crate::config::{APEX_SPEED, FRUIT_SIZE};
+use crate::difficulty::LayoutParams;
+use crate::generation::{PlatformSpec, MIN_VERTICAL_GAP};
+use crate::physics::{raycast_all, GravityScales, RayMask, Tuning};
+
+// Only count jumps that make it with some room to spare
+const SAFETY_MARGIN: f32 = 0.9;
+// Seconds between the points of a jump arc
+const ARC_STEP: f32 = 1.0 / 30.0;
+// Longest a jump arc is followed for
+const ARC_MAX_SECONDS: f32 = 4.0;
+
+// How far and high a player can get in one jump
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct JumpProfile {
+    pub jump_speed: f32,
+    pub gravity: f32,
+    pub gravity_scales: GravityScales,
+    pub air_speed: f32,
+    pub player_size: f32,
+    // Platforms narrower than this can't be landed on; only set for players
+    // who can't stop running
+    pub min_landing_width: f32,
+}
+
+impl JumpProfile {
+    pub fn new(tuning: &Tuning, player_size: f32) -> Self {
+        Self {
+            jump_speed: tuning.jump_speed,
+            gravity: tuning.gravity,
+            gravity_scales: tuning.gravity_scales,
+            air_speed: tuning.player_speed * tuning.air_control,
+            player_size,
+            min_landing_width: 0.0,
+        }
+    }
+
+    // For an auto-running player (running at `tuning`'s speed): every
+    // platform on the way has to leave time to jump or turn before running off
+    pub fn with_auto_run(self, tuning: &Tuning) -> Self {
+        Self {
+            min_landing_width: tuning.player_speed * AUTO_RUN_REACTION_SECONDS - self.player_size,
+            ..self
+        }
+    }
+
+    // A jump rises under the rising gravity until it slows to `APEX_SPEED`
+    // (`top`, when it starts out slower than that), hangs under the apex
+    // gravity until it falls that fast, then falls under the falling gravity.
+    // Fast-falling only ever shortens a jump, so it's left out.
+    fn phases(&self) -> JumpPhases {
+        let (rise, apex, fall) = (
+            self.gravity * self.gravity_scales.rise,
+            self.gravity * self.gravity_scales.apex,
+            self.gravity * self.gravity_scales.fall,
+        );
+        let top = self.jump_speed.min(APEX_SPEED);
+        JumpPhases {
+            rise,
+            apex,
+            fall,
+            top,
+            rising: (self.jump_speed - top) / rise,
+            rise_height: (self.jump_speed * self.jump_speed - top * top) / (2.0 * rise),
+        }
+    }
+
+    // How high the player's feet rise above the platform they jumped from
+    pub fn jump_height(&self) -> f32 {
+        let phases = self.phases();
+        phases.rise_height + phases.top * phases.top / (2.0 * phases.apex)
+    }
+
+    // How high above the takeoff the feet are `t` seconds into a jump
+    fn height_at(&self, t: f32) -> f32 {
+        let phases = self.phases();
+        if t <= phases.rising {
+            return self.jump_speed * t - phases.rise * t * t / 2.0;
+        }
+        let hang = t - phases.rising;
+        let hang_end = (phases.top + APEX_SPEED) / phases.apex;
+        if hang <= hang_end {
+            return phases.rise_height + phases.top * hang - phases.apex * hang * hang / 2.0;
+        }
+        let falling = hang - hang_end;
+        let hang_height = phases.rise_height + (phases.top * phases.top - APEX_SPEED * APEX_SPEED) / (2.0 * phases.apex);
+        hang_height - APEX_SPEED * falling - phases.fall * falling * falling / 2.0
+    }
+
+    // Seconds in the air until the feet come back down to `rise` above the
+    // takeoff, which has to be within the jump's height
+    fn airtime(&self, rise: f32) -> f32 {
+        let phases = self.phases();
+        let peak = self.jump_height();
+        let to_peak = phases.rising + phases.top / phases.apex;
+        // Down from the peak while still hanging, then the rest of the way falling
+        let hang_drop = APEX_SPEED * APEX_SPEED / (2.0 * phases.apex);
+        let drop = peak - rise;
+        if drop <= hang_drop {
+            return to_peak + (2.0 * drop / phases.apex).sqrt();
+        }
+        let falling = drop - hang_drop;
+        let fall_time = ((APEX_SPEED * APEX_SPEED + 2.0 * phases.fall * falling).sqrt() - APEX_SPEED) / phases.fall;
+        to_peak + APEX_SPEED / phases.apex + fall_time
+    }
+
+    // How far the player gets sideways in a jump that lands as high as it took off
+    pub fn jump_distance(&self) -> f32 {
+        self.air_speed * self.airtime(0.0)
+    }
+
+    // Why levels laid out with `layout` would hardly ever be finishable with
+    // this jump, if they wouldn't: the jump doesn't leave the ground or never
+    // comes down, it can't clear the smallest gap the generator leaves
+    // between platforms side by side, or it can't reach a platform stacked
+    // the least the generator stacks them above another. Every layout is
+    // still checked as it's made; this is for telling the player why the
+    // generator keeps running out of layouts to try.
+    pub fn layout_problem(&self, layout: &LayoutParams) -> Option<String> {
+        if !(self.gravity > 0.0 && self.gravity.is_finite()) {
+            return Some(format!("gravity of {} never brings a jump back down", self.gravity));
+        }
+        if !(self.jump_speed > 0.0 && self.jump_speed.is_finite()) {
+            return Some(format!("a jump speed of {} doesn't leave the ground", self.jump_speed));
+        }
+        let height = self.jump_height() * SAFETY_MARGIN;
+        if height < MIN_VERTICAL_GAP {
+            return Some(format!("jumps reach {:.0} up, short of the {:.0} platforms are stacked apart", height, MIN_VERTICAL_GAP));
+        }
+        // As in `can_hop`, the player can stand half over either edge
+        let across = self.jump_distance() * SAFETY_MARGIN + self.player_size;
+        if across < layout.min_gap {
+            return Some(format!("jumps reach {:.0} across, short of the {:.0} gaps between platforms", across, layout.min_gap));
+        }
+        None
+    }
+
+    // Whether a jump (or a drop) gets from one platform onto another. Heights
+    // are measured where each platform comes closest to the other, which
+    // only matters for ramps.
+    pub fn can_hop(&self, from: &PlatformSpec, to: &PlatformSpec) -> bool {
+        let rise = to.top_at(from.x) - from.top_at(to.x);
+        if rise > self.jump_height() * SAFETY_MARGIN || to.width < self.min_landing_width {
+            return false;
+        }
+
+        // The player can stand with half its body over either edge
+        let gap = ((to.x - from.x).abs() - (to.width + from.width) / 2.0 - self.player_size).max(0.0);
+        gap <= self.air_speed * self.airtime(rise) * SAFETY_MARGIN
+    }
+
+    // The path of the centre of a player jumping from `from` (its centre)
+    // while holding `direction` (-1.0 left, 1.0 right, 0.0 straight up), as
+    // points a thirtieth of a second apart. It ends where the player would
+    // land on or bump into one of `platforms`, or once its feet have fallen
+    // `drop` below where they took off.
+    pub fn jump_arc(&self, from: (f32, f32), direction: f32, platforms: &[PlatformSpec], drop: f32) -> Vec<(f32, f32)> {
+        let half_size = self.player_size / 2.0;
+        let at = |t: f32| (from.0 + direction * self.air_speed * t, from.1 + self.height_at(t));
+        let mut points = vec![from];
+        let mut t = 0.0;
+        while t < ARC_MAX_SECONDS {
+            let (previous, next) = (at(t), at(t + ARC_STEP));
+            t += ARC_STEP;
+            // One-way platforms only catch the feet on the way down
+            let rising = next.1 > previous.1;
+            let mask = if rising { RayMask::SOLID } else { RayMask::ALL };
+            let (edge, offset) = if rising { (half_size, -half_size) } else { (-half_size, half_size) };
+            let hit = raycast_all((previous.0, previous.1 + edge), (next.0, next.1 + edge), platforms, mask)
+                .into_iter()
+                .find(|hit| hit.fraction > 0.0);
+            if let Some(hit) = hit {
+                points.push((hit.point.0, hit.point.1 + offset));
+                break;
+            }
+            points.push(next);
+            if next.1 - half_size < from.1 - half_size - drop {
+                break;
+            }
+        }
+        points
+    }
+}
+
+// The three parts of a jump (see `JumpProfile::phases`): their gravities,
+// the upward speed the hang starts at, and how long and high the rise goes
+struct JumpPhases {
+    rise: f32,
+    apex: f32,
+    fall: f32,
+    top: f32,
+    rising: f32,
+    rise_height: f32,
+}
+
+// Which platforms can be reached from the first (starting) platform
+pub fn reachable_platforms(platforms: &[PlatformSpec], profile: &JumpProfile) -> Vec<bool> {
+    reachable_from(platforms, 0, profile)
+}
+
+// Which platforms can be reached from platform `start`
+pub fn reachable_from(platforms: &[PlatformSpec], start: usize, profile: &JumpProfile) -> Vec<bool> {
+    let mut reached = vec![false; platforms.len()];
+    let mut frontier = Vec::new();
+    if start < platforms.len() {
+        reached[start] = true;
+        frontier.push(start);
+    }
+    while let Some(from) = frontier.pop() {
+        for to in 0..platforms.len() {
+            if !reached[to] && profile.can_hop(&platforms[from], &platforms[to]) {
+                reached[to] = true;
+                frontier.push(to);
+            }
+        }
+    }
+    reached
+}
+
+// Whether the fruit at `fruit` (sitting on one of the platforms) can be reached
+pub fn fruit_reachable(platforms: &[PlatformSpec], fruit: (f32, f32), profile: &JumpProfile) -> bool {
+    fruit_reachable_from(platforms, 0, fruit, profile)
+}
+
+// The same, for a player standing on platform `start`
+pub fn fruit_reachable_from(platforms: &[PlatformSpec], start: usize, fruit: (f32, f32), profile: &JumpProfile) -> bool {
+    let reached = reachable_from(platforms, start, profile);
+    platforms.iter().zip(reached).any(|(platform, reached)| reached && holds_fruit(platform, fruit))
+}
+
+// Whether the fruit at `fruit` is sitting on `platform`
+pub fn holds_fruit(platform: &PlatformSpec, fruit: (f32, f32)) -> bool {
+    let on_top = (platform.y + platform.height / 2.0 + FRUIT_SIZE / 2.0 - fruit.1).abs() < 1.0;
+    on_top && (platform.x - fruit.0).abs() <= platform.width / 2.0
+}
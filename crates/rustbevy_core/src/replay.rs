@@ -0,0 +1,156 @@
+// Recorded runs and their text format
+
+use crate::config::{PHYSICS_HZ, PLAYER_SIZE};
+use crate::difficulty::AdaptiveDifficulty;
+use crate::modifiers::Modifier;
+use crate::mutators::PlayerScale;
+use crate::physics::SimInput;
+use crate::rules::{RunOutcome, RunSimulation};
+
+// Longest run a replay can hold, four hours of ticks, so a file can't ask
+// for more input than fits in memory
+const MAX_REPLAY_TICKS: usize = (4.0 * 60.0 * 60.0 * PHYSICS_HZ) as usize;
+
+// A recorded run: the seed, the fixed tick length and the input held on every tick,
+// plus what the player claims the run achieved.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Replay {
+    pub seed: u64,
+    pub dt: f32,
+    pub claimed_level: u32,
+    pub claimed_ticks: u64,
+    pub adaptive_difficulty: bool,
+    pub picks: Vec<Modifier>,
+    pub player_size: f32,
+    pub inputs: Vec<SimInput>,
+}
+
+impl Replay {
+    // Text format, one directive per line:
+    //   seed <u64>
+    //   dt <seconds per tick>
+    //   claim <level reached> <ticks>
+    //   adaptive <0|1>                   (optional, adaptive difficulty was on)
+    //   pick <modifier>                  (one per modifier draft, in order)
+    //   size <pixels>                    (optional, player size from a size mutator)
+    //   input <repeat count> <buttons>   (buttons: any of L R J U D T, or - for none)
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut replay = Replay {
+            seed: 0,
+            dt: 1.0 / PHYSICS_HZ,
+            claimed_level: 1,
+            claimed_ticks: 0,
+            adaptive_difficulty: false,
+            picks: Vec::new(),
+            player_size: PLAYER_SIZE,
+            inputs: Vec::new(),
+        };
+
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let error = || format!("line {}: could not parse '{}'", number + 1, line);
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            match fields.as_slice() {
+                ["seed", seed] => replay.seed = seed.parse().map_err(|_| error())?,
+                ["dt", dt] => replay.dt = dt.parse().map_err(|_| error())?,
+                ["claim", level, ticks] => {
+                    replay.claimed_level = level.parse().map_err(|_| error())?;
+                    replay.claimed_ticks = ticks.parse().map_err(|_| error())?;
+                }
+                ["adaptive", enabled] => replay.adaptive_difficulty = *enabled == "1",
+                ["size", size] => {
+                    // Only the sizes the size mutator plays at
+                    let size: f32 = size.parse().map_err(|_| error())?;
+                    let scales = [PlayerScale::Normal, PlayerScale::Tiny, PlayerScale::Big];
+                    if !scales.iter().any(|scale| PLAYER_SIZE * scale.factor() == size) {
+                        return Err(format!("line {}: player size {} isn't one the game plays at", number + 1, size));
+                    }
+                    replay.player_size = size;
+                }
+                ["pick", modifier] => replay.picks.push(Modifier::from_key(modifier).ok_or_else(error)?),
+                ["input", count, buttons] => {
+                    let count: usize = count.parse().map_err(|_| error())?;
+                    let total = replay.inputs.len().checked_add(count).filter(|&total| total <= MAX_REPLAY_TICKS);
+                    if total.is_none() {
+                        return Err(format!("line {}: the run is longer than {} ticks", number + 1, MAX_REPLAY_TICKS));
+                    }
+                    let input = SimInput {
+                        left: buttons.contains('L'),
+                        right: buttons.contains('R'),
+                        jump: buttons.contains('J'),
+                        up: buttons.contains('U'),
+                        down: buttons.contains('D'),
+                        throw: buttons.contains('T'),
+                    };
+                    replay.inputs.extend(std::iter::repeat_n(input, count));
+                }
+                _ => return Err(error()),
+            }
+        }
+
+        if !(replay.dt > 0.0 && replay.dt <= 0.1) {
+            return Err(format!("tick length {} is out of range", replay.dt));
+        }
+        Ok(replay)
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut text = format!(
+            "# bevy_platformer replay\nseed {}\ndt {}\nclaim {} {}\nadaptive {}\n",
+            self.seed, self.dt, self.claimed_level, self.claimed_ticks, self.adaptive_difficulty as u8
+        );
+        if self.player_size != PLAYER_SIZE {
+            text.push_str(&format!("size {}\n", self.player_size));
+        }
+        for pick in &self.picks {
+            text.push_str(&format!("pick {}\n", pick.key()));
+        }
+        let mut index = 0;
+        while index < self.inputs.len() {
+            let input = self.inputs[index];
+            let count = self.inputs[index..].iter().take_while(|other| **other == input).count();
+            let mut buttons = String::new();
+            if input.left {
+                buttons.push('L');
+            }
+            if input.right {
+                buttons.push('R');
+            }
+            if input.jump {
+                buttons.push('J');
+            }
+            if input.up {
+                buttons.push('U');
+            }
+            if input.down {
+                buttons.push('D');
+            }
+            if input.throw {
+                buttons.push('T');
+            }
+            if buttons.is_empty() {
+                buttons.push('-');
+            }
+            text.push_str(&format!("input {} {}\n", count, buttons));
+            index += count;
+        }
+        text
+    }
+
+    // Re-simulates the recorded inputs from scratch
+    pub fn simulate(&self) -> RunOutcome {
+        let mut simulation = RunSimulation::with_difficulty(self.seed, AdaptiveDifficulty::new(self.adaptive_difficulty))
+            .with_picks(self.picks.clone())
+            .with_player_size(self.player_size);
+        for input in &self.inputs {
+            if simulation.is_over() {
+                break;
+            }
+            simulation.step(*input, self.dt);
+        }
+        simulation.outcome()
+    }
+}
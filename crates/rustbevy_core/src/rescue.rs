@@ -0,0 +1,109 @@
+// Anti-frustration rescue for a Classic level's fruit. A crumbled platform, a
+// drop the player can't climb back up from or a hand-made level's mistake can
+// leave a fruit out of reach of the platform the player stands on. Once one
+// has been out of reach for `RESCUE_SECONDS` of standing around, the one
+// nearest the player is brought back: a one-way
+// stepping platform goes in between a platform the player can reach and the
+// fruit's, or if no single step bridges the gap the fruit moves to the
+// reachable platform nearest to it.
+
+use crate::config::{FRUIT_SIZE, PLATFORM_HEIGHT};
+use crate::generation::PlatformSpec;
+use crate::reachability::{fruit_reachable_from, holds_fruit, reachable_from, JumpProfile};
+
+pub const RESCUE_SECONDS: f32 = 8.0;
+const STEP_WIDTH: f32 = 120.0;
+// Where a stepping platform is tried, as a share of the way from a reachable
+// platform to the fruit's
+const STEP_SHARES: [f32; 3] = [0.5, 0.35, 0.65];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Rescue {
+    // A one-way platform to put in
+    Platform(PlatformSpec),
+    // Where the fruit goes
+    MoveFruit((f32, f32)),
+}
+
+// Counts how long the fruit has been out of reach
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct FruitWatchdog {
+    stranded: f32,
+}
+
+impl FruitWatchdog {
+    // `stranded` is None while it can't be told (the player in the air or on
+    // a ladder), which neither counts nor starts over. True once the fruit has
+    // been out of reach long enough, and the count starts over.
+    pub fn update(&mut self, stranded: Option<bool>, dt: f32) -> bool {
+        match stranded {
+            Some(true) => self.stranded += dt,
+            Some(false) => self.stranded = 0.0,
+            None => {}
+        }
+        if self.stranded < RESCUE_SECONDS {
+            return false;
+        }
+        self.stranded = 0.0;
+        true
+    }
+}
+
+// Whether the fruit at `fruit` is out of reach of a player standing on
+// platform `start` of `platforms`
+pub fn fruit_stranded(platforms: &[PlatformSpec], start: usize, fruit: (f32, f32), profile: &JumpProfile) -> bool {
+    !fruit_reachable_from(platforms, start, fruit, profile)
+}
+
+// Which of the fruit at `fruit` is out of reach of a player standing on
+// platform `start` at `player`, the nearest to them if several are
+pub fn stranded_fruit(platforms: &[PlatformSpec], start: usize, fruit: &[(f32, f32)], player: (f32, f32), profile: &JumpProfile) -> Option<usize> {
+    let distance = |index: &usize| (fruit[*index].0 - player.0).hypot(fruit[*index].1 - player.1);
+    (0..fruit.len())
+        .filter(|&index| fruit_stranded(platforms, start, fruit[index], profile))
+        .min_by(|a, b| distance(a).total_cmp(&distance(b)))
+}
+
+// What brings the fruit back in reach of a player standing on platform `start`
+pub fn rescue(platforms: &[PlatformSpec], start: usize, fruit: (f32, f32), profile: &JumpProfile) -> Rescue {
+    let reached = reachable_from(platforms, start, profile);
+    let distance = |platform: &PlatformSpec| (platform.x - fruit.0).hypot(platform.y - fruit.1);
+    let mut reachable: Vec<&PlatformSpec> = platforms.iter().zip(&reached).filter(|(_, &reached)| reached).map(|(platform, _)| platform).collect();
+    reachable.sort_by(|a, b| distance(a).total_cmp(&distance(b)));
+
+    if let Some(target) = platforms.iter().find(|platform| holds_fruit(platform, fruit)) {
+        for from in &reachable {
+            for share in STEP_SHARES {
+                let step_top = top(from) + (top(target) - top(from)) * share;
+                let step = PlatformSpec {
+                    x: from.x + (target.x - from.x) * share,
+                    y: step_top - PLATFORM_HEIGHT / 2.0,
+                    width: STEP_WIDTH,
+                    height: PLATFORM_HEIGHT,
+                    one_way: true,
+                    slope: 0.0,
+                };
+                if profile.can_hop(from, &step) && profile.can_hop(&step, target) && room_for(&step, platforms, profile.player_size) {
+                    return Rescue::Platform(step);
+                }
+            }
+        }
+    }
+    // The platform the player stands on is always reachable
+    let nearest = reachable.first().copied().unwrap_or(&platforms[start]);
+    Rescue::MoveFruit((nearest.x, top(nearest) + FRUIT_SIZE / 2.0))
+}
+
+fn top(platform: &PlatformSpec) -> f32 {
+    platform.y + platform.height / 2.0
+}
+
+// Whether `step`, and a player standing on it, are clear of every platform
+fn room_for(step: &PlatformSpec, platforms: &[PlatformSpec], player_size: f32) -> bool {
+    let (step_bottom, headroom) = (step.y - step.height / 2.0, top(step) + player_size);
+    platforms.iter().all(|platform| {
+        let apart_x = (platform.x - step.x).abs() >= (platform.width + step.width) / 2.0;
+        let apart_y = platform.y - platform.height / 2.0 >= headroom || top(platform) <= step_bottom;
+        apart_x || apart_y
+    })
+}
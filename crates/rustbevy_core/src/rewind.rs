@@ -0,0 +1,62 @@
+// The Classic player's rewind. Every physics step the player's body goes
+// into a ring buffer holding the last `REWIND_SECONDS` of them; rewinding
+// takes them back out newest first, one a step, so the player runs backwards
+// through where they've been. Rewinding drains a meter, which a full meter
+// lasts the whole buffer of, and the meter fills back up over
+// `RECHARGE_SECONDS` while the player isn't rewinding.
+
+use std::collections::VecDeque;
+
+use crate::physics::Body;
+
+// How far back the buffer reaches, and how long a full meter rewinds for
+pub const REWIND_SECONDS: f32 = 3.0;
+// How long an empty meter takes to fill up again
+const RECHARGE_SECONDS: f32 = 10.0;
+
+#[derive(Clone, Debug)]
+pub struct Rewind {
+    // Oldest first
+    frames: VecDeque<Body>,
+    // How much rewinding is left, 0 to 1
+    meter: f32,
+}
+
+impl Default for Rewind {
+    fn default() -> Self {
+        Self { frames: VecDeque::new(), meter: 1.0 }
+    }
+}
+
+impl Rewind {
+    // Keeps `body` as it was after a step `dt` long, dropping whatever is now
+    // older than the buffer reaches, and recharges the meter
+    pub fn record(&mut self, body: Body, dt: f32) {
+        let capacity = (REWIND_SECONDS / dt).round().max(1.0) as usize;
+        while self.frames.len() >= capacity {
+            self.frames.pop_front();
+        }
+        self.frames.push_back(body);
+        self.meter = (self.meter + dt / RECHARGE_SECONDS).min(1.0);
+    }
+
+    // The body a step `dt` long further back, if there's any buffer and meter
+    // left
+    pub fn step_back(&mut self, dt: f32) -> Option<Body> {
+        if self.meter <= 0.0 {
+            return None;
+        }
+        let body = self.frames.pop_back()?;
+        self.meter = (self.meter - dt / REWIND_SECONDS).max(0.0);
+        Some(body)
+    }
+
+    // Forgets where the player has been, after they were moved somewhere new
+    pub fn clear(&mut self) {
+        self.frames.clear();
+    }
+
+    pub fn meter(&self) -> f32 {
+        self.meter
+    }
+}
@@ -0,0 +1,33 @@
+// Seeded random numbers for everything generated from a seed. ChaCha8 gives
+// the same sequence for a seed on every platform, which replays, favorites and
+// online runs (every peer generating the same levels) rely on.
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+pub struct SeededRng(ChaCha8Rng);
+
+impl SeededRng {
+    pub fn new(seed: u64) -> Self {
+        Self(ChaCha8Rng::seed_from_u64(seed))
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0.gen()
+    }
+
+    // A number in 0..count; `count` must not be zero
+    pub fn below(&mut self, count: usize) -> usize {
+        self.0.gen_range(0..count)
+    }
+
+    // A number in [0, 1)
+    pub fn fraction(&mut self) -> f32 {
+        self.0.gen()
+    }
+
+    // A number in [min, max)
+    pub fn range(&mut self, min: f32, max: f32) -> f32 {
+        min + (max - min) * self.fraction()
+    }
+}
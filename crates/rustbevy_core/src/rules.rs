@@ -0,0 +1,513 @@
+// Classic-mode rules: lives, level progression and the fixed-step run loop
+
+use crate::collectible::{Collectible, POWER_UP_SECONDS};
+use crate::checkpoint::{respawn_point, touches_checkpoint};
+use crate::config::PLAYER_SPAWN;
+use crate::crumbling::CrumblingPlatform;
+use crate::difficulty::{AdaptiveDifficulty, DifficultyCurve};
+use crate::enemy::{stomp_bounce, EnemyAi, ENEMY_STOMP_SCORE};
+use crate::exit::{enters_door, ExitDoor};
+use crate::flee::FleeingFruit;
+use crate::fruit::{curse_effect, FruitKind, CURSE_SECONDS, FRUIT_SCORE};
+use crate::generation::{draft_seed, fruit_seed, PlatformSpec};
+use crate::hazard::{clear_footing, HazardTile};
+use crate::helper::{summons_helper, HelperPlatform, HELPER_PLATFORM_USES};
+use crate::ladder::{climb, Ladder};
+use crate::level::Level;
+use crate::modifiers::{draft_choices, draft_offered, Modifier, ModifierStack};
+use crate::moving::{carry, PlatformMotion};
+use crate::physics::{apply_gravity, apply_input, fell_out_of_world, integrate, keep_in_world, resolve_platform_collisions, stood_on, touches_fruit, Body, JumpTimers, SimInput, Tuning};
+use crate::portal::{teleport, Portal, PortalPair};
+use crate::projectile::{update_projectiles, Projectile, SeedPouch};
+use crate::reachability::JumpProfile;
+use crate::rescue::{rescue, stranded_fruit, FruitWatchdog, Rescue};
+use crate::score::Combo;
+use crate::status::{StatusEffect, StatusEffects};
+use crate::theme::LevelTheme;
+use crate::trap::{fling, TrapPlatform};
+use crate::wind::{blow, convey, Conveyor, Wind};
+use crate::world::WorldBounds;
+
+const MAX_STARTING_LIVES: u32 = 9;
+const MAX_CONTINUES: u32 = 5;
+
+// How many lives a Classic run starts with, and how many continues it gets
+// after losing them all. A continue starts the level the run ended on again
+// with the starting lives back and the score at zero.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GameRules {
+    pub starting_lives: u32,
+    pub continues: u32,
+}
+
+impl Default for GameRules {
+    fn default() -> Self {
+        Self { starting_lives: 3, continues: 2 }
+    }
+}
+
+impl GameRules {
+    // Whether runs start with the usual lives, so their scores stand next to
+    // everyone else's. Continues don't matter: they take the score away.
+    pub fn standard_lives(&self) -> bool {
+        self.starting_lives == Self::default().starting_lives
+    }
+
+    // One more starting life, back to one after the most
+    pub fn more_lives(self) -> Self {
+        Self { starting_lives: self.starting_lives % MAX_STARTING_LIVES + 1, ..self }
+    }
+
+    // One more continue, back to none after the most
+    pub fn more_continues(self) -> Self {
+        Self { continues: (self.continues + 1) % (MAX_CONTINUES + 1), ..self }
+    }
+}
+
+// Outcome of a classic single-player run
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RunOutcome {
+    pub level: u32,
+    pub lives: u32,
+    pub score: u32,
+    pub ticks: u64,
+    pub game_over: bool,
+}
+
+// A classic run driven tick by tick with a fixed timestep
+pub struct RunSimulation {
+    run_seed: u64,
+    pub body: Body,
+    pub jumps: JumpTimers,
+    pub platforms: Vec<PlatformSpec>,
+    pub fruit: Option<(f32, f32)>,
+    pub fruit_kind: FruitKind,
+    // The plain fruit still to collect along with it
+    pub goal_fruit: Vec<(f32, f32)>,
+    // How many fruit finish the level, and how many have been collected
+    pub fruit_goal: u32,
+    pub fruit_collected: u32,
+    // The door that finishes a door level, the keys still lying about and how
+    // many have been picked up
+    pub exit: Option<ExitDoor>,
+    pub keys: Vec<(f32, f32)>,
+    pub keys_held: usize,
+    pub bonus_fruit: Vec<(f32, f32)>,
+    pub fleeing: Option<FleeingFruit>,
+    pub power_ups: Vec<(Collectible, (f32, f32))>,
+    pub enemies: Vec<EnemyAi>,
+    pub moving: Vec<PlatformMotion>,
+    pub crumbling: Vec<CrumblingPlatform>,
+    pub traps: Vec<TrapPlatform>,
+    pub conveyors: Vec<Conveyor>,
+    pub ladders: Vec<Ladder>,
+    // Index of the ladder the player is climbing
+    pub climbing: Option<usize>,
+    pub hazards: Vec<HazardTile>,
+    pub winds: Vec<Wind>,
+    // Ice makes the ground slippery
+    pub theme: LevelTheme,
+    // Both ends of every pair of portals
+    pub portals: Vec<Portal>,
+    // Seconds until portals work again (see `portal::teleport`)
+    pub portal_cooldown: f32,
+    // The player's seeds, and the ones in the air
+    pub seeds: SeedPouch,
+    pub projectiles: Vec<Projectile>,
+    // Index of the platform the last collision check left the player standing
+    // on; moving platforms carry, crumbling ones crumble and traps spring for it
+    pub standing_on: Option<usize>,
+    // Sideways push left over from a launching trap
+    pub fling: f32,
+    // Helper platforms the player has put down, and how many more they can
+    pub helpers: Vec<HelperPlatform>,
+    pub helper_uses: u32,
+    pub checkpoints: Vec<(f32, f32)>,
+    // How long the fruit has been out of the player's reach
+    pub watchdog: FruitWatchdog,
+    // Where the player comes back after losing a life: the level's spawn
+    // point, or the last checkpoint touched
+    pub respawn_point: (f32, f32),
+    pub level: u32,
+    pub lives: u32,
+    pub score: u32,
+    pub combo: Combo,
+    pub ticks: u64,
+    pub status: StatusEffects,
+    pub difficulty: AdaptiveDifficulty,
+    pub curve: DifficultyCurve,
+    pub modifiers: ModifierStack,
+    // Draft picks to make, in order, as draft levels are reached
+    picks: Vec<Modifier>,
+    jump_held: bool,
+    throw_held: bool,
+}
+
+impl RunSimulation {
+    pub fn new(run_seed: u64) -> Self {
+        Self::with_difficulty(run_seed, AdaptiveDifficulty::default())
+    }
+
+    pub fn with_difficulty(run_seed: u64, difficulty: AdaptiveDifficulty) -> Self {
+        let mut simulation = Self {
+            run_seed,
+            body: Body::at_spawn(),
+            jumps: JumpTimers::default(),
+            platforms: Vec::new(),
+            fruit: None,
+            fruit_kind: FruitKind::Normal,
+            goal_fruit: Vec::new(),
+            fruit_goal: 0,
+            fruit_collected: 0,
+            exit: None,
+            keys: Vec::new(),
+            keys_held: 0,
+            bonus_fruit: Vec::new(),
+            fleeing: None,
+            power_ups: Vec::new(),
+            enemies: Vec::new(),
+            moving: Vec::new(),
+            crumbling: Vec::new(),
+            traps: Vec::new(),
+            conveyors: Vec::new(),
+            ladders: Vec::new(),
+            climbing: None,
+            hazards: Vec::new(),
+            winds: Vec::new(),
+            theme: LevelTheme::default(),
+            portals: Vec::new(),
+            portal_cooldown: 0.0,
+            seeds: SeedPouch::default(),
+            projectiles: Vec::new(),
+            standing_on: None,
+            fling: 0.0,
+            helpers: Vec::new(),
+            helper_uses: 0,
+            checkpoints: Vec::new(),
+            watchdog: FruitWatchdog::default(),
+            respawn_point: PLAYER_SPAWN,
+            level: 1,
+            lives: GameRules::default().starting_lives,
+            score: 0,
+            combo: Combo::default(),
+            ticks: 0,
+            status: StatusEffects::default(),
+            difficulty,
+            curve: DifficultyCurve::default(),
+            modifiers: ModifierStack::default(),
+            picks: Vec::new(),
+            jump_held: false,
+            throw_held: false,
+        };
+        simulation.build_level();
+        simulation
+    }
+
+    // Modifiers to pick at the drafts; picks that weren't offered are skipped
+    pub fn with_picks(mut self, picks: Vec<Modifier>) -> Self {
+        self.picks = picks;
+        self
+    }
+
+    // Plays the run with a resized player (the size mutators)
+    pub fn with_player_size(mut self, size: f32) -> Self {
+        self.body = self.body.with_size(size);
+        // The first level has to be reachable for this player too
+        self.build_level();
+        self
+    }
+
+    fn build_level(&mut self) {
+        let difficulty = (self.difficulty.difficulty(&self.curve, self.level) + self.modifiers.extra_difficulty()).min(1.0);
+        let profile = JumpProfile::new(&self.modifiers.tuning(Tuning::default()), self.body.size);
+        let level = Level::generate(self.run_seed, self.level, difficulty, &profile, &self.curve);
+        self.fruit_goal = level.fruit_goal();
+        self.platforms = level.platforms;
+        self.enemies = level.enemies;
+        self.power_ups = level.power_ups;
+        self.moving = level.moving;
+        self.crumbling = level.crumbling;
+        self.traps = level.traps;
+        self.conveyors = level.conveyors;
+        self.ladders = level.ladders;
+        self.climbing = None;
+        self.hazards = level.hazards;
+        self.winds = level.winds;
+        self.theme = level.theme;
+        self.portals = level.portals.iter().flat_map(PortalPair::ends).collect();
+        self.portal_cooldown = 0.0;
+        self.projectiles.clear();
+        self.standing_on = None;
+        self.helpers.clear();
+        self.helper_uses = 0;
+        self.checkpoints = level.checkpoints;
+        self.watchdog = FruitWatchdog::default();
+        self.respawn_point = PLAYER_SPAWN;
+        self.fruit = level.fruit.map(|(_, position)| position);
+        self.fruit_collected = 0;
+        self.goal_fruit = level.goal_fruit;
+        self.bonus_fruit = level.bonus_fruit;
+        self.keys = level.exit.as_ref().map_or_else(Vec::new, |exit| exit.keys.clone());
+        self.keys_held = 0;
+        self.exit = level.exit;
+        self.fruit_kind = level.fruit.map_or(FruitKind::Normal, |(kind, _)| kind);
+        self.fleeing = match level.fruit {
+            Some((FruitKind::Fleeing, position)) => FleeingFruit::new(&self.platforms, position, profile),
+            _ => None,
+        };
+    }
+
+    fn respawn(&mut self) {
+        let (x, y) = self.respawn_point;
+        self.body = Body { x, y, ..Body::at_spawn().with_size(self.body.size) };
+        self.jumps = JumpTimers::default();
+        self.climbing = None;
+        self.standing_on = None;
+        self.fling = 0.0;
+        self.portal_cooldown = 0.0;
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.lives == 0
+    }
+
+    pub fn step(&mut self, input: SimInput, dt: f32) {
+        if self.is_over() {
+            return;
+        }
+        self.ticks += 1;
+        self.difficulty.tick(dt);
+        self.status.tick(dt);
+
+        let mut jump_pressed = input.jump && !self.jump_held;
+        self.jump_held = input.jump;
+        let throw_pressed = input.throw && !self.throw_held;
+        self.throw_held = input.throw;
+
+        // Platforms move first, taking a player standing on them along
+        for motion in &mut self.moving {
+            let Some(platform) = self.platforms.get_mut(motion.platform) else {
+                continue;
+            };
+            let before = *platform;
+            motion.update(dt);
+            (platform.x, platform.y) = motion.position();
+            if self.standing_on == Some(motion.platform) {
+                carry(&mut self.body, &before, platform);
+            }
+        }
+        for crumbling in &mut self.crumbling {
+            crumbling.update(self.standing_on == Some(crumbling.platform), dt);
+        }
+
+        for helper in &mut self.helpers {
+            helper.update(dt);
+        }
+        self.helpers.retain(|helper| !helper.expired());
+        if summons_helper(&self.body, jump_pressed, input.down, self.helper_uses) {
+            self.helpers.push(HelperPlatform::beneath(&self.body));
+            self.helper_uses -= 1;
+            jump_pressed = false;
+        }
+
+        let tuning = self.status.tuning(self.theme.tuning(self.modifiers.tuning(Tuning::default())));
+        // On a ladder the climb moves the player instead of running, falling
+        // and landing; letting go hands them back to those the same step
+        if self.climbing.is_none() {
+            self.climbing = self.ladders.iter().position(|ladder| ladder.grabbed_by(&self.body, input));
+        }
+        if let Some(index) = self.climbing {
+            if !climb(&mut self.body, &mut self.jumps, &self.ladders[index], input, jump_pressed, &tuning, dt) {
+                self.climbing = None;
+                jump_pressed = false;
+            }
+        }
+        if self.climbing.is_none() {
+            apply_input(&mut self.body, &mut self.jumps, self.status.horizontal_input(input.horizontal()), jump_pressed, input.down, dt, &tuning);
+            fling(&mut self.body, &mut self.fling, dt);
+            // Belts carry whoever the last collision check left on them, and
+            // wind drifts the player wherever they are
+            if let Some(conveyor) = self.conveyors.iter().find(|conveyor| self.standing_on == Some(conveyor.platform)) {
+                convey(&mut self.body, conveyor, dt);
+            }
+            blow(&mut self.body, &self.winds, dt);
+            apply_gravity(&mut self.body, &self.jumps, dt, &tuning);
+            integrate(&mut self.body, dt);
+        }
+        // Fallen and tipped platforms aren't there to land on
+        let is_solid = |index: usize| {
+            self.crumbling.iter().all(|crumbling| crumbling.platform != index || crumbling.solid())
+                && self.traps.iter().all(|trap| trap.platform != index || trap.solid())
+        };
+        let solid: Vec<PlatformSpec> = self
+            .platforms
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| is_solid(*index))
+            .map(|(_, platform)| *platform)
+            .chain(self.helpers.iter().map(|helper| helper.spec))
+            .collect();
+        self.standing_on = if self.climbing.is_some() {
+            None
+        } else {
+            resolve_platform_collisions(&mut self.body, &solid, &self.jumps, dt);
+            keep_in_world(&mut self.body, WorldBounds::CLASSIC);
+            (0..self.platforms.len()).find(|&index| is_solid(index) && stood_on(&self.body, &self.platforms[index]))
+        };
+        // Traps react to whoever the collisions left standing on them
+        for trap in &mut self.traps {
+            let Some(platform) = self.platforms.get(trap.platform) else {
+                continue;
+            };
+            if self.standing_on == Some(trap.platform) {
+                if let Some(speed) = trap.contact(&mut self.body, platform) {
+                    self.fling = speed;
+                }
+            }
+            trap.update(dt);
+        }
+        // A portal takes the player off whatever they were standing on
+        if self.climbing.is_none() && teleport(&mut self.body, &self.portals, &mut self.portal_cooldown, dt) {
+            self.standing_on = None;
+        }
+
+        self.seeds.update(&self.body, dt);
+        if throw_pressed {
+            self.projectiles.extend(self.seeds.throw(&self.body));
+        }
+        for enemy in &mut self.enemies {
+            enemy.update(Some((self.body.x, self.body.y)), &solid, dt);
+        }
+        update_projectiles(&mut self.projectiles, &mut self.enemies, &solid, WorldBounds::CLASSIC, dt);
+
+        // A fruit out of reach for too long is brought back in reach. A fleeing
+        // fruit only runs to platforms the player can reach anyway.
+        let main_fruit = self.fruit.filter(|_| self.fleeing.is_none());
+        let remaining: Vec<(f32, f32)> = main_fruit.into_iter().chain(self.goal_fruit.iter().copied()).collect();
+        if !remaining.is_empty() {
+            let footing = clear_footing(&solid, &self.hazards);
+            let start = self.standing_on.and_then(|index| solid.iter().position(|platform| *platform == self.platforms[index]));
+            let profile = JumpProfile::new(&self.modifiers.tuning(Tuning::default()), self.body.size);
+            let player = (self.body.x, self.body.y);
+            let stranded = start.and_then(|start| stranded_fruit(&footing, start, &remaining, player, &profile).map(|index| (start, index)));
+            if self.watchdog.update(start.map(|_| stranded.is_some()), dt) {
+                match stranded.map(|(start, index)| (index, rescue(&footing, start, remaining[index], &profile))) {
+                    Some((_, Rescue::Platform(step))) => self.platforms.push(step),
+                    Some((0, Rescue::MoveFruit(position))) if main_fruit.is_some() => self.fruit = Some(position),
+                    Some((index, Rescue::MoveFruit(position))) => self.goal_fruit[index - usize::from(main_fruit.is_some())] = position,
+                    None => {}
+                }
+            }
+        }
+
+        let mut catchable = true;
+        if let Some(fleeing) = &mut self.fleeing {
+            fleeing.update((self.body.x, self.body.y), dt);
+            self.fruit = Some(fleeing.position());
+            catchable = fleeing.catchable();
+        }
+
+        if let Some(&checkpoint) = self.checkpoints.iter().find(|&&checkpoint| touches_checkpoint(&self.body, checkpoint)) {
+            self.respawn_point = respawn_point(checkpoint);
+        }
+        // Bonus fruit scores plainly, leaving the combo alone
+        if let Some(index) = self.bonus_fruit.iter().position(|&position| touches_fruit(&self.body, position, &tuning)) {
+            self.bonus_fruit.remove(index);
+            self.score += FRUIT_SCORE;
+        }
+        if let Some(index) = self.power_ups.iter().position(|(_, position)| touches_fruit(&self.body, *position, &tuning)) {
+            let (power_up, _) = self.power_ups.remove(index);
+            self.collect_power_up(power_up);
+        }
+        // Landing on an enemy defeats it and bounces the player back up
+        if let Some(index) = self.enemies.iter().position(|enemy| enemy.stomped_by(&self.body)) {
+            self.enemies.remove(index);
+            stomp_bounce(&mut self.body);
+            self.score += ENEMY_STOMP_SCORE;
+        }
+        // A shield takes one enemy hit, and the enemy with it
+        if self.status.has(StatusEffect::Shield) {
+            if let Some(index) = self.enemies.iter().position(|enemy| enemy.touches(&self.body)) {
+                self.enemies.remove(index);
+                self.status.remove(StatusEffect::Shield);
+            }
+        }
+
+        // One fruit a step, as the game collects one a frame
+        if catchable && self.fruit.is_some_and(|fruit| touches_fruit(&self.body, fruit, &tuning)) {
+            self.fruit = None;
+            self.fleeing = None;
+            self.collect_fruit(self.fruit_kind);
+        } else if let Some(index) = self.goal_fruit.iter().position(|&position| touches_fruit(&self.body, position, &tuning)) {
+            self.goal_fruit.remove(index);
+            self.collect_fruit(FruitKind::Normal);
+        }
+        // Keys are picked up like fruit, without scoring
+        if let Some(index) = self.keys.iter().position(|&position| touches_fruit(&self.body, position, &tuning)) {
+            self.keys.remove(index);
+            self.keys_held += 1;
+        }
+        let through_door = self.exit.as_ref().is_some_and(|exit| exit.opens_with(self.keys_held) && enters_door(&self.body, exit.door));
+
+        if (self.fruit_goal > 0 && self.fruit_collected >= self.fruit_goal) || through_door {
+            self.level += 1;
+            self.difficulty.record_clear();
+            // The game builds the next level before showing the draft; the
+            // player starts it at its spawn point, not the old checkpoint
+            self.build_level();
+            self.respawn();
+            if draft_offered(self.level) {
+                self.draft();
+            }
+        } else if fell_out_of_world(&self.body, WorldBounds::CLASSIC)
+            || self.enemies.iter().any(|enemy| enemy.touches(&self.body))
+            || self.hazards.iter().any(|hazard| hazard.touches(&self.body))
+        {
+            self.lives -= 1;
+            self.combo.reset();
+            self.respawn();
+            self.difficulty.record_death();
+        }
+    }
+
+    fn collect_fruit(&mut self, kind: FruitKind) {
+        self.fruit_collected += 1;
+        // Scored before record_clear resets the level clock
+        self.score += self.combo.collect(kind.score(), self.difficulty.level_seconds);
+        self.lives += kind.extra_lives();
+        if kind == FruitKind::Cursed {
+            self.status.apply(curse_effect(fruit_seed(self.run_seed, self.level)), CURSE_SECONDS);
+        }
+    }
+
+    fn collect_power_up(&mut self, power_up: Collectible) {
+        self.lives += power_up.extra_lives();
+        if power_up == Collectible::HelperPlatform {
+            self.helper_uses += HELPER_PLATFORM_USES;
+        }
+        if let Some(effect) = power_up.effect() {
+            self.status.apply(effect, POWER_UP_SECONDS);
+        }
+    }
+
+    fn draft(&mut self) {
+        if self.picks.is_empty() {
+            return;
+        }
+        let pick = self.picks.remove(0);
+        if draft_choices(draft_seed(self.run_seed, self.level)).contains(&pick) {
+            self.lives += pick.extra_lives();
+            self.modifiers.picked.push(pick);
+        }
+    }
+
+    pub fn outcome(&self) -> RunOutcome {
+        RunOutcome {
+            level: self.level,
+            lives: self.lives,
+            score: self.score,
+            ticks: self.ticks,
+            game_over: self.is_over(),
+        }
+    }
+}
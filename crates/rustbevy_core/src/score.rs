@@ -0,0 +1,36 @@
+// Classic-mode scoring. Each fruit is worth its base score plus a bonus for
+// clearing the level quickly, times the combo multiplier. Every fruit collected
+// within COMBO_WINDOW_SECONDS of reaching its level grows the combo (up to
+// MAX_COMBO); a slower fruit starts it over and losing a life breaks it.
+
+// Clearing a level faster than this earns a bonus for every second left
+pub const TIME_BONUS_SECONDS: f32 = 20.0;
+pub const TIME_BONUS_PER_SECOND: u32 = 5;
+pub const COMBO_WINDOW_SECONDS: f32 = 12.0;
+pub const MAX_COMBO: u32 = 5;
+
+pub fn time_bonus(level_seconds: f32) -> u32 {
+    (TIME_BONUS_SECONDS - level_seconds).max(0.0) as u32 * TIME_BONUS_PER_SECOND
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Combo {
+    // Quick fruits collected in a row
+    pub streak: u32,
+}
+
+impl Combo {
+    pub fn multiplier(&self) -> u32 {
+        self.streak.clamp(1, MAX_COMBO)
+    }
+
+    // Points for a fruit worth `base` collected `level_seconds` into its level
+    pub fn collect(&mut self, base: u32, level_seconds: f32) -> u32 {
+        self.streak = if level_seconds <= COMBO_WINDOW_SECONDS { self.streak + 1 } else { 1 };
+        (base + time_bonus(level_seconds)) * self.multiplier()
+    }
+
+    pub fn reset(&mut self) {
+        self.streak = 0;
+    }
+}
@@ -0,0 +1,144 @@
+// State hashes for tracking down desyncs.
+//
+// A run that should play out the same way twice (the same replay, or two
+// peers on one seed) can hash its state at a few points in every tick and
+// write the hashes out. Comparing two such logs finds the first tick and the
+// first point in it where they disagree, so the system that ran just before
+// that point is the one to look at (see `src/bin/compare_hashes.rs`).
+
+// FNV-1a over the raw bits of the values written, so it is the same on every
+// platform and run (unlike `std`'s randomly keyed hasher)
+#[derive(Clone, Copy, Debug)]
+pub struct StateHash(u64);
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+impl Default for StateHash {
+    fn default() -> Self {
+        Self(FNV_OFFSET)
+    }
+}
+
+impl StateHash {
+    pub fn write_bytes(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 = (self.0 ^ *byte as u64).wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    pub fn write_u32(&mut self, value: u32) {
+        self.write_bytes(&value.to_le_bytes());
+    }
+
+    pub fn write_u64(&mut self, value: u64) {
+        self.write_bytes(&value.to_le_bytes());
+    }
+
+    // By bits, so -0.0 and 0.0 (or two NaNs) only match if they really are the same
+    pub fn write_f32(&mut self, value: f32) {
+        self.write_u32(value.to_bits());
+    }
+
+    pub fn write_bool(&mut self, value: bool) {
+        self.write_bytes(&[value as u8]);
+    }
+
+    pub fn finish(self) -> u64 {
+        self.0
+    }
+
+    // Combines hashes of things that have no fixed order (entities, say) so
+    // the order they were hashed in doesn't matter
+    pub fn unordered(mut hashes: Vec<u64>) -> u64 {
+        hashes.sort_unstable();
+        let mut hash = Self::default();
+        for value in hashes {
+            hash.write_u64(value);
+        }
+        hash.finish()
+    }
+}
+
+// One hash: the state as it was at `stage` of `tick`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HashEntry {
+    pub tick: u64,
+    pub stage: String,
+    pub hash: u64,
+}
+
+// Where two hash logs first disagree
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Divergence {
+    // The state differs at `stage` of `tick`; the systems that ran since
+    // `last_match` (the previous stage, if any of this tick matched) changed it
+    Hash { tick: u64, stage: String, last_match: Option<String> },
+    // The logs agree until one of them ends, after `matched` entries
+    Length { matched: usize },
+    // The logs hash different stages at the same spot, so they weren't
+    // recorded by the same build or settings
+    Stage { tick: u64, left: String, right: String },
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HashLog {
+    pub entries: Vec<HashEntry>,
+}
+
+impl HashLog {
+    pub fn push(&mut self, tick: u64, stage: &str, hash: u64) {
+        self.entries.push(HashEntry { tick, stage: stage.to_string(), hash });
+    }
+
+    // Text format, one `<tick> <stage> <hash in hex>` line per entry
+    pub fn to_text(&self) -> String {
+        self.entries
+            .iter()
+            .map(|entry| format!("{} {} {:016x}\n", entry.tick, entry.stage, entry.hash))
+            .collect()
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut log = Self::default();
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let error = || format!("line {}: could not parse '{}'", number + 1, line);
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let [tick, stage, hash] = fields.as_slice() else {
+                return Err(error());
+            };
+            let tick = tick.parse().map_err(|_| error())?;
+            let hash = u64::from_str_radix(hash, 16).map_err(|_| error())?;
+            log.push(tick, stage, hash);
+        }
+        Ok(log)
+    }
+
+    // The first place this log and `other` disagree, or `None` if they match
+    pub fn first_divergence(&self, other: &HashLog) -> Option<Divergence> {
+        for (index, (left, right)) in self.entries.iter().zip(&other.entries).enumerate() {
+            if left.tick != right.tick || left.stage != right.stage {
+                return Some(Divergence::Stage {
+                    tick: left.tick,
+                    left: left.stage.clone(),
+                    right: right.stage.clone(),
+                });
+            }
+            if left.hash != right.hash {
+                let last_match = index
+                    .checked_sub(1)
+                    .map(|previous| &self.entries[previous])
+                    .filter(|previous| previous.tick == left.tick)
+                    .map(|previous| previous.stage.clone());
+                return Some(Divergence::Hash { tick: left.tick, stage: left.stage.clone(), last_match });
+            }
+        }
+        (self.entries.len() != other.entries.len()).then(|| Divergence::Length {
+            matched: self.entries.len().min(other.entries.len()),
+        })
+    }
+}
@@ -0,0 +1,81 @@
+// Broad phase for collision checks: a spatial hash of square cells. Anything
+// put in the grid is listed in every cell its box overlaps, so a query only
+// has to look at the few things in the cells around it rather than everything
+// in the level. Queries hand back candidates, not hits; the exact checks
+// (`physics::resolve_platform_collisions`, `touches_fruit`, ...) still decide.
+
+use std::collections::HashMap;
+
+// Cells wide enough that a player, an enemy or most platforms span one or two
+pub const DEFAULT_CELL_SIZE: f32 = 128.0;
+
+// Items (platform or entity ids) by the cells their boxes overlap
+#[derive(Clone, Debug)]
+pub struct SpatialGrid<T> {
+    cell_size: f32,
+    items: Vec<T>,
+    // Indices into `items`
+    cells: HashMap<(i32, i32), Vec<usize>>,
+}
+
+impl<T: Copy> Default for SpatialGrid<T> {
+    fn default() -> Self {
+        Self::new(DEFAULT_CELL_SIZE)
+    }
+}
+
+impl<T: Copy> SpatialGrid<T> {
+    pub fn new(cell_size: f32) -> Self {
+        Self { cell_size, items: Vec::new(), cells: HashMap::new() }
+    }
+
+    // Empties the grid, keeping its cells' storage for the next rebuild
+    pub fn clear(&mut self) {
+        self.items.clear();
+        self.cells.values_mut().for_each(Vec::clear);
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    // Adds `item` with a box centred on `center` reaching `half_extents` either side
+    pub fn insert(&mut self, item: T, center: (f32, f32), half_extents: (f32, f32)) {
+        let index = self.items.len();
+        self.items.push(item);
+        let ((left, bottom), (right, top)) = self.cell_range(center, half_extents);
+        for x in left..=right {
+            for y in bottom..=top {
+                self.cells.entry((x, y)).or_default().push(index);
+            }
+        }
+    }
+
+    // Everything whose box shares a cell with the box around `center`, once
+    // each and in the order it was inserted, so results don't depend on the
+    // cell layout
+    pub fn query(&self, center: (f32, f32), half_extents: (f32, f32)) -> Vec<T> {
+        let ((left, bottom), (right, top)) = self.cell_range(center, half_extents);
+        let mut indices: Vec<usize> = (left..=right)
+            .flat_map(|x| (bottom..=top).map(move |y| (x, y)))
+            .filter_map(|cell| self.cells.get(&cell))
+            .flatten()
+            .copied()
+            .collect();
+        indices.sort_unstable();
+        indices.dedup();
+        indices.into_iter().map(|index| self.items[index]).collect()
+    }
+
+    fn cell_range(&self, center: (f32, f32), half_extents: (f32, f32)) -> ((i32, i32), (i32, i32)) {
+        let cell = |value: f32| (value / self.cell_size).floor() as i32;
+        (
+            (cell(center.0 - half_extents.0), cell(center.1 - half_extents.1)),
+            (cell(center.0 + half_extents.0), cell(center.1 + half_extents.1)),
+        )
+    }
+}
@@ -0,0 +1,122 @@
+// Split times for speedrun mode. A run's splits are its run time at the end
+// of each level; the best runs are kept with all their splits, so a new run
+// can be compared against the best one level by level, and each level's
+// fastest time across them (its gold segment) can be spotted.
+
+use crate::category::format_time;
+
+// Runs kept on the splits leaderboard
+pub const SPLIT_RUNS_KEPT: usize = 5;
+
+// Run time at the end of each level, level 1 first
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Splits {
+    pub times: Vec<f32>,
+}
+
+impl Splits {
+    pub fn push(&mut self, seconds: f32) {
+        self.times.push(seconds);
+    }
+
+    pub fn levels(&self) -> usize {
+        self.times.len()
+    }
+
+    pub fn total(&self) -> Option<f32> {
+        self.times.last().copied()
+    }
+
+    // Time spent on one level (0 = level 1)
+    pub fn segment(&self, index: usize) -> Option<f32> {
+        let end = *self.times.get(index)?;
+        let start = if index == 0 { 0.0 } else { self.times[index - 1] };
+        Some(end - start)
+    }
+
+    // Whether this run beats `other`: further, or as far and sooner
+    fn beats(&self, other: &Splits) -> bool {
+        match self.levels().cmp(&other.levels()) {
+            std::cmp::Ordering::Greater => true,
+            std::cmp::Ordering::Less => false,
+            std::cmp::Ordering::Equal => self.total() < other.total(),
+        }
+    }
+}
+
+// How one split went against the leaderboard
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SplitComparison {
+    // Against the best run's split for the same level, if it got that far
+    pub delta: Option<f32>,
+    // The fastest this level has been done
+    pub gold: bool,
+}
+
+// Best runs with their splits, best first
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SplitBoard {
+    pub runs: Vec<Splits>,
+}
+
+impl SplitBoard {
+    // Adds a finished run; returns its place (1 = new best) if it made the board
+    pub fn record(&mut self, run: Splits) -> Option<usize> {
+        if run.times.is_empty() {
+            return None;
+        }
+        let place = self.runs.iter().position(|kept| run.beats(kept)).unwrap_or(self.runs.len());
+        if place >= SPLIT_RUNS_KEPT {
+            return None;
+        }
+        self.runs.insert(place, run);
+        self.runs.truncate(SPLIT_RUNS_KEPT);
+        Some(place + 1)
+    }
+
+    pub fn best(&self) -> Option<&Splits> {
+        self.runs.first()
+    }
+
+    // Fastest time on one level across the kept runs
+    pub fn best_segment(&self, index: usize) -> Option<f32> {
+        self.runs.iter().filter_map(|run| run.segment(index)).min_by(f32::total_cmp)
+    }
+
+    // How the latest split of `run` compares
+    pub fn compare(&self, run: &Splits) -> Option<SplitComparison> {
+        let index = run.levels().checked_sub(1)?;
+        let seconds = run.times[index];
+        let delta = self.best().and_then(|best| best.times.get(index)).map(|best| seconds - best);
+        let segment = run.segment(index)?;
+        let gold = self.best_segment(index).is_none_or(|best| segment < best);
+        Some(SplitComparison { delta, gold })
+    }
+
+    // One line per run: its splits in seconds, separated by spaces
+    pub fn to_text(&self) -> String {
+        self.runs
+            .iter()
+            .map(|run| run.times.iter().map(f32::to_string).collect::<Vec<_>>().join(" "))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    // Lines that don't parse are skipped
+    pub fn from_text(text: &str) -> Self {
+        let mut board = Self::default();
+        for line in text.lines() {
+            let times: Result<Vec<f32>, _> = line.split_whitespace().map(str::parse).collect();
+            if let Ok(times) = times {
+                board.record(Splits { times });
+            }
+        }
+        board
+    }
+}
+
+// `+m:ss.cc` or `-m:ss.cc`
+pub fn format_delta(seconds: f32) -> String {
+    let sign = if seconds < 0.0 { '-' } else { '+' };
+    format!("{}{}", sign, format_time(seconds.abs()))
+}
@@ -0,0 +1,91 @@
+// Timed status effects on the player: the debuffs from cursed fruit and the
+// power-ups from `collectible`
+
+use crate::physics::Tuning;
+
+// How much faster the player runs with a speed boost
+pub const SPEED_BOOST: f32 = 1.5;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StatusEffect {
+    InvertedControls,
+    Darkness,
+    SpeedBoost,
+    DoubleJump,
+    // Takes one enemy hit instead of a life
+    Shield,
+}
+
+impl StatusEffect {
+    // The debuffs a cursed fruit can put on the player
+    pub const CURSES: [StatusEffect; 2] = [StatusEffect::InvertedControls, StatusEffect::Darkness];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::InvertedControls => "Inverted controls",
+            Self::Darkness => "Darkness",
+            Self::SpeedBoost => "Speed boost",
+            Self::DoubleJump => "Double jump",
+            Self::Shield => "Shield",
+        }
+    }
+}
+
+// The effects currently on the player with their seconds left
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct StatusEffects {
+    active: Vec<(StatusEffect, f32)>,
+}
+
+impl StatusEffects {
+    // Starts `effect`, or tops it back up if it is already running
+    pub fn apply(&mut self, effect: StatusEffect, seconds: f32) {
+        match self.active.iter_mut().find(|(active, _)| *active == effect) {
+            Some((_, remaining)) => *remaining = remaining.max(seconds),
+            None => self.active.push((effect, seconds)),
+        }
+    }
+
+    pub fn tick(&mut self, dt: f32) {
+        for (_, remaining) in &mut self.active {
+            *remaining -= dt;
+        }
+        self.active.retain(|(_, remaining)| *remaining > 0.0);
+    }
+
+    pub fn has(&self, effect: StatusEffect) -> bool {
+        self.active.iter().any(|(active, _)| *active == effect)
+    }
+
+    pub fn active(&self) -> &[(StatusEffect, f32)] {
+        &self.active
+    }
+
+    pub fn remove(&mut self, effect: StatusEffect) {
+        self.active.retain(|(active, _)| *active != effect);
+    }
+
+    pub fn clear(&mut self) {
+        self.active.clear();
+    }
+
+    // The player's tuning with the power-ups that change how they move
+    pub fn tuning(&self, mut tuning: Tuning) -> Tuning {
+        if self.has(StatusEffect::SpeedBoost) {
+            tuning.player_speed *= SPEED_BOOST;
+        }
+        if self.has(StatusEffect::DoubleJump) {
+            tuning.air_jumps += 1;
+        }
+        tuning
+    }
+
+    // Horizontal input after effects that mess with the controls
+    pub fn horizontal_input(&self, horizontal: f32) -> f32 {
+        if self.has(StatusEffect::InvertedControls) {
+            -horizontal
+        } else {
+            horizontal
+        }
+    }
+}
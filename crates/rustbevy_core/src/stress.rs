@@ -0,0 +1,60 @@
+// Synthetic worlds for measuring performance: far more platforms, enemies and
+// pickups than any level has, packed over the Classic world. The benchmarks
+// (`benches/`) run the generator, the reachability checks and the collision
+// routine against them, and the game's `--stress` run plays one, so a slowdown
+// in the physics or the broad phase shows up as a number rather than a feel.
+
+use crate::config::{PLATFORM_HEIGHT, PLAYER_SPAWN};
+use crate::generation::{fruit_spot, PlatformSpec, STARTING_PLATFORM};
+use crate::level::Level;
+use crate::rng::SeededRng;
+use crate::world::WorldBounds;
+
+// What the `--stress` run and the benchmarks use
+pub const STRESS_PLATFORMS: usize = 600;
+pub const STRESS_ENEMIES: usize = 150;
+pub const STRESS_PICKUPS: usize = 300;
+// Around the spawn point, where only the starting platform goes
+const SPAWN_CLEARANCE: (f32, f32) = (160.0, 120.0);
+
+// About `count` platforms over `bounds`, one to a cell of an even grid so
+// none overlap, each at a seeded width and spot inside its cell. The starting
+// platform comes first, with the cells around the spawn point left empty.
+pub fn stress_platforms(seed: u64, count: usize, bounds: WorldBounds) -> Vec<PlatformSpec> {
+    let mut rng = SeededRng::new(seed);
+    let columns = ((count as f32 * bounds.width / bounds.height).sqrt().ceil() as usize).max(1);
+    let rows = count.div_ceil(columns).max(1);
+    let cell = (bounds.width / columns as f32, bounds.height / rows as f32);
+    let mut platforms = vec![STARTING_PLATFORM];
+    for row in 0..rows {
+        for column in 0..columns {
+            let center = (
+                -bounds.half_width() + (column as f32 + 0.5) * cell.0,
+                -bounds.half_height() + (row as f32 + 0.5) * cell.1,
+            );
+            let near_spawn = (center.0 - PLAYER_SPAWN.0).abs() < SPAWN_CLEARANCE.0 + cell.0 / 2.0
+                && (center.1 - (PLAYER_SPAWN.1 + STARTING_PLATFORM.y) / 2.0).abs() < SPAWN_CLEARANCE.1 + cell.1 / 2.0;
+            if near_spawn {
+                continue;
+            }
+            let width = rng.range(0.4, 0.8) * cell.0;
+            let x = center.0 + rng.range(-0.5, 0.5) * (cell.0 - width);
+            let y = center.1 + rng.range(-0.25, 0.25) * (cell.1 - PLATFORM_HEIGHT);
+            platforms.push(PlatformSpec { x, y, width, height: PLATFORM_HEIGHT, one_way: false, slope: 0.0 });
+        }
+    }
+    platforms
+}
+
+// A Classic level made of a stress world: `STRESS_PLATFORMS` platforms with
+// `STRESS_ENEMIES` enemies patrolling them and `STRESS_PICKUPS` bonus fruit
+// on top, spread evenly. It has no fruit to finish it.
+pub fn stress_level(seed: u64) -> Level {
+    let platforms = stress_platforms(seed, STRESS_PLATFORMS, WorldBounds::CLASSIC);
+    let spread = |count: usize| (1..platforms.len()).step_by((platforms.len() / count.max(1)).max(1)).take(count);
+    let enemies: Vec<usize> = spread(STRESS_ENEMIES).collect();
+    let bonus_fruit = spread(STRESS_PICKUPS).map(|index| fruit_spot(&platforms[index])).collect();
+    let mut level = Level::handmade(platforms, Vec::new(), None, None, PLAYER_SPAWN, &enemies, Vec::new(), &[], Vec::new(), Vec::new(), Vec::new(), Vec::new());
+    level.bonus_fruit = bonus_fruit;
+    level
+}
@@ -0,0 +1,49 @@
+// The run summary card: what a finished or lost Classic run is worth sharing.
+// The game renders these lines to a PNG; the seed line tells a friend how to
+// play the same levels.
+
+use crate::category::format_time;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RunSummary {
+    pub level: u32,
+    pub score: u32,
+    pub seconds: f32,
+    pub seed: u64,
+    // Reached the finish level rather than running out of lives
+    pub finished: bool,
+    pub mutators: Vec<String>,
+    pub modifiers: Vec<&'static str>,
+    pub assists: Vec<&'static str>,
+}
+
+impl RunSummary {
+    pub fn title(&self) -> &'static str {
+        if self.finished {
+            "RUN COMPLETE"
+        } else {
+            "GAME OVER"
+        }
+    }
+
+    // Level, score and time on the first line, then one line per list that
+    // has anything in it
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines = vec![format!("Level {}   Score {}   Time {}", self.level, self.score, format_time(self.seconds))];
+        for (label, names) in [("Mutators", self.mutators.join(", ")), ("Modifiers", self.modifiers.join(", ")), ("Assists", self.assists.join(", "))] {
+            if !names.is_empty() {
+                lines.push(format!("{}: {}", label, names));
+            }
+        }
+        lines
+    }
+
+    pub fn seed_hint(&self) -> String {
+        format!("Seed {} - play the same run with: cargo run -- --seed {}", self.seed, self.seed)
+    }
+
+    // Unique per run as long as `timestamp` (seconds) is
+    pub fn file_name(&self, timestamp: u64) -> String {
+        format!("run_{}_level_{}.png", timestamp, self.level)
+    }
+}
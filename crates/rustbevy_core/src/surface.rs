@@ -0,0 +1,55 @@
+// What a Classic platform is made of. Surfaces only change how a platform
+// looks and sounds underfoot; they all handle the same.
+
+use crate::rng::SeededRng;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Surface {
+    #[default]
+    Stone,
+    Wood,
+    Metal,
+    Ice,
+}
+
+// How often each surface comes up on a generated platform, out of 100
+const SURFACE_ODDS: [(Surface, usize); 4] = [(Surface::Stone, 40), (Surface::Wood, 25), (Surface::Metal, 20), (Surface::Ice, 15)];
+
+impl Surface {
+    pub const ALL: [Self; 4] = [Self::Stone, Self::Wood, Self::Metal, Self::Ice];
+
+    // The surface's name in level files
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Stone => "stone",
+            Self::Wood => "wood",
+            Self::Metal => "metal",
+            Self::Ice => "ice",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|surface| surface.name() == name)
+    }
+}
+
+// A surface for each of `count` generated platforms. The starting platform
+// (the first) is always stone.
+pub fn platform_surfaces(seed: u64, count: usize) -> Vec<Surface> {
+    let mut rng = SeededRng::new(seed);
+    (0..count)
+        .map(|index| {
+            if index == 0 {
+                return Surface::Stone;
+            }
+            let mut roll = rng.below(100);
+            for (surface, odds) in SURFACE_ODDS {
+                if roll < odds {
+                    return surface;
+                }
+                roll -= odds;
+            }
+            Surface::Stone
+        })
+        .collect()
+}
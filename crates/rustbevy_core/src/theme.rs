@@ -0,0 +1,44 @@
+// Level themes: the grass, cave, ice and night looks a Classic level can
+// have. The difficulty curve hands them out in turn, a few levels each, and a
+// level file can name its own. Only ice plays differently: the ground is
+// slippery, so the player takes a moment to get up to speed, stop or turn.
+
+use crate::physics::Tuning;
+
+// On ice, in run speeds a second (see `Tuning::traction`)
+const ICE_TRACTION: f32 = 2.5;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum LevelTheme {
+    #[default]
+    Grass,
+    Cave,
+    Ice,
+    Night,
+}
+
+impl LevelTheme {
+    pub const ALL: [Self; 4] = [Self::Grass, Self::Cave, Self::Ice, Self::Night];
+
+    // The theme's name in level files
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Grass => "grass",
+            Self::Cave => "cave",
+            Self::Ice => "ice",
+            Self::Night => "night",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|theme| theme.name() == name)
+    }
+
+    // `tuning` as this theme's ground leaves it
+    pub fn tuning(self, tuning: Tuning) -> Tuning {
+        match self {
+            Self::Ice => Tuning { traction: tuning.traction.min(ICE_TRACTION), ..tuning },
+            _ => tuning,
+        }
+    }
+}
@@ -0,0 +1,124 @@
+// Endless tower: a shaft one window wide, built upward a chunk at a time for
+// as long as the player keeps climbing. Its platforms are one-way, so the
+// player jumps up through them. Rows are less than a jump apart, and every row
+// has a platform the player can hop to from one on the row below, so there's
+// always a way up. Higher up the platforms get narrower and fewer, and the
+// screen scrolls up faster.
+
+use crate::config::{PLATFORM_HEIGHT, WINDOW_HEIGHT, WINDOW_WIDTH};
+use crate::generation::PlatformSpec;
+use crate::reachability::JumpProfile;
+use crate::rng::SeededRng;
+
+pub const TOWER_ROWS_PER_CHUNK: u32 = 8;
+// Room kept between the platforms and the sides of the shaft
+const SIDE_MARGIN: f32 = 20.0;
+// Rows are this share of a jump's height apart
+const ROW_SPACING: f32 = 0.75;
+// Tries at placing a row's way up before stacking it on the one below
+const PATH_ATTEMPTS: usize = 20;
+// Platform widths on the first rows and, at the narrowest, far up the tower
+const WIDEST: (f32, f32) = (140.0, 240.0);
+const NARROWEST: f32 = 0.5;
+// Rows it takes to get to the narrowest platforms
+const NARROWING_ROWS: f32 = 400.0;
+
+// Scrolling: the speed at the bottom, how much faster it gets per screen
+// climbed, and the fastest it gets
+const SCROLL_SPEED: f32 = 30.0;
+const SCROLL_SPEED_PER_SCREEN: f32 = 6.0;
+const MAX_SCROLL_SPEED: f32 = 150.0;
+
+// The floor the climb starts on, across the bottom of the first screen
+pub const TOWER_FLOOR: PlatformSpec = PlatformSpec {
+    x: 0.0,
+    y: -WINDOW_HEIGHT / 2.0 + 40.0,
+    width: WINDOW_WIDTH,
+    height: PLATFORM_HEIGHT,
+    one_way: false,
+    slope: 0.0,
+};
+
+// Builds the tower for a run seed, one chunk of rows at a time. The same
+// seed and jump profile always build the same tower.
+pub struct TowerBuilder {
+    rng: SeededRng,
+    profile: JumpProfile,
+    // The next row to build; the floor is row 0
+    row: u32,
+    // The platform on the last row built that the next row's way up starts from
+    path: PlatformSpec,
+}
+
+impl TowerBuilder {
+    pub fn new(seed: u64, profile: JumpProfile) -> Self {
+        Self { rng: SeededRng::new(seed), profile, row: 1, path: TOWER_FLOOR }
+    }
+
+    fn row_spacing(&self) -> f32 {
+        self.profile.jump_height() * ROW_SPACING
+    }
+
+    // Where the rows not built yet start
+    pub fn built_up_to(&self) -> f32 {
+        TOWER_FLOOR.y + self.row as f32 * self.row_spacing()
+    }
+
+    // The next `TOWER_ROWS_PER_CHUNK` rows
+    pub fn next_chunk(&mut self) -> Vec<PlatformSpec> {
+        let mut platforms = Vec::new();
+        for _ in 0..TOWER_ROWS_PER_CHUNK {
+            platforms.extend(self.next_row());
+        }
+        platforms
+    }
+
+    fn next_row(&mut self) -> Vec<PlatformSpec> {
+        let y = self.built_up_to();
+        let narrowing = (self.row as f32 / NARROWING_ROWS).min(1.0);
+        let scale = 1.0 - (1.0 - NARROWEST) * narrowing;
+        self.row += 1;
+
+        // The way up first, then up to two more platforms (fewer higher up)
+        // wherever they don't crowd it
+        let width = self.rng.range(WIDEST.0, WIDEST.1) * scale;
+        let mut way_up = platform_at(self.path.x, y, width);
+        for _ in 0..PATH_ATTEMPTS {
+            let platform = platform_at(self.random_x(width), y, width);
+            if self.profile.can_hop(&self.path, &platform) {
+                way_up = platform;
+                break;
+            }
+        }
+        let mut row = vec![way_up];
+        let extras = self.rng.below(3).saturating_sub((narrowing * 2.0).round() as usize);
+        for _ in 0..extras {
+            let width = self.rng.range(WIDEST.0, WIDEST.1) * scale;
+            let platform = platform_at(self.random_x(width), y, width);
+            if row.iter().all(|other| (other.x - platform.x).abs() > (other.width + platform.width) / 2.0 + self.profile.player_size * 2.0) {
+                row.push(platform);
+            }
+        }
+        self.path = way_up;
+        row
+    }
+
+    fn random_x(&mut self, width: f32) -> f32 {
+        let limit = WINDOW_WIDTH / 2.0 - width / 2.0 - SIDE_MARGIN;
+        self.rng.range(-limit, limit)
+    }
+}
+
+fn platform_at(x: f32, y: f32, width: f32) -> PlatformSpec {
+    PlatformSpec { x, y, width, height: PLATFORM_HEIGHT, one_way: true, slope: 0.0 }
+}
+
+// How fast the screen scrolls up once the climb has reached `height` above the floor
+pub fn scroll_speed(height: f32) -> f32 {
+    (SCROLL_SPEED + height.max(0.0) / WINDOW_HEIGHT * SCROLL_SPEED_PER_SCREEN).min(MAX_SCROLL_SPEED)
+}
+
+// The score for a climb: a point per ten pixels above the floor
+pub fn height_score(height: f32) -> u32 {
+    (height.max(0.0) / 10.0) as u32
+}
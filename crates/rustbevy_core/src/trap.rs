@@ -0,0 +1,135 @@
+// Trap platforms. From the difficulty curve's `traps_from_level` on, a few
+// platforms react to the player standing on them. Tilting ones tip over a
+// moment later, dumping whoever is still on them, and level out again after a
+// while. Launching ones fling the player sideways, always the same way, as
+// soon as they touch down, then need a moment to recharge.
+
+use crate::generation::PlatformSpec;
+use crate::physics::Body;
+use crate::rng::SeededRng;
+
+// How long a tilting platform holds once stood on
+pub const TILT_DELAY_SECONDS: f32 = 0.7;
+// How long it stays tipped over, with nothing to stand on
+pub const TIPPED_SECONDS: f32 = 1.5;
+// How far it leans while tipped, in radians
+pub const TIPPED_ANGLE: f32 = 1.1;
+// How far it has leant when it tips
+const WARNING_ANGLE: f32 = 0.15;
+// Sideways speed a launch gives, and the hop that comes with it
+pub const LAUNCH_SPEED: f32 = 650.0;
+pub const LAUNCH_LIFT: f32 = 380.0;
+// How quickly the sideways push fades (per second)
+pub const FLING_DECAY: f32 = 3.0;
+pub const LAUNCH_RECHARGE_SECONDS: f32 = 1.0;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TrapKind {
+    Tilt,
+    // `direction` is -1.0 (left) or 1.0 (right)
+    Launch { direction: f32 },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TrapState {
+    Ready,
+    // Seconds left before it tips, towards `side` (-1.0 or 1.0)
+    Tilting { left: f32, side: f32 },
+    // Seconds left before it levels out again
+    Tipped { left: f32, side: f32 },
+    // Seconds left before a launching platform fires again
+    Recharging(f32),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TrapPlatform {
+    // Index of the platform in its level
+    pub platform: usize,
+    pub kind: TrapKind,
+    pub state: TrapState,
+}
+
+impl TrapPlatform {
+    pub fn new(platform: usize, kind: TrapKind) -> Self {
+        Self { platform, kind, state: TrapState::Ready }
+    }
+
+    // Someone is standing on `platform` (this trap's platform) this step.
+    // Returns the sideways speed to fling `body` with when it launches them;
+    // the hop is already given.
+    pub fn contact(&mut self, body: &mut Body, platform: &PlatformSpec) -> Option<f32> {
+        if self.state != TrapState::Ready {
+            return None;
+        }
+        match self.kind {
+            TrapKind::Tilt => {
+                // It tips the way the player is leaning
+                let side = if body.x < platform.x { -1.0 } else { 1.0 };
+                self.state = TrapState::Tilting { left: TILT_DELAY_SECONDS, side };
+                None
+            }
+            TrapKind::Launch { direction } => {
+                self.state = TrapState::Recharging(LAUNCH_RECHARGE_SECONDS);
+                body.velocity_y = LAUNCH_LIFT;
+                body.grounded = false;
+                Some(direction * LAUNCH_SPEED)
+            }
+        }
+    }
+
+    // Advances the trap by `dt`. Once it starts tilting it tips whether or not
+    // the player stays.
+    pub fn update(&mut self, dt: f32) {
+        self.state = match self.state {
+            TrapState::Ready => TrapState::Ready,
+            TrapState::Tilting { left, side } if left > dt => TrapState::Tilting { left: left - dt, side },
+            TrapState::Tilting { side, .. } => TrapState::Tipped { left: TIPPED_SECONDS, side },
+            TrapState::Tipped { left, side } if left > dt => TrapState::Tipped { left: left - dt, side },
+            TrapState::Tipped { .. } => TrapState::Ready,
+            TrapState::Recharging(left) if left > dt => TrapState::Recharging(left - dt),
+            TrapState::Recharging(_) => TrapState::Ready,
+        };
+    }
+
+    // Whether the player can stand on it
+    pub fn solid(&self) -> bool {
+        !matches!(self.state, TrapState::Tipped { .. })
+    }
+
+    // How far the platform leans, in radians (counter-clockwise), for drawing
+    pub fn angle(&self) -> f32 {
+        match self.state {
+            TrapState::Tilting { left, side } => -side * WARNING_ANGLE * (1.0 - left / TILT_DELAY_SECONDS),
+            TrapState::Tipped { side, .. } => -side * TIPPED_ANGLE,
+            TrapState::Ready | TrapState::Recharging(_) => 0.0,
+        }
+    }
+}
+
+// The sideways push a launch leaves on the player, added on top of their own
+// movement every step until it fades
+pub fn fling(body: &mut Body, fling: &mut f32, dt: f32) {
+    body.velocity_x += *fling;
+    *fling *= (1.0 - FLING_DECAY * dt).max(0.0);
+    if fling.abs() < 1.0 {
+        *fling = 0.0;
+    }
+}
+
+// Up to `count` trap platforms, about half of each kind: never the starting
+// platform (the first one) or any platform listed in `keep_plain`
+pub fn place_traps(platforms: &[PlatformSpec], seed: u64, count: usize, keep_plain: &[usize]) -> Vec<TrapPlatform> {
+    let mut candidates: Vec<usize> = (1..platforms.len()).filter(|index| !keep_plain.contains(index)).collect();
+    let mut rng = SeededRng::new(seed);
+    let mut traps = Vec::new();
+    while traps.len() < count && !candidates.is_empty() {
+        let platform = candidates.swap_remove(rng.below(candidates.len()));
+        let kind = match rng.below(4) {
+            0 => TrapKind::Launch { direction: -1.0 },
+            1 => TrapKind::Launch { direction: 1.0 },
+            _ => TrapKind::Tilt,
+        };
+        traps.push(TrapPlatform::new(platform, kind));
+    }
+    traps
+}
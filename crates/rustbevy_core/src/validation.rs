@@ -0,0 +1,114 @@
+// Checks a level for the mistakes a level author wants to hear about before
+// playing it: the fruit, or the exit door or one of its keys, out of reach (by
+// jumping and falling, without landing on spikes), platforms overlapping each other, the spawn point or the fruit
+// inside a platform or a hazard, ladders not ending on a platform and ramps
+// steeper than 45°. Used by the game's `--validate-levels`.
+
+use crate::config::{FRUIT_SIZE, PLAYER_SIZE};
+use crate::generation::{PlatformSpec, STEEP_SLOPE};
+use crate::hazard::clear_footing;
+use crate::level::Level;
+use crate::reachability::{fruit_reachable, JumpProfile};
+
+// Boxes have to overlap by more than this to count, so platforms drawn edge
+// to edge don't
+const SLACK: f32 = 0.5;
+
+// A problem with a level, and where it is
+#[derive(Clone, Debug, PartialEq)]
+pub struct Issue {
+    pub position: (f32, f32),
+    pub problem: String,
+}
+
+impl Issue {
+    fn new(position: (f32, f32), problem: String) -> Self {
+        Self { position, problem }
+    }
+}
+
+// Everything wrong with `level`, with the player starting at `spawn`
+pub fn validate_level(level: &Level, spawn: (f32, f32), profile: &JumpProfile) -> Vec<Issue> {
+    let mut issues = Vec::new();
+    let platforms = &level.platforms;
+
+    // Something fruit-sized centred at `position`, called `name`, has to rest
+    // on a platform the player can get to
+    let mut check_goal = |position: (f32, f32), name: &str| {
+        let resting = platforms.iter().any(|platform| {
+            (platform.x - position.0).abs() <= platform.width / 2.0 && (platform.y + platform.height / 2.0 + FRUIT_SIZE / 2.0 - position.1).abs() < 1.0
+        });
+        if !resting {
+            issues.push(Issue::new(position, format!("{} isn't resting on top of a platform", name)));
+        } else if !fruit_reachable(&clear_footing(platforms, &level.hazards), position, profile) {
+            issues.push(Issue::new(position, format!("{} can't be reached from the starting platform", name)));
+        }
+    };
+    match (&level.exit, level.fruit) {
+        (Some(exit), _) => {
+            check_goal(exit.fruit_spot(), "the exit door");
+            for &key in &exit.keys {
+                check_goal(key, "a key");
+            }
+        }
+        (None, Some((_, fruit))) => check_goal(fruit, "the fruit"),
+        (None, None) => issues.push(Issue::new(spawn, "no fruit or exit door, so the level can't be finished".to_string())),
+    }
+
+    for (index, platform) in platforms.iter().enumerate() {
+        if platform.slope.abs() > STEEP_SLOPE {
+            issues.push(Issue::new((platform.x, platform.y), format!("platform {} is steeper than 45°", index)));
+        }
+        for (other_index, other) in platforms.iter().enumerate().skip(index + 1) {
+            if let Some(position) = overlap(platform, other) {
+                issues.push(Issue::new(position, format!("platforms {} and {} overlap", index, other_index)));
+            }
+        }
+    }
+
+    let spawn_box = square(spawn, PLAYER_SIZE);
+    let fruit_box = level.fruit.map(|(_, fruit)| square(fruit, FRUIT_SIZE));
+    for (index, platform) in platforms.iter().enumerate() {
+        if overlap(&spawn_box, platform).is_some() {
+            issues.push(Issue::new(spawn, format!("the player spawns inside platform {}", index)));
+        }
+        if let Some(fruit_box) = fruit_box.filter(|fruit_box| overlap(fruit_box, platform).is_some()) {
+            issues.push(Issue::new((fruit_box.x, fruit_box.y), format!("the fruit is inside platform {}", index)));
+        }
+    }
+    for hazard in &level.hazards {
+        let name = hazard.kind.name();
+        if overlap(&spawn_box, &hazard.spec()).is_some() {
+            issues.push(Issue::new(spawn, format!("the player spawns in the {}", name)));
+        }
+        if let Some(fruit_box) = fruit_box.filter(|fruit_box| overlap(fruit_box, &hazard.spec()).is_some()) {
+            issues.push(Issue::new((fruit_box.x, fruit_box.y), format!("the fruit is in the {}", name)));
+        }
+    }
+
+    for ladder in &level.ladders {
+        let lands_on = |height: f32| platforms.iter().any(|platform| {
+            (platform.x - ladder.x).abs() <= platform.width / 2.0 && (platform.y + platform.height / 2.0 - height).abs() < 1.0
+        });
+        if ladder.bottom >= ladder.top {
+            issues.push(Issue::new((ladder.x, ladder.bottom), "a ladder's bottom is above its top".to_string()));
+        } else if !lands_on(ladder.bottom) || !lands_on(ladder.top) {
+            issues.push(Issue::new((ladder.x, ladder.bottom), "a ladder doesn't end on top of a platform at both ends".to_string()));
+        }
+    }
+    issues
+}
+
+// A `size` by `size` box around `center`
+fn square(center: (f32, f32), size: f32) -> PlatformSpec {
+    PlatformSpec { x: center.0, y: center.1, width: size, height: size, one_way: false, slope: 0.0 }
+}
+
+// The middle of where two boxes (around the whole of a ramp) overlap, if they do
+fn overlap(a: &PlatformSpec, b: &PlatformSpec) -> Option<(f32, f32)> {
+    let left = (a.x - a.width / 2.0).max(b.x - b.width / 2.0);
+    let right = (a.x + a.width / 2.0).min(b.x + b.width / 2.0);
+    let bottom = (a.y - a.half_extent_y()).max(b.y - b.half_extent_y());
+    let top = (a.y + a.half_extent_y()).min(b.y + b.half_extent_y());
+    (right - left > SLACK && top - bottom > SLACK).then_some(((left + right) / 2.0, (bottom + top) / 2.0))
+}
@@ -0,0 +1,72 @@
+// Frame comparison for the visual regression harness. Frames are compared
+// pixel by pixel: a pixel differs when any channel is further apart than the
+// channel tolerance, and a frame passes while few enough pixels differ, so
+// tiny rasterisation differences between GPUs don't fail a run.
+
+// Largest difference in one channel (0-255) that still counts as the same
+pub const CHANNEL_TOLERANCE: u8 = 8;
+// Share of the pixels that may differ before a frame fails
+pub const MAX_DIFFERING_FRACTION: f32 = 0.002;
+
+// An RGBA8 image, rows top to bottom
+#[derive(Clone, Debug, PartialEq)]
+pub struct Frame {
+    pub width: u32,
+    pub height: u32,
+    pub rgba: Vec<u8>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FrameDiff {
+    pub differing_pixels: usize,
+    pub total_pixels: usize,
+    // Largest channel difference anywhere in the frame
+    pub max_delta: u8,
+}
+
+impl FrameDiff {
+    pub fn differing_fraction(&self) -> f32 {
+        self.differing_pixels as f32 / self.total_pixels.max(1) as f32
+    }
+
+    pub fn passes(&self) -> bool {
+        self.differing_fraction() <= MAX_DIFFERING_FRACTION
+    }
+}
+
+fn pixel_delta(a: &[u8], b: &[u8]) -> u8 {
+    a.iter().zip(b).map(|(a, b)| a.abs_diff(*b)).max().unwrap_or(0)
+}
+
+// `None` when the frames aren't the same size
+pub fn compare_frames(golden: &Frame, actual: &Frame) -> Option<FrameDiff> {
+    if (golden.width, golden.height) != (actual.width, actual.height) || golden.rgba.len() != actual.rgba.len() {
+        return None;
+    }
+    let mut diff = FrameDiff { differing_pixels: 0, total_pixels: golden.rgba.len() / 4, max_delta: 0 };
+    for (golden, actual) in golden.rgba.chunks_exact(4).zip(actual.rgba.chunks_exact(4)) {
+        let delta = pixel_delta(golden, actual);
+        diff.max_delta = diff.max_delta.max(delta);
+        if delta > CHANNEL_TOLERANCE {
+            diff.differing_pixels += 1;
+        }
+    }
+    Some(diff)
+}
+
+// The golden dimmed, with the pixels that differ in `actual` painted red
+pub fn diff_frame(golden: &Frame, actual: &Frame) -> Frame {
+    let rgba = golden
+        .rgba
+        .chunks_exact(4)
+        .zip(actual.rgba.chunks_exact(4))
+        .flat_map(|(golden, actual)| {
+            if pixel_delta(golden, actual) > CHANNEL_TOLERANCE {
+                [255, 0, 0, 255]
+            } else {
+                [golden[0] / 3, golden[1] / 3, golden[2] / 3, 255]
+            }
+        })
+        .collect();
+    Frame { width: golden.width, height: golden.height, rgba }
+}
@@ -0,0 +1,113 @@
+// Wind zones and conveyor platforms. From the difficulty curve's
+// `wind_from_level` on, a level gets a few boxes of air blowing left or
+// right; a player inside one drifts with it on top of their own movement,
+// in the air or on the ground. From `conveyors_from_level` on, a few
+// platforms are conveyor belts that carry whoever stands on them along, like
+// a moving platform that stays put. Both are kept gentler than the player's
+// own run speed, so they can always be walked against and the jump checks
+// levels are generated with still hold.
+
+use crate::generation::PlatformSpec;
+use crate::physics::Body;
+use crate::rng::SeededRng;
+use crate::world::WorldBounds;
+use crate::zones::SafeZone;
+
+const WIND_WIDTH: (f32, f32) = (360.0, 600.0);
+const WIND_HEIGHT: (f32, f32) = (280.0, 480.0);
+// How fast wind pushes a player along, in pixels a second
+const WIND_PUSH: (f32, f32) = (60.0, 110.0);
+// Tries at fitting a wind zone in without overlapping another
+const WIND_ATTEMPTS: usize = 10;
+const CONVEYOR_SPEED: (f32, f32) = (70.0, 130.0);
+
+// A box of air blowing sideways, by its centre. `push` is the drift it gives,
+// negative blowing left.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Wind {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    pub push: f32,
+}
+
+impl Wind {
+    pub fn contains(&self, body: &Body) -> bool {
+        (body.x - self.x).abs() < (self.width + body.size) / 2.0 && (body.y - self.y).abs() < (self.height + body.size) / 2.0
+    }
+
+    fn overlaps(&self, other: &Wind) -> bool {
+        (self.x - other.x).abs() < (self.width + other.width) / 2.0 && (self.y - other.y).abs() < (self.height + other.height) / 2.0
+    }
+
+    // Whether any of the box is inside `zone`
+    pub fn reaches(&self, zone: &SafeZone) -> bool {
+        let nearest_x = zone.x.clamp(self.x - self.width / 2.0, self.x + self.width / 2.0);
+        let nearest_y = zone.y.clamp(self.y - self.height / 2.0, self.y + self.height / 2.0);
+        (nearest_x - zone.x).hypot(nearest_y - zone.y) < zone.radius
+    }
+}
+
+// A platform that's a conveyor belt. `speed` is how fast its top runs,
+// negative running left.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Conveyor {
+    // Index of the platform in its level
+    pub platform: usize,
+    pub speed: f32,
+}
+
+// Moves `body` with the wind of every zone it's in for `dt`
+pub fn blow(body: &mut Body, winds: &[Wind], dt: f32) {
+    body.x += winds.iter().filter(|wind| wind.contains(body)).map(|wind| wind.push).sum::<f32>() * dt;
+}
+
+// Moves `body`, standing on `conveyor`'s platform, along the belt for `dt`
+pub fn convey(body: &mut Body, conveyor: &Conveyor, dt: f32) {
+    body.x += conveyor.speed * dt;
+}
+
+// A random sign for a push or a belt
+fn direction(rng: &mut SeededRng) -> f32 {
+    if rng.below(2) == 0 {
+        -1.0
+    } else {
+        1.0
+    }
+}
+
+// Up to `count` wind zones somewhere in `bounds`, none overlapping another
+pub fn place_wind(seed: u64, count: usize, bounds: WorldBounds) -> Vec<Wind> {
+    let mut rng = SeededRng::new(seed.wrapping_mul(11));
+    let mut winds: Vec<Wind> = Vec::new();
+    for _ in 0..count {
+        for _ in 0..WIND_ATTEMPTS {
+            let width = rng.range(WIND_WIDTH.0, WIND_WIDTH.1).min(bounds.width);
+            let height = rng.range(WIND_HEIGHT.0, WIND_HEIGHT.1).min(bounds.height);
+            let x = rng.range(-bounds.half_width() + width / 2.0, bounds.half_width() - width / 2.0);
+            let y = rng.range(-bounds.half_height() + height / 2.0, bounds.half_height() - height / 2.0);
+            let push = direction(&mut rng) * rng.range(WIND_PUSH.0, WIND_PUSH.1);
+            let wind = Wind { x, y, width, height, push };
+            if !winds.iter().any(|other| other.overlaps(&wind)) {
+                winds.push(wind);
+                break;
+            }
+        }
+    }
+    winds
+}
+
+// Up to `count` conveyor platforms: never the starting platform (the first
+// one) or any platform listed in `keep_plain`
+pub fn place_conveyors(platforms: &[PlatformSpec], seed: u64, count: usize, keep_plain: &[usize]) -> Vec<Conveyor> {
+    let mut candidates: Vec<usize> = (1..platforms.len()).filter(|index| !keep_plain.contains(index)).collect();
+    let mut rng = SeededRng::new(seed.wrapping_mul(13));
+    let mut conveyors = Vec::new();
+    while conveyors.len() < count && !candidates.is_empty() {
+        let platform = candidates.swap_remove(rng.below(candidates.len()));
+        let speed = direction(&mut rng) * rng.range(CONVEYOR_SPEED.0, CONVEYOR_SPEED.1);
+        conveyors.push(Conveyor { platform, speed });
+    }
+    conveyors
+}
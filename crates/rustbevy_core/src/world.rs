@@ -0,0 +1,41 @@
+// The area a level is laid out in and the player is kept inside
+
+use crate::config::{WINDOW_HEIGHT, WINDOW_WIDTH, WORLD_HEIGHT, WORLD_WIDTH};
+
+// A rectangle centred on the origin
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct WorldBounds {
+    pub width: f32,
+    pub height: f32,
+}
+
+impl WorldBounds {
+    // One window's worth, for the party games where everyone shares the screen
+    pub const SCREEN: Self = Self { width: WINDOW_WIDTH, height: WINDOW_HEIGHT };
+    // Classic levels, several screens wide and tall
+    pub const CLASSIC: Self = Self { width: WORLD_WIDTH, height: WORLD_HEIGHT };
+
+    pub fn half_width(self) -> f32 {
+        self.width / 2.0
+    }
+
+    pub fn half_height(self) -> f32 {
+        self.height / 2.0
+    }
+
+    // How many windows of area this covers
+    pub fn screens(self) -> f32 {
+        (self.width * self.height) / (WINDOW_WIDTH * WINDOW_HEIGHT)
+    }
+
+    // Keeps something `size` wide inside the left and right edges
+    pub fn clamp_x(self, x: f32, size: f32) -> f32 {
+        let limit = (self.half_width() - size / 2.0).max(0.0);
+        x.clamp(-limit, limit)
+    }
+
+    // Below the bottom edge, i.e. fallen out of the level
+    pub fn below(self, y: f32) -> bool {
+        y < -self.half_height()
+    }
+}
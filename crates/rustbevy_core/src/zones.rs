@@ -0,0 +1,93 @@
+// Enemy spawn zones and safe zones.
+//
+// Enemies only start inside a level's spawn zones: generated levels get one
+// per screen-sized part of the level away from the start, and a hand-made
+// level may list its own. Every level also keeps a safe zone around each spot
+// the player (re)spawns at, its spawn point and its checkpoints. No enemy,
+// crumbling or trap platform reaches within `SAFE_ZONE_RADIUS` of one, so
+// nothing the difficulty curve adds waits on top of a respawning player.
+
+use crate::checkpoint::respawn_point;
+use crate::config::{WINDOW_HEIGHT, WINDOW_WIDTH};
+use crate::generation::{PlatformSpec, STARTING_PLATFORM};
+use crate::world::WorldBounds;
+
+pub const SAFE_ZONE_RADIUS: f32 = 240.0;
+
+// A rectangle, centred on `x`, `y` like a platform
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Zone {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl Zone {
+    pub fn contains(&self, position: (f32, f32)) -> bool {
+        (position.0 - self.x).abs() <= self.width / 2.0 && (position.1 - self.y).abs() <= self.height / 2.0
+    }
+}
+
+// A circle around a respawn point
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SafeZone {
+    pub x: f32,
+    pub y: f32,
+    pub radius: f32,
+}
+
+impl SafeZone {
+    pub fn around(position: (f32, f32)) -> Self {
+        Self { x: position.0, y: position.1, radius: SAFE_ZONE_RADIUS }
+    }
+
+    // Whether any of the level stretch from `left` to `right` at height `y`
+    // is inside the zone
+    pub fn reaches(&self, left: f32, right: f32, y: f32) -> bool {
+        let nearest_x = self.x.clamp(left, right);
+        (nearest_x - self.x).hypot(y - self.y) < self.radius
+    }
+
+    pub fn touches(&self, platform: &PlatformSpec) -> bool {
+        self.reaches(platform.x - platform.width / 2.0, platform.x + platform.width / 2.0, platform.y)
+    }
+}
+
+// The safe zones of a level whose player spawns at `spawn`, with checkpoint
+// flags standing at `checkpoints`
+pub fn safe_zones(spawn: (f32, f32), checkpoints: &[(f32, f32)]) -> Vec<SafeZone> {
+    std::iter::once(spawn).chain(checkpoints.iter().map(|&checkpoint| respawn_point(checkpoint))).map(SafeZone::around).collect()
+}
+
+// Whether a hazard on `platform` would be clear of every zone in `safe`
+pub fn clear_of(platform: &PlatformSpec, safe: &[SafeZone]) -> bool {
+    !safe.iter().any(|zone| zone.touches(platform))
+}
+
+// Enemy spawn zones for a generated level: each screen-sized part of `bounds`
+// without the starting platform in it
+pub fn enemy_spawn_zones(bounds: WorldBounds) -> Vec<Zone> {
+    let columns = (bounds.width / WINDOW_WIDTH).round().max(1.0) as usize;
+    let rows = (bounds.height / WINDOW_HEIGHT).round().max(1.0) as usize;
+    let (width, height) = (bounds.width / columns as f32, bounds.height / rows as f32);
+    let mut zones = Vec::new();
+    for row in 0..rows {
+        for column in 0..columns {
+            let zone = Zone {
+                x: -bounds.half_width() + (column as f32 + 0.5) * width,
+                y: -bounds.half_height() + (row as f32 + 0.5) * height,
+                width,
+                height,
+            };
+            if !zone.contains((STARTING_PLATFORM.x, STARTING_PLATFORM.y)) {
+                zones.push(zone);
+            }
+        }
+    }
+    // A one-screen level has nowhere else for them
+    if zones.is_empty() {
+        zones.push(Zone { x: 0.0, y: 0.0, width: bounds.width, height: bounds.height });
+    }
+    zones
+}
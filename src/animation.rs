@@ -0,0 +1,148 @@
+// Player sprite-sheet animation.
+//
+// `assets/player_sheet.png` is one row of 32x32 frames: two idle frames, four
+// running, one jumping and one falling. The sheet is white and grey so the
+// sprite's color still tints it (blue for Classic, the slot colors in party
+// games). Every player gets the sheet when spawned with an `AnimationState`;
+// `animate_player` picks the state from the player's velocity and whether
+// they're on the ground, steps through its frames on a timer and flips the
+// sprite to face the way they're moving. A player standing still out over an
+// edge (the edge sensor in `rustbevy_core::physics`) teeters, flapping
+// between the jump and fall frames.
+
+use std::ops::Range;
+
+use bevy::prelude::*;
+use bevy::render::texture::{ImageLoaderSettings, ImageSampler};
+use crate::prelude::*;
+use rustbevy_core::physics;
+
+use crate::loading::LoadingAssets;
+use crate::physics::{player_body, solid_platform};
+
+pub struct AnimationPlugin;
+
+impl Plugin for AnimationPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, load_player_sheet)
+            .add_systems(Update, (
+                attach_player_sheet,
+                animate_player.run_if(in_state(AppState::Playing)),
+            ).chain());
+    }
+}
+
+const PLAYER_SHEET: &str = "player_sheet.png";
+const FRAME_SIZE: u32 = 32;
+const FRAME_COUNT: u32 = 8;
+// Slower than this counts as standing still, for running and facing
+const MIN_RUN_SPEED: f32 = 20.0;
+
+// The sheet frames of each animation
+fn frames(state: AnimationState) -> Range<usize> {
+    match state {
+        AnimationState::Idle => 0..2,
+        AnimationState::Run => 2..6,
+        AnimationState::Jump => 6..7,
+        AnimationState::Fall => 7..8,
+        AnimationState::Teeter => 6..8,
+    }
+}
+
+fn frame_seconds(state: AnimationState) -> f32 {
+    match state {
+        AnimationState::Idle => 0.5,
+        AnimationState::Run => 0.09,
+        AnimationState::Jump | AnimationState::Fall => 1.0,
+        AnimationState::Teeter => 0.15,
+    }
+}
+
+fn state_for(velocity: &Velocity, grounded: bool, over_edge: bool) -> AnimationState {
+    match grounded {
+        false if velocity.y > 0.0 => AnimationState::Jump,
+        false => AnimationState::Fall,
+        true if velocity.x.abs() >= MIN_RUN_SPEED => AnimationState::Run,
+        true if over_edge => AnimationState::Teeter,
+        true => AnimationState::Idle,
+    }
+}
+
+#[derive(Resource)]
+struct PlayerSheet {
+    image: Handle<Image>,
+    layout: Handle<TextureAtlasLayout>,
+}
+
+// The frame a player's animation is on
+#[derive(Component)]
+struct AnimationFrame {
+    frame: usize,
+    timer: Timer,
+}
+
+fn load_player_sheet(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut layouts: ResMut<Assets<TextureAtlasLayout>>,
+    mut loading: ResMut<LoadingAssets>,
+) {
+    // Pixel art stays crisp when drawn bigger than the sheet
+    let image = asset_server.load_with_settings(PLAYER_SHEET, |settings: &mut ImageLoaderSettings| {
+        settings.sampler = ImageSampler::nearest();
+    });
+    loading.track(image.clone());
+    let layout = layouts.add(TextureAtlasLayout::from_grid(UVec2::splat(FRAME_SIZE), FRAME_COUNT, 1, None, None));
+    commands.insert_resource(PlayerSheet { image, layout });
+}
+
+fn attach_player_sheet(
+    mut commands: Commands,
+    sheet: Res<PlayerSheet>,
+    player_query: Query<(Entity, &AnimationState), Added<AnimationState>>,
+) {
+    for (entity, state) in player_query.iter() {
+        commands.entity(entity).insert((
+            sheet.image.clone(),
+            TextureAtlas { layout: sheet.layout.clone(), index: frames(*state).start },
+            AnimationFrame {
+                frame: 0,
+                timer: Timer::from_seconds(frame_seconds(*state), TimerMode::Repeating),
+            },
+        ));
+    }
+}
+
+fn animate_player(
+    time: Res<Time>,
+    mut player_query: Query<(&Transform, &Velocity, &Grounded, &Collider, &mut AnimationState, &mut AnimationFrame, &mut TextureAtlas, &mut Sprite), With<Player>>,
+    platform_query: Query<(&Transform, &Platform, Has<OneWay>, Option<&Crumbling>, Option<&Trap>), Without<Player>>,
+) {
+    let platforms: Vec<_> = platform_query
+        .iter()
+        .filter_map(|(transform, platform, one_way, crumbling, trap)| solid_platform(transform, platform, one_way, crumbling, trap))
+        .collect();
+    for (transform, velocity, grounded, collider, mut state, mut animation, mut atlas, mut sprite) in player_query.iter_mut() {
+        let over_edge = physics::over_edge(&player_body(transform, velocity, grounded, collider), &platforms);
+        let next = state_for(velocity, grounded.0, over_edge);
+        if *state != next {
+            *state = next;
+            animation.frame = 0;
+            animation.timer = Timer::from_seconds(frame_seconds(next), TimerMode::Repeating);
+        } else if animation.timer.tick(time.delta()).just_finished() {
+            animation.frame = (animation.frame + 1) % frames(*state).len();
+        }
+        let index = frames(*state).start + animation.frame;
+        if atlas.index != index {
+            atlas.index = index;
+        }
+
+        // The sheet faces right; standing still keeps the last facing
+        if velocity.x.abs() >= MIN_RUN_SPEED {
+            let facing_left = velocity.x < 0.0;
+            if sprite.flip_x != facing_left {
+                sprite.flip_x = facing_left;
+            }
+        }
+    }
+}
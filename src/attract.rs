@@ -0,0 +1,184 @@
+// Main menu backdrop ("attract mode").
+//
+// Behind the menu a generated level slowly scrolls past, with a far layer of
+// stars and a middle layer of hills moving slower for a parallax effect, while
+// a bot hops around the level. The star and hill layers are left out on the
+// low graphics preset. Every backdrop entity is tagged `MainMenuUI`, so
+// it goes away together with the rest of the menu.
+
+use bevy::prelude::*;
+use crate::prelude::*;
+use rustbevy_core::bot::Bot;
+use rustbevy_core::config::{PLAYER_SIZE, WINDOW_HEIGHT, WINDOW_WIDTH};
+use rustbevy_core::difficulty;
+use rustbevy_core::generation::{generate_platform_layout, PlatformSpec};
+use rustbevy_core::physics::{self, Body, JumpTimers, Tuning};
+use rustbevy_core::rng::SeededRng;
+
+use crate::menu::MainMenuUI;
+
+pub struct AttractPlugin;
+
+impl Plugin for AttractPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AttractLevel>()
+            .add_systems(Update, (scroll_parallax, drive_attract_bot).chain().run_if(in_state(AppState::MainMenu)));
+    }
+}
+
+const SCROLL_SPEED: f32 = 40.0; // Pixels per second for the level layer
+const STAR_PARALLAX: f32 = 0.2;
+const HILL_PARALLAX: f32 = 0.5;
+const LEVEL_PARALLAX: f32 = 1.0;
+// Elements leave the screen completely before wrapping round to the other side
+const WRAP_MARGIN: f32 = 200.0;
+const MAX_BOT_STEP: f32 = 1.0 / 30.0;
+
+// Horizontally scrolling backdrop element, placed at `base_x` when the scroll is 0
+#[derive(Component)]
+pub struct Parallax {
+    base_x: f32,
+    factor: f32,
+}
+
+#[derive(Component)]
+pub struct AttractBot {
+    bot: Bot,
+    body: Body,
+    jumps: JumpTimers,
+    jump_held: bool,
+}
+
+// The level the attract bot plays on, and how far the backdrop has scrolled
+#[derive(Resource, Default)]
+pub struct AttractLevel {
+    platforms: Vec<PlatformSpec>,
+    scroll: f32,
+}
+
+// Screen x of something at `x` in a layer scrolled by `offset`
+fn wrapped_x(x: f32, offset: f32) -> f32 {
+    let period = WINDOW_WIDTH + 2.0 * WRAP_MARGIN;
+    (x - offset + WINDOW_WIDTH / 2.0 + WRAP_MARGIN).rem_euclid(period) - WINDOW_WIDTH / 2.0 - WRAP_MARGIN
+}
+
+// `priority` is how much the sprite is missed if the decoration budget is tight
+fn spawn_backdrop_sprite(commands: &mut Commands, color: Color, size: Vec2, position: Vec3, factor: f32, priority: u8) {
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color,
+                custom_size: Some(size),
+                ..default()
+            },
+            transform: Transform::from_translation(position),
+            ..default()
+        },
+        Parallax { base_x: position.x, factor },
+        Budgeted::new(BudgetCategory::Decorations, priority),
+        MainMenuUI,
+    ));
+}
+
+pub fn spawn_menu_backdrop(commands: &mut Commands, seed: u64) {
+    let mut rng = SeededRng::new(seed);
+    let mut unit = || rng.fraction();
+
+    // Far layer: stars
+    for _ in 0..40 {
+        let x = (unit() - 0.5) * (WINDOW_WIDTH + 2.0 * WRAP_MARGIN);
+        let y = (unit() - 0.3) * WINDOW_HEIGHT * 0.7;
+        let size = 2.0 + unit() * 3.0;
+        spawn_backdrop_sprite(commands, Color::srgba(1.0, 1.0, 0.9, 0.5), Vec2::splat(size), Vec3::new(x, y, -30.0), STAR_PARALLAX, 0);
+    }
+
+    // Middle layer: hills along the bottom of the screen
+    for index in 0..7 {
+        let width = 220.0 + unit() * 160.0;
+        let height = 120.0 + unit() * 180.0;
+        let x = -WINDOW_WIDTH / 2.0 - WRAP_MARGIN + index as f32 * (WINDOW_WIDTH + 2.0 * WRAP_MARGIN) / 7.0;
+        let y = -WINDOW_HEIGHT / 2.0 + height / 2.0;
+        spawn_backdrop_sprite(commands, Color::srgba(0.15, 0.3, 0.25, 0.8), Vec2::new(width, height), Vec3::new(x, y, -20.0), HILL_PARALLAX, 1);
+    }
+
+    // Level layer: a dimmed generated level with the bot on it
+    let platforms = generate_platform_layout(seed, &difficulty::DifficultyCurve::default().layout(0.0), WorldBounds::SCREEN);
+    for platform in &platforms {
+        spawn_backdrop_sprite(
+            commands,
+            Color::srgba(0.5, 0.5, 0.5, 0.45),
+            Vec2::new(platform.width, platform.height),
+            Vec3::new(platform.x, platform.y, -10.0),
+            LEVEL_PARALLAX,
+            2,
+        );
+    }
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: Color::srgba(0.0, 0.5, 1.0, 0.6),
+                custom_size: Some(Vec2::splat(PLAYER_SIZE)),
+                ..default()
+            },
+            transform: Transform::from_translation(Vec3::new(0.0, 0.0, -9.0)),
+            ..default()
+        },
+        AttractBot {
+            bot: Bot::new(seed.wrapping_add(7)),
+            body: Body::at_spawn(),
+            jumps: JumpTimers::default(),
+            jump_held: false,
+        },
+        MainMenuUI,
+    ));
+    commands.insert_resource(AttractLevel { platforms, scroll: 0.0 });
+}
+
+pub fn scroll_parallax(
+    time: Res<Time>,
+    graphics: Res<GraphicsQuality>,
+    mut attract_level: ResMut<AttractLevel>,
+    mut layer_query: Query<(&Parallax, &mut Transform, &mut Visibility)>,
+) {
+    attract_level.scroll += SCROLL_SPEED * time.delta_seconds();
+    for (parallax, mut transform, mut visibility) in layer_query.iter_mut() {
+        // The low graphics preset keeps only the level layer
+        let shown = parallax.factor == LEVEL_PARALLAX || graphics.parallax();
+        let wanted = if shown { Visibility::Inherited } else { Visibility::Hidden };
+        if *visibility != wanted {
+            *visibility = wanted;
+        }
+        if shown {
+            transform.translation.x = wrapped_x(parallax.base_x, attract_level.scroll * parallax.factor);
+        }
+    }
+}
+
+pub fn drive_attract_bot(
+    time: Res<Time>,
+    attract_level: Res<AttractLevel>,
+    mut bot_query: Query<(&mut AttractBot, &mut Transform)>,
+) {
+    let dt = time.delta_seconds().min(MAX_BOT_STEP);
+    for (mut attract_bot, mut transform) in bot_query.iter_mut() {
+        let attract_bot = &mut *attract_bot;
+        let input = attract_bot.bot.think(&attract_bot.body, &attract_level.platforms, dt);
+        let jump_pressed = input.jump && !attract_bot.jump_held;
+        attract_bot.jump_held = input.jump;
+
+        let body = &mut attract_bot.body;
+        let tuning = Tuning::default();
+        physics::apply_input(body, &mut attract_bot.jumps, input.horizontal(), jump_pressed, input.down, dt, &tuning);
+        physics::apply_gravity(body, &attract_bot.jumps, dt, &tuning);
+        physics::integrate(body, dt);
+        physics::resolve_platform_collisions(body, &attract_level.platforms, &attract_bot.jumps, dt);
+        physics::keep_in_world(body, WorldBounds::SCREEN);
+        if physics::fell_out_of_world(body, WorldBounds::SCREEN) {
+            *body = Body::at_spawn();
+            attract_bot.jumps = JumpTimers::default();
+        }
+
+        transform.translation.x = wrapped_x(body.x, attract_level.scroll * LEVEL_PARALLAX);
+        transform.translation.y = body.y;
+    }
+}
@@ -0,0 +1,44 @@
+// Entity budgets.
+//
+// Particles, enemies and menu backdrop decorations are spawned with
+// `Budgeted`. Each frame, once everything has spawned, any category over its
+// `EntityBudget` cap is trimmed back to it (see `rustbevy_core::budget` for
+// which entities go), so worst-case frame times stay bounded however many
+// effects pile up.
+
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use crate::prelude::*;
+use rustbevy_core::budget::over_budget;
+
+pub struct BudgetPlugin;
+
+impl Plugin for BudgetPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<EntityBudget>().add_systems(PostUpdate, enforce_budgets);
+    }
+}
+
+fn enforce_budgets(
+    mut commands: Commands,
+    budget: Res<EntityBudget>,
+    mut next_spawn_order: Local<u64>,
+    mut budgeted_query: Query<(Entity, &mut Budgeted)>,
+) {
+    let mut categories: HashMap<BudgetCategory, Vec<(Entity, (u8, u64))>> = HashMap::new();
+    for (entity, mut budgeted) in budgeted_query.iter_mut() {
+        if budgeted.is_added() {
+            *next_spawn_order += 1;
+            budgeted.spawn_order = *next_spawn_order;
+        }
+        categories.entry(budgeted.category).or_default().push((entity, (budgeted.priority, budgeted.spawn_order)));
+    }
+
+    for (category, members) in categories {
+        let entries: Vec<(u8, u64)> = members.iter().map(|(_, entry)| *entry).collect();
+        for index in over_budget(&entries, budget.0.cap(category)) {
+            commands.entity(members[index].0).despawn_recursive();
+        }
+    }
+}
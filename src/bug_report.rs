@@ -0,0 +1,211 @@
+// Bug report capture.
+//
+// F12 (anywhere but the main menu, where it belongs to the assist menu) packs
+// what a bug report needs into a zip in the `reports` folder of the save
+// directory: a screenshot, the last 30 seconds of the event log (screens,
+// runs, fruit, levels, deaths and errors) and of the keys and buttons pressed,
+// what the game was doing (the summary a crash report gets, run seed
+// included) and the saved settings and key bindings. A toast says where the
+// zip went. The screenshot is taken after the frame is drawn, so the zip is
+// written a frame or two after the key.
+
+use std::collections::VecDeque;
+use std::io::{Cursor, Write};
+use std::path::Path;
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::Mutex;
+
+use bevy::prelude::*;
+use bevy::render::view::screenshot::ScreenshotManager;
+use bevy::state::state::StateTransitionEvent;
+use bevy::window::PrimaryWindow;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+use crate::prelude::*;
+
+use crate::capture::capture_key;
+use crate::crash::crash_context;
+use crate::game::{save_path, unix_seconds};
+use crate::ui::Toasts;
+
+pub struct BugReportPlugin;
+
+impl Plugin for BugReportPlugin {
+    fn build(&self, app: &mut App) {
+        let (sender, receiver) = channel();
+        app.init_resource::<BugReportLog>()
+            .insert_resource(ReportScreenshots { sender, receiver: Mutex::new(receiver), pending: None })
+            .add_systems(Update, (
+                (log_events, log_inputs),
+                capture_bug_report.run_if(not(in_state(AppState::MainMenu))),
+                write_bug_report,
+            ).chain());
+    }
+}
+
+const REPORT_FOLDER: &str = "reports";
+// How far back the event log and the inputs go
+const LOG_SECONDS: f32 = 30.0;
+// Saved files copied into the report, when they exist
+const CONFIG_FILES: [&str; 2] = ["settings.txt", "keybindings.txt"];
+
+// The last `LOG_SECONDS` of what happened and what was pressed, each entry
+// stamped with the real time it happened at
+#[derive(Resource, Default)]
+struct BugReportLog {
+    events: VecDeque<(f32, String)>,
+    inputs: VecDeque<(f32, String)>,
+}
+
+impl BugReportLog {
+    fn push(entries: &mut VecDeque<(f32, String)>, now: f32, entry: String) {
+        entries.push_back((now, entry));
+        while entries.front().is_some_and(|(time, _)| now - time > LOG_SECONDS) {
+            entries.pop_front();
+        }
+    }
+
+    // The entries as lines, each with how many seconds before `now` it happened
+    fn lines(entries: &VecDeque<(f32, String)>, now: f32) -> String {
+        let lines: Vec<String> = entries
+            .iter()
+            .filter(|(time, _)| now - time <= LOG_SECONDS)
+            .map(|(time, entry)| format!("-{:5.2}s  {}", now - time, entry))
+            .collect();
+        if lines.is_empty() { "(nothing)\n".to_string() } else { lines.join("\n") + "\n" }
+    }
+}
+
+// A report's text files, waiting for its screenshot
+#[derive(Resource)]
+struct ReportScreenshots {
+    // The screenshot, or None when one couldn't be taken
+    sender: Sender<Option<Image>>,
+    receiver: Mutex<Receiver<Option<Image>>>,
+    pending: Option<Vec<(&'static str, Vec<u8>)>>,
+}
+
+fn log_events(
+    time: Res<Time<Real>>,
+    mut log: ResMut<BugReportLog>,
+    mut screen_events: EventReader<StateTransitionEvent<AppState>>,
+    mut reset_events: EventReader<GameResetEvent>,
+    mut fruit_events: EventReader<FruitCollectedEvent>,
+    mut level_events: EventReader<LevelCompletedEvent>,
+    mut death_events: EventReader<PlayerDiedEvent>,
+    mut error_events: EventReader<RuntimeErrorEvent>,
+) {
+    let now = time.elapsed_seconds();
+    let mut entries = Vec::new();
+    entries.extend(screen_events.read().map(|event| format!("Screen: {:?} -> {:?}", event.exited, event.entered)));
+    entries.extend(reset_events.read().map(|event| format!("New {:?} run", event.mode)));
+    entries.extend(fruit_events.read().map(|event| format!("{:?} collected at ({:.0}, {:.0})", event.kind, event.position.x, event.position.y)));
+    entries.extend(level_events.read().map(|event| format!("Level {} reached{}", event.level, if event.skipped { " (skipped)" } else { "" })));
+    entries.extend(death_events.read().map(|event| format!("Player died, {} lives left", event.lives_left)));
+    entries.extend(error_events.read().map(|event| format!("Error: {} ({})", event.summary, event.details)));
+    for entry in entries {
+        BugReportLog::push(&mut log.events, now, entry);
+    }
+}
+
+fn log_inputs(
+    time: Res<Time<Real>>,
+    mut log: ResMut<BugReportLog>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    gamepad_buttons: Res<ButtonInput<GamepadButton>>,
+) {
+    let now = time.elapsed_seconds();
+    let mut entries = Vec::new();
+    entries.extend(keyboard_input.get_just_pressed().map(|key| format!("{:?} pressed", key)));
+    entries.extend(keyboard_input.get_just_released().map(|key| format!("{:?} released", key)));
+    entries.extend(gamepad_buttons.get_just_pressed().map(|button| format!("{:?} pressed on gamepad {}", button.button_type, button.gamepad.id)));
+    entries.extend(gamepad_buttons.get_just_released().map(|button| format!("{:?} released on gamepad {}", button.button_type, button.gamepad.id)));
+    for entry in entries {
+        BugReportLog::push(&mut log.inputs, now, entry);
+    }
+}
+
+// F12 gathers the report's text and asks for a screenshot to go with it
+fn capture_bug_report(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    time: Res<Time<Real>>,
+    log: Res<BugReportLog>,
+    mut screenshots: ResMut<ReportScreenshots>,
+    window_query: Query<Entity, With<PrimaryWindow>>,
+    mut screenshot_manager: ResMut<ScreenshotManager>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::F12) || capture_key(&keyboard_input) || screenshots.pending.is_some() {
+        return;
+    }
+    let now = time.elapsed_seconds();
+    let game = format!("Bevy Platformer bug report\nVersion: {}\n\nGame:\n{}\n", env!("CARGO_PKG_VERSION"), crash_context());
+    let mut files: Vec<(&'static str, Vec<u8>)> = vec![
+        ("game.txt", game.into_bytes()),
+        ("events.txt", BugReportLog::lines(&log.events, now).into_bytes()),
+        ("inputs.txt", BugReportLog::lines(&log.inputs, now).into_bytes()),
+    ];
+    for file in CONFIG_FILES {
+        if let Ok(contents) = std::fs::read(save_path(file)) {
+            files.push((file, contents));
+        }
+    }
+    screenshots.pending = Some(files);
+
+    // Without a window to capture, the report is written without a screenshot
+    let sender = screenshots.sender.clone();
+    let taken = window_query.get_single().is_ok_and(|window| {
+        screenshot_manager.take_screenshot(window, move |image| {
+            let _ = sender.send(Some(image));
+        })
+        .is_ok()
+    });
+    if !taken {
+        let _ = screenshots.sender.send(None);
+    }
+}
+
+// Writes the report once its screenshot is in
+fn write_bug_report(mut screenshots: ResMut<ReportScreenshots>, mut toasts: ResMut<Toasts>, mut errors: EventWriter<RuntimeErrorEvent>) {
+    if screenshots.pending.is_none() {
+        return;
+    }
+    let Some(image) = screenshots.receiver.lock().ok().and_then(|receiver| receiver.try_recv().ok()) else {
+        return;
+    };
+    let mut files = screenshots.pending.take().unwrap_or_default();
+    match image.map(encode_png) {
+        Some(Ok(png)) => files.push(("screenshot.png", png)),
+        Some(Err(error)) => warn!("Bug report screenshot left out: {}", error),
+        None => {}
+    }
+    let seconds = unix_seconds();
+    let path = save_path(REPORT_FOLDER).join(format!("bug-report-{}.zip", seconds));
+    match write_zip(&path, &files) {
+        Ok(()) => toasts.push(format!("Bug report saved to {}", path.display())),
+        Err(error) => {
+            errors.send(RuntimeErrorEvent::new("Could not save the bug report", format!("{}: {}", path.display(), error)));
+        }
+    }
+}
+
+fn encode_png(image: Image) -> Result<Vec<u8>, String> {
+    let rgba = image.try_into_dynamic().map_err(|error| error.to_string())?.to_rgba8();
+    let mut png = Cursor::new(Vec::new());
+    rgba.write_to(&mut png, image::ImageFormat::Png).map_err(|error| error.to_string())?;
+    Ok(png.into_inner())
+}
+
+// The screenshot is already compressed and the text is small, so the files
+// are stored as they are
+fn write_zip(path: &Path, files: &[(&'static str, Vec<u8>)]) -> Result<(), String> {
+    path.parent().map_or(Ok(()), std::fs::create_dir_all).map_err(|error| error.to_string())?;
+    let file = std::fs::File::create(path).map_err(|error| error.to_string())?;
+    let mut zip = ZipWriter::new(file);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+    for (name, contents) in files {
+        zip.start_file(*name, options).map_err(|error| error.to_string())?;
+        zip.write_all(contents).map_err(|error| error.to_string())?;
+    }
+    zip.finish().map_err(|error| error.to_string())?;
+    Ok(())
+}
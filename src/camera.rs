@@ -0,0 +1,500 @@
+// Camera: setup, following the player, mirror mode and the spectator camera.
+//
+// Both cameras show the same WINDOW_WIDTH by WINDOW_HEIGHT view whatever size
+// the window is: the view is scaled to fit the window and centred, with bars
+// on the sides the window has to spare. Levels, the HUD and the menus are laid
+// out in that view, so resizing or maximizing the window only changes how big
+// it's drawn.
+//
+// Classic levels are bigger than the window, so there are two cameras. The
+// world camera (`MainCamera`) follows the player through the level with a
+// dead zone and some look-ahead in the direction they're running, and never
+// shows anything past the level's edges. In a co-op run it follows the point
+// between the two players and zooms out as they move apart. The HUD camera (`HudCamera`) stays put
+// and draws all world text and `ScreenSpace` sprites on top, so the HUD, menus
+// and toasts keep their places on screen. Text that should move with the world
+// is marked `WorldSpace`.
+//
+// Anything can shift the world camera for a while through its `CameraOffsets`;
+// the camera eases to the shift and back once it's cleared. Holding Down (or
+// Up) while standing in a single-player Classic run uses it to peek below (or
+// above) before dropping. What a camera is doing (the player it follows, where
+// it's headed, its offsets, its zoom) is kept in components on the camera
+// itself rather than in resources, and every camera system goes over all the
+// world cameras, so a second world camera, one per player in a split screen,
+// would move, zoom and be shifted on its own.
+//
+// The graphics preset is applied here too: anti-aliasing is off on Low, and
+// Fancy renders both cameras in HDR with bloom on the world camera.
+
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use bevy::core_pipeline::bloom::BloomSettings;
+use bevy::input::mouse::{MouseMotion, MouseWheel};
+use bevy::render::camera::{ClearColorConfig, ScalingMode, Viewport};
+use bevy::render::view::RenderLayers;
+use bevy::window::PrimaryWindow;
+use crate::prelude::*;
+use rustbevy_core::config::{PLAYER_SPEED, WINDOW_HEIGHT, WINDOW_WIDTH};
+
+use crate::gamepad::GamepadInput;
+use crate::overview::Overview;
+use crate::replay::ReplayPlayback;
+
+pub struct CameraPlugin;
+
+impl Plugin for CameraPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Spectator>()
+            .add_systems(Startup, setup_camera)
+            .add_systems(Update, (
+                (peek_camera, follow_player).chain(),
+                fit_view_to_window,
+                apply_mirror_mode,
+                apply_graphics_quality,
+                toggle_spectator,
+                spectator_camera_controls.run_if(spectating),
+            ))
+            // After everything has spawned this frame, so nothing shows up a
+            // frame early on the wrong camera
+            .add_systems(PostUpdate, move_to_hud_layer);
+    }
+}
+
+// Render layer of everything the HUD camera draws
+pub const HUD_LAYER: usize = 1;
+
+// Follow camera tuning
+// The player moves this far from the camera's focus before it follows
+const DEAD_ZONE: Vec2 = Vec2::new(120.0, 80.0);
+// How far ahead of a player running at full speed the camera looks
+const LOOK_AHEAD: f32 = 160.0;
+// How quickly the camera catches up (higher is snappier)
+const FOLLOW_SHARPNESS: f32 = 6.0;
+const LOOK_AHEAD_SHARPNESS: f32 = 2.0;
+// Further than this from where it should be (a respawn, a new level) the camera jumps
+const SNAP_DISTANCE: f32 = WINDOW_WIDTH;
+// Room kept between co-op players and the edges of the view
+const COOP_MARGIN: Vec2 = Vec2::new(160.0, 120.0);
+// How far out the camera zooms to keep co-op players on screen
+const COOP_MAX_ZOOM: f32 = 1.75;
+const ZOOM_SHARPNESS: f32 = 3.0;
+// How quickly the camera eases to a temporary offset and back
+const OFFSET_SHARPNESS: f32 = 5.0;
+
+// Camera peek tuning
+// How far holding Down or Up on the ground pans the camera
+const PEEK_DISTANCE: f32 = 240.0;
+// How long Down or Up is held before the camera pans, so a quick tap (a drop
+// through a platform, grabbing a ladder) doesn't nudge it
+const PEEK_DELAY: f32 = 0.3;
+
+// Spectator camera tuning
+const SPECTATOR_PAN_SPEED: f32 = 600.0;
+const SPECTATOR_ZOOM_STEP: f32 = 0.1;
+const SPECTATOR_MIN_ZOOM: f32 = 0.25;
+const SPECTATOR_MAX_ZOOM: f32 = 4.0;
+
+// Debug free-fly camera; while active, WASD/arrows drive the camera instead of the player
+#[derive(Resource, Default)]
+pub struct Spectator {
+    active: bool,
+}
+
+pub fn spectating(spectator: Res<Spectator>) -> bool {
+    spectator.active
+}
+
+#[derive(Component)]
+struct SpectatorUI;
+
+// Where a world camera is headed
+#[derive(Component, Default)]
+pub struct CameraFollow {
+    // The player this camera follows, or None to frame every player (the one
+    // camera of a single-screen run)
+    pub target: Option<Entity>,
+    // The point the dead zone is centred on
+    focus: Vec2,
+    look_ahead: f32,
+    // Eases towards the sum of the `CameraOffsets`
+    offset: Vec2,
+    // Seconds Down or Up has been held by the player peeking with this camera
+    peek_held: f32,
+}
+
+impl CameraFollow {
+    fn follows(&self, player: Entity) -> bool {
+        self.target.is_none_or(|target| target == player)
+    }
+}
+
+// Temporary shifts of a followed camera, by who asked for them, so several
+// can be held at once. The camera eases to their sum, still kept inside the
+// level, and back once they're cleared.
+#[derive(Component, Default)]
+pub struct CameraOffsets(HashMap<&'static str, Vec2>);
+
+impl CameraOffsets {
+    pub fn set(&mut self, source: &'static str, offset: Vec2) {
+        self.0.insert(source, offset);
+    }
+
+    pub fn clear(&mut self, source: &'static str) {
+        self.0.remove(source);
+    }
+
+    fn total(&self) -> Vec2 {
+        self.0.values().sum()
+    }
+}
+
+fn setup_camera(mut commands: Commands) {
+    commands.spawn((
+        Camera2dBundle {
+            projection: fixed_view(),
+            ..default()
+        },
+        MainCamera,
+        CameraFollow::default(),
+        CameraOffsets::default(),
+    ));
+    commands.spawn((
+        Camera2dBundle {
+            camera: Camera {
+                order: 1,
+                clear_color: ClearColorConfig::None,
+                ..default()
+            },
+            projection: fixed_view(),
+            ..default()
+        },
+        RenderLayers::layer(HUD_LAYER),
+        HudCamera,
+    ));
+}
+
+// A window's worth of view at the regular zoom, however big the window is
+fn fixed_view() -> OrthographicProjection {
+    OrthographicProjection {
+        scaling_mode: ScalingMode::Fixed { width: WINDOW_WIDTH, height: WINDOW_HEIGHT },
+        ..Camera2dBundle::default().projection
+    }
+}
+
+// Fits the view to the window: as big as it goes while keeping its shape,
+// centred. The bars around it are left in the clear colour.
+pub fn fit_view_to_window(
+    window_query: Query<&Window, (With<PrimaryWindow>, Changed<Window>)>,
+    mut camera_query: Query<&mut Camera, Or<(With<MainCamera>, With<HudCamera>)>>,
+) {
+    let Ok(window) = window_query.get_single() else {
+        return;
+    };
+    let window_size = UVec2::new(window.physical_width(), window.physical_height());
+    // Minimized
+    if window_size.min_element() == 0 {
+        return;
+    }
+    let scale = (window_size.x as f32 / WINDOW_WIDTH).min(window_size.y as f32 / WINDOW_HEIGHT);
+    let size = (Vec2::new(WINDOW_WIDTH, WINDOW_HEIGHT) * scale).round().as_uvec2().clamp(UVec2::ONE, window_size);
+    let position = (window_size - size) / 2;
+    for mut camera in camera_query.iter_mut() {
+        let fitted = camera.viewport.as_ref().is_some_and(|viewport| viewport.physical_position == position && viewport.physical_size == size);
+        if !fitted {
+            camera.viewport = Some(Viewport { physical_position: position, physical_size: size, ..default() });
+        }
+    }
+}
+
+// Hands world text and screen-space sprites to the HUD camera
+fn move_to_hud_layer(
+    mut commands: Commands,
+    query: Query<Entity, (Or<(Added<Text>, Added<ScreenSpace>)>, Without<Node>, Without<WorldSpace>, Without<Offscreen>)>,
+) {
+    for entity in query.iter() {
+        commands.entity(entity).insert(RenderLayers::layer(HUD_LAYER));
+    }
+}
+
+// Keeps a camera showing a window's worth of view (at `zoom`) inside the level
+fn clamp_to_world(position: Vec2, bounds: WorldBounds, zoom: f32) -> Vec2 {
+    let limit_x = (bounds.half_width() - WINDOW_WIDTH * zoom / 2.0).max(0.0);
+    let limit_y = (bounds.half_height() - WINDOW_HEIGHT * zoom / 2.0).max(0.0);
+    Vec2::new(position.x.clamp(-limit_x, limit_x), position.y.clamp(-limit_y, limit_y))
+}
+
+// Tracks the Classic player during a run, or frames both co-op players. A
+// camera holds still on the pause menu, drafts and the like, shows the middle
+// of the level while the overview is open and goes back to the origin (and the
+// regular zoom) for every other screen but the level editor and the tower.
+// Each world camera follows its own `CameraFollow::target`.
+fn follow_player(
+    time: Res<Time>,
+    app_state: Res<State<AppState>>,
+    game_mode: Res<GameMode>,
+    spectator: Res<Spectator>,
+    overview: Res<Overview>,
+    player_query: Query<(Entity, &Transform, &Velocity), With<Player>>,
+    mut camera_query: Query<(&mut Transform, &mut CameraFollow, &CameraOffsets, &mut OrthographicProjection), (With<MainCamera>, Without<Player>)>,
+) {
+    // The level editor pans the camera itself
+    if spectator.active || *app_state.get() == AppState::LevelEditor {
+        return;
+    }
+    // The tower scrolls the camera itself, and leaves it where the climb ended
+    if *game_mode == GameMode::Tower && matches!(app_state.get(), AppState::Playing | AppState::Paused | AppState::GameOver) {
+        return;
+    }
+    let in_classic_run = *game_mode == GameMode::Classic && !matches!(app_state.get(), AppState::Loading | AppState::MainMenu | AppState::Lobby | AppState::Favorites | AppState::Keybindings | AppState::CrashReport | AppState::Onboarding | AppState::Hub | AppState::CharacterSelect | AppState::SessionRecap);
+    let dt = time.delta_seconds();
+    let bounds = game_mode.world_bounds();
+    for (mut transform, mut follow, offsets, mut projection) in camera_query.iter_mut() {
+        if !in_classic_run || overview.is_open() {
+            *follow = CameraFollow { target: follow.target, ..default() };
+            transform.translation.x = 0.0;
+            transform.translation.y = 0.0;
+            // The overview sets its own zoom
+            if !in_classic_run && projection.scale != 1.0 {
+                projection.scale = 1.0;
+            }
+            continue;
+        }
+        if *app_state.get() != AppState::Playing {
+            continue;
+        }
+        // Several players are followed as one, from the middle of them all
+        let followed: Vec<(Vec2, f32)> = player_query
+            .iter()
+            .filter(|(player, _, _)| follow.follows(*player))
+            .map(|(_, transform, velocity)| (transform.translation.truncate(), velocity.x))
+            .collect();
+        if followed.is_empty() {
+            continue;
+        }
+        let (low, high) = followed.iter().fold((Vec2::MAX, Vec2::MIN), |(low, high), (position, _)| (low.min(*position), high.max(*position)));
+        let player = (low + high) / 2.0;
+        let velocity_x = followed.iter().map(|(_, velocity_x)| velocity_x).sum::<f32>() / followed.len() as f32;
+        let spread = high - low;
+
+        // Only move the focus once the player leaves the dead zone around it
+        let offset = player - follow.focus;
+        follow.focus += offset - offset.clamp(-DEAD_ZONE, DEAD_ZONE);
+
+        let wanted_look_ahead = (velocity_x / PLAYER_SPEED).clamp(-1.0, 1.0) * LOOK_AHEAD;
+        follow.look_ahead += (wanted_look_ahead - follow.look_ahead) * (1.0 - (-LOOK_AHEAD_SHARPNESS * dt).exp());
+
+        // Zoomed out far enough to fit both co-op players, but never past the level
+        let fit = (spread + COOP_MARGIN * 2.0) / Vec2::new(WINDOW_WIDTH, WINDOW_HEIGHT);
+        let level_fit = Vec2::new(bounds.width / WINDOW_WIDTH, bounds.height / WINDOW_HEIGHT).min_element();
+        let wanted_zoom = fit.max_element().clamp(1.0, COOP_MAX_ZOOM.min(level_fit).max(1.0));
+        let zoom = projection.scale + (wanted_zoom - projection.scale) * (1.0 - (-ZOOM_SHARPNESS * dt).exp());
+        if (zoom - projection.scale).abs() > f32::EPSILON {
+            projection.scale = zoom;
+        }
+
+        let offset = follow.offset;
+        follow.offset = offset + (offsets.total() - offset) * (1.0 - (-OFFSET_SHARPNESS * dt).exp());
+
+        let target = clamp_to_world(follow.focus + Vec2::new(follow.look_ahead, 0.0) + follow.offset, bounds, projection.scale);
+        let current = transform.translation.truncate();
+        let position = if current.distance(target) > SNAP_DISTANCE {
+            // Start the dead zone on the player rather than dragging it across the level
+            follow.focus = player;
+            follow.look_ahead = 0.0;
+            follow.offset = Vec2::ZERO;
+            clamp_to_world(player, bounds, projection.scale)
+        } else {
+            current.lerp(target, 1.0 - (-FOLLOW_SHARPNESS * dt).exp())
+        };
+        transform.translation.x = position.x;
+        transform.translation.y = position.y;
+    }
+}
+
+// Holding Down or Up while standing in a Classic run pans the camera to show
+// what's below or above, for a player with a camera to themselves. Not in
+// two-button mode, where Down turns the player around.
+fn peek_camera(
+    time: Res<Time>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    app_state: Res<State<AppState>>,
+    game_mode: Res<GameMode>,
+    assist_mode: Res<AssistMode>,
+    gamepad: Res<GamepadInput>,
+    playback: Option<Res<ReplayPlayback>>,
+    player_query: Query<(Entity, &Grounded, &PlayerControls, Has<Climbing>, Has<LeadPlayer>), With<Player>>,
+    mut camera_query: Query<(&mut CameraFollow, &mut CameraOffsets), With<MainCamera>>,
+) {
+    let peeking_allowed = *app_state.get() == AppState::Playing && *game_mode == GameMode::Classic && !assist_mode.0.controls.auto_run();
+    for (mut follow, mut offsets) in camera_query.iter_mut() {
+        let mut direction = 0.0;
+        // Players sharing a camera can't peek, so only a player on their own does
+        let mut players = player_query.iter().filter(|(player, ..)| follow.follows(*player));
+        if let (Some((_, grounded, controls, climbing, lead)), None) = (players.next(), players.next()) {
+            if peeking_allowed && grounded.0 && !climbing {
+                let (mut up, mut down) = (
+                    PlayerControls::any_pressed(&keyboard_input, &controls.up),
+                    PlayerControls::any_pressed(&keyboard_input, &controls.down),
+                );
+                // The controller and a replay being played back are the lead player's
+                if lead {
+                    up |= gamepad.up_held();
+                    down |= gamepad.down_held();
+                    // A replay being played back peeks where it was recorded holding Down or Up
+                    if let Some(input) = playback.as_ref().and_then(|playback| playback.input()) {
+                        (up, down) = (input.up, input.down);
+                    }
+                }
+                direction = f32::from(u8::from(up)) - f32::from(u8::from(down));
+            }
+        }
+        if direction == 0.0 {
+            follow.peek_held = 0.0;
+            offsets.clear("peek");
+            continue;
+        }
+        follow.peek_held += time.delta_seconds();
+        if follow.peek_held >= PEEK_DELAY {
+            offsets.set("peek", Vec2::new(0.0, direction * PEEK_DISTANCE));
+        }
+    }
+}
+
+// Mirror mode flips the cameras horizontally, so physics never knows about it.
+// Text is flipped back so it stays readable.
+fn apply_mirror_mode(
+    app_state: Res<State<AppState>>,
+    game_mode: Res<GameMode>,
+    mutator_mode: Res<MutatorMode>,
+    mut camera_query: Query<&mut Transform, Or<(With<MainCamera>, With<HudCamera>)>>,
+    mut text_query: Query<&mut Transform, (With<Text>, Without<MainCamera>, Without<HudCamera>, Without<Offscreen>)>,
+) {
+    let in_classic_run = matches!(app_state.get(), AppState::Playing | AppState::ModifierDraft | AppState::Paused | AppState::DeathRecap | AppState::HudEditor) && *game_mode == GameMode::Classic;
+    let flip = if in_classic_run && mutator_mode.0.mirror.mirrored() { -1.0 } else { 1.0 };
+    for mut transform in camera_query.iter_mut() {
+        if transform.scale.x != flip {
+            transform.scale.x = flip;
+        }
+    }
+    for mut transform in text_query.iter_mut() {
+        if transform.scale.x.signum() != flip {
+            transform.scale.x = transform.scale.x.abs() * flip;
+        }
+    }
+}
+
+fn apply_graphics_quality(
+    mut commands: Commands,
+    graphics: Res<GraphicsQuality>,
+    mut msaa: ResMut<Msaa>,
+    mut camera_query: Query<(Entity, &mut Camera, Has<MainCamera>), Or<(With<MainCamera>, With<HudCamera>)>>,
+) {
+    if !graphics.is_changed() {
+        return;
+    }
+    *msaa = if graphics.anti_aliasing() { Msaa::Sample4 } else { Msaa::Off };
+    for (entity, mut camera, world_camera) in camera_query.iter_mut() {
+        // Both cameras draw to the same window, so they switch to HDR together
+        camera.hdr = graphics.bloom();
+        if !world_camera {
+            continue;
+        }
+        if graphics.bloom() {
+            commands.entity(entity).insert(BloomSettings::NATURAL);
+        } else {
+            commands.entity(entity).remove::<BloomSettings>();
+        }
+    }
+}
+
+// Spectator Camera Systems
+fn toggle_spectator(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut spectator: ResMut<Spectator>,
+    mut commands: Commands,
+    mut camera_query: Query<&mut OrthographicProjection, With<MainCamera>>,
+    spectator_ui_query: Query<Entity, With<SpectatorUI>>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::F8) {
+        return;
+    }
+
+    spectator.active = !spectator.active;
+    if spectator.active {
+        // Screen-space label so it stays put while the camera flies around
+        commands.spawn((
+            TextBundle::from_section(
+                "SPECTATING - WASD/drag to move, wheel to zoom, F8 to exit",
+                TextStyle {
+                    font_size: 24.0,
+                    color: Color::srgb(1.0, 0.4, 0.8), // Pink
+                    ..default()
+                },
+            )
+            .with_style(Style {
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(10.0),
+                left: Val::Px(10.0),
+                ..default()
+            }),
+            SpectatorUI,
+        ));
+    } else {
+        for entity in spectator_ui_query.iter() {
+            commands.entity(entity).despawn();
+        }
+        // Back to the regular zoom; following the player picks up from here
+        if let Ok(mut projection) = camera_query.get_single_mut() {
+            projection.scale = 1.0;
+        }
+    }
+}
+
+fn spectator_camera_controls(
+    time: Res<Time>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mouse_buttons: Res<ButtonInput<MouseButton>>,
+    mut mouse_motion: EventReader<MouseMotion>,
+    mut mouse_wheel: EventReader<MouseWheel>,
+    mut camera_query: Query<(&mut Transform, &mut OrthographicProjection, &Camera), With<MainCamera>>,
+) {
+    let Ok((mut transform, mut projection, camera)) = camera_query.get_single_mut() else {
+        return;
+    };
+
+    // Keyboard panning, faster when zoomed out
+    let mut direction = Vec2::ZERO;
+    if keyboard_input.any_pressed([KeyCode::KeyA, KeyCode::ArrowLeft]) {
+        direction.x -= 1.0;
+    }
+    if keyboard_input.any_pressed([KeyCode::KeyD, KeyCode::ArrowRight]) {
+        direction.x += 1.0;
+    }
+    if keyboard_input.any_pressed([KeyCode::KeyS, KeyCode::ArrowDown]) {
+        direction.y -= 1.0;
+    }
+    if keyboard_input.any_pressed([KeyCode::KeyW, KeyCode::ArrowUp]) {
+        direction.y += 1.0;
+    }
+    let pan = direction.normalize_or_zero() * SPECTATOR_PAN_SPEED * projection.scale * time.delta_seconds();
+    transform.translation.x += pan.x;
+    transform.translation.y += pan.y;
+
+    // Mouse drag panning (screen y points down, world y points up), so the
+    // level follows the pointer however big the view is drawn
+    let drag: Vec2 = mouse_motion.read().map(|motion| motion.delta).sum();
+    if mouse_buttons.pressed(MouseButton::Left) {
+        let units_per_pixel = camera.physical_viewport_size().map_or(1.0, |size| WINDOW_WIDTH / size.x as f32);
+        transform.translation.x -= drag.x * units_per_pixel * projection.scale;
+        transform.translation.y += drag.y * units_per_pixel * projection.scale;
+    }
+
+    // Wheel zoom
+    let scroll: f32 = mouse_wheel.read().map(|wheel| wheel.y).sum();
+    if scroll != 0.0 {
+        projection.scale = (projection.scale * (1.0 - scroll * SPECTATOR_ZOOM_STEP))
+            .clamp(SPECTATOR_MIN_ZOOM, SPECTATOR_MAX_ZOOM);
+    }
+}
@@ -0,0 +1,194 @@
+// Campaigns: where Classic levels come from.
+//
+// Y on the main menu picks the campaign Classic runs play. Procedural Endless
+// generates every level from the run seed. The Authored Campaign plays the
+// hand-made levels in `assets/levels` and the Community Pack the ones in
+// `assets/community_levels` (see `level_loader`); both generate the levels
+// they have no file for, so a run carries on past the end of the pack.
+//
+// Each campaign is a `LevelProvider`. Starting a run, going on to the next
+// level and restarting one all ask the picked campaign for the level through
+// `CampaignLevels`. The choice is kept in the save directory. The tutorial
+// always plays the authored level 0, whichever campaign is picked, and a
+// `--stress` run plays its stress world (see `stress`) for every level.
+
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use crate::prelude::*;
+
+use crate::chat::chat_closed;
+use crate::game::{read_save_file, write_save_file};
+use crate::level_loader::{CommunityLevels, HandmadeLevel, HandmadeLevels};
+use crate::menu::MainMenuUI;
+use crate::stress::StressTest;
+use crate::tutorial::Tutorial;
+use crate::ui::Toasts;
+
+pub struct CampaignPlugin;
+
+impl Plugin for CampaignPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SelectedCampaign>()
+            .add_systems(OnEnter(AppState::MainMenu), setup_campaign_menu_line)
+            .add_systems(Update, cycle_campaign.run_if(in_state(AppState::MainMenu).and_then(chat_closed)));
+    }
+}
+
+const CAMPAIGN_FILE: &str = "campaign.txt";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Campaign {
+    Procedural,
+    #[default]
+    Authored,
+    Community,
+}
+
+impl Campaign {
+    const ALL: [Self; 3] = [Self::Procedural, Self::Authored, Self::Community];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Procedural => "Procedural Endless",
+            Self::Authored => "Authored Campaign",
+            Self::Community => "Community Pack",
+        }
+    }
+
+    // How it's written in the save file
+    fn key(self) -> &'static str {
+        match self {
+            Self::Procedural => "procedural",
+            Self::Authored => "authored",
+            Self::Community => "community",
+        }
+    }
+
+    fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|campaign| campaign.key() == key)
+    }
+
+    fn next(self) -> Self {
+        let index = Self::ALL.iter().position(|&campaign| campaign == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+}
+
+// The campaign Classic runs play
+#[derive(Resource)]
+pub struct SelectedCampaign(pub Campaign);
+
+impl Default for SelectedCampaign {
+    fn default() -> Self {
+        let saved = read_save_file(CAMPAIGN_FILE).unwrap_or_default();
+        Self(Campaign::from_key(saved.trim()).unwrap_or_default())
+    }
+}
+
+impl SelectedCampaign {
+    fn save(&self) -> Result<(), RuntimeErrorEvent> {
+        write_save_file(CAMPAIGN_FILE, self.0.key(), "the campaign")
+    }
+}
+
+// Somewhere Classic levels can be taken from
+pub trait LevelProvider {
+    // Level `number` as made by hand, or None to generate it from the run seed
+    fn level(&self, number: u32) -> Option<HandmadeLevel>;
+}
+
+// Procedural Endless: nothing is made by hand
+struct GeneratedLevels;
+
+impl LevelProvider for GeneratedLevels {
+    fn level(&self, _number: u32) -> Option<HandmadeLevel> {
+        None
+    }
+}
+
+impl LevelProvider for HandmadeLevels {
+    fn level(&self, number: u32) -> Option<HandmadeLevel> {
+        self.get(number).cloned()
+    }
+}
+
+// The levels of the picked campaign, for the systems that start levels
+#[derive(SystemParam)]
+pub struct CampaignLevels<'w> {
+    selected: Res<'w, SelectedCampaign>,
+    authored: Res<'w, HandmadeLevels>,
+    community: Res<'w, CommunityLevels>,
+    tutorial: Res<'w, Tutorial>,
+    stress: Option<Res<'w, StressTest>>,
+}
+
+impl CampaignLevels<'_> {
+    pub fn provider(&self) -> &dyn LevelProvider {
+        if let Some(stress) = &self.stress {
+            return &**stress;
+        }
+        if self.tutorial.active {
+            return &*self.authored;
+        }
+        match self.selected.0 {
+            Campaign::Procedural => &GeneratedLevels,
+            Campaign::Authored => &*self.authored,
+            Campaign::Community => &self.community.0,
+        }
+    }
+}
+
+#[derive(Component)]
+struct CampaignMenuText;
+
+fn campaign_menu_line(campaign: Campaign, authored: &HandmadeLevels, community: &CommunityLevels) -> String {
+    let pack = match campaign {
+        Campaign::Procedural => return format!("Y: Campaign {} (every level generated)", campaign.name()),
+        Campaign::Authored => authored,
+        Campaign::Community => &community.0,
+    };
+    format!("Y: Campaign {} ({} hand-made level{}, the rest generated)", campaign.name(), pack.len(), if pack.len() == 1 { "" } else { "s" })
+}
+
+fn setup_campaign_menu_line(mut commands: Commands, selected: Res<SelectedCampaign>, authored: Res<HandmadeLevels>, community: Res<CommunityLevels>) {
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                campaign_menu_line(selected.0, &authored, &community),
+                TextStyle {
+                    font_size: 20.0,
+                    color: Color::srgb(0.6, 0.9, 0.6), // Pale green
+                    ..default()
+                },
+            ),
+            transform: Transform::from_translation(Vec3::new(0.0, 270.0, 10.0)),
+            ..default()
+        },
+        MainMenuUI,
+        CampaignMenuText,
+    ));
+}
+
+fn cycle_campaign(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut selected: ResMut<SelectedCampaign>,
+    authored: Res<HandmadeLevels>,
+    community: Res<CommunityLevels>,
+    mut text_query: Query<&mut Text, With<CampaignMenuText>>,
+    mut toasts: ResMut<Toasts>,
+    mut errors: EventWriter<RuntimeErrorEvent>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::KeyY) {
+        return;
+    }
+    selected.0 = selected.0.next();
+    if selected.0 == Campaign::Community && community.0.is_empty() {
+        toasts.push("No levels in assets/community_levels yet - they'll all be generated");
+    }
+    if let Ok(mut text) = text_query.get_single_mut() {
+        text.sections[0].value = campaign_menu_line(selected.0, &authored, &community);
+    }
+    if let Err(error) = selected.save() {
+        errors.send(error);
+    }
+}
@@ -0,0 +1,210 @@
+// Screenshots and clips for sharing.
+//
+// Ctrl+F12 (anywhere but the main menu, where F12 belongs to the assist menu)
+// saves a PNG of the frame on screen, and Ctrl+F11 the last few seconds of
+// play as a looping GIF, both to the `captures` folder of the save directory
+// with the time in their names. F12 and F11 on their own are still the bug
+// report and the desync probe. While a level is being played, every few
+// frames a shrunken copy of what's on screen is kept for the clip. Frames come
+// back from the renderer a frame or two after they're drawn, through
+// `ScreenshotManager`. Encoding them (picking a GIF's colors above all) would
+// hitch the game, so it runs as a task on the `AsyncComputeTaskPool`, and a
+// toast says where the file went once it's written.
+
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::Mutex;
+
+use bevy::prelude::*;
+use bevy::render::view::screenshot::ScreenshotManager;
+use bevy::tasks::{block_on, futures_lite::future, AsyncComputeTaskPool, Task};
+use bevy::window::PrimaryWindow;
+use image::codecs::gif::{GifEncoder, Repeat};
+use image::{Delay, Frame, RgbaImage};
+use crate::prelude::*;
+
+use crate::game::{save_path, unix_seconds};
+use crate::ui::Toasts;
+
+pub struct CapturePlugin;
+
+impl Plugin for CapturePlugin {
+    fn build(&self, app: &mut App) {
+        let (sender, receiver) = channel();
+        app.insert_resource(Captures { sender, receiver: Mutex::new(receiver), clip: VecDeque::new(), last_frame: None, saving: Vec::new() })
+            .add_systems(Update, (
+                save_capture.run_if(not(in_state(AppState::MainMenu))),
+                collect_captures,
+                finish_saves,
+            ).chain())
+            // After everything else in the frame has had its chance at a
+            // screenshot, as the renderer only takes one a frame
+            .add_systems(PostUpdate, record_clip_frame.run_if(in_state(AppState::Playing)));
+    }
+}
+
+const CAPTURE_FOLDER: &str = "captures";
+// How much play a clip holds, and how many frames a second of it
+const CLIP_SECONDS: f32 = 5.0;
+const CLIP_FPS: u32 = 15;
+// Clip frames are shrunk to fit this, to keep the clip small in memory and
+// on disk
+const CLIP_SIZE: (u32, u32) = (480, 270);
+// How closely a clip's colors are picked, from 1 (best, slowest) to 30
+const GIF_SPEED: i32 = 10;
+
+// What the renderer hands back
+enum Captured {
+    Screenshot(Image),
+    // A shrunken clip frame, and the real time it was asked for
+    ClipFrame(f32, RgbaImage),
+}
+
+#[derive(Resource)]
+struct Captures {
+    sender: Sender<Captured>,
+    receiver: Mutex<Receiver<Captured>>,
+    // The last `CLIP_SECONDS` of clip frames, oldest first
+    clip: VecDeque<(f32, RgbaImage)>,
+    // When the last clip frame was asked for
+    last_frame: Option<f32>,
+    // Files being encoded and written, and where they go
+    saving: Vec<Task<Result<PathBuf, String>>>,
+}
+
+// Whether F12 or F11 is for a capture rather than a bug report or the desync
+// probe
+pub fn capture_key(keyboard_input: &ButtonInput<KeyCode>) -> bool {
+    keyboard_input.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight])
+}
+
+// Ctrl+F12 asks for a screenshot; Ctrl+F11 starts encoding the clip so far
+fn save_capture(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut captures: ResMut<Captures>,
+    window_query: Query<Entity, With<PrimaryWindow>>,
+    mut screenshot_manager: ResMut<ScreenshotManager>,
+    mut toasts: ResMut<Toasts>,
+) {
+    if !capture_key(&keyboard_input) {
+        return;
+    }
+    if keyboard_input.just_pressed(KeyCode::F12) {
+        let sender = captures.sender.clone();
+        let taken = window_query.get_single().is_ok_and(|window| {
+            screenshot_manager.take_screenshot(window, move |image| {
+                let _ = sender.send(Captured::Screenshot(image));
+            })
+            .is_ok()
+        });
+        if !taken {
+            toasts.push("Couldn't take a screenshot just now");
+        }
+    } else if keyboard_input.just_pressed(KeyCode::F11) {
+        // Frames the size the window was last, in case it changed size
+        let size = captures.clip.back().map(|(_, frame)| frame.dimensions());
+        let frames: Vec<(f32, RgbaImage)> = captures.clip.iter().filter(|(_, frame)| Some(frame.dimensions()) == size).cloned().collect();
+        if frames.is_empty() {
+            toasts.push("Nothing to save yet: clips are recorded while playing");
+            return;
+        }
+        let path = capture_path("clip", "gif");
+        toasts.push("Saving the last few seconds as a GIF");
+        captures.saving.push(AsyncComputeTaskPool::get().spawn(async move { write_gif(path, frames) }));
+    }
+}
+
+// Keeps clip frames as they come in and starts writing screenshots
+fn collect_captures(time: Res<Time<Real>>, mut captures: ResMut<Captures>) {
+    let captured: Vec<Captured> = match captures.receiver.lock() {
+        Ok(receiver) => receiver.try_iter().collect(),
+        Err(_) => return,
+    };
+    for capture in captured {
+        match capture {
+            Captured::Screenshot(image) => {
+                let path = capture_path("screenshot", "png");
+                captures.saving.push(AsyncComputeTaskPool::get().spawn(async move { write_png(path, image) }));
+            }
+            Captured::ClipFrame(at, frame) => captures.clip.push_back((at, frame)),
+        }
+    }
+    let now = time.elapsed_seconds();
+    while captures.clip.front().is_some_and(|(at, _)| now - at > CLIP_SECONDS) {
+        captures.clip.pop_front();
+    }
+}
+
+// Tells the player where each finished file went
+fn finish_saves(mut captures: ResMut<Captures>, mut toasts: ResMut<Toasts>, mut errors: EventWriter<RuntimeErrorEvent>) {
+    let mut still_saving = Vec::new();
+    for mut task in captures.saving.drain(..) {
+        match block_on(future::poll_once(&mut task)) {
+            None => still_saving.push(task),
+            Some(Ok(path)) => toasts.push(format!("Saved to {}", path.display())),
+            Some(Err(error)) => {
+                errors.send(RuntimeErrorEvent::new("Could not save the capture", error));
+            }
+        }
+    }
+    captures.saving = still_saving;
+}
+
+// Asks for the frame on screen for the clip, `CLIP_FPS` times a second
+fn record_clip_frame(
+    time: Res<Time<Real>>,
+    mut captures: ResMut<Captures>,
+    window_query: Query<Entity, With<PrimaryWindow>>,
+    mut screenshot_manager: ResMut<ScreenshotManager>,
+) {
+    let now = time.elapsed_seconds();
+    if captures.last_frame.is_some_and(|last| now - last < 1.0 / CLIP_FPS as f32) {
+        return;
+    }
+    let Ok(window) = window_query.get_single() else {
+        return;
+    };
+    let sender = captures.sender.clone();
+    // Shrunk as it comes back, so only the small copy is kept
+    let requested = screenshot_manager.take_screenshot(window, move |image| {
+        if let Ok(image) = image.try_into_dynamic() {
+            let _ = sender.send(Captured::ClipFrame(now, image.thumbnail(CLIP_SIZE.0, CLIP_SIZE.1).to_rgba8()));
+        }
+    });
+    // A frame someone else asked for a screenshot of is skipped
+    if requested.is_ok() {
+        captures.last_frame = Some(now);
+    }
+}
+
+fn capture_path(kind: &str, extension: &str) -> PathBuf {
+    save_path(CAPTURE_FOLDER).join(format!("{}-{}.{}", kind, unix_seconds(), extension))
+}
+
+fn write_png(path: PathBuf, image: Image) -> Result<PathBuf, String> {
+    // Without the alpha channel, which can hold something other than
+    // transparency
+    let rgb = image.try_into_dynamic().map_err(|error| error.to_string())?.to_rgb8();
+    path.parent().map_or(Ok(()), std::fs::create_dir_all).map_err(|error| format!("{}: {}", path.display(), error))?;
+    rgb.save_with_format(&path, image::ImageFormat::Png).map_err(|error| format!("{}: {}", path.display(), error))?;
+    Ok(path)
+}
+
+// Each frame lasts until the next one was taken; the last one as long as a
+// frame is meant to
+fn write_gif(path: PathBuf, frames: Vec<(f32, RgbaImage)>) -> Result<PathBuf, String> {
+    let fail = |error: &dyn std::fmt::Display| format!("{}: {}", path.display(), error);
+    path.parent().map_or(Ok(()), std::fs::create_dir_all).map_err(|error| fail(&error))?;
+    let file = std::fs::File::create(&path).map_err(|error| fail(&error))?;
+    let mut encoder = GifEncoder::new_with_speed(std::io::BufWriter::new(file), GIF_SPEED);
+    encoder.set_repeat(Repeat::Infinite).map_err(|error| fail(&error))?;
+    let times: Vec<f32> = frames.iter().map(|(at, _)| *at).collect();
+    for (index, (at, frame)) in frames.into_iter().enumerate() {
+        let seconds = times.get(index + 1).map_or(1.0 / CLIP_FPS as f32, |next| next - at);
+        let delay = Delay::from_numer_denom_ms((seconds * 1000.0).round().max(10.0) as u32, 1);
+        encoder.encode_frame(Frame::from_parts(frame, 0, 0, delay)).map_err(|error| fail(&error))?;
+    }
+    drop(encoder);
+    Ok(path)
+}
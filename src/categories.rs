@@ -0,0 +1,211 @@
+// Speedrun categories for Classic runs.
+//
+// C on the main menu picks the category runs are timed in: Any%, 100% (every
+// fruit and every coin) or Low% (no modifiers; 0 skips a draft). The run timer
+// counts while playing and stops at drafts and the pause menu. A run that
+// reaches the finish level still following its category's rules goes on that
+// category's best times table, which is kept in the save directory and shown
+// on the game over screen. Assisted runs and runs started from a favorite
+// level aren't timed.
+
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use crate::prelude::*;
+use rustbevy_core::category::{format_time, BestTimes, RunCategory, FINISH_LEVEL};
+
+use crate::game::{read_save_file, write_save_file};
+use crate::menu::MainMenuUI;
+use crate::ui::{despawn_screen, Toasts};
+
+pub struct CategoriesPlugin;
+
+impl Plugin for CategoriesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SelectedCategory>()
+            .init_resource::<CategoryTimes>()
+            .add_systems(OnEnter(AppState::MainMenu), setup_category_menu_line)
+            .add_systems(OnEnter(AppState::GameOver), show_best_times.run_if(resource_equals(GameMode::Classic)))
+            .add_systems(OnExit(AppState::GameOver), despawn_screen::<BestTimesUI>)
+            .add_systems(Update, (
+                cycle_category.run_if(in_state(AppState::MainMenu)),
+                (tick_run_timer, check_category_run)
+                    .chain()
+                    .run_if(in_state(AppState::Playing).and_then(resource_equals(GameMode::Classic))),
+            ));
+    }
+}
+
+const BEST_TIMES_FILE: &str = "best_times.txt";
+
+// The category Classic runs are timed in
+#[derive(Resource, Default)]
+pub struct SelectedCategory(pub RunCategory);
+
+#[derive(Resource)]
+pub struct CategoryTimes {
+    tables: HashMap<RunCategory, BestTimes>,
+}
+
+impl Default for CategoryTimes {
+    fn default() -> Self {
+        let saved = read_save_file(BEST_TIMES_FILE).unwrap_or_default();
+        let mut tables: HashMap<RunCategory, BestTimes> = HashMap::new();
+        // One `<category> <seconds>` line per finish
+        for line in saved.lines() {
+            let Some((key, seconds)) = line.split_once(' ') else {
+                continue;
+            };
+            if let (Some(category), Ok(seconds)) = (RunCategory::from_key(key), seconds.trim().parse()) {
+                tables.entry(category).or_default().record(seconds);
+            }
+        }
+        Self { tables }
+    }
+}
+
+impl CategoryTimes {
+    pub fn table(&self, category: RunCategory) -> Option<&BestTimes> {
+        self.tables.get(&category)
+    }
+
+    fn save(&self) -> Result<(), RuntimeErrorEvent> {
+        let lines: Vec<String> = RunCategory::ALL
+            .into_iter()
+            .filter_map(|category| self.tables.get(&category).map(|table| (category, table)))
+            .flat_map(|(category, table)| table.times.iter().map(move |seconds| format!("{} {}", category.key(), seconds)))
+            .collect();
+        write_save_file(BEST_TIMES_FILE, &lines.join("\n"), "best times")
+    }
+}
+
+#[derive(Component)]
+struct CategoryMenuText;
+
+#[derive(Component)]
+struct BestTimesUI;
+
+fn category_menu_line(selected: &SelectedCategory, times: &CategoryTimes) -> String {
+    let category = selected.0;
+    let best = times
+        .table(category)
+        .and_then(BestTimes::best)
+        .map_or("no time yet".to_string(), |best| format!("best {}", format_time(best)));
+    format!("C: Category {} (reach level {}; {}) - {}", category.name(), FINISH_LEVEL, category.rules(), best)
+}
+
+fn setup_category_menu_line(mut commands: Commands, selected: Res<SelectedCategory>, times: Res<CategoryTimes>) {
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                category_menu_line(&selected, &times),
+                TextStyle {
+                    font_size: 22.0,
+                    color: Color::srgb(1.0, 0.85, 0.3), // Gold
+                    ..default()
+                },
+            ),
+            transform: Transform::from_translation(Vec3::new(0.0, 70.0, 10.0)),
+            ..default()
+        },
+        MainMenuUI,
+        CategoryMenuText,
+    ));
+}
+
+fn cycle_category(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut selected: ResMut<SelectedCategory>,
+    times: Res<CategoryTimes>,
+    mut text_query: Query<&mut Text, With<CategoryMenuText>>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::KeyC) {
+        return;
+    }
+    selected.0 = selected.0.next();
+    if let Ok(mut text) = text_query.get_single_mut() {
+        text.sections[0].value = category_menu_line(&selected, &times);
+    }
+}
+
+// Counts run time without flagging `GameState` as changed every frame
+fn tick_run_timer(time: Res<Time>, mut game_state: ResMut<GameState>) {
+    let stats = &mut game_state.bypass_change_detection().stats;
+    if !stats.finished {
+        stats.seconds += time.delta_seconds();
+    }
+}
+
+fn check_category_run(
+    mut level_events: EventReader<LevelCompletedEvent>,
+    mut game_state: ResMut<GameState>,
+    assist_mode: Res<AssistMode>,
+    coin_query: Query<(), With<Fetchable>>,
+    selected: Res<SelectedCategory>,
+    mut times: ResMut<CategoryTimes>,
+    mut toasts: ResMut<Toasts>,
+    mut errors: EventWriter<RuntimeErrorEvent>,
+) {
+    let Some(event) = level_events.read().last().copied() else {
+        return;
+    };
+    // Coins still lying around when a level is done were left behind
+    game_state.stats.coins_missed += coin_query.iter().count() as u32;
+    if event.level < FINISH_LEVEL || game_state.stats.finished {
+        return;
+    }
+
+    game_state.stats.finished = true;
+    let category = selected.0;
+    if assist_mode.0.any() {
+        toasts.push(format!("Level {} reached - assisted runs aren't timed", FINISH_LEVEL));
+        return;
+    }
+    if let Some(reason) = category.broken_by(&game_state.stats) {
+        toasts.push(format!("Level {} reached, but it isn't a {} run: {}", FINISH_LEVEL, category.name(), reason));
+        return;
+    }
+    let seconds = game_state.stats.seconds;
+    let place = times.tables.entry(category).or_default().record(seconds);
+    let message = match place {
+        Some(1) => format!("{} finished in {} - new best!", category.name(), format_time(seconds)),
+        Some(place) => format!("{} finished in {} - #{} on the table", category.name(), format_time(seconds), place),
+        None => format!("{} finished in {}", category.name(), format_time(seconds)),
+    };
+    toasts.push(message);
+    if place.is_some() {
+        if let Err(error) = times.save() {
+            errors.send(error);
+        }
+    }
+}
+
+fn show_best_times(mut commands: Commands, selected: Res<SelectedCategory>, times: Res<CategoryTimes>) {
+    let category = selected.0;
+    let mut lines = vec![format!("{} best times", category.name())];
+    match times.table(category).filter(|table| !table.times.is_empty()) {
+        Some(table) => lines.extend(
+            table
+                .times
+                .iter()
+                .enumerate()
+                .map(|(index, seconds)| format!("{}. {}", index + 1, format_time(*seconds))),
+        ),
+        None => lines.push(format!("None yet - reach level {} to set one", FINISH_LEVEL)),
+    }
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                lines.join("\n"),
+                TextStyle {
+                    font_size: 22.0,
+                    color: Color::srgb(1.0, 0.85, 0.3), // Gold
+                    ..default()
+                },
+            ),
+            transform: Transform::from_translation(Vec3::new(0.0, -280.0, 10.0)),
+            ..default()
+        },
+        BestTimesUI,
+    ));
+}
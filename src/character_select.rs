@@ -0,0 +1,245 @@
+// Character select: picking the skin the Classic player wears.
+//
+// Skins (see `rustbevy_core::collection`) differ in color and a little in how
+// fast they run and how high they jump. The one picked is kept in the
+// `PlayerProfile` resource and the save directory; the player is dressed in
+// it when spawned, and its speeds go into the Classic tuning (see
+// `physics::player_tuning`), level generation included, so every level stays
+// in reach. P on the main menu opens the screen: LEFT/RIGHT looks through the
+// skins, ENTER wears the one shown and ESC goes back. Skins past the classic
+// one are unlocked by trophies in the collection, the last by a high score.
+
+use bevy::prelude::*;
+use crate::prelude::*;
+use rustbevy_core::collection::Skin;
+use rustbevy_core::config::PLAYER_SIZE;
+
+use crate::chat::chat_closed;
+use crate::game::{read_save_file, write_save_file};
+use crate::hub::{skin_color, PlayerCollection};
+use crate::menu::MainMenuUI;
+use crate::ui::{despawn_screen, Toasts};
+
+pub struct CharacterSelectPlugin;
+
+impl Plugin for CharacterSelectPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PlayerProfile>()
+            .init_resource::<CharacterCursor>()
+            .add_systems(OnEnter(AppState::MainMenu), setup_character_menu_line)
+            .add_systems(OnEnter(AppState::CharacterSelect), setup_character_select)
+            .add_systems(OnExit(AppState::CharacterSelect), despawn_screen::<CharacterSelectUI>)
+            .add_systems(Update, (
+                open_character_select.run_if(in_state(AppState::MainMenu).and_then(chat_closed)),
+                (handle_character_select_input, show_character_selection).chain().run_if(in_state(AppState::CharacterSelect)),
+                wear_skin,
+            ));
+    }
+}
+
+const PROFILE_FILE: &str = "profile.txt";
+const SKIN_SPACING: f32 = 130.0;
+const SKIN_Y: f32 = 60.0;
+const LOCKED_COLOR: Color = Color::srgb(0.22, 0.22, 0.25);
+const SELECTED_COLOR: Color = Color::srgb(1.0, 1.0, 0.4);
+
+// Who the player plays as
+#[derive(Resource, Clone, Copy)]
+pub struct PlayerProfile {
+    pub skin: Skin,
+}
+
+impl PlayerProfile {
+    // A `skin <name>` line; a skin that isn't unlocked (a hand-edited file,
+    // a reset collection) falls back to the classic one
+    fn load(collection: &PlayerCollection) -> Self {
+        let saved = read_save_file(PROFILE_FILE).unwrap_or_default();
+        let skin = saved
+            .lines()
+            .find_map(|line| line.strip_prefix("skin "))
+            .and_then(|name| Skin::from_name(name.trim()))
+            .filter(|&skin| collection.0.unlocked(skin))
+            .unwrap_or_default();
+        Self { skin }
+    }
+
+    fn save(&self) -> Result<(), RuntimeErrorEvent> {
+        write_save_file(PROFILE_FILE, &format!("skin {}", self.skin.name()), "the character")
+    }
+
+    // Wears `skin` and saves it, if it's unlocked
+    pub fn wear(&mut self, skin: Skin, collection: &PlayerCollection, toasts: &mut Toasts, errors: &mut EventWriter<RuntimeErrorEvent>) {
+        if !collection.0.unlocked(skin) {
+            toasts.push(format!("The {} skin is still locked", skin.name()));
+            return;
+        }
+        self.skin = skin;
+        toasts.push(format!("Wearing the {} skin", skin.name()));
+        if let Err(error) = self.save() {
+            errors.send(error);
+        }
+    }
+}
+
+impl FromWorld for PlayerProfile {
+    fn from_world(world: &mut World) -> Self {
+        match world.get_resource::<PlayerCollection>() {
+            Some(collection) => Self::load(collection),
+            None => Self::load(&PlayerCollection::default()),
+        }
+    }
+}
+
+// "Speed 108%   Jump 97%"
+pub fn skin_stats_line(skin: Skin) -> String {
+    format!("Speed {:.0}%   Jump {:.0}%", skin.speed() * 100.0, skin.jump() * 100.0)
+}
+
+// The skin the arrow keys are on
+#[derive(Resource, Default)]
+struct CharacterCursor(usize);
+
+#[derive(Component)]
+struct CharacterSelectUI;
+
+// A skin on the screen, by its place in `Skin::ALL`
+#[derive(Component)]
+struct SkinSprite(usize);
+
+#[derive(Component)]
+struct CharacterInfoText;
+
+fn character_menu_line(skin: Skin) -> String {
+    format!("P: Character - {} ({})", skin.name(), skin_stats_line(skin))
+}
+
+fn setup_character_menu_line(mut commands: Commands, profile: Res<PlayerProfile>) {
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                character_menu_line(profile.skin),
+                TextStyle {
+                    font_size: 20.0,
+                    color: skin_color(profile.skin),
+                    ..default()
+                },
+            ),
+            transform: Transform::from_translation(Vec3::new(0.0, -75.0, 10.0)),
+            ..default()
+        },
+        MainMenuUI,
+    ));
+}
+
+fn open_character_select(keyboard_input: Res<ButtonInput<KeyCode>>, mut next_state: ResMut<NextState<AppState>>) {
+    if keyboard_input.just_pressed(KeyCode::KeyP) {
+        next_state.set(AppState::CharacterSelect);
+    }
+}
+
+fn screen_text(commands: &mut Commands, value: impl Into<String>, y: f32, font_size: f32, color: Color) -> Entity {
+    commands
+        .spawn((
+            Text2dBundle {
+                text: Text::from_section(value, TextStyle { font_size, color, ..default() }),
+                transform: Transform::from_translation(Vec3::new(0.0, y, 10.0)),
+                ..default()
+            },
+            CharacterSelectUI,
+        ))
+        .id()
+}
+
+fn setup_character_select(
+    mut commands: Commands,
+    profile: Res<PlayerProfile>,
+    collection: Res<PlayerCollection>,
+    mut cursor: ResMut<CharacterCursor>,
+) {
+    // Starts on the skin being worn
+    cursor.0 = Skin::ALL.iter().position(|&skin| skin == profile.skin).unwrap_or(0);
+    screen_text(&mut commands, "CHARACTER", 260.0, 60.0, Color::srgb(0.7, 0.7, 1.0)); // Light blue
+    for (index, skin) in Skin::ALL.into_iter().enumerate() {
+        let x = (index as f32 - (Skin::ALL.len() as f32 - 1.0) / 2.0) * SKIN_SPACING;
+        let color = if collection.0.unlocked(skin) { skin_color(skin) } else { LOCKED_COLOR };
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite { color, custom_size: Some(Vec2::splat(PLAYER_SIZE * 2.0)), ..default() },
+                transform: Transform::from_xyz(x, SKIN_Y, 1.0),
+                ..default()
+            },
+            SkinSprite(index),
+            CharacterSelectUI,
+        ));
+    }
+    let info = screen_text(&mut commands, "", -80.0, 26.0, Color::WHITE);
+    commands.entity(info).insert(CharacterInfoText);
+    screen_text(&mut commands, "LEFT/RIGHT: choose   ENTER: wear   ESC: menu", -320.0, 20.0, Color::srgb(0.7, 0.7, 0.7));
+}
+
+fn handle_character_select_input(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut cursor: ResMut<CharacterCursor>,
+    mut profile: ResMut<PlayerProfile>,
+    collection: Res<PlayerCollection>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut toasts: ResMut<Toasts>,
+    mut errors: EventWriter<RuntimeErrorEvent>,
+) {
+    if keyboard_input.just_pressed(KeyCode::Escape) {
+        next_state.set(AppState::MainMenu);
+        return;
+    }
+    if keyboard_input.just_pressed(KeyCode::ArrowLeft) {
+        cursor.0 = cursor.0.saturating_sub(1);
+    } else if keyboard_input.just_pressed(KeyCode::ArrowRight) {
+        cursor.0 = (cursor.0 + 1).min(Skin::ALL.len() - 1);
+    }
+    let skin = Skin::ALL[cursor.0];
+    if keyboard_input.just_pressed(KeyCode::Enter) && profile.skin != skin {
+        profile.wear(skin, &collection, &mut toasts, &mut errors);
+    }
+}
+
+// Outlines the skin shown and describes it
+fn show_character_selection(
+    cursor: Res<CharacterCursor>,
+    profile: Res<PlayerProfile>,
+    collection: Res<PlayerCollection>,
+    mut gizmos: Gizmos,
+    sprite_query: Query<(&SkinSprite, &Transform)>,
+    mut info_query: Query<&mut Text, With<CharacterInfoText>>,
+) {
+    for (skin_sprite, transform) in sprite_query.iter() {
+        let skin = Skin::ALL[skin_sprite.0];
+        let size = Vec2::splat(PLAYER_SIZE * 2.0 + 10.0);
+        if skin_sprite.0 == cursor.0 {
+            gizmos.rect_2d(transform.translation.truncate(), 0.0, size, SELECTED_COLOR);
+        } else if skin == profile.skin {
+            gizmos.rect_2d(transform.translation.truncate(), 0.0, size, Color::srgb(0.6, 0.6, 0.6));
+        }
+    }
+    if !cursor.is_changed() && !profile.is_changed() {
+        return;
+    }
+    let Ok(mut text) = info_query.get_single_mut() else {
+        return;
+    };
+    let skin = Skin::ALL[cursor.0];
+    let status = if skin == profile.skin {
+        "wearing".to_string()
+    } else if collection.0.unlocked(skin) {
+        "ENTER to wear".to_string()
+    } else {
+        let trophy = skin.unlocked_by().expect("locked skins have a trophy");
+        format!("unlocked by {}: {}", trophy.name(), trophy.goal())
+    };
+    text.sections[0].value = format!("{} - {}\n{}", skin.name(), status, skin_stats_line(skin));
+}
+
+// The Classic player wears the picked skin
+fn wear_skin(profile: Res<PlayerProfile>, mut player_query: Query<&mut Sprite, (Added<Player>, Without<PartyPlayer>)>) {
+    for mut sprite in player_query.iter_mut() {
+        sprite.color = skin_color(profile.skin);
+    }
+}
@@ -0,0 +1,326 @@
+// Chat and emotes for online sessions.
+
+use bevy::prelude::*;
+use bevy::input::keyboard::{Key, KeyboardInput};
+use crate::prelude::*;
+
+use crate::lobby::{handle_lobby_input, local_player_name, Lobby, LobbyStage, NetSession};
+use crate::net::{LobbyMember, LobbyMessage, PeerId, Recipient, RelayClient};
+
+pub struct ChatPlugin;
+
+impl Plugin for ChatPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Chat>()
+            .add_systems(Startup, setup_chat_overlay)
+            .add_systems(Update, (
+                chat_input.after(handle_lobby_input),
+                send_emotes.run_if(in_state(AppState::Playing).and_then(chat_closed)),
+                update_chat_overlay,
+                update_emote_bubbles,
+            ));
+    }
+}
+
+// Chat and emotes
+const CHAT_VISIBLE_SECONDS: f32 = 10.0;
+const CHAT_MAX_LINES: usize = 6;
+const CHAT_MAX_LENGTH: usize = 60;
+const CHAT_BURST: usize = 3; // Messages allowed per rate-limit window
+const CHAT_WINDOW_SECONDS: f64 = 5.0;
+const EMOTE_COOLDOWN_SECONDS: f64 = 1.0;
+const EMOTE_BUBBLE_SECONDS: f32 = 2.0;
+
+#[derive(Component)]
+struct ChatOverlayText;
+
+#[derive(Component)]
+struct EmoteBubble(Timer);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Emote {
+    GoodGame,
+    Wow,
+    Fire,
+}
+
+impl Emote {
+    const ALL: [Emote; 3] = [Emote::GoodGame, Emote::Wow, Emote::Fire];
+
+    // The bundled font has no emoji glyphs, so emotes are drawn as short text
+    fn label(self) -> &'static str {
+        match self {
+            Self::GoodGame => "gg",
+            Self::Wow => ":O",
+            Self::Fire => "FIRE!",
+        }
+    }
+
+    fn key(self) -> KeyCode {
+        match self {
+            Self::GoodGame => KeyCode::Digit1,
+            Self::Wow => KeyCode::Digit2,
+            Self::Fire => KeyCode::Digit3,
+        }
+    }
+}
+
+// Chat log and input line shared by the lobby and online games (host-relayed)
+#[derive(Resource)]
+pub struct Chat {
+    enabled: bool,
+    typing: Option<String>,
+    lines: Vec<(String, f32)>,
+    recent_sends: Vec<f64>,
+    last_emote: f64,
+}
+
+impl Default for Chat {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            typing: None,
+            lines: Vec::new(),
+            recent_sends: Vec::new(),
+            last_emote: f64::NEG_INFINITY,
+        }
+    }
+}
+
+impl Chat {
+    pub fn push(&mut self, line: String) {
+        self.lines.push((line, 0.0));
+        if self.lines.len() > CHAT_MAX_LINES {
+            self.lines.remove(0);
+        }
+    }
+
+    // Allows CHAT_BURST messages per CHAT_WINDOW_SECONDS
+    fn try_spend(&mut self, now: f64) -> bool {
+        self.recent_sends.retain(|sent| now - sent < CHAT_WINDOW_SECONDS);
+        if self.recent_sends.len() >= CHAT_BURST {
+            return false;
+        }
+        self.recent_sends.push(now);
+        true
+    }
+}
+
+pub fn chat_closed(chat: Res<Chat>) -> bool {
+    chat.typing.is_none()
+}
+
+// Shows an incoming chat/emote and, on the host, relays it to everyone else.
+// The host stamps the sender's lobby name so guests can't impersonate each other.
+pub fn receive_chat(
+    relay: &mut dyn RelayClient,
+    members: &[LobbyMember],
+    peer: PeerId,
+    message: LobbyMessage,
+    chat: &mut Chat,
+) {
+    let message = match message {
+        LobbyMessage::Chat { from, text } if relay.is_host() => {
+            let from = members.iter().find(|member| member.peer == peer).map_or(from, |member| member.name.clone());
+            LobbyMessage::Chat { from, text }
+        }
+        LobbyMessage::Emote { from, emote } if relay.is_host() => {
+            let from = members.iter().find(|member| member.peer == peer).map_or(from, |member| member.name.clone());
+            LobbyMessage::Emote { from, emote }
+        }
+        other => other,
+    };
+    if relay.is_host() {
+        relay.send(Recipient::Everyone, &message);
+    }
+    if chat.enabled {
+        match message {
+            LobbyMessage::Chat { from, text } => chat.push(format!("{}: {}", from, text)),
+            LobbyMessage::Emote { from, emote } => chat.push(format!("{} {}", from, emote)),
+            _ => {}
+        }
+    }
+}
+
+// Chat and Emote Systems
+fn setup_chat_overlay(mut commands: Commands) {
+    // Screen-space so it stays readable whatever the camera is doing
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font_size: 20.0,
+                color: Color::srgb(1.0, 1.0, 1.0),
+                ..default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(40.0),
+            left: Val::Px(10.0),
+            ..default()
+        }),
+        ChatOverlayText,
+    ));
+}
+
+fn chat_input(
+    time: Res<Time>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut typed_keys: EventReader<KeyboardInput>,
+    mut chat: ResMut<Chat>,
+    mut lobby: ResMut<Lobby>,
+    mut session: ResMut<NetSession>,
+) {
+    // Drain every frame so the T that opens the prompt isn't typed into it
+    let typed: Vec<KeyboardInput> = typed_keys.read().filter(|event| event.state.is_pressed()).cloned().collect();
+
+    if keyboard_input.just_pressed(KeyCode::F7) {
+        chat.enabled = !chat.enabled;
+        chat.typing = None;
+    }
+
+    let lobby_connected = lobby.stage == LobbyStage::Connected;
+    let relay = if lobby_connected { lobby.relay.as_deref_mut() } else { session.relay.as_deref_mut() };
+    let Some(relay) = relay else {
+        chat.typing = None;
+        return;
+    };
+
+    let Some(mut line) = chat.typing.take() else {
+        if chat.enabled && keyboard_input.just_pressed(KeyCode::KeyT) {
+            chat.typing = Some(String::new());
+        }
+        return;
+    };
+
+    for event in &typed {
+        match &event.logical_key {
+            Key::Character(characters) => {
+                for character in characters.chars() {
+                    if line.chars().count() < CHAT_MAX_LENGTH {
+                        line.push(character);
+                    }
+                }
+            }
+            Key::Space if line.chars().count() < CHAT_MAX_LENGTH => line.push(' '),
+            Key::Backspace => {
+                line.pop();
+            }
+            Key::Escape => return,
+            Key::Enter => {
+                let text = line.trim().to_string();
+                if text.is_empty() {
+                    return;
+                }
+                if !chat.try_spend(time.elapsed_seconds_f64()) {
+                    chat.push("(slow down - chat is rate limited)".to_string());
+                    return;
+                }
+                let message = LobbyMessage::Chat { from: local_player_name(), text: text.clone() };
+                if relay.is_host() {
+                    // The host is the relay, so show our own line straight away
+                    chat.push(format!("{}: {}", local_player_name(), text));
+                    relay.send(Recipient::Everyone, &message);
+                } else {
+                    // Guests see their line when the host echoes it back
+                    relay.send(Recipient::Host, &message);
+                }
+                return;
+            }
+            _ => {}
+        }
+    }
+    chat.typing = Some(line);
+}
+
+fn send_emotes(
+    time: Res<Time>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut chat: ResMut<Chat>,
+    mut session: ResMut<NetSession>,
+    mut commands: Commands,
+    player_query: Query<(Entity, Option<&PartyPlayer>), With<Player>>,
+) {
+    if !chat.enabled {
+        return;
+    }
+    let Some(emote) = Emote::ALL.into_iter().find(|emote| keyboard_input.just_pressed(emote.key())) else {
+        return;
+    };
+
+    let now = time.elapsed_seconds_f64();
+    if now - chat.last_emote < EMOTE_COOLDOWN_SECONDS || !chat.try_spend(now) {
+        return;
+    }
+    chat.last_emote = now;
+
+    // Bubble over the local player (player 1 when several share the keyboard)
+    let local_player = player_query
+        .iter()
+        .find(|(_, party_player)| party_player.is_none_or(|party_player| party_player.slot == 0));
+    if let Some((player, _)) = local_player {
+        commands.entity(player).with_children(|parent| {
+            parent.spawn((
+                Text2dBundle {
+                    text: Text::from_section(
+                        emote.label(),
+                        TextStyle {
+                            font_size: 28.0,
+                            color: Color::srgb(1.0, 1.0, 1.0),
+                            ..default()
+                        },
+                    ),
+                    transform: Transform::from_translation(Vec3::new(0.0, 70.0, 2.0)),
+                    ..default()
+                },
+                EmoteBubble(Timer::from_seconds(EMOTE_BUBBLE_SECONDS, TimerMode::Once)),
+                Transient::new(EMOTE_BUBBLE_SECONDS),
+                WorldSpace,
+            ));
+        });
+    }
+
+    // Remote players can't see our sprite, so the emote goes to their chat log
+    if let Some(relay) = session.relay.as_deref_mut() {
+        let message = LobbyMessage::Emote { from: local_player_name(), emote: emote.label().to_string() };
+        let recipient = if relay.is_host() { Recipient::Everyone } else { Recipient::Host };
+        relay.send(recipient, &message);
+    }
+}
+
+fn update_chat_overlay(time: Res<Time>, mut chat: ResMut<Chat>, mut overlay_query: Query<&mut Text, With<ChatOverlayText>>) {
+    for (_, age) in chat.lines.iter_mut() {
+        *age += time.delta_seconds();
+    }
+    chat.lines.retain(|(_, age)| *age < CHAT_VISIBLE_SECONDS);
+
+    let Ok(mut text) = overlay_query.get_single_mut() else {
+        return;
+    };
+    let mut overlay = String::new();
+    if chat.enabled {
+        for (line, _) in &chat.lines {
+            overlay.push_str(line);
+            overlay.push('\n');
+        }
+        if let Some(typing) = &chat.typing {
+            overlay.push_str(&format!("> {}_", typing));
+        }
+    }
+    text.sections[0].value = overlay;
+}
+
+fn update_emote_bubbles(
+    time: Res<Time>,
+    mut commands: Commands,
+    mut bubble_query: Query<(Entity, &mut EmoteBubble, &mut Transform)>,
+) {
+    for (entity, mut bubble, mut transform) in bubble_query.iter_mut() {
+        // Drift upwards while visible
+        transform.translation.y += 20.0 * time.delta_seconds();
+        if bubble.0.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
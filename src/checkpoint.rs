@@ -0,0 +1,123 @@
+//! Checkpoints in Classic levels.
+//!
+//! Each checkpoint is a flag on a platform (see `rustbevy_core::checkpoint` for
+//! where generated levels put them). Touching one moves the `RespawnPoint`
+//! there, so losing a life brings the player back at that flag with the level
+//! as it is, rather than at the level's spawn point. The flag the player will
+//! respawn at is bright green, ones already touched are dimmed and the rest
+//! are grey. A new level (or restarting this one) starts from its spawn point
+//! again.
+
+use bevy::prelude::*;
+use crate::prelude::*;
+use rustbevy_core::checkpoint::{respawn_point, touches_checkpoint, CHECKPOINT_HEIGHT, CHECKPOINT_WIDTH};
+
+use crate::physics::player_body;
+use crate::ui::Toasts;
+
+pub struct CheckpointPlugin;
+
+impl Plugin for CheckpointPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RespawnPoint>()
+            .add_systems(Update, (reach_checkpoints, color_checkpoints)
+                .chain()
+                .run_if(in_state(AppState::Playing).and_then(resource_equals(GameMode::Classic))));
+    }
+}
+
+const POLE_COLOR: Color = Color::srgb(0.85, 0.85, 0.85);
+const UNREACHED_COLOR: Color = Color::srgb(0.45, 0.45, 0.45);
+const REACHED_COLOR: Color = Color::srgb(0.2, 0.5, 0.2);
+const ACTIVE_COLOR: Color = Color::srgb(0.2, 1.0, 0.3);
+const PENNANT_SIZE: Vec2 = Vec2::new(26.0, 16.0);
+
+// The pennant on a checkpoint's pole, recoloured as it's reached
+#[derive(Component)]
+struct CheckpointPennant;
+
+/// A checkpoint flag whose pole stands at `(x, y)`
+pub fn spawn_checkpoint(commands: &mut Commands, (x, y): (f32, f32)) {
+    commands
+        .spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: POLE_COLOR,
+                    custom_size: Some(Vec2::new(CHECKPOINT_WIDTH, CHECKPOINT_HEIGHT)),
+                    ..default()
+                },
+                transform: Transform::from_translation(Vec3::new(x, y + CHECKPOINT_HEIGHT / 2.0, -0.5)),
+                ..default()
+            },
+            Checkpoint { position: Vec2::new(x, y), reached: false },
+        ))
+        .with_children(|pole| {
+            pole.spawn((
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: UNREACHED_COLOR,
+                        custom_size: Some(PENNANT_SIZE),
+                        ..default()
+                    },
+                    transform: Transform::from_translation(Vec3::new(
+                        (CHECKPOINT_WIDTH + PENNANT_SIZE.x) / 2.0,
+                        (CHECKPOINT_HEIGHT - PENNANT_SIZE.y) / 2.0,
+                        0.1,
+                    )),
+                    ..default()
+                },
+                CheckpointPennant,
+            ));
+        });
+}
+
+fn reach_checkpoints(
+    player_query: Query<(&Transform, &Velocity, &Grounded, &Collider), With<Player>>,
+    mut checkpoint_query: Query<&mut Checkpoint>,
+    mut respawn: ResMut<RespawnPoint>,
+    mut toasts: ResMut<Toasts>,
+    mut sound_events: EventWriter<PlaySoundEvent>,
+) {
+    // Either co-op player reaching a checkpoint moves the shared respawn point
+    let bodies: Vec<_> = player_query
+        .iter()
+        .map(|(transform, velocity, grounded, collider)| player_body(transform, velocity, grounded, collider))
+        .collect();
+    for mut checkpoint in checkpoint_query.iter_mut() {
+        let position = (checkpoint.position.x, checkpoint.position.y);
+        if !bodies.iter().any(|body| touches_checkpoint(body, position)) {
+            continue;
+        }
+        let point = Vec2::from(respawn_point(position));
+        if respawn.0 != point {
+            respawn.0 = point;
+        }
+        if !checkpoint.reached {
+            checkpoint.reached = true;
+            toasts.push("Checkpoint!".to_string());
+            sound_events.send(PlaySoundEvent { sound_type: SoundType::LevelUp, position: None });
+        }
+    }
+}
+
+fn color_checkpoints(
+    respawn: Res<RespawnPoint>,
+    checkpoint_query: Query<(Ref<Checkpoint>, &Children)>,
+    mut pennant_query: Query<&mut Sprite, With<CheckpointPennant>>,
+) {
+    for (checkpoint, children) in checkpoint_query.iter() {
+        if !respawn.is_changed() && !checkpoint.is_changed() {
+            continue;
+        }
+        let active = respawn.0 == Vec2::from(respawn_point((checkpoint.position.x, checkpoint.position.y)));
+        let color = match (active, checkpoint.reached) {
+            (true, _) => ACTIVE_COLOR,
+            (false, true) => REACHED_COLOR,
+            (false, false) => UNREACHED_COLOR,
+        };
+        let mut pennants = pennant_query.iter_many_mut(children);
+        while let Some(mut sprite) = pennants.fetch_next() {
+            sprite.color = color;
+        }
+    }
+}
@@ -0,0 +1,198 @@
+// Hatchling companion for Classic runs.
+//
+// C hatches a little chick (or sends it home again). It trails the player
+// along the path they took a moment ago, so it hops where they hopped. E sends
+// it to fetch the nearest `Fetchable` pickup in range, and it squeaks whenever a
+// `Hazard` gets close to the player.
+
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+use crate::prelude::*;
+
+use crate::chat::chat_closed;
+use crate::ui::Toasts;
+
+pub struct CompanionPlugin;
+
+impl Plugin for CompanionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CompanionSettings>()
+            .add_systems(Update, (
+                toggle_companion.run_if(chat_closed),
+                spawn_companion,
+                send_companion_to_fetch.run_if(in_state(AppState::Playing).and_then(chat_closed)),
+                move_companion.run_if(in_state(AppState::Playing)),
+                squeak_near_hazards.run_if(in_state(AppState::Playing)),
+            ).chain());
+    }
+}
+
+// How far behind the player the chick walks, in seconds of their path
+const FOLLOW_DELAY_SECONDS: f32 = 0.35;
+const COMPANION_SIZE: f32 = 20.0;
+const FETCH_RADIUS: f32 = 250.0;
+const FETCH_SPEED: f32 = 400.0;
+// Close enough to pick something up
+const FETCH_REACH: f32 = 15.0;
+const HAZARD_RADIUS: f32 = 140.0;
+const SQUEAK_COOLDOWN_SECONDS: f32 = 1.5;
+
+#[derive(Resource, Default)]
+pub struct CompanionSettings {
+    enabled: bool,
+}
+
+#[derive(Component, Default)]
+pub struct Companion {
+    // Where the player's feet were, with the game time they were there
+    history: VecDeque<(f32, Vec2)>,
+    fetching: Option<Entity>,
+    squeak_cooldown: f32,
+}
+
+fn in_classic_run(app_state: &State<AppState>, game_mode: &GameMode) -> bool {
+    matches!(app_state.get(), AppState::Playing | AppState::ModifierDraft | AppState::Paused | AppState::DeathRecap | AppState::HudEditor) && *game_mode == GameMode::Classic
+}
+
+pub fn toggle_companion(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    app_state: Res<State<AppState>>,
+    game_mode: Res<GameMode>,
+    mut settings: ResMut<CompanionSettings>,
+) {
+    if keyboard_input.just_pressed(KeyCode::KeyC) && *app_state.get() == AppState::Playing && *game_mode == GameMode::Classic {
+        settings.enabled = !settings.enabled;
+    }
+}
+
+// Hatches the chick next to the player, or removes it when it isn't wanted
+pub fn spawn_companion(
+    mut commands: Commands,
+    app_state: Res<State<AppState>>,
+    game_mode: Res<GameMode>,
+    settings: Res<CompanionSettings>,
+    player_query: Query<&Transform, With<LeadPlayer>>,
+    companion_query: Query<Entity, With<Companion>>,
+) {
+    let wanted = settings.enabled && in_classic_run(&app_state, &game_mode);
+    if !wanted {
+        for entity in companion_query.iter() {
+            commands.entity(entity).despawn();
+        }
+        return;
+    }
+    let Ok(player_transform) = player_query.get_single() else {
+        return;
+    };
+    if companion_query.is_empty() {
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: Color::srgb(1.0, 0.95, 0.4), // Chick yellow
+                    custom_size: Some(Vec2::splat(COMPANION_SIZE)),
+                    ..default()
+                },
+                transform: Transform::from_translation(player_transform.translation.truncate().extend(1.0)),
+                ..default()
+            },
+            Companion::default(),
+        ));
+    }
+}
+
+pub fn send_companion_to_fetch(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    player_query: Query<&Transform, With<LeadPlayer>>,
+    fetchable_query: Query<(Entity, &Transform), With<Fetchable>>,
+    mut companion_query: Query<&mut Companion>,
+    mut toasts: ResMut<Toasts>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::KeyE) {
+        return;
+    }
+    let (Ok(player_transform), Ok(mut companion)) = (player_query.get_single(), companion_query.get_single_mut()) else {
+        return;
+    };
+    let player = player_transform.translation.truncate();
+    let nearest = fetchable_query
+        .iter()
+        .map(|(entity, transform)| (entity, transform.translation.truncate().distance(player)))
+        .filter(|(_, distance)| *distance <= FETCH_RADIUS)
+        .min_by(|a, b| a.1.total_cmp(&b.1));
+    match nearest {
+        Some((entity, _)) => companion.fetching = Some(entity),
+        None => toasts.push("Nothing nearby to fetch"),
+    }
+}
+
+// Walks the chick along the player's path, or off to fetch something
+pub fn move_companion(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut game_state: ResMut<GameState>,
+    player_query: Query<(&Transform, &Collider), (With<LeadPlayer>, Without<Companion>)>,
+    fetchable_query: Query<(&Transform, &Fetchable), Without<Companion>>,
+    mut companion_query: Query<(&mut Transform, &mut Companion)>,
+    mut sound_events: EventWriter<PlaySoundEvent>,
+) {
+    let Ok((mut transform, mut companion)) = companion_query.get_single_mut() else {
+        return;
+    };
+    let now = time.elapsed_seconds();
+
+    if let Ok((player_transform, collider)) = player_query.get_single() {
+        let feet = player_transform.translation.truncate() - Vec2::new(0.0, collider.size / 2.0);
+        companion.history.push_back((now, feet));
+    }
+
+    if let Some(target) = companion.fetching {
+        let Ok((target_transform, fetchable)) = fetchable_query.get(target) else {
+            companion.fetching = None;
+            return;
+        };
+        let position = transform.translation.truncate();
+        let to_target = target_transform.translation.truncate() - position;
+        if to_target.length() <= FETCH_REACH {
+            game_state.score += fetchable.score;
+            commands.entity(target).despawn();
+            sound_events.send(PlaySoundEvent { sound_type: SoundType::Collect, position: None });
+            companion.fetching = None;
+        } else {
+            let step = to_target.clamp_length_max(FETCH_SPEED * time.delta_seconds());
+            transform.translation += step.extend(0.0);
+        }
+        return;
+    }
+
+    // Drop the path older than the follow delay, keeping the point to stand on
+    while companion.history.len() > 1 && companion.history[1].0 <= now - FOLLOW_DELAY_SECONDS {
+        companion.history.pop_front();
+    }
+    if let Some(&(_, feet)) = companion.history.front() {
+        transform.translation.x = feet.x;
+        transform.translation.y = feet.y + COMPANION_SIZE / 2.0;
+    }
+}
+
+pub fn squeak_near_hazards(
+    time: Res<Time>,
+    player_query: Query<&Transform, With<LeadPlayer>>,
+    hazard_query: Query<&Transform, With<Hazard>>,
+    mut companion_query: Query<&mut Companion>,
+    mut sound_events: EventWriter<PlaySoundEvent>,
+) {
+    let (Ok(player_transform), Ok(mut companion)) = (player_query.get_single(), companion_query.get_single_mut()) else {
+        return;
+    };
+    companion.squeak_cooldown = (companion.squeak_cooldown - time.delta_seconds()).max(0.0);
+    let player = player_transform.translation.truncate();
+    let hazard_close = hazard_query
+        .iter()
+        .map(|transform| transform.translation.truncate())
+        .find(|hazard| hazard.distance(player) <= HAZARD_RADIUS);
+    if hazard_close.is_some() && companion.squeak_cooldown == 0.0 {
+        sound_events.send(PlaySoundEvent { sound_type: SoundType::Squeak, position: hazard_close });
+        companion.squeak_cooldown = SQUEAK_COOLDOWN_SECONDS;
+    }
+}
@@ -0,0 +1,342 @@
+// Gameplay components shared with extension plugins
+
+use bevy::prelude::*;
+use rustbevy_core::budget::BudgetCategory;
+use rustbevy_core::collectible::Collectible;
+use rustbevy_core::crumbling::CrumblingPlatform;
+use rustbevy_core::enemy::EnemyAi;
+use rustbevy_core::exit::ExitDoor as DoorSpec;
+use rustbevy_core::flee::FleeingFruit;
+use rustbevy_core::generation::PlatformSpec;
+use rustbevy_core::fruit::FruitKind;
+use rustbevy_core::hazard::HazardTile;
+use rustbevy_core::ladder::Ladder as LadderSpan;
+use rustbevy_core::look::PlatformLook;
+use rustbevy_core::moving::PlatformMotion;
+use rustbevy_core::physics::JumpTimers;
+use rustbevy_core::portal::Portal;
+use rustbevy_core::projectile::Projectile;
+use rustbevy_core::status::StatusEffects;
+use rustbevy_core::surface::Surface;
+use rustbevy_core::trap::TrapPlatform;
+use rustbevy_core::wind::{Conveyor, Wind};
+
+use crate::resources::InputMap;
+
+#[derive(Component)]
+pub struct Player;
+
+#[derive(Component)]
+pub struct Platform {
+    pub width: f32,
+    pub height: f32,
+    // See `PlatformSpec::slope`; the sprite is turned to match
+    pub slope: f32,
+}
+
+impl Platform {
+    // The platform at `transform`, for the shared rules
+    pub fn spec(&self, transform: &Transform, one_way: bool) -> PlatformSpec {
+        PlatformSpec {
+            x: transform.translation.x,
+            y: transform.translation.y,
+            width: self.width,
+            height: self.height,
+            one_way,
+            slope: self.slope,
+        }
+    }
+}
+
+// What a Classic-mode platform is made of, for footstep and landing sounds;
+// platforms without one are stone
+#[derive(Component, Clone, Copy)]
+pub struct PlatformSurface(pub Surface);
+
+// How a Classic-mode platform is dressed up; looks only, see `dressing`
+#[derive(Component, Clone, Copy)]
+pub struct PlatformDressing(pub PlatformLook);
+
+// A Classic-mode platform players can jump up through, and drop down through
+// with down + jump
+#[derive(Component)]
+pub struct OneWay;
+
+// A platform following a path; players standing on it ride along
+#[derive(Component)]
+pub struct MovingPlatform(pub PlatformMotion);
+
+// A platform that falls a moment after someone lands on it and comes back later
+#[derive(Component)]
+pub struct Crumbling(pub CrumblingPlatform);
+
+// A platform that tips over or launches the player sideways when stood on
+#[derive(Component)]
+pub struct Trap(pub TrapPlatform);
+
+// A conveyor belt platform; players standing on it are carried along
+#[derive(Component)]
+pub struct ConveyorPlatform(pub Conveyor);
+
+// A ladder or rope players climb with up and down
+#[derive(Component)]
+pub struct Ladder(pub LadderSpan);
+
+#[derive(Component)]
+pub struct Fruit;
+
+// What a Classic-mode pickup is: the level's fruit (which also has `Fruit`)
+// or one of the power-ups
+#[derive(Component, Clone, Copy)]
+pub struct Pickup(pub Collectible);
+
+// Timed effects on a Classic-mode player: a cursed fruit's debuff or the
+// power-ups they picked up
+#[derive(Component, Clone, Default)]
+pub struct PlayerStatus(pub StatusEffects);
+
+// A checkpoint flag in a Classic level; `position` is the foot of its pole
+#[derive(Component)]
+pub struct Checkpoint {
+    pub position: Vec2,
+    // The player has touched it this level
+    pub reached: bool,
+}
+
+// A door level's exit; players walk in to finish the level once they hold
+// every key to it between them
+#[derive(Component)]
+pub struct ExitDoor {
+    pub spec: DoorSpec,
+    pub keys_held: usize,
+}
+
+impl ExitDoor {
+    pub fn is_open(&self) -> bool {
+        self.spec.opens_with(self.keys_held)
+    }
+}
+
+// A key to the level's exit door, lying where it can be picked up
+#[derive(Component)]
+pub struct Key;
+
+// Blessed, cursed or fleeing variant of a Classic-mode fruit; plain fruit has none
+#[derive(Component)]
+pub struct FruitVariant(pub FruitKind);
+
+// Where a fleeing fruit is running to and how many hops it has left
+#[derive(Component)]
+pub struct Fleeing(pub FleeingFruit);
+
+// Which animation a player's sprite is playing, from how they're moving
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AnimationState {
+    #[default]
+    Idle,
+    Run,
+    Jump,
+    Fall,
+    // Standing still with its middle out over an edge
+    Teeter,
+}
+
+#[derive(Component)]
+pub struct Velocity {
+    pub x: f32,
+    pub y: f32,
+}
+
+#[derive(Component)]
+pub struct Grounded(pub bool);
+
+// The platform the last collision check left the player standing on, taken
+// away when they leave it. Moving platforms carry whoever stands on them and
+// crumbling platforms start to crumble under them.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StandingOn(pub Entity);
+
+// The ladder a player is climbing. Climbing players are left out of running,
+// gravity and the collision checks; the ladder moves them.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Climbing(pub Entity);
+
+// The side (-1.0 left, 1.0 right) of a platform the player touched in mid-air
+// at the last collision check, for wall slides and wall jumps
+#[derive(Component, Default)]
+pub struct WallContact(pub Option<f32>);
+
+// Coyote time, a queued jump and mid-air jumps left, kept by player movement
+#[derive(Component, Default)]
+pub struct JumpState(pub JumpTimers);
+
+// A jump pressed since the last physics step. Input is read every frame but
+// physics steps at a fixed rate, so the press waits here for the next step.
+#[derive(Component, Default)]
+pub struct JumpPress(pub bool);
+
+// Square collision box of a player; the sprite is drawn at the same size
+#[derive(Component)]
+pub struct Collider {
+    pub size: f32,
+}
+
+// Keys driving a single player entity, so several players can share one
+// keyboard. The Classic player's follow the `InputMap`.
+#[derive(Component)]
+pub struct PlayerControls {
+    pub left: Vec<KeyCode>,
+    pub right: Vec<KeyCode>,
+    pub jump: Vec<KeyCode>,
+    pub up: Vec<KeyCode>,
+    pub down: Vec<KeyCode>,
+}
+
+impl PlayerControls {
+    // The default keys; rebound keys are applied once the player is spawned
+    pub fn single_player() -> Self {
+        InputMap::default().player_controls()
+    }
+
+    pub fn party_slot(slot: usize) -> Self {
+        let (left, right, jump, down) = match slot {
+            0 => (KeyCode::KeyA, KeyCode::KeyD, KeyCode::KeyW, KeyCode::KeyS),
+            1 => (KeyCode::ArrowLeft, KeyCode::ArrowRight, KeyCode::ArrowUp, KeyCode::ArrowDown),
+            2 => (KeyCode::KeyJ, KeyCode::KeyL, KeyCode::KeyI, KeyCode::KeyK),
+            _ => (KeyCode::Numpad4, KeyCode::Numpad6, KeyCode::Numpad8, KeyCode::Numpad5),
+        };
+        Self {
+            left: vec![left],
+            right: vec![right],
+            jump: vec![jump],
+            // Up climbs with the jump key, as up is also jump by default
+            up: vec![jump],
+            down: vec![down],
+        }
+    }
+
+    pub fn any_pressed(keyboard_input: &ButtonInput<KeyCode>, keys: &[KeyCode]) -> bool {
+        keys.iter().any(|key| keyboard_input.pressed(*key))
+    }
+
+    pub fn any_just_pressed(keyboard_input: &ButtonInput<KeyCode>, keys: &[KeyCode]) -> bool {
+        keys.iter().any(|key| keyboard_input.just_pressed(*key))
+    }
+}
+
+// The second player of a co-op Classic run. It's a `Player` like the first,
+// with its own controls, velocity and status effects.
+#[derive(Component)]
+pub struct CoopPartner;
+
+// The player the controller (or a replay being played back) drives alongside
+// their own keys, and the one single-player features follow: the seed pouch,
+// rewind, replays, the companion and the practice and debug readouts. It's
+// the Classic player, the first in co-op; a co-op partner and party players
+// play their keys only. Those features find their player by this rather than
+// by leaving others out, so they keep working whoever else joins; everything
+// else treats every `Player` alike.
+#[derive(Component)]
+pub struct LeadPlayer;
+
+// Party mode player marker (slot 0-3)
+#[derive(Component)]
+pub struct PartyPlayer {
+    pub slot: usize,
+}
+
+// A Classic-mode enemy; touching it costs a life like falling does
+#[derive(Component)]
+pub struct Enemy(pub EnemyAi);
+
+// A small pickup (a coin or the like) worth `score` points that the companion
+// can be sent to fetch
+#[derive(Component)]
+pub struct Fetchable {
+    pub score: u32,
+}
+
+// Anything dangerous to touch; the companion squeaks when one is near the player
+#[derive(Component)]
+pub struct Hazard;
+
+// Spikes or a lava pool; touching it costs a life
+#[derive(Component)]
+pub struct HazardArea(pub HazardTile);
+
+// A box of wind; players inside it drift the way it blows
+#[derive(Component)]
+pub struct WindZone(pub Wind);
+
+// One end of a pair of portals; players walking in come out at the other
+#[derive(Component)]
+pub struct PortalEnd {
+    pub portal: Portal,
+    // Which of the level's pairs it belongs to, for its color
+    pub pair: usize,
+}
+
+// A seed the Classic player threw, in flight
+#[derive(Component)]
+pub struct ThrownSeed(pub Projectile);
+
+// The 2D camera rendering the world; it follows the Classic player around
+#[derive(Component)]
+pub struct MainCamera;
+
+// The 2D camera rendering the HUD, menus and other screen text on top of the
+// world. It never moves, so anything it draws stays put on screen.
+#[derive(Component)]
+pub struct HudCamera;
+
+// A sprite drawn by the HUD camera (menu backdrops, overlays, ...). World text
+// (`Text2dBundle`) is drawn by the HUD camera unless marked `WorldSpace`.
+#[derive(Component, Clone, Copy)]
+pub struct ScreenSpace;
+
+// Text that belongs to something in the world (a label above the player, ...)
+// and moves with the world camera
+#[derive(Component)]
+pub struct WorldSpace;
+
+// Drawn by a camera rendering to an image rather than the window (the run
+// summary card); it keeps its own render layer and is never mirrored
+#[derive(Component)]
+pub struct Offscreen;
+
+// An entity that counts against its category's `EntityBudget` cap. Over the
+// cap, the lowest `priority` goes first, and the newest among equals.
+#[derive(Component)]
+pub struct Budgeted {
+    pub category: BudgetCategory,
+    pub priority: u8,
+    // Set when the budget first sees it
+    pub spawn_order: u64,
+}
+
+impl Budgeted {
+    pub fn new(category: BudgetCategory, priority: u8) -> Self {
+        Self { category, priority, spawn_order: 0 }
+    }
+}
+
+// A short-lived effect (a particle, a projectile, floating text). It is
+// despawned once it has been around for `max_seconds` or has drifted well out
+// of its camera's view, whichever comes first, so effects nobody tidies up
+// can't pile up over a long run.
+#[derive(Component)]
+pub struct Transient {
+    pub age: f32,
+    pub max_seconds: f32,
+}
+
+impl Transient {
+    pub fn new(max_seconds: f32) -> Self {
+        Self { age: 0.0, max_seconds }
+    }
+}
+
+// Dialogue (or anything else that should be heard over the soundtrack): the
+// music stays ducked while an entity with this is around
+#[derive(Component)]
+pub struct DucksMusic;
@@ -0,0 +1,223 @@
+// Crash reports.
+//
+// A panic hook writes a report to the `crashes` folder in the save directory:
+// the panic message and where it happened, a backtrace, and what the game was
+// doing (screen, mode, level, lives, score, run seed). The hook can't look at
+// the world, so `record_crash_context` keeps a summary of it up to date for
+// the hook to copy. The next launch opens the Crash Report screen after the
+// loading screen: V shows the report, E copies it to the folder the game was
+// started from (to attach to a bug report) and ENTER carries on to the main
+// menu. Each report is offered once.
+
+use std::backtrace::Backtrace;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use bevy::prelude::*;
+use crate::prelude::*;
+
+use crate::game::{save_path, unix_seconds};
+use crate::platform::CurrentLevel;
+use crate::ui::{despawn_screen, Toasts};
+
+pub struct CrashReportPlugin;
+
+impl Plugin for CrashReportPlugin {
+    fn build(&self, app: &mut App) {
+        install_panic_hook();
+        app.insert_resource(PendingCrashReport { report: PendingCrashReport::load(), viewing: false })
+            .add_systems(OnEnter(AppState::CrashReport), setup_crash_screen)
+            .add_systems(OnExit(AppState::CrashReport), despawn_screen::<CrashReportUI>)
+            .add_systems(Update, (
+                record_crash_context,
+                handle_crash_screen_input.run_if(in_state(AppState::CrashReport)),
+            ));
+    }
+}
+
+const CRASH_FOLDER: &str = "crashes";
+// Names the report the next launch hasn't offered yet
+const UNREAD_FILE: &str = "unread.txt";
+// Report lines that fit on the screen when viewing it
+const VIEW_LINES: usize = 28;
+
+// What the game was doing, for the next crash report
+static CRASH_CONTEXT: Mutex<String> = Mutex::new(String::new());
+
+// The report from the last session's crash, if it hasn't been offered yet
+#[derive(Resource, Default)]
+pub struct PendingCrashReport {
+    report: Option<(PathBuf, String)>,
+    viewing: bool,
+}
+
+impl PendingCrashReport {
+    fn load() -> Option<(PathBuf, String)> {
+        let folder = save_path(CRASH_FOLDER);
+        let name = std::fs::read_to_string(folder.join(UNREAD_FILE)).ok()?;
+        let path = folder.join(name.trim());
+        let contents = std::fs::read_to_string(&path).ok()?;
+        Some((path, contents))
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.report.is_some()
+    }
+}
+
+#[derive(Component)]
+struct CrashReportUI;
+
+#[derive(Component)]
+struct CrashReportBody;
+
+fn install_panic_hook() {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        // Still print the panic to the console as usual
+        default_hook(info);
+
+        let message = info
+            .payload()
+            .downcast_ref::<&str>()
+            .map(|message| message.to_string())
+            .or_else(|| info.payload().downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "(no message)".to_string());
+        let location = info.location().map_or("unknown".to_string(), |location| location.to_string());
+        let thread = std::thread::current().name().unwrap_or("unnamed").to_string();
+        let context = crash_context();
+        let seconds = unix_seconds();
+
+        let report = format!(
+            "Bevy Platformer crash report\nVersion: {}\nTime: {} (seconds since 1970)\n\nPanic: {}\nAt: {}\nThread: {}\n\nGame:\n{}\n\nBacktrace:\n{}\n",
+            env!("CARGO_PKG_VERSION"),
+            seconds,
+            message,
+            location,
+            thread,
+            if context.is_empty() { "  (not started)".to_string() } else { context },
+            Backtrace::force_capture()
+        );
+        let name = format!("crash-{}.txt", seconds);
+        let folder = save_path(CRASH_FOLDER);
+        let written = std::fs::create_dir_all(&folder)
+            .and_then(|_| std::fs::write(folder.join(&name), report))
+            .and_then(|_| std::fs::write(folder.join(UNREAD_FILE), &name));
+        match written {
+            Ok(()) => eprintln!("Crash report saved to {}", folder.join(&name).display()),
+            Err(error) => eprintln!("Could not save a crash report to {}: {}", folder.display(), error),
+        }
+    }));
+}
+
+// What the game is doing, as a crash report would put it (empty before the
+// first frame)
+pub fn crash_context() -> String {
+    // A panic while the summary was being written leaves the lock poisoned
+    CRASH_CONTEXT.lock().map_or_else(|poisoned| poisoned.into_inner().clone(), |context| context.clone())
+}
+
+// Keeps the summary the panic hook copies into a report up to date
+fn record_crash_context(
+    app_state: Res<State<AppState>>,
+    game_mode: Res<GameMode>,
+    game_state: Res<GameState>,
+    game_rng: Res<GameRng>,
+    current_level: Option<Res<CurrentLevel>>,
+) {
+    let level_changed = current_level.as_ref().is_some_and(|level| level.is_changed());
+    if !(app_state.is_changed() || game_mode.is_changed() || game_state.is_changed() || level_changed) {
+        return;
+    }
+    let mut lines = vec![
+        format!("  Screen: {:?}", app_state.get()),
+        format!("  Mode: {:?}", *game_mode),
+        format!("  Level: {}, lives: {}, score: {}", game_state.level, game_state.lives, game_state.score),
+        format!("  Run seed: {}", game_rng.run_seed()),
+    ];
+    if let Some(level) = current_level {
+        lines.push(format!("  Level generated from seed {} at difficulty {:.2}", level.run_seed, level.difficulty));
+    }
+    if let Ok(mut context) = CRASH_CONTEXT.lock() {
+        *context = lines.join("\n");
+    }
+}
+
+fn crash_screen_text(text: impl Into<String>, font_size: f32, color: Color, y: f32) -> Text2dBundle {
+    Text2dBundle {
+        text: Text::from_section(text, TextStyle { font_size, color, ..default() }),
+        transform: Transform::from_translation(Vec3::new(0.0, y, 10.0)),
+        ..default()
+    }
+}
+
+fn setup_crash_screen(mut commands: Commands, pending: Res<PendingCrashReport>) {
+    let Some((path, contents)) = &pending.report else {
+        return;
+    };
+    let panic = contents
+        .lines()
+        .find_map(|line| line.strip_prefix("Panic: "))
+        .unwrap_or("(no message)");
+
+    commands.spawn((
+        crash_screen_text("The game crashed last time", 48.0, Color::srgb(1.0, 0.4, 0.4), 300.0), // Light red
+        CrashReportUI,
+    ));
+    commands.spawn((
+        crash_screen_text(format!("{}\nA crash report was saved to {}", panic, path.display()), 20.0, Color::srgb(0.8, 0.8, 0.8), 230.0),
+        CrashReportUI,
+    ));
+    commands.spawn((
+        crash_screen_text("V: View report   E: Export report   ENTER: Continue", 24.0, Color::srgb(1.0, 1.0, 0.0), -320.0), // Yellow
+        CrashReportUI,
+    ));
+    commands.spawn((
+        crash_screen_text("", 14.0, Color::srgb(0.7, 0.7, 0.7), -40.0),
+        CrashReportUI,
+        CrashReportBody,
+    ));
+}
+
+fn handle_crash_screen_input(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut pending: ResMut<PendingCrashReport>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut body_query: Query<&mut Text, With<CrashReportBody>>,
+    mut toasts: ResMut<Toasts>,
+    mut errors: EventWriter<RuntimeErrorEvent>,
+) {
+    let Some((path, contents)) = pending.report.clone() else {
+        next_state.set(AppState::MainMenu);
+        return;
+    };
+
+    if keyboard_input.just_pressed(KeyCode::KeyV) {
+        pending.viewing = !pending.viewing;
+        if let Ok(mut text) = body_query.get_single_mut() {
+            text.sections[0].value = if pending.viewing {
+                contents.lines().take(VIEW_LINES).collect::<Vec<_>>().join("\n")
+            } else {
+                String::new()
+            };
+        }
+    } else if keyboard_input.just_pressed(KeyCode::KeyE) {
+        let export = std::env::current_dir()
+            .map(|folder| folder.join(path.file_name().unwrap_or_default()))
+            .and_then(|export| std::fs::copy(&path, &export).map(|_| export));
+        match export {
+            Ok(export) => toasts.push(format!("Crash report exported to {}", export.display())),
+            Err(error) => {
+                errors.send(RuntimeErrorEvent::new("Could not export the crash report", format!("{}: {}", path.display(), error)));
+            }
+        }
+    } else if keyboard_input.any_just_pressed([KeyCode::Enter, KeyCode::Escape]) {
+        // Offered once; the report itself stays in the crash folder
+        let unread = save_path(CRASH_FOLDER).join(UNREAD_FILE);
+        if let Err(error) = std::fs::remove_file(&unread) {
+            errors.send(RuntimeErrorEvent::new("Could not clear the crash report", format!("{}: {}", unread.display(), error)));
+        }
+        *pending = PendingCrashReport::default();
+        next_state.set(AppState::MainMenu);
+    }
+}
@@ -0,0 +1,52 @@
+// Culling of transient effects.
+//
+// Anything marked `Transient` is despawned once it outlives its
+// `max_seconds` or once it is more than `CULL_MARGIN` outside the view of the
+// camera drawing it (the HUD camera for `ScreenSpace` sprites, the world camera
+// for everything else). Effects normally tidy themselves up; this is the
+// backstop for the ones that fly off screen or get left behind by a level
+// change, so long runs don't slowly fill up with them. How many may exist at
+// once is up to the entity budget (see `budget`).
+
+use bevy::prelude::*;
+use crate::prelude::*;
+use rustbevy_core::config::{WINDOW_HEIGHT, WINDOW_WIDTH};
+
+pub struct CullingPlugin;
+
+impl Plugin for CullingPlugin {
+    fn build(&self, app: &mut App) {
+        // Once transforms are up to date, so children are checked where they are
+        app.add_systems(PostUpdate, cull_transients.after(TransformSystem::TransformPropagate));
+    }
+}
+
+// How far outside a camera's view a transient may go before it's dropped
+const CULL_MARGIN: f32 = WINDOW_HEIGHT / 2.0;
+
+// What a camera shows, in world units
+pub fn camera_view(transform: &GlobalTransform, projection: &OrthographicProjection) -> Rect {
+    let area = projection.area;
+    let centre = transform.translation().truncate();
+    Rect::from_corners(centre + area.min, centre + area.max)
+}
+
+fn cull_transients(
+    time: Res<Time>,
+    mut commands: Commands,
+    world_camera_query: Query<(&GlobalTransform, &OrthographicProjection), With<MainCamera>>,
+    mut transient_query: Query<(Entity, &mut Transient, &GlobalTransform, Has<ScreenSpace>)>,
+) {
+    let screen_view = Rect::from_center_size(Vec2::ZERO, Vec2::new(WINDOW_WIDTH, WINDOW_HEIGHT)).inflate(CULL_MARGIN);
+    let world_view = world_camera_query
+        .get_single()
+        .map_or(screen_view, |(transform, projection)| camera_view(transform, projection).inflate(CULL_MARGIN));
+
+    for (entity, mut transient, transform, screen_space) in transient_query.iter_mut() {
+        transient.age += time.delta_seconds();
+        let view = if screen_space { screen_view } else { world_view };
+        if transient.age >= transient.max_seconds || !view.contains(transform.translation().truncate()) {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
@@ -0,0 +1,220 @@
+// Custom games (see `rustbevy_core::custom`).
+//
+// A on the main menu opens the Custom Game screen: UP/DOWN picks a row and
+// LEFT/RIGHT changes it, ENTER starts a Classic run with those numbers and ESC
+// returns to the menu. The run's levels come from the usual generator, fed a
+// `DifficultyCurve` made from the picks; the curve the game had before is put
+// back on returning to the menu. The screen also picks a theme to keep for
+// the whole run. Custom games don't set high scores and aren't recorded as replays.
+
+use bevy::prelude::*;
+use crate::prelude::*;
+use rustbevy_core::difficulty;
+
+use crate::chat::chat_closed;
+use crate::menu::MainMenuUI;
+use crate::theme::theme_title;
+use crate::ui::despawn_screen;
+
+pub struct CustomGamePlugin;
+
+impl Plugin for CustomGamePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CustomGameScreen>()
+            .init_resource::<UsualCurve>()
+            .add_systems(OnEnter(AppState::MainMenu), (end_custom_game, setup_custom_game_menu_line))
+            .add_systems(OnEnter(AppState::CustomGame), setup_custom_game_screen)
+            .add_systems(OnExit(AppState::CustomGame), despawn_screen::<CustomGameUI>)
+            .add_systems(Update, (
+                open_custom_game.run_if(in_state(AppState::MainMenu).and_then(chat_closed)),
+                (handle_custom_game_input, update_custom_game_lines).chain().run_if(in_state(AppState::CustomGame)),
+            ));
+    }
+}
+
+const SELECTED_COLOR: Color = Color::srgb(1.0, 1.0, 0.0); // Yellow
+const OPTION_COLOR: Color = Color::srgb(0.8, 0.8, 0.8);
+
+// The curve in use before a custom game replaced it
+#[derive(Resource, Default)]
+struct UsualCurve(Option<difficulty::DifficultyCurve>);
+
+// The rows of the Custom Game screen
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum CustomGameRow {
+    PlatformCount,
+    PlatformWidth,
+    Hazards,
+    Enemies,
+    Theme,
+}
+
+impl CustomGameRow {
+    const ALL: [Self; 5] = [Self::PlatformCount, Self::PlatformWidth, Self::Hazards, Self::Enemies, Self::Theme];
+
+    fn label(self, custom_game: &CustomGameMode) -> String {
+        let game = &custom_game.game;
+        match self {
+            Self::PlatformCount => format!("Platforms per screen: {}-{}", game.platforms_per_screen.0, game.platforms_per_screen.1),
+            Self::PlatformWidth => format!("Platform width: {:.0}-{:.0}", game.platform_widths.0, game.platform_widths.1),
+            Self::Hazards => format!("Spikes and lava: {}", game.hazards.name()),
+            Self::Enemies => format!("Enemies per level: {}", game.enemies),
+            Self::Theme => format!("Theme: {}", game.theme.map_or("Changes every 5 levels", theme_title)),
+        }
+    }
+
+    // Moves the row one step forward (`steps` > 0) or back; the lists wrap around
+    fn change(self, custom_game: &mut CustomGameMode, steps: i32) {
+        match self {
+            Self::PlatformCount => custom_game.game.step_platform_count(steps),
+            Self::PlatformWidth => custom_game.game.step_platform_widths(steps),
+            Self::Hazards => custom_game.game.step_hazards(steps),
+            Self::Enemies => custom_game.game.step_enemies(steps),
+            Self::Theme => custom_game.game.step_theme(steps),
+        }
+    }
+}
+
+// The row picked on the Custom Game screen
+#[derive(Resource, Default)]
+struct CustomGameScreen {
+    selected: usize,
+}
+
+#[derive(Component)]
+struct CustomGameUI;
+
+#[derive(Component)]
+struct CustomGameLine(usize);
+
+fn setup_custom_game_menu_line(mut commands: Commands) {
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                "A: Custom Game (pick the platforms, hazards, enemies and theme)",
+                TextStyle {
+                    font_size: 20.0,
+                    color: Color::srgb(0.6, 0.8, 1.0), // Sky blue
+                    ..default()
+                },
+            ),
+            transform: Transform::from_translation(Vec3::new(0.0, 350.0, 10.0)),
+            ..default()
+        },
+        MainMenuUI,
+    ));
+}
+
+// Back on the menu, runs are the usual ones again
+fn end_custom_game(mut custom_game: ResMut<CustomGameMode>, mut usual_curve: ResMut<UsualCurve>, mut curve: ResMut<DifficultyCurve>) {
+    custom_game.active = false;
+    if let Some(usual) = usual_curve.0.take() {
+        curve.0 = usual;
+    }
+}
+
+fn open_custom_game(keyboard_input: Res<ButtonInput<KeyCode>>, mut next_state: ResMut<NextState<AppState>>) {
+    if keyboard_input.just_pressed(KeyCode::KeyA) {
+        next_state.set(AppState::CustomGame);
+    }
+}
+
+fn custom_game_line(index: usize, custom_game: &CustomGameMode, screen: &CustomGameScreen) -> (String, Color) {
+    let label = CustomGameRow::ALL[index].label(custom_game);
+    if index == screen.selected {
+        (format!("> {} <", label), SELECTED_COLOR)
+    } else {
+        (label, OPTION_COLOR)
+    }
+}
+
+fn setup_custom_game_screen(mut commands: Commands, custom_game: Res<CustomGameMode>, mut screen: ResMut<CustomGameScreen>) {
+    *screen = CustomGameScreen::default();
+
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                "CUSTOM GAME",
+                TextStyle {
+                    font_size: 60.0,
+                    color: Color::srgb(0.6, 0.8, 1.0), // Sky blue
+                    ..default()
+                },
+            ),
+            transform: Transform::from_translation(Vec3::new(0.0, 260.0, 10.0)),
+            ..default()
+        },
+        CustomGameUI,
+    ));
+
+    for index in 0..CustomGameRow::ALL.len() {
+        let (line, color) = custom_game_line(index, &custom_game, &screen);
+        commands.spawn((
+            Text2dBundle {
+                text: Text::from_section(line, TextStyle { font_size: 30.0, color, ..default() }),
+                transform: Transform::from_translation(Vec3::new(0.0, 150.0 - index as f32 * 50.0, 10.0)),
+                ..default()
+            },
+            CustomGameLine(index),
+            CustomGameUI,
+        ));
+    }
+
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                "UP/DOWN: choose   LEFT/RIGHT: change   ENTER: play   ESC: back\nNo high scores or replays for custom games",
+                TextStyle {
+                    font_size: 24.0,
+                    color: Color::srgb(0.6, 0.6, 0.6), // Gray
+                    ..default()
+                },
+            ),
+            transform: Transform::from_translation(Vec3::new(0.0, -260.0, 10.0)),
+            ..default()
+        },
+        CustomGameUI,
+    ));
+}
+
+fn handle_custom_game_input(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut screen: ResMut<CustomGameScreen>,
+    mut custom_game: ResMut<CustomGameMode>,
+    mut usual_curve: ResMut<UsualCurve>,
+    mut curve: ResMut<DifficultyCurve>,
+    mut game_mode: ResMut<GameMode>,
+    mut game_rng: ResMut<GameRng>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    let count = CustomGameRow::ALL.len();
+    if keyboard_input.just_pressed(KeyCode::Escape) {
+        next_state.set(AppState::MainMenu);
+    } else if keyboard_input.just_pressed(KeyCode::Enter) {
+        let usual = *usual_curve.0.get_or_insert(curve.0);
+        curve.0 = custom_game.game.curve(&usual);
+        custom_game.active = true;
+        *game_mode = GameMode::Classic;
+        game_rng.stop_sharing();
+        next_state.set(AppState::Playing);
+    } else if keyboard_input.any_just_pressed([KeyCode::ArrowUp, KeyCode::KeyW]) {
+        screen.selected = (screen.selected + count - 1) % count;
+    } else if keyboard_input.any_just_pressed([KeyCode::ArrowDown, KeyCode::KeyS]) {
+        screen.selected = (screen.selected + 1) % count;
+    } else if keyboard_input.any_just_pressed([KeyCode::ArrowLeft, KeyCode::KeyA]) {
+        CustomGameRow::ALL[screen.selected].change(&mut custom_game, -1);
+    } else if keyboard_input.any_just_pressed([KeyCode::ArrowRight, KeyCode::KeyD]) {
+        CustomGameRow::ALL[screen.selected].change(&mut custom_game, 1);
+    }
+}
+
+fn update_custom_game_lines(custom_game: Res<CustomGameMode>, screen: Res<CustomGameScreen>, mut line_query: Query<(&CustomGameLine, &mut Text)>) {
+    if !custom_game.is_changed() && !screen.is_changed() {
+        return;
+    }
+    for (line, mut text) in line_query.iter_mut() {
+        let (value, color) = custom_game_line(line.0, &custom_game, &screen);
+        text.sections[0].value = value;
+        text.sections[0].style.color = color;
+    }
+}
@@ -0,0 +1,162 @@
+// Daily challenges on the main menu (see `rustbevy_core::daily`).
+//
+// Each day brings two objectives, shown at the top of the main menu with the
+// progress made in today's Classic runs and the streak of days played in a
+// row. Opening the game counts as playing that day. Finished objectives and
+// long streaks add to the collection, which earns trophies and skins (see
+// `hub`). As with the collection, assisted runs don't count. Progress is kept
+// in the save directory.
+
+use bevy::prelude::*;
+use crate::prelude::*;
+use rustbevy_core::daily::{DailyChallenges, DailyEvent, SECONDS_PER_DAY};
+
+use crate::game::{read_save_file, unix_seconds, write_save_file};
+use crate::hub::{announce_trophies, PlayerCollection};
+use crate::menu::MainMenuUI;
+use crate::ui::Toasts;
+
+pub struct DailyPlugin;
+
+impl Plugin for DailyPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Daily>()
+            .init_resource::<LostLifeThisLevel>()
+            .add_systems(OnEnter(AppState::MainMenu), (visit_today, setup_daily_menu_lines).chain())
+            .add_systems(Update, track_daily_challenges.run_if(in_state(AppState::Playing).and_then(resource_equals(GameMode::Classic))));
+    }
+}
+
+const DAILY_FILE: &str = "daily.txt";
+const DAILY_COLOR: Color = Color::srgb(0.55, 0.85, 1.0);
+const DONE_COLOR: Color = Color::srgb(0.3, 1.0, 0.4);
+
+#[derive(Resource)]
+struct Daily(DailyChallenges);
+
+impl Default for Daily {
+    fn default() -> Self {
+        let saved = read_save_file(DAILY_FILE).unwrap_or_default();
+        Self(DailyChallenges::from_text(&saved))
+    }
+}
+
+impl Daily {
+    fn save(&self) -> Result<(), RuntimeErrorEvent> {
+        write_save_file(DAILY_FILE, &self.0.to_text(), "the daily challenges")
+    }
+}
+
+// Whether the player has lost a life since the current level started
+#[derive(Resource, Default)]
+struct LostLifeThisLevel(bool);
+
+// Days since the Unix epoch, in UTC
+fn today() -> u64 {
+    let seconds = unix_seconds();
+    seconds / SECONDS_PER_DAY
+}
+
+// Moves on to today's objectives when the day has changed, saving the streak
+fn roll_over(daily: &mut Daily, collection: &mut PlayerCollection, toasts: &mut Toasts, errors: &mut EventWriter<RuntimeErrorEvent>) {
+    if !daily.0.visit(today()) {
+        return;
+    }
+    if daily.0.streak > 1 {
+        toasts.push(format!("Daily streak: {} days in a row", daily.0.streak));
+    }
+    record_daily(daily, collection, toasts, errors);
+}
+
+// Saves the challenges and passes the streak and tally on to the collection
+fn record_daily(daily: &Daily, collection: &mut PlayerCollection, toasts: &mut Toasts, errors: &mut EventWriter<RuntimeErrorEvent>) {
+    if let Err(error) = daily.save() {
+        errors.send(error);
+    }
+    let earned = collection.0.record_daily(daily.0.best_streak, daily.0.completed);
+    if earned.is_empty() {
+        return;
+    }
+    announce_trophies(toasts, &earned);
+    if let Err(error) = collection.save() {
+        errors.send(error);
+    }
+}
+
+fn visit_today(
+    mut daily: ResMut<Daily>,
+    mut collection: ResMut<PlayerCollection>,
+    mut toasts: ResMut<Toasts>,
+    mut errors: EventWriter<RuntimeErrorEvent>,
+) {
+    roll_over(&mut daily, &mut collection, &mut toasts, &mut errors);
+}
+
+fn setup_daily_menu_lines(mut commands: Commands, daily: Res<Daily>) {
+    let streak = match daily.0.streak {
+        1 => "1 day".to_string(),
+        days => format!("{} days", days),
+    };
+    let mut sections = vec![TextSection::new(
+        format!("Daily challenges - streak: {} (best {})\n", streak, daily.0.best_streak),
+        TextStyle { font_size: 22.0, color: DAILY_COLOR, ..default() },
+    )];
+    for (index, progress) in daily.0.objectives.iter().enumerate() {
+        let target = progress.objective.target();
+        let status = if progress.done() { "done".to_string() } else { format!("{}/{}", progress.count.min(target), target) };
+        let separator = if index == 0 { "" } else { "     " };
+        sections.push(TextSection::new(
+            format!("{}{}: {}", separator, progress.objective.description(), status),
+            TextStyle { font_size: 20.0, color: if progress.done() { DONE_COLOR } else { Color::WHITE }, ..default() },
+        ));
+    }
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_sections(sections),
+            transform: Transform::from_translation(Vec3::new(0.0, 300.0, 10.0)),
+            ..default()
+        },
+        MainMenuUI,
+    ));
+}
+
+fn track_daily_challenges(
+    mut reset_events: EventReader<GameResetEvent>,
+    mut death_events: EventReader<PlayerDiedEvent>,
+    mut fruit_events: EventReader<FruitCollectedEvent>,
+    mut level_events: EventReader<LevelCompletedEvent>,
+    assist_mode: Res<AssistMode>,
+    mut lost_life: ResMut<LostLifeThisLevel>,
+    mut daily: ResMut<Daily>,
+    mut collection: ResMut<PlayerCollection>,
+    mut toasts: ResMut<Toasts>,
+    mut errors: EventWriter<RuntimeErrorEvent>,
+) {
+    if reset_events.read().count() > 0 {
+        lost_life.0 = false;
+    }
+    if death_events.read().count() > 0 {
+        lost_life.0 = true;
+    }
+    let mut events: Vec<DailyEvent> = fruit_events.read().map(|event| DailyEvent::Fruit(event.kind)).collect();
+    for event in level_events.read() {
+        if !event.skipped {
+            events.push(DailyEvent::LevelCleared { reached: event.level, flawless: !lost_life.0 });
+        }
+        lost_life.0 = false;
+    }
+    if events.is_empty() || assist_mode.0.any() {
+        return;
+    }
+
+    // A run going past midnight counts towards the new day
+    roll_over(&mut daily, &mut collection, &mut toasts, &mut errors);
+    let mut finished = Vec::new();
+    for event in events {
+        finished.extend(daily.0.record(event));
+    }
+    for objective in &finished {
+        toasts.push(format!("Daily challenge done: {}", objective.description()));
+    }
+    record_daily(&daily, &mut collection, &mut toasts, &mut errors);
+}
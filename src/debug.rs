@@ -0,0 +1,612 @@
+// Developer tools, only built with `--features debug_tools`.
+//
+// F3 (anywhere but the main menu, where it belongs to the assists) toggles an
+// overlay with the frame rate, the Classic player's position, velocity and
+// footing, how many entities of each kind are around and how long the heavier
+// work takes on average (see `profiling`), and outlines what collides: the
+// player, platforms, hazards, enemies and pickups. Beside it, a timeline lists the last gameplay events with the physics tick they came on:
+// runs starting, levels being built (with the run's seed), fruit collected,
+// lives lost and levels completed. Events on the same tick are listed in that
+// order, whichever was sent first. With the overlay up, clicking a player,
+// platform, enemy or pickup (anywhere inside the box it collides with) selects
+// it, and a panel lists its components with the values of the ones that matter
+// to play; Alt and the arrow keys nudge it a unit at a time (ten with Shift),
+// and clicking nothing lets it go. The backquote key opens a console that
+// takes a command per line:
+//
+//   set_level N     jump to level N of the run
+//   give_lives N    N more lives
+//   tp X Y          move the player to X, Y
+//   regen_seed S    play the run on seed S, from this level on
+//
+// The level commands only work during a Classic run. While the console is
+// open the game doesn't see the keyboard.
+
+use std::collections::VecDeque;
+
+use bevy::diagnostic::{DiagnosticsStore, FrameTimeDiagnosticsPlugin};
+use bevy::ecs::component::Components;
+use bevy::ecs::system::SystemParam;
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::input::InputSystem;
+use bevy::prelude::*;
+use bevy::sprite::Anchor;
+use bevy::utils::get_short_name;
+use crate::prelude::*;
+use rustbevy_core::config::{FRUIT_SIZE, WINDOW_HEIGHT, WINDOW_WIDTH};
+use rustbevy_core::enemy::ENEMY_SIZE;
+
+use crate::chat::{chat_closed, Chat};
+use crate::editor::cursor_world_position;
+use crate::gamepad::read_gamepad;
+use crate::physics::Interpolated;
+use crate::platform::{platform_shape, CurrentLevel};
+use crate::profiling;
+
+pub struct DebugToolsPlugin;
+
+impl Plugin for DebugToolsPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<FrameTimeDiagnosticsPlugin>() {
+            app.add_plugins(FrameTimeDiagnosticsPlugin);
+        }
+        app.init_resource::<DebugTools>()
+            .init_resource::<EventTimeline>()
+            .add_systems(Startup, setup_debug_text)
+            .add_systems(FixedFirst, count_tick)
+            .add_systems(PreUpdate, swallow_keyboard
+                .after(InputSystem)
+                .before(read_gamepad)
+                .run_if(console_open))
+            .add_systems(PreUpdate, nudge_selected
+                .after(InputSystem)
+                .before(read_gamepad)
+                .run_if(not(console_open)))
+            .add_systems(Update, (
+                toggle_overlay.run_if(not(in_state(AppState::MainMenu))),
+                pick_entity,
+                console_input,
+                update_overlay,
+                update_inspector,
+                record_events,
+                update_timeline,
+                draw_colliders,
+                update_console,
+            ).chain());
+    }
+}
+
+const CONSOLE_MAX_LINES: usize = 6;
+const CONSOLE_MAX_LENGTH: usize = 60;
+const TIMELINE_MAX_LINES: usize = 12;
+const TEXT_COLOR: Color = Color::srgb(0.6, 1.0, 0.6); // Pale green
+const SELECTED_COLOR: Color = Color::WHITE;
+// Component names listed per line of the inspector
+const COMPONENTS_PER_LINE: usize = 4;
+
+#[derive(Resource, Default)]
+struct DebugTools {
+    overlay: bool,
+    // The line being typed, while the console is open
+    console: Option<String>,
+    // The commands typed and what came of them
+    lines: Vec<String>,
+    // The entity being inspected
+    selected: Option<Entity>,
+}
+
+impl DebugTools {
+    fn push(&mut self, line: String) {
+        self.lines.push(line);
+        if self.lines.len() > CONSOLE_MAX_LINES {
+            self.lines.remove(0);
+        }
+    }
+}
+
+// The last gameplay events, newest last
+#[derive(Resource, Default)]
+struct EventTimeline {
+    // Physics steps since the app started
+    tick: u64,
+    lines: VecDeque<String>,
+}
+
+impl EventTimeline {
+    fn push(&mut self, line: String) {
+        if self.lines.len() == TIMELINE_MAX_LINES {
+            self.lines.pop_front();
+        }
+        self.lines.push_back(format!("[{:>6}] {}", self.tick, line));
+    }
+}
+
+#[derive(Component)]
+struct DebugOverlayText;
+
+#[derive(Component)]
+struct DebugTimelineText;
+
+#[derive(Component)]
+struct DebugConsoleText;
+
+#[derive(Component)]
+struct DebugInspectorText;
+
+// Everything that collides, for outlining and picking
+#[derive(SystemParam)]
+struct ColliderBoxes<'w, 's> {
+    players: Query<'w, 's, (Entity, &'static Transform, &'static Collider), With<Player>>,
+    platforms: Query<'w, 's, (Entity, &'static Transform, &'static Platform)>,
+    enemies: Query<'w, 's, (Entity, &'static Transform), With<Enemy>>,
+    pickups: Query<'w, 's, (Entity, &'static Transform), With<Pickup>>,
+}
+
+impl ColliderBoxes<'_, '_> {
+    // Each one's transform, the size of its box as the physics sees it and
+    // the color it's outlined in
+    fn iter(&self) -> impl Iterator<Item = (Entity, &Transform, Vec2, Color)> {
+        let players = self.players.iter().map(|(entity, transform, collider)| (entity, transform, Vec2::splat(collider.size), Color::srgb(0.0, 1.0, 0.0)));
+        let platforms = self.platforms.iter().map(|(entity, transform, platform)| {
+            (entity, transform, platform_shape(&platform.spec(transform, false)).0, Color::srgb(1.0, 1.0, 0.0))
+        });
+        let enemies = self.enemies.iter().map(|(entity, transform)| (entity, transform, Vec2::splat(ENEMY_SIZE), Color::srgb(1.0, 0.2, 0.2)));
+        let pickups = self.pickups.iter().map(|(entity, transform)| (entity, transform, Vec2::splat(FRUIT_SIZE), Color::srgb(0.0, 1.0, 1.0)));
+        players.chain(platforms).chain(enemies).chain(pickups)
+    }
+}
+
+// Whether `point` is inside a box of `size` at `transform`, turned with it
+fn box_contains(transform: &Transform, size: Vec2, point: Vec2) -> bool {
+    let (_, _, angle) = transform.rotation.to_euler(EulerRot::XYZ);
+    let local = Vec2::from_angle(-angle).rotate(point - transform.translation.truncate());
+    local.abs().cmple(size / 2.0).all()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum ConsoleCommand {
+    SetLevel(u32),
+    GiveLives(u32),
+    Teleport(f32, f32),
+    RegenSeed(u64),
+}
+
+fn parse_command(line: &str) -> Result<ConsoleCommand, String> {
+    let words: Vec<&str> = line.split_whitespace().collect();
+    let number = |word: Option<&&str>| word.and_then(|word| word.parse::<u64>().ok());
+    let coordinate = |word: Option<&&str>| word.and_then(|word| word.parse::<f32>().ok()).filter(|value| value.is_finite());
+    match words.first().copied() {
+        Some("set_level") => number(words.get(1))
+            .and_then(|level| u32::try_from(level).ok())
+            .filter(|&level| level >= 1)
+            .map(ConsoleCommand::SetLevel)
+            .ok_or_else(|| "usage: set_level N (1 or more)".to_string()),
+        Some("give_lives") => number(words.get(1))
+            .and_then(|lives| u32::try_from(lives).ok())
+            .map(ConsoleCommand::GiveLives)
+            .ok_or_else(|| "usage: give_lives N".to_string()),
+        Some("tp") => coordinate(words.get(1))
+            .zip(coordinate(words.get(2)))
+            .map(|(x, y)| ConsoleCommand::Teleport(x, y))
+            .ok_or_else(|| "usage: tp X Y".to_string()),
+        Some("regen_seed") => number(words.get(1))
+            .map(ConsoleCommand::RegenSeed)
+            .ok_or_else(|| "usage: regen_seed SEED".to_string()),
+        Some(other) => Err(format!("unknown command '{}' (set_level, give_lives, tp, regen_seed)", other)),
+        None => Err("type a command".to_string()),
+    }
+}
+
+fn console_open(tools: Res<DebugTools>) -> bool {
+    tools.console.is_some()
+}
+
+// Keys typed into the console shouldn't also move the player or open menus
+fn swallow_keyboard(mut keyboard_input: ResMut<ButtonInput<KeyCode>>) {
+    keyboard_input.reset_all();
+}
+
+fn setup_debug_text(mut commands: Commands) {
+    let style = TextStyle { font_size: 16.0, color: TEXT_COLOR, ..default() };
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section("", style.clone()),
+            text_anchor: Anchor::TopLeft,
+            transform: Transform::from_translation(Vec3::new(-WINDOW_WIDTH / 2.0 + 10.0, WINDOW_HEIGHT / 2.0 - 110.0, 20.0)),
+            visibility: Visibility::Hidden,
+            ..default()
+        },
+        DebugOverlayText,
+    ));
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section("", style.clone()),
+            text_anchor: Anchor::TopRight,
+            transform: Transform::from_translation(Vec3::new(WINDOW_WIDTH / 2.0 - 10.0, WINDOW_HEIGHT / 2.0 - 110.0, 20.0)),
+            visibility: Visibility::Hidden,
+            ..default()
+        },
+        DebugTimelineText,
+    ));
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section("", style),
+            text_anchor: Anchor::BottomLeft,
+            transform: Transform::from_translation(Vec3::new(-WINDOW_WIDTH / 2.0 + 10.0, -WINDOW_HEIGHT / 2.0 + 80.0, 20.0)),
+            visibility: Visibility::Hidden,
+            ..default()
+        },
+        DebugConsoleText,
+    ));
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section("", TextStyle { font_size: 16.0, color: TEXT_COLOR, ..default() }),
+            text_anchor: Anchor::BottomRight,
+            transform: Transform::from_translation(Vec3::new(WINDOW_WIDTH / 2.0 - 10.0, -WINDOW_HEIGHT / 2.0 + 80.0, 20.0)),
+            visibility: Visibility::Hidden,
+            ..default()
+        },
+        DebugInspectorText,
+    ));
+}
+
+fn toggle_overlay(keyboard_input: Res<ButtonInput<KeyCode>>, mut tools: ResMut<DebugTools>) {
+    if keyboard_input.just_pressed(KeyCode::F3) {
+        tools.overlay = !tools.overlay;
+    }
+}
+
+// A click picks what's under the cursor, the smallest box when several are
+// (a fruit rather than the platform it sits on)
+fn pick_entity(
+    mouse_buttons: Res<ButtonInput<MouseButton>>,
+    mut tools: ResMut<DebugTools>,
+    windows: Query<&Window>,
+    camera_query: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
+    colliders: ColliderBoxes,
+) {
+    if !tools.overlay || !mouse_buttons.just_pressed(MouseButton::Left) {
+        return;
+    }
+    let Some(cursor) = cursor_world_position(&windows, &camera_query) else {
+        return;
+    };
+    tools.selected = colliders
+        .iter()
+        .filter(|(_, transform, size, _)| box_contains(transform, *size, cursor))
+        .min_by(|a, b| (a.2.x * a.2.y).total_cmp(&(b.2.x * b.2.y)))
+        .map(|(entity, ..)| entity);
+}
+
+// Alt and the arrow keys move the selected entity, and are kept from the
+// player and the menus
+fn nudge_selected(
+    mut commands: Commands,
+    mut keyboard_input: ResMut<ButtonInput<KeyCode>>,
+    tools: Res<DebugTools>,
+    mut transform_query: Query<&mut Transform>,
+) {
+    let Some(selected) = tools.selected.filter(|_| tools.overlay) else {
+        return;
+    };
+    if !keyboard_input.any_pressed([KeyCode::AltLeft, KeyCode::AltRight]) {
+        return;
+    }
+    let step = if keyboard_input.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) { 10.0 } else { 1.0 };
+    let mut nudge = Vec2::ZERO;
+    for (key, direction) in [(KeyCode::ArrowLeft, Vec2::NEG_X), (KeyCode::ArrowRight, Vec2::X), (KeyCode::ArrowDown, Vec2::NEG_Y), (KeyCode::ArrowUp, Vec2::Y)] {
+        if keyboard_input.just_pressed(key) {
+            nudge += direction * step;
+        }
+        keyboard_input.reset(key);
+    }
+    if nudge == Vec2::ZERO {
+        return;
+    }
+    if let Ok(mut transform) = transform_query.get_mut(selected) {
+        // Moved, not slid there between physics steps
+        commands.entity(selected).remove::<Interpolated>();
+        transform.translation += nudge.extend(0.0);
+    }
+}
+
+fn console_input(
+    mut commands: Commands,
+    mut typed_keys: EventReader<KeyboardInput>,
+    mut tools: ResMut<DebugTools>,
+    chat: Res<Chat>,
+    app_state: Res<State<AppState>>,
+    game_mode: Res<GameMode>,
+    mut game_state: ResMut<GameState>,
+    mut game_rng: ResMut<GameRng>,
+    mut player_query: Query<(Entity, &mut Transform, &mut Velocity), With<LeadPlayer>>,
+    fruit_query: Query<Entity, With<Fruit>>,
+    mut level_events: EventWriter<LevelCompletedEvent>,
+) {
+    // Read from the events rather than the key state, which is cleared while
+    // the console is open
+    let typed: Vec<KeyboardInput> = typed_keys.read().filter(|event| event.state.is_pressed()).cloned().collect();
+    let Some(mut line) = tools.console.take() else {
+        if chat_closed(chat) && typed.iter().any(|event| event.key_code == KeyCode::Backquote) {
+            tools.console = Some(String::new());
+        }
+        return;
+    };
+
+    for event in &typed {
+        match (&event.key_code, &event.logical_key) {
+            (KeyCode::Backquote, _) | (_, Key::Escape) => return,
+            (_, Key::Backspace) => {
+                line.pop();
+            }
+            (_, Key::Enter) => {
+                let reply = parse_command(&line).and_then(|command| {
+                    let classic_run = *app_state.get() == AppState::Playing && *game_mode == GameMode::Classic;
+                    match command {
+                        ConsoleCommand::SetLevel(_) | ConsoleCommand::RegenSeed(_) if !classic_run => {
+                            return Err("only during a Classic run".to_string());
+                        }
+                        ConsoleCommand::SetLevel(level) => {
+                            game_state.level = level;
+                            for entity in fruit_query.iter() {
+                                commands.entity(entity).despawn();
+                            }
+                            level_events.send(LevelCompletedEvent { level, skipped: true });
+                        }
+                        ConsoleCommand::GiveLives(lives) => game_state.lives = game_state.lives.saturating_add(lives),
+                        ConsoleCommand::Teleport(x, y) => {
+                            let Ok((entity, mut transform, mut velocity)) = player_query.get_single_mut() else {
+                                return Err("no player to move".to_string());
+                            };
+                            // Moved, not slid there between physics steps
+                            commands.entity(entity).remove::<Interpolated>();
+                            transform.translation.x = x;
+                            transform.translation.y = y;
+                            *velocity = Velocity { x: 0.0, y: 0.0 };
+                        }
+                        ConsoleCommand::RegenSeed(seed) => {
+                            game_rng.resume_run(seed);
+                            for entity in fruit_query.iter() {
+                                commands.entity(entity).despawn();
+                            }
+                            level_events.send(LevelCompletedEvent { level: game_state.level, skipped: true });
+                        }
+                    }
+                    Ok("ok".to_string())
+                });
+                let reply = reply.unwrap_or_else(|error| error);
+                tools.push(format!("> {}: {}", line.trim(), reply));
+                line.clear();
+            }
+            (_, Key::Space) if line.chars().count() < CONSOLE_MAX_LENGTH => line.push(' '),
+            (_, Key::Character(characters)) => {
+                for character in characters.chars() {
+                    if line.chars().count() < CONSOLE_MAX_LENGTH {
+                        line.push(character);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    tools.console = Some(line);
+}
+
+fn update_overlay(
+    tools: Res<DebugTools>,
+    diagnostics: Res<DiagnosticsStore>,
+    player_query: Query<(&Transform, &Velocity, &Grounded), With<LeadPlayer>>,
+    entity_query: Query<Entity>,
+    platform_query: Query<(), With<Platform>>,
+    enemy_query: Query<(), With<Enemy>>,
+    pickup_query: Query<(), With<Pickup>>,
+    transient_query: Query<(), With<Transient>>,
+    mut text_query: Query<(&mut Text, &mut Visibility), With<DebugOverlayText>>,
+) {
+    let Ok((mut text, mut visibility)) = text_query.get_single_mut() else {
+        return;
+    };
+    let shown = if tools.overlay { Visibility::Inherited } else { Visibility::Hidden };
+    if *visibility != shown {
+        *visibility = shown;
+    }
+    if !tools.overlay {
+        return;
+    }
+
+    let fps = diagnostics
+        .get(&FrameTimeDiagnosticsPlugin::FPS)
+        .and_then(|fps| fps.smoothed())
+        .map_or("-".to_string(), |fps| format!("{:.0}", fps));
+    let player = match player_query.get_single() {
+        Ok((transform, velocity, grounded)) => format!(
+            "Player: ({:.1}, {:.1})\nVelocity: ({:.1}, {:.1})\nGrounded: {}",
+            transform.translation.x,
+            transform.translation.y,
+            velocity.x,
+            velocity.y,
+            grounded.0
+        ),
+        Err(_) => "Player: -".to_string(),
+    };
+    let markers: String = profiling::MARKERS
+        .iter()
+        .map(|(name, path)| {
+            let took = diagnostics.get(path).and_then(|marker| marker.average()).map_or("-".to_string(), |took| format!("{:.2} ms", took));
+            format!("\n{}: {}", name, took)
+        })
+        .collect();
+    text.sections[0].value = format!(
+        "FPS: {}\n{}\nEntities: {} (platforms {}, enemies {}, pickups {}, effects {}){}",
+        fps,
+        player,
+        entity_query.iter().count(),
+        platform_query.iter().count(),
+        enemy_query.iter().count(),
+        pickup_query.iter().count(),
+        transient_query.iter().count(),
+        markers
+    );
+}
+
+// What the selected entity is made of
+fn inspect(entity: EntityRef, components: &Components) -> String {
+    let mut lines = vec![format!("Selected {}", entity.id())];
+    if let Some(transform) = entity.get::<Transform>() {
+        let (_, _, angle) = transform.rotation.to_euler(EulerRot::XYZ);
+        lines.push(format!(
+            "Transform: ({:.1}, {:.1}, {:.1}), {:.1} deg, scale ({:.2}, {:.2})",
+            transform.translation.x,
+            transform.translation.y,
+            transform.translation.z,
+            angle.to_degrees(),
+            transform.scale.x,
+            transform.scale.y
+        ));
+    }
+    if let Some(velocity) = entity.get::<Velocity>() {
+        lines.push(format!("Velocity: ({:.1}, {:.1})", velocity.x, velocity.y));
+    }
+    if let Some(grounded) = entity.get::<Grounded>() {
+        lines.push(format!("Grounded: {}", grounded.0));
+    }
+    if let Some(collider) = entity.get::<Collider>() {
+        lines.push(format!("Collider: {:.0}", collider.size));
+    }
+    if let Some(platform) = entity.get::<Platform>() {
+        lines.push(format!("Platform: {:.0} x {:.0}, slope {:.2}", platform.width, platform.height, platform.slope));
+    }
+    if let Some(enemy) = entity.get::<Enemy>() {
+        lines.push(format!("Enemy: {:?}", enemy.0));
+    }
+    if let Some(pickup) = entity.get::<Pickup>() {
+        lines.push(format!("Pickup: {:?}", pickup.0));
+    }
+    if let Some(variant) = entity.get::<FruitVariant>() {
+        lines.push(format!("FruitVariant: {:?}", variant.0));
+    }
+    let names: Vec<String> = entity
+        .archetype()
+        .components()
+        .filter_map(|id| components.get_info(id))
+        .map(|info| get_short_name(info.name()))
+        .collect();
+    lines.push(format!("Components ({}):", names.len()));
+    lines.extend(names.chunks(COMPONENTS_PER_LINE).map(|names| format!("  {}", names.join(", "))));
+    lines.push("Alt+arrows: nudge (Shift: 10)".to_string());
+    lines.join("\n")
+}
+
+fn update_inspector(
+    tools: Res<DebugTools>,
+    components: &Components,
+    entity_query: Query<EntityRef, Without<DebugInspectorText>>,
+    mut text_query: Query<(&mut Text, &mut Visibility), With<DebugInspectorText>>,
+) {
+    let Ok((mut text, mut visibility)) = text_query.get_single_mut() else {
+        return;
+    };
+    let selected = tools.selected.filter(|_| tools.overlay).and_then(|selected| entity_query.get(selected).ok());
+    let shown = if selected.is_some() { Visibility::Inherited } else { Visibility::Hidden };
+    if *visibility != shown {
+        *visibility = shown;
+    }
+    if let Some(entity) = selected {
+        let value = inspect(entity, components);
+        if text.sections[0].value != value {
+            text.sections[0].value = value;
+        }
+    }
+}
+
+fn count_tick(mut timeline: ResMut<EventTimeline>) {
+    timeline.tick += 1;
+}
+
+// Kept whether the overlay is showing or not, so turning it on shows what
+// just happened
+fn record_events(
+    mut timeline: ResMut<EventTimeline>,
+    current_level: Res<CurrentLevel>,
+    mut reset_events: EventReader<GameResetEvent>,
+    mut fruit_events: EventReader<FruitCollectedEvent>,
+    mut died_events: EventReader<PlayerDiedEvent>,
+    mut completed_events: EventReader<LevelCompletedEvent>,
+) {
+    for reset in reset_events.read() {
+        timeline.push(format!("GameReset {:?}", reset.mode));
+    }
+    // Every level built puts a new `CurrentLevel` in place of the one there
+    // from the start
+    if current_level.is_changed() && !current_level.is_added() {
+        timeline.push(format!(
+            "LevelGenerated {} seed {} ({:?})",
+            current_level.level, current_level.run_seed, current_level.theme
+        ));
+    }
+    for fruit in fruit_events.read() {
+        timeline.push(format!("FruitCollected {:?} at ({:.0}, {:.0})", fruit.kind, fruit.position.x, fruit.position.y));
+    }
+    for died in died_events.read() {
+        timeline.push(format!("PlayerDied {:?}, {} lives left", died.player, died.lives_left));
+    }
+    for completed in completed_events.read() {
+        let skipped = if completed.skipped { " (skipped)" } else { "" };
+        timeline.push(format!("LevelCompleted {}{}", completed.level, skipped));
+    }
+}
+
+fn update_timeline(tools: Res<DebugTools>, timeline: Res<EventTimeline>, mut text_query: Query<(&mut Text, &mut Visibility), With<DebugTimelineText>>) {
+    let Ok((mut text, mut visibility)) = text_query.get_single_mut() else {
+        return;
+    };
+    let shown = if tools.overlay { Visibility::Inherited } else { Visibility::Hidden };
+    if *visibility != shown {
+        *visibility = shown;
+    }
+    if !tools.overlay || !timeline.is_changed() && !tools.is_changed() {
+        return;
+    }
+    let lines: Vec<&str> = timeline.lines.iter().map(String::as_str).collect();
+    text.sections[0].value = format!("Events (tick):\n{}", lines.join("\n"));
+}
+
+// Outlines everything that collides, as the physics sees it, and the
+// selected entity
+fn draw_colliders(tools: Res<DebugTools>, mut gizmos: Gizmos, colliders: ColliderBoxes, hazard_query: Query<&HazardArea>) {
+    if !tools.overlay {
+        return;
+    }
+    for (entity, transform, size, color) in colliders.iter() {
+        let (_, _, angle) = transform.rotation.to_euler(EulerRot::XYZ);
+        gizmos.rect_2d(transform.translation.truncate(), angle, size, color);
+        if tools.selected == Some(entity) {
+            gizmos.rect_2d(transform.translation.truncate(), angle, size + Vec2::splat(6.0), SELECTED_COLOR);
+        }
+    }
+    for hazard in hazard_query.iter() {
+        let spec = hazard.0.spec();
+        gizmos.rect_2d(Vec2::new(spec.x, spec.y), 0.0, Vec2::new(spec.width, spec.height), Color::srgb(1.0, 0.5, 0.0));
+    }
+}
+
+fn update_console(tools: Res<DebugTools>, mut text_query: Query<(&mut Text, &mut Visibility), With<DebugConsoleText>>) {
+    let Ok((mut text, mut visibility)) = text_query.get_single_mut() else {
+        return;
+    };
+    let shown = if tools.console.is_some() { Visibility::Inherited } else { Visibility::Hidden };
+    if *visibility != shown {
+        *visibility = shown;
+    }
+    let Some(line) = &tools.console else {
+        return;
+    };
+    let mut value = tools.lines.join("\n");
+    if !value.is_empty() {
+        value.push('\n');
+    }
+    value.push_str(&format!("] {}_", line));
+    if text.sections[0].value != value {
+        text.sections[0].value = value;
+    }
+}
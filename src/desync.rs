@@ -0,0 +1,170 @@
+// Desync probe, a developer tool for the deterministic/rollback work.
+//
+// F11 starts and stops it. While it records, the game state that should play
+// out the same way on every machine (players, enemies, moving platforms, lives
+// and score) is hashed at several stages of every physics step: after input,
+// gravity, velocity, collisions and enemies. A run's hashes go to the
+// `desync` folder in the save directory when recording stops, the run ends or
+// a new run starts. Record the same replay twice, or two peers playing one
+// seed, and `compare_hashes` (in rustbevy_core) names the first step and
+// stage where the logs differ: the systems between that stage and the one
+// before it are where the runs went apart.
+
+use bevy::prelude::*;
+use crate::prelude::*;
+use rustbevy_core::enemy::EnemyBehavior;
+use rustbevy_core::snapshot::{HashLog, StateHash};
+
+use crate::capture::capture_key;
+use crate::chat::chat_closed;
+use crate::enemy::move_enemies;
+use crate::game::{clock_seed, write_save_file};
+use crate::physics::{apply_gravity, apply_velocity, check_collisions, player_movement};
+use crate::ui::Toasts;
+
+pub struct DesyncPlugin;
+
+impl Plugin for DesyncPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DesyncProbe>()
+            .add_systems(OnEnter(AppState::GameOver), finish_run_log)
+            .add_systems(Update, (toggle_desync_probe.run_if(chat_closed), start_run_log).chain())
+            .add_systems(FixedUpdate, (
+                hash_stage::<0>.after(player_movement).before(apply_gravity),
+                hash_stage::<1>.after(apply_gravity).before(apply_velocity),
+                hash_stage::<2>.after(apply_velocity).before(check_collisions),
+                hash_stage::<3>.after(check_collisions).before(move_enemies),
+                hash_stage::<4>.after(move_enemies),
+            ).run_if(in_state(AppState::Playing).and_then(probe_recording)));
+    }
+}
+
+// What each `hash_stage` hashes after, in the order they run in a step
+const STAGES: [&str; 5] = ["input", "gravity", "velocity", "collisions", "enemies"];
+const DESYNC_FOLDER: &str = "desync";
+
+#[derive(Resource, Default)]
+pub struct DesyncProbe {
+    recording: bool,
+    // Physics steps of the current run so far
+    tick: u64,
+    log: HashLog,
+}
+
+fn probe_recording(probe: Res<DesyncProbe>) -> bool {
+    probe.recording
+}
+
+// Writes out the run's hashes, if there are any, and starts a new log
+fn save_log(probe: &mut DesyncProbe, run_seed: u64, toasts: &mut Toasts, errors: &mut EventWriter<RuntimeErrorEvent>) {
+    let log = std::mem::take(&mut probe.log);
+    probe.tick = 0;
+    if log.entries.is_empty() {
+        return;
+    }
+    let file = format!("{}/{}-{}.txt", DESYNC_FOLDER, run_seed, clock_seed());
+    match write_save_file(&file, &log.to_text(), "the desync log") {
+        Ok(()) => toasts.push(format!("Desync log saved: {}", file)),
+        Err(error) => {
+            errors.send(error);
+        }
+    }
+}
+
+fn toggle_desync_probe(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    game_rng: Res<GameRng>,
+    mut probe: ResMut<DesyncProbe>,
+    mut toasts: ResMut<Toasts>,
+    mut errors: EventWriter<RuntimeErrorEvent>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::F11) || capture_key(&keyboard_input) {
+        return;
+    }
+    probe.recording = !probe.recording;
+    if probe.recording {
+        toasts.push("Desync probe recording");
+    } else {
+        save_log(&mut probe, game_rng.run_seed(), &mut toasts, &mut errors);
+    }
+}
+
+// Each run gets its own log, counting steps from its start
+fn start_run_log(
+    mut reset_events: EventReader<GameResetEvent>,
+    game_rng: Res<GameRng>,
+    mut probe: ResMut<DesyncProbe>,
+    mut toasts: ResMut<Toasts>,
+    mut errors: EventWriter<RuntimeErrorEvent>,
+) {
+    if reset_events.read().count() > 0 && probe.recording {
+        save_log(&mut probe, game_rng.run_seed(), &mut toasts, &mut errors);
+    }
+}
+
+fn finish_run_log(
+    game_rng: Res<GameRng>,
+    mut probe: ResMut<DesyncProbe>,
+    mut toasts: ResMut<Toasts>,
+    mut errors: EventWriter<RuntimeErrorEvent>,
+) {
+    if probe.recording {
+        save_log(&mut probe, game_rng.run_seed(), &mut toasts, &mut errors);
+    }
+}
+
+fn hash_stage<const STAGE: usize>(
+    mut probe: ResMut<DesyncProbe>,
+    game_state: Res<GameState>,
+    player_query: Query<(&Transform, &Velocity, &Grounded), With<Player>>,
+    enemy_query: Query<(&Transform, &Enemy)>,
+    platform_query: Query<&Transform, With<MovingPlatform>>,
+) {
+    if STAGE == 0 {
+        probe.tick += 1;
+    }
+
+    // Entities are hashed one by one and combined in any order, since peers
+    // don't spawn them with the same ids
+    let players = player_query
+        .iter()
+        .map(|(transform, velocity, grounded)| {
+            let mut hash = StateHash::default();
+            hash.write_f32(transform.translation.x);
+            hash.write_f32(transform.translation.y);
+            hash.write_f32(velocity.x);
+            hash.write_f32(velocity.y);
+            hash.write_bool(grounded.0);
+            hash.finish()
+        })
+        .collect();
+    let enemies = enemy_query
+        .iter()
+        .map(|(transform, enemy)| {
+            let mut hash = StateHash::default();
+            hash.write_f32(transform.translation.x);
+            hash.write_f32(transform.translation.y);
+            hash.write_bool(enemy.0.behavior == EnemyBehavior::Chase);
+            hash.finish()
+        })
+        .collect();
+    let platforms = platform_query
+        .iter()
+        .map(|transform| {
+            let mut hash = StateHash::default();
+            hash.write_f32(transform.translation.x);
+            hash.write_f32(transform.translation.y);
+            hash.finish()
+        })
+        .collect();
+
+    let mut hash = StateHash::default();
+    hash.write_u64(StateHash::unordered(players));
+    hash.write_u64(StateHash::unordered(enemies));
+    hash.write_u64(StateHash::unordered(platforms));
+    hash.write_u32(game_state.level);
+    hash.write_u32(game_state.lives);
+    hash.write_u32(game_state.score);
+    let tick = probe.tick;
+    probe.log.push(tick, STAGES[STAGE], hash.finish());
+}
@@ -0,0 +1,109 @@
+//! Dressing up Classic-mode platforms (see `rustbevy_core::look`): a color a
+//! little off their surface's, a trim along the edges and a tile pattern.
+//!
+//! The trims and tiles are sprites of their own, laid over the platform. They
+//! aren't its children, since a level is cleared by despawning its platforms;
+//! instead they follow their platform around every frame (moving, shaking,
+//! blinking out when it crumbles) and go when it's gone.
+
+use bevy::prelude::*;
+use bevy::sprite::Anchor;
+use crate::prelude::*;
+use rustbevy_core::look::{EdgeTrim, PlatformLook, TilePattern};
+
+use crate::physics::interpolate_positions;
+use crate::platform::platform_shape;
+
+pub struct DressingPlugin;
+
+impl Plugin for DressingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(PostUpdate, follow_platforms.after(interpolate_positions).before(TransformSystem::TransformPropagate));
+    }
+}
+
+const LIP_HEIGHT: f32 = 3.0;
+const LIP_COLOR: Color = Color::srgba(1.0, 1.0, 1.0, 0.25);
+const CAP_WIDTH: f32 = 6.0;
+const CAP_COLOR: Color = Color::srgba(0.0, 0.0, 0.0, 0.25);
+const SEAM_WIDTH: f32 = 2.0;
+const SEAM_COLOR: Color = Color::srgba(0.0, 0.0, 0.0, 0.2);
+const CHECKER_COLOR: Color = Color::srgba(0.0, 0.0, 0.0, 0.12);
+
+// A trim or tile sprite on `platform`, `offset` from its centre along the
+// platform's own axes
+#[derive(Component)]
+struct PlatformTrim {
+    platform: Entity,
+    offset: Vec2,
+}
+
+/// `color` turned and lightened or darkened by a platform's look
+pub fn dressed_color(color: Color, look: &PlatformLook) -> Color {
+    let hsla = Hsla::from(color).rotate_hue(look.hue_shift);
+    hsla.with_lightness((hsla.lightness + look.lightness_shift).clamp(0.0, 1.0)).into()
+}
+
+/// Lays the trims and tiles of `look` over a spawned platform
+pub fn dress_platform(commands: &mut Commands, platform: Entity, spec: &PlatformSpec, look: PlatformLook) {
+    commands.entity(platform).insert(PlatformDressing(look));
+    let (size, _) = platform_shape(spec);
+    let mut pieces: Vec<(Vec2, Vec2, Color)> = Vec::new();
+    match look.edge {
+        EdgeTrim::Plain => {}
+        EdgeTrim::Lip => pieces.push((Vec2::new(0.0, (size.y - LIP_HEIGHT) / 2.0), Vec2::new(size.x, LIP_HEIGHT), LIP_COLOR)),
+        EdgeTrim::Caps => {
+            for side in [-1.0, 1.0] {
+                pieces.push((Vec2::new(side * (size.x - CAP_WIDTH) / 2.0, 0.0), Vec2::new(CAP_WIDTH, size.y), CAP_COLOR));
+            }
+        }
+    }
+    let tile = size.x / look.tiles as f32;
+    let left = -size.x / 2.0;
+    match look.pattern {
+        TilePattern::Plain => {}
+        TilePattern::Seams => {
+            for index in 1..look.tiles {
+                pieces.push((Vec2::new(left + index as f32 * tile, 0.0), Vec2::new(SEAM_WIDTH, size.y), SEAM_COLOR));
+            }
+        }
+        TilePattern::Checker => {
+            for index in (1..look.tiles).step_by(2) {
+                pieces.push((Vec2::new(left + (index as f32 + 0.5) * tile, 0.0), Vec2::new(tile, size.y), CHECKER_COLOR));
+            }
+        }
+    }
+    for (offset, size, color) in pieces {
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite { color, custom_size: Some(size), ..default() },
+                transform: Transform::from_translation(Vec3::new(spec.x + offset.x, spec.y + offset.y, 0.1)),
+                ..default()
+            },
+            PlatformTrim { platform, offset },
+        ));
+    }
+}
+
+// Keeps trims and tiles on their platforms, shown when they are
+fn follow_platforms(
+    mut commands: Commands,
+    platform_query: Query<(&Transform, &Visibility, &Sprite), (With<Platform>, Without<PlatformTrim>)>,
+    mut trim_query: Query<(Entity, &PlatformTrim, &mut Transform, &mut Visibility)>,
+) {
+    for (entity, trim, mut transform, mut visibility) in trim_query.iter_mut() {
+        let Ok((platform_transform, platform_visibility, sprite)) = platform_query.get(trim.platform) else {
+            commands.entity(entity).despawn();
+            continue;
+        };
+        // A crumbling platform's shake moves its sprite off its anchor
+        let shake = match sprite.anchor {
+            Anchor::Custom(anchor) => -anchor * sprite.custom_size.unwrap_or_default(),
+            _ => Vec2::ZERO,
+        };
+        let offset = platform_transform.rotation * (trim.offset + shake).extend(0.1);
+        transform.translation = platform_transform.translation + offset;
+        transform.rotation = platform_transform.rotation;
+        visibility.set_if_neq(*platform_visibility);
+    }
+}
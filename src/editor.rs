@@ -0,0 +1,581 @@
+// Level editor for hand-made Classic levels.
+//
+// N on the main menu opens it on level 1's file (see `level_loader` for the
+// format); Page Up / Page Down pick another level. The number keys pick a
+// tool:
+//
+// 1 Platforms: drag on empty space to draw one, drag one to move it, mouse
+//   wheel over one to change its width (with Shift, its height)
+// 2 Fruit: click to put the level's fruit there
+// 3 Enemies: click a platform to put an enemy on it, or take it off
+// 4 Spawn: click to put the player's spawn point there
+//
+// Right click removes a platform. Arrow keys pan around the level. Green
+// circles are the safe zones, where enemies are left out, and red rectangles
+// the enemy zones, if the file has any. Dotted arcs show where a jump off
+// either end of the platform under the mouse would go, to check the gaps
+// around it. Ladders, ropes, spikes, lava, portals and the exit door and its
+// keys from the file are shown, but are edited in the file. Ctrl+S
+// writes `assets/levels/level_N.ron` and plays it as level N right away, no
+// restart needed. ESC goes back to the menu, dropping unsaved changes.
+
+use bevy::input::mouse::{MouseScrollUnit, MouseWheel};
+use bevy::prelude::*;
+use crate::prelude::*;
+use rustbevy_core::config::{FRUIT_SIZE, PLATFORM_HEIGHT, PLAYER_SIZE, PLAYER_SPAWN};
+use rustbevy_core::exit::{DOOR_HEIGHT, DOOR_WIDTH};
+use rustbevy_core::hazard::HazardKind;
+use rustbevy_core::ladder::{LADDER_WIDTH, ROPE_WIDTH};
+use rustbevy_core::physics::Tuning;
+use rustbevy_core::portal::{PORTAL_HEIGHT, PORTAL_WIDTH};
+use rustbevy_core::reachability::JumpProfile;
+use rustbevy_core::surface::Surface;
+use rustbevy_core::zones::safe_zones;
+
+use crate::chat::chat_closed;
+use crate::exit::{door_color, KEY_COLOR, KEY_SIZE};
+use crate::hazard::hazard_color;
+use crate::level_loader::{level_file_path, level_file_warning, parse_level_file, HandmadeLevels, LevelFile, PlatformFile, LEVEL_FORMAT_VERSION};
+use crate::jump_arc::{draw_jump_arc, JUMP_ARC_DROP};
+use crate::ladder::ladder_color;
+use crate::platform::{platform_shape, surface_color};
+use crate::portal::portal_color;
+use crate::ui::{despawn_screen, Toasts};
+
+pub struct EditorPlugin;
+
+impl Plugin for EditorPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<EditorLevel>()
+            .init_resource::<EditorDrag>()
+            .add_systems(OnEnter(AppState::LevelEditor), enter_level_editor)
+            .add_systems(OnExit(AppState::LevelEditor), (exit_level_editor, despawn_screen::<EditorUI>, despawn_screen::<EditorShape>))
+            .add_systems(Update, (
+                open_level_editor.run_if(in_state(AppState::MainMenu).and_then(chat_closed)),
+                (handle_editor_keys, pan_editor_camera, edit_with_mouse, resize_with_wheel, draw_editor_level, draw_editor_guides)
+                    .chain()
+                    .run_if(in_state(AppState::LevelEditor)),
+            ));
+    }
+}
+
+// Positions and sizes snap to this grid
+const EDITOR_GRID: f32 = 10.0;
+const MIN_PLATFORM_WIDTH: f32 = 40.0;
+const PAN_SPEED: f32 = 700.0;
+const FRUIT_COLOR: Color = Color::srgb(1.0, 0.5, 0.0);
+const ENEMY_COLOR: Color = Color::srgb(0.55, 0.1, 0.1);
+const SPAWN_COLOR: Color = Color::srgba(0.4, 0.8, 1.0, 0.5);
+const GUIDE_COLOR: Color = Color::srgb(1.0, 1.0, 0.4);
+const BOUNDS_COLOR: Color = Color::srgb(0.8, 0.8, 0.8);
+const SAFE_ZONE_COLOR: Color = Color::srgb(0.4, 1.0, 0.5);
+const ENEMY_ZONE_COLOR: Color = Color::srgb(0.9, 0.3, 0.3);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum EditorTool {
+    Platforms,
+    Fruit,
+    Enemies,
+    Spawn,
+}
+
+impl EditorTool {
+    const ALL: [EditorTool; 4] = [EditorTool::Platforms, EditorTool::Fruit, EditorTool::Enemies, EditorTool::Spawn];
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Platforms => "Platforms",
+            Self::Fruit => "Fruit",
+            Self::Enemies => "Enemies",
+            Self::Spawn => "Spawn",
+        }
+    }
+}
+
+// The level being edited
+#[derive(Resource)]
+struct EditorLevel {
+    number: u32,
+    file: LevelFile,
+    tool: EditorTool,
+    // Changed since it was loaded or saved
+    unsaved: bool,
+}
+
+impl Default for EditorLevel {
+    fn default() -> Self {
+        Self { number: 1, file: blank_level(), tool: EditorTool::Platforms, unsaved: false }
+    }
+}
+
+impl EditorLevel {
+    // Level `number` from its file, or a blank one when it has none
+    fn load(&mut self, number: u32, errors: &mut EventWriter<RuntimeErrorEvent>) {
+        let path = level_file_path(number);
+        self.number = number;
+        self.unsaved = false;
+        self.file = match std::fs::read(&path) {
+            Ok(bytes) => parse_level_file(&bytes).unwrap_or_else(|error| {
+                errors.send(RuntimeErrorEvent::new(format!("Could not open level {} in the editor", number), format!("{}: {}", path.display(), error)));
+                blank_level()
+            }),
+            Err(_) => blank_level(),
+        };
+        for warning in &self.file.warnings {
+            errors.send(level_file_warning(number, warning));
+        }
+    }
+
+    fn save(&mut self) -> Result<(), RuntimeErrorEvent> {
+        let path = level_file_path(self.number);
+        let what = format!("Could not save level {}", self.number);
+        // Whatever it was read from, it's written in this version's format
+        self.file.version = LEVEL_FORMAT_VERSION;
+        let text = ron::ser::to_string_pretty(&self.file, ron::ser::PrettyConfig::default())
+            .map_err(|error| RuntimeErrorEvent::new(what.clone(), error.to_string()))?;
+        path.parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|_| std::fs::write(&path, text))
+            .map_err(|error| RuntimeErrorEvent::new(what, format!("{}: {}", path.display(), error)))?;
+        self.unsaved = false;
+        Ok(())
+    }
+
+    // The topmost platform under `point`
+    fn platform_at(&self, point: Vec2) -> Option<usize> {
+        self.file.platforms.iter().rposition(|platform| {
+            let middle = platform.spec().top_at(point.x) - platform.height / 2.0;
+            (point.x - platform.x).abs() <= platform.width / 2.0 && (point.y - middle).abs() <= platform.height / 2.0
+        })
+    }
+
+    // Removes a platform and whatever stood on it, keeping the other
+    // platforms' enemies, checkpoints and paths on the right platforms
+    fn remove_platform(&mut self, index: usize) {
+        let file = &mut self.file;
+        file.platforms.remove(index);
+        let reindex = |platform: usize| (platform != index).then(|| if platform > index { platform - 1 } else { platform });
+        file.enemies = file.enemies.iter().filter_map(|&platform| reindex(platform)).collect();
+        file.checkpoints = file.checkpoints.iter().filter_map(|&platform| reindex(platform)).collect();
+        file.moving.retain_mut(|moving| match reindex(moving.platform) {
+            Some(platform) => {
+                moving.platform = platform;
+                true
+            }
+            None => false,
+        });
+        self.unsaved = true;
+    }
+}
+
+// A level with just the starting platform under the spawn point
+fn blank_level() -> LevelFile {
+    LevelFile {
+        version: LEVEL_FORMAT_VERSION,
+        player_spawn: PLAYER_SPAWN,
+        theme: None,
+        platforms: vec![PlatformFile { x: 0.0, y: 100.0, width: 200.0, height: PLATFORM_HEIGHT, surface: None, one_way: false, slope: 0.0 }],
+        fruit: None,
+        exit: None,
+        enemies: Vec::new(),
+        enemy_zones: Vec::new(),
+        checkpoints: Vec::new(),
+        moving: Vec::new(),
+        ladders: Vec::new(),
+        hazards: Vec::new(),
+        portals: Vec::new(),
+        warnings: Vec::new(),
+    }
+}
+
+// What the mouse is doing with the platforms tool
+#[derive(Resource, Default)]
+enum EditorDrag {
+    #[default]
+    Idle,
+    // Drawing a new platform from this corner
+    Drawing(Vec2),
+    // Moving a platform, grabbed this far from its centre
+    Moving(usize, Vec2),
+}
+
+#[derive(Component)]
+struct EditorUI;
+
+#[derive(Component)]
+struct EditorStatusText;
+
+// Sprites showing the level, rebuilt whenever it changes
+#[derive(Component)]
+struct EditorShape;
+
+fn snap(point: Vec2) -> Vec2 {
+    (point / EDITOR_GRID).round() * EDITOR_GRID
+}
+
+// A platform spanning two corners, no smaller than the smallest platform
+fn platform_between(a: Vec2, b: Vec2) -> PlatformFile {
+    let (a, b) = (snap(a), snap(b));
+    let centre = (a + b) / 2.0;
+    let size = (a - b).abs().max(Vec2::new(MIN_PLATFORM_WIDTH, PLATFORM_HEIGHT));
+    PlatformFile { x: centre.x, y: centre.y, width: size.x, height: size.y, surface: None, one_way: false, slope: 0.0 }
+}
+
+fn editor_status(editor: &EditorLevel) -> String {
+    let tools: Vec<String> = EditorTool::ALL
+        .into_iter()
+        .enumerate()
+        .map(|(index, tool)| {
+            let marker = if tool == editor.tool { ">" } else { "" };
+            format!("{}{}: {}", marker, index + 1, tool.name())
+        })
+        .collect();
+    format!(
+        "LEVEL EDITOR - level {}{}   {}\nRight click: remove platform   Arrows: pan   PgUp/PgDn: level   Ctrl+S: save   ESC: menu",
+        editor.number,
+        if editor.unsaved { " (unsaved)" } else { "" },
+        tools.join("   ")
+    )
+}
+
+fn open_level_editor(keyboard_input: Res<ButtonInput<KeyCode>>, mut next_state: ResMut<NextState<AppState>>) {
+    if keyboard_input.just_pressed(KeyCode::KeyN) {
+        next_state.set(AppState::LevelEditor);
+    }
+}
+
+fn enter_level_editor(
+    mut commands: Commands,
+    mut editor: ResMut<EditorLevel>,
+    mut camera_query: Query<&mut Transform, With<MainCamera>>,
+    mut errors: EventWriter<RuntimeErrorEvent>,
+) {
+    let number = editor.number;
+    editor.load(number, &mut errors);
+    if let Ok(mut transform) = camera_query.get_single_mut() {
+        transform.translation.x = 0.0;
+        transform.translation.y = 0.0;
+    }
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                editor_status(&editor),
+                TextStyle {
+                    font_size: 20.0,
+                    color: Color::srgb(0.0, 1.0, 0.0), // Green
+                    ..default()
+                },
+            ),
+            transform: Transform::from_translation(Vec3::new(0.0, 360.0, 16.0)),
+            ..default()
+        },
+        EditorStatusText,
+        EditorUI,
+    ));
+}
+
+fn exit_level_editor(mut drag: ResMut<EditorDrag>) {
+    *drag = EditorDrag::Idle;
+}
+
+fn handle_editor_keys(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut editor: ResMut<EditorLevel>,
+    mut handmade_levels: ResMut<HandmadeLevels>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut toasts: ResMut<Toasts>,
+    mut errors: EventWriter<RuntimeErrorEvent>,
+    mut status_query: Query<&mut Text, With<EditorStatusText>>,
+) {
+    if keyboard_input.just_pressed(KeyCode::Escape) {
+        next_state.set(AppState::MainMenu);
+        return;
+    }
+    let keys = [KeyCode::Digit1, KeyCode::Digit2, KeyCode::Digit3, KeyCode::Digit4];
+    if let Some(index) = keys.iter().position(|key| keyboard_input.just_pressed(*key)) {
+        editor.tool = EditorTool::ALL[index];
+    }
+    let step = if keyboard_input.just_pressed(KeyCode::PageUp) {
+        1
+    } else if keyboard_input.just_pressed(KeyCode::PageDown) {
+        -1
+    } else {
+        0
+    };
+    if step != 0 {
+        let number = editor.number.saturating_add_signed(step).max(1);
+        if editor.unsaved {
+            toasts.push(format!("Level {} changes dropped", editor.number));
+        }
+        editor.load(number, &mut errors);
+    }
+    let ctrl = keyboard_input.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+    if ctrl && keyboard_input.just_pressed(KeyCode::KeyS) {
+        match editor.save() {
+            Ok(()) => {
+                handmade_levels.set(editor.number, &editor.file);
+                toasts.push(format!("Level {} saved to {}", editor.number, level_file_path(editor.number).display()));
+            }
+            Err(error) => {
+                errors.send(error);
+            }
+        }
+    }
+
+    if editor.is_changed() {
+        if let Ok(mut text) = status_query.get_single_mut() {
+            text.sections[0].value = editor_status(&editor);
+        }
+    }
+}
+
+// Arrow keys move around the level, without going past its edges
+fn pan_editor_camera(
+    time: Res<Time>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut camera_query: Query<&mut Transform, With<MainCamera>>,
+) {
+    let Ok(mut transform) = camera_query.get_single_mut() else {
+        return;
+    };
+    let mut direction = Vec2::ZERO;
+    for (key, step) in [(KeyCode::ArrowLeft, Vec2::NEG_X), (KeyCode::ArrowRight, Vec2::X), (KeyCode::ArrowUp, Vec2::Y), (KeyCode::ArrowDown, Vec2::NEG_Y)] {
+        if keyboard_input.pressed(key) {
+            direction += step;
+        }
+    }
+    if direction == Vec2::ZERO {
+        return;
+    }
+    let bounds = GameMode::Classic.world_bounds();
+    let limit = Vec2::new(bounds.half_width(), bounds.half_height());
+    let position = (transform.translation.truncate() + direction * PAN_SPEED * time.delta_seconds()).clamp(-limit, limit);
+    transform.translation.x = position.x;
+    transform.translation.y = position.y;
+}
+
+pub fn cursor_world_position(windows: &Query<&Window>, camera_query: &Query<(&Camera, &GlobalTransform), With<MainCamera>>) -> Option<Vec2> {
+    let cursor = windows.get_single().ok()?.cursor_position()?;
+    let (camera, camera_transform) = camera_query.get_single().ok()?;
+    // The view may sit inside bars, and the camera wants the cursor from its corner
+    let corner = camera.logical_viewport_rect()?.min;
+    camera.viewport_to_world_2d(camera_transform, cursor - corner)
+}
+
+fn edit_with_mouse(
+    mouse_buttons: Res<ButtonInput<MouseButton>>,
+    windows: Query<&Window>,
+    camera_query: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
+    mut editor: ResMut<EditorLevel>,
+    mut drag: ResMut<EditorDrag>,
+) {
+    let Some(cursor) = cursor_world_position(&windows, &camera_query) else {
+        return;
+    };
+
+    if mouse_buttons.just_pressed(MouseButton::Right) {
+        // The level always keeps a platform to start on
+        if let Some(index) = editor.platform_at(cursor).filter(|_| editor.file.platforms.len() > 1) {
+            editor.remove_platform(index);
+        }
+        *drag = EditorDrag::Idle;
+        return;
+    }
+
+    if mouse_buttons.just_pressed(MouseButton::Left) {
+        match editor.tool {
+            EditorTool::Platforms => {
+                *drag = match editor.platform_at(cursor) {
+                    Some(index) => {
+                        let platform = &editor.file.platforms[index];
+                        EditorDrag::Moving(index, cursor - Vec2::new(platform.x, platform.y))
+                    }
+                    None => EditorDrag::Drawing(cursor),
+                };
+            }
+            EditorTool::Fruit => {
+                editor.file.fruit = Some(snap(cursor).into());
+                editor.unsaved = true;
+            }
+            EditorTool::Enemies => {
+                if let Some(index) = editor.platform_at(cursor) {
+                    let enemies = &mut editor.file.enemies;
+                    match enemies.iter().position(|&platform| platform == index) {
+                        Some(enemy) => {
+                            enemies.remove(enemy);
+                        }
+                        None => enemies.push(index),
+                    }
+                    editor.unsaved = true;
+                }
+            }
+            EditorTool::Spawn => {
+                editor.file.player_spawn = snap(cursor).into();
+                editor.unsaved = true;
+            }
+        }
+    }
+
+    match *drag {
+        EditorDrag::Moving(index, grab) if mouse_buttons.pressed(MouseButton::Left) => {
+            let position = snap(cursor - grab);
+            let moved = editor.file.platforms.get(index).is_some_and(|platform| (platform.x, platform.y) != (position.x, position.y));
+            if moved {
+                let platform = &mut editor.file.platforms[index];
+                platform.x = position.x;
+                platform.y = position.y;
+                editor.unsaved = true;
+            }
+        }
+        EditorDrag::Drawing(start) if mouse_buttons.just_released(MouseButton::Left) => {
+            editor.file.platforms.push(platform_between(start, cursor));
+            editor.unsaved = true;
+            *drag = EditorDrag::Idle;
+        }
+        EditorDrag::Drawing(_) => {}
+        _ => *drag = EditorDrag::Idle,
+    }
+}
+
+// Mouse wheel over a platform widens or narrows it; with Shift it changes
+// its height
+fn resize_with_wheel(
+    mut wheel_events: EventReader<MouseWheel>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    windows: Query<&Window>,
+    camera_query: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
+    mut editor: ResMut<EditorLevel>,
+) {
+    let scroll: f32 = wheel_events
+        .read()
+        .map(|event| match event.unit {
+            MouseScrollUnit::Line => event.y,
+            MouseScrollUnit::Pixel => event.y / 20.0,
+        })
+        .sum();
+    if scroll == 0.0 {
+        return;
+    }
+    let Some(index) = cursor_world_position(&windows, &camera_query).and_then(|cursor| editor.platform_at(cursor)) else {
+        return;
+    };
+    let step = scroll.signum() * EDITOR_GRID * 2.0;
+    let platform = &mut editor.file.platforms[index];
+    if keyboard_input.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) {
+        platform.height = (platform.height + step).max(PLATFORM_HEIGHT);
+    } else {
+        platform.width = (platform.width + step).max(MIN_PLATFORM_WIDTH);
+    }
+    editor.unsaved = true;
+}
+
+fn shape(commands: &mut Commands, position: Vec2, size: Vec2, color: Color, z: f32) -> Entity {
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color,
+                custom_size: Some(size),
+                ..default()
+            },
+            transform: Transform::from_translation(position.extend(z)),
+            ..default()
+        },
+        EditorShape,
+    )).id()
+}
+
+// Respawns the level's sprites when it changes
+fn draw_editor_level(mut commands: Commands, editor: Res<EditorLevel>, shape_query: Query<Entity, With<EditorShape>>) {
+    if !editor.is_changed() {
+        return;
+    }
+    for entity in shape_query.iter() {
+        commands.entity(entity).despawn();
+    }
+    let file = &editor.file;
+    for ladder in &file.ladders {
+        let width = if ladder.rope { ROPE_WIDTH } else { LADDER_WIDTH };
+        let position = Vec2::new(ladder.x, (ladder.top + ladder.bottom) / 2.0);
+        shape(&mut commands, position, Vec2::new(width, ladder.top - ladder.bottom), ladder_color(ladder.rope), -0.5);
+    }
+    for hazard in &file.hazards {
+        let color = HazardKind::from_name(&hazard.kind).map_or(Color::WHITE, hazard_color);
+        shape(&mut commands, Vec2::new(hazard.x, hazard.y), Vec2::new(hazard.width, hazard.height), color, 0.1);
+    }
+    for (index, portal) in file.portals.iter().enumerate() {
+        for end in [portal.a, portal.b] {
+            shape(&mut commands, end.into(), Vec2::new(PORTAL_WIDTH, PORTAL_HEIGHT), portal_color(index).with_alpha(0.4), -0.4);
+        }
+    }
+    if let Some(exit) = &file.exit {
+        let door = Vec2::new(exit.door.0, exit.door.1 + DOOR_HEIGHT / 2.0);
+        shape(&mut commands, door, Vec2::new(DOOR_WIDTH, DOOR_HEIGHT), door_color(false), -0.4);
+        for &key in &exit.keys {
+            shape(&mut commands, key.into(), KEY_SIZE, KEY_COLOR, 1.0);
+        }
+    }
+    for platform in &file.platforms {
+        let surface = platform.surface.as_deref().and_then(Surface::from_name).unwrap_or(Surface::Stone);
+        let (size, rotation) = platform_shape(&platform.spec());
+        let sprite = shape(&mut commands, Vec2::new(platform.x, platform.y), size, surface_color(surface, platform.one_way, false, None), 0.0);
+        commands.entity(sprite).insert(Transform::from_xyz(platform.x, platform.y, 0.0).with_rotation(rotation));
+    }
+    for platform in file.enemies.iter().filter_map(|&index| file.platforms.get(index)) {
+        let position = Vec2::new(platform.x, platform.y + platform.height / 2.0 + PLAYER_SIZE / 2.0);
+        shape(&mut commands, position, Vec2::splat(PLAYER_SIZE), ENEMY_COLOR, 1.0);
+    }
+    if let Some(fruit) = file.fruit {
+        shape(&mut commands, fruit.into(), Vec2::splat(FRUIT_SIZE), FRUIT_COLOR, 1.0);
+    }
+    shape(&mut commands, file.player_spawn.into(), Vec2::splat(PLAYER_SIZE), SPAWN_COLOR, 2.0);
+}
+
+// The level's edges, its zones, the platform under the mouse (and the jumps
+// off its ends) and the one being drawn
+fn draw_editor_guides(
+    mut gizmos: Gizmos,
+    editor: Res<EditorLevel>,
+    drag: Res<EditorDrag>,
+    windows: Query<&Window>,
+    camera_query: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
+) {
+    let bounds = GameMode::Classic.world_bounds();
+    gizmos.rect_2d(Vec2::ZERO, 0.0, Vec2::new(bounds.half_width(), bounds.half_height()) * 2.0, BOUNDS_COLOR);
+    let file = &editor.file;
+    let checkpoints: Vec<(f32, f32)> = file
+        .checkpoints
+        .iter()
+        .filter_map(|&index| file.platforms.get(index))
+        .map(|platform| (platform.x, platform.y + platform.height / 2.0))
+        .collect();
+    for zone in safe_zones(file.player_spawn, &checkpoints) {
+        gizmos.circle_2d(Vec2::new(zone.x, zone.y), zone.radius, SAFE_ZONE_COLOR);
+    }
+    for zone in &file.enemy_zones {
+        gizmos.rect_2d(Vec2::new(zone.x, zone.y), 0.0, Vec2::new(zone.width, zone.height), ENEMY_ZONE_COLOR);
+    }
+    let Some(cursor) = cursor_world_position(&windows, &camera_query) else {
+        return;
+    };
+    let hovered = match *drag {
+        EditorDrag::Drawing(_) => None,
+        _ => editor.platform_at(cursor),
+    };
+    let outlined = match *drag {
+        EditorDrag::Drawing(start) => Some(platform_between(start, cursor)),
+        _ => hovered.map(|index| file.platforms[index].clone()),
+    };
+    if let Some(platform) = outlined {
+        let (size, _) = platform_shape(&platform.spec());
+        gizmos.rect_2d(Vec2::new(platform.x, platform.y), platform.slope.atan(), size, GUIDE_COLOR);
+    }
+    let Some(from) = hovered.map(|index| file.platforms[index].spec()) else {
+        return;
+    };
+    let platforms: Vec<_> = file.platforms.iter().map(PlatformFile::spec).collect();
+    let profile = JumpProfile::new(&Tuning::default(), PLAYER_SIZE);
+    for direction in [-1.0, 1.0] {
+        let edge = (from.x + direction * from.width / 2.0, from.y + from.height / 2.0 + PLAYER_SIZE / 2.0);
+        draw_jump_arc(&mut gizmos, &profile.jump_arc(edge, direction, &platforms, JUMP_ARC_DROP), GUIDE_COLOR);
+    }
+}
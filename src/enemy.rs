@@ -0,0 +1,75 @@
+//! Enemies patrolling their platforms and chasing the player in Classic runs.
+
+use bevy::prelude::*;
+use crate::prelude::*;
+use rustbevy_core::enemy::{EnemyAi, EnemyBehavior, ENEMY_SIZE};
+
+use crate::physics::{check_collisions, solid_platform};
+use crate::spatial::SpatialIndex;
+
+pub struct EnemyPlugin;
+
+impl Plugin for EnemyPlugin {
+    fn build(&self, app: &mut App) {
+        app.configure_sets(FixedUpdate, EnemyAiSet.run_if(in_state(AppState::Playing).and_then(resource_equals(GameMode::Classic))))
+            .add_systems(FixedUpdate, move_enemies.in_set(EnemyAiSet).after(check_collisions));
+    }
+}
+
+const ENEMY_PATROL_COLOR: Color = Color::srgb(0.55, 0.1, 0.1); // Dark red
+const ENEMY_CHASE_COLOR: Color = Color::srgb(1.0, 0.15, 0.15); // Bright red while chasing
+
+// Enemy patrol and chase movement, Classic runs only. It steps with the
+// player's physics; touching an enemy is checked after the steps.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+struct EnemyAiSet;
+
+pub fn spawn_enemy(commands: &mut Commands, ai: EnemyAi) {
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: ENEMY_PATROL_COLOR,
+                custom_size: Some(Vec2::splat(ENEMY_SIZE)),
+                ..default()
+            },
+            transform: Transform::from_translation(Vec3::new(ai.x, ai.y, 0.5)),
+            ..default()
+        },
+        Enemy(ai),
+        Hazard,
+        Budgeted::new(BudgetCategory::Enemies, 0),
+    ));
+}
+
+/// Patrols and chases (the nearest player, in co-op), stopping at edges;
+/// touching an enemy is handled by check_player_death
+pub fn move_enemies(
+    time: Res<Time>,
+    index: Res<SpatialIndex>,
+    player_query: Query<&Transform, (With<Player>, Without<Enemy>)>,
+    platform_query: Query<(&Transform, &Platform, Has<OneWay>, Option<&Crumbling>, Option<&Trap>), Without<Enemy>>,
+    mut enemy_query: Query<(&mut Transform, &mut Sprite, &mut Enemy)>,
+) {
+    for (mut transform, mut sprite, mut enemy) in enemy_query.iter_mut() {
+        let enemy_position = transform.translation;
+        // Anything further away than it chases can't hide the player or hold it up
+        let reach = enemy.0.chase_radius + ENEMY_SIZE;
+        let platforms: Vec<_> = index
+            .platforms
+            .query((enemy_position.x, enemy_position.y), (reach, reach))
+            .into_iter()
+            .filter_map(|entity| platform_query.get(entity).ok())
+            .filter_map(|(transform, platform, one_way, crumbling, trap)| solid_platform(transform, platform, one_way, crumbling, trap))
+            .collect();
+        let player = player_query
+            .iter()
+            .min_by(|a, b| a.translation.distance_squared(enemy_position).total_cmp(&b.translation.distance_squared(enemy_position)))
+            .map(|transform| (transform.translation.x, transform.translation.y));
+        enemy.0.update(player, &platforms, time.delta_seconds());
+        transform.translation.x = enemy.0.x;
+        sprite.color = match enemy.0.behavior {
+            EnemyBehavior::Patrol => ENEMY_PATROL_COLOR,
+            EnemyBehavior::Chase => ENEMY_CHASE_COLOR,
+        };
+    }
+}
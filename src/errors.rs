@@ -0,0 +1,38 @@
+// Non-fatal error reporting.
+//
+// Anything that fails without stopping the game (saving, loading an asset, a
+// network call) sends a `RuntimeErrorEvent`. The details are logged and the
+// player gets a short toast, once per message while it's still on screen, so a
+// failure that repeats every frame doesn't flood the banner. Asset load
+// failures are picked up from Bevy's own events, so loaders don't have to send
+// anything.
+
+use bevy::asset::UntypedAssetLoadFailedEvent;
+use bevy::prelude::*;
+use crate::prelude::*;
+
+use crate::ui::Toasts;
+
+pub struct ErrorReportPlugin;
+
+impl Plugin for ErrorReportPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<RuntimeErrorEvent>()
+            .add_systems(Update, (report_asset_failures, report_runtime_errors).chain());
+    }
+}
+
+fn report_asset_failures(mut failed_events: EventReader<UntypedAssetLoadFailedEvent>, mut errors: EventWriter<RuntimeErrorEvent>) {
+    for event in failed_events.read() {
+        errors.send(RuntimeErrorEvent::new(format!("Could not load {}", event.path), event.error.to_string()));
+    }
+}
+
+fn report_runtime_errors(mut error_events: EventReader<RuntimeErrorEvent>, mut toasts: ResMut<Toasts>) {
+    for event in error_events.read() {
+        error!("{}: {}", event.summary, event.details);
+        if !toasts.contains(&event.summary) {
+            toasts.push(event.summary.clone());
+        }
+    }
+}
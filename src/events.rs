@@ -0,0 +1,156 @@
+// Gameplay events. Extension plugins can read these to react to what happens
+// in a run, or send `PlaySoundEvent` to reuse the game's sound effects.
+
+use bevy::prelude::*;
+use rustbevy_core::fruit::FruitKind;
+use rustbevy_core::mixer::Duck;
+use rustbevy_core::surface::Surface;
+
+use crate::resources::GameMode;
+
+// Ask the audio system to play one of the game's sounds. `position` is where
+// in the world it comes from, for sounds the player might not be looking at
+// the source of; sound indicators point there.
+#[derive(Event)]
+pub struct PlaySoundEvent {
+    pub sound_type: SoundType,
+    pub position: Option<Vec2>,
+}
+
+#[derive(Clone, Copy)]
+pub enum SoundType {
+    Jump,
+    Collect,
+    Death,
+    // A player touching down after a jump or fall
+    Land,
+    // The same, on a platform of a known surface
+    LandOn(Surface),
+    // A player's step while running along a platform
+    Footstep(Surface),
+    // Jingle for reaching a new level
+    LevelUp,
+    // The companion's warning chirp
+    Squeak,
+    // Jingle for an extra life
+    ExtraLife,
+    // A crumbling platform starting to give way
+    Crack,
+}
+
+impl SoundType {
+    // The sound's name in audio pack manifests
+    pub fn name(&self) -> &'static str {
+        match self {
+            SoundType::Jump => "jump",
+            SoundType::Collect => "collect",
+            SoundType::Death => "death",
+            SoundType::Land => "land",
+            SoundType::LandOn(surface) => match surface {
+                Surface::Stone => "land_stone",
+                Surface::Wood => "land_wood",
+                Surface::Metal => "land_metal",
+                Surface::Ice => "land_ice",
+            },
+            SoundType::Footstep(surface) => match surface {
+                Surface::Stone => "step_stone",
+                Surface::Wood => "step_wood",
+                Surface::Metal => "step_metal",
+                Surface::Ice => "step_ice",
+            },
+            SoundType::LevelUp => "level_up",
+            SoundType::Squeak => "squeak",
+            SoundType::ExtraLife => "extra_life",
+            SoundType::Crack => "crack",
+        }
+    }
+
+    // How the music ducks under it, for the sounds that have to be heard
+    pub fn duck(&self) -> Option<Duck> {
+        match self {
+            SoundType::Death => Some(Duck::HIT),
+            SoundType::LevelUp | SoundType::ExtraLife => Some(Duck::JINGLE),
+            _ => None,
+        }
+    }
+}
+
+// Ask the particle system for a burst of one of the game's effects, without
+// knowing how it's drawn. Bursts happen in the world at `position`, except
+// `Confetti`, which is thrown across the whole screen and ignores it. Nothing
+// is drawn when the graphics quality turns particles off.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct SpawnParticles {
+    pub effect: ParticleEffect,
+    pub position: Vec2,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParticleEffect {
+    // A fruit being picked up
+    FruitBurst,
+    // Puffs kicked up by a hard landing; `position` is the player's feet
+    LandingDust,
+    // A player dying, or an enemy being stomped
+    DeathExplosion,
+    // Confetti from the bottom corners and a few fireworks, for finishing a level or a game
+    Confetti { fireworks: usize },
+}
+
+// Sent when a new run starts (not when play resumes after the pause menu or a
+// draft). Run state is reset and the first level built in response.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct GameResetEvent {
+    pub mode: GameMode,
+}
+
+// Sent when the player touches the fruit in Classic mode. The fruit is already
+// gone; scoring it and moving on to the next level happen in response.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct FruitCollectedEvent {
+    pub kind: FruitKind,
+    pub position: Vec2,
+}
+
+// Sent once a Classic mode level is done; `level` is the new level. The next
+// level is built in response. `skipped` is set when the level skip assist
+// moved the player on instead of the fruit.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct LevelCompletedEvent {
+    pub level: u32,
+    pub skipped: bool,
+}
+
+// Sent when a Classic mode player falls out of the world or runs into an enemy.
+// The life is taken (down to `lives_left`) and the player respawned or the run
+// ended in response.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct PlayerDiedEvent {
+    pub player: Entity,
+    pub lives_left: u32,
+}
+
+// Sent every physics step a player stands on a platform, once the collisions
+// are resolved. `landed` is set on the step the player came down on it.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct PlatformContactEvent {
+    pub player: Entity,
+    pub platform: Entity,
+    pub landed: bool,
+}
+
+// Send when something fails that the game can carry on without: a save file
+// that couldn't be written, an asset that didn't load, a network call. The
+// player sees `summary` in a toast; `details` (paths, the OS error) goes to the
+// log.
+#[derive(Event, Clone, Debug)]
+pub struct RuntimeErrorEvent {
+    pub summary: String,
+    pub details: String,
+}
+
+impl RuntimeErrorEvent {
+    pub fn new(summary: impl Into<String>, details: impl Into<String>) -> Self {
+        Self { summary: summary.into(), details: details.into() }
+    }
+}
@@ -0,0 +1,166 @@
+//! Exit doors and keys in Classic door levels (see `rustbevy_core::exit` for
+//! which levels have them and where they go). A player touching a key picks
+//! it up for everyone; once every key is held the door opens, and a player
+//! walking into the open door finishes the level the way collecting the last
+//! fruit does, with a `LevelCompletedEvent`.
+//!
+//! The door is a dark wooden slab with a gold lock while it's shut, and a
+//! lit doorway once it's open. Keys are gold.
+
+use bevy::prelude::*;
+use crate::prelude::*;
+use rustbevy_core::config::FRUIT_SIZE;
+use rustbevy_core::exit::{enters_door, ExitDoor as DoorSpec, DOOR_HEIGHT, DOOR_WIDTH};
+use rustbevy_core::physics;
+
+use crate::character_select::PlayerProfile;
+use crate::fruit::build_next_level;
+use crate::physics::{player_body, player_tuning};
+use crate::ui::Toasts;
+
+pub struct ExitPlugin;
+
+impl Plugin for ExitPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, (pick_up_keys, enter_open_doors, color_doors)
+            .chain()
+            .before(build_next_level)
+            .run_if(in_state(AppState::Playing).and_then(resource_equals(GameMode::Classic))));
+    }
+}
+
+const LOCKED_COLOR: Color = Color::srgb(0.4, 0.25, 0.12); // Dark wood
+const OPEN_COLOR: Color = Color::srgb(1.0, 0.9, 0.55); // Lit doorway
+pub const KEY_COLOR: Color = Color::srgb(1.0, 0.8, 0.15); // Gold
+const LOCK_SIZE: Vec2 = Vec2::new(10.0, 12.0);
+pub const KEY_SIZE: Vec2 = Vec2::new(FRUIT_SIZE, FRUIT_SIZE * 0.5);
+
+pub fn door_color(open: bool) -> Color {
+    if open {
+        OPEN_COLOR
+    } else {
+        LOCKED_COLOR
+    }
+}
+
+// The lock on a shut door, hidden once it opens
+#[derive(Component)]
+struct DoorLock;
+
+/// Spawns the door and its keys
+pub fn spawn_exit(commands: &mut Commands, spec: &DoorSpec) {
+    let (x, y) = spec.door;
+    let door = ExitDoor { spec: spec.clone(), keys_held: 0 };
+    let open = door.is_open();
+    commands
+        .spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: door_color(open),
+                    custom_size: Some(Vec2::new(DOOR_WIDTH, DOOR_HEIGHT)),
+                    ..default()
+                },
+                transform: Transform::from_translation(Vec3::new(x, y + DOOR_HEIGHT / 2.0, -0.5)),
+                ..default()
+            },
+            door,
+        ))
+        .with_children(|door| {
+            door.spawn((
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: KEY_COLOR,
+                        custom_size: Some(LOCK_SIZE),
+                        ..default()
+                    },
+                    transform: Transform::from_translation(Vec3::new(DOOR_WIDTH / 4.0, 0.0, 0.1)),
+                    visibility: if open { Visibility::Hidden } else { Visibility::Inherited },
+                    ..default()
+                },
+                DoorLock,
+            ));
+        });
+    for &(x, y) in &spec.keys {
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: KEY_COLOR,
+                    custom_size: Some(KEY_SIZE),
+                    ..default()
+                },
+                transform: Transform::from_translation(Vec3::new(x, y, 0.0)),
+                ..default()
+            },
+            Key,
+        ));
+    }
+}
+
+// Keys are picked up the way fruit is, one a frame
+fn pick_up_keys(
+    mut commands: Commands,
+    player_query: Query<(&Transform, &Velocity, &Grounded, &Collider), With<Player>>,
+    key_query: Query<(Entity, &Transform), (With<Key>, Without<Player>)>,
+    mut door_query: Query<&mut ExitDoor>,
+    run_modifiers: Res<RunModifiers>,
+    assist_mode: Res<AssistMode>,
+    player_profile: Res<PlayerProfile>,
+    mut toasts: ResMut<Toasts>,
+    mut sound_events: EventWriter<PlaySoundEvent>,
+) {
+    let tuning = player_tuning(GameMode::Classic, &run_modifiers, &assist_mode, &player_profile);
+    let bodies: Vec<_> = player_query
+        .iter()
+        .map(|(transform, velocity, grounded, collider)| player_body(transform, velocity, grounded, collider))
+        .collect();
+    let Some((key, position)) = key_query
+        .iter()
+        .map(|(key, transform)| (key, transform.translation.truncate()))
+        .find(|&(_, position)| bodies.iter().any(|body| physics::touches_fruit(body, position.into(), &tuning)))
+    else {
+        return;
+    };
+    commands.entity(key).despawn();
+    sound_events.send(PlaySoundEvent { sound_type: SoundType::Collect, position: Some(position) });
+    for mut door in door_query.iter_mut() {
+        door.keys_held += 1;
+        if door.is_open() {
+            toasts.push("The exit door is open!".to_string());
+        } else {
+            toasts.push(format!("Key {} of {}", door.keys_held, door.spec.keys.len()));
+        }
+    }
+}
+
+// Any player walking into an open door finishes the level for everyone
+fn enter_open_doors(
+    player_query: Query<(&Transform, &Velocity, &Grounded, &Collider), With<Player>>,
+    door_query: Query<&ExitDoor>,
+    mut game_state: ResMut<GameState>,
+    mut difficulty: ResMut<Difficulty>,
+    mut level_events: EventWriter<LevelCompletedEvent>,
+) {
+    let entered = door_query.iter().filter(|door| door.is_open()).any(|door| {
+        player_query
+            .iter()
+            .any(|(transform, velocity, grounded, collider)| enters_door(&player_body(transform, velocity, grounded, collider), door.spec.door))
+    });
+    if entered {
+        game_state.level += 1;
+        level_events.send(LevelCompletedEvent { level: game_state.level, skipped: false });
+        difficulty.0.record_clear();
+    }
+}
+
+fn color_doors(
+    mut door_query: Query<(&ExitDoor, &Children, &mut Sprite), Changed<ExitDoor>>,
+    mut lock_query: Query<&mut Visibility, With<DoorLock>>,
+) {
+    for (door, children, mut sprite) in door_query.iter_mut() {
+        sprite.color = door_color(door.is_open());
+        let mut locks = lock_query.iter_many_mut(children);
+        while let Some(mut visibility) = locks.fetch_next() {
+            *visibility = if door.is_open() { Visibility::Hidden } else { Visibility::Inherited };
+        }
+    }
+}
@@ -0,0 +1,224 @@
+// Favorite levels.
+//
+// From the pause menu the player can favorite the level they're on. A favorite
+// is everything needed to generate that layout again (run seed, level number,
+// difficulty) plus the mode it was played in. Favorites are saved in the save
+// directory and listed, by level name, on the Favorites screen (F on the main menu), where a
+// number key drops you straight into that level.
+
+use bevy::prelude::*;
+use crate::prelude::*;
+use rustbevy_core::naming::level_name;
+
+use crate::game::{read_save_file, write_save_file};
+use crate::streamer::StreamerMode;
+use crate::ui::despawn_screen;
+
+pub struct FavoritesPlugin;
+
+impl Plugin for FavoritesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Favorites>()
+            .init_resource::<PendingFavorite>()
+            .add_systems(OnEnter(AppState::Favorites), enter_favorites)
+            .add_systems(OnExit(AppState::Favorites), despawn_screen::<FavoritesUI>)
+            .add_systems(Update, (handle_favorites_input.run_if(in_state(AppState::Favorites)), save_favorites).chain());
+    }
+}
+
+const FAVORITES_FILE: &str = "favorites.txt";
+// One per number key on the Favorites screen
+pub const MAX_FAVORITES: usize = 9;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Favorite {
+    pub run_seed: u64,
+    pub level: u32,
+    pub difficulty: f32,
+    pub mode: GameMode,
+}
+
+impl Favorite {
+    // One line of the save file: "<seed> <level> <difficulty> <mode name>"
+    fn to_line(self) -> String {
+        format!("{} {} {} {}", self.run_seed, self.level, self.difficulty, self.mode.name())
+    }
+
+    fn from_line(line: &str) -> Option<Self> {
+        let mut fields = line.trim().splitn(4, ' ');
+        Some(Self {
+            run_seed: fields.next()?.parse().ok()?,
+            level: fields.next()?.parse().ok()?,
+            difficulty: fields.next()?.parse().ok()?,
+            mode: GameMode::from_name(fields.next()?)?,
+        })
+    }
+
+    fn label(self, streamer: &StreamerMode) -> String {
+        format!("Level {}: {} ({}, seed {}, difficulty {:.0}%)", self.level, level_name(self.run_seed, self.level), self.mode.name(), streamer.seed_label(self.run_seed), self.difficulty * 100.0)
+    }
+}
+
+#[derive(Resource)]
+pub struct Favorites {
+    pub saved: Vec<Favorite>,
+}
+
+impl Default for Favorites {
+    fn default() -> Self {
+        let saved = read_save_file(FAVORITES_FILE).unwrap_or_default();
+        Self {
+            saved: saved.lines().filter_map(Favorite::from_line).take(MAX_FAVORITES).collect(),
+        }
+    }
+}
+
+impl Favorites {
+    // Message for the toast banner saying how it went
+    pub fn add(&mut self, favorite: Favorite) -> &'static str {
+        if self.saved.contains(&favorite) {
+            return "This level is already a favorite";
+        }
+        if self.saved.len() >= MAX_FAVORITES {
+            return "Favorites are full - remove one on the Favorites screen first";
+        }
+        self.saved.push(favorite);
+        "Level saved to favorites"
+    }
+
+    fn save(&self) -> Result<(), RuntimeErrorEvent> {
+        let lines: Vec<String> = self.saved.iter().map(|favorite| favorite.to_line()).collect();
+        write_save_file(FAVORITES_FILE, &lines.join("\n"), "favorites")
+    }
+}
+
+// The favorite to start at when the next game is set up
+#[derive(Resource, Default)]
+pub struct PendingFavorite(pub Option<Favorite>);
+
+#[derive(Component)]
+pub struct FavoritesUI;
+
+pub fn setup_favorites_screen(commands: &mut Commands, favorites: &Favorites, streamer: &StreamerMode) {
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                "FAVORITES",
+                TextStyle {
+                    font_size: 60.0,
+                    color: Color::srgb(1.0, 0.5, 0.0), // Orange
+                    ..default()
+                },
+            ),
+            transform: Transform::from_translation(Vec3::new(0.0, 280.0, 10.0)),
+            ..default()
+        },
+        FavoritesUI,
+    ));
+
+    let lines = if favorites.saved.is_empty() {
+        "No favorites yet. Press P during a Classic run and F to favorite a level.".to_string()
+    } else {
+        favorites
+            .saved
+            .iter()
+            .enumerate()
+            .map(|(index, favorite)| format!("{}: {}", index + 1, favorite.label(streamer)))
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                lines,
+                TextStyle {
+                    font_size: 26.0,
+                    color: Color::srgb(1.0, 1.0, 0.0), // Yellow
+                    ..default()
+                },
+            ),
+            transform: Transform::from_translation(Vec3::new(0.0, 40.0, 10.0)),
+            ..default()
+        },
+        FavoritesUI,
+    ));
+
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                "1-9: Play   SHIFT + 1-9: Remove   ESC: Back",
+                TextStyle {
+                    font_size: 25.0,
+                    color: Color::srgb(0.0, 1.0, 0.0), // Green
+                    ..default()
+                },
+            ),
+            transform: Transform::from_translation(Vec3::new(0.0, -250.0, 10.0)),
+            ..default()
+        },
+        FavoritesUI,
+    ));
+}
+
+fn save_favorites(favorites: Res<Favorites>, mut errors: EventWriter<RuntimeErrorEvent>) {
+    if favorites.is_changed() && !favorites.is_added() {
+        if let Err(error) = favorites.save() {
+            errors.send(error);
+        }
+    }
+}
+
+fn enter_favorites(mut commands: Commands, favorites: Res<Favorites>, streamer: Res<StreamerMode>) {
+    setup_favorites_screen(&mut commands, &favorites, &streamer);
+}
+
+pub fn handle_favorites_input(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut commands: Commands,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut game_mode: ResMut<GameMode>,
+    mut game_rng: ResMut<GameRng>,
+    mut favorites: ResMut<Favorites>,
+    mut pending_favorite: ResMut<PendingFavorite>,
+    favorites_query: Query<Entity, With<FavoritesUI>>,
+    streamer: Res<StreamerMode>,
+) {
+    const NUMBER_KEYS: [KeyCode; MAX_FAVORITES] = [
+        KeyCode::Digit1,
+        KeyCode::Digit2,
+        KeyCode::Digit3,
+        KeyCode::Digit4,
+        KeyCode::Digit5,
+        KeyCode::Digit6,
+        KeyCode::Digit7,
+        KeyCode::Digit8,
+        KeyCode::Digit9,
+    ];
+
+    if keyboard_input.just_pressed(KeyCode::Escape) {
+        next_state.set(AppState::MainMenu);
+        return;
+    }
+
+    let Some(index) = NUMBER_KEYS.iter().position(|key| keyboard_input.just_pressed(*key)) else {
+        return;
+    };
+    let Some(favorite) = favorites.saved.get(index).copied() else {
+        return;
+    };
+
+    if keyboard_input.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) {
+        favorites.saved.remove(index);
+        // Rebuild the list
+        for entity in favorites_query.iter() {
+            commands.entity(entity).despawn();
+        }
+        setup_favorites_screen(&mut commands, &favorites, &streamer);
+        return;
+    }
+
+    *game_mode = GameMode::Classic;
+    game_rng.stop_sharing();
+    pending_favorite.0 = Some(favorite);
+    next_state.set(AppState::Playing);
+}
@@ -0,0 +1,376 @@
+//! Fruit: spawning, fleeing fruit, collecting it and finishing a level, plus
+//! the power-ups lying around Classic levels.
+//!
+//! Collecting runs as a chain of event handlers: `check_fruit_collection` sends
+//! `FruitCollectedEvent`, `score_fruit` scores it, `finish_level` counts it
+//! toward the level's `FruitGoal` and sends `LevelCompletedEvent` once the goal
+//! is reached, and `build_next_level` clears the level away and starts
+//! generating the next one (see `transition`).
+//! Power-ups are simpler: `collect_power_ups` applies them on the spot.
+
+use bevy::prelude::*;
+use crate::prelude::*;
+use rustbevy_core::collectible::POWER_UP_SECONDS;
+use rustbevy_core::fruit::{self, FruitKind, CURSE_SECONDS};
+use rustbevy_core::config::FRUIT_SIZE;
+use rustbevy_core::generation;
+use rustbevy_core::helper::HELPER_PLATFORM_USES;
+use rustbevy_core::physics;
+
+use crate::campaign::CampaignLevels;
+use crate::character_select::PlayerProfile;
+use crate::helper::HelperUses;
+use crate::physics::{classic_jump_profile, player_body, player_tuning};
+use crate::pool::{EntityPool, PoolKind};
+use crate::spatial::SpatialIndex;
+use crate::transition::PendingLevel;
+use crate::ui::Toasts;
+
+pub struct FruitPlugin;
+
+const BONUS_FRUIT_SIZE: f32 = FRUIT_SIZE * 0.7;
+
+impl Plugin for FruitPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<FruitCollectedEvent>()
+            .add_event::<LevelCompletedEvent>()
+            .init_resource::<FruitGoal>()
+            .add_systems(Update, (
+                // After the next level is built, so a finished level's fruit
+                // isn't replaced on its way out
+                setup_fruits_when_ready.after(build_next_level).run_if(
+                    in_state(AppState::Playing)
+                        .and_then(not(resource_equals(GameMode::Tag)))
+                        .and_then(not(resource_equals(GameMode::Territory)))
+                        .and_then(not(resource_equals(GameMode::Tower))),
+                ),
+                (flee_from_player, check_fruit_collection, collect_power_ups, score_fruit, finish_level, build_next_level)
+                    .chain()
+                    .run_if(in_state(AppState::Playing).and_then(resource_equals(GameMode::Classic))),
+                celebrate_level_complete,
+            ));
+    }
+}
+
+/// A plain fruit on one of the level's fruit spots (never the starting platform)
+pub fn setup_fruits_with_seed(commands: &mut Commands, pool: &mut EntityPool, layout: &LevelLayout, seed: u64) {
+    if let Some(position) = generation::fruit_position(&layout.0.fruit_spots(), seed) {
+        spawn_fruit(commands, pool, position, FruitKind::Normal);
+    }
+}
+
+pub fn fruit_color(kind: FruitKind) -> Color {
+    match kind {
+        FruitKind::Normal => Color::srgb(1.0, 0.5, 0.0), // Orange color for fruit
+        FruitKind::Blessed => Color::srgb(1.0, 1.0, 0.7), // Pale gold
+        FruitKind::Cursed => Color::srgb(0.6, 0.1, 0.8), // Purple
+        FruitKind::Fleeing => Color::srgb(0.3, 1.0, 0.9), // Cyan
+    }
+}
+
+pub fn spawn_fruit(commands: &mut Commands, pool: &mut EntityPool, (x, y): (f32, f32), kind: FruitKind) -> Entity {
+    let mut fruit = pool.take(commands, PoolKind::Fruit);
+    fruit.insert((
+        SpriteBundle {
+            sprite: Sprite {
+                color: fruit_color(kind),
+                custom_size: Some(Vec2::new(FRUIT_SIZE, FRUIT_SIZE)),
+                ..default()
+            },
+            transform: Transform::from_translation(Vec3::new(x, y, 0.0)),
+            ..default()
+        },
+        Fruit,
+        Pickup(Collectible::Fruit),
+    ));
+    if kind != FruitKind::Normal {
+        fruit.insert(FruitVariant(kind));
+    }
+    fruit.id()
+}
+
+/// A bonus fruit: smaller than the level's fruit, and worth points without
+/// finishing the level
+pub fn spawn_bonus_fruit(commands: &mut Commands, pool: &mut EntityPool, (x, y): (f32, f32)) {
+    pool.take(commands, PoolKind::Fruit).insert((
+        SpriteBundle {
+            sprite: Sprite {
+                color: Color::srgb(1.0, 0.65, 0.2),
+                custom_size: Some(Vec2::splat(BONUS_FRUIT_SIZE)),
+                ..default()
+            },
+            transform: Transform::from_translation(Vec3::new(x, y, 0.0)),
+            ..default()
+        },
+        Pickup(Collectible::Fruit),
+    ));
+}
+
+/// A power-up, drawn as a diamond so it doesn't pass for the fruit
+pub fn spawn_power_up(commands: &mut Commands, (x, y): (f32, f32), power_up: Collectible) {
+    let color = match power_up {
+        Collectible::Fruit => Color::srgb(1.0, 0.5, 0.0),
+        Collectible::SpeedBoost => Color::srgb(1.0, 0.95, 0.2), // Yellow
+        Collectible::ExtraLife => Color::srgb(1.0, 0.35, 0.5), // Pink
+        Collectible::DoubleJumpToken => Color::srgb(0.4, 0.7, 1.0), // Sky blue
+        Collectible::Shield => Color::srgb(0.85, 0.85, 0.95), // Silver
+        Collectible::HelperPlatform => Color::srgb(0.55, 0.85, 0.6), // Mint
+    };
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color,
+                custom_size: Some(Vec2::splat(FRUIT_SIZE * 0.75)),
+                ..default()
+            },
+            transform: Transform::from_translation(Vec3::new(x, y, 0.0)).with_rotation(Quat::from_rotation_z(std::f32::consts::FRAC_PI_4)),
+            ..default()
+        },
+        Pickup(power_up),
+    ));
+}
+
+/// Lets a fleeing fruit run from the player (the nearest one, in co-op); the
+/// hops happen in game time
+pub fn flee_from_player(
+    time: Res<Time>,
+    player_query: Query<&Transform, With<Player>>,
+    mut fruit_query: Query<(&mut Transform, &mut Fleeing), Without<Player>>,
+    mut sound_events: EventWriter<PlaySoundEvent>,
+) {
+    for (mut transform, mut fleeing) in fruit_query.iter_mut() {
+        let fruit = transform.translation;
+        let Some(player_transform) = player_query.iter().min_by(|a, b| a.translation.distance_squared(fruit).total_cmp(&b.translation.distance_squared(fruit))) else {
+            return;
+        };
+        let player = (player_transform.translation.x, player_transform.translation.y);
+        if fleeing.0.update(player, time.delta_seconds()) {
+            sound_events.send(PlaySoundEvent { sound_type: SoundType::Jump, position: None });
+        }
+        let (x, y) = fleeing.0.position();
+        transform.translation.x = x;
+        transform.translation.y = y;
+    }
+}
+
+/// Only spots the touch; scoring and the next level follow from the event. In
+/// co-op either player's touch counts.
+pub fn check_fruit_collection(
+    mut commands: Commands,
+    index: Res<SpatialIndex>,
+    player_query: Query<(&Transform, &Velocity, &Grounded, &Collider), With<Player>>,
+    fruit_query: Query<(Entity, &Transform, Option<&FruitVariant>, Option<&Fleeing>), (With<Fruit>, Without<Player>)>,
+    run_modifiers: Res<RunModifiers>,
+    assist_mode: Res<AssistMode>,
+    player_profile: Res<PlayerProfile>,
+    mut fruit_events: EventWriter<FruitCollectedEvent>,
+    mut particle_events: EventWriter<SpawnParticles>,
+) {
+    let tuning = player_tuning(GameMode::Classic, &run_modifiers, &assist_mode, &player_profile);
+    for (player_transform, velocity, grounded, collider) in player_query.iter() {
+        let body = player_body(player_transform, velocity, grounded, collider);
+        for candidate in index.touchables_near(player_transform.translation.truncate(), physics::pickup_reach(&body, &tuning)) {
+            let Ok((fruit_entity, fruit_transform, variant, fleeing)) = fruit_query.get(candidate) else {
+                continue;
+            };
+            let fruit_position = (fruit_transform.translation.x, fruit_transform.translation.y);
+            if fleeing.is_some_and(|fleeing| !fleeing.0.catchable()) {
+                continue;
+            }
+
+            // Check if player is close enough to collect the fruit (collision detection)
+            if physics::touches_fruit(&body, fruit_position, &tuning) {
+                commands.entity(fruit_entity).despawn();
+                fruit_events.send(FruitCollectedEvent {
+                    kind: variant.map_or(FruitKind::Normal, |variant| variant.0),
+                    position: fruit_transform.translation.truncate(),
+                });
+                particle_events.send(SpawnParticles { effect: ParticleEffect::FruitBurst, position: fruit_transform.translation.truncate() });
+                return; // Only collect one fruit per frame
+            }
+        }
+    }
+}
+
+// Puts a touched power-up's effect on the player who touched it, adds extra
+// lives and scores bonus fruit
+fn collect_power_ups(
+    mut commands: Commands,
+    index: Res<SpatialIndex>,
+    mut player_query: Query<(&Transform, &Velocity, &Grounded, &Collider, &mut PlayerStatus), With<Player>>,
+    pickup_query: Query<(Entity, &Transform, &Pickup), (Without<Fruit>, Without<Player>)>,
+    run_modifiers: Res<RunModifiers>,
+    assist_mode: Res<AssistMode>,
+    player_profile: Res<PlayerProfile>,
+    mut game_state: ResMut<GameState>,
+    mut helper_uses: ResMut<HelperUses>,
+    mut toasts: ResMut<Toasts>,
+    mut sound_events: EventWriter<PlaySoundEvent>,
+    mut particle_events: EventWriter<SpawnParticles>,
+) {
+    let tuning = player_tuning(GameMode::Classic, &run_modifiers, &assist_mode, &player_profile);
+    // Both co-op players can reach a power-up in the same frame; the first takes it
+    let mut collected = Vec::new();
+    for (player_transform, velocity, grounded, collider, mut player_status) in player_query.iter_mut() {
+        let body = player_body(player_transform, velocity, grounded, collider);
+        for candidate in index.touchables_near(player_transform.translation.truncate(), physics::pickup_reach(&body, &tuning)) {
+            let Ok((entity, transform, pickup)) = pickup_query.get(candidate) else {
+                continue;
+            };
+            if collected.contains(&entity) || !physics::touches_fruit(&body, (transform.translation.x, transform.translation.y), &tuning) {
+                continue;
+            }
+            collected.push(entity);
+            commands.entity(entity).despawn();
+            sound_events.send(PlaySoundEvent { sound_type: SoundType::Collect, position: None });
+            let power_up = pickup.0;
+            // Bonus fruit scores plainly, leaving the combo alone
+            if power_up == Collectible::Fruit {
+                game_state.score += fruit::FRUIT_SCORE;
+                toasts.push(format!("Bonus fruit! +{}", fruit::FRUIT_SCORE));
+                particle_events.send(SpawnParticles { effect: ParticleEffect::FruitBurst, position: transform.translation.truncate() });
+                continue;
+            }
+            if power_up == Collectible::HelperPlatform {
+                helper_uses.0 += HELPER_PLATFORM_USES;
+                toasts.push(format!("Helper platforms! Down + jump in mid-air ({} left)", helper_uses.0));
+                continue;
+            }
+            if power_up.extra_lives() > 0 {
+                game_state.lives += power_up.extra_lives();
+                sound_events.send(PlaySoundEvent { sound_type: SoundType::ExtraLife, position: None });
+            }
+            if let Some(effect) = power_up.effect() {
+                player_status.0.apply(effect, POWER_UP_SECONDS);
+            }
+            toasts.push(format!("{}!", power_up.name()));
+        }
+    }
+}
+
+/// Scores the fruit and applies its blessing or curse
+pub fn score_fruit(
+    mut fruit_events: EventReader<FruitCollectedEvent>,
+    mut game_state: ResMut<GameState>,
+    game_rng: Res<GameRng>,
+    difficulty: Res<Difficulty>,
+    mut player_query: Query<&mut PlayerStatus, With<Player>>,
+    mut toasts: ResMut<Toasts>,
+    mut sound_events: EventWriter<PlaySoundEvent>,
+) {
+    for event in fruit_events.read() {
+        // Scored before record_clear resets the level clock
+        let points = game_state.combo.collect(event.kind.score(), difficulty.0.level_seconds);
+        game_state.score += points;
+        if game_state.combo.multiplier() > 1 {
+            toasts.push(format!("Combo x{}! +{}", game_state.combo.multiplier(), points));
+        }
+        if event.kind.extra_lives() > 0 {
+            game_state.lives += event.kind.extra_lives();
+            sound_events.send(PlaySoundEvent { sound_type: SoundType::ExtraLife, position: None });
+        }
+        match event.kind {
+            FruitKind::Normal | FruitKind::Fleeing => {}
+            FruitKind::Blessed => toasts.push("Blessed fruit! +1 life".to_string()),
+            FruitKind::Cursed => {
+                let effect = fruit::curse_effect(generation::fruit_seed(game_rng.run_seed(), game_state.level));
+                // Co-op players share the curse as they share the lives
+                for mut player_status in player_query.iter_mut() {
+                    player_status.0.apply(effect, CURSE_SECONDS);
+                }
+                toasts.push(format!("Cursed fruit! Double score, but: {}", effect.name()));
+            }
+        }
+    }
+}
+
+/// Counts collected fruit toward the level's goal and finishes the level once
+/// it's reached
+pub fn finish_level(
+    mut fruit_events: EventReader<FruitCollectedEvent>,
+    mut fruit_goal: ResMut<FruitGoal>,
+    mut game_state: ResMut<GameState>,
+    mut difficulty: ResMut<Difficulty>,
+    mut level_events: EventWriter<LevelCompletedEvent>,
+) {
+    let collected = fruit_events.read().count() as u32;
+    if collected == 0 {
+        return;
+    }
+    fruit_goal.collected += collected;
+    // A door level's fruit is all bonus; the door finishes it (see `exit`)
+    if fruit_goal.target > 0 && fruit_goal.collected >= fruit_goal.target {
+        game_state.level += 1;
+        level_events.send(LevelCompletedEvent { level: game_state.level, skipped: false });
+        difficulty.0.record_clear();
+    }
+}
+
+/// Starts working out the next level and hands over to the transition, which
+/// clears the finished level away and spawns the next one (see `transition`)
+pub fn build_next_level(
+    mut level_events: EventReader<LevelCompletedEvent>,
+    player_query: Query<&Collider, With<Player>>,
+    game_rng: Res<GameRng>,
+    difficulty: Res<Difficulty>,
+    curve: Res<DifficultyCurve>,
+    run_modifiers: Res<RunModifiers>,
+    assist_mode: Res<AssistMode>,
+    player_profile: Res<PlayerProfile>,
+    campaign_levels: CampaignLevels,
+    mut pending_level: ResMut<PendingLevel>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    let Some(level) = level_events.read().last().map(|event| event.level) else {
+        return;
+    };
+    // Co-op players are the same size, so either one's jumps shape the level
+    let Some(collider) = player_query.iter().next() else {
+        return;
+    };
+
+    // Seeded runs ignore adaptive difficulty so every peer (and every retry) gets the same levels
+    let level_difficulty = if game_rng.fixed_levels() {
+        curve.0.base(level)
+    } else {
+        difficulty.0.difficulty(&curve.0, level)
+    };
+    let level_difficulty = (level_difficulty + run_modifiers.0.extra_difficulty()).min(1.0);
+
+    // The next level of the run, generated from the run seed
+    let profile = classic_jump_profile(&run_modifiers, &assist_mode, &player_profile, collider.size);
+    pending_level.generate(
+        campaign_levels.provider().level(level),
+        game_rng.run_seed(),
+        level,
+        level_difficulty,
+        profile,
+        curve.0,
+    );
+    next_state.set(AppState::LevelTransition);
+}
+
+fn celebrate_level_complete(mut level_events: EventReader<LevelCompletedEvent>, mut particle_events: EventWriter<SpawnParticles>) {
+    for _ in level_events.read().filter(|event| !event.skipped) {
+        particle_events.send(SpawnParticles { effect: ParticleEffect::Confetti { fireworks: 2 }, position: Vec2::ZERO });
+    }
+}
+
+fn setup_fruits_when_ready(
+    mut commands: Commands,
+    game_rng: Res<GameRng>,
+    layout: Res<LevelLayout>,
+    mut fruit_goal: ResMut<FruitGoal>,
+    mut pool: ResMut<EntityPool>,
+    fruit_query: Query<Entity, With<Fruit>>,
+    door_query: Query<(), With<ExitDoor>>,
+) {
+    // Only setup fruits if we have platforms, but no fruits and no door to finish the level
+    if !layout.0.platforms.is_empty() && fruit_query.is_empty() && door_query.is_empty() {
+        setup_fruits_with_seed(&mut commands, &mut pool, &layout, game_rng.run_seed().wrapping_add(99));
+        // A level that came without fruit is finished by this one
+        if fruit_goal.target == 0 {
+            fruit_goal.target = 1;
+        }
+    }
+}
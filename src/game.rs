@@ -0,0 +1,240 @@
+//! App state, run-wide resources and setting up a run when play starts.
+//!
+//! Entering play with an empty world sends `GameResetEvent`; the run state is
+//! reset and the first level built in response.
+
+use std::path::PathBuf;
+
+use bevy::prelude::*;
+use crate::prelude::*;
+use rustbevy_core::difficulty::AdaptiveDifficulty;
+use rustbevy_core::generation;
+
+use crate::campaign::CampaignLevels;
+use crate::character_select::PlayerProfile;
+use crate::favorites::PendingFavorite;
+use crate::party::{setup_party_ui, spawn_party_player, tag_player, PartyRound};
+use crate::physics::classic_jump_profile;
+use crate::platform::{spawn_classic_level, spawn_platforms};
+use crate::player::{spawn_coop_partner, spawn_player};
+use crate::pool::EntityPool;
+use crate::score::HighScore;
+use crate::ui::{setup_game_ui, GameUI};
+
+pub struct GamePlugin;
+
+impl Plugin for GamePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<AppState>()
+            .init_resource::<GameState>()
+            .init_resource::<GameMode>()
+            .init_resource::<GameRng>()
+            .init_resource::<Difficulty>()
+            .init_resource::<DifficultyCurve>()
+            .init_resource::<LevelLayout>()
+            .init_resource::<AssistMode>()
+            .init_resource::<MutatorMode>()
+            .init_resource::<RulesMode>()
+            .init_resource::<CustomGameMode>()
+            .init_resource::<RunModifiers>()
+            .init_resource::<CoopMode>()
+            .add_event::<GameResetEvent>()
+            .add_systems(OnEnter(AppState::Playing), (start_new_run, reset_run, setup_game_entities).chain())
+            .add_systems(Update, tick_level_clock.run_if(in_state(AppState::Playing).and_then(resource_equals(GameMode::Classic))));
+    }
+}
+
+/// Where small save files (dismissed hints, ...) are kept
+pub fn save_path(file: &str) -> PathBuf {
+    let base = std::env::var_os("APPDATA")
+        .or_else(|| std::env::var_os("HOME"))
+        .map(PathBuf::from)
+        .unwrap_or_default();
+    base.join(".bevy_platformer").join(file)
+}
+
+/// The contents of a save file, if there is one. In the browser, where there's
+/// no file system, save files live in local storage under the same names.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn read_save_file(file: &str) -> Option<String> {
+    std::fs::read_to_string(save_path(file)).ok()
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn read_save_file(file: &str) -> Option<String> {
+    local_storage()?.get_item(&storage_key(file)).ok()?
+}
+
+/// Writes a save file, making the save directory first. A failure comes back
+/// ready to send; `what` names what was being saved, for the player.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn write_save_file(file: &str, contents: &str, what: &str) -> Result<(), RuntimeErrorEvent> {
+    let path = save_path(file);
+    path.parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .and_then(|_| std::fs::write(&path, contents))
+        .map_err(|error| RuntimeErrorEvent::new(format!("Could not save {}", what), format!("{}: {}", path.display(), error)))
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn write_save_file(file: &str, contents: &str, what: &str) -> Result<(), RuntimeErrorEvent> {
+    let storage = local_storage().ok_or_else(|| RuntimeErrorEvent::new(format!("Could not save {}", what), "local storage is unavailable"))?;
+    storage
+        .set_item(&storage_key(file), contents)
+        .map_err(|error| RuntimeErrorEvent::new(format!("Could not save {}", what), format!("{}: {:?}", file, error)))
+}
+
+/// Deletes a save file; one that was never written is already gone
+#[cfg(not(target_arch = "wasm32"))]
+pub fn remove_save_file(file: &str, what: &str) -> Result<(), RuntimeErrorEvent> {
+    let path = save_path(file);
+    match std::fs::remove_file(&path) {
+        Err(error) if error.kind() != std::io::ErrorKind::NotFound => {
+            Err(RuntimeErrorEvent::new(format!("Could not remove {}", what), format!("{}: {}", path.display(), error)))
+        }
+        _ => Ok(()),
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn remove_save_file(file: &str, what: &str) -> Result<(), RuntimeErrorEvent> {
+    let Some(storage) = local_storage() else {
+        return Ok(());
+    };
+    storage
+        .remove_item(&storage_key(file))
+        .map_err(|error| RuntimeErrorEvent::new(format!("Could not remove {}", what), format!("{}: {:?}", file, error)))
+}
+
+#[cfg(target_arch = "wasm32")]
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok()?
+}
+
+#[cfg(target_arch = "wasm32")]
+fn storage_key(file: &str) -> String {
+    format!("bevy_platformer/{}", file)
+}
+
+// `SystemTime` from Bevy rather than std: std's panics in the browser
+fn since_epoch() -> std::time::Duration {
+    bevy::utils::SystemTime::now()
+        .duration_since(bevy::utils::SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+}
+
+pub fn clock_seed() -> u64 {
+    since_epoch().as_nanos() as u64
+}
+
+/// Wall-clock seconds, for timestamps in file names and the daily challenge
+pub fn unix_seconds() -> u64 {
+    since_epoch().as_secs()
+}
+
+fn tick_level_clock(time: Res<Time>, mut difficulty: ResMut<Difficulty>) {
+    difficulty.0.tick(time.delta_seconds());
+}
+
+// Coming back from the pause menu or a draft finds everything still there;
+// an empty world means a new run
+fn start_new_run(
+    game_mode: Res<GameMode>,
+    player_query: Query<Entity, With<Player>>,
+    platform_query: Query<Entity, With<Platform>>,
+    ui_query: Query<Entity, With<GameUI>>,
+    mut reset_events: EventWriter<GameResetEvent>,
+) {
+    if player_query.is_empty() && platform_query.is_empty() && ui_query.is_empty() {
+        reset_events.send(GameResetEvent { mode: *game_mode });
+    }
+}
+
+/// Clears what the last run left behind
+pub fn reset_run(
+    mut reset_events: EventReader<GameResetEvent>,
+    mut game_state: ResMut<GameState>,
+    mut difficulty: ResMut<Difficulty>,
+    mut run_modifiers: ResMut<RunModifiers>,
+    rules_mode: Res<RulesMode>,
+) {
+    for event in reset_events.read() {
+        difficulty.0 = AdaptiveDifficulty::new(difficulty.0.enabled);
+        run_modifiers.0 = Default::default();
+        if event.mode == GameMode::Classic {
+            *game_state = GameState::new(rules_mode.0);
+        } else if event.mode == GameMode::Tower {
+            *game_state = GameState::default();
+        }
+    }
+}
+
+/// Game Setup System
+pub fn setup_game_entities(
+    mut commands: Commands,
+    mut reset_events: EventReader<GameResetEvent>,
+    party_round: Res<PartyRound>,
+    mut game_rng: ResMut<GameRng>,
+    difficulty: Res<Difficulty>,
+    curve: Res<DifficultyCurve>,
+    assist_mode: Res<AssistMode>,
+    player_profile: Res<PlayerProfile>,
+    run_modifiers: Res<RunModifiers>,
+    mutator_mode: Res<MutatorMode>,
+    mut game_state: ResMut<GameState>,
+    mut pending_favorite: ResMut<PendingFavorite>,
+    high_score: Res<HighScore>,
+    campaign_levels: CampaignLevels,
+    coop_mode: Res<CoopMode>,
+    mut pool: ResMut<EntityPool>,
+) {
+    let Some(event) = reset_events.read().last().copied() else {
+        return;
+    };
+    // The tower builds itself as it's climbed (see `tower`)
+    if event.mode == GameMode::Tower {
+        return;
+    }
+    // The Classic player spawns below, once its level says where
+    if event.mode != GameMode::Classic {
+        for slot in 0..party_round.player_count {
+            let player = spawn_party_player(&mut commands, slot, party_round.player_count);
+            if event.mode == GameMode::Tag && slot == party_round.starting_it {
+                tag_player(&mut commands, player);
+            }
+        }
+    }
+
+    // Generate initial platforms
+    let initial_seed = game_rng.start_run();
+    if event.mode == GameMode::Classic {
+        // A new run, or a favorite level picked on the Favorites screen
+        let player_size = mutator_mode.0.player_size();
+        let profile = classic_jump_profile(&run_modifiers, &assist_mode, &player_profile, player_size);
+        let player_spawn = match pending_favorite.0.take() {
+            Some(favorite) => {
+                game_state.level = favorite.level;
+                game_state.stats.started_mid_run = true;
+                game_rng.resume_run(favorite.run_seed);
+                spawn_classic_level(&mut commands, &mut pool, campaign_levels.provider(), favorite.run_seed, favorite.level, favorite.difficulty, profile, &curve.0)
+            }
+            None => spawn_classic_level(&mut commands, &mut pool, campaign_levels.provider(), initial_seed, 1, difficulty.0.difficulty(&curve.0, 1), profile, &curve.0),
+        };
+        spawn_player(&mut commands, player_size, player_spawn);
+        if coop_mode.0 {
+            spawn_coop_partner(&mut commands, player_size, player_spawn);
+        }
+    } else {
+        let layout = curve.0.layout(difficulty.0.difficulty(&curve.0, 1));
+        let platforms = generation::generate_platform_layout(initial_seed, &layout, event.mode.world_bounds());
+        spawn_platforms(&mut commands, &mut pool, &platforms);
+        commands.insert_resource(LevelLayout(generation::LevelLayout::generated(platforms)));
+    }
+
+    // Setup UI
+    match event.mode {
+        GameMode::Classic => setup_game_ui(commands, assist_mode.0, mutator_mode.0, high_score.best, game_state.continues),
+        GameMode::FruitGrab | GameMode::Tag | GameMode::Territory => setup_party_ui(commands, party_round.player_count),
+        GameMode::Tower => {}
+    }
+}
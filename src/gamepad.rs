@@ -0,0 +1,134 @@
+// Controller support for the Classic player.
+//
+// The first controller plugged in drives the player alongside the keyboard:
+// the left stick or d-pad moves (a half-tilted stick walks at half speed) and
+// climbs, the south face button (A / Cross) jumps, the west one (X /
+// Square) throws a seed and the left bumper rewinds. Controllers can come
+// and go while the game runs; when the active one is unplugged the next
+// connected one, if any, takes over.
+
+use bevy::input::gamepad::{GamepadConnection, GamepadConnectionEvent};
+use bevy::input::InputSystem;
+use bevy::prelude::*;
+
+use crate::ui::Toasts;
+
+pub struct GamepadPlugin;
+
+impl Plugin for GamepadPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ActiveGamepad>()
+            .init_resource::<GamepadInput>()
+            .add_systems(PreUpdate, (track_gamepads, read_gamepad).chain().after(InputSystem));
+    }
+}
+
+// Stick tilt below this counts as centred; above it the tilt is rescaled so
+// movement still starts from zero
+const STICK_DEAD_ZONE: f32 = 0.2;
+// The stick tilted further than this up or down holds up or down, for
+// climbing and dropping through platforms
+const STICK_HOLD: f32 = 0.5;
+
+// The controller that drives the player
+#[derive(Resource, Default)]
+pub struct ActiveGamepad(pub Option<Gamepad>);
+
+// This frame's controller input, read by the player's physics
+#[derive(Resource, Default)]
+pub struct GamepadInput {
+    // -1 (full left) to 1 (full right)
+    pub horizontal: f32,
+    // -1 (full down) to 1 (full up)
+    pub vertical: f32,
+    pub jump_just_pressed: bool,
+    pub jump_held: bool,
+    pub throw_just_pressed: bool,
+    pub rewind_held: bool,
+}
+
+impl GamepadInput {
+    pub fn up_held(&self) -> bool {
+        self.vertical > STICK_HOLD
+    }
+
+    pub fn down_held(&self) -> bool {
+        self.vertical < -STICK_HOLD
+    }
+}
+
+fn track_gamepads(
+    mut connection_events: EventReader<GamepadConnectionEvent>,
+    gamepads: Res<Gamepads>,
+    mut active: ResMut<ActiveGamepad>,
+    mut toasts: ResMut<Toasts>,
+) {
+    for event in connection_events.read() {
+        match &event.connection {
+            GamepadConnection::Connected(info) => {
+                if active.0.is_none() {
+                    active.0 = Some(event.gamepad);
+                    toasts.push(format!("Controller connected: {}", info.name));
+                }
+            }
+            GamepadConnection::Disconnected => {
+                if active.0 == Some(event.gamepad) {
+                    active.0 = gamepads.iter().find(|gamepad| *gamepad != event.gamepad);
+                    toasts.push(match active.0 {
+                        Some(_) => "Controller disconnected - switched to the next one".to_string(),
+                        None => "Controller disconnected".to_string(),
+                    });
+                }
+            }
+        }
+    }
+}
+
+fn stick_input(value: f32) -> f32 {
+    if value.abs() < STICK_DEAD_ZONE {
+        return 0.0;
+    }
+    value.signum() * ((value.abs() - STICK_DEAD_ZONE) / (1.0 - STICK_DEAD_ZONE)).min(1.0)
+}
+
+pub fn read_gamepad(
+    active: Res<ActiveGamepad>,
+    axes: Res<Axis<GamepadAxis>>,
+    buttons: Res<ButtonInput<GamepadButton>>,
+    mut input: ResMut<GamepadInput>,
+) {
+    let Some(gamepad) = active.0 else {
+        *input = GamepadInput::default();
+        return;
+    };
+    let button = |button_type| GamepadButton::new(gamepad, button_type);
+    // The d-pad is all or nothing and wins over the stick
+    let mut horizontal = 0.0;
+    if buttons.pressed(button(GamepadButtonType::DPadLeft)) {
+        horizontal -= 1.0;
+    }
+    if buttons.pressed(button(GamepadButtonType::DPadRight)) {
+        horizontal += 1.0;
+    }
+    if horizontal == 0.0 {
+        horizontal = stick_input(axes.get(GamepadAxis::new(gamepad, GamepadAxisType::LeftStickX)).unwrap_or_default());
+    }
+    let mut vertical = 0.0;
+    if buttons.pressed(button(GamepadButtonType::DPadDown)) {
+        vertical -= 1.0;
+    }
+    if buttons.pressed(button(GamepadButtonType::DPadUp)) {
+        vertical += 1.0;
+    }
+    if vertical == 0.0 {
+        vertical = stick_input(axes.get(GamepadAxis::new(gamepad, GamepadAxisType::LeftStickY)).unwrap_or_default());
+    }
+    *input = GamepadInput {
+        horizontal,
+        vertical,
+        jump_just_pressed: buttons.just_pressed(button(GamepadButtonType::South)),
+        jump_held: buttons.pressed(button(GamepadButtonType::South)),
+        throw_just_pressed: buttons.just_pressed(button(GamepadButtonType::West)),
+        rewind_held: buttons.pressed(button(GamepadButtonType::LeftTrigger)),
+    };
+}
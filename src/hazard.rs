@@ -0,0 +1,84 @@
+//! Spikes and lava pools in Classic levels (see `rustbevy_core::hazard` for
+//! where generated levels put them). Touching one is a death like running into
+//! an enemy, spotted by `check_player_death`; the companion squeaks near them
+//! too, as they're spawned with `Hazard`.
+//!
+//! Spikes are a pale steel strip with a row of teeth drawn along its top; lava
+//! glows, brightening and dimming unless reduced motion is on.
+
+use bevy::color::Mix;
+use bevy::prelude::*;
+use bevy::sprite::Anchor;
+use crate::prelude::*;
+use rustbevy_core::hazard::{HazardKind, HazardTile};
+
+use crate::settings::Settings;
+
+pub struct HazardPlugin;
+
+impl Plugin for HazardPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, (draw_spike_teeth, glow_lava)
+            .run_if(in_state(AppState::Playing).and_then(resource_equals(GameMode::Classic))));
+    }
+}
+
+const SPIKE_COLOR: Color = Color::srgb(0.7, 0.72, 0.78); // Steel
+const SPIKE_TOOTH_WIDTH: f32 = 10.0;
+const LAVA_COLOR: Color = Color::srgb(1.0, 0.35, 0.05); // Orange-red
+const LAVA_GLOW_COLOR: Color = Color::srgb(1.0, 0.7, 0.1); // Yellow-orange
+// Full glow cycles a second
+const LAVA_GLOW_RATE: f32 = 0.6;
+
+pub fn hazard_color(kind: HazardKind) -> Color {
+    match kind {
+        HazardKind::Spikes => SPIKE_COLOR,
+        HazardKind::Lava => LAVA_COLOR,
+    }
+}
+
+pub fn spawn_hazard(commands: &mut Commands, tile: HazardTile) {
+    // A spike strip's sprite is its bottom half; the teeth are drawn above it
+    let (height, anchor) = match tile.kind {
+        HazardKind::Spikes => (tile.height / 2.0, Anchor::TopCenter),
+        HazardKind::Lava => (tile.height, Anchor::Center),
+    };
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: hazard_color(tile.kind),
+                custom_size: Some(Vec2::new(tile.width, height)),
+                anchor,
+                ..default()
+            },
+            transform: Transform::from_translation(Vec3::new(tile.x, tile.y, 0.2)),
+            ..default()
+        },
+        HazardArea(tile),
+        Hazard,
+    ));
+}
+
+fn draw_spike_teeth(mut gizmos: Gizmos, hazard_query: Query<&HazardArea>) {
+    for hazard in hazard_query.iter().filter(|hazard| hazard.0.kind == HazardKind::Spikes) {
+        let tile = hazard.0;
+        let teeth = (tile.width / SPIKE_TOOTH_WIDTH).round().max(1.0) as usize;
+        let tooth_width = tile.width / teeth as f32;
+        let (left, base, tip) = (tile.x - tile.width / 2.0, tile.y, tile.y + tile.height / 2.0);
+        let points = (0..=teeth * 2).map(|step| {
+            let x = left + step as f32 * tooth_width / 2.0;
+            Vec2::new(x, if step % 2 == 1 { tip } else { base })
+        });
+        gizmos.linestrip_2d(points, SPIKE_COLOR);
+    }
+}
+
+fn glow_lava(time: Res<Time>, settings: Res<Settings>, mut hazard_query: Query<(&HazardArea, &mut Sprite)>) {
+    let glow = if settings.reduced_motion { 0.0 } else { (time.elapsed_seconds() * LAVA_GLOW_RATE * std::f32::consts::TAU).sin() * 0.5 + 0.5 };
+    let color = LAVA_COLOR.mix(&LAVA_GLOW_COLOR, glow * 0.5);
+    for (hazard, mut sprite) in hazard_query.iter_mut() {
+        if hazard.0.kind == HazardKind::Lava && sprite.color != color {
+            sprite.color = color;
+        }
+    }
+}
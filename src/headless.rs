@@ -0,0 +1,143 @@
+// Headless simulation: the game's rules with no window, GPU or audio device,
+// for automated gameplay tests (`cargo test --features headless`).
+//
+// `headless_app()` builds an App from Bevy's `MinimalPlugins` and the plugins
+// the simulation is made of (the run, physics and its broad phase, the
+// player, platforms (and the pool they're recycled through), their ladders
+// and traps, wind and conveyors, portals, helper platforms, checkpoints,
+// fruit and its rescue, exit doors, enemies, and the profiler markers they
+// record), with the resources and events the rest of the game would otherwise
+// provide.
+// Every `update()` is exactly one physics step of game time, so a test
+// decides how far the game gets; input goes in as keyboard events, the way
+// the window would deliver it.
+
+use std::time::Duration;
+
+use bevy::input::keyboard::{Key, KeyboardInput, NativeKey};
+use bevy::input::{ButtonState, InputPlugin};
+use bevy::state::app::StatesPlugin;
+use bevy::time::TimeUpdateStrategy;
+use bevy::prelude::*;
+use rustbevy_core::config::PHYSICS_HZ;
+
+use crate::camera::Spectator;
+use crate::campaign::SelectedCampaign;
+use crate::character_select::PlayerProfile;
+use crate::chat::Chat;
+use crate::favorites::PendingFavorite;
+use crate::gamepad::GamepadInput;
+use crate::level_loader::{CommunityLevels, HandmadeLevels};
+use crate::menu::ModifierDraft;
+use crate::overview::Overview;
+use crate::party::PartyRound;
+use crate::prelude::*;
+use crate::score::HighScore;
+use crate::settings::Settings;
+use crate::tutorial::Tutorial;
+use crate::ui::Toasts;
+use crate::{checkpoint, dressing, enemy, exit, fruit, game, helper, ladder, physics, platform, player, portal, pool, profiling, projectile, replay, rescue, spatial, transition, trap, wind};
+
+// Saves go here rather than to the player's own save directory
+const SAVE_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/target/headless/save");
+
+pub fn headless_app() -> App {
+    std::env::set_var("APPDATA", SAVE_DIR);
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, StatesPlugin, InputPlugin, TransformPlugin, HierarchyPlugin))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(1.0 / PHYSICS_HZ as f64)))
+        // What the menus, camera, HUD, sound and the rest of the game provide
+        .init_resource::<PartyRound>()
+        .init_resource::<PendingFavorite>()
+        .init_resource::<HighScore>()
+        .init_resource::<HandmadeLevels>()
+        .init_resource::<CommunityLevels>()
+        .init_resource::<SelectedCampaign>()
+        .init_resource::<Tutorial>()
+        .init_resource::<Toasts>()
+        .init_resource::<ModifierDraft>()
+        .init_resource::<GamepadInput>()
+        .init_resource::<Spectator>()
+        .init_resource::<Chat>()
+        .init_resource::<Overview>()
+        .init_resource::<Settings>()
+        .init_resource::<PlayerProfile>()
+        .add_event::<PlaySoundEvent>()
+        .add_event::<SpawnParticles>()
+        .add_event::<RuntimeErrorEvent>()
+        .add_plugins((
+            game::GamePlugin,
+            physics::PhysicsPlugin,
+            spatial::SpatialPlugin,
+            player::PlayerPlugin,
+            platform::PlatformPlugin,
+            pool::PoolPlugin,
+            dressing::DressingPlugin,
+            ladder::LadderPlugin,
+            fruit::FruitPlugin,
+            rescue::FruitRescuePlugin,
+            enemy::EnemyPlugin,
+            projectile::ProjectilePlugin,
+            transition::TransitionPlugin,
+            replay::ReplayPlugin,
+        ))
+        .add_plugins((
+            trap::TrapPlugin,
+            wind::WindPlugin,
+            portal::PortalPlugin,
+            helper::HelperPlugin,
+            checkpoint::CheckpointPlugin,
+            exit::ExitPlugin,
+            profiling::ProfilingPlugin,
+        ));
+    app.finish();
+    app.cleanup();
+    app
+}
+
+// Starts a Classic run from the menu and builds its first level
+pub fn start_classic_run(app: &mut App) {
+    *app.world_mut().resource_mut::<GameMode>() = GameMode::Classic;
+    app.world_mut().resource_mut::<NextState<AppState>>().set(AppState::Playing);
+    app.update();
+    app.update();
+}
+
+pub fn step(app: &mut App, steps: u32) {
+    for _ in 0..steps {
+        app.update();
+    }
+}
+
+fn send_key(app: &mut App, key_code: KeyCode, state: ButtonState) {
+    app.world_mut().send_event(KeyboardInput {
+        key_code,
+        logical_key: Key::Unidentified(NativeKey::Unidentified),
+        state,
+        window: Entity::PLACEHOLDER,
+    });
+}
+
+// Held from the next update until released
+pub fn press(app: &mut App, key_code: KeyCode) {
+    send_key(app, key_code, ButtonState::Pressed);
+}
+
+pub fn release(app: &mut App, key_code: KeyCode) {
+    send_key(app, key_code, ButtonState::Released);
+}
+
+// Moves something the simulation steps, as a respawn would; it's simulated
+// from there on the next step
+pub fn teleport(app: &mut App, entity: Entity, position: Vec2) {
+    let mut entity = app.world_mut().entity_mut(entity);
+    entity.remove::<physics::Interpolated>();
+    if let Some(mut transform) = entity.get_mut::<Transform>() {
+        transform.translation.x = position.x;
+        transform.translation.y = position.y;
+    }
+}
+
+pub fn find_player(app: &mut App) -> Option<Entity> {
+    app.world_mut().query_filtered::<Entity, With<Player>>().iter(app.world()).next()
+}
@@ -0,0 +1,100 @@
+// Helper platforms in Classic runs: down + jump in mid-air puts a platform
+// under the player while they have uses left from the power-up (see
+// `rustbevy_core::helper`). The platform is an ordinary `Platform` entity, so
+// collisions and the platform hints pick it up like the level's own; it
+// blinks for its last second and then goes away.
+
+use bevy::prelude::*;
+use crate::prelude::*;
+use rustbevy_core::helper::{summons_helper, HelperPlatform};
+
+use crate::chat::chat_closed;
+use crate::physics::{check_collisions, player_body, player_movement};
+use crate::platform::spawn_platforms;
+use crate::pool::EntityPool;
+use crate::replay::ReplayPlayback;
+
+pub struct HelperPlugin;
+
+impl Plugin for HelperPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<HelperUses>()
+            .add_systems(Update, reset_helper_uses)
+            .add_systems(FixedUpdate, (
+                summon_helper_platform.run_if(chat_closed).before(player_movement),
+                expire_helper_platforms.before(check_collisions),
+            ).run_if(in_state(AppState::Playing).and_then(resource_equals(GameMode::Classic))));
+    }
+}
+
+// How long before it goes that a helper platform starts blinking
+const BLINK_SECONDS: f32 = 1.0;
+const BLINK_RATE: f32 = 8.0;
+
+// Helper platforms the Classic player can still put down on this level
+#[derive(Resource, Default)]
+pub struct HelperUses(pub u32);
+
+#[derive(Component)]
+struct Helper(HelperPlatform);
+
+// Uses don't carry over to the next level or run
+fn reset_helper_uses(
+    mut level_events: EventReader<LevelCompletedEvent>,
+    mut reset_events: EventReader<GameResetEvent>,
+    mut uses: ResMut<HelperUses>,
+) {
+    if level_events.read().count() + reset_events.read().count() > 0 {
+        uses.0 = 0;
+    }
+}
+
+// Takes the jump press for a helper platform instead, so `player_movement`
+// doesn't jump with it
+pub(crate) fn summon_helper_platform(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    assist_mode: Res<AssistMode>,
+    mut uses: ResMut<HelperUses>,
+    mut pool: ResMut<EntityPool>,
+    playback: Option<Res<ReplayPlayback>>,
+    mut player_query: Query<(&Transform, &Velocity, &Grounded, &Collider, &PlayerControls, &mut JumpPress, Has<LeadPlayer>), With<Player>>,
+) {
+    // Co-op players summon from the same stock of uses
+    for (transform, velocity, grounded, collider, controls, mut jump_press, lead) in player_query.iter_mut() {
+        // Down turns the auto-running player around instead (auto-run is the
+        // lead player's)
+        let down_held = (!lead || !assist_mode.0.controls.auto_run())
+            && playback
+                .as_ref()
+                .and_then(|playback| playback.input())
+                .map_or(PlayerControls::any_pressed(&keyboard_input, &controls.down), |input| input.down);
+        let body = player_body(transform, velocity, grounded, collider);
+        if !summons_helper(&body, jump_press.0, down_held, uses.0) {
+            continue;
+        }
+        jump_press.0 = false;
+        uses.0 -= 1;
+        let helper = HelperPlatform::beneath(&body);
+        if let Some(&platform) = spawn_platforms(&mut commands, &mut pool, &[helper.spec]).first() {
+            commands.entity(platform).insert(Helper(helper));
+        }
+    }
+}
+
+fn expire_helper_platforms(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut helper_query: Query<(Entity, &mut Helper, &mut Visibility)>,
+) {
+    for (entity, mut helper, mut visibility) in helper_query.iter_mut() {
+        helper.0.update(time.delta_seconds());
+        if helper.0.expired() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        let left = helper.0.seconds_left;
+        let shown = left > BLINK_SECONDS || ((left * BLINK_RATE) as u32).is_multiple_of(2);
+        visibility.set_if_neq(if shown { Visibility::Inherited } else { Visibility::Hidden });
+    }
+}
@@ -0,0 +1,216 @@
+// Contextual tutorial hints for Classic mode.
+//
+// A few small rules watch what the player does (pushing into a wall, falling off
+// again and again, wandering around without grabbing the fruit). When one fires
+// its hint is shown on the toast banner. Pressing X while a hint is up hides that
+// hint for good; dismissed hints are remembered in the save directory.
+
+use std::collections::{HashMap, HashSet};
+
+use bevy::prelude::*;
+use crate::prelude::*;
+
+use crate::camera::spectating;
+use crate::chat::chat_closed;
+use crate::fruit::score_fruit;
+use crate::player::handle_player_death;
+use crate::game::{read_save_file, write_save_file};
+use crate::ui::{update_toasts, Toasts};
+
+pub struct HintsPlugin;
+
+impl Plugin for HintsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Hints>()
+            .add_systems(Update, (
+                (
+                    watch_wall_pushing.run_if(not(spectating)),
+                    count_falls,
+                    watch_fruit_hunt,
+                ).after(score_fruit).after(handle_player_death)
+                    .run_if(in_state(AppState::Playing).and_then(resource_equals(GameMode::Classic))),
+                dismiss_hint.run_if(chat_closed).before(update_toasts),
+            ));
+    }
+}
+
+const WALL_PUSH_SECONDS: f32 = 0.3; // Pushing against something this long counts as a bump
+const WALL_BUMPS_FOR_HINT: u32 = 3;
+const FALLS_FOR_HINT: u32 = 3;
+const FRUIT_HUNT_SECONDS: f32 = 20.0;
+const HINTS_FILE: &str = "dismissed_hints.txt";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Hint {
+    WallBlocked,
+    Falling,
+    FindFruit,
+}
+
+impl Hint {
+    const ALL: [Hint; 3] = [Hint::WallBlocked, Hint::Falling, Hint::FindFruit];
+
+    // Name written to the save file
+    fn key(self) -> &'static str {
+        match self {
+            Self::WallBlocked => "wall_blocked",
+            Self::Falling => "falling",
+            Self::FindFruit => "find_fruit",
+        }
+    }
+
+    fn message(self) -> &'static str {
+        match self {
+            Self::WallBlocked => "Hint: Stuck against a platform? Jump (SPACE) and steer over it in the air",
+            Self::Falling => "Hint: Falling off costs a life. Land in the middle of platforms and tap jump early",
+            Self::FindFruit => "Hint: Reach the orange fruit to move on to the next level",
+        }
+    }
+
+    fn banner_text(self) -> String {
+        format!("{}   (X: don't show again)", self.message())
+    }
+}
+
+#[derive(Resource)]
+pub struct Hints {
+    dismissed: HashSet<Hint>,
+    // Each hint is shown at most once per session
+    shown: HashSet<Hint>,
+    showing: Option<Hint>,
+    push_seconds: f32,
+    wall_bumps: u32,
+    falls: u32,
+    hunt_seconds: f32,
+}
+
+impl Default for Hints {
+    fn default() -> Self {
+        let saved = read_save_file(HINTS_FILE).unwrap_or_default();
+        let dismissed = Hint::ALL
+            .into_iter()
+            .filter(|hint| saved.lines().any(|line| line.trim() == hint.key()))
+            .collect();
+        Self {
+            dismissed,
+            shown: HashSet::new(),
+            showing: None,
+            push_seconds: 0.0,
+            wall_bumps: 0,
+            falls: 0,
+            hunt_seconds: 0.0,
+        }
+    }
+}
+
+impl Hints {
+    fn trigger(&mut self, hint: Hint, toasts: &mut Toasts) {
+        if self.dismissed.contains(&hint) || !self.shown.insert(hint) {
+            return;
+        }
+        toasts.push(hint.banner_text());
+        self.showing = Some(hint);
+    }
+
+    fn save(&self) -> Result<(), RuntimeErrorEvent> {
+        let keys: Vec<&str> = Hint::ALL
+            .into_iter()
+            .filter(|hint| self.dismissed.contains(hint))
+            .map(Hint::key)
+            .collect();
+        write_save_file(HINTS_FILE, &keys.join("\n"), "dismissed hints")
+    }
+}
+
+// Counts the times a player keeps walking into the side of something
+pub fn watch_wall_pushing(
+    time: Res<Time>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    player_query: Query<(Entity, &Transform, &Grounded, &PlayerControls, &Collider), With<Player>>,
+    // Where each player was last frame
+    mut last_x: Local<HashMap<Entity, f32>>,
+    mut hints: ResMut<Hints>,
+    mut toasts: ResMut<Toasts>,
+) {
+    let mut pushing = false;
+    let mut seen = HashMap::new();
+    for (player, transform, grounded, controls, collider) in player_query.iter() {
+        let x = transform.translation.x;
+        let previous_x = last_x.get(&player).copied();
+        seen.insert(player, x);
+
+        let holding_direction = PlayerControls::any_pressed(&keyboard_input, &controls.left)
+            != PlayerControls::any_pressed(&keyboard_input, &controls.right);
+        // The level's edges stop the player too, but jumping doesn't help there
+        let at_level_edge = x.abs() >= WorldBounds::CLASSIC.half_width() - collider.size / 2.0 - 1.0;
+        let blocked = previous_x.is_some_and(|previous_x| (x - previous_x).abs() < 0.5);
+        pushing |= holding_direction && grounded.0 && blocked && !at_level_edge;
+    }
+    // Players gone since last frame are forgotten
+    *last_x = seen;
+
+    if pushing {
+        let was_bump = hints.push_seconds >= WALL_PUSH_SECONDS;
+        hints.push_seconds += time.delta_seconds();
+        if !was_bump && hints.push_seconds >= WALL_PUSH_SECONDS {
+            hints.wall_bumps += 1;
+            if hints.wall_bumps >= WALL_BUMPS_FOR_HINT {
+                hints.trigger(Hint::WallBlocked, &mut toasts);
+            }
+        }
+    } else {
+        hints.push_seconds = 0.0;
+    }
+}
+
+pub fn count_falls(mut death_events: EventReader<PlayerDiedEvent>, mut hints: ResMut<Hints>, mut toasts: ResMut<Toasts>) {
+    for _ in death_events.read() {
+        hints.falls += 1;
+        // A fresh attempt at the level, so the fruit hunt starts over
+        hints.hunt_seconds = 0.0;
+        if hints.falls >= FALLS_FOR_HINT {
+            hints.trigger(Hint::Falling, &mut toasts);
+        }
+    }
+}
+
+pub fn watch_fruit_hunt(
+    time: Res<Time>,
+    mut level_events: EventReader<LevelCompletedEvent>,
+    mut hints: ResMut<Hints>,
+    mut toasts: ResMut<Toasts>,
+) {
+    if level_events.read().count() > 0 {
+        hints.hunt_seconds = 0.0;
+        return;
+    }
+    hints.hunt_seconds += time.delta_seconds();
+    if hints.hunt_seconds >= FRUIT_HUNT_SECONDS {
+        hints.trigger(Hint::FindFruit, &mut toasts);
+    }
+}
+
+pub fn dismiss_hint(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut hints: ResMut<Hints>,
+    mut toasts: ResMut<Toasts>,
+    mut errors: EventWriter<RuntimeErrorEvent>,
+) {
+    let Some(hint) = hints.showing else {
+        return;
+    };
+    let text = hint.banner_text();
+    // The banner has already moved past this hint
+    if !toasts.contains(&text) {
+        hints.showing = None;
+        return;
+    }
+    if keyboard_input.just_pressed(KeyCode::KeyX) && toasts.current() == Some(text.as_str()) {
+        hints.dismissed.insert(hint);
+        hints.showing = None;
+        toasts.dismiss();
+        if let Err(error) = hints.save() {
+            errors.send(error);
+        }
+    }
+}
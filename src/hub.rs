@@ -0,0 +1,352 @@
+// Trophy room: the long-term collection on display.
+//
+// Classic runs earn trophies for milestones (levels reached, fruit collected,
+// rare fruit caught, a high score), some of which unlock player skins; see
+// `rustbevy_core::collection`. Everything is kept in the save directory. H on
+// the main menu opens the room, with the trophies, the skins and the rare
+// fruit on three shelves. Arrow keys pick something to look at, ENTER wears
+// the picked skin (as on the character select screen, see `character_select`)
+// and ESC goes back to the menu. Like the high score,
+// assisted runs don't add to the collection, and levels only count in runs
+// that started at level 1.
+
+use bevy::color::Mix;
+use bevy::prelude::*;
+use crate::prelude::*;
+use rustbevy_core::collection::{Collection, Skin, Trophy};
+use rustbevy_core::config::{FRUIT_SIZE, PLAYER_SIZE};
+use rustbevy_core::fruit::FruitKind;
+
+use crate::character_select::{skin_stats_line, PlayerProfile};
+use crate::chat::chat_closed;
+use crate::fruit::fruit_color;
+use crate::game::{read_save_file, write_save_file};
+use crate::menu::MainMenuUI;
+use crate::settings::Settings;
+use crate::ui::{despawn_screen, Toasts};
+
+pub struct HubPlugin;
+
+impl Plugin for HubPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PlayerCollection>()
+            .init_resource::<HubCursor>()
+            .add_systems(OnEnter(AppState::MainMenu), setup_hub_menu_line)
+            .add_systems(OnEnter(AppState::Hub), setup_hub)
+            .add_systems(OnExit(AppState::Hub), despawn_screen::<HubUI>)
+            .add_systems(Update, (
+                open_hub.run_if(in_state(AppState::MainMenu).and_then(chat_closed)),
+                (handle_hub_input, show_hub_selection).chain().run_if(in_state(AppState::Hub)),
+                record_collection.run_if(in_state(AppState::Playing).and_then(resource_equals(GameMode::Classic))),
+            ));
+    }
+}
+
+const COLLECTION_FILE: &str = "collection.txt";
+// Shelf heights, top to bottom: trophies, skins, rare fruit
+const SHELF_Y: [f32; 3] = [150.0, 0.0, -150.0];
+const ITEM_SPACING: f32 = 90.0;
+const SHELF_COLOR: Color = Color::srgb(0.45, 0.32, 0.22);
+const TROPHY_COLOR: Color = Color::srgb(1.0, 0.8, 0.25);
+const LOCKED_COLOR: Color = Color::srgb(0.22, 0.22, 0.25);
+const SELECTED_COLOR: Color = Color::srgb(1.0, 1.0, 0.4);
+// How far and how fast the picked item bobs
+const BOB_HEIGHT: f32 = 6.0;
+const BOB_SPEED: f32 = 4.0;
+
+#[derive(Resource)]
+pub struct PlayerCollection(pub Collection);
+
+impl Default for PlayerCollection {
+    fn default() -> Self {
+        let saved = read_save_file(COLLECTION_FILE).unwrap_or_default();
+        Self(Collection::from_text(&saved))
+    }
+}
+
+impl PlayerCollection {
+    pub fn save(&self) -> Result<(), RuntimeErrorEvent> {
+        write_save_file(COLLECTION_FILE, &self.0.to_text(), "the trophy collection")
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum HubItem {
+    Trophy(Trophy),
+    Skin(Skin),
+    RareFruit(FruitKind),
+}
+
+const RARE_FRUIT: [FruitKind; 3] = [FruitKind::Blessed, FruitKind::Cursed, FruitKind::Fleeing];
+
+// The items on each shelf
+fn shelf(row: usize) -> Vec<HubItem> {
+    match row {
+        0 => Trophy::ALL.into_iter().map(HubItem::Trophy).collect(),
+        1 => Skin::ALL.into_iter().map(HubItem::Skin).collect(),
+        _ => RARE_FRUIT.into_iter().map(HubItem::RareFruit).collect(),
+    }
+}
+
+fn item_position(row: usize, column: usize) -> Vec2 {
+    let count = shelf(row).len();
+    let x = (column as f32 - (count as f32 - 1.0) / 2.0) * ITEM_SPACING;
+    Vec2::new(x, SHELF_Y[row])
+}
+
+impl HubItem {
+    fn size(self) -> Vec2 {
+        match self {
+            HubItem::Trophy(_) => Vec2::new(36.0, 48.0),
+            HubItem::Skin(_) => Vec2::splat(PLAYER_SIZE * 1.5),
+            HubItem::RareFruit(_) => Vec2::splat(FRUIT_SIZE * 1.5),
+        }
+    }
+
+    fn color(self, collection: &Collection) -> Color {
+        match self {
+            HubItem::Trophy(trophy) if collection.has(trophy) => TROPHY_COLOR,
+            HubItem::Skin(skin) if collection.unlocked(skin) => skin_color(skin),
+            HubItem::RareFruit(kind) if collection.rare_fruit(kind) > 0 => fruit_color(kind),
+            _ => LOCKED_COLOR,
+        }
+    }
+
+    fn description(self, collection: &Collection, worn: Skin) -> String {
+        match self {
+            HubItem::Trophy(trophy) => {
+                let status = if collection.has(trophy) { "earned" } else { "not earned yet" };
+                format!("{} - {} ({})", trophy.name(), trophy.goal(), status)
+            }
+            HubItem::Skin(skin) if worn == skin => format!("{} skin (wearing) - {}", skin.name(), skin_stats_line(skin)),
+            HubItem::Skin(skin) if collection.unlocked(skin) => format!("{} skin - ENTER to wear - {}", skin.name(), skin_stats_line(skin)),
+            HubItem::Skin(skin) => {
+                let trophy = skin.unlocked_by().expect("locked skins have a trophy");
+                format!("{} skin - unlocked by {}: {}", skin.name(), trophy.name(), trophy.goal())
+            }
+            HubItem::RareFruit(kind) => format!("{:?} fruit - collected {}", kind, collection.rare_fruit(kind)),
+        }
+    }
+}
+
+pub fn skin_color(skin: Skin) -> Color {
+    let (red, green, blue) = skin.color();
+    Color::srgb(red, green, blue)
+}
+
+// Which shelf and item the arrow keys are on
+#[derive(Resource, Default)]
+struct HubCursor {
+    row: usize,
+    column: usize,
+}
+
+#[derive(Component)]
+struct HubUI;
+
+#[derive(Component)]
+struct HubSprite {
+    item: HubItem,
+    row: usize,
+    column: usize,
+}
+
+#[derive(Component)]
+struct HubInfoText;
+
+fn hub_menu_line(collection: &Collection) -> String {
+    format!("H: Trophy room ({}/{} trophies)", collection.trophies.len(), Trophy::ALL.len())
+}
+
+fn setup_hub_menu_line(mut commands: Commands, collection: Res<PlayerCollection>) {
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                hub_menu_line(&collection.0),
+                TextStyle {
+                    font_size: 20.0,
+                    color: TROPHY_COLOR,
+                    ..default()
+                },
+            ),
+            transform: Transform::from_translation(Vec3::new(0.0, -175.0, 10.0)),
+            ..default()
+        },
+        MainMenuUI,
+    ));
+}
+
+fn open_hub(keyboard_input: Res<ButtonInput<KeyCode>>, mut next_state: ResMut<NextState<AppState>>) {
+    if keyboard_input.just_pressed(KeyCode::KeyH) {
+        next_state.set(AppState::Hub);
+    }
+}
+
+fn text(commands: &mut Commands, value: impl Into<String>, position: Vec2, font_size: f32, color: Color) -> Entity {
+    commands
+        .spawn((
+            Text2dBundle {
+                text: Text::from_section(value, TextStyle { font_size, color, ..default() }),
+                transform: Transform::from_translation(position.extend(10.0)),
+                ..default()
+            },
+            HubUI,
+        ))
+        .id()
+}
+
+fn setup_hub(mut commands: Commands, collection: Res<PlayerCollection>, mut cursor: ResMut<HubCursor>) {
+    *cursor = HubCursor::default();
+    text(&mut commands, "TROPHY ROOM", Vec2::new(0.0, 290.0), 60.0, TROPHY_COLOR);
+    let labels = ["Trophies", "Skins", "Rare fruit"];
+    for (row, label) in labels.into_iter().enumerate() {
+        let shelf_y = SHELF_Y[row] - 40.0;
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite { color: SHELF_COLOR, custom_size: Some(Vec2::new(920.0, 10.0)), ..default() },
+                transform: Transform::from_xyz(0.0, shelf_y, 0.0),
+                ..default()
+            },
+            HubUI,
+        ));
+        text(&mut commands, label, Vec2::new(-500.0, SHELF_Y[row]), 24.0, Color::srgb(0.8, 0.8, 0.8));
+        for (column, item) in shelf(row).into_iter().enumerate() {
+            let position = item_position(row, column);
+            let size = item.size();
+            commands.spawn((
+                SpriteBundle {
+                    sprite: Sprite { color: item.color(&collection.0), custom_size: Some(size), ..default() },
+                    transform: Transform::from_translation(Vec3::new(position.x, shelf_y + 5.0 + size.y / 2.0, 1.0)),
+                    ..default()
+                },
+                HubSprite { item, row, column },
+                HubUI,
+            ));
+            if let HubItem::RareFruit(kind) = item {
+                let count = collection.0.rare_fruit(kind);
+                text(&mut commands, format!("x{}", count), Vec2::new(position.x, shelf_y - 20.0), 20.0, Color::WHITE);
+            }
+        }
+    }
+    let info = text(&mut commands, "", Vec2::new(0.0, -260.0), 26.0, Color::WHITE);
+    commands.entity(info).insert(HubInfoText);
+    text(&mut commands, "Arrows: look around   ENTER: wear skin   ESC: menu", Vec2::new(0.0, -320.0), 20.0, Color::srgb(0.7, 0.7, 0.7));
+}
+
+fn handle_hub_input(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut cursor: ResMut<HubCursor>,
+    collection: Res<PlayerCollection>,
+    mut profile: ResMut<PlayerProfile>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut toasts: ResMut<Toasts>,
+    mut errors: EventWriter<RuntimeErrorEvent>,
+) {
+    if keyboard_input.just_pressed(KeyCode::Escape) {
+        next_state.set(AppState::MainMenu);
+        return;
+    }
+    if keyboard_input.just_pressed(KeyCode::ArrowUp) && cursor.row > 0 {
+        cursor.row -= 1;
+    } else if keyboard_input.just_pressed(KeyCode::ArrowDown) && cursor.row < SHELF_Y.len() - 1 {
+        cursor.row += 1;
+    }
+    let count = shelf(cursor.row).len();
+    if keyboard_input.just_pressed(KeyCode::ArrowLeft) {
+        cursor.column = cursor.column.saturating_sub(1);
+    } else if keyboard_input.just_pressed(KeyCode::ArrowRight) {
+        cursor.column += 1;
+    }
+    cursor.column = cursor.column.min(count - 1);
+
+    if !keyboard_input.just_pressed(KeyCode::Enter) {
+        return;
+    }
+    let HubItem::Skin(skin) = shelf(cursor.row)[cursor.column] else {
+        return;
+    };
+    if profile.skin != skin {
+        profile.wear(skin, &collection, &mut toasts, &mut errors);
+    }
+}
+
+// Bobs the picked item, outlines it and describes it
+fn show_hub_selection(
+    time: Res<Time>,
+    cursor: Res<HubCursor>,
+    collection: Res<PlayerCollection>,
+    profile: Res<PlayerProfile>,
+    settings: Res<Settings>,
+    mut gizmos: Gizmos,
+    mut sprite_query: Query<(&HubSprite, &mut Transform, &mut Sprite)>,
+    mut info_query: Query<&mut Text, With<HubInfoText>>,
+) {
+    for (hub_sprite, mut transform, mut sprite) in sprite_query.iter_mut() {
+        let selected = hub_sprite.row == cursor.row && hub_sprite.column == cursor.column;
+        let size = hub_sprite.item.size();
+        let rest_y = SHELF_Y[hub_sprite.row] - 35.0 + size.y / 2.0;
+        let bob = if selected && !settings.reduced_motion { (time.elapsed_seconds() * BOB_SPEED).sin().abs() * BOB_HEIGHT } else { 0.0 };
+        transform.translation.y = rest_y + bob;
+        let mut color = hub_sprite.item.color(&collection.0);
+        if hub_sprite.item == HubItem::Skin(profile.skin) {
+            color = color.mix(&Color::WHITE, 0.15);
+        }
+        sprite.color = color;
+        if selected {
+            gizmos.rect_2d(transform.translation.truncate(), 0.0, size + Vec2::splat(10.0), SELECTED_COLOR);
+        }
+    }
+    if cursor.is_changed() || collection.is_changed() || profile.is_changed() {
+        if let Ok(mut text) = info_query.get_single_mut() {
+            text.sections[0].value = shelf(cursor.row)[cursor.column].description(&collection.0, profile.skin);
+        }
+    }
+}
+
+// Adds reached levels, collected fruit and the score to the collection, with a toast
+// for each trophy earned
+fn record_collection(
+    mut level_events: EventReader<LevelCompletedEvent>,
+    mut fruit_events: EventReader<FruitCollectedEvent>,
+    game_state: Res<GameState>,
+    assist_mode: Res<AssistMode>,
+    mut collection: ResMut<PlayerCollection>,
+    mut toasts: ResMut<Toasts>,
+    mut errors: EventWriter<RuntimeErrorEvent>,
+) {
+    let assisted = assist_mode.0.any();
+    let mut earned = Vec::new();
+    let mut changed = false;
+    for event in fruit_events.read() {
+        if !assisted {
+            earned.extend(collection.0.record_fruit(event.kind));
+            changed = true;
+        }
+    }
+    for event in level_events.read() {
+        if !assisted && !event.skipped && !game_state.stats.started_mid_run && event.level > collection.0.best_level {
+            earned.extend(collection.0.record_level(event.level));
+            changed = true;
+        }
+    }
+    if !assisted && game_state.score > collection.0.best_score {
+        earned.extend(collection.0.record_score(game_state.score));
+        changed = true;
+    }
+    announce_trophies(&mut toasts, &earned);
+    if changed {
+        if let Err(error) = collection.save() {
+            errors.send(error);
+        }
+    }
+}
+
+// A toast for each trophy in `earned`, naming the skin it unlocks
+pub fn announce_trophies(toasts: &mut Toasts, earned: &[Trophy]) {
+    for &trophy in earned {
+        match Skin::ALL.into_iter().find(|skin| skin.unlocked_by() == Some(trophy)) {
+            Some(skin) => toasts.push(format!("Trophy earned: {} - {} skin unlocked", trophy.name(), skin.name())),
+            None => toasts.push(format!("Trophy earned: {}", trophy.name())),
+        }
+    }
+}
@@ -0,0 +1,434 @@
+// HUD layout for Classic runs.
+//
+// Every readout is a `bevy_ui` node anchored to one corner of the view (the
+// HUD camera's viewport, which is the window less any bars around it), an
+// offset in from it, so the HUD keeps its place when the window is resized.
+// H on the pause menu opens the HUD editor: drag the lives, level, score, high
+// score, timer, seeds, fruits, rewind and continues readouts and the minimap
+// around with the mouse (they snap to a grid and anchor to the nearest corner)
+// and show or hide each one with 1-9 and 0. Hidden readouts stay faintly visible while editing so they can
+// still be moved. The layout is kept in the save directory.
+
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use bevy::window::{PrimaryWindow, WindowResized};
+use crate::prelude::*;
+
+use crate::camera::fit_view_to_window;
+use crate::game::{read_save_file, write_save_file};
+use crate::ui::despawn_screen;
+
+pub struct HudLayoutPlugin;
+
+impl Plugin for HudLayoutPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<HudLayout>()
+            .init_resource::<HudDrag>()
+            .add_systems(OnEnter(AppState::HudEditor), enter_hud_editor)
+            .add_systems(OnExit(AppState::HudEditor), (exit_hud_editor, despawn_screen::<HudEditorUI>))
+            .add_systems(Update, (
+                (drag_hud_elements, handle_hud_editor_input).run_if(in_state(AppState::HudEditor)),
+                apply_hud_layout,
+            ).chain().after(fit_view_to_window));
+    }
+}
+
+const HUD_LAYOUT_FILE: &str = "hud_layout.txt";
+const SNAP_GRID: f32 = 25.0;
+// How faint hidden readouts are drawn in the editor
+const HIDDEN_ALPHA: f32 = 0.3;
+
+// A HUD readout that can be moved or hidden
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum HudElement {
+    Lives,
+    Level,
+    Score,
+    HighScore,
+    Timer,
+    Seeds,
+    Fruits,
+    Rewind,
+    Continues,
+    Minimap,
+}
+
+impl HudElement {
+    const ALL: [HudElement; 10] = [
+        HudElement::Lives,
+        HudElement::Level,
+        HudElement::Score,
+        HudElement::HighScore,
+        HudElement::Timer,
+        HudElement::Seeds,
+        HudElement::Fruits,
+        HudElement::Rewind,
+        HudElement::Continues,
+        HudElement::Minimap,
+    ];
+
+    // Name written to the save file
+    fn key(self) -> &'static str {
+        match self {
+            Self::Lives => "lives",
+            Self::Level => "level",
+            Self::Score => "score",
+            Self::HighScore => "high_score",
+            Self::Timer => "timer",
+            Self::Seeds => "seeds",
+            Self::Fruits => "fruits",
+            Self::Rewind => "rewind",
+            Self::Continues => "continues",
+            Self::Minimap => "minimap",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Lives => "Lives",
+            Self::Level => "Level",
+            Self::Score => "Score",
+            Self::HighScore => "High Score",
+            Self::Timer => "Timer",
+            Self::Seeds => "Seeds",
+            Self::Fruits => "Fruits",
+            Self::Rewind => "Rewind",
+            Self::Continues => "Continues",
+            Self::Minimap => "Minimap",
+        }
+    }
+
+    fn default_slot(self) -> HudSlot {
+        let (corner, offset) = match self {
+            Self::Lives => (HudCorner::TopLeft, Vec2::new(25.0, 25.0)),
+            Self::Level => (HudCorner::TopRight, Vec2::new(25.0, 25.0)),
+            Self::Score => (HudCorner::TopLeft, Vec2::new(25.0, 75.0)),
+            Self::HighScore => (HudCorner::TopLeft, Vec2::new(25.0, 115.0)),
+            Self::Timer => (HudCorner::TopRight, Vec2::new(25.0, 75.0)),
+            Self::Seeds => (HudCorner::TopLeft, Vec2::new(25.0, 150.0)),
+            Self::Fruits => (HudCorner::TopRight, Vec2::new(25.0, 115.0)),
+            Self::Rewind => (HudCorner::TopRight, Vec2::new(25.0, 150.0)),
+            Self::Continues => (HudCorner::TopLeft, Vec2::new(25.0, 185.0)),
+            Self::Minimap => (HudCorner::BottomRight, Vec2::new(25.0, 25.0)),
+        };
+        HudSlot { corner, offset, shown: true }
+    }
+}
+
+// The window corner a readout is anchored to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum HudCorner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl HudCorner {
+    const ALL: [HudCorner; 4] = [HudCorner::TopLeft, HudCorner::TopRight, HudCorner::BottomLeft, HudCorner::BottomRight];
+
+    fn key(self) -> &'static str {
+        match self {
+            Self::TopLeft => "top_left",
+            Self::TopRight => "top_right",
+            Self::BottomLeft => "bottom_left",
+            Self::BottomRight => "bottom_right",
+        }
+    }
+
+    fn right(self) -> bool {
+        matches!(self, Self::TopRight | Self::BottomRight)
+    }
+
+    fn bottom(self) -> bool {
+        matches!(self, Self::BottomLeft | Self::BottomRight)
+    }
+
+    // The corner of a `window`-sized screen nearest to `point`
+    fn nearest(point: Vec2, window: Vec2) -> Self {
+        match (point.x > window.x / 2.0, point.y > window.y / 2.0) {
+            (false, false) => Self::TopLeft,
+            (true, false) => Self::TopRight,
+            (false, true) => Self::BottomLeft,
+            (true, true) => Self::BottomRight,
+        }
+    }
+}
+
+// Where a readout goes: `offset` pixels in from its corner, to its nearest
+// edges
+#[derive(Clone, Copy)]
+struct HudSlot {
+    corner: HudCorner,
+    offset: Vec2,
+    shown: bool,
+}
+
+impl HudSlot {
+    // The slot of a `size` readout whose top left corner is at `top_left`
+    fn at(top_left: Vec2, size: Vec2, window: Vec2, shown: bool) -> Self {
+        let corner = HudCorner::nearest(top_left + size / 2.0, window);
+        let x = if corner.right() { window.x - top_left.x - size.x } else { top_left.x };
+        let y = if corner.bottom() { window.y - top_left.y - size.y } else { top_left.y };
+        Self { corner, offset: Vec2::new(x, y), shown }
+    }
+
+    // Absolute positioning for the readout's node, kept on a `window`-sized
+    // screen
+    fn apply(&self, style: &mut Style, size: Vec2, window: Vec2) {
+        let offset = self.offset.clamp(Vec2::ZERO, (window - size).max(Vec2::ZERO));
+        let (x, y) = (Val::Px(offset.x), Val::Px(offset.y));
+        style.position_type = PositionType::Absolute;
+        (style.left, style.right) = if self.corner.right() { (Val::Auto, x) } else { (x, Val::Auto) };
+        (style.top, style.bottom) = if self.corner.bottom() { (Val::Auto, y) } else { (y, Val::Auto) };
+    }
+}
+
+#[derive(Resource)]
+pub struct HudLayout {
+    slots: HashMap<HudElement, HudSlot>,
+}
+
+impl Default for HudLayout {
+    fn default() -> Self {
+        let saved = read_save_file(HUD_LAYOUT_FILE).unwrap_or_default();
+        let mut layout = Self::reset();
+        // One `<element> <corner> <x> <y> <shown|hidden>` line per readout
+        for line in saved.lines() {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            let [key, corner, x, y, shown] = parts[..] else {
+                continue;
+            };
+            let Some(element) = HudElement::ALL.into_iter().find(|element| element.key() == key) else {
+                continue;
+            };
+            let Some(corner) = HudCorner::ALL.into_iter().find(|anchor| anchor.key() == corner) else {
+                continue;
+            };
+            if let (Ok(x), Ok(y)) = (x.parse(), y.parse()) {
+                layout.slots.insert(element, HudSlot { corner, offset: Vec2::new(x, y), shown: shown != "hidden" });
+            }
+        }
+        layout
+    }
+}
+
+impl HudLayout {
+    // Every readout back where it started, shown
+    fn reset() -> Self {
+        Self { slots: HudElement::ALL.into_iter().map(|element| (element, element.default_slot())).collect() }
+    }
+
+    fn slot(&self, element: HudElement) -> HudSlot {
+        self.slots.get(&element).copied().unwrap_or(element.default_slot())
+    }
+
+    // Shows `element` if it was hidden and hides it if it was shown, returning
+    // whether it's shown now
+    pub fn toggle(&mut self, element: HudElement) -> bool {
+        let slot = self.slot(element);
+        self.slots.insert(element, HudSlot { shown: !slot.shown, ..slot });
+        !slot.shown
+    }
+
+    fn save(&self) -> Result<(), RuntimeErrorEvent> {
+        let lines: Vec<String> = HudElement::ALL
+            .into_iter()
+            .map(|element| {
+                let slot = self.slot(element);
+                let shown = if slot.shown { "shown" } else { "hidden" };
+                format!("{} {} {} {} {}", element.key(), slot.corner.key(), slot.offset.x, slot.offset.y, shown)
+            })
+            .collect();
+        write_save_file(HUD_LAYOUT_FILE, &lines.join("\n"), "the HUD layout")
+    }
+}
+
+// The readout being dragged and where on it the mouse grabbed it
+#[derive(Resource, Default)]
+struct HudDrag(Option<(HudElement, Vec2)>);
+
+#[derive(Component)]
+struct HudEditorUI;
+
+#[derive(Component)]
+struct HudEditorStatusText;
+
+fn editor_status(layout: &HudLayout) -> String {
+    let states: Vec<String> = HudElement::ALL
+        .into_iter()
+        .enumerate()
+        .map(|(index, element)| {
+            let shown = if layout.slot(element).shown { "on" } else { "off" };
+            // The tenth is on 0
+            format!("{}: {} {}", (index + 1) % 10, element.label(), shown)
+        })
+        .collect();
+    states.join("   ")
+}
+
+fn enter_hud_editor(mut commands: Commands, layout: Res<HudLayout>) {
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                "HUD EDITOR - drag readouts with the mouse   R: Reset   H / ESC: Done",
+                TextStyle {
+                    font_size: 24.0,
+                    color: Color::srgb(0.0, 1.0, 0.0), // Green
+                    ..default()
+                },
+            ),
+            transform: Transform::from_translation(Vec3::new(0.0, 0.0, 16.0)),
+            ..default()
+        },
+        HudEditorUI,
+    ));
+
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                editor_status(&layout),
+                TextStyle {
+                    font_size: 22.0,
+                    color: Color::srgb(0.8, 0.8, 0.8), // Light gray
+                    ..default()
+                },
+            ),
+            transform: Transform::from_translation(Vec3::new(0.0, -35.0, 16.0)),
+            ..default()
+        },
+        HudEditorStatusText,
+        HudEditorUI,
+    ));
+}
+
+fn exit_hud_editor(layout: Res<HudLayout>, mut drag: ResMut<HudDrag>, mut errors: EventWriter<RuntimeErrorEvent>) {
+    drag.0 = None;
+    if let Err(error) = layout.save() {
+        errors.send(error);
+    }
+}
+
+// Where the HUD is laid out in the window, in logical pixels
+fn hud_view(camera_query: &Query<&Camera, With<HudCamera>>) -> Option<Rect> {
+    camera_query.get_single().ok()?.logical_viewport_rect()
+}
+
+// Readouts are dragged in UI coordinates: logical pixels from the view's top
+// left corner
+fn drag_hud_elements(
+    mouse_buttons: Res<ButtonInput<MouseButton>>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    camera_query: Query<&Camera, With<HudCamera>>,
+    element_query: Query<(&HudElement, &Node, &GlobalTransform)>,
+    mut layout: ResMut<HudLayout>,
+    mut drag: ResMut<HudDrag>,
+) {
+    if !mouse_buttons.pressed(MouseButton::Left) {
+        drag.0 = None;
+        return;
+    }
+    let Ok(window) = window_query.get_single() else {
+        return;
+    };
+    let (Some(cursor), Some(view)) = (window.cursor_position(), hud_view(&camera_query)) else {
+        return;
+    };
+    let cursor = cursor - view.min;
+
+    if mouse_buttons.just_pressed(MouseButton::Left) {
+        drag.0 = element_query
+            .iter()
+            .map(|(element, node, transform)| (element, node.logical_rect(transform)))
+            .find(|(_, rect)| rect.contains(cursor))
+            .map(|(element, rect)| (*element, cursor - rect.min));
+    }
+
+    let Some((element, grab_offset)) = drag.0 else {
+        return;
+    };
+    let Some((_, node, _)) = element_query.iter().find(|(dragged, _, _)| **dragged == element) else {
+        return;
+    };
+    let window = view.size();
+    let size = node.size();
+    let top_left = ((cursor - grab_offset) / SNAP_GRID).round() * SNAP_GRID;
+    let top_left = top_left.clamp(Vec2::ZERO, (window - size).max(Vec2::ZERO));
+    let slot = HudSlot::at(top_left, size, window, layout.slot(element).shown);
+    let current = layout.slot(element);
+    if current.corner != slot.corner || current.offset != slot.offset {
+        layout.slots.insert(element, slot);
+    }
+}
+
+fn handle_hud_editor_input(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut layout: ResMut<HudLayout>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut status_query: Query<&mut Text, With<HudEditorStatusText>>,
+) {
+    if keyboard_input.any_just_pressed([KeyCode::KeyH, KeyCode::Escape]) {
+        next_state.set(AppState::Paused);
+        return;
+    }
+    if keyboard_input.just_pressed(KeyCode::KeyR) {
+        *layout = HudLayout::reset();
+    }
+    let keys = [KeyCode::Digit1, KeyCode::Digit2, KeyCode::Digit3, KeyCode::Digit4, KeyCode::Digit5, KeyCode::Digit6, KeyCode::Digit7, KeyCode::Digit8, KeyCode::Digit9, KeyCode::Digit0];
+    if let Some(index) = keys.iter().position(|key| keyboard_input.just_pressed(*key)) {
+        layout.toggle(HudElement::ALL[index]);
+    }
+
+    if layout.is_changed() {
+        if let Ok(mut text) = status_query.get_single_mut() {
+            text.sections[0].value = editor_status(&layout);
+        }
+    }
+}
+
+// Puts every readout where the layout says, including ones just spawned, and
+// keeps them on screen when the window is resized
+fn apply_hud_layout(
+    mut resize_events: EventReader<WindowResized>,
+    layout: Res<HudLayout>,
+    app_state: Res<State<AppState>>,
+    camera_query: Query<&Camera, With<HudCamera>>,
+    mut element_query: Query<(Ref<HudElement>, &Node, &mut Style, &mut Visibility, Option<&mut Text>, Option<&Children>)>,
+    mut heart_query: Query<&mut UiImage>,
+    mut child_text_query: Query<&mut Text, Without<HudElement>>,
+) {
+    let resized = resize_events.read().count() > 0;
+    let Some(view) = hud_view(&camera_query) else {
+        return;
+    };
+    let editing = *app_state.get() == AppState::HudEditor;
+    let refresh = layout.is_changed() || app_state.is_changed() || resized;
+    for (element, node, mut style, mut visibility, text, children) in element_query.iter_mut() {
+        if !refresh && !element.is_added() {
+            continue;
+        }
+        let slot = layout.slot(*element);
+        slot.apply(&mut style, node.size(), view.size());
+        *visibility = if slot.shown || editing { Visibility::Inherited } else { Visibility::Hidden };
+        let alpha = if slot.shown { 1.0 } else { HIDDEN_ALPHA };
+        if let Some(mut text) = text {
+            set_text_alpha(&mut text, alpha);
+        }
+        // The lives readout's hearts
+        for &child in children.into_iter().flatten() {
+            if let Ok(mut image) = heart_query.get_mut(child) {
+                image.color.set_alpha(alpha);
+            }
+            if let Ok(mut text) = child_text_query.get_mut(child) {
+                set_text_alpha(&mut text, alpha);
+            }
+        }
+    }
+}
+
+fn set_text_alpha(text: &mut Text, alpha: f32) {
+    for section in text.sections.iter_mut() {
+        section.style.color.set_alpha(alpha);
+    }
+}
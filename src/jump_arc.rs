@@ -0,0 +1,63 @@
+// Jump arc assist (J on the main menu): a dotted line from each standing
+// Classic player showing where a jump would go, worked out from the player's
+// jump speed, air speed and gravity by `JumpProfile::jump_arc`. While the
+// player stands still it shows a jump to either side; while they run, a jump
+// the way they're running. The level editor draws the same arcs off the
+// platform under the mouse.
+
+use bevy::prelude::*;
+use crate::prelude::*;
+use rustbevy_core::generation::PlatformSpec;
+
+use crate::character_select::PlayerProfile;
+use crate::physics::{classic_jump_profile, solid_platform};
+
+pub struct JumpArcPlugin;
+
+impl Plugin for JumpArcPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, draw_jump_arcs
+            .run_if(in_state(AppState::Playing).and_then(resource_equals(GameMode::Classic))));
+    }
+}
+
+const JUMP_ARC_COLOR: Color = Color::srgba(1.0, 1.0, 1.0, 0.6);
+// Radius of each dot
+const JUMP_ARC_DOT: f32 = 2.0;
+// Arcs going off the platforms are followed this far down
+pub const JUMP_ARC_DROP: f32 = 300.0;
+
+fn draw_jump_arcs(
+    mut gizmos: Gizmos,
+    assist_mode: Res<AssistMode>,
+    player_profile: Res<PlayerProfile>,
+    run_modifiers: Res<RunModifiers>,
+    player_query: Query<(&Transform, &Velocity, &Grounded, &Collider), With<Player>>,
+    platform_query: Query<(&Transform, &Platform, Has<OneWay>, Option<&Crumbling>, Option<&Trap>), Without<Player>>,
+) {
+    if !assist_mode.0.jump_arc {
+        return;
+    }
+    let platforms: Vec<PlatformSpec> = platform_query
+        .iter()
+        .filter_map(|(transform, platform, one_way, crumbling, trap)| solid_platform(transform, platform, one_way, crumbling, trap))
+        .collect();
+    for (transform, velocity, grounded, collider) in player_query.iter() {
+        if !grounded.0 {
+            continue;
+        }
+        let profile = classic_jump_profile(&run_modifiers, &assist_mode, &player_profile, collider.size);
+        let directions: &[f32] = if velocity.x == 0.0 { &[-1.0, 1.0] } else { &[velocity.x.signum()] };
+        let from = (transform.translation.x, transform.translation.y);
+        for &direction in directions {
+            draw_jump_arc(&mut gizmos, &profile.jump_arc(from, direction, &platforms, JUMP_ARC_DROP), JUMP_ARC_COLOR);
+        }
+    }
+}
+
+// A jump arc as a line of dots
+pub fn draw_jump_arc(gizmos: &mut Gizmos, arc: &[(f32, f32)], color: Color) {
+    for &(x, y) in arc {
+        gizmos.circle_2d(Vec2::new(x, y), JUMP_ARC_DOT, color);
+    }
+}
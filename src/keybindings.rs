@@ -0,0 +1,527 @@
+// Rebindable Classic controls.
+//
+// The keys for moving, jumping, climbing and pausing live in the `InputMap` resource,
+// loaded from the save directory at startup. K on the main menu opens the
+// Keybindings screen: UP/DOWN picks an action, ENTER then any key binds that
+// key to it (ESC cancels), BACKSPACE puts the action back to its default keys
+// and ESC returns to the menu. Changes are saved straight away and apply to
+// the Classic player the moment they're made.
+//
+// The top row of the screen is a dropdown of control presets: the built-in
+// ones (arrows, WASD, one-handed layouts) and any the player saved. ENTER
+// opens it, UP/DOWN and ENTER swap the whole layout in, DELETE removes a saved
+// one. S saves the current keys as a new preset, kept in the save directory.
+
+use bevy::prelude::*;
+use crate::prelude::*;
+
+use crate::game::{read_save_file, write_save_file};
+use crate::menu::MainMenuUI;
+use crate::ui::{despawn_screen, Toasts};
+
+pub struct KeybindingsPlugin;
+
+impl Plugin for KeybindingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(load_input_map())
+            .insert_resource(load_custom_presets())
+            .init_resource::<RebindScreen>()
+            .add_systems(OnEnter(AppState::MainMenu), setup_controls_menu_line)
+            .add_systems(OnEnter(AppState::Keybindings), setup_keybindings_screen)
+            .add_systems(OnExit(AppState::Keybindings), despawn_screen::<KeybindingsUI>)
+            .add_systems(Update, (
+                open_keybindings.run_if(in_state(AppState::MainMenu)),
+                (handle_rebind_input, update_keybinding_lines).chain().run_if(in_state(AppState::Keybindings)),
+                (save_input_map, apply_input_map).chain(),
+                save_custom_presets,
+            ));
+    }
+}
+
+const KEYBINDINGS_FILE: &str = "keybindings.txt";
+const PRESETS_FILE: &str = "control_presets.txt";
+const SELECTED_COLOR: Color = Color::srgb(1.0, 1.0, 0.0); // Yellow
+const ACTION_COLOR: Color = Color::srgb(0.8, 0.8, 0.8);
+
+// Keys that can be bound, so the keybindings file can name them
+const BINDABLE_KEYS: &[KeyCode] = &[
+    KeyCode::KeyA, KeyCode::KeyB, KeyCode::KeyC, KeyCode::KeyD, KeyCode::KeyE, KeyCode::KeyF, KeyCode::KeyG,
+    KeyCode::KeyH, KeyCode::KeyI, KeyCode::KeyJ, KeyCode::KeyK, KeyCode::KeyL, KeyCode::KeyM, KeyCode::KeyN,
+    KeyCode::KeyO, KeyCode::KeyP, KeyCode::KeyQ, KeyCode::KeyR, KeyCode::KeyS, KeyCode::KeyT, KeyCode::KeyU,
+    KeyCode::KeyV, KeyCode::KeyW, KeyCode::KeyX, KeyCode::KeyY, KeyCode::KeyZ,
+    KeyCode::Digit0, KeyCode::Digit1, KeyCode::Digit2, KeyCode::Digit3, KeyCode::Digit4,
+    KeyCode::Digit5, KeyCode::Digit6, KeyCode::Digit7, KeyCode::Digit8, KeyCode::Digit9,
+    KeyCode::Numpad0, KeyCode::Numpad1, KeyCode::Numpad2, KeyCode::Numpad3, KeyCode::Numpad4,
+    KeyCode::Numpad5, KeyCode::Numpad6, KeyCode::Numpad7, KeyCode::Numpad8, KeyCode::Numpad9,
+    KeyCode::ArrowLeft, KeyCode::ArrowRight, KeyCode::ArrowUp, KeyCode::ArrowDown,
+    KeyCode::Space, KeyCode::Enter, KeyCode::Escape, KeyCode::Tab, KeyCode::Backspace,
+    KeyCode::ShiftLeft, KeyCode::ShiftRight, KeyCode::ControlLeft, KeyCode::ControlRight,
+    KeyCode::AltLeft, KeyCode::AltRight, KeyCode::Comma, KeyCode::Period, KeyCode::Slash,
+    KeyCode::Semicolon, KeyCode::Quote, KeyCode::BracketLeft, KeyCode::BracketRight,
+    KeyCode::Minus, KeyCode::Equal, KeyCode::Backquote, KeyCode::Backslash,
+];
+
+// A key's name in the keybindings file, e.g. "KeyA" or "ArrowLeft"
+fn key_name(key: KeyCode) -> String {
+    format!("{:?}", key)
+}
+
+fn key_from_name(name: &str) -> Option<KeyCode> {
+    BINDABLE_KEYS.iter().copied().find(|key| key_name(*key) == name)
+}
+
+// How a key reads on screen: "A", "1", "ArrowLeft", ...
+fn key_label(key: KeyCode) -> String {
+    let name = key_name(key);
+    name.strip_prefix("Key").or_else(|| name.strip_prefix("Digit")).unwrap_or(&name).to_string()
+}
+
+pub fn keys_label(keys: &[KeyCode]) -> String {
+    if keys.is_empty() {
+        return "(none)".to_string();
+    }
+    keys.iter().map(|key| key_label(*key)).collect::<Vec<_>>().join(" / ")
+}
+
+// The main actions with their keys on one line, e.g. "Move left: A   Move right: D   ..."
+pub fn layout_summary(input_map: &InputMap) -> String {
+    [InputAction::MoveLeft, InputAction::MoveRight, InputAction::Jump, InputAction::Pause]
+        .into_iter()
+        .map(|action| format!("{}: {}", action.label(), keys_label(input_map.keys(action))))
+        .collect::<Vec<_>>()
+        .join("   ")
+}
+
+// Applies one `<action> <key> <key> ...` line to `input_map`
+fn read_binding_line(line: &str, input_map: &mut InputMap) {
+    let mut fields = line.split_whitespace();
+    let Some(action) = fields.next().and_then(InputAction::from_key) else {
+        return;
+    };
+    let keys: Vec<KeyCode> = fields.filter_map(key_from_name).collect();
+    if !keys.is_empty() {
+        input_map.bind(action, keys);
+    }
+}
+
+fn binding_lines(input_map: &InputMap) -> Vec<String> {
+    InputAction::ALL
+        .into_iter()
+        .map(|action| {
+            let keys: Vec<String> = input_map.keys(action).iter().map(|key| key_name(*key)).collect();
+            format!("{} {}", action.key(), keys.join(" "))
+        })
+        .collect()
+}
+
+fn load_input_map() -> InputMap {
+    let saved = read_save_file(KEYBINDINGS_FILE).unwrap_or_default();
+    let mut input_map = InputMap::default();
+    // One line per rebound action
+    for line in saved.lines() {
+        read_binding_line(line, &mut input_map);
+    }
+    input_map
+}
+
+fn save_input_map(input_map: Res<InputMap>, mut errors: EventWriter<RuntimeErrorEvent>) {
+    if !input_map.is_changed() || input_map.is_added() {
+        return;
+    }
+    if let Err(error) = write_save_file(KEYBINDINGS_FILE, &binding_lines(&input_map).join("\n"), "keybindings") {
+        errors.send(error);
+    }
+}
+
+// A whole control layout, picked from the presets dropdown
+#[derive(Clone)]
+pub struct ControlPreset {
+    pub name: String,
+    pub input_map: InputMap,
+    // Saved by the player, so it can be deleted
+    custom: bool,
+}
+
+fn built_in_preset(name: &str, left: &[KeyCode], right: &[KeyCode], jump: &[KeyCode], up: &[KeyCode], down: &[KeyCode], pause: &[KeyCode]) -> ControlPreset {
+    let mut input_map = InputMap::default();
+    for (action, keys) in InputAction::ALL.into_iter().zip([left, right, jump, up, down, pause]) {
+        input_map.bind(action, keys.to_vec());
+    }
+    ControlPreset { name: name.to_string(), input_map, custom: false }
+}
+
+pub fn built_in_presets() -> Vec<ControlPreset> {
+    vec![
+        ControlPreset { name: "Default".to_string(), input_map: InputMap::default(), custom: false },
+        built_in_preset("Arrows + Space", &[KeyCode::ArrowLeft], &[KeyCode::ArrowRight], &[KeyCode::Space], &[KeyCode::ArrowUp], &[KeyCode::ArrowDown], &[KeyCode::Escape]),
+        built_in_preset("WASD", &[KeyCode::KeyA], &[KeyCode::KeyD], &[KeyCode::KeyW, KeyCode::Space], &[KeyCode::KeyW], &[KeyCode::KeyS], &[KeyCode::Escape]),
+        built_in_preset("One-handed left", &[KeyCode::KeyA], &[KeyCode::KeyD], &[KeyCode::KeyW], &[KeyCode::KeyW], &[KeyCode::KeyS], &[KeyCode::KeyQ]),
+        built_in_preset("One-handed right", &[KeyCode::KeyJ], &[KeyCode::KeyL], &[KeyCode::KeyI], &[KeyCode::KeyI], &[KeyCode::KeyK], &[KeyCode::KeyP]),
+    ]
+}
+
+// The presets the player saved
+#[derive(Resource, Default)]
+struct CustomPresets(Vec<ControlPreset>);
+
+impl CustomPresets {
+    // Built-in presets first, then the saved ones
+    fn all(&self) -> Vec<ControlPreset> {
+        built_in_presets().into_iter().chain(self.0.iter().cloned()).collect()
+    }
+
+    // The preset the keys are set to, if they match one
+    fn matching(&self, input_map: &InputMap) -> Option<ControlPreset> {
+        self.all().into_iter().find(|preset| preset.input_map == *input_map)
+    }
+
+    // "Custom 1", "Custom 2", ...: the first one not taken
+    fn next_name(&self) -> String {
+        (1..)
+            .map(|number| format!("Custom {}", number))
+            .find(|name| self.0.iter().all(|preset| preset.name != *name))
+            .unwrap_or_default()
+    }
+}
+
+fn load_custom_presets() -> CustomPresets {
+    let saved = read_save_file(PRESETS_FILE).unwrap_or_default();
+    let mut presets: Vec<ControlPreset> = Vec::new();
+    // A `preset <name>` line, then its binding lines
+    for line in saved.lines() {
+        if let Some(name) = line.strip_prefix("preset ") {
+            presets.push(ControlPreset { name: name.trim().to_string(), input_map: InputMap::default(), custom: true });
+        } else if let Some(preset) = presets.last_mut() {
+            read_binding_line(line, &mut preset.input_map);
+        }
+    }
+    CustomPresets(presets)
+}
+
+fn save_custom_presets(presets: Res<CustomPresets>, mut errors: EventWriter<RuntimeErrorEvent>) {
+    if !presets.is_changed() || presets.is_added() {
+        return;
+    }
+    let lines: Vec<String> = presets
+        .0
+        .iter()
+        .flat_map(|preset| std::iter::once(format!("preset {}", preset.name)).chain(binding_lines(&preset.input_map)))
+        .collect();
+    if let Err(error) = write_save_file(PRESETS_FILE, &lines.join("\n"), "control presets") {
+        errors.send(error);
+    }
+}
+
+// Keeps the lead player's keys in step with the input map
+fn apply_input_map(
+    input_map: Res<InputMap>,
+    coop_mode: Res<CoopMode>,
+    mut player_query: Query<&mut PlayerControls, With<LeadPlayer>>,
+) {
+    for mut controls in player_query.iter_mut() {
+        if input_map.is_changed() || controls.is_added() {
+            *controls = input_map.player_controls();
+            if coop_mode.0 {
+                share_keyboard(&mut controls);
+            }
+        }
+    }
+}
+
+// In a co-op run the partner has the WASD keys, so the first player keeps the
+// rest of their bindings, and the arrow key for an action left with none
+fn share_keyboard(controls: &mut PlayerControls) {
+    let partner = PlayerControls::party_slot(0);
+    let arrows = PlayerControls::party_slot(1);
+    for (keys, taken, fallback) in [
+        (&mut controls.left, &partner.left, &arrows.left),
+        (&mut controls.right, &partner.right, &arrows.right),
+        (&mut controls.jump, &partner.jump, &arrows.jump),
+        (&mut controls.up, &partner.up, &arrows.up),
+        (&mut controls.down, &partner.down, &arrows.down),
+    ] {
+        keys.retain(|key| !taken.contains(key));
+        if keys.is_empty() {
+            keys.clone_from(fallback);
+        }
+    }
+}
+
+// Which row is picked (the presets dropdown, then the actions), whether the
+// next key press binds it and, while the dropdown is open, the preset it's on
+#[derive(Resource, Default)]
+struct RebindScreen {
+    selected: usize,
+    listening: bool,
+    dropdown: Option<usize>,
+}
+
+impl RebindScreen {
+    // The action on the picked row, unless it's the presets row
+    fn action(&self) -> Option<InputAction> {
+        self.selected.checked_sub(1).map(|index| InputAction::ALL[index])
+    }
+}
+
+#[derive(Component)]
+struct KeybindingsUI;
+
+#[derive(Component)]
+struct KeybindingLine(usize);
+
+#[derive(Component)]
+struct PresetLine;
+
+#[derive(Component)]
+struct PresetDropdownText;
+
+fn preset_name(input_map: &InputMap, presets: &CustomPresets) -> String {
+    presets.matching(input_map).map_or("Custom (unsaved)".to_string(), |preset| preset.name)
+}
+
+fn controls_menu_line(input_map: &InputMap, presets: &CustomPresets) -> String {
+    format!(
+        "Controls ({}): {} / {} to move, {} to jump   (K: Rebind keys)",
+        preset_name(input_map, presets),
+        keys_label(input_map.keys(InputAction::MoveLeft)),
+        keys_label(input_map.keys(InputAction::MoveRight)),
+        keys_label(input_map.keys(InputAction::Jump))
+    )
+}
+
+fn setup_controls_menu_line(mut commands: Commands, input_map: Res<InputMap>, presets: Res<CustomPresets>) {
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                controls_menu_line(&input_map, &presets),
+                TextStyle {
+                    font_size: 25.0,
+                    color: Color::srgb(0.7, 0.7, 1.0), // Light blue
+                    ..default()
+                },
+            ),
+            transform: Transform::from_translation(Vec3::new(0.0, -150.0, 10.0)),
+            ..default()
+        },
+        MainMenuUI,
+    ));
+}
+
+fn open_keybindings(keyboard_input: Res<ButtonInput<KeyCode>>, mut next_state: ResMut<NextState<AppState>>) {
+    if keyboard_input.just_pressed(KeyCode::KeyK) {
+        next_state.set(AppState::Keybindings);
+    }
+}
+
+fn preset_line(input_map: &InputMap, presets: &CustomPresets, screen: &RebindScreen) -> (String, Color) {
+    let name = preset_name(input_map, presets);
+    if screen.selected != 0 {
+        return (format!("Preset: {}", name), ACTION_COLOR);
+    }
+    let arrow = if screen.dropdown.is_some() { "^" } else { "v" };
+    (format!("> Preset: {} {} <", name, arrow), SELECTED_COLOR)
+}
+
+fn preset_dropdown_text(presets: &CustomPresets, screen: &RebindScreen) -> String {
+    let Some(highlighted) = screen.dropdown else {
+        return String::new();
+    };
+    presets
+        .all()
+        .iter()
+        .enumerate()
+        .map(|(index, preset)| {
+            let marker = if index == highlighted { ">" } else { " " };
+            let saved = if preset.custom { " (saved)" } else { "" };
+            format!("{} {}{}", marker, preset.name, saved)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn keybinding_line(index: usize, input_map: &InputMap, screen: &RebindScreen) -> (String, Color) {
+    let action = InputAction::ALL[index];
+    if screen.action() != Some(action) {
+        return (format!("{}: {}", action.label(), keys_label(input_map.keys(action))), ACTION_COLOR);
+    }
+    let keys = if screen.listening { "press a key...".to_string() } else { keys_label(input_map.keys(action)) };
+    (format!("> {}: {} <", action.label(), keys), SELECTED_COLOR)
+}
+
+fn setup_keybindings_screen(mut commands: Commands, input_map: Res<InputMap>, presets: Res<CustomPresets>, mut screen: ResMut<RebindScreen>) {
+    *screen = RebindScreen::default();
+
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                "KEYBINDINGS",
+                TextStyle {
+                    font_size: 60.0,
+                    color: Color::srgb(0.7, 0.7, 1.0), // Light blue
+                    ..default()
+                },
+            ),
+            transform: Transform::from_translation(Vec3::new(0.0, 200.0, 10.0)),
+            ..default()
+        },
+        KeybindingsUI,
+    ));
+
+    let (line, color) = preset_line(&input_map, &presets, &screen);
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(line, TextStyle { font_size: 30.0, color, ..default() }),
+            transform: Transform::from_translation(Vec3::new(0.0, 120.0, 10.0)),
+            ..default()
+        },
+        PresetLine,
+        KeybindingsUI,
+    ));
+    // The open dropdown's list, to the right of the rows
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                "",
+                TextStyle {
+                    font_size: 24.0,
+                    color: SELECTED_COLOR,
+                    ..default()
+                },
+            ),
+            text_anchor: bevy::sprite::Anchor::TopLeft,
+            transform: Transform::from_translation(Vec3::new(300.0, 130.0, 10.0)),
+            ..default()
+        },
+        PresetDropdownText,
+        KeybindingsUI,
+    ));
+
+    for index in 0..InputAction::ALL.len() {
+        let (line, color) = keybinding_line(index, &input_map, &screen);
+        commands.spawn((
+            Text2dBundle {
+                text: Text::from_section(line, TextStyle { font_size: 30.0, color, ..default() }),
+                transform: Transform::from_translation(Vec3::new(0.0, 60.0 - index as f32 * 45.0, 10.0)),
+                ..default()
+            },
+            KeybindingLine(index),
+            KeybindingsUI,
+        ));
+    }
+
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                "UP / DOWN: Choose   ENTER: Rebind / Presets   BACKSPACE: Default keys   S: Save as preset   ESC: Back",
+                TextStyle {
+                    font_size: 22.0,
+                    color: Color::srgb(0.0, 1.0, 0.0), // Green
+                    ..default()
+                },
+            ),
+            transform: Transform::from_translation(Vec3::new(0.0, -230.0, 10.0)),
+            ..default()
+        },
+        KeybindingsUI,
+    ));
+}
+
+fn handle_rebind_input(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut input_map: ResMut<InputMap>,
+    mut presets: ResMut<CustomPresets>,
+    mut screen: ResMut<RebindScreen>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut toasts: ResMut<Toasts>,
+) {
+    if screen.listening {
+        let Some(action) = screen.action() else {
+            return;
+        };
+        if keyboard_input.just_pressed(KeyCode::Escape) {
+            screen.listening = false;
+        } else if let Some(key) = keyboard_input.get_just_pressed().copied().find(|key| BINDABLE_KEYS.contains(key)) {
+            input_map.bind(action, vec![key]);
+            screen.listening = false;
+        }
+        return;
+    }
+
+    if let Some(highlighted) = screen.dropdown {
+        let all = presets.all();
+        if keyboard_input.just_pressed(KeyCode::Escape) {
+            screen.dropdown = None;
+        } else if keyboard_input.just_pressed(KeyCode::ArrowUp) {
+            screen.dropdown = Some((highlighted + all.len() - 1) % all.len());
+        } else if keyboard_input.just_pressed(KeyCode::ArrowDown) {
+            screen.dropdown = Some((highlighted + 1) % all.len());
+        } else if keyboard_input.just_pressed(KeyCode::Enter) {
+            *input_map = all[highlighted].input_map.clone();
+            screen.dropdown = None;
+        } else if keyboard_input.just_pressed(KeyCode::Delete) && all[highlighted].custom {
+            let name = &all[highlighted].name;
+            presets.0.retain(|preset| preset.name != *name);
+            toasts.push(format!("Deleted preset {}", name));
+            screen.dropdown = Some(highlighted.min(all.len() - 2));
+        }
+        return;
+    }
+
+    let count = InputAction::ALL.len() + 1;
+    if keyboard_input.just_pressed(KeyCode::Escape) {
+        next_state.set(AppState::MainMenu);
+    } else if keyboard_input.just_pressed(KeyCode::ArrowUp) {
+        screen.selected = (screen.selected + count - 1) % count;
+    } else if keyboard_input.just_pressed(KeyCode::ArrowDown) {
+        screen.selected = (screen.selected + 1) % count;
+    } else if keyboard_input.just_pressed(KeyCode::Enter) {
+        match screen.action() {
+            Some(_) => screen.listening = true,
+            // Opens on the preset in use
+            None => {
+                let current = presets.all().iter().position(|preset| preset.input_map == *input_map);
+                screen.dropdown = Some(current.unwrap_or_default());
+            }
+        }
+    } else if keyboard_input.just_pressed(KeyCode::Backspace) {
+        if let Some(action) = screen.action() {
+            let defaults = InputMap::default().keys(action).to_vec();
+            input_map.bind(action, defaults);
+        }
+    } else if keyboard_input.just_pressed(KeyCode::KeyS) {
+        match presets.matching(&input_map) {
+            Some(preset) => toasts.push(format!("These keys are already the {} preset", preset.name)),
+            None => {
+                let name = presets.next_name();
+                toasts.push(format!("Saved as preset {}", name));
+                presets.0.push(ControlPreset { name, input_map: input_map.clone(), custom: true });
+            }
+        }
+    }
+}
+
+fn update_keybinding_lines(
+    input_map: Res<InputMap>,
+    presets: Res<CustomPresets>,
+    screen: Res<RebindScreen>,
+    mut text_query: Query<(&KeybindingLine, &mut Text)>,
+    mut preset_query: Query<&mut Text, (With<PresetLine>, Without<KeybindingLine>)>,
+    mut dropdown_query: Query<&mut Text, (With<PresetDropdownText>, Without<PresetLine>, Without<KeybindingLine>)>,
+) {
+    if !input_map.is_changed() && !presets.is_changed() && !screen.is_changed() {
+        return;
+    }
+    for (line, mut text) in text_query.iter_mut() {
+        let (value, color) = keybinding_line(line.0, &input_map, &screen);
+        text.sections[0].value = value;
+        text.sections[0].style.color = color;
+    }
+    if let Ok(mut text) = preset_query.get_single_mut() {
+        let (value, color) = preset_line(&input_map, &presets, &screen);
+        text.sections[0].value = value;
+        text.sections[0].style.color = color;
+    }
+    if let Ok(mut text) = dropdown_query.get_single_mut() {
+        text.sections[0].value = preset_dropdown_text(&presets, &screen);
+    }
+}
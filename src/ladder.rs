@@ -0,0 +1,127 @@
+//! Ladders and ropes in Classic levels (see `rustbevy_core::ladder` for where
+//! generated levels put them and how a climb goes).
+//!
+//! A player on one holding up or down grabs it. `climb_ladders` then moves
+//! them every physics step, ahead of `player_movement`, and marks them
+//! `Climbing`, which running, gravity and the platform collisions leave alone.
+//! Jumping, or climbing off either end, hands them back to the regular
+//! physics the same step.
+
+use bevy::prelude::*;
+use crate::prelude::*;
+use rustbevy_core::ladder::{self as ladder_rules, Ladder as LadderSpan, LADDER_WIDTH, ROPE_WIDTH};
+use rustbevy_core::physics::SimInput;
+
+use crate::camera::spectating;
+use crate::character_select::PlayerProfile;
+use crate::chat::chat_closed;
+use crate::gamepad::GamepadInput;
+use crate::helper::summon_helper_platform;
+use crate::physics::{player_body, player_movement, player_tuning, store_player_body};
+use crate::replay::ReplayPlayback;
+
+pub struct LadderPlugin;
+
+impl Plugin for LadderPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(FixedUpdate, climb_ladders
+            .after(summon_helper_platform)
+            .before(player_movement)
+            .run_if(in_state(AppState::Playing).and_then(not(spectating)).and_then(chat_closed)));
+    }
+}
+
+const LADDER_COLOR: Color = Color::srgb(0.55, 0.35, 0.15); // Brown
+const ROPE_COLOR: Color = Color::srgb(0.85, 0.75, 0.5); // Tan
+
+pub fn ladder_color(rope: bool) -> Color {
+    if rope {
+        ROPE_COLOR
+    } else {
+        LADDER_COLOR
+    }
+}
+
+/// A ladder or rope, drawn behind the platforms it joins
+pub fn spawn_ladder(commands: &mut Commands, ladder: LadderSpan) {
+    let width = if ladder.rope { ROPE_WIDTH } else { LADDER_WIDTH };
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: ladder_color(ladder.rope),
+                custom_size: Some(Vec2::new(width, ladder.top - ladder.bottom)),
+                ..default()
+            },
+            transform: Transform::from_translation(Vec3::new(ladder.x, (ladder.top + ladder.bottom) / 2.0, -0.6)),
+            ..default()
+        },
+        Ladder(ladder),
+    ));
+}
+
+// Grabs, climbs and lets go of ladders, reading the keys (and the controller
+// or a replay) the way `player_movement` does. A jump press is the climb's
+// while the player is on a ladder, so `player_movement` doesn't jump again.
+fn climb_ladders(
+    mut commands: Commands,
+    time: Res<Time>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    game_mode: Res<GameMode>,
+    run_modifiers: Res<RunModifiers>,
+    assist_mode: Res<AssistMode>,
+    player_profile: Res<PlayerProfile>,
+    gamepad: Res<GamepadInput>,
+    playback: Option<Res<ReplayPlayback>>,
+    ladder_query: Query<(Entity, &Ladder)>,
+    mut player_query: Query<(Entity, &mut Transform, &mut Velocity, &mut Grounded, &mut JumpState, &mut JumpPress, &PlayerControls, &Collider, Option<&PlayerStatus>, Option<&Climbing>, Has<LeadPlayer>), With<Player>>,
+    mut sound_events: EventWriter<PlaySoundEvent>,
+) {
+    let tuning = player_tuning(*game_mode, &run_modifiers, &assist_mode, &player_profile);
+    for (player, mut transform, mut velocity, mut grounded, mut jump_state, mut jump_press, controls, collider, player_status, climbing, lead) in player_query.iter_mut() {
+        let tuning = player_status.map_or(tuning, |status| status.0.tuning(tuning));
+        let mut input = SimInput {
+            left: PlayerControls::any_pressed(&keyboard_input, &controls.left),
+            right: PlayerControls::any_pressed(&keyboard_input, &controls.right),
+            jump: false,
+            up: PlayerControls::any_pressed(&keyboard_input, &controls.up),
+            down: PlayerControls::any_pressed(&keyboard_input, &controls.down),
+            throw: false,
+        };
+        // The controller plays alongside the lead player's keys, and a
+        // replay being played back stands in for both
+        if lead {
+            input.left |= gamepad.horizontal < 0.0;
+            input.right |= gamepad.horizontal > 0.0;
+            input.up |= gamepad.up_held();
+            input.down |= gamepad.down_held();
+            if let Some(recorded) = playback.as_ref().and_then(|playback| playback.input()) {
+                input = recorded;
+            }
+        }
+
+        let mut body = player_body(&transform, &velocity, &grounded, collider);
+        // A ladder that's gone (the level moved on) is let go of
+        let held = climbing.and_then(|climbing| ladder_query.get(climbing.0).ok());
+        let Some((ladder, span)) = held.or_else(|| ladder_query.iter().find(|(_, ladder)| ladder.0.grabbed_by(&body, input))) else {
+            if climbing.is_some() {
+                commands.entity(player).remove::<Climbing>();
+            }
+            continue;
+        };
+        let on_ladder = span.0.reaches(&body);
+        let jump_pressed = std::mem::take(&mut jump_press.0);
+        if ladder_rules::climb(&mut body, &mut jump_state.0, &span.0, input, jump_pressed, &tuning, time.delta_seconds()) {
+            if climbing != Some(&Climbing(ladder)) {
+                commands.entity(player).insert(Climbing(ladder)).remove::<StandingOn>();
+            }
+        } else {
+            if climbing.is_some() {
+                commands.entity(player).remove::<Climbing>();
+            }
+            if on_ladder && jump_pressed && !input.up {
+                sound_events.send(PlaySoundEvent { sound_type: SoundType::Jump, position: None });
+            }
+        }
+        store_player_body(&body, &mut transform, &mut velocity, &mut grounded);
+    }
+}
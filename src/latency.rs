@@ -0,0 +1,228 @@
+// Input latency probe for Classic runs.
+//
+// F9 toggles it. Every move or jump press flashes a white square in the bottom
+// right corner on the frame the game sees the key, and a green one on the frame
+// the player actually starts moving that way. The overlay counts the frames in
+// between (0 means the player moved on the same frame the key was read). Film
+// the screen with a fast camera and the gap between key and white flash is the
+// OS/input part; white to green is what the game adds.
+//
+// Physics steps at a fixed rate (see `physics`). A press moves the player on
+// the first step after it was read, which is the same frame unless the display
+// runs faster than the physics and no step falls on that frame.
+
+use std::collections::VecDeque;
+
+use bevy::core::FrameCount;
+use bevy::prelude::*;
+use crate::prelude::*;
+use rustbevy_core::config::{WINDOW_HEIGHT, WINDOW_WIDTH};
+
+use crate::chat::chat_closed;
+
+pub struct LatencyPlugin;
+
+impl Plugin for LatencyPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LatencyProbe>()
+            .add_systems(Update, (
+                toggle_latency_probe.run_if(chat_closed),
+                measure_input_latency
+                    .run_if(in_state(AppState::Playing).and_then(resource_equals(GameMode::Classic))),
+                update_latency_overlay,
+            ).chain());
+    }
+}
+
+// Presses averaged in the overlay
+const SAMPLE_COUNT: usize = 30;
+// A press that hasn't moved the player by then (against a wall, ...) is dropped
+const GIVE_UP_FRAMES: u32 = 30;
+// How long each flash stays up, so it shows on camera
+const FLASH_FRAMES: u32 = 4;
+const FLASH_SIZE: f32 = 40.0;
+
+#[derive(Clone, Copy, PartialEq)]
+enum PressKind {
+    Left,
+    Right,
+    Jump,
+}
+
+impl PressKind {
+    // Whether the player moved the way this press asks for
+    fn moved(self, from: Vec2, to: Vec2) -> bool {
+        match self {
+            Self::Left => to.x < from.x,
+            Self::Right => to.x > from.x,
+            Self::Jump => to.y > from.y,
+        }
+    }
+}
+
+// A press still waiting for the player to move
+struct PendingPress {
+    kind: PressKind,
+    frame: u32,
+    // Where the player was before the press was read
+    from: Vec2,
+}
+
+#[derive(Resource, Default)]
+pub struct LatencyProbe {
+    enabled: bool,
+    pending: Option<PendingPress>,
+    // Player position at the end of the last frame
+    last_position: Option<Vec2>,
+    samples: VecDeque<u32>,
+    // Frames the press and movement flashes have left
+    press_flash: u32,
+    move_flash: u32,
+}
+
+impl LatencyProbe {
+    fn overlay_text(&self) -> String {
+        let Some(last) = self.samples.back() else {
+            return "Input latency: press a move or jump key".to_string();
+        };
+        let average = self.samples.iter().sum::<u32>() as f32 / self.samples.len() as f32;
+        let worst = self.samples.iter().max().copied().unwrap_or_default();
+        format!(
+            "Input latency: last {} frames, avg {:.1}, worst {} ({} presses)",
+            last,
+            average,
+            worst,
+            self.samples.len()
+        )
+    }
+}
+
+#[derive(Component)]
+struct LatencyOverlayText;
+
+#[derive(Component)]
+enum LatencyFlash {
+    Press,
+    Move,
+}
+
+fn toggle_latency_probe(keyboard_input: Res<ButtonInput<KeyCode>>, mut probe: ResMut<LatencyProbe>) {
+    if keyboard_input.just_pressed(KeyCode::F9) {
+        *probe = LatencyProbe { enabled: !probe.enabled, ..default() };
+    }
+}
+
+fn measure_input_latency(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    frame_count: Res<FrameCount>,
+    mut probe: ResMut<LatencyProbe>,
+    player_query: Query<(&Transform, &PlayerControls), With<LeadPlayer>>,
+) {
+    if !probe.enabled {
+        return;
+    }
+    let Ok((transform, controls)) = player_query.get_single() else {
+        probe.last_position = None;
+        return;
+    };
+    let position = transform.translation.truncate();
+    let last_position = probe.last_position.replace(position);
+
+    let pressed = if PlayerControls::any_just_pressed(&keyboard_input, &controls.jump) {
+        Some(PressKind::Jump)
+    } else if PlayerControls::any_just_pressed(&keyboard_input, &controls.left) {
+        Some(PressKind::Left)
+    } else if PlayerControls::any_just_pressed(&keyboard_input, &controls.right) {
+        Some(PressKind::Right)
+    } else {
+        None
+    };
+    if let (Some(kind), Some(from)) = (pressed, last_position) {
+        probe.pending = Some(PendingPress { kind, frame: frame_count.0, from });
+        probe.press_flash = FLASH_FRAMES;
+    }
+
+    let Some(pending) = probe.pending.as_ref() else {
+        return;
+    };
+    let waited = frame_count.0.wrapping_sub(pending.frame);
+    if pending.kind.moved(pending.from, position) {
+        probe.pending = None;
+        probe.move_flash = FLASH_FRAMES;
+        if probe.samples.len() == SAMPLE_COUNT {
+            probe.samples.pop_front();
+        }
+        probe.samples.push_back(waited);
+    } else if waited > GIVE_UP_FRAMES {
+        probe.pending = None;
+    }
+}
+
+fn spawn_flash(commands: &mut Commands, flash: LatencyFlash, x: f32, color: Color) {
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color,
+                custom_size: Some(Vec2::splat(FLASH_SIZE)),
+                ..default()
+            },
+            transform: Transform::from_translation(Vec3::new(x, -WINDOW_HEIGHT / 2.0 + 40.0, 15.0)),
+            visibility: Visibility::Hidden,
+            ..default()
+        },
+        flash,
+        ScreenSpace,
+    ));
+}
+
+fn update_latency_overlay(
+    mut commands: Commands,
+    mut probe: ResMut<LatencyProbe>,
+    mut text_query: Query<(Entity, &mut Text), With<LatencyOverlayText>>,
+    mut flash_query: Query<(Entity, &LatencyFlash, &mut Visibility)>,
+) {
+    if !probe.enabled {
+        for entity in text_query.iter().map(|(entity, _)| entity).chain(flash_query.iter().map(|(entity, ..)| entity)) {
+            commands.entity(entity).despawn();
+        }
+        return;
+    }
+
+    let value = probe.overlay_text();
+    match text_query.get_single_mut() {
+        Ok((_, mut text)) => {
+            if text.sections[0].value != value {
+                text.sections[0].value = value;
+            }
+        }
+        Err(_) => {
+            commands.spawn((
+                Text2dBundle {
+                    text: Text::from_section(
+                        value,
+                        TextStyle {
+                            font_size: 18.0,
+                            color: Color::srgb(0.6, 1.0, 0.6), // Pale green
+                            ..default()
+                        },
+                    ),
+                    transform: Transform::from_translation(Vec3::new(WINDOW_WIDTH / 2.0 - 260.0, -WINDOW_HEIGHT / 2.0 + 90.0, 12.0)),
+                    ..default()
+                },
+                LatencyOverlayText,
+            ));
+            spawn_flash(&mut commands, LatencyFlash::Press, WINDOW_WIDTH / 2.0 - 90.0, Color::WHITE);
+            spawn_flash(&mut commands, LatencyFlash::Move, WINDOW_WIDTH / 2.0 - 40.0, Color::srgb(0.0, 1.0, 0.0));
+        }
+    }
+
+    for (_, flash, mut visibility) in flash_query.iter_mut() {
+        let frames_left = match flash {
+            LatencyFlash::Press => probe.press_flash,
+            LatencyFlash::Move => probe.move_flash,
+        };
+        *visibility = if frames_left > 0 { Visibility::Inherited } else { Visibility::Hidden };
+    }
+    probe.press_flash = probe.press_flash.saturating_sub(1);
+    probe.move_flash = probe.move_flash.saturating_sub(1);
+}
@@ -0,0 +1,574 @@
+// Hand-made Classic levels.
+//
+// Every `assets/levels/level_N.ron` is loaded at startup and replaces the
+// generated layout of level N in the authored campaign; levels without a file
+// are generated as usual. `assets/community_levels` is a second folder of the
+// same files, for a level pack from someone else (see `campaign` for picking
+// which set is played). Runs start at level 1; `level_0.ron` is the tutorial
+// (see `tutorial`).
+// A level file lists the platforms (the first one is where the run starts),
+// the fruit's centre, the player's spawn point, the platforms enemies start
+// on, by index, and the platforms that move, with their paths. A platform may
+// name its surface (stone, wood, metal or ice); it is stone otherwise, and
+// may be a ramp with a `slope` (see `PlatformSpec::slope`), flat otherwise. Enemy
+// zones, if listed, are the only areas enemies may start in, and enemies too
+// close to the spawn point or a checkpoint are left out (see
+// `rustbevy_core::zones`). Ladders and ropes run straight up at `x` from the
+// `bottom` height to the `top` one, normally the tops of the two platforms they
+// join. Hazards are spikes or lava (see `rustbevy_core::hazard`), placed by
+// their centre. Portals come in linked pairs, `a` and `b` by their centres,
+// with the player's velocity turned `quarter_turns` quarter turns
+// anticlockwise on the way from `a` to `b` (see `rustbevy_core::portal`).
+// An `exit` door, standing with the middle of its bottom edge at `door`,
+// finishes the level in place of the fruit once every one of its `keys` (by
+// their centres, resting like fruit) has been picked up (see
+// `rustbevy_core::exit`).
+// A `theme` (grass, cave, ice or night) keeps the level to that one instead of
+// the one its number would get (see `rustbevy_core::theme`).
+// The level editor (see `editor`) writes these files too:
+//
+// (
+//     version: 5,
+//     player_spawn: (0.0, 200.0),
+//     theme: "ice",
+//     platforms: [(x: 0.0, y: 100.0, width: 200.0, height: 20.0), (x: 220.0, y: 160.0, width: 150.0, height: 20.0, surface: "ice"), (x: 420.0, y: 200.0, width: 120.0, height: 20.0, slope: 0.25), ...],
+//     fruit: Some((300.0, 42.5)),
+//     enemies: [2],
+//     enemy_zones: [(x: 600.0, y: 0.0, width: 800.0, height: 600.0)],
+//     moving: [
+//         (platform: 3, path: Sine(horizontal: true, amplitude: 80.0, period: 3.0)),
+//         (platform: 4, path: Waypoints(points: [(0.0, 120.0), (150.0, 120.0)], speed: 60.0)),
+//     ],
+//     ladders: [(x: 40.0, bottom: 110.0, top: 290.0), (x: 260.0, bottom: 170.0, top: 330.0, rope: true)],
+//     hazards: [(kind: "spikes", x: 270.0, y: 177.0, width: 50.0, height: 14.0), (kind: "lava", x: 500.0, y: -340.0, width: 400.0, height: 40.0)],
+//     portals: [(a: (40.0, 150.0), b: (420.0, 250.0)), (a: (220.0, 210.0), b: (600.0, 90.0), quarter_turns: 2)],
+//     exit: (door: (300.0, 170.0), keys: [(40.0, 122.5)]),
+// )
+//
+// Files carry the format `version` they were written in. New fields always
+// come with a default, so an older file loads as it is and gets the defaults
+// for whatever it predates; anything needing more than that goes in
+// `upgrade_level_file`. A file from a newer version still loads, but what this
+// version doesn't know (fields, hazard kinds, surfaces) is left out, and the
+// player is told through the error toasts rather than finding out in play.
+//
+// `--validate-levels` checks every file in the folder (see
+// `rustbevy_core::validation`) and prints what's wrong with each, without
+// starting the game.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::PathBuf;
+
+use bevy::prelude::*;
+use crate::prelude::*;
+#[cfg(not(target_arch = "wasm32"))]
+use bevy::asset::io::file::FileAssetReader;
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, AsyncReadExt, LoadContext, LoadedFolder};
+use rustbevy_core::config::{PLAYER_SIZE, PLAYER_SPAWN};
+use rustbevy_core::exit::ExitDoor;
+use rustbevy_core::generation::PlatformSpec;
+use rustbevy_core::hazard::{HazardKind, HazardTile};
+use rustbevy_core::ladder::Ladder;
+use rustbevy_core::level::Level;
+use rustbevy_core::moving::PlatformPath;
+use rustbevy_core::physics::Tuning;
+use rustbevy_core::portal::PortalPair;
+use rustbevy_core::reachability::JumpProfile;
+use rustbevy_core::surface::Surface;
+use rustbevy_core::theme::LevelTheme;
+use rustbevy_core::validation::validate_level;
+use rustbevy_core::zones::Zone;
+use ron::extensions::Extensions;
+use serde::{Deserialize, Serialize};
+
+use crate::loading::LoadingAssets;
+use crate::tutorial::TUTORIAL_LEVEL;
+
+const ASSET_FOLDER: &str = "assets";
+const LEVEL_FOLDER: &str = "levels";
+const COMMUNITY_FOLDER: &str = "community_levels";
+// The format version written by this build. Versions so far:
+// 1. Everything up to the hazards; files from before versions were written
+// 2. The `version` field itself
+// 3. The `portals` field
+// 4. The `theme` field
+// 5. The `exit` field
+pub const LEVEL_FORMAT_VERSION: u32 = 5;
+// Every field a level file can have, to tell which ones a newer file added
+const LEVEL_FIELDS: [&str; 13] =
+    ["version", "player_spawn", "theme", "platforms", "fruit", "exit", "enemies", "enemy_zones", "checkpoints", "moving", "ladders", "hazards", "portals"];
+
+pub struct LevelLoaderPlugin;
+
+impl Plugin for LevelLoaderPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<LevelFile>()
+            .init_asset_loader::<LevelFileLoader>()
+            .init_resource::<HandmadeLevels>()
+            .init_resource::<CommunityLevels>()
+            .add_systems(Startup, load_level_files)
+            .add_systems(Update, collect_level_files);
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct PlatformFile {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub surface: Option<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub one_way: bool,
+    #[serde(default, skip_serializing_if = "is_flat")]
+    pub slope: f32,
+}
+
+fn is_flat(slope: &f32) -> bool {
+    *slope == 0.0
+}
+
+impl PlatformFile {
+    pub fn spec(&self) -> PlatformSpec {
+        PlatformSpec { x: self.x, y: self.y, width: self.width, height: self.height, one_way: self.one_way, slope: self.slope }
+    }
+}
+
+// Mirrors `PlatformPath`; waypoints are offsets from where the platform is placed
+#[derive(Serialize, Deserialize, Clone)]
+pub enum PathFile {
+    Sine { horizontal: bool, amplitude: f32, period: f32 },
+    Waypoints { points: Vec<(f32, f32)>, speed: f32 },
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct MovingFile {
+    pub platform: usize,
+    pub path: PathFile,
+}
+
+// Mirrors `Ladder`
+#[derive(Serialize, Deserialize, Clone)]
+pub struct LadderFile {
+    pub x: f32,
+    pub bottom: f32,
+    pub top: f32,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub rope: bool,
+}
+
+// Mirrors `HazardTile`, with the kind by name
+#[derive(Serialize, Deserialize, Clone)]
+pub struct HazardFile {
+    pub kind: String,
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+// Mirrors `PortalPair`
+#[derive(Serialize, Deserialize, Clone)]
+pub struct PortalFile {
+    pub a: (f32, f32),
+    pub b: (f32, f32),
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub quarter_turns: u8,
+}
+
+fn is_zero(turns: &u8) -> bool {
+    *turns == 0
+}
+
+// Mirrors `ExitDoor`
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ExitFile {
+    pub door: (f32, f32),
+    #[serde(default)]
+    pub keys: Vec<(f32, f32)>,
+}
+
+// Mirrors `Zone`
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ZoneFile {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+#[derive(Asset, TypePath, Serialize, Deserialize, Clone)]
+pub struct LevelFile {
+    #[serde(default = "unversioned")]
+    pub version: u32,
+    #[serde(default = "default_spawn")]
+    pub player_spawn: (f32, f32),
+    // The level's theme by name; the one its number gets when there's none
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub theme: Option<String>,
+    pub platforms: Vec<PlatformFile>,
+    #[serde(default)]
+    pub fruit: Option<(f32, f32)>,
+    // The door that finishes the level in the fruit's place
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exit: Option<ExitFile>,
+    #[serde(default)]
+    pub enemies: Vec<usize>,
+    // Where enemies may start; anywhere when there are none
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub enemy_zones: Vec<ZoneFile>,
+    // Platforms with a checkpoint flag on them
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub checkpoints: Vec<usize>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub moving: Vec<MovingFile>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ladders: Vec<LadderFile>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub hazards: Vec<HazardFile>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub portals: Vec<PortalFile>,
+    // What reading the file had to work around, for the player to hear about
+    #[serde(skip)]
+    pub warnings: Vec<String>,
+}
+
+// Files from before versions were written
+fn unversioned() -> u32 {
+    1
+}
+
+fn default_spawn() -> (f32, f32) {
+    PLAYER_SPAWN
+}
+
+#[derive(Debug)]
+pub enum LevelFileError {
+    Io(std::io::Error),
+    Format(ron::error::SpannedError),
+}
+
+impl fmt::Display for LevelFileError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LevelFileError::Io(error) => write!(f, "could not read level file: {error}"),
+            LevelFileError::Format(error) => write!(f, "bad level file: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for LevelFileError {}
+
+// Optional fields can be written without the `Some(...)`, as in
+// `surface: "ice"`. The file comes back upgraded to this version's format.
+pub fn parse_level_file(bytes: &[u8]) -> Result<LevelFile, LevelFileError> {
+    let options = ron::Options::default().with_default_extension(Extensions::IMPLICIT_SOME);
+    let mut file: LevelFile = options.from_bytes(bytes).map_err(LevelFileError::Format)?;
+    // Read again loosely, only to see which fields it has
+    if let Ok(ron::Value::Map(fields)) = options.from_bytes::<ron::Value>(bytes) {
+        let unknown: Vec<String> = fields
+            .keys()
+            .filter_map(|key| match key {
+                ron::Value::String(name) if !LEVEL_FIELDS.contains(&name.as_str()) => Some(name.clone()),
+                _ => None,
+            })
+            .collect();
+        if !unknown.is_empty() {
+            file.warnings.push(format!("left out what this version doesn't know: {}", unknown.join(", ")));
+        }
+    }
+    upgrade_level_file(&mut file);
+    Ok(file)
+}
+
+// Brings a file written in an older format version up to this one
+fn upgrade_level_file(file: &mut LevelFile) {
+    if file.version > LEVEL_FORMAT_VERSION {
+        file.warnings.push(format!("made for a newer version of the game (format {}, this one reads {})", file.version, LEVEL_FORMAT_VERSION));
+        return;
+    }
+    // Versions 2 to 5 only added fields, so older files need nothing past
+    // their defaults; changes that do are made here, oldest first
+    file.version = LEVEL_FORMAT_VERSION;
+}
+
+#[derive(Default)]
+struct LevelFileLoader;
+
+impl AssetLoader for LevelFileLoader {
+    type Asset = LevelFile;
+    type Settings = ();
+    type Error = LevelFileError;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext<'_>,
+    ) -> Result<LevelFile, LevelFileError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await.map_err(LevelFileError::Io)?;
+        parse_level_file(&bytes)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["ron"]
+    }
+}
+
+// A loaded level file, ready to spawn
+#[derive(Clone)]
+pub struct HandmadeLevel {
+    pub level: Level,
+    pub player_spawn: Vec2,
+    // The file's own theme, if it names one
+    pub theme: Option<LevelTheme>,
+}
+
+#[derive(Resource, Default)]
+pub struct HandmadeLevels {
+    folder: Handle<LoadedFolder>,
+    levels: HashMap<u32, HandmadeLevel>,
+}
+
+impl HandmadeLevels {
+    pub fn get(&self, level: u32) -> Option<&HandmadeLevel> {
+        self.levels.get(&level)
+    }
+
+    // How many levels a run can reach, so leaving out the tutorial
+    pub fn len(&self) -> usize {
+        self.levels.keys().filter(|&&number| number != TUTORIAL_LEVEL).count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // Plays `file` as level `number` from now on, without waiting for the
+    // file to be loaded again
+    pub fn set(&mut self, number: u32, file: &LevelFile) {
+        self.levels.insert(number, handmade_level(file).0);
+    }
+
+    // Fills the table from the files in its folder, passing on their warnings
+    fn collect(&mut self, folders: &Assets<LoadedFolder>, level_files: &Assets<LevelFile>, errors: &mut EventWriter<RuntimeErrorEvent>) {
+        let Some(folder) = folders.get(&self.folder) else {
+            return;
+        };
+        let mut levels = HashMap::new();
+        for handle in &folder.handles {
+            let Ok(handle) = handle.clone().try_typed::<LevelFile>() else {
+                continue;
+            };
+            let number = handle.path().and_then(|path| path.path().file_name()?.to_str().and_then(level_number));
+            let (Some(number), Some(file)) = (number, level_files.get(&handle)) else {
+                continue;
+            };
+            let (handmade, warnings) = handmade_level(file);
+            for warning in &warnings {
+                errors.send(level_file_warning(number, warning));
+            }
+            levels.insert(number, handmade);
+        }
+        self.levels = levels;
+    }
+}
+
+// The community level pack, the same kind of table from its own folder
+#[derive(Resource, Default)]
+pub struct CommunityLevels(pub HandmadeLevels);
+
+// `level_12.ron` is level 12
+fn level_number(file_name: &str) -> Option<u32> {
+    file_name.strip_prefix("level_")?.strip_suffix(".ron")?.parse().ok()
+}
+
+// Where a folder of level files is on disk
+#[cfg(not(target_arch = "wasm32"))]
+fn level_folder(folder: &str) -> PathBuf {
+    FileAssetReader::get_base_path().join(ASSET_FOLDER).join(folder)
+}
+
+// A browser has no disk to look at; reading or writing here fails and says so
+#[cfg(target_arch = "wasm32")]
+fn level_folder(folder: &str) -> PathBuf {
+    PathBuf::from(ASSET_FOLDER).join(folder)
+}
+
+// Where level `number`'s file is on disk
+pub fn level_file_path(number: u32) -> PathBuf {
+    level_folder(LEVEL_FOLDER).join(format!("level_{}.ron", number))
+}
+
+// The level in `file`, with everything its file's warnings and the names
+// this version doesn't know had to leave out
+fn handmade_level(file: &LevelFile) -> (HandmadeLevel, Vec<String>) {
+    let mut warnings = file.warnings.clone();
+    let platforms = file.platforms.iter().map(PlatformFile::spec).collect();
+    let surfaces = file
+        .platforms
+        .iter()
+        .map(|platform| {
+            let name = platform.surface.as_deref().unwrap_or(Surface::Stone.name());
+            Surface::from_name(name).unwrap_or_else(|| {
+                warnings.push(format!("unknown surface {:?}, using stone", name));
+                Surface::Stone
+            })
+        })
+        .collect();
+    let moving = file
+        .moving
+        .iter()
+        .map(|moving| {
+            let path = match moving.path.clone() {
+                PathFile::Sine { horizontal, amplitude, period } => PlatformPath::Sine { horizontal, amplitude, period },
+                PathFile::Waypoints { points, speed } => PlatformPath::Waypoints { points, speed },
+            };
+            (moving.platform, path)
+        })
+        .collect();
+    let enemy_zones = file.enemy_zones.iter().map(|zone| Zone { x: zone.x, y: zone.y, width: zone.width, height: zone.height }).collect();
+    let ladders = file.ladders.iter().map(|ladder| Ladder { x: ladder.x, bottom: ladder.bottom, top: ladder.top, rope: ladder.rope }).collect();
+    let hazards = file
+        .hazards
+        .iter()
+        .filter_map(|hazard| {
+            let Some(kind) = HazardKind::from_name(&hazard.kind) else {
+                warnings.push(format!("unknown hazard {:?}, leaving it out", hazard.kind));
+                return None;
+            };
+            Some(HazardTile { kind, x: hazard.x, y: hazard.y, width: hazard.width, height: hazard.height })
+        })
+        .collect();
+    let portals = file.portals.iter().map(|portal| PortalPair { a: portal.a, b: portal.b, quarter_turns: portal.quarter_turns }).collect();
+    let exit = file.exit.as_ref().map(|exit| ExitDoor { door: exit.door, keys: exit.keys.clone() });
+    if exit.is_some() && file.fruit.is_some() {
+        warnings.push("has both a fruit and an exit door; the door takes the fruit's place".to_string());
+    }
+    let level = Level::handmade(platforms, surfaces, file.fruit, exit, file.player_spawn, &file.enemies, enemy_zones, &file.checkpoints, moving, ladders, hazards, portals);
+    let left_out = file.enemies.len() - level.enemies.len();
+    if left_out > 0 {
+        warnings.push(format!("left out {} enemies outside the enemy zones or too close to a respawn point", left_out));
+    }
+    if level.portals.len() < file.portals.len() {
+        warnings.push(format!("left out {} portal pairs too close to a respawn point", file.portals.len() - level.portals.len()));
+    }
+    let theme = file.theme.as_deref().and_then(|name| {
+        let theme = LevelTheme::from_name(name);
+        if theme.is_none() {
+            warnings.push(format!("unknown theme {:?}, using the usual one", name));
+        }
+        theme
+    });
+    let handmade = HandmadeLevel {
+        level,
+        player_spawn: Vec2::new(file.player_spawn.0, file.player_spawn.1),
+        theme,
+    };
+    (handmade, warnings)
+}
+
+// A warning about level `number`'s file, for the error toasts
+pub fn level_file_warning(number: u32, warning: &str) -> RuntimeErrorEvent {
+    RuntimeErrorEvent::new(format!("Level {} may not play as made", number), format!("level_{}.ron: {}", number, warning))
+}
+
+fn load_level_files(
+    asset_server: Res<AssetServer>,
+    mut handmade_levels: ResMut<HandmadeLevels>,
+    mut community_levels: ResMut<CommunityLevels>,
+    mut loading: ResMut<LoadingAssets>,
+) {
+    handmade_levels.folder = asset_server.load_folder(LEVEL_FOLDER);
+    community_levels.0.folder = asset_server.load_folder(COMMUNITY_FOLDER);
+    loading.track(handmade_levels.folder.clone());
+    loading.track(community_levels.0.folder.clone());
+}
+
+// Rebuilds the level tables whenever a folder or one of its files (re)loads
+fn collect_level_files(
+    mut folder_events: EventReader<AssetEvent<LoadedFolder>>,
+    mut level_events: EventReader<AssetEvent<LevelFile>>,
+    folders: Res<Assets<LoadedFolder>>,
+    level_files: Res<Assets<LevelFile>>,
+    mut handmade_levels: ResMut<HandmadeLevels>,
+    mut community_levels: ResMut<CommunityLevels>,
+    mut errors: EventWriter<RuntimeErrorEvent>,
+) {
+    let folder_changed = folder_events.read().count() > 0;
+    let files_changed = level_events.read().count() > 0;
+    if !folder_changed && !files_changed {
+        return;
+    }
+    handmade_levels.collect(&folders, &level_files, &mut errors);
+    community_levels.0.collect(&folders, &level_files, &mut errors);
+}
+
+// `--validate-levels` checks the level files instead of playing
+pub fn validate_levels_argument() -> bool {
+    std::env::args().skip(1).any(|arg| arg == "--validate-levels")
+}
+
+// Reads every level file on disk, the community pack's too, and prints the
+// issues found in each, with where in the level they are. Returns whether
+// every file is free of them.
+pub fn validate_level_files() -> bool {
+    let authored = validate_folder(LEVEL_FOLDER);
+    let community = validate_folder(COMMUNITY_FOLDER);
+    authored && community
+}
+
+fn validate_folder(folder: &str) -> bool {
+    let folder = level_folder(folder);
+    println!("{}:", folder.display());
+    let entries = match std::fs::read_dir(&folder) {
+        Ok(entries) => entries,
+        Err(error) => {
+            println!("Could not read {}: {}", folder.display(), error);
+            return false;
+        }
+    };
+    let mut files: Vec<(u32, PathBuf)> = Vec::new();
+    for path in entries.filter_map(|entry| entry.ok().map(|entry| entry.path())) {
+        let name = path.file_name().and_then(|name| name.to_str()).unwrap_or_default();
+        match level_number(name) {
+            Some(number) => files.push((number, path)),
+            None => println!("{}: not named level_N.ron, so the game doesn't load it", name),
+        }
+    }
+    files.sort();
+
+    let profile = JumpProfile::new(&Tuning::default(), PLAYER_SIZE);
+    let mut clean = true;
+    for (_, path) in &files {
+        let name = path.file_name().and_then(|name| name.to_str()).unwrap_or_default();
+        let file: LevelFile = match std::fs::read(path).map_err(LevelFileError::Io).and_then(|bytes| parse_level_file(&bytes)) {
+            Ok(file) => file,
+            Err(error) => {
+                println!("{}: {}", name, error);
+                clean = false;
+                continue;
+            }
+        };
+        let (handmade, warnings) = handmade_level(&file);
+        for warning in &warnings {
+            println!("{}: warning: {}", name, warning);
+        }
+        let issues = validate_level(&handmade.level, file.player_spawn, &profile);
+        if issues.is_empty() {
+            println!("{}: ok", name);
+            continue;
+        }
+        clean = false;
+        println!("{}: {} issue{}", name, issues.len(), if issues.len() == 1 { "" } else { "s" });
+        for issue in issues {
+            println!("  ({:.0}, {:.0}): {}", issue.position.0, issue.position.1, issue.problem);
+        }
+    }
+    println!("{} level file{} checked", files.len(), if files.len() == 1 { "" } else { "s" });
+    clean
+}
@@ -0,0 +1,132 @@
+// Level skip assist for Classic runs.
+//
+// F10 on the main menu turns it on. Each level keeps a save state of the run
+// as it was when the level started. After losing LEVEL_SKIP_FAILED_ATTEMPTS
+// lives on one level, K skips it: the save state is loaded back (so the lives
+// lost trying come back) and the level is finished through the usual
+// `LevelCompletedEvent`, so the next level, drafts and run stats carry on as if
+// the fruit had been collected, just without its points. The game over screen
+// shows how many levels were skipped.
+
+use bevy::prelude::*;
+use crate::prelude::*;
+use rustbevy_core::assists::LEVEL_SKIP_FAILED_ATTEMPTS;
+
+use crate::chat::chat_closed;
+use crate::fruit::build_next_level;
+use crate::ui::{despawn_screen, Toasts};
+
+pub struct LevelSkipPlugin;
+
+impl Plugin for LevelSkipPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LevelSave>()
+            .add_systems(OnEnter(AppState::GameOver), show_skipped_levels)
+            .add_systems(OnExit(AppState::GameOver), despawn_screen::<SkippedLevelsUI>)
+            .add_systems(Update, (
+                save_level_state,
+                count_failed_attempts,
+                skip_level.run_if(chat_closed).before(build_next_level),
+            ).chain().run_if(in_state(AppState::Playing).and_then(resource_equals(GameMode::Classic))));
+    }
+}
+
+// The run as it was when the current level started
+#[derive(Resource, Default)]
+pub struct LevelSave {
+    level: u32,
+    lives: u32,
+    // Lives lost on this level so far
+    failed_attempts: u32,
+}
+
+impl LevelSave {
+    fn can_skip(&self) -> bool {
+        self.failed_attempts >= LEVEL_SKIP_FAILED_ATTEMPTS
+    }
+}
+
+#[derive(Component)]
+struct SkippedLevelsUI;
+
+fn save_level_state(
+    mut reset_events: EventReader<GameResetEvent>,
+    mut level_events: EventReader<LevelCompletedEvent>,
+    game_state: Res<GameState>,
+    mut save: ResMut<LevelSave>,
+) {
+    let started = reset_events.read().count() > 0;
+    let cleared = level_events.read().count() > 0;
+    if started || cleared {
+        *save = LevelSave { level: game_state.level, lives: game_state.lives, failed_attempts: 0 };
+    }
+}
+
+fn count_failed_attempts(
+    mut death_events: EventReader<PlayerDiedEvent>,
+    assist_mode: Res<AssistMode>,
+    mut save: ResMut<LevelSave>,
+    mut toasts: ResMut<Toasts>,
+) {
+    for event in death_events.read() {
+        save.failed_attempts += 1;
+        if assist_mode.0.level_skip && event.lives_left > 0 && save.failed_attempts == LEVEL_SKIP_FAILED_ATTEMPTS {
+            toasts.push("Stuck? Press K to skip this level".to_string());
+        }
+    }
+}
+
+fn skip_level(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    assist_mode: Res<AssistMode>,
+    mut save: ResMut<LevelSave>,
+    mut game_state: ResMut<GameState>,
+    mut difficulty: ResMut<Difficulty>,
+    fruit_query: Query<Entity, With<Fruit>>,
+    mut level_events: EventWriter<LevelCompletedEvent>,
+    mut toasts: ResMut<Toasts>,
+) {
+    if !assist_mode.0.level_skip || !save.can_skip() || !keyboard_input.just_pressed(KeyCode::KeyK) {
+        return;
+    }
+    // Nothing to skip past until the next level is built
+    if game_state.level != save.level {
+        return;
+    }
+
+    // Load the save state, then finish the level without its fruit
+    game_state.lives = save.lives;
+    game_state.combo.reset();
+    game_state.stats.levels_skipped += 1;
+    for entity in fruit_query.iter() {
+        commands.entity(entity).despawn();
+    }
+    game_state.level += 1;
+    level_events.send(LevelCompletedEvent { level: game_state.level, skipped: true });
+    difficulty.0.record_clear();
+    save.failed_attempts = 0;
+    toasts.push(format!("Level {} skipped", save.level));
+}
+
+fn show_skipped_levels(mut commands: Commands, game_state: Res<GameState>) {
+    let skipped = game_state.stats.levels_skipped;
+    if skipped == 0 {
+        return;
+    }
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                format!("Levels skipped: {}", skipped),
+                TextStyle {
+                    font_size: 22.0,
+                    color: Color::srgb(1.0, 0.7, 0.4), // Light orange
+                    ..default()
+                },
+            ),
+            transform: Transform::from_translation(Vec3::new(0.0, -125.0, 10.0)),
+            ..default()
+        },
+        SkippedLevelsUI,
+    ));
+}
@@ -0,0 +1,255 @@
+// Lint policy: Bevy systems take their resources and queries as arguments and
+// spell out their query types, so long argument lists and nested types are how
+// the game is written rather than a smell. These two are allowed crate-wide
+// instead of on each system.
+#![allow(clippy::too_many_arguments, clippy::type_complexity)]
+
+//! The game itself, and the Bevy-side types shared with extension plugins.
+//!
+//! The rendering-free rules live in `rustbevy_core`; this crate holds the
+//! game's plugins (`GamePlugins` has them all, which `main()` adds after
+//! Bevy's `DefaultPlugins`) and the components, events and resources the game's
+//! systems work with, so another plugin can add content without touching the
+//! game's internals. Register your plugin next to the built-in ones in `main()`
+//! with `.add_plugins(...)`.
+//!
+//! Extension points:
+//! - Events to listen to: `GameResetEvent` (a new run starts),
+//!   `FruitCollectedEvent` (which fruit, where), `LevelCompletedEvent` (new
+//!   level reached), `PlayerDiedEvent` (fell out of the world, lives left),
+//!   `PlatformContactEvent` (which platform a player stands on, every physics
+//!   step) and `PlaySoundEvent` (which can also be sent to play a game sound).
+//! - Events to send: `RuntimeErrorEvent` when something fails that the game
+//!   can carry on without; the player gets a toast and the details are logged.
+//!   `SpawnParticles` asks for one of the game's particle effects (fruit burst,
+//!   landing dust, death explosion, confetti) at a spot.
+//! - State to read: `AppState` (current screen, a Bevy `States`). Gameplay
+//!   systems only run while `AppState::Playing`; gate yours with
+//!   `run_if(in_state(AppState::Playing))`, or hook `OnEnter`/`OnExit`.
+//! - Resources to read: `GameState` (lives/continues/level), `GameMode` (Classic, the tower or a
+//!   party game) and `LevelLayout` (the level's platforms as generated, and
+//!   which one the player starts on; place things on it from here).
+//! - Resources to override: insert `GameAudio` before `Startup` to replace the
+//!   sound effects (audio packs are then left alone), or `DifficultyCurve` to
+//!   change how fast levels get harder.
+//! - Components to query: `Player`, `Platform`, `Fruit`, `Velocity`,
+//!   `Grounded`, `StandingOn` (the platform under the player, while there is
+//!   one), `Climbing` (the ladder the player is on, while they climb) and `AnimationState` (idle, run, jump or fall). Anything spawned with `Platform` is collided with by the player;
+//!   anything spawned with `Fruit` can be collected in Classic mode (on door levels, an open `ExitDoor` finishes the level instead, once every `Key` is picked up), and
+//!   `Pickup` says what a pickup is; players standing on a `ConveyorPlatform` are carried along it, ones inside a `WindZone` drift with it and ones walking into a `PortalEnd` come out at its twin; `PlayerStatus` holds the player's timed
+//!   effects (curses and power-ups). The
+//!   companion fetches anything spawned with `Fetchable` and squeaks near
+//!   anything spawned with `Hazard`; `HazardArea` (spikes, lava) also costs a life on contact. The music ducks while anything spawned with
+//!   `DucksMusic` (dialogue) is around. Effects spawned with `Transient` are
+//!   cleaned up once they expire or leave the camera's view, and ones spawned
+//!   with `Budgeted` count against that category's `EntityBudget` cap.
+//!
+//! Reusing the simulation: the plugins gameplay is made of are public, one per
+//! module (`game::GamePlugin`, `physics::PhysicsPlugin`, `platform::PlatformPlugin`,
+//! `fruit::FruitPlugin`, `enemy::EnemyPlugin` and the rest of what `headless`
+//! adds), for other binaries (an editor, a trainer, a benchmark) to build their
+//! own App from without the window, menus and HUD. So are the functions they
+//! spawn levels and step the player with (`platform::plan_classic_level`,
+//! `platform::spawn_planned_level`, `physics::player_body` and the like). The
+//! level format is in `level_loader` (hand-made level files) and
+//! `rustbevy_core::level`, and generation in `rustbevy_core::generation`; the
+//! core crate is re-exported as `rustbevy_core`. With the `headless` feature,
+//! `headless::headless_app()` puts the simulation together, with the
+//! resources the rest of the game would provide.
+
+mod animation;
+mod attract;
+mod budget;
+mod bug_report;
+mod camera;
+mod campaign;
+mod capture;
+mod categories;
+mod character_select;
+mod chat;
+pub mod checkpoint;
+mod companion;
+pub mod components;
+mod crash;
+mod culling;
+mod custom_game;
+mod daily;
+#[cfg(feature = "debug_tools")]
+mod debug;
+mod desync;
+pub mod dressing;
+mod editor;
+pub mod enemy;
+mod errors;
+pub mod events;
+pub mod exit;
+mod favorites;
+pub mod fruit;
+pub mod game;
+mod gamepad;
+#[cfg(feature = "headless")]
+pub mod headless;
+pub mod hazard;
+mod helper;
+mod hints;
+mod hub;
+mod hud_layout;
+mod jump_arc;
+mod keybindings;
+pub mod ladder;
+mod latency;
+pub mod level_loader;
+mod level_skip;
+mod lives;
+mod loading;
+mod lobby;
+mod menu;
+mod minimap;
+mod net;
+mod onboarding;
+mod overview;
+mod particles;
+mod party;
+mod pause;
+pub mod physics;
+pub mod platform;
+pub mod player;
+pub mod pool;
+pub mod portal;
+mod practice;
+pub mod prelude;
+pub mod profiling;
+mod progress;
+pub mod projectile;
+mod recap;
+pub mod replay;
+pub mod rescue;
+pub mod resources;
+mod rewind;
+mod score;
+#[cfg(feature = "debug_tools")]
+mod scrubber;
+mod session;
+mod settings;
+mod sky;
+mod sound;
+mod sound_indicators;
+pub mod spatial;
+mod speed_lines;
+pub mod speedrun;
+mod status_server;
+mod streamer;
+pub mod stress;
+mod summary;
+mod theme;
+mod tower;
+pub mod transition;
+pub mod trap;
+mod tutorial;
+mod ui;
+pub mod visual;
+pub mod wind;
+mod wobble;
+
+/// The core crate, so other binaries can reach level generation, the level
+/// format and the rest of the rules through this one
+pub use rustbevy_core;
+
+use bevy::app::PluginGroupBuilder;
+use bevy::prelude::*;
+
+/// Every plugin the game is made of, in the order they're added
+pub struct GamePlugins;
+
+impl PluginGroup for GamePlugins {
+    fn build(self) -> PluginGroupBuilder {
+        let group = PluginGroupBuilder::start::<Self>()
+            .add(game::GamePlugin)
+            .add(camera::CameraPlugin)
+            .add(sound::SoundPlugin)
+            .add(ui::UiPlugin)
+            .add(menu::MenuPlugin)
+            .add(physics::PhysicsPlugin)
+            .add(spatial::SpatialPlugin)
+            .add(player::PlayerPlugin)
+            .add(platform::PlatformPlugin)
+            .add(ladder::LadderPlugin)
+            .add(hazard::HazardPlugin)
+            .add(projectile::ProjectilePlugin)
+            .add(rewind::RewindPlugin)
+            .add(jump_arc::JumpArcPlugin)
+            .add(level_loader::LevelLoaderPlugin)
+            .add(campaign::CampaignPlugin)
+            .add(enemy::EnemyPlugin)
+            .add(fruit::FruitPlugin)
+            .add(rescue::FruitRescuePlugin)
+            .add(party::PartyPlugin)
+            .add(lobby::LobbyPlugin)
+            .add(chat::ChatPlugin)
+            .add(attract::AttractPlugin)
+            .add(favorites::FavoritesPlugin)
+            .add(hints::HintsPlugin)
+            .add(overview::OverviewPlugin)
+            .add(minimap::MinimapPlugin)
+            .add(practice::PracticePlugin)
+            .add(recap::RecapPlugin)
+            .add(companion::CompanionPlugin)
+            .add(particles::ParticlesPlugin)
+            .add(wobble::WobblePlugin)
+            .add(dressing::DressingPlugin)
+            .add(speed_lines::SpeedLinesPlugin)
+            .add(sound_indicators::SoundIndicatorsPlugin)
+            .add(score::ScorePlugin)
+            .add(hud_layout::HudLayoutPlugin)
+            .add(streamer::StreamerPlugin)
+            .add(stress::StressPlugin)
+            .add(status_server::StatusServerPlugin)
+            .add(latency::LatencyPlugin)
+            .add(settings::SettingsPlugin)
+            .add(custom_game::CustomGamePlugin)
+            .add(categories::CategoriesPlugin)
+            .add(level_skip::LevelSkipPlugin)
+            .add(lives::LivesPlugin)
+            .add(culling::CullingPlugin)
+            .add(animation::AnimationPlugin)
+            .add(budget::BudgetPlugin)
+            .add(pause::PausePlugin)
+            .add(session::SessionPlugin)
+            .add(keybindings::KeybindingsPlugin)
+            .add(loading::LoadingPlugin)
+            .add(gamepad::GamepadPlugin)
+            .add(errors::ErrorReportPlugin)
+            .add(checkpoint::CheckpointPlugin)
+            .add(exit::ExitPlugin)
+            .add(crash::CrashReportPlugin)
+            .add(onboarding::OnboardingPlugin)
+            .add(bug_report::BugReportPlugin)
+            .add(capture::CapturePlugin)
+            .add(desync::DesyncPlugin)
+            .add(sky::SkyPlugin)
+            .add(theme::ThemePlugin)
+            .add(progress::ProgressPlugin)
+            .add(helper::HelperPlugin)
+            .add(speedrun::SpeedrunPlugin)
+            .add(editor::EditorPlugin)
+            .add(trap::TrapPlugin)
+            .add(wind::WindPlugin)
+            .add(wind::WindVisualsPlugin)
+            .add(portal::PortalPlugin)
+            .add(portal::PortalVisualsPlugin)
+            .add(pool::PoolPlugin)
+            .add(profiling::ProfilingPlugin)
+            .add(hub::HubPlugin)
+            .add(character_select::CharacterSelectPlugin)
+            .add(daily::DailyPlugin)
+            .add(summary::SummaryPlugin)
+            .add(replay::ReplayPlugin)
+            .add(tower::TowerPlugin)
+            .add(transition::TransitionPlugin)
+            .add(tutorial::TutorialPlugin)
+            .add(visual::VisualTestPlugin);
+        #[cfg(feature = "debug_tools")]
+        let group = group.add(debug::DebugToolsPlugin).add(scrubber::ScrubberPlugin);
+        group
+    }
+}
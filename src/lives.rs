@@ -0,0 +1,141 @@
+// Starting lives and continues for Classic runs (see
+// `rustbevy_core::rules::GameRules`).
+//
+// I on the main menu picks how many lives a run starts with, Shift+I how
+// many continues it gets. When the lives run out with a continue left, C on
+// the game over screen spends it: the level the run ended on is built again
+// from the top and played with the starting lives back, but the score goes
+// back to zero. A continued run counts as one that didn't start from level 1
+// for the speedrun categories, and stops being recorded as a replay.
+
+use bevy::prelude::*;
+use crate::prelude::*;
+use rustbevy_core::rules::GameRules;
+
+use crate::campaign::CampaignLevels;
+use crate::character_select::PlayerProfile;
+use crate::chat::chat_closed;
+use crate::menu::MainMenuUI;
+use crate::physics::classic_jump_profile;
+use crate::platform::{spawn_classic_level, CurrentLevel};
+use crate::player::{spawn_coop_partner, spawn_player};
+use crate::pool::LevelTeardown;
+use crate::replay::ReplayRecorder;
+use crate::ui::Toasts;
+
+pub struct LivesPlugin;
+
+impl Plugin for LivesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(AppState::MainMenu), setup_rules_menu_line)
+            .add_systems(Update, (
+                change_rules.run_if(in_state(AppState::MainMenu).and_then(chat_closed)),
+                continue_run.run_if(in_state(AppState::GameOver).and_then(resource_equals(GameMode::Classic)).and_then(chat_closed)),
+            ));
+    }
+}
+
+#[derive(Component)]
+struct RulesMenuText;
+
+fn rules_menu_line(rules: GameRules) -> String {
+    format!(
+        "I: Lives {}   Shift+I: Continues {}{}",
+        rules.starting_lives,
+        rules.continues,
+        if rules.standard_lives() { "" } else { "   (high scores and replays need the usual lives)" }
+    )
+}
+
+fn setup_rules_menu_line(mut commands: Commands, rules_mode: Res<RulesMode>) {
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                rules_menu_line(rules_mode.0),
+                TextStyle {
+                    font_size: 20.0,
+                    color: Color::srgb(1.0, 0.5, 0.6), // Heart pink
+                    ..default()
+                },
+            ),
+            transform: Transform::from_translation(Vec3::new(0.0, 210.0, 10.0)),
+            ..default()
+        },
+        MainMenuUI,
+        RulesMenuText,
+    ));
+}
+
+fn change_rules(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut rules_mode: ResMut<RulesMode>,
+    mut text_query: Query<&mut Text, With<RulesMenuText>>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::KeyI) {
+        return;
+    }
+    rules_mode.0 = if keyboard_input.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) {
+        rules_mode.0.more_continues()
+    } else {
+        rules_mode.0.more_lives()
+    };
+    if let Ok(mut text) = text_query.get_single_mut() {
+        text.sections[0].value = rules_menu_line(rules_mode.0);
+    }
+}
+
+// Spends a continue: builds the level the run ended on again, the way
+// restarting it from the pause menu does, and puts the player (both co-op
+// players) back at its start
+fn continue_run(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut game_state: ResMut<GameState>,
+    rules_mode: Res<RulesMode>,
+    mut teardown: LevelTeardown,
+    current_level: Res<CurrentLevel>,
+    curve: Res<DifficultyCurve>,
+    run_modifiers: Res<RunModifiers>,
+    assist_mode: Res<AssistMode>,
+    mutator_mode: Res<MutatorMode>,
+    coop_mode: Res<CoopMode>,
+    player_profile: Res<PlayerProfile>,
+    campaign_levels: CampaignLevels,
+    mut recorder: ResMut<ReplayRecorder>,
+    mut toasts: ResMut<Toasts>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    if game_state.continues == 0 || !keyboard_input.just_pressed(KeyCode::KeyC) {
+        return;
+    }
+    teardown.clear_all(&mut commands);
+
+    let player_size = mutator_mode.0.player_size();
+    let profile = classic_jump_profile(&run_modifiers, &assist_mode, &player_profile, player_size);
+    let player_spawn = spawn_classic_level(
+        &mut commands,
+        &mut teardown.pool,
+        campaign_levels.provider(),
+        current_level.run_seed,
+        current_level.level,
+        current_level.difficulty,
+        profile,
+        &curve.0,
+    );
+    spawn_player(&mut commands, player_size, player_spawn);
+    if coop_mode.0 {
+        spawn_coop_partner(&mut commands, player_size, player_spawn);
+    }
+
+    game_state.continues -= 1;
+    game_state.lives = rules_mode.0.starting_lives;
+    game_state.score = 0;
+    game_state.combo.reset();
+    game_state.stats.started_mid_run = true;
+    recorder.discard();
+    toasts.push(match game_state.continues {
+        0 => "Continue! That was the last one".to_string(),
+        left => format!("Continue! {} left", left),
+    });
+    next_state.set(AppState::Playing);
+}
@@ -0,0 +1,150 @@
+// Loading screen.
+//
+// The game starts on `AppState::Loading`. Plugins hand the assets they load at
+// startup (sounds, audio packs, the player sprite sheet, hand-made levels) to
+// `LoadingAssets::track`, and this screen shows a progress bar until every one
+// of them, and everything it depends on, has finished loading. Then it moves
+// on to the main menu, so nothing pops in or hitches the first time it's used.
+// An asset that fails to load counts as done (it is reported like any other
+// load failure, see `errors`), so a broken file can't leave the game stuck
+// here. (The font is built in and needs no loading.) If the last session
+// crashed, its report is offered before the menu (see `crash`); a first launch
+// goes on to onboarding instead (see `onboarding`).
+
+use bevy::asset::RecursiveDependencyLoadState;
+use bevy::prelude::*;
+use crate::prelude::*;
+
+use crate::crash::PendingCrashReport;
+use crate::onboarding::Onboarding;
+use crate::ui::despawn_screen;
+
+pub struct LoadingPlugin;
+
+impl Plugin for LoadingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LoadingAssets>()
+            .add_systems(OnEnter(AppState::Loading), setup_loading_screen)
+            .add_systems(OnExit(AppState::Loading), despawn_screen::<LoadingUI>)
+            .add_systems(Update, update_loading.run_if(in_state(AppState::Loading)));
+    }
+}
+
+const BAR_WIDTH: f32 = 600.0;
+const BAR_HEIGHT: f32 = 24.0;
+
+// Assets to wait for before leaving the loading screen
+#[derive(Resource, Default)]
+pub struct LoadingAssets {
+    handles: Vec<UntypedHandle>,
+}
+
+impl LoadingAssets {
+    pub fn track(&mut self, handle: impl Into<UntypedHandle>) {
+        self.handles.push(handle.into());
+    }
+}
+
+#[derive(Component)]
+struct LoadingUI;
+
+#[derive(Component)]
+struct LoadingBarFill;
+
+#[derive(Component)]
+struct LoadingText;
+
+fn loading_line(done: usize, total: usize) -> String {
+    format!("Loading... {}/{}", done, total)
+}
+
+fn setup_loading_screen(mut commands: Commands) {
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                loading_line(0, 0),
+                TextStyle {
+                    font_size: 36.0,
+                    color: Color::srgb(0.8, 0.8, 0.8), // Light gray
+                    ..default()
+                },
+            ),
+            transform: Transform::from_translation(Vec3::new(0.0, 50.0, 10.0)),
+            ..default()
+        },
+        LoadingText,
+        LoadingUI,
+    ));
+
+    // Bar outline, with the fill growing from its left end
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: Color::srgb(0.25, 0.25, 0.25),
+                custom_size: Some(Vec2::new(BAR_WIDTH + 6.0, BAR_HEIGHT + 6.0)),
+                ..default()
+            },
+            transform: Transform::from_translation(Vec3::new(0.0, 0.0, 10.0)),
+            ..default()
+        },
+        LoadingUI,
+        ScreenSpace,
+    ));
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: Color::srgb(0.0, 1.0, 0.0), // Green
+                custom_size: Some(Vec2::new(0.0, BAR_HEIGHT)),
+                anchor: bevy::sprite::Anchor::CenterLeft,
+                ..default()
+            },
+            transform: Transform::from_translation(Vec3::new(-BAR_WIDTH / 2.0, 0.0, 11.0)),
+            ..default()
+        },
+        LoadingBarFill,
+        LoadingUI,
+        ScreenSpace,
+    ));
+}
+
+fn update_loading(
+    asset_server: Res<AssetServer>,
+    loading: Res<LoadingAssets>,
+    crash_report: Res<PendingCrashReport>,
+    onboarding: Res<Onboarding>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut fill_query: Query<&mut Sprite, With<LoadingBarFill>>,
+    mut text_query: Query<&mut Text, With<LoadingText>>,
+) {
+    let done = loading
+        .handles
+        .iter()
+        .filter(|handle| {
+            matches!(
+                asset_server.get_recursive_dependency_load_state(handle.id()),
+                Some(RecursiveDependencyLoadState::Loaded | RecursiveDependencyLoadState::Failed)
+            )
+        })
+        .count();
+
+    let total = loading.handles.len();
+    let progress = if total == 0 { 1.0 } else { done as f32 / total as f32 };
+    if let Ok(mut sprite) = fill_query.get_single_mut() {
+        sprite.custom_size = Some(Vec2::new(BAR_WIDTH * progress, BAR_HEIGHT));
+    }
+    if let Ok(mut text) = text_query.get_single_mut() {
+        let line = loading_line(done, total);
+        if text.sections[0].value != line {
+            text.sections[0].value = line;
+        }
+    }
+    if done == total {
+        next_state.set(if crash_report.is_pending() {
+            AppState::CrashReport
+        } else if onboarding.pending {
+            AppState::Onboarding
+        } else {
+            AppState::MainMenu
+        });
+    }
+}
@@ -0,0 +1,547 @@
+// Online lobby and the in-game network session.
+
+use bevy::prelude::*;
+use bevy::input::keyboard::{Key, KeyboardInput};
+use crate::prelude::*;
+
+use crate::chat::{chat_closed, receive_chat, Chat};
+use crate::game::clock_seed;
+use crate::net::{self, LobbyMember, LobbyMessage, PeerId, Recipient, RelayClient, UdpRelay};
+use crate::party::PartyRound;
+use crate::streamer::StreamerMode;
+use crate::ui::despawn_screen;
+
+pub struct LobbyPlugin;
+
+impl Plugin for LobbyPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Lobby>()
+            .init_resource::<NetSession>()
+            .add_systems(OnEnter(AppState::Lobby), enter_lobby)
+            .add_systems(OnExit(AppState::Lobby), despawn_screen::<LobbyUI>)
+            .add_systems(Update, (
+                (handle_lobby_input.run_if(chat_closed), lobby_network, update_lobby_ui).chain().run_if(in_state(AppState::Lobby)),
+                session_network.run_if(
+                    in_state(AppState::Playing)
+                        .or_else(in_state(AppState::LevelTransition))
+                        .or_else(in_state(AppState::ModifierDraft)),
+                ),
+            ));
+    }
+}
+
+// Online lobby tuning
+const LOBBY_HEARTBEAT_SECONDS: f32 = 1.0; // Roster broadcast and ping interval
+const LOBBY_HOST_TIMEOUT_SECONDS: f32 = 5.0;
+const LOBBY_COUNTDOWN_SECONDS: f32 = 3.0;
+// Toast for a lobby or chat message that could not be sent
+const NETWORK_ERROR: &str = "Network error - messages aren't getting through";
+
+#[derive(Component)]
+struct LobbyUI;
+
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+enum LobbyText {
+    Header,
+    Roster,
+    Help,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LobbyStage {
+    Choosing,
+    EnteringCode,
+    Connected,
+}
+
+// Online lobby: who is connected, the agreed seed/mode and the start countdown.
+// The host is authoritative; clients mirror the roster it broadcasts.
+#[derive(Resource)]
+pub struct Lobby {
+    pub stage: LobbyStage,
+    pub relay: Option<Box<dyn RelayClient>>,
+    code_input: String,
+    local_peer: PeerId,
+    members: Vec<LobbyMember>,
+    seed: u64,
+    mode: GameMode,
+    countdown: Option<Timer>,
+    heartbeat: Timer,
+    host_silence: Timer,
+    epoch: bevy::utils::Instant,
+    status: String,
+}
+
+impl Default for Lobby {
+    fn default() -> Self {
+        Self {
+            stage: LobbyStage::Choosing,
+            relay: None,
+            code_input: String::new(),
+            local_peer: net::HOST_PEER,
+            members: Vec::new(),
+            seed: 0,
+            mode: GameMode::Classic,
+            countdown: None,
+            heartbeat: Timer::from_seconds(LOBBY_HEARTBEAT_SECONDS, TimerMode::Repeating),
+            host_silence: Timer::from_seconds(LOBBY_HOST_TIMEOUT_SECONDS, TimerMode::Once),
+            epoch: bevy::utils::Instant::now(),
+            status: String::new(),
+        }
+    }
+}
+
+impl Lobby {
+    fn is_host(&self) -> bool {
+        self.relay.as_ref().is_some_and(|relay| relay.is_host())
+    }
+
+    fn send(&mut self, to: Recipient, message: LobbyMessage) {
+        if let Some(relay) = self.relay.as_mut() {
+            relay.send(to, &message);
+        }
+    }
+
+    fn roster(&self) -> LobbyMessage {
+        LobbyMessage::Roster {
+            members: self.members.clone(),
+            seed: self.seed,
+            mode: self.mode.name().to_string(),
+        }
+    }
+
+    fn elapsed_ms(&self) -> u64 {
+        self.epoch.elapsed().as_millis() as u64
+    }
+
+    fn leave(&mut self, status: &str) {
+        self.send(Recipient::Everyone, LobbyMessage::Leave);
+        self.send(Recipient::Host, LobbyMessage::Leave);
+        *self = Self {
+            status: status.to_string(),
+            ..default()
+        };
+    }
+}
+
+// Connection carried over from the lobby into an online game, so chat keeps
+// working. The host keeps pinging everyone as the lobby does, so the relay
+// doesn't drop a guest who hasn't said anything for a while.
+#[derive(Resource)]
+pub struct NetSession {
+    pub relay: Option<Box<dyn RelayClient>>,
+    members: Vec<LobbyMember>,
+    heartbeat: Timer,
+}
+
+impl Default for NetSession {
+    fn default() -> Self {
+        Self {
+            relay: None,
+            members: Vec::new(),
+            heartbeat: Timer::from_seconds(LOBBY_HEARTBEAT_SECONDS, TimerMode::Repeating),
+        }
+    }
+}
+
+impl NetSession {
+    pub fn close(&mut self) {
+        if let Some(relay) = self.relay.as_mut() {
+            relay.send(Recipient::Everyone, &LobbyMessage::Leave);
+            relay.send(Recipient::Host, &LobbyMessage::Leave);
+        }
+        *self = Self::default();
+    }
+}
+
+pub fn local_player_name() -> String {
+    std::env::var("USERNAME")
+        .or_else(|_| std::env::var("USER"))
+        .unwrap_or_else(|_| "Player".to_string())
+}
+
+fn enter_lobby(mut commands: Commands) {
+    setup_lobby_ui(&mut commands);
+}
+
+// Online Lobby Systems
+fn setup_lobby_ui(commands: &mut Commands) {
+    let texts = [
+        (LobbyText::Header, 40.0, Color::srgb(1.0, 0.5, 0.0), 250.0), // Orange
+        (LobbyText::Roster, 28.0, Color::srgb(1.0, 1.0, 1.0), 60.0), // White
+        (LobbyText::Help, 22.0, Color::srgb(0.7, 0.7, 1.0), -250.0), // Light blue
+    ];
+    for (kind, font_size, color, y) in texts {
+        commands.spawn((
+            Text2dBundle {
+                text: Text::from_section(
+                    "",
+                    TextStyle {
+                        font_size,
+                        color,
+                        ..default()
+                    },
+                ),
+                transform: Transform::from_translation(Vec3::new(0.0, y, 10.0)),
+                ..default()
+            },
+            LobbyUI,
+            kind,
+        ));
+    }
+}
+
+pub fn handle_lobby_input(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut typed_keys: EventReader<KeyboardInput>,
+    mut lobby: ResMut<Lobby>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    // Always drain typed keys so the J that opened the code prompt isn't typed into it
+    let typed: Vec<KeyboardInput> = typed_keys.read().filter(|event| event.state.is_pressed()).cloned().collect();
+
+    if keyboard_input.just_pressed(KeyCode::Escape) {
+        if lobby.stage == LobbyStage::Choosing {
+            next_state.set(AppState::MainMenu);
+        } else {
+            lobby.leave("");
+        }
+        return;
+    }
+
+    match lobby.stage {
+        LobbyStage::Choosing => {
+            if keyboard_input.just_pressed(KeyCode::KeyH) {
+                match UdpRelay::host(net::DEFAULT_LOBBY_PORT) {
+                    Ok(relay) => {
+                        let seed = clock_seed();
+                        *lobby = Lobby {
+                            stage: LobbyStage::Connected,
+                            relay: Some(Box::new(relay)),
+                            members: vec![LobbyMember {
+                                peer: net::HOST_PEER,
+                                name: local_player_name(),
+                                ready: false,
+                                ping_ms: 0,
+                            }],
+                            seed,
+                            ..default()
+                        };
+                    }
+                    Err(error) => lobby.status = format!("Could not host: {}", error),
+                }
+            } else if keyboard_input.just_pressed(KeyCode::KeyJ) {
+                lobby.stage = LobbyStage::EnteringCode;
+                lobby.code_input.clear();
+                lobby.status.clear();
+            }
+        }
+        LobbyStage::EnteringCode => {
+            for event in &typed {
+                match &event.logical_key {
+                    Key::Character(characters) => {
+                        for character in characters.chars().filter(|c| c.is_ascii_alphanumeric()) {
+                            if lobby.code_input.len() < 10 {
+                                lobby.code_input.push(character.to_ascii_uppercase());
+                            }
+                        }
+                    }
+                    Key::Backspace => {
+                        lobby.code_input.pop();
+                    }
+                    Key::Enter => {
+                        let code = lobby.code_input.clone();
+                        match UdpRelay::join(&code) {
+                            Ok(relay) => {
+                                let mut relay: Box<dyn RelayClient> = Box::new(relay);
+                                relay.send(Recipient::Host, &LobbyMessage::Hello { name: local_player_name() });
+                                lobby.relay = Some(relay);
+                                lobby.stage = LobbyStage::Connected;
+                                lobby.status = "Connecting...".to_string();
+                            }
+                            Err(error) => lobby.status = format!("Could not join: {}", error),
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+        LobbyStage::Connected => {
+            if lobby.countdown.is_some() {
+                return;
+            }
+
+            if keyboard_input.just_pressed(KeyCode::Space) {
+                let local_peer = lobby.local_peer;
+                let mut ready = false;
+                if let Some(member) = lobby.members.iter_mut().find(|member| member.peer == local_peer) {
+                    member.ready = !member.ready;
+                    ready = member.ready;
+                }
+                if lobby.is_host() {
+                    let roster = lobby.roster();
+                    lobby.send(Recipient::Everyone, roster);
+                } else {
+                    lobby.send(Recipient::Host, LobbyMessage::Ready { ready });
+                }
+            }
+
+            if lobby.is_host() {
+                let mut settings_changed = false;
+                if keyboard_input.just_pressed(KeyCode::KeyR) {
+                    lobby.seed = clock_seed();
+                    settings_changed = true;
+                }
+                if keyboard_input.just_pressed(KeyCode::KeyM) {
+                    // Tag needs several players on one keyboard, so it's not offered online
+                    lobby.mode = match lobby.mode {
+                        GameMode::Classic => GameMode::FruitGrab,
+                        _ => GameMode::Classic,
+                    };
+                    settings_changed = true;
+                }
+                if settings_changed {
+                    // Changing the rules un-readies everyone
+                    for member in lobby.members.iter_mut() {
+                        member.ready = false;
+                    }
+                    let roster = lobby.roster();
+                    lobby.send(Recipient::Everyone, roster);
+                }
+
+                let everyone_ready = lobby.members.len() > 1 && lobby.members.iter().all(|member| member.ready);
+                if keyboard_input.just_pressed(KeyCode::Enter) && everyone_ready {
+                    let start = LobbyMessage::Start {
+                        seed: lobby.seed,
+                        mode: lobby.mode.name().to_string(),
+                        countdown: LOBBY_COUNTDOWN_SECONDS,
+                    };
+                    lobby.send(Recipient::Everyone, start);
+                    lobby.countdown = Some(Timer::from_seconds(LOBBY_COUNTDOWN_SECONDS, TimerMode::Once));
+                }
+            }
+        }
+    }
+}
+
+fn lobby_network(
+    time: Res<Time>,
+    mut lobby: ResMut<Lobby>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut game_mode: ResMut<GameMode>,
+    mut party_round: ResMut<PartyRound>,
+    mut game_rng: ResMut<GameRng>,
+    mut session: ResMut<NetSession>,
+    mut chat: ResMut<Chat>,
+    mut errors: EventWriter<RuntimeErrorEvent>,
+) {
+    let lobby: &mut Lobby = &mut lobby;
+    let Some(relay) = lobby.relay.as_mut() else {
+        return;
+    };
+    if let Some(error) = relay.take_error() {
+        errors.send(RuntimeErrorEvent::new(NETWORK_ERROR, error));
+    }
+    let messages = relay.poll();
+    let silent_peers = relay.drop_silent_peers();
+    let is_host = relay.is_host();
+
+    if is_host {
+        let mut roster_changed = !silent_peers.is_empty();
+        lobby.members.retain(|member| !silent_peers.contains(&member.peer));
+
+        for (peer, message) in messages {
+            match message {
+                LobbyMessage::Hello { name } => {
+                    if lobby.countdown.is_none() && !lobby.members.iter().any(|member| member.peer == peer) {
+                        lobby.members.push(LobbyMember {
+                            peer,
+                            name,
+                            ready: false,
+                            ping_ms: 0,
+                        });
+                    }
+                    lobby.send(Recipient::Peer(peer), LobbyMessage::Welcome { peer });
+                    roster_changed = true;
+                }
+                LobbyMessage::Ready { ready } => {
+                    if let Some(member) = lobby.members.iter_mut().find(|member| member.peer == peer) {
+                        member.ready = ready;
+                        roster_changed = true;
+                    }
+                }
+                LobbyMessage::Pong { nonce } => {
+                    let ping_ms = lobby.elapsed_ms().saturating_sub(nonce) as u32;
+                    if let Some(member) = lobby.members.iter_mut().find(|member| member.peer == peer) {
+                        member.ping_ms = ping_ms;
+                    }
+                }
+                LobbyMessage::Ping { nonce } => lobby.send(Recipient::Peer(peer), LobbyMessage::Pong { nonce }),
+                LobbyMessage::Leave => {
+                    lobby.members.retain(|member| member.peer != peer);
+                    roster_changed = true;
+                }
+                message @ (LobbyMessage::Chat { .. } | LobbyMessage::Emote { .. }) => {
+                    if let Some(relay) = lobby.relay.as_deref_mut() {
+                        receive_chat(relay, &lobby.members, peer, message, &mut chat);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        // Someone dropping out mid-countdown calls the start off
+        if roster_changed && lobby.countdown.is_some() {
+            lobby.countdown = None;
+            lobby.status = "A player left - start cancelled".to_string();
+        }
+
+        if lobby.heartbeat.tick(time.delta()).just_finished() || roster_changed {
+            let nonce = lobby.elapsed_ms();
+            lobby.send(Recipient::Everyone, LobbyMessage::Ping { nonce });
+            let roster = lobby.roster();
+            lobby.send(Recipient::Everyone, roster);
+        }
+    } else {
+        for (peer, message) in messages {
+            lobby.host_silence.reset();
+            match message {
+                LobbyMessage::Welcome { peer } => {
+                    lobby.local_peer = peer;
+                    lobby.status.clear();
+                }
+                LobbyMessage::Roster { members, seed, mode } => {
+                    lobby.members = members;
+                    lobby.seed = seed;
+                    lobby.mode = GameMode::from_name(&mode).unwrap_or(GameMode::Classic);
+                }
+                LobbyMessage::Ping { nonce } => lobby.send(Recipient::Host, LobbyMessage::Pong { nonce }),
+                LobbyMessage::Start { seed, mode, countdown } => {
+                    lobby.seed = seed;
+                    lobby.mode = GameMode::from_name(&mode).unwrap_or(GameMode::Classic);
+                    lobby.countdown = Some(Timer::from_seconds(countdown, TimerMode::Once));
+                }
+                LobbyMessage::Leave => {
+                    lobby.leave("The host closed the lobby");
+                    return;
+                }
+                message @ (LobbyMessage::Chat { .. } | LobbyMessage::Emote { .. }) => {
+                    if let Some(relay) = lobby.relay.as_deref_mut() {
+                        receive_chat(relay, &lobby.members, peer, message, &mut chat);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        if lobby.host_silence.tick(time.delta()).just_finished() {
+            lobby.leave("Lost connection to the host");
+            return;
+        }
+    }
+
+    let countdown_done = lobby
+        .countdown
+        .as_mut()
+        .is_some_and(|countdown| countdown.tick(time.delta()).just_finished());
+    if countdown_done {
+        // Everyone plays the agreed mode on the agreed seed on their own machine
+        *game_mode = lobby.mode;
+        if lobby.mode == GameMode::FruitGrab {
+            *party_round = PartyRound::new(GameMode::FruitGrab, 1);
+        }
+        game_rng.share_runs(lobby.seed);
+        // Keep the connection open for chat during the game
+        *session = NetSession {
+            relay: lobby.relay.take(),
+            members: std::mem::take(&mut lobby.members),
+            ..default()
+        };
+        *lobby = Lobby::default();
+        next_state.set(AppState::Playing);
+    }
+}
+
+fn update_lobby_ui(lobby: Res<Lobby>, streamer: Res<StreamerMode>, mut text_query: Query<(&mut Text, &LobbyText)>) {
+    let header = match lobby.stage {
+        LobbyStage::Choosing => "ONLINE LOBBY".to_string(),
+        LobbyStage::EnteringCode => format!("Lobby code: {}_", lobby.code_input),
+        LobbyStage::Connected => match lobby.relay.as_ref() {
+            Some(relay) => format!("Lobby {}", relay.lobby_code()),
+            None => "ONLINE LOBBY".to_string(),
+        },
+    };
+
+    let mut roster = String::new();
+    if lobby.stage == LobbyStage::Connected {
+        for member in &lobby.members {
+            let you = if member.peer == lobby.local_peer { " (you)" } else { "" };
+            let role = if member.peer == net::HOST_PEER { "host" } else { "guest" };
+            let ready = if member.ready { "READY" } else { "not ready" };
+            roster.push_str(&format!("{}{} [{}] - {} - {} ms\n", member.name, you, role, ready, member.ping_ms));
+        }
+        roster.push_str(&format!("\nSeed: {}   Mode: {}", streamer.seed_label(lobby.seed), lobby.mode.name()));
+        if let Some(countdown) = &lobby.countdown {
+            roster.push_str(&format!("\n\nStarting in {}...", countdown.remaining_secs().ceil() as u32));
+        }
+    }
+    if !lobby.status.is_empty() {
+        roster.push_str(&format!("\n{}", lobby.status));
+    }
+
+    let help = match lobby.stage {
+        LobbyStage::Choosing => "H to host a lobby, J to join with a code, ESC to go back",
+        LobbyStage::EnteringCode => "Type the host's code and press ENTER, ESC to cancel",
+        LobbyStage::Connected if lobby.is_host() => {
+            "SPACE ready, R new seed, M change mode, ENTER start when everyone is ready, ESC leave"
+        }
+        LobbyStage::Connected => "SPACE to toggle ready, ESC to leave",
+    };
+
+    for (mut text, kind) in text_query.iter_mut() {
+        text.sections[0].value = match kind {
+            LobbyText::Header => header.clone(),
+            LobbyText::Roster => roster.clone(),
+            LobbyText::Help => help.to_string(),
+        };
+    }
+}
+
+fn session_network(time: Res<Time>, mut session: ResMut<NetSession>, mut chat: ResMut<Chat>, mut errors: EventWriter<RuntimeErrorEvent>) {
+    let session: &mut NetSession = &mut session;
+    let Some(relay) = session.relay.as_deref_mut() else {
+        return;
+    };
+    if let Some(error) = relay.take_error() {
+        errors.send(RuntimeErrorEvent::new(NETWORK_ERROR, error));
+    }
+
+    let messages = relay.poll();
+    for peer in relay.drop_silent_peers() {
+        session.members.retain(|member| member.peer != peer);
+    }
+
+    for (peer, message) in messages {
+        match message {
+            LobbyMessage::Ping { nonce } => {
+                let recipient = if relay.is_host() { Recipient::Peer(peer) } else { Recipient::Host };
+                relay.send(recipient, &LobbyMessage::Pong { nonce });
+            }
+            LobbyMessage::Leave if !relay.is_host() => {
+                chat.push("The host left - chat disconnected".to_string());
+                *session = NetSession::default();
+                return;
+            }
+            LobbyMessage::Leave => session.members.retain(|member| member.peer != peer),
+            message @ (LobbyMessage::Chat { .. } | LobbyMessage::Emote { .. }) => {
+                receive_chat(relay, &session.members, peer, message, &mut chat);
+            }
+            _ => {}
+        }
+    }
+
+    // Nobody needs the round trip here, only the answer keeping the guest in
+    if relay.is_host() && session.heartbeat.tick(time.delta()).just_finished() {
+        relay.send(Recipient::Everyone, &LobbyMessage::Ping { nonce: 0 });
+    }
+}
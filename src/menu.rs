@@ -0,0 +1,789 @@
+// Menu screens: main menu, assist and mutator menus, modifier draft and game
+// over. The pause menu has a module of its own (`pause`).
+
+use bevy::prelude::*;
+use crate::prelude::*;
+use rustbevy_core::assists::Assists;
+use rustbevy_core::config::{WINDOW_HEIGHT, WINDOW_WIDTH};
+use rustbevy_core::modifiers::{Modifier, DRAFT_CHOICES};
+use rustbevy_core::mutators::Mutators;
+
+use crate::attract;
+use crate::chat::chat_closed;
+use crate::game::clock_seed;
+use crate::lobby::NetSession;
+use crate::party::{PartyGameText, PartyRound, SelectedPartyGame};
+use crate::replay::ReplayPlayback;
+use crate::score::HighScore;
+use crate::settings::Settings;
+use crate::sound::AudioPacks;
+use crate::streamer::ChatVotes;
+use crate::ui::{despawn_screen, AdaptiveDifficultyText, GameUI};
+
+pub struct MenuPlugin;
+
+impl Plugin for MenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ModifierDraft>()
+            // Each screen is built on entering its state and torn down on leaving it
+            .add_systems(OnEnter(AppState::MainMenu), enter_main_menu)
+            .add_systems(OnExit(AppState::MainMenu), despawn_screen::<MainMenuUI>)
+            .add_systems(OnEnter(AppState::ModifierDraft), enter_modifier_draft)
+            .add_systems(OnExit(AppState::ModifierDraft), despawn_screen::<ModifierDraftUI>)
+            .add_systems(OnEnter(AppState::GameOver), enter_game_over.run_if(resource_equals(GameMode::Classic)))
+            .add_systems(OnExit(AppState::GameOver), despawn_screen::<GameOverUI>)
+            .add_systems(Update, (
+                (handle_main_menu_input, handle_assist_menu_input, handle_mutator_menu_input, handle_sound_pack_menu_input).run_if(in_state(AppState::MainMenu)),
+                handle_game_over_input.run_if(in_state(AppState::GameOver)),
+                handle_modifier_draft_input.run_if(in_state(AppState::ModifierDraft).and_then(chat_closed).and_then(not(resource_exists::<ReplayPlayback>))),
+            ));
+    }
+}
+
+// Menu Components
+#[derive(Component)]
+pub struct MainMenuUI;
+
+#[derive(Component)]
+struct GameOverUI;
+
+#[derive(Component)]
+struct AssistMenuText;
+
+#[derive(Component)]
+struct MutatorMenuText;
+
+#[derive(Component)]
+struct SoundPackMenuText;
+
+#[derive(Component)]
+struct ModifierDraftUI;
+
+// The modifiers offered by the draft on screen
+#[derive(Resource, Default)]
+pub struct ModifierDraft {
+    pub choices: Vec<Modifier>,
+}
+
+fn setup_main_menu(
+    mut commands: Commands,
+    difficulty: Res<Difficulty>,
+    assist_mode: Res<AssistMode>,
+    mutator_mode: Res<MutatorMode>,
+    audio_packs: Res<AudioPacks>,
+    settings: Res<Settings>,
+) {
+    // A bot playing a scrolling level behind the menu
+    attract::spawn_menu_backdrop(&mut commands, clock_seed());
+
+    // Main title
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                "BEVY PLATFORMER",
+                TextStyle {
+                    font_size: 80.0,
+                    color: Color::srgb(1.0, 0.5, 0.0), // Orange
+                    ..default()
+                },
+            ),
+            transform: Transform::from_translation(Vec3::new(0.0, 150.0, 10.0)),
+            ..default()
+        },
+        MainMenuUI,
+    ));
+
+    // Subtitle
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                "AI-Generated Game",
+                TextStyle {
+                    font_size: 30.0,
+                    color: Color::srgb(0.8, 0.8, 0.8), // Light gray
+                    ..default()
+                },
+            ),
+            transform: Transform::from_translation(Vec3::new(0.0, 100.0, 10.0)),
+            ..default()
+        },
+        MainMenuUI,
+    ));
+
+    // Start instruction
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                "Press SPACE to Start   (Q: Co-op   U: Tower   F: Favorites   N: Level editor   W: Tutorial)",
+                TextStyle {
+                    font_size: 36.0,
+                    color: Color::srgb(0.0, 1.0, 0.0), // Green
+                    ..default()
+                },
+            ),
+            transform: Transform::from_translation(Vec3::new(0.0, -50.0, 10.0)),
+            ..default()
+        },
+        MainMenuUI,
+    ));
+
+    // Audio pack choice
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                sound_pack_menu_line(&audio_packs, &settings),
+                TextStyle {
+                    font_size: 22.0,
+                    color: Color::srgb(0.6, 0.9, 1.0), // Sky blue
+                    ..default()
+                },
+            ),
+            transform: Transform::from_translation(Vec3::new(0.0, 40.0, 10.0)),
+            ..default()
+        },
+        MainMenuUI,
+        SoundPackMenuText,
+    ));
+
+    // Challenge mutators
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                mutator_menu_line(mutator_mode.0),
+                TextStyle {
+                    font_size: 22.0,
+                    color: Color::srgb(0.8, 0.6, 1.0), // Lavender
+                    ..default()
+                },
+            ),
+            transform: Transform::from_translation(Vec3::new(0.0, -100.0, 10.0)),
+            ..default()
+        },
+        MainMenuUI,
+        MutatorMenuText,
+    ));
+
+    // Objective instruction
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                "Collect orange fruits to advance levels!",
+                TextStyle {
+                    font_size: 25.0,
+                    color: Color::srgb(1.0, 1.0, 0.0), // Yellow
+                    ..default()
+                },
+            ),
+            transform: Transform::from_translation(Vec3::new(0.0, -200.0, 10.0)),
+            ..default()
+        },
+        MainMenuUI,
+    ));
+
+    // Party mode instruction
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                party_menu_line(GameMode::FruitGrab),
+                TextStyle {
+                    font_size: 25.0,
+                    color: Color::srgb(1.0, 0.4, 0.8), // Pink
+                    ..default()
+                },
+            ),
+            transform: Transform::from_translation(Vec3::new(0.0, -260.0, 10.0)),
+            ..default()
+        },
+        MainMenuUI,
+        PartyGameText,
+    ));
+
+    setup_online_menu_line(&mut commands);
+
+    // Adaptive difficulty option
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                adaptive_difficulty_menu_line(difficulty.0.enabled),
+                TextStyle {
+                    font_size: 22.0,
+                    color: Color::srgb(0.8, 0.8, 0.8), // Light gray
+                    ..default()
+                },
+            ),
+            transform: Transform::from_translation(Vec3::new(0.0, -340.0, 10.0)),
+            ..default()
+        },
+        MainMenuUI,
+        AdaptiveDifficultyText,
+    ));
+
+    // Assist toggles
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                assist_menu_line(assist_mode.0),
+                TextStyle {
+                    font_size: 22.0,
+                    color: Color::srgb(1.0, 0.7, 0.4), // Light orange
+                    ..default()
+                },
+            ),
+            transform: Transform::from_translation(Vec3::new(0.0, -370.0, 10.0)),
+            ..default()
+        },
+        MainMenuUI,
+        AssistMenuText,
+    ));
+}
+
+fn assist_menu_line(assists: Assists) -> String {
+    let on_off = |on: bool| if on { "ON" } else { "OFF" };
+    format!(
+        "Assists: F1 higher jump {} | F2 slower speed {} | F3 infinite lives {} | F5 platform hints {} | F10 level skip {}\nJ jump arc {} | Shift+F3 no hazard damage {} | F12 controls: {}",
+        on_off(assists.higher_jump),
+        on_off(assists.slow_motion),
+        on_off(assists.infinite_lives),
+        on_off(assists.reachable_hints),
+        on_off(assists.level_skip),
+        on_off(assists.jump_arc),
+        on_off(assists.harmless_hazards),
+        assists.controls.description(),
+    )
+}
+
+fn mutator_menu_line(mutators: Mutators) -> String {
+    format!(
+        "Mutators: M mirror mode {} | Z player size {} | X / shift+X game speed {}%",
+        mutators.mirror.label(),
+        mutators.scale.label(),
+        mutators.speed.percent(),
+    )
+}
+
+fn sound_pack_menu_line(audio_packs: &AudioPacks, settings: &Settings) -> String {
+    format!(
+        "S: Sound pack {}   - / =: Music {:.0}%   [ / ]: Effects {:.0}%   G: Graphics {}   B: Reduced motion {}",
+        audio_packs.selected().unwrap_or("(loading)"),
+        settings.music_volume * 100.0,
+        settings.effects_volume * 100.0,
+        settings.graphics.name(),
+        if settings.reduced_motion { "ON" } else { "OFF" }
+    )
+}
+
+fn adaptive_difficulty_menu_line(enabled: bool) -> String {
+    format!("D: Adaptive difficulty {}", if enabled { "ON" } else { "OFF" })
+}
+
+fn setup_online_menu_line(commands: &mut Commands) {
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                "Online: press O to host or join a lobby",
+                TextStyle {
+                    font_size: 25.0,
+                    color: Color::srgb(0.4, 1.0, 0.8), // Mint
+                    ..default()
+                },
+            ),
+            transform: Transform::from_translation(Vec3::new(0.0, -300.0, 10.0)),
+            ..default()
+        },
+        MainMenuUI,
+    ));
+}
+
+fn party_menu_line(party_game: GameMode) -> String {
+    format!("Party: press 2, 3 or 4 for {} (TAB to switch game)", party_game.name())
+}
+
+fn setup_game_over(
+    commands: &mut Commands,
+    game_state: Res<GameState>,
+    difficulty: &Difficulty,
+    curve: &DifficultyCurve,
+    assists: Assists,
+    mutators: Mutators,
+    high_score: &HighScore,
+) {
+    // Game Over title
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                "GAME OVER",
+                TextStyle {
+                    font_size: 80.0,
+                    color: Color::srgb(1.0, 0.0, 0.0), // Red
+                    ..default()
+                },
+            ),
+            transform: Transform::from_translation(Vec3::new(0.0, 150.0, 10.0)),
+            ..default()
+        },
+        GameOverUI,
+    ));
+
+    // High score, or the news that this run set one
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                if high_score.beaten { "NEW HIGH SCORE!".to_string() } else { format!("High Score: {}", high_score.best) },
+                TextStyle {
+                    font_size: 30.0,
+                    color: Color::srgb(1.0, 0.85, 0.3), // Gold
+                    ..default()
+                },
+            ),
+            transform: Transform::from_translation(Vec3::new(0.0, 95.0, 10.0)),
+            ..default()
+        },
+        GameOverUI,
+    ));
+
+    // Final stats
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                format!("Final Level: {}   Score: {}", game_state.level, game_state.score),
+                TextStyle {
+                    font_size: 40.0,
+                    color: Color::srgb(1.0, 1.0, 0.0), // Yellow
+                    ..default()
+                },
+            ),
+            transform: Transform::from_translation(Vec3::new(0.0, 50.0, 10.0)),
+            ..default()
+        },
+        GameOverUI,
+    ));
+
+    // Difficulty the last level was generated with
+    let base = curve.0.base(game_state.level);
+    let difficulty_text = if difficulty.0.enabled {
+        format!(
+            "Adaptive difficulty: {:.0}% (base curve {:.0}%, adjusted {:+.0}%)",
+            difficulty.0.difficulty(&curve.0, game_state.level) * 100.0,
+            base * 100.0,
+            difficulty.0.offset * 100.0,
+        )
+    } else {
+        format!("Difficulty: {:.0}% (adaptive difficulty off)", base * 100.0)
+    };
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                difficulty_text,
+                TextStyle {
+                    font_size: 22.0,
+                    color: Color::srgb(0.8, 0.8, 0.8), // Light gray
+                    ..default()
+                },
+            ),
+            transform: Transform::from_translation(Vec3::new(0.0, 5.0, 10.0)),
+            ..default()
+        },
+        GameOverUI,
+    ));
+
+    // Assisted runs are clearly marked, and so are mutated ones (a changed
+    // game speed among them)
+    let mut marks = Vec::new();
+    if assists.any() {
+        marks.push((format!("Assisted run: {}", assists.labels().join(", ")), Color::srgb(1.0, 0.7, 0.4))); // Light orange
+    }
+    if mutators.any() {
+        marks.push((format!("Mutators: {}", mutators.labels().join(", ")), Color::srgb(0.8, 0.6, 1.0))); // Lavender
+    }
+    let extra_lines = marks.len().saturating_sub(1) as f32;
+    for (index, (mark, color)) in marks.into_iter().enumerate() {
+        commands.spawn((
+            Text2dBundle {
+                text: Text::from_section(mark, TextStyle { font_size: 22.0, color, ..default() }),
+                transform: Transform::from_translation(Vec3::new(0.0, -100.0 - index as f32 * 25.0, 10.0)),
+                ..default()
+            },
+            GameOverUI,
+        ));
+    }
+
+    // Restart instruction, and the continue if there's one left (see `lives`)
+    let restart_text = match game_state.continues {
+        0 => "R: Restart   ESC: Main Menu   Q: Quit Game".to_string(),
+        left => format!("C: Continue ({} left)   R: Restart   ESC: Main Menu   Q: Quit Game", left),
+    };
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                restart_text,
+                TextStyle {
+                    font_size: 30.0,
+                    color: Color::srgb(0.0, 1.0, 0.0), // Green
+                    ..default()
+                },
+            ),
+            transform: Transform::from_translation(Vec3::new(0.0, -50.0, 10.0)),
+            ..default()
+        },
+        GameOverUI,
+    ));
+
+    // Achievement message based on level reached
+    let achievement_text = if game_state.level >= 10 {
+        "Amazing! You're a platforming master!"
+    } else if game_state.level >= 5 {
+        "Great job! You're getting good at this!"
+    } else {
+        "Good try! Practice makes perfect!"
+    };
+
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                achievement_text,
+                TextStyle {
+                    font_size: 25.0,
+                    color: Color::srgb(0.8, 0.8, 1.0), // Light blue
+                    ..default()
+                },
+            ),
+            transform: Transform::from_translation(Vec3::new(0.0, -150.0 - extra_lines * 25.0, 10.0)),
+            ..default()
+        },
+        GameOverUI,
+    ));
+}
+
+// Menu Input Systems
+fn handle_main_menu_input(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut game_mode: ResMut<GameMode>,
+    mut party_round: ResMut<PartyRound>,
+    mut selected_party_game: ResMut<SelectedPartyGame>,
+    mut game_rng: ResMut<GameRng>,
+    mut party_text_query: Query<&mut Text, (With<PartyGameText>, Without<AdaptiveDifficultyText>)>,
+    mut difficulty: ResMut<Difficulty>,
+    mut difficulty_text_query: Query<&mut Text, With<AdaptiveDifficultyText>>,
+    mut coop_mode: ResMut<CoopMode>,
+) {
+    if keyboard_input.just_pressed(KeyCode::KeyO) {
+        next_state.set(AppState::Lobby);
+        return;
+    }
+
+    if keyboard_input.just_pressed(KeyCode::KeyF) {
+        next_state.set(AppState::Favorites);
+        return;
+    }
+
+    if keyboard_input.just_pressed(KeyCode::KeyD) {
+        difficulty.0.enabled = !difficulty.0.enabled;
+        if let Ok(mut text) = difficulty_text_query.get_single_mut() {
+            text.sections[0].value = adaptive_difficulty_menu_line(difficulty.0.enabled);
+        }
+    }
+
+    if keyboard_input.just_pressed(KeyCode::Tab) {
+        selected_party_game.0 = match selected_party_game.0 {
+            GameMode::FruitGrab => GameMode::Tag,
+            GameMode::Tag => GameMode::Territory,
+            _ => GameMode::FruitGrab,
+        };
+        if let Ok(mut text) = party_text_query.get_single_mut() {
+            text.sections[0].value = party_menu_line(selected_party_game.0);
+        }
+    }
+
+    let party_players = if keyboard_input.just_pressed(KeyCode::Digit2) {
+        Some(2)
+    } else if keyboard_input.just_pressed(KeyCode::Digit3) {
+        Some(3)
+    } else if keyboard_input.just_pressed(KeyCode::Digit4) {
+        Some(4)
+    } else {
+        None
+    };
+
+    let coop = keyboard_input.just_pressed(KeyCode::KeyQ);
+    let tower = keyboard_input.just_pressed(KeyCode::KeyU);
+    if keyboard_input.just_pressed(KeyCode::Space) || coop || tower || party_players.is_some() {
+        match party_players {
+            Some(player_count) => {
+                *game_mode = selected_party_game.0;
+                *party_round = PartyRound::new(*game_mode, player_count);
+            }
+            None if tower => *game_mode = GameMode::Tower,
+            None => *game_mode = GameMode::Classic,
+        }
+        coop_mode.0 = coop && !tower && party_players.is_none();
+        game_rng.stop_sharing();
+        next_state.set(AppState::Playing);
+    }
+}
+
+fn handle_assist_menu_input(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut assist_mode: ResMut<AssistMode>,
+    mut text_query: Query<&mut Text, With<AssistMenuText>>,
+) {
+    let assists = &mut assist_mode.0;
+    if keyboard_input.just_pressed(KeyCode::F1) {
+        assists.higher_jump = !assists.higher_jump;
+    } else if keyboard_input.just_pressed(KeyCode::F2) {
+        assists.slow_motion = !assists.slow_motion;
+    } else if keyboard_input.just_pressed(KeyCode::F3) && keyboard_input.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) {
+        assists.harmless_hazards = !assists.harmless_hazards;
+    } else if keyboard_input.just_pressed(KeyCode::F3) {
+        assists.infinite_lives = !assists.infinite_lives;
+    } else if keyboard_input.just_pressed(KeyCode::F5) {
+        assists.reachable_hints = !assists.reachable_hints;
+    } else if keyboard_input.just_pressed(KeyCode::F10) {
+        assists.level_skip = !assists.level_skip;
+    } else if keyboard_input.just_pressed(KeyCode::KeyJ) {
+        assists.jump_arc = !assists.jump_arc;
+    } else if keyboard_input.just_pressed(KeyCode::F12) {
+        assists.controls = assists.controls.next();
+    } else {
+        return;
+    }
+    if let Ok(mut text) = text_query.get_single_mut() {
+        text.sections[0].value = assist_menu_line(*assists);
+    }
+}
+
+// The pack list fills in as packs load, so the line is refreshed on any change
+fn handle_sound_pack_menu_input(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut audio_packs: ResMut<AudioPacks>,
+    mut settings: ResMut<Settings>,
+    mut text_query: Query<&mut Text, With<SoundPackMenuText>>,
+) {
+    if keyboard_input.just_pressed(KeyCode::KeyS) {
+        audio_packs.select_next();
+    }
+    if keyboard_input.just_pressed(KeyCode::KeyG) {
+        settings.graphics = settings.graphics.next();
+    }
+    if keyboard_input.just_pressed(KeyCode::KeyB) {
+        settings.reduced_motion = !settings.reduced_motion;
+    }
+    let volume_keys = [
+        (KeyCode::Minus, false, -1),
+        (KeyCode::Equal, false, 1),
+        (KeyCode::BracketLeft, true, -1),
+        (KeyCode::BracketRight, true, 1),
+    ];
+    for (key, effects, steps) in volume_keys {
+        if keyboard_input.just_pressed(key) {
+            let volume = if effects { &mut settings.effects_volume } else { &mut settings.music_volume };
+            Settings::step_volume(volume, steps);
+        }
+    }
+    if !audio_packs.is_changed() && !settings.is_changed() {
+        return;
+    }
+    if let Ok(mut text) = text_query.get_single_mut() {
+        text.sections[0].value = sound_pack_menu_line(&audio_packs, &settings);
+    }
+}
+
+fn handle_mutator_menu_input(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut mutator_mode: ResMut<MutatorMode>,
+    mut text_query: Query<&mut Text, With<MutatorMenuText>>,
+) {
+    if keyboard_input.just_pressed(KeyCode::KeyM) {
+        mutator_mode.0.mirror = mutator_mode.0.mirror.next();
+    } else if keyboard_input.just_pressed(KeyCode::KeyZ) {
+        mutator_mode.0.scale = mutator_mode.0.scale.next();
+    } else if keyboard_input.just_pressed(KeyCode::KeyX) {
+        let slower = keyboard_input.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+        mutator_mode.0.speed = mutator_mode.0.speed.step(if slower { -1 } else { 1 });
+    } else {
+        return;
+    }
+    if let Ok(mut text) = text_query.get_single_mut() {
+        text.sections[0].value = mutator_menu_line(mutator_mode.0);
+    }
+}
+
+fn setup_modifier_draft(commands: &mut Commands, modifier_draft: &ModifierDraft, run_modifiers: &RunModifiers) {
+    // Dim the paused level behind the choices
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: Color::srgba(0.0, 0.0, 0.0, 0.7),
+                custom_size: Some(Vec2::new(WINDOW_WIDTH, WINDOW_HEIGHT)),
+                ..default()
+            },
+            transform: Transform::from_translation(Vec3::new(0.0, 0.0, 15.0)),
+            ..default()
+        },
+        ModifierDraftUI,
+        ScreenSpace,
+    ));
+
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                "Choose a modifier (0: skip)",
+                TextStyle {
+                    font_size: 60.0,
+                    color: Color::srgb(1.0, 0.5, 0.0), // Orange
+                    ..default()
+                },
+            ),
+            transform: Transform::from_translation(Vec3::new(0.0, 220.0, 16.0)),
+            ..default()
+        },
+        ModifierDraftUI,
+    ));
+
+    for (index, modifier) in modifier_draft.choices.iter().enumerate() {
+        let y = 110.0 - index as f32 * 110.0;
+        commands.spawn((
+            Text2dBundle {
+                text: Text::from_section(
+                    format!("{}: {}", index + 1, modifier.name()),
+                    TextStyle {
+                        font_size: 36.0,
+                        color: Color::srgb(1.0, 1.0, 0.0), // Yellow
+                        ..default()
+                    },
+                ),
+                transform: Transform::from_translation(Vec3::new(0.0, y, 16.0)),
+                ..default()
+            },
+            ModifierDraftUI,
+        ));
+        commands.spawn((
+            Text2dBundle {
+                text: Text::from_section(
+                    modifier.description(),
+                    TextStyle {
+                        font_size: 24.0,
+                        color: Color::srgb(0.8, 0.8, 1.0), // Light blue
+                        ..default()
+                    },
+                ),
+                transform: Transform::from_translation(Vec3::new(0.0, y - 38.0, 16.0)),
+                ..default()
+            },
+            ModifierDraftUI,
+        ));
+    }
+
+    // What the run already has stacked
+    let active = if run_modifiers.0.picked.is_empty() {
+        "Active modifiers: none".to_string()
+    } else {
+        let names: Vec<&str> = run_modifiers.0.picked.iter().map(|modifier| modifier.name()).collect();
+        format!("Active modifiers: {}", names.join(", "))
+    };
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                active,
+                TextStyle {
+                    font_size: 22.0,
+                    color: Color::srgb(0.8, 0.8, 0.8), // Light gray
+                    ..default()
+                },
+            ),
+            transform: Transform::from_translation(Vec3::new(0.0, -250.0, 16.0)),
+            ..default()
+        },
+        ModifierDraftUI,
+    ));
+}
+
+fn handle_modifier_draft_input(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut game_state: ResMut<GameState>,
+    modifier_draft: Res<ModifierDraft>,
+    mut run_modifiers: ResMut<RunModifiers>,
+    chat_votes: Res<ChatVotes>,
+    mut sound_events: EventWriter<PlaySoundEvent>,
+) {
+    // Skipping keeps a Low% run going
+    if keyboard_input.just_pressed(KeyCode::Digit0) {
+        next_state.set(AppState::Playing);
+        return;
+    }
+    let keys = [KeyCode::Digit1, KeyCode::Digit2, KeyCode::Digit3];
+    let picked = keys.iter().take(DRAFT_CHOICES).position(|key| keyboard_input.just_pressed(*key));
+    // In streamer mode ENTER goes with the chat's vote
+    let chat_pick = keyboard_input.just_pressed(KeyCode::Enter).then(|| chat_votes.leader()).flatten();
+    let Some(index) = picked.or(chat_pick) else {
+        return;
+    };
+    let Some(modifier) = modifier_draft.choices.get(index).copied() else {
+        return;
+    };
+
+    run_modifiers.0.picked.push(modifier);
+    if modifier.extra_lives() > 0 {
+        game_state.lives += modifier.extra_lives();
+        sound_events.send(PlaySoundEvent { sound_type: SoundType::ExtraLife, position: None });
+    }
+    game_state.stats.modifiers_picked += 1;
+    next_state.set(AppState::Playing);
+}
+
+fn handle_game_over_input(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut commands: Commands,
+    platform_query: Query<Entity, Or<(With<Platform>, With<Enemy>, With<Checkpoint>, With<ExitDoor>, With<Key>, With<Ladder>, With<HazardArea>, With<WindZone>, With<PortalEnd>, With<ThrownSeed>)>>,
+    pickup_query: Query<Entity, With<Pickup>>,
+    ui_query: Query<Entity, With<GameUI>>,
+) {
+    if keyboard_input.just_pressed(KeyCode::KeyR) {
+        // Clear the old run; entering play with an empty world starts a new one
+        for entity in platform_query.iter().chain(pickup_query.iter()).chain(ui_query.iter()) {
+            commands.entity(entity).despawn_recursive();
+        }
+        next_state.set(AppState::Playing);
+    } else if keyboard_input.any_just_pressed([KeyCode::Escape, KeyCode::KeyQ]) {
+        // Clean up the leftover level so the next mode starts fresh
+        for entity in platform_query.iter().chain(ui_query.iter()) {
+            commands.entity(entity).despawn_recursive();
+        }
+        // Return to main menu, or quit the game by way of the session recap
+        next_state.set(if keyboard_input.just_pressed(KeyCode::KeyQ) { AppState::SessionRecap } else { AppState::MainMenu });
+    }
+}
+
+fn enter_main_menu(
+    commands: Commands,
+    difficulty: Res<Difficulty>,
+    assist_mode: Res<AssistMode>,
+    mutator_mode: Res<MutatorMode>,
+    audio_packs: Res<AudioPacks>,
+    settings: Res<Settings>,
+    mut session: ResMut<NetSession>,
+    mut coop_mode: ResMut<CoopMode>,
+) {
+    // Back at the menu the online game is over, and so is the co-op run
+    session.close();
+    coop_mode.0 = false;
+    setup_main_menu(commands, difficulty, assist_mode, mutator_mode, audio_packs, settings);
+}
+
+fn enter_game_over(
+    mut commands: Commands,
+    game_state: Res<GameState>,
+    difficulty: Res<Difficulty>,
+    curve: Res<DifficultyCurve>,
+    assist_mode: Res<AssistMode>,
+    mutator_mode: Res<MutatorMode>,
+    high_score: Res<HighScore>,
+) {
+    setup_game_over(&mut commands, game_state, &difficulty, &curve, assist_mode.0, mutator_mode.0, &high_score);
+}
+
+fn enter_modifier_draft(mut commands: Commands, modifier_draft: Res<ModifierDraft>, run_modifiers: Res<RunModifiers>) {
+    setup_modifier_draft(&mut commands, &modifier_draft, &run_modifiers);
+}
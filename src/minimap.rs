@@ -0,0 +1,179 @@
+// Minimap for Classic runs.
+//
+// Levels bigger than the window get a small map of the whole level: a
+// rectangle for each platform, a dot for the player and a marker for the
+// fruit, following them as they move. It's laid out from the mode's world
+// bounds, so it keeps the level's shape whatever size that is. The map is a
+// HUD readout (`HudElement::Minimap`), so it starts in the bottom right corner
+// and the HUD editor moves or hides it like the others; M toggles it too. It
+// steps aside while the level overview (Tab) is open.
+
+use bevy::prelude::*;
+use crate::prelude::*;
+use rustbevy_core::world::WorldBounds;
+
+use crate::chat::chat_closed;
+use crate::hud_layout::{HudElement, HudLayout};
+use crate::overview::Overview;
+use crate::ui::Toasts;
+
+pub struct MinimapPlugin;
+
+impl Plugin for MinimapPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, (
+            toggle_minimap.run_if(in_state(AppState::Playing).and_then(chat_closed)),
+            update_minimap,
+        ).chain());
+    }
+}
+
+// How wide the map is drawn; its height follows the level's shape
+const MINIMAP_WIDTH: f32 = 220.0;
+const BACKGROUND_COLOR: Color = Color::srgba(0.0, 0.0, 0.0, 0.5);
+const PLATFORM_COLOR: Color = Color::srgba(0.8, 0.8, 0.8, 0.8);
+const PLAYER_COLOR: Color = Color::srgb(0.4, 0.8, 1.0);
+const FRUIT_COLOR: Color = Color::srgb(1.0, 0.6, 0.2);
+const PLAYER_DOT_SIZE: f32 = 5.0;
+const FRUIT_MARKER_SIZE: f32 = 7.0;
+
+#[derive(Component)]
+struct MinimapUI;
+
+// A shape on the map standing in for something in the level, kept where
+// that is
+#[derive(Component)]
+struct MinimapMark {
+    target: Entity,
+    size: Vec2,
+}
+
+// How big the map is and how far it shrinks the level
+struct MinimapView {
+    size: Vec2,
+    scale: f32,
+}
+
+impl MinimapView {
+    fn new(bounds: WorldBounds) -> Self {
+        let scale = MINIMAP_WIDTH / bounds.width;
+        let size = Vec2::new(bounds.width, bounds.height) * scale;
+        Self { size, scale }
+    }
+
+    // The point on the map, in pixels from its top left corner, for a point in
+    // the level, kept inside the map
+    fn point(&self, world: Vec2) -> Vec2 {
+        let half = self.size / 2.0;
+        let point = (world * self.scale).clamp(-half, half);
+        Vec2::new(half.x + point.x, half.y - point.y)
+    }
+}
+
+fn toggle_minimap(keyboard_input: Res<ButtonInput<KeyCode>>, mut layout: ResMut<HudLayout>, mut toasts: ResMut<Toasts>) {
+    if keyboard_input.just_pressed(KeyCode::KeyM) {
+        let shown = layout.toggle(HudElement::Minimap);
+        toasts.push(if shown { "Minimap ON" } else { "Minimap OFF" });
+    }
+}
+
+fn spawn_mark(parent: &mut ChildBuilder, target: Entity, size: Vec2, color: Color) {
+    parent.spawn((
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                width: Val::Px(size.x),
+                height: Val::Px(size.y),
+                ..default()
+            },
+            background_color: color.into(),
+            // Placed by `update_minimap` on the next frame
+            visibility: Visibility::Hidden,
+            ..default()
+        },
+        MinimapMark { target, size },
+    ));
+}
+
+// Builds the map again whenever a platform, player or fruit comes or goes,
+// and moves every mark to where its target is now. The HUD layout places the
+// map and shows or hides it
+fn update_minimap(
+    mut commands: Commands,
+    overview: Res<Overview>,
+    app_state: Res<State<AppState>>,
+    game_mode: Res<GameMode>,
+    added_query: Query<(), Or<(Added<Platform>, Added<Player>, Added<Fruit>)>>,
+    mut removed_platforms: RemovedComponents<Platform>,
+    mut removed_players: RemovedComponents<Player>,
+    mut removed_fruit: RemovedComponents<Fruit>,
+    platform_query: Query<(Entity, &Platform)>,
+    player_query: Query<Entity, With<Player>>,
+    fruit_query: Query<Entity, With<Fruit>>,
+    ui_query: Query<Entity, With<MinimapUI>>,
+    target_query: Query<&Transform, Without<MinimapMark>>,
+    mut mark_query: Query<(&MinimapMark, &mut Style, &mut Transform, &mut Visibility)>,
+) {
+    let bounds = game_mode.world_bounds();
+    // Kept in the HUD editor so it can be moved there
+    let wanted = !overview.is_open()
+        && matches!(app_state.get(), AppState::Playing | AppState::HudEditor)
+        && *game_mode == GameMode::Classic
+        && bounds.screens() > 1.0;
+    // Read them all, so none are left over for the next frame
+    let removed = removed_platforms.read().count() + removed_players.read().count() + removed_fruit.read().count();
+    if !wanted {
+        for entity in ui_query.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+        return;
+    }
+
+    let view = MinimapView::new(bounds);
+    if ui_query.is_empty() || !added_query.is_empty() || removed > 0 {
+        for entity in ui_query.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+        commands
+            .spawn((
+                NodeBundle {
+                    style: Style {
+                        width: Val::Px(view.size.x),
+                        height: Val::Px(view.size.y),
+                        ..default()
+                    },
+                    background_color: BACKGROUND_COLOR.into(),
+                    ..default()
+                },
+                HudElement::Minimap,
+                MinimapUI,
+            ))
+            .with_children(|parent| {
+                for (entity, platform) in platform_query.iter() {
+                    // At least a pixel, so thin platforms don't vanish
+                    let size = (Vec2::new(platform.width, platform.height) * view.scale).max(Vec2::ONE);
+                    spawn_mark(parent, entity, size, PLATFORM_COLOR);
+                }
+                for fruit in fruit_query.iter() {
+                    spawn_mark(parent, fruit, Vec2::splat(FRUIT_MARKER_SIZE), FRUIT_COLOR);
+                }
+                for player in player_query.iter() {
+                    spawn_mark(parent, player, Vec2::splat(PLAYER_DOT_SIZE), PLAYER_COLOR);
+                }
+            });
+        return;
+    }
+
+    for (mark, mut style, mut transform, mut visibility) in mark_query.iter_mut() {
+        let Ok(target) = target_query.get(mark.target) else {
+            continue;
+        };
+        let top_left = view.point(target.translation.truncate()) - mark.size / 2.0;
+        style.left = Val::Px(top_left.x);
+        style.top = Val::Px(top_left.y);
+        // Sloped platforms are turned on the map too; the UI layout only
+        // moves the node, so the turn is kept
+        transform.rotation = target.rotation;
+        *visibility = Visibility::Inherited;
+    }
+}
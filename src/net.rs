@@ -0,0 +1,321 @@
+// Lightweight matchmaking / relay layer for the online lobby.
+//
+// The host owns the lobby and relays everything: clients only ever talk to the
+// host, and the host forwards whatever the other members need to see. Messages
+// are small text lines so they are easy to inspect with a packet sniffer.
+
+use std::collections::HashMap;
+use std::io::ErrorKind;
+use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4, UdpSocket};
+use std::time::Duration;
+
+use bevy::utils::Instant;
+
+pub type PeerId = u32;
+
+// The host is always peer 0
+pub const HOST_PEER: PeerId = 0;
+pub const DEFAULT_LOBBY_PORT: u16 = 7777;
+
+// Peers that stay silent this long are considered gone
+const PEER_TIMEOUT: Duration = Duration::from_secs(5);
+
+// Crockford base32 keeps codes short and avoids easily confused letters
+const CODE_ALPHABET: &[u8] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+const CODE_LENGTH: usize = 10;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct LobbyMember {
+    pub peer: PeerId,
+    pub name: String,
+    pub ready: bool,
+    pub ping_ms: u32,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum LobbyMessage {
+    Hello { name: String },
+    Welcome { peer: PeerId },
+    Roster { members: Vec<LobbyMember>, seed: u64, mode: String },
+    Ready { ready: bool },
+    Ping { nonce: u64 },
+    Pong { nonce: u64 },
+    Start { seed: u64, mode: String, countdown: f32 },
+    Chat { from: String, text: String },
+    Emote { from: String, emote: String },
+    Leave,
+}
+
+impl LobbyMessage {
+    pub fn encode(&self) -> String {
+        match self {
+            Self::Hello { name } => format!("HELLO|{}", sanitize(name)),
+            Self::Welcome { peer } => format!("WELCOME|{}", peer),
+            Self::Roster { members, seed, mode } => {
+                let members: Vec<String> = members
+                    .iter()
+                    .map(|member| format!("{},{},{},{}", member.peer, sanitize(&member.name), member.ready as u8, member.ping_ms))
+                    .collect();
+                format!("ROSTER|{}|{}|{}", seed, sanitize(mode), members.join(";"))
+            }
+            Self::Ready { ready } => format!("READY|{}", *ready as u8),
+            Self::Ping { nonce } => format!("PING|{}", nonce),
+            Self::Pong { nonce } => format!("PONG|{}", nonce),
+            Self::Start { seed, mode, countdown } => format!("START|{}|{}|{}", seed, sanitize(mode), countdown),
+            Self::Chat { from, text } => format!("CHAT|{}|{}", sanitize(from), sanitize(text)),
+            Self::Emote { from, emote } => format!("EMOTE|{}|{}", sanitize(from), sanitize(emote)),
+            Self::Leave => "LEAVE".to_string(),
+        }
+    }
+
+    pub fn decode(line: &str) -> Option<Self> {
+        let mut fields = line.split('|');
+        let message = match fields.next()? {
+            "HELLO" => Self::Hello { name: fields.next()?.to_string() },
+            "WELCOME" => Self::Welcome { peer: fields.next()?.parse().ok()? },
+            "ROSTER" => {
+                let seed = fields.next()?.parse().ok()?;
+                let mode = fields.next()?.to_string();
+                let members = fields
+                    .next()
+                    .unwrap_or_default()
+                    .split(';')
+                    .filter(|entry| !entry.is_empty())
+                    .map(|entry| {
+                        let mut parts = entry.split(',');
+                        Some(LobbyMember {
+                            peer: parts.next()?.parse().ok()?,
+                            name: parts.next()?.to_string(),
+                            ready: parts.next()? == "1",
+                            ping_ms: parts.next()?.parse().ok()?,
+                        })
+                    })
+                    .collect::<Option<Vec<_>>>()?;
+                Self::Roster { members, seed, mode }
+            }
+            "READY" => Self::Ready { ready: fields.next()? == "1" },
+            "PING" => Self::Ping { nonce: fields.next()?.parse().ok()? },
+            "PONG" => Self::Pong { nonce: fields.next()?.parse().ok()? },
+            "START" => Self::Start {
+                seed: fields.next()?.parse().ok()?,
+                mode: fields.next()?.to_string(),
+                countdown: fields.next()?.parse().ok()?,
+            },
+            "CHAT" => Self::Chat {
+                from: fields.next()?.to_string(),
+                text: fields.next()?.to_string(),
+            },
+            "EMOTE" => Self::Emote {
+                from: fields.next()?.to_string(),
+                emote: fields.next()?.to_string(),
+            },
+            "LEAVE" => Self::Leave,
+            _ => return None,
+        };
+        Some(message)
+    }
+}
+
+// Strip the characters the wire format uses as separators
+fn sanitize(text: &str) -> String {
+    text.chars().filter(|c| !matches!(c, '|' | ';' | ',' | '\n')).collect()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Recipient {
+    Host,
+    Peer(PeerId),
+    Everyone,
+}
+
+// Anything that can carry lobby messages between the host and its members.
+// The UDP implementation below is the only one today; a hosted relay server
+// would slot in behind the same interface.
+pub trait RelayClient: Send + Sync {
+    fn send(&mut self, to: Recipient, message: &LobbyMessage);
+    fn poll(&mut self) -> Vec<(PeerId, LobbyMessage)>;
+    // Peers the host hasn't heard from recently (always empty for clients)
+    fn drop_silent_peers(&mut self) -> Vec<PeerId>;
+    fn lobby_code(&self) -> &str;
+    fn is_host(&self) -> bool;
+    // Why the last failed send failed, if one has since the previous call
+    fn take_error(&mut self) -> Option<String>;
+}
+
+pub struct UdpRelay {
+    socket: UdpSocket,
+    code: String,
+    host_address: Option<SocketAddr>,
+    peers: HashMap<PeerId, (SocketAddr, Instant)>,
+    next_peer: PeerId,
+    send_error: Option<std::io::Error>,
+}
+
+impl UdpRelay {
+    pub fn host(port: u16) -> std::io::Result<Self> {
+        let socket = UdpSocket::bind(SocketAddr::from((Ipv4Addr::UNSPECIFIED, port)))?;
+        socket.set_nonblocking(true)?;
+        let code = encode_lobby_code(SocketAddrV4::new(local_ipv4(), socket.local_addr()?.port()));
+        Ok(Self {
+            socket,
+            code,
+            host_address: None,
+            peers: HashMap::new(),
+            next_peer: HOST_PEER + 1,
+            send_error: None,
+        })
+    }
+
+    pub fn join(code: &str) -> std::io::Result<Self> {
+        let host_address = decode_lobby_code(code)
+            .ok_or_else(|| std::io::Error::new(ErrorKind::InvalidInput, "invalid lobby code"))?;
+        let socket = UdpSocket::bind(SocketAddr::from((Ipv4Addr::UNSPECIFIED, 0)))?;
+        socket.set_nonblocking(true)?;
+        Ok(Self {
+            socket,
+            code: code.to_uppercase(),
+            host_address: Some(SocketAddr::V4(host_address)),
+            peers: HashMap::new(),
+            next_peer: HOST_PEER + 1,
+            send_error: None,
+        })
+    }
+
+    fn send_raw(&mut self, address: SocketAddr, message: &LobbyMessage) {
+        // UDP is fire-and-forget and the lobby re-sends its state every second
+        // anyway, so a failure is only kept to be reported
+        if let Err(error) = self.socket.send_to(message.encode().as_bytes(), address) {
+            self.send_error = Some(error);
+        }
+    }
+}
+
+impl RelayClient for UdpRelay {
+    fn send(&mut self, to: Recipient, message: &LobbyMessage) {
+        match (to, self.host_address) {
+            (Recipient::Host, Some(host)) => self.send_raw(host, message),
+            (Recipient::Peer(peer), None) => {
+                if let Some(&(address, _)) = self.peers.get(&peer) {
+                    self.send_raw(address, message);
+                }
+            }
+            (Recipient::Everyone, None) => {
+                let addresses: Vec<SocketAddr> = self.peers.values().map(|(address, _)| *address).collect();
+                for address in addresses {
+                    self.send_raw(address, message);
+                }
+            }
+            // Clients can only talk to the host, and the host has no host
+            _ => {}
+        }
+    }
+
+    fn poll(&mut self) -> Vec<(PeerId, LobbyMessage)> {
+        let mut received = Vec::new();
+        let mut buffer = [0u8; 2048];
+        loop {
+            let (length, address) = match self.socket.recv_from(&mut buffer) {
+                Ok(packet) => packet,
+                Err(error) if error.kind() == ErrorKind::WouldBlock => break,
+                // Windows reports ICMP "port unreachable" as a recv error; skip it
+                Err(_) => continue,
+            };
+            let Some(message) = std::str::from_utf8(&buffer[..length]).ok().and_then(LobbyMessage::decode) else {
+                continue;
+            };
+
+            let sender = match self.host_address {
+                Some(host) if host == address => HOST_PEER,
+                Some(_) => continue, // Clients ignore anyone but their host
+                None => {
+                    let known = self.peers.iter().find(|(_, (peer_address, _))| *peer_address == address).map(|(peer, _)| *peer);
+                    let peer = known.unwrap_or_else(|| {
+                        let peer = self.next_peer;
+                        self.next_peer += 1;
+                        peer
+                    });
+                    self.peers.insert(peer, (address, Instant::now()));
+                    peer
+                }
+            };
+            if message == LobbyMessage::Leave && self.host_address.is_none() {
+                self.peers.remove(&sender);
+            }
+            received.push((sender, message));
+        }
+        received
+    }
+
+    fn drop_silent_peers(&mut self) -> Vec<PeerId> {
+        let silent: Vec<PeerId> = self
+            .peers
+            .iter()
+            .filter(|(_, (_, last_seen))| last_seen.elapsed() > PEER_TIMEOUT)
+            .map(|(peer, _)| *peer)
+            .collect();
+        for peer in &silent {
+            self.peers.remove(peer);
+        }
+        silent
+    }
+
+    fn lobby_code(&self) -> &str {
+        &self.code
+    }
+
+    fn is_host(&self) -> bool {
+        self.host_address.is_none()
+    }
+
+    fn take_error(&mut self) -> Option<String> {
+        self.send_error.take().map(|error| error.to_string())
+    }
+}
+
+// Address other machines on the LAN can reach us at. Connecting a UDP socket
+// sends nothing; it just makes the OS pick the outgoing interface.
+fn local_ipv4() -> Ipv4Addr {
+    UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0))
+        .and_then(|socket| {
+            socket.connect((Ipv4Addr::new(8, 8, 8, 8), 80))?;
+            socket.local_addr()
+        })
+        .ok()
+        .and_then(|address| match address {
+            SocketAddr::V4(v4) => Some(*v4.ip()),
+            SocketAddr::V6(_) => None,
+        })
+        .unwrap_or(Ipv4Addr::LOCALHOST)
+}
+
+// Packs the host's IPv4 address and port into a 10 character code
+pub fn encode_lobby_code(address: SocketAddrV4) -> String {
+    let mut value = (u64::from(u32::from(*address.ip())) << 16) | u64::from(address.port());
+    let mut code = vec![b'0'; CODE_LENGTH];
+    for slot in code.iter_mut().rev() {
+        *slot = CODE_ALPHABET[(value % 32) as usize];
+        value /= 32;
+    }
+    String::from_utf8(code).unwrap_or_default()
+}
+
+pub fn decode_lobby_code(code: &str) -> Option<SocketAddrV4> {
+    let code = code.trim().to_uppercase();
+    if code.len() != CODE_LENGTH {
+        return None;
+    }
+    let mut value: u64 = 0;
+    for byte in code.bytes() {
+        // Accept the usual Crockford look-alikes
+        let byte = match byte {
+            b'O' => b'0',
+            b'I' | b'L' => b'1',
+            other => other,
+        };
+        let digit = CODE_ALPHABET.iter().position(|c| *c == byte)? as u64;
+        value = value * 32 + digit;
+    }
+    let ip = Ipv4Addr::from((value >> 16) as u32);
+    let port = (value & 0xFFFF) as u16;
+    Some(SocketAddrV4::new(ip, port))
+}
@@ -0,0 +1,213 @@
+// First-run onboarding.
+//
+// The first launch (no onboarding file in the save directory) goes from the
+// loading screen to the Onboarding screen instead of the menu. It walks
+// through a few steps, one at a time: picking a control layout from the
+// built-in presets, then setting the master volume against a test sound and
+// the music. After the last step the player is dropped straight into a
+// Classic run, where the contextual hints take over. ESC on any step skips
+// the rest and goes to the main menu; either way it isn't shown again.
+//
+// There's no language step: the game only has its English text so far.
+
+use bevy::prelude::*;
+use crate::prelude::*;
+
+use crate::game::{read_save_file, write_save_file};
+use crate::keybindings::{built_in_presets, layout_summary};
+use crate::replay::ReplayPlayback;
+use crate::settings::Settings;
+use crate::ui::despawn_screen;
+use crate::visual::VisualTest;
+
+pub struct OnboardingPlugin;
+
+impl Plugin for OnboardingPlugin {
+    fn build(&self, app: &mut App) {
+        // Replays and visual tests start from the menu and have no one to ask
+        let unattended = app.world().contains_resource::<ReplayPlayback>() || app.world().contains_resource::<VisualTest>();
+        app.insert_resource(Onboarding { pending: !unattended && read_save_file(ONBOARDING_FILE).is_none(), ..default() })
+            .add_systems(OnEnter(AppState::Onboarding), start_onboarding)
+            .add_systems(OnExit(AppState::Onboarding), despawn_screen::<OnboardingUI>)
+            .add_systems(Update, (handle_onboarding_input, draw_onboarding_step).chain().run_if(in_state(AppState::Onboarding)));
+    }
+}
+
+// Written once onboarding is done or skipped
+const ONBOARDING_FILE: &str = "onboarding.txt";
+const SELECTED_COLOR: Color = Color::srgb(1.0, 1.0, 0.0); // Yellow
+const OPTION_COLOR: Color = Color::srgb(0.8, 0.8, 0.8);
+const HELP_COLOR: Color = Color::srgb(0.6, 0.6, 0.6); // Gray
+// Blocks in the volume bar, one per volume step
+const VOLUME_BLOCKS: usize = 10;
+
+// The steps, in the order they're taken
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum OnboardingStep {
+    #[default]
+    Controls,
+    Volume,
+}
+
+impl OnboardingStep {
+    const ALL: [Self; 2] = [Self::Controls, Self::Volume];
+
+    fn title(self) -> &'static str {
+        match self {
+            Self::Controls => "Pick your controls",
+            Self::Volume => "Set the volume",
+        }
+    }
+
+    fn help(self) -> &'static str {
+        match self {
+            Self::Controls => "UP/DOWN: choose   ENTER: next   ESC: skip",
+            Self::Volume => "LEFT/RIGHT: volume   ENTER: start playing   ESC: skip",
+        }
+    }
+
+    // The step after this one, None after the last
+    fn next(self) -> Option<Self> {
+        let index = Self::ALL.iter().position(|&step| step == self)?;
+        Self::ALL.get(index + 1).copied()
+    }
+}
+
+// Whether this launch still has onboarding to go through, the step it's on
+// and the control preset picked on the Controls step
+#[derive(Resource, Default)]
+pub struct Onboarding {
+    pub pending: bool,
+    step: OnboardingStep,
+    preset: usize,
+}
+
+#[derive(Component)]
+struct OnboardingUI;
+
+fn start_onboarding(mut onboarding: ResMut<Onboarding>) {
+    onboarding.step = OnboardingStep::default();
+    onboarding.preset = 0;
+}
+
+// Done or skipped, onboarding isn't offered again
+fn finish_onboarding(onboarding: &mut Onboarding, errors: &mut EventWriter<RuntimeErrorEvent>) {
+    onboarding.pending = false;
+    if let Err(error) = write_save_file(ONBOARDING_FILE, "done", "onboarding") {
+        errors.send(error);
+    }
+}
+
+fn handle_onboarding_input(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut onboarding: ResMut<Onboarding>,
+    mut input_map: ResMut<InputMap>,
+    mut settings: ResMut<Settings>,
+    mut game_mode: ResMut<GameMode>,
+    mut coop_mode: ResMut<CoopMode>,
+    mut game_rng: ResMut<GameRng>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut sound_events: EventWriter<PlaySoundEvent>,
+    mut errors: EventWriter<RuntimeErrorEvent>,
+) {
+    if keyboard_input.just_pressed(KeyCode::Escape) {
+        finish_onboarding(&mut onboarding, &mut errors);
+        next_state.set(AppState::MainMenu);
+        return;
+    }
+    match onboarding.step {
+        OnboardingStep::Controls => {
+            let presets = built_in_presets();
+            let steps = keyboard_input.just_pressed(KeyCode::ArrowDown) as i32 - keyboard_input.just_pressed(KeyCode::ArrowUp) as i32;
+            if steps != 0 {
+                onboarding.preset = (onboarding.preset as i32 + steps).rem_euclid(presets.len() as i32) as usize;
+            }
+            if keyboard_input.just_pressed(KeyCode::Enter) {
+                if let Some(preset) = presets.into_iter().nth(onboarding.preset) {
+                    *input_map = preset.input_map;
+                }
+            }
+        }
+        OnboardingStep::Volume => {
+            let steps = keyboard_input.just_pressed(KeyCode::ArrowRight) as i32 - keyboard_input.just_pressed(KeyCode::ArrowLeft) as i32;
+            if steps != 0 {
+                Settings::step_volume(&mut settings.master_volume, steps);
+                // Something to judge the new volume by, over the music
+                sound_events.send(PlaySoundEvent { sound_type: SoundType::Collect, position: None });
+            }
+        }
+    }
+    if !keyboard_input.just_pressed(KeyCode::Enter) {
+        return;
+    }
+    match onboarding.step.next() {
+        Some(step) => onboarding.step = step,
+        None => {
+            finish_onboarding(&mut onboarding, &mut errors);
+            *game_mode = GameMode::Classic;
+            coop_mode.0 = false;
+            game_rng.stop_sharing();
+            next_state.set(AppState::Playing);
+        }
+    }
+}
+
+fn onboarding_text(text: impl Into<String>, font_size: f32, color: Color, y: f32) -> Text2dBundle {
+    Text2dBundle {
+        text: Text::from_section(text, TextStyle { font_size, color, ..default() }),
+        transform: Transform::from_translation(Vec3::new(0.0, y, 10.0)),
+        ..default()
+    }
+}
+
+// The step's lines, drawn again whenever anything on them changes
+fn draw_onboarding_step(
+    mut commands: Commands,
+    onboarding: Res<Onboarding>,
+    settings: Res<Settings>,
+    ui_query: Query<Entity, With<OnboardingUI>>,
+) {
+    if !onboarding.is_changed() && !settings.is_changed() {
+        return;
+    }
+    for entity in ui_query.iter() {
+        commands.entity(entity).despawn();
+    }
+    let step_number = OnboardingStep::ALL.iter().position(|&step| step == onboarding.step).unwrap_or(0) + 1;
+    let heading = format!("Step {} of {}: {}", step_number, OnboardingStep::ALL.len(), onboarding.step.title());
+    commands.spawn((onboarding_text("WELCOME", 60.0, Color::srgb(0.7, 0.7, 1.0), 260.0), OnboardingUI)); // Light blue
+    commands.spawn((onboarding_text(heading, 32.0, Color::WHITE, 190.0), OnboardingUI));
+
+    match onboarding.step {
+        OnboardingStep::Controls => {
+            let presets = built_in_presets();
+            for (index, preset) in presets.iter().enumerate() {
+                let selected = index == onboarding.preset;
+                let line = if selected { format!("> {} <", preset.name) } else { preset.name.clone() };
+                let color = if selected { SELECTED_COLOR } else { OPTION_COLOR };
+                commands.spawn((onboarding_text(line, 30.0, color, 110.0 - index as f32 * 45.0), OnboardingUI));
+            }
+            if let Some(preset) = presets.get(onboarding.preset) {
+                commands.spawn((onboarding_text(layout_summary(&preset.input_map), 20.0, OPTION_COLOR, -150.0), OnboardingUI));
+            }
+            commands.spawn((
+                onboarding_text("Gamepads work too. Every key can be changed later with K on the main menu", 20.0, HELP_COLOR, -190.0),
+                OnboardingUI,
+            ));
+        }
+        OnboardingStep::Volume => {
+            let filled = (settings.master_volume * VOLUME_BLOCKS as f32).round() as usize;
+            let bar = format!("[{}{}]", "#".repeat(filled), "-".repeat(VOLUME_BLOCKS - filled));
+            commands.spawn((
+                onboarding_text(format!("Master volume: {:.0}%", settings.master_volume * 100.0), 30.0, SELECTED_COLOR, 80.0),
+                OnboardingUI,
+            ));
+            commands.spawn((onboarding_text(bar, 30.0, SELECTED_COLOR, 30.0), OnboardingUI));
+            commands.spawn((
+                onboarding_text("A test sound plays with each change. Music and effects can be set apart in Settings (E on the menu)", 20.0, HELP_COLOR, -40.0),
+                OnboardingUI,
+            ));
+        }
+    }
+    commands.spawn((onboarding_text(onboarding.step.help(), 24.0, HELP_COLOR, -320.0), OnboardingUI));
+}
@@ -0,0 +1,204 @@
+// Level overview for Classic runs.
+//
+// Holding Tab zooms the camera out to show the whole level with its edges
+// outlined, marks the players, the fruit, the checkpoints and anything
+// dangerous (spikes, lava and enemies), and slows the game right down so
+// you can look around without falling off something. The follow camera moves
+// to the middle of the level while the overview is open.
+
+use bevy::prelude::*;
+use bevy::render::camera::ScalingMode;
+use crate::prelude::*;
+use rustbevy_core::config::{WINDOW_HEIGHT, WINDOW_WIDTH, WORLD_HEIGHT, WORLD_WIDTH};
+
+use crate::chat::chat_closed;
+
+pub struct OverviewPlugin;
+
+impl Plugin for OverviewPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Overview>()
+            .add_systems(Update, (toggle_overview.run_if(chat_closed), update_overview).chain());
+    }
+}
+
+// Room left around the level when zoomed out to it
+const OVERVIEW_MARGIN: f32 = 1.1;
+const OVERVIEW_GAME_SPEED: f32 = 0.15;
+const MARKER_Z: f32 = 14.0;
+const BORDER_THICKNESS: f32 = 4.0;
+// Markers and the border are sized for a camera with the whole window
+const WINDOW_VIEW: Vec2 = Vec2::new(WINDOW_WIDTH, WINDOW_HEIGHT);
+
+#[derive(Resource, Default)]
+pub struct Overview {
+    open: bool,
+}
+
+impl Overview {
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    // Multiplier for how fast game time passes
+    pub fn game_speed(&self) -> f32 {
+        if self.open {
+            OVERVIEW_GAME_SPEED
+        } else {
+            1.0
+        }
+    }
+}
+
+// Everything drawn only while the overview is open
+#[derive(Component)]
+pub struct OverviewUI;
+
+// Label that follows whatever it marks around
+#[derive(Component)]
+pub struct OverviewMarker {
+    target: Entity,
+    offset: f32,
+}
+
+pub fn toggle_overview(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    app_state: Res<State<AppState>>,
+    game_mode: Res<GameMode>,
+    mut overview: ResMut<Overview>,
+) {
+    let open = keyboard_input.pressed(KeyCode::Tab) && *app_state.get() == AppState::Playing && *game_mode == GameMode::Classic;
+    if overview.open != open {
+        overview.open = open;
+    }
+}
+
+// Camera zoom that fits the whole level in the window
+// The zoom that fits the whole level in a camera whose regular view is `view`
+// (the window's, or a split screen's share of it)
+fn overview_zoom(view: Vec2) -> f32 {
+    (WORLD_WIDTH / view.x).max(WORLD_HEIGHT / view.y) * OVERVIEW_MARGIN
+}
+
+// A camera's view at the regular zoom
+fn regular_view(projection: &OrthographicProjection) -> Vec2 {
+    match projection.scaling_mode {
+        ScalingMode::Fixed { width, height } => Vec2::new(width, height),
+        _ => WINDOW_VIEW,
+    }
+}
+
+// Markers are scaled up with the zoom so they stay readable
+fn spawn_marker(commands: &mut Commands, target: Entity, label: &str, color: Color, offset: f32) {
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                label,
+                TextStyle {
+                    font_size: 24.0,
+                    color,
+                    ..default()
+                },
+            ),
+            transform: Transform::from_translation(Vec3::new(0.0, 0.0, MARKER_Z)).with_scale(Vec3::splat(overview_zoom(WINDOW_VIEW))),
+            ..default()
+        },
+        OverviewMarker { target, offset: offset * overview_zoom(WINDOW_VIEW) },
+        OverviewUI,
+        WorldSpace,
+    ));
+}
+
+fn spawn_level_border(commands: &mut Commands) {
+    let color = Color::srgba(1.0, 1.0, 1.0, 0.5);
+    let thickness = BORDER_THICKNESS * overview_zoom(WINDOW_VIEW);
+    let sides = [
+        (Vec2::new(0.0, WORLD_HEIGHT / 2.0), Vec2::new(WORLD_WIDTH, thickness)),
+        (Vec2::new(0.0, -WORLD_HEIGHT / 2.0), Vec2::new(WORLD_WIDTH, thickness)),
+        (Vec2::new(-WORLD_WIDTH / 2.0, 0.0), Vec2::new(thickness, WORLD_HEIGHT)),
+        (Vec2::new(WORLD_WIDTH / 2.0, 0.0), Vec2::new(thickness, WORLD_HEIGHT)),
+    ];
+    for (position, size) in sides {
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color,
+                    custom_size: Some(size),
+                    ..default()
+                },
+                transform: Transform::from_translation(position.extend(MARKER_Z)),
+                ..default()
+            },
+            OverviewUI,
+        ));
+    }
+
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                "LEVEL OVERVIEW (release Tab to play)",
+                TextStyle {
+                    font_size: 26.0,
+                    color: Color::srgb(1.0, 1.0, 1.0),
+                    ..default()
+                },
+            ),
+            transform: Transform::from_translation(Vec3::new(0.0, WINDOW_HEIGHT / 2.0 - 20.0, MARKER_Z)),
+            ..default()
+        },
+        OverviewUI,
+    ));
+}
+
+pub fn update_overview(
+    mut commands: Commands,
+    overview: Res<Overview>,
+    player_query: Query<Entity, With<Player>>,
+    fruit_query: Query<Entity, With<Fruit>>,
+    checkpoint_query: Query<Entity, With<Checkpoint>>,
+    hazard_query: Query<(Entity, Option<&HazardArea>), With<Hazard>>,
+    target_query: Query<&Transform, Without<OverviewMarker>>,
+    mut marker_query: Query<(Entity, &OverviewMarker, &mut Transform)>,
+    ui_query: Query<Entity, With<OverviewUI>>,
+    mut camera_query: Query<&mut OrthographicProjection, With<MainCamera>>,
+) {
+    if overview.is_changed() {
+        // Each camera zooms out to fit the level in its own view
+        for mut projection in camera_query.iter_mut() {
+            projection.scale = if overview.open { overview_zoom(regular_view(&projection)) } else { 1.0 };
+        }
+
+        for entity in ui_query.iter() {
+            commands.entity(entity).despawn();
+        }
+        if overview.open {
+            spawn_level_border(&mut commands);
+            for player in player_query.iter() {
+                spawn_marker(&mut commands, player, "YOU", Color::srgb(0.4, 0.8, 1.0), 50.0);
+            }
+            for fruit in fruit_query.iter() {
+                spawn_marker(&mut commands, fruit, "FRUIT", Color::srgb(1.0, 0.6, 0.2), 35.0);
+            }
+            for checkpoint in checkpoint_query.iter() {
+                spawn_marker(&mut commands, checkpoint, "CHECKPOINT", Color::srgb(0.5, 1.0, 0.5), 45.0);
+            }
+            // Spikes and lava by name; anything else dangerous is an enemy
+            for (hazard, area) in hazard_query.iter() {
+                let label = area.map_or("ENEMY".to_string(), |area| area.0.kind.name().to_uppercase());
+                spawn_marker(&mut commands, hazard, &label, Color::srgb(1.0, 0.3, 0.3), 30.0);
+            }
+        }
+        return;
+    }
+
+    // Keep the labels above whatever they mark
+    for (entity, marker, mut transform) in marker_query.iter_mut() {
+        match target_query.get(marker.target) {
+            Ok(target) => {
+                transform.translation.x = target.translation.x;
+                transform.translation.y = target.translation.y + marker.offset;
+            }
+            Err(_) => commands.entity(entity).despawn(),
+        }
+    }
+}
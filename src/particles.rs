@@ -0,0 +1,277 @@
+// Small sprite particle system.
+//
+// An `EmitterPreset` describes one kind of burst (how many particles, how fast,
+// how long they live, their colors). `emit` spawns a burst of short-lived
+// sprites that move, fall, slow down and fade out in `update_particles`.
+// Fireworks are rockets that fly up and explode into a `FIREWORK_BURST`.
+// Celebrations are drawn over the screen, so their particles are `ScreenSpace`
+// and stay put while the camera follows the player; the gameplay bursts
+// (fruit, landing dust, deaths) happen in the world. Both are `Transient`, so
+// rockets and sparks that leave the screen are culled early.
+//
+// Gameplay systems ask for effects with a `SpawnParticles` event, which
+// `spawn_requested_particles` turns into bursts.
+
+use bevy::diagnostic::Diagnostics;
+use bevy::prelude::*;
+use crate::prelude::*;
+use rustbevy_core::config::{WINDOW_HEIGHT, WINDOW_WIDTH};
+use rustbevy_core::rng::SeededRng;
+
+use crate::game::clock_seed;
+use crate::profiling::{self, millis_since, Instant};
+
+pub struct ParticlesPlugin;
+
+impl Plugin for ParticlesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<SpawnParticles>()
+            .add_systems(Update, (spawn_requested_particles, update_fireworks, update_particles));
+    }
+}
+
+pub struct EmitterPreset<'a> {
+    pub count: usize,
+    pub speed: (f32, f32),
+    // How far (in radians) particles may stray from the burst's direction
+    pub spread: f32,
+    pub gravity: f32,
+    pub drag: f32, // Fraction of velocity lost per second
+    pub lifetime: (f32, f32),
+    pub size: Vec2,
+    pub colors: &'a [Color],
+    // Drawn over the screen rather than in the world
+    pub screen_space: bool,
+}
+
+const CELEBRATION_COLORS: &[Color] = &[
+    Color::srgb(1.0, 0.3, 0.3),
+    Color::srgb(1.0, 0.8, 0.2),
+    Color::srgb(0.3, 1.0, 0.4),
+    Color::srgb(0.3, 0.7, 1.0),
+    Color::srgb(0.9, 0.4, 1.0),
+];
+
+// Paper confetti thrown up and inwards from a screen corner
+pub const CONFETTI: EmitterPreset<'static> = EmitterPreset {
+    count: 60,
+    speed: (350.0, 750.0),
+    spread: 0.5,
+    gravity: 600.0,
+    drag: 1.2,
+    lifetime: (1.5, 2.5),
+    size: Vec2::new(8.0, 5.0),
+    colors: CELEBRATION_COLORS,
+    screen_space: true,
+};
+
+// Sparks of an exploding firework, in every direction
+pub const FIREWORK_BURST: EmitterPreset<'static> = EmitterPreset {
+    count: 45,
+    speed: (120.0, 320.0),
+    spread: std::f32::consts::PI,
+    gravity: 150.0,
+    drag: 1.5,
+    lifetime: (0.8, 1.4),
+    size: Vec2::new(5.0, 5.0),
+    colors: CELEBRATION_COLORS,
+    screen_space: true,
+};
+
+// Golden sparkles around a fruit that was picked up
+pub const FRUIT_BURST: EmitterPreset<'static> = EmitterPreset {
+    count: 20,
+    speed: (100.0, 260.0),
+    spread: std::f32::consts::PI,
+    gravity: 250.0,
+    drag: 2.5,
+    lifetime: (0.35, 0.6),
+    size: Vec2::new(5.0, 5.0),
+    colors: &[Color::srgb(1.0, 0.85, 0.3), Color::srgb(1.0, 1.0, 0.7), Color::srgb(1.0, 0.5, 0.3)],
+    screen_space: false,
+};
+
+// A few puffs to either side of a player's feet
+pub const LANDING_DUST: EmitterPreset<'static> = EmitterPreset {
+    count: 8,
+    speed: (40.0, 110.0),
+    spread: 1.3,
+    gravity: 60.0,
+    drag: 3.0,
+    lifetime: (0.25, 0.45),
+    size: Vec2::new(5.0, 4.0),
+    colors: &[Color::srgb(0.75, 0.7, 0.62), Color::srgb(0.6, 0.57, 0.52)],
+    screen_space: false,
+};
+
+pub const DEATH_EXPLOSION: EmitterPreset<'static> = EmitterPreset {
+    count: 40,
+    speed: (150.0, 420.0),
+    spread: std::f32::consts::PI,
+    gravity: 450.0,
+    drag: 1.0,
+    lifetime: (0.6, 1.1),
+    size: Vec2::new(6.0, 6.0),
+    colors: &[Color::srgb(1.0, 0.3, 0.2), Color::srgb(1.0, 0.6, 0.1), Color::srgb(0.3, 0.3, 0.3)],
+    screen_space: false,
+};
+
+const FIREWORK_SPEED: f32 = 650.0;
+const PARTICLE_Z: f32 = 20.0; // In front of the level and world-space UI
+
+#[derive(Component)]
+pub struct Particle {
+    velocity: Vec2,
+    gravity: f32,
+    drag: f32,
+    spin: f32,
+    lifetime: Timer,
+}
+
+// A rocket that explodes into a burst when its fuse runs out
+#[derive(Component)]
+pub struct Firework {
+    fuse: Timer,
+    color: Color,
+}
+
+// Spreads `preset.count` particles around `direction`; the rng only affects looks
+pub fn emit(commands: &mut Commands, preset: &EmitterPreset, origin: Vec2, direction: f32, rng: &mut SeededRng) {
+    for _ in 0..preset.count {
+        let angle = direction + (rng.fraction() * 2.0 - 1.0) * preset.spread;
+        let speed = rng.range(preset.speed.0, preset.speed.1);
+        let color = preset.colors[rng.below(preset.colors.len())];
+        let mut particle = commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color,
+                    custom_size: Some(preset.size),
+                    ..default()
+                },
+                transform: Transform::from_translation(origin.extend(PARTICLE_Z)),
+                ..default()
+            },
+            Particle {
+                velocity: Vec2::from_angle(angle) * speed,
+                gravity: preset.gravity,
+                drag: preset.drag,
+                spin: (rng.fraction() * 2.0 - 1.0) * 10.0,
+                lifetime: Timer::from_seconds(rng.range(preset.lifetime.0, preset.lifetime.1), TimerMode::Once),
+            },
+            Transient::new(preset.lifetime.1),
+            Budgeted::new(BudgetCategory::Particles, 0),
+        ));
+        if preset.screen_space {
+            particle.insert(ScreenSpace);
+        }
+    }
+}
+
+pub fn launch_firework(commands: &mut Commands, origin: Vec2, fuse_seconds: f32, color: Color) {
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color,
+                custom_size: Some(Vec2::new(6.0, 14.0)),
+                ..default()
+            },
+            transform: Transform::from_translation(origin.extend(PARTICLE_Z)),
+            ..default()
+        },
+        Firework {
+            fuse: Timer::from_seconds(fuse_seconds, TimerMode::Once),
+            color,
+        },
+        // A little longer than the fuse, so it gets to explode
+        Transient::new(fuse_seconds + 1.0),
+        // Rockets are few and each one is a whole burst, so sparks go first
+        Budgeted::new(BudgetCategory::Particles, 1),
+        ScreenSpace,
+    ));
+}
+
+// The celebration preset: confetti from both bottom corners plus a few
+// fireworks. `fireworks` controls how big the show is.
+pub fn celebrate(commands: &mut Commands, fireworks: usize, seed: u64) {
+    let mut rng = SeededRng::new(seed);
+    let bottom = -WINDOW_HEIGHT / 2.0;
+    let corner_x = WINDOW_WIDTH / 2.0 - 40.0;
+    emit(commands, &CONFETTI, Vec2::new(-corner_x, bottom), std::f32::consts::FRAC_PI_2 - 0.4, &mut rng);
+    emit(commands, &CONFETTI, Vec2::new(corner_x, bottom), std::f32::consts::FRAC_PI_2 + 0.4, &mut rng);
+
+    for index in 0..fireworks {
+        let x = (rng.fraction() - 0.5) * (WINDOW_WIDTH - 300.0);
+        let color = CELEBRATION_COLORS[index % CELEBRATION_COLORS.len()];
+        let fuse = 0.6 + index as f32 * 0.35 + rng.fraction() * 0.2;
+        launch_firework(commands, Vec2::new(x, bottom), fuse, color);
+    }
+}
+
+fn spawn_requested_particles(
+    mut commands: Commands,
+    graphics: Res<GraphicsQuality>,
+    mut particle_events: EventReader<SpawnParticles>,
+) {
+    if !graphics.particles() {
+        particle_events.clear();
+        return;
+    }
+    for (index, event) in particle_events.read().enumerate() {
+        let seed = clock_seed().wrapping_add(index as u64);
+        let mut rng = SeededRng::new(seed);
+        let up = std::f32::consts::FRAC_PI_2;
+        match event.effect {
+            ParticleEffect::FruitBurst => emit(&mut commands, &FRUIT_BURST, event.position, up, &mut rng),
+            ParticleEffect::LandingDust => emit(&mut commands, &LANDING_DUST, event.position, up, &mut rng),
+            ParticleEffect::DeathExplosion => emit(&mut commands, &DEATH_EXPLOSION, event.position, up, &mut rng),
+            ParticleEffect::Confetti { fireworks } => celebrate(&mut commands, fireworks, seed),
+        }
+    }
+}
+
+pub fn update_fireworks(
+    time: Res<Time>,
+    mut commands: Commands,
+    mut firework_query: Query<(Entity, &mut Firework, &mut Transform)>,
+) {
+    for (entity, mut firework, mut transform) in firework_query.iter_mut() {
+        transform.translation.y += FIREWORK_SPEED * time.delta_seconds();
+        if firework.fuse.tick(time.delta()).finished() {
+            let origin = transform.translation.truncate();
+            let seed = entity.to_bits() ^ time.elapsed().as_nanos() as u64;
+            let burst = EmitterPreset {
+                colors: std::slice::from_ref(&firework.color),
+                ..FIREWORK_BURST
+            };
+            // Mostly the rocket's own color with a few mixed sparks
+            emit(&mut commands, &burst, origin, 0.0, &mut SeededRng::new(seed));
+            emit(&mut commands, &EmitterPreset { count: 15, ..FIREWORK_BURST }, origin, 0.0, &mut SeededRng::new(seed.wrapping_add(1)));
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+pub fn update_particles(
+    time: Res<Time>,
+    mut commands: Commands,
+    mut particle_query: Query<(Entity, &mut Particle, &mut Transform, &mut Sprite)>,
+    mut diagnostics: Diagnostics,
+) {
+    let started = Instant::now();
+    let delta = time.delta_seconds();
+    for (entity, mut particle, mut transform, mut sprite) in particle_query.iter_mut() {
+        if particle.lifetime.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        particle.velocity.y -= particle.gravity * delta;
+        let drag = (1.0 - particle.drag * delta).max(0.0);
+        particle.velocity *= drag;
+        transform.translation += (particle.velocity * delta).extend(0.0);
+        transform.rotate_z(particle.spin * delta);
+
+        // Fade out over the last part of the particle's life
+        sprite.color.set_alpha(particle.lifetime.fraction_remaining().min(0.3) / 0.3);
+    }
+    diagnostics.add_measurement(&profiling::PARTICLES, || millis_since(started));
+}
@@ -0,0 +1,650 @@
+// Local party mode: Fruit Grab, Tag and Territory rounds and their results.
+
+use bevy::prelude::*;
+use crate::prelude::*;
+use rustbevy_core::config::{JUMP_SPEED, PLAYER_SIZE, WINDOW_HEIGHT, WINDOW_WIDTH};
+
+use crate::fruit::setup_fruits_with_seed;
+use crate::pool::{EntityPool, LevelTeardown};
+use crate::physics::{apply_velocity, player_movement};
+use crate::ui::{despawn_screen, GameUI};
+
+pub struct PartyPlugin;
+
+impl Plugin for PartyPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PartyRound>()
+            .init_resource::<SelectedPartyGame>()
+            .add_systems(OnEnter(AppState::PartyResults), enter_party_results)
+            .add_systems(OnExit(AppState::PartyResults), despawn_screen::<PartyResultsUI>)
+            .add_systems(Update, (
+                handle_party_results_input.run_if(in_state(AppState::PartyResults)),
+                (
+                    party_player_bumping,
+                    (party_fruit_collection, party_fruit_spawner).run_if(resource_equals(GameMode::FruitGrab)),
+                    (tag_players, tick_tag_grace).run_if(resource_equals(GameMode::Tag)),
+                    (claim_platforms, tint_platforms, territory_scores).chain(),
+                    party_player_respawn,
+                    party_round_timer,
+                    update_party_ui,
+                ).run_if(in_state(AppState::Playing).and_then(in_party_mode)),
+            ))
+            .add_systems(FixedUpdate, apply_bump_knockback
+                .after(player_movement)
+                .before(apply_velocity)
+                .run_if(in_state(AppState::Playing).and_then(in_party_mode)));
+    }
+}
+
+// Party mode (Fruit Grab) tuning
+const PARTY_ROUND_SECONDS: f32 = 60.0;
+const PARTY_FRUIT_RESPAWN_SECONDS: f32 = 4.0;
+const PARTY_MAX_FRUITS: usize = 3;
+const PARTY_BUMP_SPEED: f32 = 450.0; // Horizontal knockback when two players collide
+const PARTY_BUMP_DECAY: f32 = 6.0; // How quickly knockback fades (per second)
+const TAG_ROUND_SECONDS: f32 = 90.0;
+const TAG_GRACE_SECONDS: f32 = 1.5; // Newly tagged players can't tag anyone straight away
+const PLATFORM_TINT_SECONDS: f32 = 1.5; // How long a platform keeps the color of whoever stood on it
+const PARTY_PLAYER_COLORS: [Color; 4] = [
+    Color::srgb(0.0, 0.5, 1.0), // Blue
+    Color::srgb(1.0, 0.2, 0.2), // Red
+    Color::srgb(0.2, 0.9, 0.2), // Green
+    Color::srgb(1.0, 0.9, 0.1), // Yellow
+];
+
+// Horizontal knockback left over from bumping into another player
+#[derive(Component)]
+struct Bump(f32);
+
+// Tag mode: this player is "it" and can tag others once the grace period is over
+#[derive(Component)]
+struct Tagged {
+    grace: Timer,
+}
+
+// Party modes: the last player to stand on a platform. Outside Territory the
+// tint fades back to the platform's own color after a moment.
+#[derive(Component, Default)]
+pub struct PlatformOwner {
+    slot: Option<usize>,
+    tint: f32,
+    // The platform's color from before it was tinted, while it is
+    own_color: Option<Color>,
+}
+
+#[derive(Component)]
+struct PartyScoreText(usize);
+
+#[derive(Component)]
+struct PartyTimerText;
+
+#[derive(Component)]
+struct PartyResultsUI;
+
+#[derive(Component)]
+pub struct PartyGameText;
+
+// Party game picked on the main menu (TAB cycles through them)
+#[derive(Resource)]
+pub struct SelectedPartyGame(pub GameMode);
+
+impl Default for SelectedPartyGame {
+    fn default() -> Self {
+        Self(GameMode::FruitGrab)
+    }
+}
+
+// State of a party round. Scores are fruits grabbed in Fruit Grab, seconds
+// survived untagged in Tag and the percentage of platforms owned in Territory.
+#[derive(Resource)]
+pub struct PartyRound {
+    pub player_count: usize,
+    scores: Vec<u32>,
+    round_timer: Timer,
+    fruit_timer: Timer,
+    pub starting_it: usize,
+}
+
+impl PartyRound {
+    pub fn new(mode: GameMode, player_count: usize) -> Self {
+        let round_seconds = if mode == GameMode::Tag { TAG_ROUND_SECONDS } else { PARTY_ROUND_SECONDS };
+        Self {
+            player_count,
+            scores: vec![0; player_count],
+            round_timer: Timer::from_seconds(round_seconds, TimerMode::Once),
+            fruit_timer: Timer::from_seconds(PARTY_FRUIT_RESPAWN_SECONDS, TimerMode::Repeating),
+            starting_it: 0,
+        }
+    }
+}
+
+impl Default for PartyRound {
+    fn default() -> Self {
+        Self::new(GameMode::FruitGrab, 2)
+    }
+}
+
+fn in_party_mode(game_mode: Res<GameMode>) -> bool {
+    game_mode.is_party()
+}
+
+fn enter_party_results(
+    mut commands: Commands,
+    party_round: Res<PartyRound>,
+    game_mode: Res<GameMode>,
+    mut particle_events: EventWriter<SpawnParticles>,
+) {
+    // Set up the podium screen
+    setup_party_results(&mut commands, &party_round, *game_mode);
+    // End of the game: the full celebration
+    particle_events.send(SpawnParticles { effect: ParticleEffect::Confetti { fireworks: 6 }, position: Vec2::ZERO });
+}
+
+// Party Mode (Fruit Grab) Systems
+fn party_spawn_position(slot: usize, player_count: usize) -> Vec3 {
+    // Spread the players across the starting platform
+    let offset = slot as f32 - (player_count as f32 - 1.0) / 2.0;
+    Vec3::new(offset * 55.0, 200.0, 0.0)
+}
+
+pub fn spawn_party_player(commands: &mut Commands, slot: usize, player_count: usize) -> Entity {
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: PARTY_PLAYER_COLORS[slot],
+                custom_size: Some(Vec2::splat(PLAYER_SIZE)),
+                ..default()
+            },
+            transform: Transform::from_translation(party_spawn_position(slot, player_count)),
+            ..default()
+        },
+        Player,
+        PartyPlayer { slot },
+        PlayerControls::party_slot(slot),
+        Velocity { x: 0.0, y: 0.0 },
+        Grounded(false),
+        WallContact::default(),
+        JumpState::default(),
+        JumpPress::default(),
+        Collider { size: PLAYER_SIZE },
+        AnimationState::default(),
+        Bump(0.0),
+    )).id()
+}
+
+pub fn setup_party_ui(mut commands: Commands, player_count: usize) {
+    // Round timer in center top
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                format!("Time: {}", PARTY_ROUND_SECONDS as u32),
+                TextStyle {
+                    font_size: 40.0,
+                    color: Color::srgb(1.0, 0.5, 0.0), // Orange
+                    ..default()
+                },
+            ),
+            transform: Transform::from_translation(Vec3::new(0.0, WINDOW_HEIGHT / 2.0 - 50.0, 10.0)),
+            ..default()
+        },
+        PartyTimerText,
+        GameUI,
+    ));
+
+    // One score counter per player, in the player's color, along the bottom
+    let spacing = WINDOW_WIDTH / player_count as f32;
+    for (slot, color) in PARTY_PLAYER_COLORS.iter().enumerate().take(player_count) {
+        let x = -WINDOW_WIDTH / 2.0 + spacing * (slot as f32 + 0.5);
+        commands.spawn((
+            Text2dBundle {
+                text: Text::from_section(
+                    format!("P{}: 0", slot + 1),
+                    TextStyle {
+                        font_size: 35.0,
+                        color: *color,
+                        ..default()
+                    },
+                ),
+                transform: Transform::from_translation(Vec3::new(x, -WINDOW_HEIGHT / 2.0 + 30.0, 10.0)),
+                ..default()
+            },
+            PartyScoreText(slot),
+            GameUI,
+        ));
+    }
+}
+
+fn party_player_bumping(
+    mut player_query: Query<(&mut Transform, &mut Velocity, &mut Bump, &Collider), With<PartyPlayer>>,
+) {
+    let mut combinations = player_query.iter_combinations_mut();
+    while let Some([(mut transform_a, mut velocity_a, mut bump_a, collider_a), (mut transform_b, mut velocity_b, mut bump_b, collider_b)]) =
+        combinations.fetch_next()
+    {
+        let delta = transform_b.translation - transform_a.translation;
+        let touching = (collider_a.size + collider_b.size) / 2.0;
+        let overlap_x = touching - delta.x.abs();
+        let overlap_y = touching - delta.y.abs();
+        if overlap_x <= 0.0 || overlap_y <= 0.0 {
+            continue;
+        }
+
+        // Push both players apart and knock them back in opposite directions
+        let direction = if delta.x >= 0.0 { 1.0 } else { -1.0 };
+        transform_a.translation.x -= direction * overlap_x / 2.0;
+        transform_b.translation.x += direction * overlap_x / 2.0;
+        bump_a.0 = -direction * PARTY_BUMP_SPEED;
+        bump_b.0 = direction * PARTY_BUMP_SPEED;
+
+        // Landing on someone's head gives a small hop
+        if overlap_y < overlap_x {
+            if delta.y > 0.0 && velocity_b.y < 0.0 {
+                velocity_b.y = JUMP_SPEED * 0.5;
+            } else if delta.y < 0.0 && velocity_a.y < 0.0 {
+                velocity_a.y = JUMP_SPEED * 0.5;
+            }
+        }
+    }
+}
+
+fn apply_bump_knockback(
+    time: Res<Time>,
+    mut player_query: Query<(&mut Velocity, &mut Bump)>,
+) {
+    for (mut velocity, mut bump) in player_query.iter_mut() {
+        velocity.x += bump.0;
+        bump.0 *= (1.0 - PARTY_BUMP_DECAY * time.delta_seconds()).max(0.0);
+        if bump.0.abs() < 1.0 {
+            bump.0 = 0.0;
+        }
+    }
+}
+
+fn party_fruit_collection(
+    mut commands: Commands,
+    player_query: Query<(&Transform, &PartyPlayer)>,
+    fruit_query: Query<(Entity, &Transform), (With<Fruit>, Without<Player>)>,
+    mut party_round: ResMut<PartyRound>,
+    mut sound_events: EventWriter<PlaySoundEvent>,
+    mut particle_events: EventWriter<SpawnParticles>,
+) {
+    for (fruit_entity, fruit_transform) in fruit_query.iter() {
+        // The closest player within reach grabs the fruit
+        let winner = player_query
+            .iter()
+            .map(|(transform, party_player)| (transform.translation.distance(fruit_transform.translation), party_player.slot))
+            .filter(|(distance, _)| *distance < 30.0)
+            .min_by(|a, b| a.0.total_cmp(&b.0));
+
+        if let Some((_, slot)) = winner {
+            sound_events.send(PlaySoundEvent { sound_type: SoundType::Collect, position: None });
+            particle_events.send(SpawnParticles { effect: ParticleEffect::FruitBurst, position: fruit_transform.translation.truncate() });
+            commands.entity(fruit_entity).despawn();
+            party_round.scores[slot] += 1;
+        }
+    }
+}
+
+fn party_fruit_spawner(
+    time: Res<Time>,
+    mut commands: Commands,
+    mut party_round: ResMut<PartyRound>,
+    layout: Res<LevelLayout>,
+    mut pool: ResMut<EntityPool>,
+    mut game_rng: ResMut<GameRng>,
+    fruit_query: Query<Entity, With<Fruit>>,
+) {
+    // Periodically drop another fruit while the arena isn't full, where the
+    // round's seed says, so `--seed` plays the round the same way again
+    if party_round.fruit_timer.tick(time.delta()).just_finished() && fruit_query.iter().count() < PARTY_MAX_FRUITS {
+        let seed = game_rng.next_in_run();
+        setup_fruits_with_seed(&mut commands, &mut pool, &layout, seed);
+    }
+}
+
+fn party_player_respawn(
+    mut player_query: Query<(&mut Transform, &mut Velocity, &mut Bump, &PartyPlayer)>,
+    party_round: Res<PartyRound>,
+    mut sound_events: EventWriter<PlaySoundEvent>,
+) {
+    // Falling off the screen just puts you back at the start - no lives in party mode
+    for (mut transform, mut velocity, mut bump, party_player) in player_query.iter_mut() {
+        if transform.translation.y < -WINDOW_HEIGHT / 2.0 {
+            sound_events.send(PlaySoundEvent { sound_type: SoundType::Death, position: None });
+            transform.translation = party_spawn_position(party_player.slot, party_round.player_count);
+            velocity.x = 0.0;
+            velocity.y = 0.0;
+            bump.0 = 0.0;
+        }
+    }
+}
+
+pub fn tag_player(commands: &mut Commands, player: Entity) {
+    commands
+        .entity(player)
+        .insert(Tagged {
+            grace: Timer::from_seconds(TAG_GRACE_SECONDS, TimerMode::Once),
+        })
+        .with_children(|parent| {
+            // "IT" marker floating above the player
+            parent.spawn((
+                Text2dBundle {
+                    text: Text::from_section(
+                        "IT",
+                        TextStyle {
+                            font_size: 30.0,
+                            color: Color::srgb(1.0, 0.0, 0.0), // Red
+                            ..default()
+                        },
+                    ),
+                    transform: Transform::from_translation(Vec3::new(0.0, 45.0, 1.0)),
+                    ..default()
+                },
+                WorldSpace,
+            ));
+        });
+}
+
+fn tag_players(
+    mut commands: Commands,
+    mut party_round: ResMut<PartyRound>,
+    player_query: Query<(Entity, &Transform, &PartyPlayer, Option<&Tagged>)>,
+    mut sound_events: EventWriter<PlaySoundEvent>,
+) {
+    let survived = party_round.round_timer.elapsed_secs() as u32;
+    let mut newly_tagged = Vec::new();
+    for [(entity_a, transform_a, party_a, tagged_a), (entity_b, transform_b, party_b, tagged_b)] in
+        player_query.iter_combinations()
+    {
+        let delta = transform_b.translation - transform_a.translation;
+        // Slightly generous reach so a bump always counts as a tag
+        if delta.x.abs() > 55.0 || delta.y.abs() > 55.0 {
+            continue;
+        }
+
+        // Only a chaser whose grace period is over can pass the infection on
+        let target = match (tagged_a, tagged_b) {
+            (Some(tagger), None) if tagger.grace.finished() => Some((entity_b, party_b.slot)),
+            (None, Some(tagger)) if tagger.grace.finished() => Some((entity_a, party_a.slot)),
+            _ => None,
+        };
+
+        if let Some((runner, slot)) = target.filter(|(runner, _)| !newly_tagged.contains(runner)) {
+            newly_tagged.push(runner);
+            sound_events.send(PlaySoundEvent { sound_type: SoundType::Collect, position: None });
+            party_round.scores[slot] = survived;
+            tag_player(&mut commands, runner);
+        }
+    }
+}
+
+fn tick_tag_grace(time: Res<Time>, mut tagged_query: Query<&mut Tagged>) {
+    for mut tagged in tagged_query.iter_mut() {
+        tagged.grace.tick(time.delta());
+    }
+}
+
+// Hands each platform a player is standing on over to that player
+fn claim_platforms(
+    player_query: Query<(&StandingOn, &PartyPlayer)>,
+    mut platform_query: Query<&mut PlatformOwner>,
+) {
+    for (standing_on, party_player) in player_query.iter() {
+        if let Ok(mut owner) = platform_query.get_mut(standing_on.0) {
+            owner.slot = Some(party_player.slot);
+            owner.tint = PLATFORM_TINT_SECONDS;
+        }
+    }
+}
+
+// Tints owned platforms toward their owner's color, and gives a platform its
+// own color back once the tint is gone
+fn tint_platforms(
+    time: Res<Time>,
+    game_mode: Res<GameMode>,
+    mut platform_query: Query<(&mut Sprite, &mut PlatformOwner)>,
+) {
+    for (mut sprite, mut owner) in platform_query.iter_mut() {
+        owner.tint = (owner.tint - time.delta_seconds()).max(0.0);
+        // Territory keeps a platform's color for as long as its owner holds it
+        let strength = if *game_mode == GameMode::Territory { 1.0 } else { owner.tint / PLATFORM_TINT_SECONDS };
+        match owner.slot {
+            Some(slot) if strength > 0.0 => {
+                let own_color = *owner.own_color.get_or_insert(sprite.color);
+                sprite.color = own_color.mix(&PARTY_PLAYER_COLORS[slot], 0.7 * strength);
+            }
+            _ => {
+                if let Some(own_color) = owner.own_color.take() {
+                    sprite.color = own_color;
+                }
+            }
+        }
+    }
+}
+
+fn territory_scores(
+    game_mode: Res<GameMode>,
+    mut party_round: ResMut<PartyRound>,
+    platform_query: Query<&PlatformOwner>,
+) {
+    if *game_mode != GameMode::Territory {
+        return;
+    }
+    let total = platform_query.iter().count().max(1);
+    for slot in 0..party_round.player_count {
+        let owned = platform_query.iter().filter(|owner| owner.slot == Some(slot)).count();
+        party_round.scores[slot] = (owned * 100 / total) as u32;
+    }
+}
+
+fn party_round_timer(
+    time: Res<Time>,
+    mut commands: Commands,
+    game_mode: Res<GameMode>,
+    mut party_round: ResMut<PartyRound>,
+    mut next_state: ResMut<NextState<AppState>>,
+    player_query: Query<Entity, With<Player>>,
+    untagged_query: Query<&PartyPlayer, Without<Tagged>>,
+    fruit_query: Query<Entity, With<Fruit>>,
+    ui_query: Query<Entity, With<GameUI>>,
+) {
+    let time_up = party_round.round_timer.tick(time.delta()).just_finished();
+    // Tag ends early once only one runner is left (or nobody, in a two-player round)
+    let runners = untagged_query.iter().count();
+    let tag_decided = *game_mode == GameMode::Tag
+        && (runners == 0 || (runners == 1 && party_round.player_count > 2));
+
+    if time_up || tag_decided {
+        if *game_mode == GameMode::Tag {
+            // Winners are credited with the whole round: the survivors, or the
+            // starting "it" if nobody got away
+            let full_round = party_round.round_timer.duration().as_secs_f32().ceil() as u32;
+            for party_player in untagged_query.iter() {
+                party_round.scores[party_player.slot] = full_round;
+            }
+            if runners == 0 {
+                let starting_it = party_round.starting_it;
+                party_round.scores[starting_it] = full_round;
+            }
+        }
+
+        // Clear the arena but keep the platforms as a backdrop for the podium
+        for entity in player_query.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+        for entity in fruit_query.iter().chain(ui_query.iter()) {
+            commands.entity(entity).despawn();
+        }
+        next_state.set(AppState::PartyResults);
+    }
+}
+
+fn party_score_label(game_mode: GameMode, score: u32) -> String {
+    match game_mode {
+        GameMode::Tag => format!("{}s", score),
+        GameMode::Territory => format!("{}%", score),
+        _ => score.to_string(),
+    }
+}
+
+fn update_party_ui(
+    game_mode: Res<GameMode>,
+    party_round: Res<PartyRound>,
+    mut timer_query: Query<&mut Text, (With<PartyTimerText>, Without<PartyScoreText>)>,
+    mut score_query: Query<(&mut Text, &PartyScoreText), Without<PartyTimerText>>,
+    tagged_query: Query<&PartyPlayer, With<Tagged>>,
+) {
+    if let Ok(mut text) = timer_query.get_single_mut() {
+        let remaining = party_round.round_timer.remaining_secs().ceil() as u32;
+        text.sections[0].value = format!("Time: {}", remaining);
+    }
+
+    for (mut text, score_text) in score_query.iter_mut() {
+        let slot = score_text.0;
+        text.sections[0].value = match *game_mode {
+            GameMode::Tag if tagged_query.iter().any(|party_player| party_player.slot == slot) => {
+                format!("P{}: IT", slot + 1)
+            }
+            GameMode::Tag => format!("P{}: RUN", slot + 1),
+            _ => format!("P{}: {}", slot + 1, party_score_label(*game_mode, party_round.scores[slot])),
+        };
+    }
+}
+
+fn setup_party_results(commands: &mut Commands, party_round: &PartyRound, game_mode: GameMode) {
+    // Rank players by score (ties keep slot order)
+    let mut ranking: Vec<usize> = (0..party_round.player_count).collect();
+    ranking.sort_by(|a, b| party_round.scores[*b].cmp(&party_round.scores[*a]));
+
+    let best_score = party_round.scores[ranking[0]];
+    let winners = ranking.iter().filter(|slot| party_round.scores[**slot] == best_score).count();
+    let headline = if winners > 1 {
+        "It's a draw!".to_string()
+    } else {
+        format!("Player {} wins!", ranking[0] + 1)
+    };
+
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                headline,
+                TextStyle {
+                    font_size: 70.0,
+                    color: Color::srgb(1.0, 0.5, 0.0), // Orange
+                    ..default()
+                },
+            ),
+            transform: Transform::from_translation(Vec3::new(0.0, 280.0, 10.0)),
+            ..default()
+        },
+        PartyResultsUI,
+    ));
+
+    // Podium blocks: 2nd on the left, 1st in the middle, 3rd on the right
+    let podium = [(0.0, 180.0), (-220.0, 130.0), (220.0, 90.0)];
+    let podium_base = -180.0;
+    for (place, slot) in ranking.iter().enumerate().take(podium.len()) {
+        let (x, height) = podium[place];
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: Color::srgb(0.8, 0.8, 0.8),
+                    custom_size: Some(Vec2::new(180.0, height)),
+                    ..default()
+                },
+                transform: Transform::from_translation(Vec3::new(x, podium_base + height / 2.0, 5.0)),
+                ..default()
+            },
+            PartyResultsUI,
+        ));
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: PARTY_PLAYER_COLORS[*slot],
+                    custom_size: Some(Vec2::new(50.0, 50.0)),
+                    ..default()
+                },
+                transform: Transform::from_translation(Vec3::new(x, podium_base + height + 25.0, 6.0)),
+                ..default()
+            },
+            PartyResultsUI,
+        ));
+        commands.spawn((
+            Text2dBundle {
+                text: Text::from_section(
+                    format!("{}\nP{}: {}", place + 1, slot + 1, party_score_label(game_mode, party_round.scores[*slot])),
+                    TextStyle {
+                        font_size: 30.0,
+                        color: Color::srgb(0.1, 0.1, 0.1),
+                        ..default()
+                    },
+                ),
+                transform: Transform::from_translation(Vec3::new(x, podium_base + height / 2.0, 10.0)),
+                ..default()
+            },
+            PartyResultsUI,
+        ));
+    }
+
+    // Anyone who missed the podium gets a line underneath
+    for (place, slot) in ranking.iter().enumerate().skip(podium.len()) {
+        commands.spawn((
+            Text2dBundle {
+                text: Text::from_section(
+                    format!("{}. P{}: {}", place + 1, slot + 1, party_score_label(game_mode, party_round.scores[*slot])),
+                    TextStyle {
+                        font_size: 30.0,
+                        color: PARTY_PLAYER_COLORS[*slot],
+                        ..default()
+                    },
+                ),
+                transform: Transform::from_translation(Vec3::new(0.0, podium_base - 40.0, 10.0)),
+                ..default()
+            },
+            PartyResultsUI,
+        ));
+    }
+
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                "Press R for a Rematch or ESC to return to Main Menu",
+                TextStyle {
+                    font_size: 30.0,
+                    color: Color::srgb(0.0, 1.0, 0.0), // Green
+                    ..default()
+                },
+            ),
+            transform: Transform::from_translation(Vec3::new(0.0, -300.0, 10.0)),
+            ..default()
+        },
+        PartyResultsUI,
+    ));
+}
+
+fn handle_party_results_input(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut next_state: ResMut<NextState<AppState>>,
+    game_mode: Res<GameMode>,
+    mut party_round: ResMut<PartyRound>,
+    mut commands: Commands,
+    mut teardown: LevelTeardown,
+) {
+    let rematch = keyboard_input.just_pressed(KeyCode::KeyR);
+    if rematch || keyboard_input.just_pressed(KeyCode::Escape) {
+        // Clean up the old arena, its platforms and fruit back into the pool
+        teardown.clear_all(&mut commands);
+
+        if rematch {
+            // setup_game_entities rebuilds the arena for the same players,
+            // and in Tag the role of "it" passes to the next player
+            let player_count = party_round.player_count;
+            let starting_it = (party_round.starting_it + 1) % player_count;
+            *party_round = PartyRound::new(*game_mode, player_count);
+            party_round.starting_it = starting_it;
+            next_state.set(AppState::Playing);
+        } else {
+            next_state.set(AppState::MainMenu);
+        }
+    }
+}
@@ -0,0 +1,264 @@
+// Pause menu for Classic runs.
+//
+// The pause key (P or ESC unless rebound) pauses the run. Gameplay systems only run while `AppState::Playing`,
+// so physics, enemies and timers all hold still behind the menu. The menu is a
+// short list picked with UP/DOWN (or W/S) and ENTER: resume, restart the level
+// from the top, quit to the main menu, or quit the game (after the session
+// recap, see `session`). F favorites the level and H opens the HUD editor
+// straight from here.
+
+use bevy::prelude::*;
+use crate::prelude::*;
+use rustbevy_core::config::{WINDOW_HEIGHT, WINDOW_WIDTH};
+
+use crate::campaign::CampaignLevels;
+use crate::character_select::PlayerProfile;
+use crate::chat::chat_closed;
+use crate::favorites::{Favorite, Favorites};
+use crate::physics::classic_jump_profile;
+use crate::platform::{spawn_classic_level, CurrentLevel};
+use crate::pool::LevelTeardown;
+use crate::ui::{despawn_screen, GameUI, Toasts};
+
+pub struct PausePlugin;
+
+impl Plugin for PausePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PauseMenu>()
+            .add_event::<RestartLevelEvent>()
+            .add_systems(OnEnter(AppState::Paused), setup_pause_menu)
+            .add_systems(OnExit(AppState::Paused), despawn_screen::<PauseUI>)
+            .add_systems(Update, (
+                handle_pause_input,
+                update_pause_options.run_if(in_state(AppState::Paused)),
+                restart_level,
+            ).chain().run_if(resource_equals(GameMode::Classic).and_then(chat_closed)));
+    }
+}
+
+const SELECTED_COLOR: Color = Color::srgb(1.0, 1.0, 0.0); // Yellow
+const OPTION_COLOR: Color = Color::srgb(0.8, 0.8, 0.8);
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum PauseOption {
+    Resume,
+    RestartLevel,
+    Quit,
+    QuitGame,
+}
+
+impl PauseOption {
+    const ALL: [Self; 4] = [Self::Resume, Self::RestartLevel, Self::Quit, Self::QuitGame];
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Resume => "Resume",
+            Self::RestartLevel => "Restart Level",
+            Self::Quit => "Quit to Main Menu",
+            Self::QuitGame => "Quit Game",
+        }
+    }
+}
+
+// Which option is highlighted
+#[derive(Resource, Default)]
+struct PauseMenu {
+    selected: usize,
+}
+
+#[derive(Component)]
+struct PauseUI;
+
+// Restart Level was picked; the level is rebuilt in response
+#[derive(Event)]
+struct RestartLevelEvent;
+
+#[derive(Component)]
+struct PauseOptionText(usize);
+
+fn option_line(index: usize, selected: usize) -> (String, Color) {
+    let label = PauseOption::ALL[index].label();
+    if index == selected {
+        (format!("> {} <", label), SELECTED_COLOR)
+    } else {
+        (label.to_string(), OPTION_COLOR)
+    }
+}
+
+fn setup_pause_menu(mut commands: Commands, mut pause_menu: ResMut<PauseMenu>) {
+    pause_menu.selected = 0;
+
+    // Dim the level behind the menu
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: Color::srgba(0.0, 0.0, 0.0, 0.6),
+                custom_size: Some(Vec2::new(WINDOW_WIDTH, WINDOW_HEIGHT)),
+                ..default()
+            },
+            transform: Transform::from_translation(Vec3::new(0.0, 0.0, 15.0)),
+            ..default()
+        },
+        PauseUI,
+        ScreenSpace,
+    ));
+
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                "PAUSED",
+                TextStyle {
+                    font_size: 80.0,
+                    color: Color::srgb(1.0, 0.5, 0.0), // Orange
+                    ..default()
+                },
+            ),
+            transform: Transform::from_translation(Vec3::new(0.0, 120.0, 16.0)),
+            ..default()
+        },
+        PauseUI,
+    ));
+
+    for index in 0..PauseOption::ALL.len() {
+        let (line, color) = option_line(index, pause_menu.selected);
+        commands.spawn((
+            Text2dBundle {
+                text: Text::from_section(line, TextStyle { font_size: 32.0, color, ..default() }),
+                transform: Transform::from_translation(Vec3::new(0.0, 30.0 - index as f32 * 45.0, 16.0)),
+                ..default()
+            },
+            PauseOptionText(index),
+            PauseUI,
+        ));
+    }
+
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                "UP / DOWN: Choose   ENTER: Select   Pause key: Resume   F: Favorite this level   H: Edit HUD",
+                TextStyle {
+                    font_size: 22.0,
+                    color: Color::srgb(0.0, 1.0, 0.0), // Green
+                    ..default()
+                },
+            ),
+            transform: Transform::from_translation(Vec3::new(0.0, -170.0, 16.0)),
+            ..default()
+        },
+        PauseUI,
+    ));
+}
+
+fn handle_pause_input(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    input_map: Res<InputMap>,
+    mut commands: Commands,
+    app_state: Res<State<AppState>>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut pause_menu: ResMut<PauseMenu>,
+    current_level: Res<CurrentLevel>,
+    mut favorites: ResMut<Favorites>,
+    mut toasts: ResMut<Toasts>,
+    mut restart_events: EventWriter<RestartLevelEvent>,
+    run_query: Query<Entity, Or<(With<Player>, With<Platform>, With<Pickup>, With<Enemy>, With<Checkpoint>, With<ExitDoor>, With<Key>, With<Ladder>, With<HazardArea>, With<WindZone>, With<PortalEnd>, With<ThrownSeed>, With<GameUI>)>>,
+) {
+    let pause_pressed = input_map.just_pressed(InputAction::Pause, &keyboard_input);
+    match app_state.get() {
+        AppState::Playing if pause_pressed => next_state.set(AppState::Paused),
+        AppState::Paused => {
+            let count = PauseOption::ALL.len();
+            if keyboard_input.any_just_pressed([KeyCode::ArrowUp, KeyCode::KeyW]) {
+                pause_menu.selected = (pause_menu.selected + count - 1) % count;
+            } else if keyboard_input.any_just_pressed([KeyCode::ArrowDown, KeyCode::KeyS]) {
+                pause_menu.selected = (pause_menu.selected + 1) % count;
+            }
+
+            let chosen = if pause_pressed {
+                Some(PauseOption::Resume)
+            } else if keyboard_input.any_just_pressed([KeyCode::Enter, KeyCode::Space]) {
+                Some(PauseOption::ALL[pause_menu.selected])
+            } else {
+                None
+            };
+            match chosen {
+                Some(PauseOption::Resume) => next_state.set(AppState::Playing),
+                Some(PauseOption::RestartLevel) => {
+                    restart_events.send(RestartLevelEvent);
+                    next_state.set(AppState::Playing);
+                }
+                Some(option @ (PauseOption::Quit | PauseOption::QuitGame)) => {
+                    for entity in run_query.iter() {
+                        commands.entity(entity).despawn();
+                    }
+                    next_state.set(if option == PauseOption::Quit { AppState::MainMenu } else { AppState::SessionRecap });
+                }
+                None if keyboard_input.just_pressed(KeyCode::KeyF) => {
+                    let message = favorites.add(Favorite {
+                        run_seed: current_level.run_seed,
+                        level: current_level.level,
+                        difficulty: current_level.difficulty,
+                        mode: GameMode::Classic,
+                    });
+                    toasts.push(message.to_string());
+                }
+                None if keyboard_input.just_pressed(KeyCode::KeyH) => next_state.set(AppState::HudEditor),
+                None => {}
+            }
+        }
+        _ => {}
+    }
+}
+
+fn update_pause_options(pause_menu: Res<PauseMenu>, mut text_query: Query<(&PauseOptionText, &mut Text)>) {
+    if !pause_menu.is_changed() {
+        return;
+    }
+    for (option, mut text) in text_query.iter_mut() {
+        let (line, color) = option_line(option.0, pause_menu.selected);
+        text.sections[0].value = line;
+        text.sections[0].style.color = color;
+    }
+}
+
+// Rebuilds the level on screen as it was generated and puts the player (both
+// co-op players) back at its start. Lives already lost on it stay lost, and like a death it breaks
+// the combo.
+fn restart_level(
+    mut commands: Commands,
+    mut restart_events: EventReader<RestartLevelEvent>,
+    mut player_query: Query<(&mut Transform, &mut Velocity, &Collider), With<Player>>,
+    mut teardown: LevelTeardown,
+    current_level: Res<CurrentLevel>,
+    curve: Res<DifficultyCurve>,
+    run_modifiers: Res<RunModifiers>,
+    assist_mode: Res<AssistMode>,
+    player_profile: Res<PlayerProfile>,
+    campaign_levels: CampaignLevels,
+    mut game_state: ResMut<GameState>,
+) {
+    if restart_events.read().count() == 0 {
+        return;
+    }
+    let Some(size) = player_query.iter().next().map(|(_, _, collider)| collider.size) else {
+        return;
+    };
+    teardown.clear_all(&mut commands);
+
+    let profile = classic_jump_profile(&run_modifiers, &assist_mode, &player_profile, size);
+    let player_spawn = spawn_classic_level(
+        &mut commands,
+        &mut teardown.pool,
+        campaign_levels.provider(),
+        current_level.run_seed,
+        current_level.level,
+        current_level.difficulty,
+        profile,
+        &curve.0,
+    );
+    for (mut player_transform, mut velocity, _) in player_query.iter_mut() {
+        player_transform.translation = player_spawn.extend(0.0);
+        velocity.x = 0.0;
+        velocity.y = 0.0;
+    }
+    game_state.combo.reset();
+}
@@ -0,0 +1,394 @@
+//! Player physics: input, gravity, velocity and platform collisions, all
+//! driven by the shared rules in `rustbevy_core::physics`.
+//!
+//! The simulation (players, moving platforms and enemies) steps in
+//! `FixedUpdate` at PHYSICS_HZ, the tick replays are simulated with, so a jump
+//! is as high and a fall as fast at 30 fps as at 240, and a slow frame can't
+//! carry the player through a platform. Input is read every frame: a jump press
+//! waits in `JumpPress` for the next step. `Update` systems see where the
+//! simulation put things; only for drawing are positions interpolated between
+//! the last two steps, and put back at the start of the next frame.
+
+use bevy::diagnostic::Diagnostics;
+use bevy::input::InputSystem;
+use bevy::prelude::*;
+use bevy::transform::TransformSystem;
+use crate::prelude::*;
+use rustbevy_core::autorun::AutoRun;
+use rustbevy_core::config::PHYSICS_HZ;
+use rustbevy_core::generation::PlatformSpec;
+use rustbevy_core::physics::{self, Body, Tuning};
+use rustbevy_core::reachability::JumpProfile;
+
+use crate::camera::spectating;
+use crate::character_select::PlayerProfile;
+use crate::chat::chat_closed;
+use crate::gamepad::{read_gamepad, GamepadInput};
+use crate::overview;
+use crate::platform::CurrentLevel;
+use crate::profiling::{self, millis_since, Instant};
+use crate::replay::ReplayPlayback;
+use crate::spatial::SpatialIndex;
+
+pub struct PhysicsPlugin;
+
+impl Plugin for PhysicsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Time::<Fixed>::from_hz(PHYSICS_HZ as f64))
+            .init_resource::<AutoRunState>()
+            .add_event::<PlatformContactEvent>()
+            .add_systems(First, restore_simulated_positions)
+            .add_systems(PreUpdate, latch_jump_presses
+                .after(InputSystem)
+                .after(read_gamepad)
+                .run_if(in_state(AppState::Playing).and_then(not(spectating)).and_then(chat_closed).and_then(not(resource_exists::<ReplayPlayback>))))
+            .add_systems(FixedFirst, (start_interpolating, record_previous_positions).chain())
+            .add_systems(FixedUpdate, (
+                player_movement.run_if(not(spectating).and_then(chat_closed)),
+                apply_gravity,
+                apply_velocity,
+                check_collisions,
+            ).chain().run_if(in_state(AppState::Playing)))
+            .add_systems(FixedLast, record_simulated_positions)
+            .add_systems(Update, (
+                apply_game_speed.after(overview::update_overview),
+                reset_auto_run,
+                warn_impossible_jumps.run_if(in_state(AppState::Playing).and_then(resource_equals(GameMode::Classic))),
+            ))
+            .add_systems(PostUpdate, interpolate_positions.before(TransformSystem::TransformPropagate));
+    }
+}
+
+/// Which way the auto-running Classic player is heading (see the one-switch
+/// and two-button assists)
+#[derive(Resource, Default)]
+pub struct AutoRunState(AutoRun);
+
+// Every run starts heading right
+fn reset_auto_run(mut reset_events: EventReader<GameResetEvent>, mut auto_run: ResMut<AutoRunState>) {
+    if reset_events.read().count() > 0 {
+        *auto_run = AutoRunState::default();
+    }
+}
+
+// Landings faster than this kick up dust; a hop onto a step doesn't
+const LANDING_DUST_SPEED: f32 = 450.0;
+
+/// Where the simulation had something at the last two physics steps. Players,
+/// enemies, thrown seeds and moving platforms get one the first step they're around.
+#[derive(Component)]
+pub struct Interpolated {
+    previous: Vec2,
+    current: Vec2,
+}
+
+fn start_interpolating(
+    mut commands: Commands,
+    query: Query<(Entity, &Transform), (Or<(With<Player>, With<Enemy>, With<ThrownSeed>, With<MovingPlatform>)>, Without<Interpolated>)>,
+) {
+    for (entity, transform) in query.iter() {
+        let position = transform.translation.truncate();
+        commands.entity(entity).insert(Interpolated { previous: position, current: position });
+    }
+}
+
+fn record_previous_positions(mut query: Query<(&Transform, &mut Interpolated)>) {
+    for (transform, mut interpolated) in query.iter_mut() {
+        interpolated.previous = transform.translation.truncate();
+    }
+}
+
+fn record_simulated_positions(mut query: Query<(&Transform, &mut Interpolated)>) {
+    for (transform, mut interpolated) in query.iter_mut() {
+        interpolated.current = transform.translation.truncate();
+    }
+}
+
+/// Draws things part of the way from their previous step to their latest
+pub fn interpolate_positions(fixed_time: Res<Time<Fixed>>, mut query: Query<(&mut Transform, &mut Interpolated)>) {
+    let blend = fixed_time.overstep_fraction();
+    for (mut transform, mut interpolated) in query.iter_mut() {
+        let position = transform.translation.truncate();
+        // Moved outside the simulation since its last step (a respawn, a new
+        // level): show it there straight away rather than sliding over
+        if position != interpolated.current {
+            interpolated.previous = position;
+            interpolated.current = position;
+        }
+        let drawn = interpolated.previous.lerp(interpolated.current, blend);
+        transform.translation.x = drawn.x;
+        transform.translation.y = drawn.y;
+    }
+}
+
+// Puts back the simulated positions the last frame was drawn between
+fn restore_simulated_positions(mut query: Query<(&mut Transform, &Interpolated)>) {
+    for (mut transform, interpolated) in query.iter_mut() {
+        transform.translation.x = interpolated.current.x;
+        transform.translation.y = interpolated.current.y;
+    }
+}
+
+// Remembers jump presses until a physics step uses them, so a press isn't
+// lost on a frame with no step or used twice on a frame with two
+fn latch_jump_presses(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    gamepad: Res<GamepadInput>,
+    mut player_query: Query<(&mut JumpPress, &PlayerControls, Has<LeadPlayer>), With<Player>>,
+) {
+    for (mut jump_press, controls, lead) in player_query.iter_mut() {
+        // The controller plays alongside the lead player's keys
+        let pressed = PlayerControls::any_just_pressed(&keyboard_input, &controls.jump) || (lead && gamepad.jump_just_pressed);
+        if pressed {
+            jump_press.0 = true;
+        }
+    }
+}
+
+/// Movement tuning for the current game: the skin, drafted modifiers and assists apply to Classic only
+pub fn player_tuning(game_mode: GameMode, run_modifiers: &RunModifiers, assist_mode: &AssistMode, profile: &PlayerProfile) -> Tuning {
+    if game_mode != GameMode::Classic {
+        return Tuning::default();
+    }
+    let mut tuning = run_modifiers.0.tuning(profile.skin.tuning(Tuning::default()));
+    tuning.jump_speed = assist_mode.0.jump_speed(tuning.jump_speed);
+    tuning.player_speed = assist_mode.0.player_speed(tuning.player_speed);
+    tuning
+}
+
+/// What a Classic player of `player_size` can jump to, for checking levels
+pub fn classic_jump_profile(run_modifiers: &RunModifiers, assist_mode: &AssistMode, profile: &PlayerProfile, player_size: f32) -> JumpProfile {
+    let tuning = player_tuning(GameMode::Classic, run_modifiers, assist_mode, profile);
+    let profile = JumpProfile::new(&tuning, player_size);
+    if assist_mode.0.controls.auto_run() {
+        profile.with_auto_run(&tuning)
+    } else {
+        profile
+    }
+}
+
+// Tells the player when their jump, with the drafted modifiers, assists and
+// skin, can't make the gaps and heights the generator builds this level's
+// layouts with (see `JumpProfile::layout_problem`). Checked on each new level
+// and whenever the jump changes, once per problem.
+fn warn_impossible_jumps(
+    current_level: Res<CurrentLevel>,
+    curve: Res<DifficultyCurve>,
+    run_modifiers: Res<RunModifiers>,
+    assist_mode: Res<AssistMode>,
+    player_profile: Res<PlayerProfile>,
+    mutator_mode: Res<MutatorMode>,
+    mut warned: Local<Option<String>>,
+    mut errors: EventWriter<RuntimeErrorEvent>,
+) {
+    let jump_changed = run_modifiers.is_changed() || assist_mode.is_changed() || player_profile.is_changed() || mutator_mode.is_changed();
+    if !current_level.is_changed() && !jump_changed {
+        return;
+    }
+    let profile = classic_jump_profile(&run_modifiers, &assist_mode, &player_profile, mutator_mode.0.player_size());
+    let problem = profile.layout_problem(&curve.0.layout(current_level.difficulty));
+    if problem != *warned {
+        if let Some(problem) = &problem {
+            errors.send(RuntimeErrorEvent::new("Levels may not be finishable with this jump", problem.clone()));
+        }
+        *warned = problem;
+    }
+}
+
+/// Copies a player's components into a simulation body and back
+pub fn player_body(transform: &Transform, velocity: &Velocity, grounded: &Grounded, collider: &Collider) -> Body {
+    Body {
+        x: transform.translation.x,
+        y: transform.translation.y,
+        velocity_x: velocity.x,
+        velocity_y: velocity.y,
+        grounded: grounded.0,
+        // Only player movement needs these; it sets them from `WallContact`
+        // and the platform it's standing on
+        wall: None,
+        slope: 0.0,
+        size: collider.size,
+    }
+}
+
+pub fn store_player_body(body: &Body, transform: &mut Transform, velocity: &mut Velocity, grounded: &mut Grounded) {
+    transform.translation.x = body.x;
+    transform.translation.y = body.y;
+    velocity.x = body.velocity_x;
+    velocity.y = body.velocity_y;
+    grounded.0 = body.grounded;
+}
+
+pub fn player_movement(
+    time: Res<Time>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    game_mode: Res<GameMode>,
+    run_modifiers: Res<RunModifiers>,
+    assist_mode: Res<AssistMode>,
+    player_profile: Res<PlayerProfile>,
+    mutator_mode: Res<MutatorMode>,
+    current_level: Res<CurrentLevel>,
+    gamepad: Res<GamepadInput>,
+    playback: Option<Res<ReplayPlayback>>,
+    mut auto_run: ResMut<AutoRunState>,
+    mut player_query: Query<(&mut Velocity, &mut JumpState, &mut JumpPress, &Transform, &Grounded, &WallContact, &PlayerControls, &Collider, Option<&PlayerStatus>, Option<&StandingOn>, Has<LeadPlayer>), (With<Player>, Without<Climbing>)>,
+    platform_query: Query<&Platform>,
+    mut sound_events: EventWriter<PlaySoundEvent>,
+) {
+    let tuning = player_tuning(*game_mode, &run_modifiers, &assist_mode, &player_profile);
+    // Ice is slippery underfoot
+    let tuning = if *game_mode == GameMode::Classic { current_level.theme.tuning(tuning) } else { tuning };
+    for (mut velocity, mut jump_state, mut jump_press, transform, grounded, wall_contact, controls, collider, player_status, standing_on, lead) in player_query.iter_mut() {
+        let tuning = player_status.map_or(tuning, |status| status.0.tuning(tuning));
+        // Horizontal movement - works in air and on ground
+        let mut horizontal_input = 0.0;
+        if PlayerControls::any_pressed(&keyboard_input, &controls.left) {
+            horizontal_input -= 1.0;
+        }
+        if PlayerControls::any_pressed(&keyboard_input, &controls.right) {
+            horizontal_input += 1.0;
+        }
+        let jump_pressed = std::mem::take(&mut jump_press.0);
+        let mut down_held = PlayerControls::any_pressed(&keyboard_input, &controls.down);
+        // The controller plays alongside the lead player's keys
+        if lead {
+            horizontal_input = (horizontal_input + gamepad.horizontal).clamp(-1.0, 1.0);
+            down_held |= gamepad.down_held();
+            // A replay being played back stands in for both
+            if let Some(input) = playback.as_ref().and_then(|playback| playback.input()) {
+                horizontal_input = input.horizontal();
+                down_held = input.down;
+            }
+        }
+        let mut horizontal_input = player_status.map_or(horizontal_input, |status| status.0.horizontal_input(horizontal_input));
+        if *game_mode == GameMode::Classic {
+            horizontal_input = mutator_mode.0.horizontal_input(horizontal_input);
+        }
+        // Auto-run heads where the player turned it, whatever flips the keys.
+        // It's the lead player's; a co-op partner runs with the regular keys.
+        let controls_scheme = assist_mode.0.controls;
+        if *game_mode == GameMode::Classic && controls_scheme.auto_run() && lead {
+            // Running into the edge of the level turns the player around
+            let edge = game_mode.world_bounds().half_width() - collider.size / 2.0 - 1.0;
+            if transform.translation.x * auto_run.0.direction() >= edge {
+                auto_run.0.turn();
+            }
+            let switch_held = PlayerControls::any_pressed(&keyboard_input, &controls.jump) || gamepad.jump_held;
+            // Down turns around in two-button mode, so it can't drop through platforms
+            horizontal_input = auto_run.0.update(controls_scheme, switch_held, down_held, time.delta_seconds());
+            down_held = false;
+        }
+
+        let slope = standing_on.and_then(|standing_on| platform_query.get(standing_on.0).ok()).map_or(0.0, |platform| platform.slope);
+        let mut body = Body { wall: wall_contact.0, slope, ..player_body(&Transform::default(), &velocity, grounded, collider) };
+        if physics::apply_input(&mut body, &mut jump_state.0, horizontal_input, jump_pressed, down_held, time.delta_seconds(), &tuning) {
+            sound_events.send(PlaySoundEvent { sound_type: SoundType::Jump, position: None });
+        }
+        velocity.x = body.velocity_x;
+        velocity.y = body.velocity_y;
+    }
+}
+
+pub fn apply_gravity(
+    time: Res<Time>,
+    game_mode: Res<GameMode>,
+    run_modifiers: Res<RunModifiers>,
+    assist_mode: Res<AssistMode>,
+    player_profile: Res<PlayerProfile>,
+    mut query: Query<(&mut Velocity, &JumpState, &Grounded, &Collider), (With<Player>, Without<Climbing>)>,
+) {
+    let tuning = player_tuning(*game_mode, &run_modifiers, &assist_mode, &player_profile);
+    for (mut velocity, jump_state, grounded, collider) in query.iter_mut() {
+        let mut body = player_body(&Transform::default(), &velocity, grounded, collider);
+        physics::apply_gravity(&mut body, &jump_state.0, time.delta_seconds(), &tuning);
+        velocity.y = body.velocity_y;
+    }
+}
+
+pub fn apply_velocity(
+    time: Res<Time>,
+    mut query: Query<(&mut Transform, &Velocity)>,
+) {
+    for (mut transform, velocity) in query.iter_mut() {
+        transform.translation.x += velocity.x * time.delta_seconds();
+        transform.translation.y += velocity.y * time.delta_seconds();
+    }
+}
+
+/// A platform for the shared rules, unless it's a fallen crumbling platform or
+/// a tipped trap, which aren't there to land on
+pub fn solid_platform(transform: &Transform, platform: &Platform, one_way: bool, crumbling: Option<&Crumbling>, trap: Option<&Trap>) -> Option<PlatformSpec> {
+    let solid = crumbling.is_none_or(|crumbling| crumbling.0.solid()) && trap.is_none_or(|trap| trap.0.solid());
+    solid.then(|| platform.spec(transform, one_way))
+}
+
+pub fn check_collisions(
+    mut commands: Commands,
+    time: Res<Time>,
+    game_mode: Res<GameMode>,
+    index: Res<SpatialIndex>,
+    mut player_query: Query<(Entity, &mut Transform, &mut Velocity, &mut Grounded, &mut WallContact, &JumpState, &Collider, Option<&StandingOn>), (With<Player>, Without<Climbing>)>,
+    platform_query: Query<(Entity, &Transform, &Platform, Has<OneWay>, Option<&Crumbling>, Option<&Trap>), Without<Player>>,
+    mut particle_events: EventWriter<SpawnParticles>,
+    mut contact_events: EventWriter<PlatformContactEvent>,
+    mut diagnostics: Diagnostics,
+) {
+    let started = Instant::now();
+    for (player, mut player_transform, mut velocity, mut grounded, mut wall_contact, jump_state, collider, standing_on) in player_query.iter_mut() {
+        let mut body = player_body(&player_transform, &velocity, &grounded, collider);
+        let (entities, platforms): (Vec<Entity>, Vec<PlatformSpec>) = index
+            .platforms_near(&body, time.delta_seconds())
+            .into_iter()
+            .filter_map(|entity| platform_query.get(entity).ok())
+            .filter_map(|(entity, transform, platform, one_way, crumbling, trap)| {
+                solid_platform(transform, platform, one_way, crumbling, trap).map(|spec| (entity, spec))
+            })
+            .unzip();
+        let was_grounded = body.grounded;
+        let falling_speed = -body.velocity_y;
+        physics::resolve_platform_collisions(&mut body, &platforms, &jump_state.0, time.delta_seconds());
+        physics::keep_in_world(&mut body, game_mode.world_bounds());
+        if body.grounded && !was_grounded && falling_speed >= LANDING_DUST_SPEED {
+            let feet = Vec2::new(body.x, body.y - body.size / 2.0);
+            particle_events.send(SpawnParticles { effect: ParticleEffect::LandingDust, position: feet });
+        }
+        let stood_on = platforms.iter().position(|platform| physics::stood_on(&body, platform)).map(|index| entities[index]);
+        match stood_on {
+            Some(platform) => {
+                if standing_on != Some(&StandingOn(platform)) {
+                    commands.entity(player).insert(StandingOn(platform));
+                }
+                contact_events.send(PlatformContactEvent { player, platform, landed: !was_grounded });
+            }
+            None if standing_on.is_some() => {
+                commands.entity(player).remove::<StandingOn>();
+            }
+            None => {}
+        }
+        store_player_body(&body, &mut player_transform, &mut velocity, &mut grounded);
+        wall_contact.0 = body.wall;
+    }
+    diagnostics.add_measurement(&profiling::COLLISION, || millis_since(started));
+}
+
+// Scales how fast the whole game runs in a Classic run: the game speed
+// mutator, the slow-motion assist and the level overview (slower still while
+// it's open). Fixed physics steps come around more or less often; sounds and
+// music play in real time, so nothing changes pitch.
+fn apply_game_speed(
+    app_state: Res<State<AppState>>,
+    game_mode: Res<GameMode>,
+    assist_mode: Res<AssistMode>,
+    mutator_mode: Res<MutatorMode>,
+    overview: Res<overview::Overview>,
+    mut time: ResMut<Time<Virtual>>,
+) {
+    let speed = if *app_state.get() == AppState::Playing && *game_mode == GameMode::Classic {
+        mutator_mode.0.speed.factor() * assist_mode.0.game_speed() * overview.game_speed()
+    } else {
+        1.0
+    };
+    if time.relative_speed() != speed {
+        time.set_relative_speed(speed);
+    }
+}
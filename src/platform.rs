@@ -0,0 +1,416 @@
+//! Platforms: spawning a generated or hand-made level and the reachable
+//! platform hints assist.
+
+use std::collections::HashMap;
+
+use bevy::color::Mix;
+use bevy::prelude::*;
+use bevy::sprite::Anchor;
+use crate::prelude::*;
+use rustbevy_core::crumbling::CrumbleState;
+use rustbevy_core::difficulty::DifficultyCurve;
+use rustbevy_core::flee::FleeingFruit;
+use rustbevy_core::fruit::FruitKind;
+use rustbevy_core::generation::{self, PlatformSpec};
+use rustbevy_core::level::Level;
+use rustbevy_core::look::PlatformLook;
+use rustbevy_core::moving::carry;
+use rustbevy_core::config::PLAYER_SPAWN;
+use rustbevy_core::reachability::JumpProfile;
+use rustbevy_core::surface::Surface;
+use rustbevy_core::theme::LevelTheme;
+use rustbevy_core::trap::TrapKind;
+
+use crate::campaign::LevelProvider;
+use crate::character_select::PlayerProfile;
+use crate::checkpoint::spawn_checkpoint;
+use crate::dressing::{dress_platform, dressed_color};
+use crate::enemy::spawn_enemy;
+use crate::exit::spawn_exit;
+use crate::fruit::{spawn_bonus_fruit, spawn_fruit, spawn_power_up};
+use crate::hazard::spawn_hazard;
+use crate::ladder::spawn_ladder;
+use crate::level_loader::HandmadeLevel;
+use crate::party::PlatformOwner;
+use crate::physics::{check_collisions, classic_jump_profile, player_body};
+use crate::pool::{EntityPool, PoolKind};
+use crate::portal::spawn_portal_pair;
+use crate::settings::Settings;
+use crate::theme::themed_color;
+use crate::wind::spawn_wind;
+
+pub struct PlatformPlugin;
+
+impl Plugin for PlatformPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CurrentLevel>()
+            .add_systems(FixedUpdate, (move_platforms, crumble_platforms)
+                .chain()
+                .before(check_collisions)
+                .run_if(in_state(AppState::Playing).and_then(resource_equals(GameMode::Classic))))
+            .add_systems(Update, highlight_reachable_platforms
+                .run_if(in_state(AppState::Playing).and_then(resource_equals(GameMode::Classic))));
+    }
+}
+
+pub const PLATFORM_COLOR: Color = Color::srgb(0.5, 0.5, 0.5);
+const WOOD_COLOR: Color = Color::srgb(0.55, 0.42, 0.3);
+const METAL_COLOR: Color = Color::srgb(0.45, 0.5, 0.58);
+const ICE_COLOR: Color = Color::srgb(0.62, 0.78, 0.88);
+const REACHABLE_PLATFORM_COLOR: Color = Color::srgb(0.5, 0.62, 0.52); // Faint green
+const ONE_WAY_ALPHA: f32 = 0.5;
+// Crumbling platforms are darker than their surface
+const CRUMBLING_SHADE: f32 = 0.7;
+// Blinks per second of a platform about to fall
+const CRUMBLE_BLINK_RATE: f32 = 12.0;
+// How far (in pixels) and how fast (in radians per second) it shakes meanwhile
+const CRUMBLE_SHAKE: f32 = 3.0;
+const CRUMBLE_SHAKE_SPEED: f32 = 60.0;
+// Trap platforms take on some of their trap's color
+const TILT_TRAP_COLOR: Color = Color::srgb(0.6, 0.3, 0.8); // Purple
+const LAUNCH_TRAP_COLOR: Color = Color::srgb(1.0, 0.55, 0.1); // Orange
+const TRAP_TINT: f32 = 0.4;
+
+/// How the Classic-mode level on screen was generated, for favoriting it, and
+/// its theme. Where its player respawns is in `RespawnPoint`.
+#[derive(Resource, Default)]
+pub struct CurrentLevel {
+    pub run_seed: u64,
+    pub level: u32,
+    pub difficulty: f32,
+    pub theme: LevelTheme,
+}
+
+/// Spawns the platforms (out of `pool` while it has any) and returns their
+/// entities, in the same order
+pub fn spawn_platforms(commands: &mut Commands, pool: &mut EntityPool, platforms: &[PlatformSpec]) -> Vec<Entity> {
+    platforms.iter().map(|platform| {
+        let (size, rotation) = platform_shape(platform);
+        let mut entity = pool.take(commands, PoolKind::Platform);
+        entity.insert((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: PLATFORM_COLOR,
+                    custom_size: Some(size),
+                    ..default()
+                },
+                transform: Transform::from_translation(Vec3::new(platform.x, platform.y, 0.0)).with_rotation(rotation),
+                ..default()
+            },
+            Platform { width: platform.width, height: platform.height, slope: platform.slope },
+            PlatformOwner::default(),
+        ));
+        if platform.one_way {
+            entity.insert(OneWay);
+        }
+        entity.id()
+    }).collect()
+}
+
+/// The size and turn of a platform's sprite: a ramp is drawn as a bar along
+/// its slope, as thick across as the slab is
+pub fn platform_shape(platform: &PlatformSpec) -> (Vec2, Quat) {
+    let stretch = platform.slope.hypot(1.0);
+    (Vec2::new(platform.width * stretch, platform.height / stretch), Quat::from_rotation_z(platform.slope.atan()))
+}
+
+/// One-way platforms are see-through, so they read as something to jump
+/// through, crumbling ones are darker and traps are tinted by their kind
+pub fn surface_color(surface: Surface, one_way: bool, crumbling: bool, trap: Option<TrapKind>) -> Color {
+    let color = match surface {
+        Surface::Stone => PLATFORM_COLOR,
+        Surface::Wood => WOOD_COLOR,
+        Surface::Metal => METAL_COLOR,
+        Surface::Ice => ICE_COLOR,
+    };
+    let color = if crumbling { color.mix(&Color::BLACK, 1.0 - CRUMBLING_SHADE) } else { color };
+    let color = match trap {
+        Some(TrapKind::Tilt) => color.mix(&TILT_TRAP_COLOR, TRAP_TINT),
+        Some(TrapKind::Launch { .. }) => color.mix(&LAUNCH_TRAP_COLOR, TRAP_TINT),
+        None => color,
+    };
+    if one_way {
+        color.with_alpha(ONE_WAY_ALPHA)
+    } else {
+        color
+    }
+}
+
+/// What the platform right under a body's feet is made of; stone when it's
+/// standing on nothing in particular
+pub fn surface_under<'a>(
+    position: Vec2,
+    size: f32,
+    platforms: impl IntoIterator<Item = (&'a Transform, &'a Platform, Option<&'a PlatformSurface>)>,
+) -> Surface {
+    let feet = position.y - size / 2.0;
+    platforms
+        .into_iter()
+        .find(|(transform, platform, _)| {
+            (platform.spec(transform, false).top_at(position.x) - feet).abs() < 2.0
+                && (transform.translation.x - position.x).abs() < (platform.width + size) / 2.0
+        })
+        .and_then(|(_, _, surface)| surface.map(|surface| surface.0))
+        .unwrap_or_default()
+}
+
+/// A Classic-mode level worked out and ready to spawn
+pub struct PlannedLevel {
+    pub layout: Level,
+    pub player_spawn: Vec2,
+    pub run_seed: u64,
+    pub level: u32,
+    pub difficulty: f32,
+    pub profile: JumpProfile,
+    /// Why the level may not be finishable, for a hand-made level this jump
+    /// can't clear
+    pub warning: Option<String>,
+}
+
+/// Works out a Classic-mode level whose fruit this player can reach, or takes
+/// `handmade` (the hand-made level with that number) if there is one. A
+/// hand-made level without a theme of its own gets the one `curve` gives its
+/// number. Touches
+/// nothing in the world, so it can run off the main thread (see `transition`).
+pub fn plan_classic_level(
+    handmade: Option<HandmadeLevel>,
+    run_seed: u64,
+    level: u32,
+    difficulty: f32,
+    profile: JumpProfile,
+    curve: &DifficultyCurve,
+) -> PlannedLevel {
+    let _span = info_span!("plan_classic_level", level).entered();
+    let mut warning = None;
+    let (layout, player_spawn) = match handmade {
+        Some(handmade) => {
+            if !handmade.level.finishable(&profile) {
+                warning = Some(format!("hand-made level {} can't be finished with the current jump", level));
+            }
+            let mut layout = handmade.level;
+            layout.theme = handmade.theme.unwrap_or_else(|| curve.theme(level));
+            (layout, handmade.player_spawn)
+        }
+        None => (Level::generate(run_seed, level, difficulty, &profile, curve), Vec2::from(PLAYER_SPAWN)),
+    };
+    PlannedLevel { layout, player_spawn, run_seed, level, difficulty, profile, warning }
+}
+
+/// Plans and spawns a Classic-mode level straight away. Returns where the
+/// player starts.
+pub fn spawn_classic_level(
+    commands: &mut Commands,
+    pool: &mut EntityPool,
+    levels: &dyn LevelProvider,
+    run_seed: u64,
+    level: u32,
+    difficulty: f32,
+    profile: JumpProfile,
+    curve: &DifficultyCurve,
+) -> Vec2 {
+    let planned = plan_classic_level(levels.level(level), run_seed, level, difficulty, profile, curve);
+    spawn_planned_level(commands, pool, &planned)
+}
+
+/// Spawns a planned level, its platforms and fruit out of `pool` while it has
+/// any, and passes on its warning as a `RuntimeErrorEvent`. Returns where the
+/// player starts.
+pub fn spawn_planned_level(commands: &mut Commands, pool: &mut EntityPool, planned: &PlannedLevel) -> Vec2 {
+    let PlannedLevel { ref layout, player_spawn, run_seed, level, difficulty, profile, ref warning } = *planned;
+    if let Some(warning) = warning {
+        let error = RuntimeErrorEvent::new("This level may not be finishable with this jump", warning.clone());
+        commands.add(move |world: &mut World| {
+            world.send_event(error);
+        });
+    }
+    commands.insert_resource(CurrentLevel { run_seed, level, difficulty, theme: layout.theme });
+    commands.insert_resource(RespawnPoint(player_spawn));
+    commands.insert_resource(FruitGoal { collected: 0, target: layout.fruit_goal() });
+    commands.insert_resource(LevelLayout(generation::LevelLayout::with_spawn(layout.platforms.clone(), player_spawn.into())));
+    let platforms = spawn_platforms(commands, pool, &layout.platforms);
+    let look_seed = generation::look_seed(run_seed, level);
+    for (index, ((&platform, spec), &surface)) in platforms.iter().zip(&layout.platforms).zip(&layout.surfaces).enumerate() {
+        let crumbling = layout.crumbling.iter().any(|crumbling| crumbling.platform == index);
+        let trap = layout.traps.iter().find(|trap| trap.platform == index).map(|trap| trap.kind);
+        let look = PlatformLook::new(look_seed, index, spec.width);
+        commands.entity(platform).insert((PlatformSurface(surface), Sprite {
+            color: dressed_color(themed_color(surface_color(surface, spec.one_way, crumbling, trap), layout.theme), &look),
+            custom_size: Some(platform_shape(spec).0),
+            ..default()
+        }));
+        dress_platform(commands, platform, spec, look);
+    }
+    for motion in &layout.moving {
+        if let Some(&platform) = platforms.get(motion.platform) {
+            commands.entity(platform).insert(MovingPlatform(motion.clone()));
+        }
+    }
+    for crumbling in &layout.crumbling {
+        if let Some(&platform) = platforms.get(crumbling.platform) {
+            commands.entity(platform).insert(Crumbling(*crumbling));
+        }
+    }
+    for trap in &layout.traps {
+        if let Some(&platform) = platforms.get(trap.platform) {
+            commands.entity(platform).insert(Trap(*trap));
+        }
+    }
+    for conveyor in &layout.conveyors {
+        if let Some(&platform) = platforms.get(conveyor.platform) {
+            commands.entity(platform).insert(ConveyorPlatform(*conveyor));
+        }
+    }
+    for enemy in &layout.enemies {
+        spawn_enemy(commands, *enemy);
+    }
+    if let Some((kind, position)) = layout.fruit {
+        let fruit = spawn_fruit(commands, pool, position, kind);
+        if let Some(fleeing) = FleeingFruit::new(&layout.platforms, position, profile).filter(|_| kind == FruitKind::Fleeing) {
+            commands.entity(fruit).insert(Fleeing(fleeing));
+        }
+    }
+    for &position in &layout.goal_fruit {
+        spawn_fruit(commands, pool, position, FruitKind::Normal);
+    }
+    if let Some(exit) = &layout.exit {
+        spawn_exit(commands, exit);
+    }
+    for &position in &layout.bonus_fruit {
+        spawn_bonus_fruit(commands, pool, position);
+    }
+    for &(power_up, position) in &layout.power_ups {
+        spawn_power_up(commands, position, power_up);
+    }
+    for &position in &layout.checkpoints {
+        spawn_checkpoint(commands, position);
+    }
+    for &ladder in &layout.ladders {
+        spawn_ladder(commands, ladder);
+    }
+    for &hazard in &layout.hazards {
+        spawn_hazard(commands, hazard);
+    }
+    for &wind in &layout.winds {
+        spawn_wind(commands, wind);
+    }
+    for (index, &pair) in layout.portals.iter().enumerate() {
+        spawn_portal_pair(commands, pair, index);
+    }
+    player_spawn
+}
+
+/// Starts crumbling platforms someone stands on (see `StandingOn`), and drops and brings them back
+/// on their timers. A crumbling platform cracks, shakes and blinks; a fallen one is
+/// hidden and no longer collides (see `check_collisions`). The shake only moves
+/// the sprite, so the platform stays where the player stands on it.
+pub fn crumble_platforms(
+    time: Res<Time>,
+    settings: Res<Settings>,
+    mut platform_query: Query<(Entity, &Transform, &Platform, &mut Crumbling, &mut Visibility, &mut Sprite), Without<Player>>,
+    player_query: Query<&StandingOn, With<Player>>,
+    mut particle_events: EventWriter<SpawnParticles>,
+    mut sound_events: EventWriter<PlaySoundEvent>,
+) {
+    for (entity, transform, platform, mut crumbling, mut visibility, mut sprite) in platform_query.iter_mut() {
+        let stood = player_query.iter().any(|standing_on| standing_on.0 == entity);
+        let was_solid = crumbling.0.solid();
+        let was_whole = crumbling.0.state == CrumbleState::Solid;
+        crumbling.0.update(stood, time.delta_seconds());
+        if was_whole && matches!(crumbling.0.state, CrumbleState::Crumbling(_)) {
+            sound_events.send(PlaySoundEvent { sound_type: SoundType::Crack, position: Some(transform.translation.truncate()) });
+        }
+        if was_solid && !crumbling.0.solid() {
+            particle_events.send(SpawnParticles { effect: ParticleEffect::LandingDust, position: transform.translation.truncate() });
+        }
+        let shown = match crumbling.0.state {
+            CrumbleState::Solid => true,
+            CrumbleState::Crumbling(left) => ((left * CRUMBLE_BLINK_RATE) as u32).is_multiple_of(2),
+            CrumbleState::Fallen(_) => false,
+        };
+        visibility.set_if_neq(if shown { Visibility::Inherited } else { Visibility::Hidden });
+        let anchor = match crumbling.0.state {
+            CrumbleState::Crumbling(left) if !settings.reduced_motion => {
+                let shake = (left * CRUMBLE_SHAKE_SPEED).sin() * CRUMBLE_SHAKE;
+                Anchor::Custom(Vec2::new(shake / platform.width, 0.0))
+            }
+            _ => Anchor::Center,
+        };
+        if sprite.anchor != anchor {
+            sprite.anchor = anchor;
+        }
+    }
+}
+
+/// Moves the moving platforms along their paths, carrying whoever stands on them
+pub fn move_platforms(
+    time: Res<Time>,
+    mut platform_query: Query<(Entity, &mut Transform, &Platform, &mut MovingPlatform, Has<OneWay>), Without<Player>>,
+    mut player_query: Query<(&mut Transform, &Velocity, &Grounded, &Collider, &StandingOn), With<Player>>,
+) {
+    for (entity, mut transform, platform, mut moving, one_way) in platform_query.iter_mut() {
+        let before = platform.spec(&transform, one_way);
+        moving.0.update(time.delta_seconds());
+        let (x, y) = moving.0.position();
+        transform.translation.x = x;
+        transform.translation.y = y;
+        let after = PlatformSpec { x, y, ..before };
+
+        for (mut player_transform, velocity, grounded, collider, standing_on) in player_query.iter_mut() {
+            if standing_on.0 != entity {
+                continue;
+            }
+            let mut body = player_body(&player_transform, velocity, grounded, collider);
+            carry(&mut body, &before, &after);
+            player_transform.translation.x = body.x;
+            player_transform.translation.y = body.y;
+        }
+    }
+}
+
+// Platform hints assist: tints the platforms a single jump can reach from the
+// platform a player is standing on (or last stood on)
+fn highlight_reachable_platforms(
+    assist_mode: Res<AssistMode>,
+    player_profile: Res<PlayerProfile>,
+    run_modifiers: Res<RunModifiers>,
+    current_level: Res<CurrentLevel>,
+    // Each player's, with how far they can jump
+    mut standing_on: Local<HashMap<Entity, (PlatformSpec, JumpProfile)>>,
+    player_query: Query<(Entity, &Transform, &Grounded, &Collider), With<Player>>,
+    mut platform_query: Query<(&Transform, &Platform, Option<&PlatformSurface>, Option<&PlatformDressing>, Has<OneWay>, Has<Crumbling>, Option<&Trap>, &mut Sprite), Without<Player>>,
+) {
+    let spec = |transform: &Transform, platform: &Platform, one_way: bool| platform.spec(transform, one_way);
+    // Players gone since last frame are forgotten
+    standing_on.retain(|player, _| player_query.contains(*player));
+    for (player, player_transform, grounded, collider) in player_query.iter() {
+        if !grounded.0 {
+            continue;
+        }
+        let feet = player_transform.translation.y - collider.size / 2.0;
+        let below = platform_query.iter().map(|(transform, platform, _, _, one_way, _, _, _)| spec(transform, platform, one_way)).find(|platform| {
+            (platform.top_at(player_transform.translation.x) - feet).abs() < 2.0
+                && (platform.x - player_transform.translation.x).abs() < (platform.width + collider.size) / 2.0
+        });
+        if let Some(below) = below {
+            standing_on.insert(player, (below, classic_jump_profile(&run_modifiers, &assist_mode, &player_profile, collider.size)));
+        }
+    }
+    // Forget platforms from the previous level
+    standing_on.retain(|_, (from, _)| platform_query.iter().any(|(transform, platform, _, _, one_way, _, _, _)| spec(transform, platform, one_way) == *from));
+
+    for (transform, platform, surface, dressing, one_way, crumbling, trap, mut sprite) in platform_query.iter_mut() {
+        let target = spec(transform, platform, one_way);
+        let reachable = assist_mode.0.reachable_hints
+            && standing_on.values().any(|(from, profile)| *from != target && profile.can_hop(from, &target));
+        let color = if reachable {
+            REACHABLE_PLATFORM_COLOR
+        } else {
+            let color = surface_color(surface.map_or(Surface::Stone, |surface| surface.0), one_way, crumbling, trap.map(|trap| trap.0.kind));
+            let color = themed_color(color, current_level.theme);
+            dressing.map_or(color, |dressing| dressed_color(color, &dressing.0))
+        };
+        if sprite.color != color {
+            sprite.color = color;
+        }
+    }
+}
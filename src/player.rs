@@ -0,0 +1,240 @@
+//! The Classic player: spawning, status effects, the darkness overlay and
+//! losing lives. `check_player_death` sends `PlayerDiedEvent` and
+//! `handle_player_death` takes the life and respawns the player. A co-op run
+//! has a second player (`CoopPartner`) that dies and respawns the same way,
+//! drawing on the same lives.
+
+use bevy::prelude::*;
+use crate::prelude::*;
+use rustbevy_core::config::{WINDOW_HEIGHT, WINDOW_WIDTH};
+use rustbevy_core::enemy::{stomp_bounce, ENEMY_STOMP_SCORE};
+use rustbevy_core::physics;
+use rustbevy_core::status::StatusEffect;
+
+use crate::physics::player_body;
+use crate::spatial::SpatialIndex;
+use crate::ui::{StatusText, Toasts};
+
+pub struct PlayerPlugin;
+
+impl Plugin for PlayerPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<PlayerDiedEvent>()
+            .add_systems(Update, (
+                tick_status_effects,
+                (check_player_death, handle_player_death).chain(),
+                update_darkness,
+            ).run_if(in_state(AppState::Playing).and_then(resource_equals(GameMode::Classic))));
+    }
+}
+
+/// Half the size of the square the player can still see in the dark
+pub const DARKNESS_VIEW_RADIUS: f32 = 110.0;
+
+/// One of the four black panels that leave only a square around the player visible
+#[derive(Component)]
+pub enum DarknessOverlay {
+    Left,
+    Right,
+    Top,
+    Bottom,
+}
+
+/// The Classic-mode player at the level's spawn point
+pub fn spawn_player(commands: &mut Commands, size: f32, position: Vec2) -> Entity {
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: Color::srgb(0.0, 0.5, 1.0),
+                custom_size: Some(Vec2::splat(size)),
+                ..default()
+            },
+            transform: Transform::from_translation(position.extend(0.0)),
+            ..default()
+        },
+        Player,
+        LeadPlayer,
+        PlayerControls::single_player(),
+        Velocity { x: 0.0, y: 0.0 },
+        Grounded(false),
+        WallContact::default(),
+        JumpState::default(),
+        JumpPress::default(),
+        Collider { size },
+        AnimationState::default(),
+        PlayerStatus::default(),
+    )).id()
+}
+
+/// The second player of a co-op run: the Classic player on the partner's
+/// keys, tinted so the two can be told apart
+pub fn spawn_coop_partner(commands: &mut Commands, size: f32, position: Vec2) -> Entity {
+    let partner = spawn_player(commands, size, position);
+    commands.entity(partner).insert((
+        Sprite {
+            color: Color::srgb(1.0, 0.2, 0.2),
+            custom_size: Some(Vec2::splat(size)),
+            ..default()
+        },
+        PlayerControls::party_slot(0),
+        CoopPartner,
+    )).remove::<LeadPlayer>();
+    partner
+}
+
+pub fn tick_status_effects(
+    time: Res<Time>,
+    mut player_query: Query<(&mut PlayerStatus, Has<CoopPartner>), With<Player>>,
+    mut status_query: Query<&mut Text, With<StatusText>>,
+) {
+    for (mut player_status, _) in player_query.iter_mut() {
+        player_status.0.tick(time.delta_seconds());
+    }
+    if let Ok(mut text) = status_query.get_single_mut() {
+        // The partner's effects are marked, so each player knows which are theirs
+        let effects: Vec<String> = player_query
+            .iter()
+            .flat_map(|(player_status, partner)| {
+                let owner = if partner { "P2 " } else { "" };
+                player_status.0.active().iter().map(move |(effect, remaining)| format!("{}{} {:.0}s", owner, effect.name(), remaining.ceil()))
+            })
+            .collect();
+        text.sections[0].value = effects.join("   ");
+    }
+}
+
+// Keeps the darkness panels around the player while the debuff lasts (in a
+// co-op run, around a player who has it)
+fn update_darkness(
+    player_query: Query<(&Transform, &PlayerStatus), (With<Player>, Without<DarknessOverlay>)>,
+    mut overlay_query: Query<(&DarknessOverlay, &mut Transform, &mut Visibility)>,
+) {
+    let player = player_query.iter().find(|(_, status)| status.0.has(StatusEffect::Darkness));
+    let dark = player.is_some();
+    let center = player.map_or(Vec3::ZERO, |(transform, _)| transform.translation);
+    for (panel, mut transform, mut visibility) in overlay_query.iter_mut() {
+        *visibility = if dark { Visibility::Visible } else { Visibility::Hidden };
+        let offset = match panel {
+            DarknessOverlay::Left => Vec2::new(-DARKNESS_VIEW_RADIUS - WINDOW_WIDTH, 0.0),
+            DarknessOverlay::Right => Vec2::new(DARKNESS_VIEW_RADIUS + WINDOW_WIDTH, 0.0),
+            DarknessOverlay::Top => Vec2::new(0.0, DARKNESS_VIEW_RADIUS + WINDOW_HEIGHT),
+            DarknessOverlay::Bottom => Vec2::new(0.0, -DARKNESS_VIEW_RADIUS - WINDOW_HEIGHT),
+        };
+        transform.translation.x = center.x + offset.x;
+        transform.translation.y = center.y + offset.y;
+    }
+}
+
+/// Only spots the death; losing the life follows from the event. Landing on an
+/// enemy defeats it instead, for points and a bounce. A shield takes a hit
+/// from the side, and the enemy with it, but not spikes or lava. Co-op players share the
+/// lives, so two deaths in one frame cost two of them.
+pub fn check_player_death(
+    mut commands: Commands,
+    index: Res<SpatialIndex>,
+    mut game_state: ResMut<GameState>,
+    mut player_query: Query<(Entity, &Transform, &mut Velocity, &mut Grounded, &Collider, &mut PlayerStatus), With<Player>>,
+    enemy_query: Query<(&Enemy, &Transform), Without<Player>>,
+    hazard_query: Query<&HazardArea>,
+    assist_mode: Res<AssistMode>,
+    mut toasts: ResMut<Toasts>,
+    mut death_events: EventWriter<PlayerDiedEvent>,
+    mut particle_events: EventWriter<SpawnParticles>,
+) {
+    let mut lives = game_state.lives;
+    // Enemies stomped or shielded against this frame, which a co-op partner can't touch again
+    let mut defeated = Vec::new();
+    for (player_entity, player_transform, mut velocity, mut grounded, collider, mut player_status) in player_query.iter_mut() {
+        // Check if player fell below the level or ran into an enemy or a hazard
+        let mut body = player_body(player_transform, &velocity, &grounded, collider);
+        let touchables = index.touchables_near(player_transform.translation.truncate(), collider.size / 2.0);
+        let enemies: Vec<(Entity, &Enemy, &Transform)> = touchables
+            .iter()
+            .filter(|candidate| !defeated.contains(*candidate))
+            .filter_map(|&candidate| enemy_query.get(candidate).ok().map(|(enemy, transform)| (candidate, enemy, transform)))
+            .collect();
+        if let Some(&(enemy_entity, _, enemy_transform)) = enemies.iter().find(|(_, enemy, _)| enemy.0.stomped_by(&body)) {
+            stomp_bounce(&mut body);
+            velocity.y = body.velocity_y;
+            grounded.0 = body.grounded;
+            game_state.score += ENEMY_STOMP_SCORE;
+            commands.entity(enemy_entity).despawn();
+            defeated.push(enemy_entity);
+            particle_events.send(SpawnParticles { effect: ParticleEffect::DeathExplosion, position: enemy_transform.translation.truncate() });
+            continue;
+        }
+        let mut hit_enemy = enemies.iter().find(|(_, enemy, _)| enemy.0.touches(&body)).map(|&(enemy_entity, _, _)| enemy_entity);
+        if let Some(enemy_entity) = hit_enemy.filter(|_| player_status.0.has(StatusEffect::Shield)) {
+            player_status.0.remove(StatusEffect::Shield);
+            commands.entity(enemy_entity).despawn();
+            defeated.push(enemy_entity);
+            toasts.push("Shield broken!".to_string());
+            hit_enemy = None;
+        }
+        let hit_enemy = hit_enemy.is_some();
+        // The no hazard damage assist makes spikes and lava harmless
+        let hit_hazard = !assist_mode.0.harmless_hazards && touchables.iter().any(|&candidate| hazard_query.get(candidate).is_ok_and(|hazard| hazard.0.touches(&body)));
+        if physics::fell_out_of_world(&body, WorldBounds::CLASSIC) || hit_enemy || hit_hazard {
+            if !assist_mode.0.infinite_lives {
+                lives = lives.saturating_sub(1);
+            }
+            death_events.send(PlayerDiedEvent { player: player_entity, lives_left: lives });
+            particle_events.send(SpawnParticles { effect: ParticleEffect::DeathExplosion, position: player_transform.translation.truncate() });
+        }
+    }
+}
+
+/// Takes the life, then respawns the player or ends the run
+pub fn handle_player_death(
+    mut commands: Commands,
+    mut death_events: EventReader<PlayerDiedEvent>,
+    mut game_state: ResMut<GameState>,
+    mut difficulty: ResMut<Difficulty>,
+    pickup_query: Query<Entity, With<Pickup>>,
+    mutator_mode: Res<MutatorMode>,
+    respawn: Res<RespawnPoint>,
+    status_query: Query<&PlayerStatus>,
+    player_query: Query<(Entity, Has<CoopPartner>), With<Player>>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    // Who died and who respawned this frame, in case a second death ends the run
+    let mut died = Vec::new();
+    let mut respawned = Vec::new();
+    for event in death_events.read() {
+        game_state.lives = event.lives_left;
+        game_state.combo.reset();
+        difficulty.0.record_death();
+
+        // Despawn the player
+        commands.entity(event.player).despawn();
+        died.push(event.player);
+
+        // If no lives left, go to game over screen
+        if game_state.lives == 0 {
+            next_state.set(AppState::GameOver);
+            // A co-op partner still standing goes too, so the next run starts empty
+            let survivors = player_query.iter().map(|(player, _)| player).filter(|player| !died.contains(player));
+            for player in survivors.chain(respawned.drain(..)) {
+                commands.entity(player).despawn();
+            }
+
+            // Clear pickups but keep platforms
+            for entity in pickup_query.iter() {
+                commands.entity(entity).despawn();
+            }
+        } else {
+            // Respawn the player at the last checkpoint (or the level's spawn
+            // point), keeping their status effects
+            let partner = player_query.get(event.player).is_ok_and(|(_, partner)| partner);
+            let player = if partner {
+                spawn_coop_partner(&mut commands, mutator_mode.0.player_size(), respawn.0)
+            } else {
+                spawn_player(&mut commands, mutator_mode.0.player_size(), respawn.0)
+            };
+            respawned.push(player);
+            if let Ok(status) = status_query.get(event.player) {
+                commands.entity(player).insert(status.clone());
+            }
+        }
+    }
+}
@@ -0,0 +1,118 @@
+//! Platforms and fruit recycled from one level to the next.
+//!
+//! Clearing a level used to despawn every platform and fruit on it, only for
+//! the next level to spawn as many again. Now a finished level's platforms and
+//! fruit go into the `EntityPool` instead: each is stripped down to a hidden,
+//! bare sprite (dressing and all its other components gone) and parked, and
+//! the next level's are made out of them, their sprite, transform and
+//! components set afresh as if just spawned. New entities are only spawned
+//! once the pool runs dry. Everything else a level leaves behind is still
+//! despawned. The pool only fills up when a level is cleared to make way for
+//! another (see `LevelTeardown`): moving on to the next level, restarting one,
+//! continuing it or leaving a party round's results. Parked entities stay
+//! around between runs, for the next run's first level.
+
+use bevy::ecs::system::{EntityCommands, SystemParam};
+use bevy::prelude::*;
+use crate::prelude::*;
+
+pub struct PoolPlugin;
+
+impl Plugin for PoolPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<EntityPool>();
+    }
+}
+
+/// Marks an entity parked in the pool
+#[derive(Component)]
+pub struct Pooled;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PoolKind {
+    Platform,
+    Fruit,
+}
+
+/// Parked entities, by what they'll be made into
+#[derive(Resource, Default)]
+pub struct EntityPool {
+    platforms: Vec<Entity>,
+    fruit: Vec<Entity>,
+}
+
+/// What a level leaves behind: its platforms, enemies, pickups, checkpoints,
+/// ladders, hazards, wind, portals and thrown seeds, and which of them can be
+/// pooled
+pub type LevelEntityQuery<'w, 's> = Query<
+    'w,
+    's,
+    (Entity, Has<Platform>, Option<&'static Pickup>),
+    Or<(With<Platform>, With<Enemy>, With<Pickup>, With<Checkpoint>, With<ExitDoor>, With<Key>, With<Ladder>, With<HazardArea>, With<WindZone>, With<PortalEnd>, With<ThrownSeed>)>,
+>;
+
+impl EntityPool {
+    fn parked(&mut self, kind: PoolKind) -> &mut Vec<Entity> {
+        match kind {
+            PoolKind::Platform => &mut self.platforms,
+            PoolKind::Fruit => &mut self.fruit,
+        }
+    }
+
+    /// An entity to make a `kind` out of: a parked one, or a new empty one.
+    /// Insert a whole bundle on it, since it may still have an old sprite and
+    /// transform.
+    pub fn take<'a>(&mut self, commands: &'a mut Commands, kind: PoolKind) -> EntityCommands<'a> {
+        match self.parked(kind).pop() {
+            Some(entity) => {
+                let mut entity = commands.entity(entity);
+                entity.remove::<Pooled>();
+                entity
+            }
+            None => commands.spawn_empty(),
+        }
+    }
+
+    /// Strips `entity` down to a hidden sprite and parks it
+    pub fn release(&mut self, commands: &mut Commands, entity: Entity, kind: PoolKind) {
+        commands.entity(entity).despawn_descendants().retain::<SpriteBundle>().insert((Pooled, Visibility::Hidden));
+        self.parked(kind).push(entity);
+    }
+
+}
+
+/// What a level left behind and the pool its platforms and fruit go to, for
+/// the systems that clear a level to build another
+#[derive(SystemParam)]
+pub struct LevelTeardown<'w, 's> {
+    pub pool: ResMut<'w, EntityPool>,
+    level_query: LevelEntityQuery<'w, 's>,
+}
+
+impl LevelTeardown<'_, '_> {
+    /// Clears up to `count` of the level's entities: platforms and fruit are
+    /// parked, the rest despawned. Returns whether that was the last of them.
+    pub fn clear(&mut self, commands: &mut Commands, count: usize) -> bool {
+        let mut left = self.level_query.iter();
+        for (entity, platform, pickup) in left.by_ref().take(count) {
+            if platform {
+                self.pool.release(commands, entity, PoolKind::Platform);
+            } else if pickup.is_some_and(|pickup| pickup.0 == Collectible::Fruit) {
+                self.pool.release(commands, entity, PoolKind::Fruit);
+            } else {
+                // With whatever hangs off it, like a checkpoint's pennant
+                commands.entity(entity).despawn_recursive();
+            }
+        }
+        left.next().is_none()
+    }
+
+    /// Whether there's nothing of a level left to clear
+    pub fn is_clear(&self) -> bool {
+        self.level_query.is_empty()
+    }
+
+    pub fn clear_all(&mut self, commands: &mut Commands) {
+        self.clear(commands, usize::MAX);
+    }
+}
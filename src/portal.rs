@@ -0,0 +1,155 @@
+//! Portals in Classic levels (see `rustbevy_core::portal` for where generated
+//! levels put them and how a pair sends the player across). Every physics
+//! step, once collisions have settled where the player is, a player inside a
+//! re-armed portal comes out of its twin with their speed kept, or turned if
+//! the pair says so. How long until the portals work again lives on the player
+//! as `PortalCooldown`; a new life or level starts without one. Climbing a
+//! ladder keeps the player out of them.
+//!
+//! Both ends of a pair are drawn in the same color, a faint oval with arms of
+//! light swirling into its middle. With reduced motion on, the swirl holds
+//! still. The swirl is drawn by `PortalVisualsPlugin`, so the headless
+//! simulation can send players through without drawing anything.
+
+use std::f32::consts::TAU;
+
+use bevy::prelude::*;
+use crate::prelude::*;
+use rustbevy_core::portal::{teleport, Portal, PortalPair, PORTAL_HEIGHT, PORTAL_WIDTH};
+
+use crate::physics::{check_collisions, player_body, store_player_body, Interpolated};
+use crate::settings::Settings;
+use crate::trap::react_to_platform_contact;
+
+pub struct PortalPlugin;
+
+impl Plugin for PortalPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(FixedUpdate, teleport_players
+                .after(check_collisions)
+                .after(react_to_platform_contact)
+                .run_if(in_state(AppState::Playing).and_then(resource_equals(GameMode::Classic))))
+            .add_systems(Update, forget_cooldowns);
+    }
+}
+
+/// The swirls in the portals
+pub struct PortalVisualsPlugin;
+
+impl Plugin for PortalVisualsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, draw_portals.run_if(in_state(AppState::Playing).and_then(resource_equals(GameMode::Classic))));
+    }
+}
+
+// The colors pairs take in turn, so it's clear which ends go together
+const PORTAL_COLORS: [Color; 3] = [
+    Color::srgb(0.3, 0.8, 1.0),  // Cyan
+    Color::srgb(1.0, 0.55, 0.1), // Orange
+    Color::srgb(0.75, 0.4, 1.0), // Violet
+];
+const PORTAL_ALPHA: f32 = 0.2;
+const SWIRL_ARMS: usize = 3;
+// Points along each arm, from the rim to the middle
+const SWIRL_POINTS: usize = 16;
+// How far round an arm curls on its way in, in turns
+const SWIRL_CURL: f32 = 0.75;
+// Turns a second
+const SWIRL_SPEED: f32 = 0.6;
+
+/// The color of the level's `pair`th pair of portals
+pub fn portal_color(pair: usize) -> Color {
+    PORTAL_COLORS[pair % PORTAL_COLORS.len()]
+}
+
+// Seconds until portals work again for this player (see `teleport`)
+#[derive(Component)]
+pub(crate) struct PortalCooldown(f32);
+
+/// Spawns both ends of the level's `index`th pair
+pub fn spawn_portal_pair(commands: &mut Commands, pair: PortalPair, index: usize) {
+    for portal in pair.ends() {
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: portal_color(index).with_alpha(PORTAL_ALPHA),
+                    custom_size: Some(Vec2::new(PORTAL_WIDTH, PORTAL_HEIGHT)),
+                    ..default()
+                },
+                transform: Transform::from_translation(Vec3::new(portal.x, portal.y, -0.5)),
+                ..default()
+            },
+            PortalEnd { portal, pair: index },
+        ));
+    }
+}
+
+// Sends players through the portals they've walked into. The way out is a
+// jump across the level, so the drawn position isn't eased there from the
+// way in.
+pub(crate) fn teleport_players(
+    mut commands: Commands,
+    time: Res<Time>,
+    portal_query: Query<&PortalEnd>,
+    mut player_query: Query<(Entity, &mut Transform, &mut Velocity, &mut Grounded, &Collider, Option<&mut PortalCooldown>), (With<Player>, Without<Climbing>)>,
+    mut particle_events: EventWriter<SpawnParticles>,
+) {
+    let portals: Vec<Portal> = portal_query.iter().map(|end| end.portal).collect();
+    for (player, mut transform, mut velocity, mut grounded, collider, cooldown) in player_query.iter_mut() {
+        let mut left = cooldown.as_deref().map_or(0.0, |cooldown| cooldown.0);
+        let mut body = player_body(&transform, &velocity, &grounded, collider);
+        let entry = transform.translation.truncate();
+        let went = teleport(&mut body, &portals, &mut left, time.delta_seconds());
+        match cooldown {
+            Some(mut cooldown) => cooldown.0 = left,
+            None if left > 0.0 => {
+                commands.entity(player).insert(PortalCooldown(left));
+            }
+            None => {}
+        }
+        if !went {
+            continue;
+        }
+        store_player_body(&body, &mut transform, &mut velocity, &mut grounded);
+        commands.entity(player).remove::<(Interpolated, StandingOn)>();
+        for position in [entry, transform.translation.truncate()] {
+            particle_events.send(SpawnParticles { effect: ParticleEffect::LandingDust, position });
+        }
+    }
+}
+
+// A cooldown doesn't outlive a death or the level
+fn forget_cooldowns(
+    mut commands: Commands,
+    mut died_events: EventReader<PlayerDiedEvent>,
+    mut completed_events: EventReader<LevelCompletedEvent>,
+    cooldown_query: Query<Entity, With<PortalCooldown>>,
+) {
+    let died: Vec<Entity> = died_events.read().map(|died| died.player).collect();
+    let completed = completed_events.read().count() > 0;
+    for player in cooldown_query.iter() {
+        if completed || died.contains(&player) {
+            commands.entity(player).remove::<PortalCooldown>();
+        }
+    }
+}
+
+// An oval rim, and arms curling from it into the middle as they turn
+fn draw_portals(time: Res<Time>, settings: Res<Settings>, mut gizmos: Gizmos, portal_query: Query<&PortalEnd>) {
+    let spin = if settings.reduced_motion { 0.0 } else { time.elapsed_seconds() * SWIRL_SPEED * TAU };
+    let half_size = Vec2::new(PORTAL_WIDTH, PORTAL_HEIGHT) / 2.0;
+    for end in portal_query.iter() {
+        let centre = Vec2::new(end.portal.x, end.portal.y);
+        let color = portal_color(end.pair);
+        gizmos.ellipse_2d(centre, 0.0, half_size, color);
+        for arm in 0..SWIRL_ARMS {
+            let start = spin + arm as f32 * TAU / SWIRL_ARMS as f32;
+            let points = (0..=SWIRL_POINTS).map(|point| {
+                let reach = 1.0 - point as f32 / SWIRL_POINTS as f32;
+                let angle = start + (1.0 - reach) * SWIRL_CURL * TAU;
+                centre + Vec2::new(angle.cos(), angle.sin()) * half_size * reach
+            });
+            gizmos.linestrip_2d(points, color.with_alpha(0.6));
+        }
+    }
+}
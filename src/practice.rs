@@ -0,0 +1,136 @@
+// Practice HUD: live physics numbers for the Classic-mode player.
+//
+// F4 toggles a small overlay in the bottom left showing the player's velocity,
+// how long they've been off the ground (in seconds and 60 Hz frames), the
+// coyote and jump buffer windows (and whether they're open right now), mid-air
+// jumps left and the apex height of the last jump. Handy
+// for tuning and for players who want to know exactly what their jumps do.
+
+use bevy::prelude::*;
+use crate::prelude::*;
+use rustbevy_core::config::{WINDOW_HEIGHT, WINDOW_WIDTH};
+use rustbevy_core::physics::{COYOTE_SECONDS, JUMP_BUFFER_SECONDS};
+
+
+pub struct PracticePlugin;
+
+impl Plugin for PracticePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PracticeHud>()
+            .add_systems(Update, (
+                track_jump_stats.run_if(in_state(AppState::Playing).and_then(resource_equals(GameMode::Classic))),
+                (toggle_practice_hud, update_practice_hud).chain(),
+            ));
+    }
+}
+
+const FRAMES_PER_SECOND: f32 = 60.0;
+
+#[derive(Component)]
+pub struct PracticeHudText;
+
+#[derive(Resource, Default)]
+pub struct PracticeHud {
+    visible: bool,
+    airborne_seconds: f32,
+    // Height the current jump or fall started from, and the highest point since
+    takeoff_y: Option<f32>,
+    peak_y: f32,
+    last_apex: Option<f32>,
+}
+
+pub fn toggle_practice_hud(keyboard_input: Res<ButtonInput<KeyCode>>, mut practice_hud: ResMut<PracticeHud>) {
+    if keyboard_input.just_pressed(KeyCode::F4) {
+        practice_hud.visible = !practice_hud.visible;
+    }
+}
+
+// Runs after collisions so `Grounded` is this frame's value
+pub fn track_jump_stats(
+    time: Res<Time>,
+    player_query: Query<(&Transform, &Grounded), With<LeadPlayer>>,
+    mut practice_hud: ResMut<PracticeHud>,
+) {
+    let Ok((transform, grounded)) = player_query.get_single() else {
+        return;
+    };
+    let y = transform.translation.y;
+    if grounded.0 {
+        if let Some(takeoff_y) = practice_hud.takeoff_y.take() {
+            practice_hud.last_apex = Some(practice_hud.peak_y - takeoff_y);
+        }
+        practice_hud.airborne_seconds = 0.0;
+    } else {
+        if practice_hud.takeoff_y.is_none() {
+            practice_hud.takeoff_y = Some(y);
+            practice_hud.peak_y = y;
+        }
+        practice_hud.peak_y = practice_hud.peak_y.max(y);
+        practice_hud.airborne_seconds += time.delta_seconds();
+    }
+}
+
+pub fn update_practice_hud(
+    mut commands: Commands,
+    app_state: Res<State<AppState>>,
+    game_mode: Res<GameMode>,
+    practice_hud: Res<PracticeHud>,
+    player_query: Query<(&Velocity, &JumpState), With<LeadPlayer>>,
+    mut text_query: Query<(Entity, &mut Text), With<PracticeHudText>>,
+) {
+    let showing = practice_hud.visible && *app_state.get() == AppState::Playing && *game_mode == GameMode::Classic;
+    let Some((velocity, jump_state)) = player_query.get_single().ok().filter(|_| showing) else {
+        for (entity, _) in text_query.iter() {
+            commands.entity(entity).despawn();
+        }
+        return;
+    };
+
+    let seconds = practice_hud.airborne_seconds;
+    let apex = practice_hud.last_apex.map_or("-".to_string(), |apex| format!("{:.1}", apex));
+    let lines = [
+        format!("Velocity: x {:.1}  y {:.1}", velocity.x, velocity.y),
+        format!("Since grounded: {:.3}s ({} frames)", seconds, (seconds * FRAMES_PER_SECOND).round()),
+        format!(
+            "Coyote window: {:.2}s ({} frames){}",
+            COYOTE_SECONDS,
+            (COYOTE_SECONDS * FRAMES_PER_SECOND).round(),
+            if !jump_state.0.jumped && seconds > 0.0 && seconds <= COYOTE_SECONDS { " OPEN" } else { "" }
+        ),
+        format!(
+            "Jump buffer: {:.2}s ({} frames){}",
+            JUMP_BUFFER_SECONDS,
+            (JUMP_BUFFER_SECONDS * FRAMES_PER_SECOND).round(),
+            if jump_state.0.buffered > 0.0 { " QUEUED" } else { "" }
+        ),
+        format!("Air jumps left: {}", jump_state.0.air_jumps_left),
+        format!("Last jump apex: {}", apex),
+    ];
+    let value = lines.join("\n");
+
+    match text_query.get_single_mut() {
+        Ok((_, mut text)) => {
+            if text.sections[0].value != value {
+                text.sections[0].value = value;
+            }
+        }
+        Err(_) => {
+            commands.spawn((
+                Text2dBundle {
+                    text: Text::from_section(
+                        value,
+                        TextStyle {
+                            font_size: 18.0,
+                            color: Color::srgb(0.6, 1.0, 0.6), // Pale green
+                            ..default()
+                        },
+                    )
+                    .with_justify(JustifyText::Left),
+                    transform: Transform::from_translation(Vec3::new(-WINDOW_WIDTH / 2.0 + 170.0, -WINDOW_HEIGHT / 2.0 + 70.0, 12.0)),
+                    ..default()
+                },
+                PracticeHudText,
+            ));
+        }
+    }
+}
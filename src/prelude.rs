@@ -0,0 +1,10 @@
+// Everything an extension plugin usually needs: `use bevy_platformer::prelude::*;`
+
+pub use crate::components::*;
+pub use crate::events::*;
+pub use crate::resources::*;
+pub use rustbevy_core::budget::BudgetCategory;
+pub use rustbevy_core::collectible::Collectible;
+pub use rustbevy_core::generation::PlatformSpec;
+pub use rustbevy_core::surface::Surface;
+pub use rustbevy_core::world::WorldBounds;
@@ -0,0 +1,51 @@
+//! Profiler markers for the heavier work the game does.
+//!
+//! Working out a level, resolving collisions, moving particles and streaming a
+//! level in and out are each timed into a Bevy diagnostic under `profiling/`
+//! (in milliseconds), kept in memory for the F3 overlay (see `debug`). Level
+//! generation and streaming also run in tracing spans of their own; the
+//! collision and particle systems get theirs from Bevy. Level streaming is
+//! clearing the finished level away and spawning the next one behind the
+//! transition title; what's timed there is the game's side of it, as the
+//! entities themselves go when the frame's commands are applied. Built with the
+//! `tracy` feature, Bevy sends these spans and one for every system to the
+//! Tracy profiler.
+
+use bevy::diagnostic::{Diagnostic, DiagnosticPath, RegisterDiagnostic};
+use bevy::prelude::*;
+pub use bevy::utils::Instant;
+
+pub struct ProfilingPlugin;
+
+impl Plugin for ProfilingPlugin {
+    fn build(&self, app: &mut App) {
+        for (_, path) in MARKERS {
+            app.register_diagnostic(Diagnostic::new(path).with_suffix("ms"));
+        }
+    }
+}
+
+pub const GENERATION: DiagnosticPath = DiagnosticPath::const_new("profiling/generation");
+pub const COLLISION: DiagnosticPath = DiagnosticPath::const_new("profiling/collision");
+pub const PARTICLES: DiagnosticPath = DiagnosticPath::const_new("profiling/particles");
+pub const STREAMING: DiagnosticPath = DiagnosticPath::const_new("profiling/streaming");
+
+/// Each marker, with the name the overlay shows it by
+pub const MARKERS: [(&str, DiagnosticPath); 4] = [
+    ("Generation", GENERATION),
+    ("Collision", COLLISION),
+    ("Particles", PARTICLES),
+    ("Streaming", STREAMING),
+];
+
+/// Milliseconds since `start`
+pub fn millis_since(start: Instant) -> f64 {
+    start.elapsed().as_secs_f64() * 1000.0
+}
+
+/// Runs `work`, handing back what it made and how many milliseconds it took
+pub fn timed<T>(work: impl FnOnce() -> T) -> (T, f64) {
+    let start = Instant::now();
+    let made = work();
+    (made, millis_since(start))
+}
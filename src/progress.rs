@@ -0,0 +1,183 @@
+// Run progress saves.
+//
+// A Classic run is saved whenever one of its levels starts or a modifier is
+// drafted. The save holds the run seed and the level with the difficulty it
+// was generated at, which is enough to build that level again exactly. It
+// also holds lives, score and the drafted modifiers. ENTER on the main menu
+// continues the saved run from the start of that level. The save is removed
+// when the run ends, so a lost run can't be continued. Online runs aren't
+// saved.
+
+use bevy::prelude::*;
+use crate::prelude::*;
+use rustbevy_core::modifiers::Modifier;
+use serde::{Deserialize, Serialize};
+
+use crate::chat::chat_closed;
+use crate::favorites::{Favorite, PendingFavorite};
+use crate::game::{read_save_file, remove_save_file, reset_run, setup_game_entities, write_save_file};
+use crate::menu::MainMenuUI;
+use crate::platform::CurrentLevel;
+use crate::streamer::StreamerMode;
+
+pub struct ProgressPlugin;
+
+impl Plugin for ProgressPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(SavedRun(RunSave::load()))
+            .init_resource::<PendingContinue>()
+            .add_systems(OnEnter(AppState::MainMenu), setup_continue_menu_line)
+            .add_systems(OnEnter(AppState::Playing), restore_run.after(reset_run).before(setup_game_entities))
+            .add_systems(OnEnter(AppState::GameOver), forget_run)
+            .add_systems(Update, (
+                continue_run.run_if(in_state(AppState::MainMenu).and_then(chat_closed)),
+                save_run.run_if(in_state(AppState::Playing).and_then(resource_equals(GameMode::Classic))),
+            ));
+    }
+}
+
+const SAVE_FILE: &str = "run.ron";
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct RunSave {
+    pub run_seed: u64,
+    pub level: u32,
+    // What the level was generated at, adaptive difficulty and all
+    pub difficulty: f32,
+    pub lives: u32,
+    pub score: u32,
+    // Drafted modifiers, by name
+    pub modifiers: Vec<String>,
+}
+
+impl RunSave {
+    fn load() -> Option<Self> {
+        let text = read_save_file(SAVE_FILE)?;
+        ron::from_str(&text).ok()
+    }
+
+    fn save(&self) -> Result<(), RuntimeErrorEvent> {
+        let text = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(|error| RuntimeErrorEvent::new("Could not save the run", error.to_string()))?;
+        write_save_file(SAVE_FILE, &text, "the run")
+    }
+
+    fn label(&self, streamer: &StreamerMode) -> String {
+        format!(
+            "ENTER: Continue at level {} ({} lives, {} points, seed {})",
+            self.level,
+            self.lives,
+            self.score,
+            streamer.seed_label(self.run_seed)
+        )
+    }
+}
+
+// The run that can be continued, if there is one
+#[derive(Resource, Default)]
+pub struct SavedRun(pub Option<RunSave>);
+
+// The save to restore when the next run is set up
+#[derive(Resource, Default)]
+struct PendingContinue(Option<RunSave>);
+
+fn setup_continue_menu_line(mut commands: Commands, saved: Res<SavedRun>, streamer: Res<StreamerMode>) {
+    let Some(save) = &saved.0 else {
+        return;
+    };
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                save.label(&streamer),
+                TextStyle {
+                    font_size: 24.0,
+                    color: Color::srgb(0.0, 1.0, 0.0), // Green
+                    ..default()
+                },
+            ),
+            transform: Transform::from_translation(Vec3::new(0.0, -230.0, 10.0)),
+            ..default()
+        },
+        MainMenuUI,
+    ));
+}
+
+// Starts the saved run's level the way a favorite level is started, with the
+// rest of the save restored in `restore_run`
+fn continue_run(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    saved: Res<SavedRun>,
+    mut game_mode: ResMut<GameMode>,
+    mut game_rng: ResMut<GameRng>,
+    mut pending_favorite: ResMut<PendingFavorite>,
+    mut pending_continue: ResMut<PendingContinue>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::Enter) {
+        return;
+    }
+    let Some(save) = &saved.0 else {
+        return;
+    };
+    *game_mode = GameMode::Classic;
+    game_rng.stop_sharing();
+    pending_favorite.0 = Some(Favorite { run_seed: save.run_seed, level: save.level, difficulty: save.difficulty, mode: GameMode::Classic });
+    pending_continue.0 = Some(save.clone());
+    next_state.set(AppState::Playing);
+}
+
+// Puts the saved lives, score and modifiers back once the new run is reset
+fn restore_run(
+    mut reset_events: EventReader<GameResetEvent>,
+    mut pending_continue: ResMut<PendingContinue>,
+    mut game_state: ResMut<GameState>,
+    mut run_modifiers: ResMut<RunModifiers>,
+) {
+    if reset_events.read().last().is_none() {
+        return;
+    }
+    let Some(save) = pending_continue.0.take() else {
+        return;
+    };
+    game_state.lives = save.lives;
+    game_state.score = save.score;
+    run_modifiers.0.picked = save.modifiers.iter().filter_map(|name| Modifier::from_name(name)).collect();
+    game_state.stats.modifiers_picked = run_modifiers.0.picked.len() as u32;
+}
+
+fn save_run(
+    current_level: Res<CurrentLevel>,
+    run_modifiers: Res<RunModifiers>,
+    game_state: Res<GameState>,
+    game_rng: Res<GameRng>,
+    mut saved: ResMut<SavedRun>,
+    mut errors: EventWriter<RuntimeErrorEvent>,
+) {
+    if !(current_level.is_changed() || run_modifiers.is_changed()) || game_rng.sharing() || game_state.lives == 0 {
+        return;
+    }
+    let save = RunSave {
+        run_seed: current_level.run_seed,
+        level: current_level.level,
+        difficulty: current_level.difficulty,
+        lives: game_state.lives,
+        score: game_state.score,
+        modifiers: run_modifiers.0.picked.iter().map(|modifier| modifier.name().to_string()).collect(),
+    };
+    if saved.0.as_ref() == Some(&save) {
+        return;
+    }
+    if let Err(error) = save.save() {
+        errors.send(error);
+    }
+    saved.0 = Some(save);
+}
+
+fn forget_run(game_mode: Res<GameMode>, mut saved: ResMut<SavedRun>, mut errors: EventWriter<RuntimeErrorEvent>) {
+    if *game_mode != GameMode::Classic || saved.0.take().is_none() {
+        return;
+    }
+    if let Err(error) = remove_save_file(SAVE_FILE, "the saved run") {
+        errors.send(error);
+    }
+}
@@ -0,0 +1,125 @@
+//! Thrown seeds in Classic runs (see `rustbevy_core::projectile`).
+//!
+//! F, or the controller's west face button (X / Square), tosses a seed the way
+//! the Classic player faces. Like a jump, the press waits in `ThrowPress` for
+//! the next physics step, which is also where replays record and feed it.
+//! Seeds fly and fall every step after the enemies have moved; one that hits
+//! an enemy defeats it, and one that hits a solid platform or leaves the world
+//! is gone. The pouch (`Seeds`) is shown on the HUD.
+
+use bevy::input::InputSystem;
+use bevy::prelude::*;
+use crate::prelude::*;
+use rustbevy_core::projectile::{SeedPouch, SEED_SIZE};
+
+use crate::camera::spectating;
+use crate::chat::chat_closed;
+use crate::enemy::move_enemies;
+use crate::gamepad::{read_gamepad, GamepadInput};
+use crate::physics::{check_collisions, player_body, solid_platform};
+use crate::replay::ReplayPlayback;
+use crate::spatial::SpatialIndex;
+
+pub struct ProjectilePlugin;
+
+impl Plugin for ProjectilePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Seeds>()
+            .init_resource::<ThrowPress>()
+            .add_systems(PreUpdate, latch_throw_presses
+                .after(InputSystem)
+                .after(read_gamepad)
+                .run_if(in_state(AppState::Playing).and_then(not(spectating)).and_then(chat_closed).and_then(not(resource_exists::<ReplayPlayback>))))
+            .add_systems(Update, refill_seeds)
+            .add_systems(FixedUpdate, (
+                throw_seeds.after(check_collisions).before(move_enemies),
+                move_seeds.after(move_enemies),
+            ).run_if(in_state(AppState::Playing).and_then(resource_equals(GameMode::Classic))));
+    }
+}
+
+const SEED_COLOR: Color = Color::srgb(0.85, 0.7, 0.3); // Husk brown
+
+/// The Classic player's seeds
+#[derive(Resource, Default)]
+pub struct Seeds(pub SeedPouch);
+
+/// A throw pressed since the last physics step
+#[derive(Resource, Default)]
+pub struct ThrowPress(pub bool);
+
+fn latch_throw_presses(keyboard_input: Res<ButtonInput<KeyCode>>, gamepad: Res<GamepadInput>, mut throw_press: ResMut<ThrowPress>) {
+    if keyboard_input.just_pressed(KeyCode::KeyF) || gamepad.throw_just_pressed {
+        throw_press.0 = true;
+    }
+}
+
+// Every run starts with a full pouch
+fn refill_seeds(mut reset_events: EventReader<GameResetEvent>, mut seeds: ResMut<Seeds>, mut throw_press: ResMut<ThrowPress>) {
+    if reset_events.read().count() > 0 {
+        *seeds = Seeds::default();
+        throw_press.0 = false;
+    }
+}
+
+fn throw_seeds(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut seeds: ResMut<Seeds>,
+    mut throw_press: ResMut<ThrowPress>,
+    player_query: Query<(&Transform, &Velocity, &Grounded, &Collider), With<LeadPlayer>>,
+) {
+    let pressed = std::mem::take(&mut throw_press.0);
+    let Ok((transform, velocity, grounded, collider)) = player_query.get_single() else {
+        return;
+    };
+    let body = player_body(transform, velocity, grounded, collider);
+    seeds.0.update(&body, time.delta_seconds());
+    if let Some(projectile) = pressed.then(|| seeds.0.throw(&body)).flatten() {
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: SEED_COLOR,
+                    custom_size: Some(Vec2::splat(SEED_SIZE)),
+                    ..default()
+                },
+                transform: Transform::from_translation(Vec3::new(projectile.x, projectile.y, 0.6)),
+                ..default()
+            },
+            ThrownSeed(projectile),
+        ));
+    }
+}
+
+fn move_seeds(
+    mut commands: Commands,
+    time: Res<Time>,
+    index: Res<SpatialIndex>,
+    platform_query: Query<(&Transform, &Platform, Has<OneWay>, Option<&Crumbling>, Option<&Trap>), Without<ThrownSeed>>,
+    mut seed_query: Query<(Entity, &mut Transform, &mut ThrownSeed)>,
+    enemy_query: Query<(Entity, &Enemy)>,
+) {
+    let mut defeated: Vec<Entity> = Vec::new();
+    for (entity, mut transform, mut seed) in seed_query.iter_mut() {
+        seed.0.update(time.delta_seconds());
+        transform.translation.x = seed.0.x;
+        transform.translation.y = seed.0.y;
+        let hit_enemy = enemy_query.iter().find(|(enemy, ai)| !defeated.contains(enemy) && seed.0.hits(&ai.0));
+        if let Some((enemy, _)) = hit_enemy {
+            commands.entity(enemy).despawn();
+            defeated.push(enemy);
+            commands.entity(entity).despawn();
+            continue;
+        }
+        let platforms: Vec<_> = index
+            .platforms
+            .query((seed.0.x, seed.0.y), (SEED_SIZE, SEED_SIZE))
+            .into_iter()
+            .filter_map(|entity| platform_query.get(entity).ok())
+            .filter_map(|(transform, platform, one_way, crumbling, trap)| solid_platform(transform, platform, one_way, crumbling, trap))
+            .collect();
+        if seed.0.hits_platform(&platforms) || seed.0.out_of(WorldBounds::CLASSIC) {
+            commands.entity(entity).despawn();
+        }
+    }
+}
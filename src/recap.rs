@@ -0,0 +1,251 @@
+// Death recap for Classic runs.
+//
+// The last few seconds of play are kept as snapshots of where the player,
+// the enemies and the fruit were. With the recap on (F6 toggles it), losing a
+// life plays those seconds back in slow motion before the player respawns, so
+// you can see what got you. Any key skips straight back to the game.
+
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+use crate::prelude::*;
+use rustbevy_core::config::{FRUIT_SIZE, WINDOW_HEIGHT};
+use rustbevy_core::enemy::ENEMY_SIZE;
+
+use crate::chat::chat_closed;
+use crate::enemy::move_enemies;
+use crate::player::check_player_death;
+use crate::ui::Toasts;
+
+pub struct RecapPlugin;
+
+impl Plugin for RecapPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DeathRecap>()
+            .add_systems(OnEnter(AppState::DeathRecap), enter_recap)
+            .add_systems(OnExit(AppState::DeathRecap), exit_recap)
+            .add_systems(Update, (
+                toggle_recap.run_if(chat_closed),
+                (
+                    record_snapshots.after(move_enemies).before(check_player_death),
+                    start_recap.after(check_player_death),
+                ).run_if(in_state(AppState::Playing).and_then(resource_equals(GameMode::Classic))),
+                play_recap.run_if(in_state(AppState::DeathRecap)),
+            ));
+    }
+}
+
+const RECAP_SECONDS: f32 = 3.0;
+const RECAP_SPEED: f32 = 0.35;
+const GHOST_Z: f32 = 5.0;
+
+struct Snapshot {
+    time: f32,
+    level: u32,
+    player: Option<(Vec2, f32)>,
+    enemies: Vec<Vec2>,
+    fruit: Option<Vec2>,
+}
+
+#[derive(Resource, Default)]
+pub struct DeathRecap {
+    enabled: bool,
+    snapshots: VecDeque<Snapshot>,
+    // Seconds of the recording played back so far
+    elapsed: f32,
+}
+
+impl DeathRecap {
+    fn duration(&self) -> f32 {
+        match (self.snapshots.front(), self.snapshots.back()) {
+            (Some(first), Some(last)) => last.time - first.time,
+            _ => 0.0,
+        }
+    }
+
+    // The snapshot `elapsed` seconds into the recording
+    fn at(&self, elapsed: f32) -> Option<&Snapshot> {
+        let start = self.snapshots.front()?.time;
+        self.snapshots
+            .iter()
+            .find(|snapshot| snapshot.time - start >= elapsed)
+            .or(self.snapshots.back())
+    }
+}
+
+// Stand-ins drawn during the recap
+#[derive(Component)]
+pub struct RecapGhost {
+    kind: GhostKind,
+}
+
+enum GhostKind {
+    Player,
+    Enemy(usize),
+    Fruit,
+}
+
+#[derive(Component)]
+pub struct RecapUI;
+
+pub fn toggle_recap(keyboard_input: Res<ButtonInput<KeyCode>>, mut recap: ResMut<DeathRecap>, mut toasts: ResMut<Toasts>) {
+    if keyboard_input.just_pressed(KeyCode::F6) {
+        recap.enabled = !recap.enabled;
+        toasts.push(if recap.enabled { "Death recap ON" } else { "Death recap OFF" });
+    }
+}
+
+pub fn record_snapshots(
+    time: Res<Time>,
+    game_state: Res<GameState>,
+    mut recap: ResMut<DeathRecap>,
+    player_query: Query<(&Transform, &Collider), With<LeadPlayer>>,
+    enemy_query: Query<&Transform, With<Enemy>>,
+    fruit_query: Query<&Transform, With<Fruit>>,
+) {
+    if !recap.enabled {
+        return;
+    }
+    let now = time.elapsed_seconds();
+    // A new level starts a fresh recording
+    if recap.snapshots.front().is_some_and(|snapshot| snapshot.level != game_state.level) {
+        recap.snapshots.clear();
+    }
+    while recap.snapshots.front().is_some_and(|snapshot| snapshot.time < now - RECAP_SECONDS) {
+        recap.snapshots.pop_front();
+    }
+    let player = player_query
+        .get_single()
+        .ok()
+        .map(|(transform, collider)| (transform.translation.truncate(), collider.size));
+    // On levels with several fruit, the one nearest the player
+    let origin = player.map_or(Vec2::ZERO, |(position, _)| position);
+    let fruit = fruit_query
+        .iter()
+        .map(|transform| transform.translation.truncate())
+        .min_by(|a, b| a.distance_squared(origin).total_cmp(&b.distance_squared(origin)));
+    recap.snapshots.push_back(Snapshot {
+        time: now,
+        level: game_state.level,
+        player,
+        enemies: enemy_query.iter().map(|transform| transform.translation.truncate()).collect(),
+        fruit,
+    });
+}
+
+// Switches to the recap when a life is lost and the run goes on
+pub fn start_recap(
+    mut death_events: EventReader<PlayerDiedEvent>,
+    mut recap: ResMut<DeathRecap>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    let respawning = death_events.read().any(|event| event.lives_left > 0);
+    if respawning && recap.enabled && !recap.snapshots.is_empty() {
+        recap.elapsed = 0.0;
+        next_state.set(AppState::DeathRecap);
+    }
+}
+
+fn spawn_ghost(commands: &mut Commands, kind: GhostKind, color: Color, size: f32) {
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color,
+                custom_size: Some(Vec2::splat(size)),
+                ..default()
+            },
+            transform: Transform::from_translation(Vec3::new(0.0, 0.0, GHOST_Z)),
+            // Shown once play_recap has placed it
+            visibility: Visibility::Hidden,
+            ..default()
+        },
+        RecapGhost { kind },
+        RecapUI,
+    ));
+}
+
+pub fn enter_recap(
+    mut commands: Commands,
+    recap: Res<DeathRecap>,
+    mut live_query: Query<&mut Visibility, Or<(With<Player>, With<Enemy>, With<Pickup>)>>,
+) {
+    // The live entities hide while their ghosts play back
+    for mut visibility in live_query.iter_mut() {
+        *visibility = Visibility::Hidden;
+    }
+
+    let Some(first) = recap.snapshots.front() else {
+        return;
+    };
+    let player_size = first.player.map_or(0.0, |(_, size)| size);
+    spawn_ghost(&mut commands, GhostKind::Player, Color::srgba(0.0, 0.5, 1.0, 0.8), player_size);
+    for index in 0..first.enemies.len() {
+        spawn_ghost(&mut commands, GhostKind::Enemy(index), Color::srgba(1.0, 0.15, 0.15, 0.8), ENEMY_SIZE);
+    }
+    if first.fruit.is_some() {
+        spawn_ghost(&mut commands, GhostKind::Fruit, Color::srgba(1.0, 0.5, 0.0, 0.8), FRUIT_SIZE);
+    }
+
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                "REPLAY (press any key to skip)",
+                TextStyle {
+                    font_size: 30.0,
+                    color: Color::srgb(1.0, 1.0, 1.0),
+                    ..default()
+                },
+            ),
+            transform: Transform::from_translation(Vec3::new(0.0, WINDOW_HEIGHT / 2.0 - 100.0, 12.0)),
+            ..default()
+        },
+        RecapUI,
+    ));
+}
+
+pub fn play_recap(
+    time: Res<Time<Real>>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut recap: ResMut<DeathRecap>,
+    mut ghost_query: Query<(&RecapGhost, &mut Transform, &mut Visibility)>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    recap.elapsed += time.delta_seconds() * RECAP_SPEED;
+    if recap.elapsed >= recap.duration() || keyboard_input.get_just_pressed().next().is_some() {
+        next_state.set(AppState::Playing);
+        return;
+    }
+
+    let Some(snapshot) = recap.at(recap.elapsed) else {
+        return;
+    };
+    for (ghost, mut transform, mut visibility) in ghost_query.iter_mut() {
+        let position = match ghost.kind {
+            GhostKind::Player => snapshot.player.map(|(position, _)| position),
+            GhostKind::Enemy(index) => snapshot.enemies.get(index).copied(),
+            GhostKind::Fruit => snapshot.fruit,
+        };
+        match position {
+            Some(position) => {
+                transform.translation = position.extend(GHOST_Z);
+                *visibility = Visibility::Inherited;
+            }
+            None => *visibility = Visibility::Hidden,
+        }
+    }
+}
+
+pub fn exit_recap(
+    mut commands: Commands,
+    mut recap: ResMut<DeathRecap>,
+    ui_query: Query<Entity, With<RecapUI>>,
+    mut live_query: Query<&mut Visibility, (Or<(With<Player>, With<Enemy>, With<Pickup>)>, Without<RecapUI>)>,
+) {
+    for entity in ui_query.iter() {
+        commands.entity(entity).despawn();
+    }
+    for mut visibility in live_query.iter_mut() {
+        *visibility = Visibility::Inherited;
+    }
+    recap.snapshots.clear();
+}
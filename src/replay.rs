@@ -0,0 +1,342 @@
+// Replay recording and playback for Classic runs.
+//
+// While a Classic run is played, the input of every physics step (left,
+// right, up and down held, and whether a jump or a seed throw was pressed on
+// that step) is recorded
+// along with the run seed and the modifiers drafted, in the text format of
+// `rustbevy_core::replay` that `validate_run` checks. At game over the replay
+// is saved to the save directory's `replays` folder. Only single-player runs
+// whose levels follow from the seed alone are recorded: not co-op, assisted or
+// mutated runs, runs started with other than the usual lives, custom games,
+// runs in a skin that moves differently, favorite levels, or runs where
+// adaptive difficulty reshapes the levels. Rewinding or continuing stops a recording.
+//
+// `cargo run -- --replay <file>` plays one back: the run starts on the
+// replay's seed and every physics step takes its input from the replay rather
+// than the keyboard or controller, drafts pick what the replay picked, and
+// the game steps exactly one physics step per frame, the way a recording made
+// at 60 fps or more was played. Rules that aren't part of the physics (fruit,
+// deaths, power-ups) are checked once a frame, so a run recorded at a lower
+// frame rate can drift. A run recorded at 60 fps or more plays out the same in
+// `Replay::simulate`, level changes and checkpoint respawns included; the
+// gameplay tests check that. Controller sticks are recorded as full left or
+// right.
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use crate::prelude::*;
+use rustbevy_core::collection::Skin;
+use rustbevy_core::config::{PHYSICS_HZ, PLAYER_SIZE};
+use rustbevy_core::modifiers::Modifier;
+use rustbevy_core::physics::SimInput;
+use rustbevy_core::replay::Replay;
+
+use crate::character_select::PlayerProfile;
+use crate::game::{save_path, setup_game_entities, unix_seconds, write_save_file};
+use crate::gamepad::GamepadInput;
+use crate::helper::summon_helper_platform;
+use crate::menu::ModifierDraft;
+use crate::physics::player_movement;
+use crate::projectile::ThrowPress;
+use crate::ui::Toasts;
+
+pub struct ReplayPlugin;
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ReplayRecorder>()
+            .add_systems(OnEnter(AppState::Playing), start_recording.after(setup_game_entities))
+            .add_systems(OnExit(AppState::ModifierDraft), record_draft)
+            .add_systems(OnEnter(AppState::GameOver), save_replay)
+            .add_systems(FixedUpdate, (record_tick, feed_recorded_input.run_if(resource_exists::<ReplayPlayback>))
+                .before(summon_helper_platform)
+                .before(player_movement)
+                .run_if(in_state(AppState::Playing).and_then(resource_equals(GameMode::Classic))))
+            .add_systems(Update, (
+                start_playback.run_if(in_state(AppState::MainMenu)),
+                pick_recorded_modifier.run_if(in_state(AppState::ModifierDraft)),
+                end_playback,
+            ).run_if(resource_exists::<ReplayPlayback>));
+    }
+}
+
+const REPLAY_DIR: &str = "replays";
+// Stick positions further over than this are recorded as full left or right
+const STICK_THRESHOLD: f32 = 0.5;
+
+// The replay of the Classic run being played, while it's one that can be replayed
+#[derive(Resource, Default)]
+pub struct ReplayRecorder {
+    replay: Option<Replay>,
+    // Drafts that ended with a pick, to tell a pick from a skip
+    picked: usize,
+}
+
+impl ReplayRecorder {
+    pub fn replay(&self) -> Option<&Replay> {
+        self.replay.as_ref()
+    }
+
+    // Stops recording the run, for when it does something replays can't
+    // play back
+    pub fn discard(&mut self) {
+        self.replay = None;
+    }
+}
+
+// A replay being played back
+#[derive(Resource)]
+pub struct ReplayPlayback {
+    replay: Replay,
+    // Physics steps played so far
+    tick: usize,
+    // Drafts played so far
+    drafts: usize,
+    started: bool,
+    // How game time advanced before playback took it over
+    time_strategy: Option<TimeUpdateStrategy>,
+    // The skin the player wore before playback put on the classic one
+    worn_skin: Option<Skin>,
+}
+
+impl ReplayPlayback {
+    pub fn new(replay: Replay) -> Self {
+        Self { replay, tick: 0, drafts: 0, started: false, time_strategy: None, worn_skin: None }
+    }
+
+    // The input of the physics step being simulated
+    pub fn input(&self) -> Option<SimInput> {
+        self.tick.checked_sub(1).and_then(|tick| self.replay.inputs.get(tick)).copied()
+    }
+
+    fn finished(&self) -> bool {
+        self.tick >= self.replay.inputs.len()
+    }
+}
+
+// `--replay <file>` plays back a saved replay
+pub fn replay_argument() -> Option<ReplayPlayback> {
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg != "--replay" {
+            continue;
+        }
+        let Some(path) = args.next() else {
+            println!("--replay needs a file; playing normally");
+            return None;
+        };
+        let replay = match std::fs::read_to_string(&path).map_err(|error| error.to_string()).and_then(|text| Replay::parse(&text)) {
+            Ok(replay) => replay,
+            Err(error) => {
+                println!("Could not read the replay {}: {}; playing normally", path, error);
+                return None;
+            }
+        };
+        if (replay.dt - 1.0 / PHYSICS_HZ).abs() > 1e-6 || replay.player_size != PLAYER_SIZE {
+            println!("{} wasn't recorded by this game (different tick length or player size); playing normally", path);
+            return None;
+        }
+        return Some(ReplayPlayback::new(replay));
+    }
+    None
+}
+
+// A Classic run is recorded from its first step, as long as its levels and
+// physics come from the seed and the inputs alone
+fn start_recording(
+    mut reset_events: EventReader<GameResetEvent>,
+    mut recorder: ResMut<ReplayRecorder>,
+    playback: Option<Res<ReplayPlayback>>,
+    game_rng: Res<GameRng>,
+    game_state: Res<GameState>,
+    difficulty: Res<Difficulty>,
+    assist_mode: Res<AssistMode>,
+    mutator_mode: Res<MutatorMode>,
+    rules_mode: Res<RulesMode>,
+    custom_game: Res<CustomGameMode>,
+    coop_mode: Res<CoopMode>,
+    player_profile: Res<PlayerProfile>,
+) {
+    let Some(event) = reset_events.read().last() else {
+        return;
+    };
+    let replayable = event.mode == GameMode::Classic
+        && playback.is_none()
+        && !coop_mode.0
+        && !assist_mode.0.any()
+        && !mutator_mode.0.any()
+        && rules_mode.0.standard_lives()
+        && !custom_game.active
+        && !player_profile.skin.changes_physics()
+        && !game_state.stats.started_mid_run
+        && (game_rng.fixed_levels() || !difficulty.0.enabled);
+    *recorder = ReplayRecorder::default();
+    if replayable {
+        recorder.replay = Some(Replay {
+            seed: game_rng.run_seed(),
+            dt: 1.0 / PHYSICS_HZ,
+            claimed_level: 1,
+            claimed_ticks: 0,
+            adaptive_difficulty: false,
+            picks: Vec::new(),
+            player_size: PLAYER_SIZE,
+            inputs: Vec::new(),
+        });
+    }
+}
+
+// What the player is doing this step, read the way `player_movement` reads it.
+// A jump is recorded on the step it's pressed for.
+fn record_tick(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    gamepad: Res<GamepadInput>,
+    throw_press: Res<ThrowPress>,
+    mut recorder: ResMut<ReplayRecorder>,
+    player_query: Query<(&JumpPress, &PlayerControls), With<LeadPlayer>>,
+) {
+    let Some(replay) = recorder.replay.as_mut() else {
+        return;
+    };
+    let input = player_query.get_single().map_or(SimInput::default(), |(jump_press, controls)| SimInput {
+        left: PlayerControls::any_pressed(&keyboard_input, &controls.left) || gamepad.horizontal < -STICK_THRESHOLD,
+        right: PlayerControls::any_pressed(&keyboard_input, &controls.right) || gamepad.horizontal > STICK_THRESHOLD,
+        jump: jump_press.0,
+        up: PlayerControls::any_pressed(&keyboard_input, &controls.up) || gamepad.up_held(),
+        down: PlayerControls::any_pressed(&keyboard_input, &controls.down) || gamepad.down_held(),
+        throw: throw_press.0,
+    });
+    replay.inputs.push(input);
+}
+
+// One pick per draft; a skipped draft is recorded as a modifier it didn't
+// offer, which replaying skips too
+fn record_draft(mut recorder: ResMut<ReplayRecorder>, modifier_draft: Res<ModifierDraft>, run_modifiers: Res<RunModifiers>) {
+    let recorder = &mut *recorder;
+    let Some(replay) = recorder.replay.as_mut() else {
+        return;
+    };
+    let pick = if run_modifiers.0.picked.len() > recorder.picked {
+        recorder.picked += 1;
+        run_modifiers.0.picked.last().copied()
+    } else {
+        Modifier::ALL.into_iter().find(|modifier| !modifier_draft.choices.contains(modifier))
+    };
+    if let Some(pick) = pick {
+        replay.picks.push(pick);
+    }
+}
+
+fn save_replay(
+    mut recorder: ResMut<ReplayRecorder>,
+    game_state: Res<GameState>,
+    mut toasts: ResMut<Toasts>,
+    mut errors: EventWriter<RuntimeErrorEvent>,
+) {
+    let Some(mut replay) = recorder.replay.take() else {
+        return;
+    };
+    replay.claimed_level = game_state.level;
+    replay.claimed_ticks = replay.inputs.len() as u64;
+    let timestamp = unix_seconds();
+    let file = format!("{}/run_{}.txt", REPLAY_DIR, timestamp);
+    match write_save_file(&file, &replay.to_text(), "the replay") {
+        Ok(()) => toasts.push(format!("Replay saved - watch it with --replay {}", save_path(&file).display())),
+        Err(error) => {
+            errors.send(error);
+        }
+    }
+}
+
+// Starts the replayed run from the main menu, with nothing on that the
+// recording didn't have, and one physics step per frame
+fn start_playback(
+    mut commands: Commands,
+    mut playback: ResMut<ReplayPlayback>,
+    mut time_strategy: ResMut<TimeUpdateStrategy>,
+    mut game_mode: ResMut<GameMode>,
+    mut game_rng: ResMut<GameRng>,
+    mut assist_mode: ResMut<AssistMode>,
+    mut mutator_mode: ResMut<MutatorMode>,
+    mut coop_mode: ResMut<CoopMode>,
+    mut player_profile: ResMut<PlayerProfile>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    // Back on the menu after the run started: it was quit before the end
+    if playback.started {
+        stop_playback(&mut commands, &mut playback, &mut time_strategy, &mut player_profile);
+        return;
+    }
+    playback.started = true;
+    let frame = TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(playback.replay.dt));
+    playback.time_strategy = Some(std::mem::replace(&mut *time_strategy, frame));
+    *game_mode = GameMode::Classic;
+    *game_rng = GameRng::from_seed(playback.replay.seed);
+    *assist_mode = AssistMode::default();
+    *mutator_mode = MutatorMode::default();
+    coop_mode.0 = false;
+    // Replays are recorded at the classic skin's speeds; the worn skin isn't saved over
+    playback.worn_skin = Some(std::mem::take(&mut player_profile.skin));
+    next_state.set(AppState::Playing);
+}
+
+// Hands the lead player this step's recorded input; a jump is pressed on
+// the steps where the recording starts holding it
+fn feed_recorded_input(
+    mut playback: ResMut<ReplayPlayback>,
+    mut throw_press: ResMut<ThrowPress>,
+    mut player_query: Query<&mut JumpPress, With<LeadPlayer>>,
+) {
+    let previous = playback.input();
+    playback.tick += 1;
+    let jump_pressed = playback.input().is_some_and(|input| input.jump && !previous.is_some_and(|previous| previous.jump));
+    for mut jump_press in player_query.iter_mut() {
+        jump_press.0 = jump_pressed;
+    }
+    throw_press.0 = playback.input().is_some_and(|input| input.throw && !previous.is_some_and(|previous| previous.throw));
+}
+
+fn pick_recorded_modifier(
+    mut playback: ResMut<ReplayPlayback>,
+    modifier_draft: Res<ModifierDraft>,
+    mut run_modifiers: ResMut<RunModifiers>,
+    mut game_state: ResMut<GameState>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut sound_events: EventWriter<PlaySoundEvent>,
+) {
+    let pick = playback.replay.picks.get(playback.drafts).copied();
+    playback.drafts += 1;
+    if let Some(modifier) = pick.filter(|modifier| modifier_draft.choices.contains(modifier)) {
+        run_modifiers.0.picked.push(modifier);
+        if modifier.extra_lives() > 0 {
+            game_state.lives += modifier.extra_lives();
+            sound_events.send(PlaySoundEvent { sound_type: SoundType::ExtraLife, position: None });
+        }
+        game_state.stats.modifiers_picked += 1;
+    }
+    next_state.set(AppState::Playing);
+}
+
+fn end_playback(
+    mut commands: Commands,
+    mut playback: ResMut<ReplayPlayback>,
+    mut time_strategy: ResMut<TimeUpdateStrategy>,
+    mut player_profile: ResMut<PlayerProfile>,
+    mut toasts: ResMut<Toasts>,
+) {
+    if playback.finished() {
+        stop_playback(&mut commands, &mut playback, &mut time_strategy, &mut player_profile);
+        toasts.push("Replay finished - the controls are yours");
+    }
+}
+
+fn stop_playback(commands: &mut Commands, playback: &mut ReplayPlayback, time_strategy: &mut TimeUpdateStrategy, player_profile: &mut PlayerProfile) {
+    if let Some(strategy) = playback.time_strategy.take() {
+        *time_strategy = strategy;
+    }
+    if let Some(skin) = playback.worn_skin.take() {
+        player_profile.skin = skin;
+    }
+    commands.remove_resource::<ReplayPlayback>();
+}
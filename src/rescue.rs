@@ -0,0 +1,116 @@
+//! Fruit rescue in Classic runs: when a fruit has been out of reach of the
+//! platform the player stands on (of every player's, in co-op) for a while (a
+//! crumbled platform broke the only way up, or they dropped somewhere they
+//! can't climb back from), a stepping platform appears or the fruit nearest
+//! them moves somewhere they can reach, as `rustbevy_core::rescue` decides. Checked every physics step from the
+//! platforms as they are right then, fallen and tipped ones left out.
+
+use bevy::prelude::*;
+use crate::prelude::*;
+use rustbevy_core::hazard::clear_footing;
+use rustbevy_core::rescue::{fruit_stranded, rescue, stranded_fruit, FruitWatchdog, Rescue};
+use rustbevy_core::surface::Surface;
+
+use crate::character_select::PlayerProfile;
+use crate::physics::{check_collisions, classic_jump_profile, solid_platform};
+use crate::platform::{spawn_platforms, surface_color};
+use crate::pool::EntityPool;
+use crate::ui::Toasts;
+
+pub struct FruitRescuePlugin;
+
+impl Plugin for FruitRescuePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FruitWatch>()
+            .add_systems(Update, reset_fruit_watch)
+            .add_systems(FixedUpdate, rescue_stranded_fruit
+                .after(check_collisions)
+                .run_if(in_state(AppState::Playing).and_then(resource_equals(GameMode::Classic))));
+    }
+}
+
+#[derive(Resource, Default)]
+struct FruitWatch(FruitWatchdog);
+
+// Every level starts the count over
+fn reset_fruit_watch(
+    mut level_events: EventReader<LevelCompletedEvent>,
+    mut reset_events: EventReader<GameResetEvent>,
+    mut watch: ResMut<FruitWatch>,
+) {
+    if level_events.read().count() + reset_events.read().count() > 0 {
+        watch.0 = FruitWatchdog::default();
+    }
+}
+
+fn rescue_stranded_fruit(
+    mut commands: Commands,
+    time: Res<Time>,
+    run_modifiers: Res<RunModifiers>,
+    assist_mode: Res<AssistMode>,
+    player_profile: Res<PlayerProfile>,
+    mut watch: ResMut<FruitWatch>,
+    mut pool: ResMut<EntityPool>,
+    mut toasts: ResMut<Toasts>,
+    mut particle_events: EventWriter<SpawnParticles>,
+    player_query: Query<(&Transform, &Collider, Option<&StandingOn>), With<Player>>,
+    platform_query: Query<(Entity, &Transform, &Platform, Has<OneWay>, Option<&Crumbling>, Option<&Trap>), Without<Fruit>>,
+    hazard_query: Query<&HazardArea>,
+    mut fruit_query: Query<&mut Transform, (With<Fruit>, Without<Fleeing>, Without<Player>)>,
+) {
+    let fruit: Vec<(f32, f32)> = fruit_query.iter().map(|transform| (transform.translation.x, transform.translation.y)).collect();
+    if fruit.is_empty() {
+        return;
+    }
+    let (entities, platforms): (Vec<Entity>, Vec<PlatformSpec>) = platform_query
+        .iter()
+        .filter_map(|(entity, transform, platform, one_way, crumbling, trap)| {
+            solid_platform(transform, platform, one_way, crumbling, trap).map(|spec| (entity, spec))
+        })
+        .unzip();
+    let hazards: Vec<_> = hazard_query.iter().map(|hazard| hazard.0).collect();
+    let footing = clear_footing(&platforms, &hazards);
+    // Only told from the players standing on a platform. A fruit is stranded
+    // once none of them can reach it, and rescued for the first of them.
+    let standing: Vec<_> = player_query
+        .iter()
+        .filter_map(|(transform, collider, standing_on)| {
+            let standing_on = standing_on?.0;
+            let start = entities.iter().position(|&entity| entity == standing_on)?;
+            let profile = classic_jump_profile(&run_modifiers, &assist_mode, &player_profile, collider.size);
+            Some((start, (transform.translation.x, transform.translation.y), profile))
+        })
+        .collect();
+    let stranded = standing.first().and_then(|(start, player, profile)| {
+        stranded_fruit(&footing, *start, &fruit, *player, profile)
+            .filter(|&index| standing[1..].iter().all(|(start, _, profile)| fruit_stranded(&footing, *start, fruit[index], profile)))
+            .map(|index| (*start, index, profile))
+    });
+    if !watch.0.update((!standing.is_empty()).then_some(stranded.is_some()), time.delta_seconds()) {
+        return;
+    }
+    let Some((start, index, profile)) = stranded else {
+        return;
+    };
+    match rescue(&footing, start, fruit[index], profile) {
+        Rescue::Platform(step) => {
+            for platform in spawn_platforms(&mut commands, &mut pool, &[step]) {
+                commands.entity(platform).insert(Sprite {
+                    color: surface_color(Surface::Stone, step.one_way, false, None),
+                    custom_size: Some(Vec2::new(step.width, step.height)),
+                    ..default()
+                });
+            }
+            toasts.push("A platform appeared to help you reach the fruit");
+        }
+        Rescue::MoveFruit((x, y)) => {
+            let Some(mut fruit_transform) = fruit_query.iter_mut().nth(index) else {
+                return;
+            };
+            fruit_transform.translation.x = x;
+            fruit_transform.translation.y = y;
+            particle_events.send(SpawnParticles { effect: ParticleEffect::FruitBurst, position: Vec2::new(x, y) });
+            toasts.push("The fruit moved where you can reach it");
+        }
+    }
+}
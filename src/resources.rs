@@ -0,0 +1,443 @@
+// Game-wide resources shared with extension plugins
+
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use rustbevy_core::assists::Assists;
+use rustbevy_core::budget::Budget;
+use rustbevy_core::category::RunStats;
+use rustbevy_core::config::PLAYER_SPAWN;
+use rustbevy_core::custom::CustomGame;
+use rustbevy_core::difficulty::{self, AdaptiveDifficulty};
+use rustbevy_core::generation;
+use rustbevy_core::modifiers::ModifierStack;
+use rustbevy_core::mutators::Mutators;
+use rustbevy_core::rng::SeededRng;
+use rustbevy_core::rules::GameRules;
+use rustbevy_core::score::Combo;
+use rustbevy_core::world::WorldBounds;
+
+use crate::components::PlayerControls;
+use crate::game::clock_seed;
+
+// Lives, continues, level, score and fruit combo of the current Classic run,
+// plus what the speedrun categories track (run time, drafted modifiers,
+// missed coins)
+#[derive(Resource)]
+pub struct GameState {
+    pub lives: u32,
+    // Continues left
+    pub continues: u32,
+    pub level: u32,
+    pub score: u32,
+    pub combo: Combo,
+    pub stats: RunStats,
+}
+
+impl Default for GameState {
+    fn default() -> Self {
+        Self::new(GameRules::default())
+    }
+}
+
+impl GameState {
+    // A run starting out under `rules`
+    pub fn new(rules: GameRules) -> Self {
+        Self {
+            lives: rules.starting_lives,
+            continues: rules.continues,
+            level: 1,
+            score: 0,
+            combo: Combo::default(),
+            stats: RunStats::default(),
+        }
+    }
+}
+
+// Which screen the game is on. Screens are set up in `OnEnter` and cleaned up
+// in `OnExit`; gameplay systems only run while `Playing`.
+#[derive(States, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum AppState {
+    // Waiting for the startup assets, with a progress bar
+    #[default]
+    Loading,
+    MainMenu,
+    Playing,
+    GameOver,
+    PartyResults,
+    Lobby,
+    ModifierDraft,
+    // Waiting for the next level to be generated, between levels
+    LevelTransition,
+    Paused,
+    Favorites,
+    // Slow-motion replay of the seconds before losing a life
+    DeathRecap,
+    // Moving HUD readouts around, opened from the pause menu
+    HudEditor,
+    // Rebinding the Classic controls, opened from the main menu
+    Keybindings,
+    // Offering the last session's crash report, between loading and the menu
+    CrashReport,
+    // Walking a first-time player through setup, between loading and their first run
+    Onboarding,
+    // Building a hand-made level, opened from the main menu
+    LevelEditor,
+    // The trophy room, opened from the main menu
+    Hub,
+    // Picking the player's skin, opened from the main menu
+    CharacterSelect,
+    // Display, volume and UI scale settings, opened from the main menu
+    Settings,
+    // Picking how a custom game's levels are generated, opened from the main menu
+    CustomGame,
+    // What this session played, shown on quitting the game before it closes
+    SessionRecap,
+}
+
+// Which rules the current game is played with
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GameMode {
+    #[default]
+    Classic,
+    FruitGrab,
+    Tag,
+    Territory,
+    // The endless tower: climbing a one-screen-wide shaft as it scrolls up
+    Tower,
+}
+
+impl GameMode {
+    pub fn is_party(self) -> bool {
+        matches!(self, Self::FruitGrab | Self::Tag | Self::Territory)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Classic => "Classic",
+            Self::FruitGrab => "Fruit Grab",
+            Self::Tag => "Tag",
+            Self::Territory => "Territory",
+            Self::Tower => "Tower",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        [Self::Classic, Self::FruitGrab, Self::Tag, Self::Territory, Self::Tower].into_iter().find(|mode| mode.name() == name)
+    }
+
+    // Classic levels are bigger than the window; party games share one screen,
+    // and the tower is one screen wide (it has no top or bottom)
+    pub fn world_bounds(self) -> WorldBounds {
+        if self.is_party() || self == Self::Tower {
+            WorldBounds::SCREEN
+        } else {
+            WorldBounds::CLASSIC
+        }
+    }
+}
+
+// Where run seeds come from. Every level of a run is generated from its run
+// seed, so a seed always gives the same levels. Runs draw their seeds from a
+// seeded RNG: from the clock normally, or from `--seed` so a session replays
+// the same runs. The online lobby pins one seed so every peer plays the same
+// levels.
+#[derive(Resource)]
+pub struct GameRng {
+    rng: SeededRng,
+    // The seed `--seed` asked for, used by the first run
+    first: Option<u64>,
+    // Seed shared with the other players of an online session
+    shared: Option<u64>,
+    // Started from `--seed`
+    seeded: bool,
+    run: u64,
+    // Draws made during the run, following from its seed
+    in_run: SeededRng,
+}
+
+impl Default for GameRng {
+    fn default() -> Self {
+        let clock = clock_seed();
+        Self { rng: SeededRng::new(clock), first: None, shared: None, seeded: false, run: clock, in_run: SeededRng::new(clock) }
+    }
+}
+
+impl GameRng {
+    // The first run uses `seed` itself, later ones follow from it
+    pub fn from_seed(seed: u64) -> Self {
+        Self { rng: SeededRng::new(seed), first: Some(seed), shared: None, seeded: true, run: seed, in_run: SeededRng::new(seed) }
+    }
+
+    // Every run plays on `seed` until `stop_sharing`
+    pub fn share_runs(&mut self, seed: u64) {
+        self.shared = Some(seed);
+    }
+
+    pub fn stop_sharing(&mut self) {
+        self.shared = None;
+    }
+
+    // Playing the seed of an online session
+    pub fn sharing(&self) -> bool {
+        self.shared.is_some()
+    }
+
+    // Picks the seed of a new run
+    pub fn start_run(&mut self) -> u64 {
+        self.run = match (self.shared, self.first.take()) {
+            (Some(seed), _) | (None, Some(seed)) => seed,
+            (None, None) => self.rng.next_u64(),
+        };
+        self.in_run = SeededRng::new(self.run);
+        self.run
+    }
+
+    // Continues a run that was played before (a favorite level)
+    pub fn resume_run(&mut self, seed: u64) {
+        self.run = seed;
+        self.in_run = SeededRng::new(seed);
+    }
+
+    // A seed for something placed while the run is played (a party fruit
+    // drop), the same every time the run's seed is played
+    pub fn next_in_run(&mut self) -> u64 {
+        self.in_run.next_u64()
+    }
+
+    pub fn run_seed(&self) -> u64 {
+        self.run
+    }
+
+    // Seeded runs must give the same levels on every machine and every try,
+    // so adaptive difficulty leaves them alone
+    pub fn fixed_levels(&self) -> bool {
+        self.shared.is_some() || self.seeded
+    }
+}
+
+// Sound effect handles. Insert your own before `Startup` runs to replace the
+// default sounds; the game only loads its own when none is present.
+#[derive(Resource)]
+pub struct GameAudio {
+    pub jump_sound: Handle<AudioSource>,
+    pub collect_sound: Handle<AudioSource>,
+    pub death_sound: Handle<AudioSource>,
+}
+
+// Difficulty tracking for the current Classic run. `enabled` is the main menu's
+// adaptive difficulty option; when off, levels follow the base curve.
+#[derive(Resource, Default)]
+pub struct Difficulty(pub AdaptiveDifficulty);
+
+// How difficulty grows with the level number: layout, enemies and moving,
+// one-way and crumbling platforms. Replace it to retune the scaling.
+#[derive(Resource, Default)]
+pub struct DifficultyCurve(pub difficulty::DifficultyCurve);
+
+// The platforms of the level being played, as generated, and which one the
+// player starts on. Fruit placement reads the layout from here rather than
+// querying `Platform` entities, which lag a frame behind a level swap.
+#[derive(Resource, Default)]
+pub struct LevelLayout(pub generation::LevelLayout);
+
+// How many fruit finish the current Classic level, and how many of them have
+// been collected. The level is finished once `collected` reaches `target`.
+#[derive(Resource, Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FruitGoal {
+    pub collected: u32,
+    pub target: u32,
+}
+
+// Assists picked on the main menu; they apply to Classic mode only
+#[derive(Resource, Default)]
+pub struct AssistMode(pub Assists);
+
+// Challenge mutators picked on the main menu; like assists they apply to Classic mode only
+#[derive(Resource, Default)]
+pub struct MutatorMode(pub Mutators);
+
+// Starting lives and continues picked on the main menu, for Classic runs
+#[derive(Resource, Default)]
+pub struct RulesMode(pub GameRules);
+
+// The custom game set up on the Custom Game screen, and whether the current
+// run is one
+#[derive(Resource, Default)]
+pub struct CustomGameMode {
+    pub active: bool,
+    pub game: CustomGame,
+}
+
+// Modifiers drafted so far in the current Classic run. Movement, gravity, fruit
+// pickup and level generation all read their tuning from here.
+#[derive(Resource, Default)]
+pub struct RunModifiers(pub ModifierStack);
+
+// Whether the Classic run is played by two players side by side: a second
+// player on WASD, lives shared, and either player's fruit finishing the level
+#[derive(Resource, Default)]
+pub struct CoopMode(pub bool);
+
+// Where the Classic player comes back after losing a life: the level's spawn
+// point, or the last checkpoint they touched
+#[derive(Resource)]
+pub struct RespawnPoint(pub Vec2);
+
+impl Default for RespawnPoint {
+    fn default() -> Self {
+        Self(Vec2::from(PLAYER_SPAWN))
+    }
+}
+
+// How many particles, enemies and decorations may exist at once. Lower the
+// caps for slower machines; entities spawned with `Budgeted` over a cap are
+// dropped, lowest priority first.
+#[derive(Resource, Default)]
+pub struct EntityBudget(pub Budget);
+
+// How much the game draws beyond the essentials. Low skips particles, the
+// menu's parallax layers and anti-aliasing for slow machines; Fancy adds bloom.
+// Picked on the main menu and kept in the settings file.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum GraphicsQuality {
+    Low,
+    #[default]
+    Normal,
+    Fancy,
+}
+
+impl GraphicsQuality {
+    pub const ALL: [Self; 3] = [Self::Low, Self::Normal, Self::Fancy];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Low => "Low",
+            Self::Normal => "Normal",
+            Self::Fancy => "Fancy",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|quality| quality.name().eq_ignore_ascii_case(name))
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            Self::Low => Self::Normal,
+            Self::Normal => Self::Fancy,
+            Self::Fancy => Self::Low,
+        }
+    }
+
+    pub fn particles(self) -> bool {
+        self != Self::Low
+    }
+
+    pub fn parallax(self) -> bool {
+        self != Self::Low
+    }
+
+    pub fn anti_aliasing(self) -> bool {
+        self != Self::Low
+    }
+
+    pub fn bloom(self) -> bool {
+        self == Self::Fancy
+    }
+}
+
+// Something the Classic player can do with a key
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum InputAction {
+    MoveLeft,
+    MoveRight,
+    Jump,
+    // Climbs up a ladder or rope
+    Up,
+    // Held with jump to drop through a one-way platform; also climbs down
+    Down,
+    Pause,
+}
+
+impl InputAction {
+    pub const ALL: [Self; 6] = [Self::MoveLeft, Self::MoveRight, Self::Jump, Self::Up, Self::Down, Self::Pause];
+
+    // The action's name in the keybindings file
+    pub fn key(self) -> &'static str {
+        match self {
+            Self::MoveLeft => "move_left",
+            Self::MoveRight => "move_right",
+            Self::Jump => "jump",
+            Self::Up => "up",
+            Self::Down => "down",
+            Self::Pause => "pause",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|action| action.key() == key)
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::MoveLeft => "Move left",
+            Self::MoveRight => "Move right",
+            Self::Jump => "Jump",
+            Self::Up => "Up (climb)",
+            Self::Down => "Down (+ jump: drop through, climb)",
+            Self::Pause => "Pause",
+        }
+    }
+}
+
+// The keys bound to each `InputAction` in Classic runs. Party games keep their
+// fixed per-slot keys (see `PlayerControls::party_slot`).
+#[derive(Resource, Clone, Debug, PartialEq)]
+pub struct InputMap {
+    bindings: HashMap<InputAction, Vec<KeyCode>>,
+}
+
+impl Default for InputMap {
+    fn default() -> Self {
+        Self {
+            bindings: HashMap::from([
+                (InputAction::MoveLeft, vec![KeyCode::ArrowLeft, KeyCode::KeyA]),
+                (InputAction::MoveRight, vec![KeyCode::ArrowRight, KeyCode::KeyD]),
+                (InputAction::Jump, vec![KeyCode::Space, KeyCode::ArrowUp, KeyCode::KeyW]),
+                (InputAction::Up, vec![KeyCode::ArrowUp, KeyCode::KeyW]),
+                (InputAction::Down, vec![KeyCode::ArrowDown, KeyCode::KeyS]),
+                (InputAction::Pause, vec![KeyCode::KeyP, KeyCode::Escape]),
+            ]),
+        }
+    }
+}
+
+impl InputMap {
+    pub fn keys(&self, action: InputAction) -> &[KeyCode] {
+        self.bindings.get(&action).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn bind(&mut self, action: InputAction, keys: Vec<KeyCode>) {
+        self.bindings.insert(action, keys);
+    }
+
+    pub fn pressed(&self, action: InputAction, keyboard_input: &ButtonInput<KeyCode>) -> bool {
+        keyboard_input.any_pressed(self.keys(action).iter().copied())
+    }
+
+    pub fn just_pressed(&self, action: InputAction, keyboard_input: &ButtonInput<KeyCode>) -> bool {
+        keyboard_input.any_just_pressed(self.keys(action).iter().copied())
+    }
+
+    // Movement keys for the Classic player entity
+    pub fn player_controls(&self) -> PlayerControls {
+        PlayerControls {
+            left: self.keys(InputAction::MoveLeft).to_vec(),
+            right: self.keys(InputAction::MoveRight).to_vec(),
+            jump: self.keys(InputAction::Jump).to_vec(),
+            up: self.keys(InputAction::Up).to_vec(),
+            down: self.keys(InputAction::Down).to_vec(),
+        }
+    }
+}
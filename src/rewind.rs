@@ -0,0 +1,103 @@
+// Rewind for Classic runs (see `rustbevy_core::rewind`).
+//
+// Every physics step, once collisions have settled where the player is, the
+// player's body goes into `RewindBuffer`. Holding R, or the controller's left
+// bumper, plays it back the other way a step at a time, so a missed jump can
+// be undone; letting go carries on from there with the speed the player had
+// at that moment. Rewinding drains the meter shown on the HUD, which fills up
+// again while the player isn't rewinding. Dying, finishing a level or
+// starting a run forgets where the player has been.
+//
+// Replays only record what was pressed, not rewinds, so the first rewind of a
+// run stops it being recorded.
+
+use bevy::input::InputSystem;
+use bevy::prelude::*;
+use crate::prelude::*;
+use rustbevy_core::rewind::Rewind;
+
+use crate::camera::spectating;
+use crate::chat::chat_closed;
+use crate::gamepad::{read_gamepad, GamepadInput};
+use crate::physics::{check_collisions, player_body};
+use crate::portal::teleport_players;
+use crate::replay::{ReplayPlayback, ReplayRecorder};
+use crate::ui::Toasts;
+
+pub struct RewindPlugin;
+
+impl Plugin for RewindPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RewindBuffer>()
+            .init_resource::<RewindHeld>()
+            .add_systems(PreUpdate, (
+                release_rewind,
+                hold_rewind.run_if(in_state(AppState::Playing).and_then(not(spectating)).and_then(chat_closed).and_then(not(resource_exists::<ReplayPlayback>))),
+            ).chain().after(InputSystem).after(read_gamepad))
+            .add_systems(Update, forget_rewind)
+            .add_systems(FixedUpdate, rewind_player
+                .after(check_collisions)
+                .after(teleport_players)
+                .run_if(in_state(AppState::Playing).and_then(resource_equals(GameMode::Classic))));
+    }
+}
+
+// The Classic player's rewind buffer and meter
+#[derive(Resource, Default)]
+pub struct RewindBuffer(pub Rewind);
+
+// Whether the player is holding rewind this frame
+#[derive(Resource, Default)]
+struct RewindHeld(bool);
+
+fn release_rewind(mut held: ResMut<RewindHeld>) {
+    held.0 = false;
+}
+
+fn hold_rewind(keyboard_input: Res<ButtonInput<KeyCode>>, gamepad: Res<GamepadInput>, mut held: ResMut<RewindHeld>) {
+    held.0 = keyboard_input.pressed(KeyCode::KeyR) || gamepad.rewind_held;
+}
+
+// A new run starts with a full meter; a new life or level only with nothing
+// to rewind to
+fn forget_rewind(
+    mut reset_events: EventReader<GameResetEvent>,
+    mut died_events: EventReader<PlayerDiedEvent>,
+    mut completed_events: EventReader<LevelCompletedEvent>,
+    mut rewind: ResMut<RewindBuffer>,
+) {
+    if reset_events.read().count() > 0 {
+        *rewind = RewindBuffer::default();
+    }
+    if died_events.read().count() > 0 || completed_events.read().count() > 0 {
+        rewind.0.clear();
+    }
+}
+
+// Takes the player a step back while rewind is held and there's buffer and
+// meter left for it, and keeps where the step left them otherwise
+fn rewind_player(
+    time: Res<Time>,
+    held: Res<RewindHeld>,
+    mut rewind: ResMut<RewindBuffer>,
+    mut recorder: ResMut<ReplayRecorder>,
+    mut toasts: ResMut<Toasts>,
+    mut player_query: Query<(&mut Transform, &mut Velocity, &mut Grounded, &Collider), With<LeadPlayer>>,
+) {
+    let Ok((mut transform, mut velocity, mut grounded, collider)) = player_query.get_single_mut() else {
+        return;
+    };
+    let dt = time.delta_seconds();
+    if let Some(body) = held.0.then(|| rewind.0.step_back(dt)).flatten() {
+        transform.translation.x = body.x;
+        transform.translation.y = body.y;
+        *velocity = Velocity { x: body.velocity_x, y: body.velocity_y };
+        grounded.0 = body.grounded;
+        if recorder.replay().is_some() {
+            recorder.discard();
+            toasts.push("Rewound - this run won't be saved as a replay");
+        }
+        return;
+    }
+    rewind.0.record(player_body(&transform, &velocity, &grounded, collider), dt);
+}
@@ -0,0 +1,70 @@
+// High score for Classic runs.
+//
+// The best score is kept in the save directory, loaded at startup and written
+// back whenever a run beats it. Assisted runs, custom games and runs started
+// with more or fewer lives than usual don't count towards it.
+
+use bevy::prelude::*;
+use crate::prelude::*;
+
+use crate::game::{read_save_file, write_save_file};
+
+const HIGH_SCORE_FILE: &str = "high_score.txt";
+
+pub struct ScorePlugin;
+
+impl Plugin for ScorePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<HighScore>()
+            .add_systems(OnEnter(AppState::Playing), start_run)
+            .add_systems(Update, record_high_score.run_if(in_state(AppState::Playing).and_then(resource_equals(GameMode::Classic))));
+    }
+}
+
+#[derive(Resource)]
+pub struct HighScore {
+    pub best: u32,
+    // The current run has set a new high score
+    pub beaten: bool,
+}
+
+impl Default for HighScore {
+    fn default() -> Self {
+        let saved = read_save_file(HIGH_SCORE_FILE).unwrap_or_default();
+        Self {
+            best: saved.trim().parse().unwrap_or(0),
+            beaten: false,
+        }
+    }
+}
+
+impl HighScore {
+    fn save(&self) -> Result<(), RuntimeErrorEvent> {
+        write_save_file(HIGH_SCORE_FILE, &self.best.to_string(), "the high score")
+    }
+}
+
+// A fresh run hasn't beaten anything yet
+fn start_run(mut reset_events: EventReader<GameResetEvent>, mut high_score: ResMut<HighScore>) {
+    if reset_events.read().count() > 0 {
+        high_score.beaten = false;
+    }
+}
+
+fn record_high_score(
+    game_state: Res<GameState>,
+    assist_mode: Res<AssistMode>,
+    rules_mode: Res<RulesMode>,
+    custom_game: Res<CustomGameMode>,
+    mut high_score: ResMut<HighScore>,
+    mut errors: EventWriter<RuntimeErrorEvent>,
+) {
+    if !game_state.is_changed() || assist_mode.0.any() || !rules_mode.0.standard_lives() || custom_game.active || game_state.score <= high_score.best {
+        return;
+    }
+    high_score.best = game_state.score;
+    high_score.beaten = true;
+    if let Err(error) = high_score.save() {
+        errors.send(error);
+    }
+}
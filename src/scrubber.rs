@@ -0,0 +1,304 @@
+// Time-travel scrubber for Classic runs, only built with
+// `--features debug_tools`.
+//
+// Every physics step of the current level leaves a snapshot of what moves in
+// the buffer: the players, enemies, thrown seeds and moving or crumbling
+// platforms, with their positions, velocities and footing. Backslash stops the
+// simulation and opens a timeline at the bottom of the screen; comma steps back
+// a tick and period forward (with Shift, ten at a time), putting everything
+// back where that tick left it. Stepping forward from the newest tick runs one
+// more physics step, with whatever keys are held. Backslash again resumes from
+// the tick shown, dropping the ticks after it. Turn on the F3 overlay to see
+// the colliders while stepping.
+//
+// Only what the snapshots hold is put back: a fruit collected, a life lost or
+// anything spawned or despawned in the ticks stepped over stays as it is.
+
+use std::collections::VecDeque;
+
+use bevy::app::FixedMain;
+use bevy::prelude::*;
+use crate::prelude::*;
+use rustbevy_core::config::WINDOW_HEIGHT;
+use rustbevy_core::crumbling::CrumblingPlatform;
+use rustbevy_core::enemy::EnemyAi;
+use rustbevy_core::moving::PlatformMotion;
+
+use crate::chat::chat_closed;
+use crate::physics::Interpolated;
+use crate::platform::CurrentLevel;
+
+pub struct ScrubberPlugin;
+
+impl Plugin for ScrubberPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Scrubber>()
+            .add_systems(FixedLast, record_tick.run_if(in_state(AppState::Playing).and_then(resource_equals(GameMode::Classic))))
+            .add_systems(Update, (
+                toggle_scrubber.run_if(chat_closed),
+                scrub_timeline.run_if(scrubbing),
+                update_timeline,
+            ).chain());
+    }
+}
+
+// Ten seconds of physics steps
+const BUFFER_TICKS: usize = 600;
+const FAST_STEP: usize = 10;
+const TIMELINE_WIDTH: f32 = 600.0;
+const TIMELINE_Y: f32 = -WINDOW_HEIGHT / 2.0 + 30.0;
+const TIMELINE_Z: f32 = 20.0;
+const TEXT_COLOR: Color = Color::srgb(0.6, 1.0, 0.6); // Pale green
+
+// One thing's state after a physics step
+#[derive(Clone)]
+struct BodySnapshot {
+    entity: Entity,
+    translation: Vec3,
+    velocity: Option<Vec2>,
+    grounded: Option<bool>,
+    standing_on: Option<Entity>,
+    motion: Option<PlatformMotion>,
+    enemy: Option<EnemyAi>,
+    crumbling: Option<CrumblingPlatform>,
+}
+
+struct TickSnapshot {
+    // Counted from the start of the session
+    tick: u64,
+    level: u32,
+    bodies: Vec<BodySnapshot>,
+}
+
+#[derive(Resource, Default)]
+struct Scrubber {
+    open: bool,
+    // The snapshot shown while open, by its place in `ticks`
+    cursor: usize,
+    ticks: VecDeque<TickSnapshot>,
+    next_tick: u64,
+}
+
+fn scrubbing(scrubber: Res<Scrubber>) -> bool {
+    scrubber.open
+}
+
+#[derive(Component)]
+struct TimelineUI;
+
+#[derive(Component)]
+struct TimelineCursor;
+
+#[derive(Component)]
+struct TimelineText;
+
+type SnapshotQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static Transform,
+        Option<&'static Velocity>,
+        Option<&'static Grounded>,
+        Option<&'static StandingOn>,
+        Option<&'static MovingPlatform>,
+        Option<&'static Enemy>,
+        Option<&'static Crumbling>,
+    ),
+    Or<(With<Player>, With<Enemy>, With<ThrownSeed>, With<MovingPlatform>, With<Crumbling>)>,
+>;
+
+fn record_tick(mut scrubber: ResMut<Scrubber>, current_level: Res<CurrentLevel>, query: SnapshotQuery) {
+    // A new level starts a fresh buffer
+    if scrubber.ticks.front().is_some_and(|snapshot| snapshot.level != current_level.level) {
+        scrubber.ticks.clear();
+    }
+    if scrubber.ticks.len() == BUFFER_TICKS {
+        scrubber.ticks.pop_front();
+    }
+    let bodies = query
+        .iter()
+        .map(|(entity, transform, velocity, grounded, standing_on, moving, enemy, crumbling)| BodySnapshot {
+            entity,
+            translation: transform.translation,
+            velocity: velocity.map(|velocity| Vec2::new(velocity.x, velocity.y)),
+            grounded: grounded.map(|grounded| grounded.0),
+            standing_on: standing_on.map(|standing_on| standing_on.0),
+            motion: moving.map(|moving| moving.0.clone()),
+            enemy: enemy.map(|enemy| enemy.0),
+            crumbling: crumbling.map(|crumbling| crumbling.0),
+        })
+        .collect();
+    let tick = scrubber.next_tick;
+    scrubber.next_tick += 1;
+    scrubber.ticks.push_back(TickSnapshot { tick, level: current_level.level, bodies });
+    scrubber.cursor = scrubber.ticks.len() - 1;
+}
+
+fn toggle_scrubber(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    app_state: Res<State<AppState>>,
+    game_mode: Res<GameMode>,
+    mut scrubber: ResMut<Scrubber>,
+    mut time: ResMut<Time<Virtual>>,
+) {
+    let classic_run = *app_state.get() == AppState::Playing && *game_mode == GameMode::Classic;
+    let toggled = keyboard_input.just_pressed(KeyCode::Backslash);
+    if !scrubber.open {
+        if toggled && classic_run && !scrubber.ticks.is_empty() {
+            scrubber.open = true;
+            scrubber.cursor = scrubber.ticks.len() - 1;
+            time.pause();
+        }
+        return;
+    }
+    // Leaving the run (the pause menu, a game over) resumes it too
+    if toggled || !classic_run {
+        scrubber.open = false;
+        let kept = scrubber.cursor + 1;
+        scrubber.ticks.truncate(kept);
+        time.unpause();
+    }
+}
+
+// Puts everything in the snapshot at `index` in the buffer that's still
+// around back as it was
+fn restore(world: &mut World, index: usize) {
+    let bodies = world.resource::<Scrubber>().ticks[index].bodies.clone();
+    for body in bodies {
+        let Some(mut entity) = world.get_entity_mut(body.entity) else {
+            continue;
+        };
+        // Put there, not slid there between physics steps
+        entity.remove::<Interpolated>();
+        if let Some(mut transform) = entity.get_mut::<Transform>() {
+            transform.translation = body.translation;
+        }
+        if let (Some(mut velocity), Some(saved)) = (entity.get_mut::<Velocity>(), body.velocity) {
+            *velocity = Velocity { x: saved.x, y: saved.y };
+        }
+        if let (Some(mut grounded), Some(saved)) = (entity.get_mut::<Grounded>(), body.grounded) {
+            grounded.0 = saved;
+        }
+        match body.standing_on {
+            Some(platform) => {
+                entity.insert(StandingOn(platform));
+            }
+            None => {
+                entity.remove::<StandingOn>();
+            }
+        }
+        if let (Some(mut moving), Some(saved)) = (entity.get_mut::<MovingPlatform>(), body.motion) {
+            moving.0 = saved;
+        }
+        if let (Some(mut enemy), Some(saved)) = (entity.get_mut::<Enemy>(), body.enemy) {
+            enemy.0 = saved;
+        }
+        if let (Some(mut crumbling), Some(saved)) = (entity.get_mut::<Crumbling>(), body.crumbling) {
+            crumbling.0 = saved;
+        }
+    }
+}
+
+// Runs one physics step by hand while virtual time is stopped, the way the
+// fixed loop would
+fn step_simulation(world: &mut World) {
+    let timestep = world.resource::<Time<Fixed>>().timestep();
+    world.resource_mut::<Time<Fixed>>().advance_by(timestep);
+    let fixed = world.resource::<Time<Fixed>>().as_generic();
+    *world.resource_mut::<Time>() = fixed;
+    world.run_schedule(FixedMain);
+    let virtual_time = world.resource::<Time<Virtual>>().as_generic();
+    *world.resource_mut::<Time>() = virtual_time;
+}
+
+fn scrub_timeline(world: &mut World) {
+    let keyboard_input = world.resource::<ButtonInput<KeyCode>>();
+    let steps = if keyboard_input.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) { FAST_STEP } else { 1 };
+    let back = keyboard_input.just_pressed(KeyCode::Comma);
+    let forward = keyboard_input.just_pressed(KeyCode::Period);
+    if back {
+        let cursor = world.resource::<Scrubber>().cursor.saturating_sub(steps);
+        world.resource_mut::<Scrubber>().cursor = cursor;
+        restore(world, cursor);
+    } else if forward {
+        for _ in 0..steps {
+            let scrubber = world.resource::<Scrubber>();
+            if scrubber.cursor + 1 < scrubber.ticks.len() {
+                let cursor = scrubber.cursor + 1;
+                world.resource_mut::<Scrubber>().cursor = cursor;
+                restore(world, cursor);
+            } else {
+                // Recorded, and the cursor moved onto it, by `record_tick`
+                step_simulation(world);
+            }
+        }
+    }
+}
+
+fn spawn_timeline(commands: &mut Commands) {
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite { color: Color::srgba(0.0, 0.0, 0.0, 0.6), custom_size: Some(Vec2::new(TIMELINE_WIDTH, 8.0)), ..default() },
+            transform: Transform::from_xyz(0.0, TIMELINE_Y, TIMELINE_Z),
+            ..default()
+        },
+        TimelineUI,
+        ScreenSpace,
+    ));
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite { color: TEXT_COLOR, custom_size: Some(Vec2::new(4.0, 18.0)), ..default() },
+            transform: Transform::from_xyz(0.0, TIMELINE_Y, TIMELINE_Z + 1.0),
+            ..default()
+        },
+        TimelineCursor,
+        TimelineUI,
+        ScreenSpace,
+    ));
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section("", TextStyle { font_size: 18.0, color: TEXT_COLOR, ..default() }),
+            transform: Transform::from_xyz(0.0, TIMELINE_Y + 25.0, TIMELINE_Z),
+            ..default()
+        },
+        TimelineText,
+        TimelineUI,
+    ));
+}
+
+fn update_timeline(
+    mut commands: Commands,
+    scrubber: Res<Scrubber>,
+    ui_query: Query<Entity, With<TimelineUI>>,
+    mut cursor_query: Query<&mut Transform, With<TimelineCursor>>,
+    mut text_query: Query<&mut Text, With<TimelineText>>,
+) {
+    if !scrubber.open {
+        for entity in ui_query.iter() {
+            commands.entity(entity).despawn();
+        }
+        return;
+    }
+    if ui_query.is_empty() {
+        spawn_timeline(&mut commands);
+        return;
+    }
+    let Some(snapshot) = scrubber.ticks.get(scrubber.cursor) else {
+        return;
+    };
+    let newest = scrubber.ticks.len() - 1;
+    let along = if newest == 0 { 1.0 } else { scrubber.cursor as f32 / newest as f32 };
+    for mut transform in cursor_query.iter_mut() {
+        transform.translation.x = (along - 0.5) * TIMELINE_WIDTH;
+    }
+    if let Ok(mut text) = text_query.get_single_mut() {
+        text.sections[0].value = format!(
+            "Tick {} ({} behind, {} recorded)   ,/. step   Shift: x{}   \\: resume here",
+            snapshot.tick,
+            newest - scrubber.cursor,
+            scrubber.ticks.len(),
+            FAST_STEP
+        );
+    }
+}
@@ -0,0 +1,95 @@
+// Session statistics and the recap shown when quitting.
+//
+// `SessionStats` adds up everything played since the game was launched,
+// across runs and modes: time spent playing, Classic levels cleared (skipped
+// ones don't count), the longest fruit combo and the fruit collected. Quitting
+// the game from the pause menu or the game over screen (Q) goes to the
+// SessionRecap screen with them first; any key then closes the game.
+
+use bevy::prelude::*;
+use crate::prelude::*;
+use rustbevy_core::category::format_time;
+
+use crate::ui::despawn_screen;
+
+pub struct SessionPlugin;
+
+impl Plugin for SessionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SessionStats>()
+            .add_systems(OnEnter(AppState::SessionRecap), setup_session_recap)
+            .add_systems(OnExit(AppState::SessionRecap), despawn_screen::<SessionRecapUI>)
+            .add_systems(Update, (
+                count_play_time.run_if(in_state(AppState::Playing)),
+                record_session_stats,
+                close_after_recap.run_if(in_state(AppState::SessionRecap)),
+            ));
+    }
+}
+
+const LABEL_COLOR: Color = Color::srgb(0.8, 0.8, 0.8);
+const VALUE_COLOR: Color = Color::srgb(1.0, 1.0, 0.0); // Yellow
+
+// What this launch of the game has played
+#[derive(Resource, Default)]
+pub struct SessionStats {
+    pub seconds_played: f32,
+    pub levels_cleared: u32,
+    pub best_combo: u32,
+    pub fruit_collected: u32,
+}
+
+#[derive(Component)]
+struct SessionRecapUI;
+
+// Wall-clock time, so a slowed-down game speed doesn't shorten it
+fn count_play_time(time: Res<Time<Real>>, mut stats: ResMut<SessionStats>) {
+    stats.seconds_played += time.delta_seconds();
+}
+
+fn record_session_stats(
+    mut level_events: EventReader<LevelCompletedEvent>,
+    mut fruit_events: EventReader<FruitCollectedEvent>,
+    game_state: Res<GameState>,
+    mut stats: ResMut<SessionStats>,
+) {
+    stats.fruit_collected += fruit_events.read().count() as u32;
+    stats.levels_cleared += level_events.read().filter(|event| !event.skipped).count() as u32;
+    if game_state.combo.streak > stats.best_combo {
+        stats.best_combo = game_state.combo.streak;
+    }
+}
+
+fn recap_text(value: impl Into<String>, font_size: f32, color: Color, x: f32, y: f32) -> (Text2dBundle, SessionRecapUI) {
+    (
+        Text2dBundle {
+            text: Text::from_section(value, TextStyle { font_size, color, ..default() }),
+            transform: Transform::from_translation(Vec3::new(x, y, 10.0)),
+            ..default()
+        },
+        SessionRecapUI,
+    )
+}
+
+fn setup_session_recap(mut commands: Commands, stats: Res<SessionStats>) {
+    commands.spawn(recap_text("THANKS FOR PLAYING", 60.0, Color::srgb(0.7, 0.7, 1.0), 0.0, 200.0)); // Light blue
+    commands.spawn(recap_text("This session", 30.0, Color::WHITE, 0.0, 130.0));
+    let lines = [
+        ("Time played", format_time(stats.seconds_played)),
+        ("Levels cleared", stats.levels_cleared.to_string()),
+        ("Best combo", format!("{} in a row", stats.best_combo)),
+        ("Fruit collected", stats.fruit_collected.to_string()),
+    ];
+    for (index, (label, value)) in lines.into_iter().enumerate() {
+        let y = 70.0 - index as f32 * 45.0;
+        commands.spawn(recap_text(format!("{}:", label), 28.0, LABEL_COLOR, -120.0, y));
+        commands.spawn(recap_text(value, 28.0, VALUE_COLOR, 120.0, y));
+    }
+    commands.spawn(recap_text("Press any key to quit", 24.0, Color::srgb(0.0, 1.0, 0.0), 0.0, -180.0)); // Green
+}
+
+fn close_after_recap(keyboard_input: Res<ButtonInput<KeyCode>>, mut exit_events: EventWriter<AppExit>) {
+    if keyboard_input.get_just_pressed().next().is_some() {
+        exit_events.send(AppExit::Success);
+    }
+}
@@ -0,0 +1,436 @@
+// Player settings kept in the save directory between sessions: volumes, the
+// graphics preset and speed lines, sound indicators, the window (size,
+// fullscreen, vsync) and the UI scale.
+//
+// E on the main menu opens the Settings screen: UP/DOWN picks a setting and
+// LEFT/RIGHT (or ENTER) changes it, ESC returns to the menu. Every change is
+// saved and applied straight away, to the window, the `UiScale` resource and
+// the sounds playing.
+
+use bevy::prelude::*;
+use bevy::window::{PresentMode, PrimaryWindow, WindowMode};
+use crate::prelude::*;
+
+use crate::game::{read_save_file, write_save_file};
+use crate::menu::MainMenuUI;
+use crate::ui::despawn_screen;
+
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Settings>()
+            .init_resource::<GraphicsQuality>()
+            .init_resource::<SettingsScreen>()
+            .add_systems(OnEnter(AppState::MainMenu), setup_settings_menu_line)
+            .add_systems(OnEnter(AppState::Settings), setup_settings_screen)
+            .add_systems(OnExit(AppState::Settings), despawn_screen::<SettingsUI>)
+            .add_systems(Update, (
+                open_settings.run_if(in_state(AppState::MainMenu)),
+                (handle_settings_input, update_settings_lines).chain().run_if(in_state(AppState::Settings)),
+                (save_settings, apply_graphics_setting, apply_window_settings, apply_ui_scale),
+            ));
+    }
+}
+
+const SETTINGS_FILE: &str = "settings.txt";
+// Volumes move in these steps and stay between 0 and 1
+const VOLUME_STEP: f32 = 0.1;
+// Window sizes to pick from; the first is the size the game is laid out for
+const RESOLUTIONS: [(u32, u32); 5] = [(1200, 800), (1280, 720), (1600, 900), (1920, 1080), (960, 640)];
+// UI scale steps and limits
+const UI_SCALE_STEP: f32 = 0.1;
+const UI_SCALE_RANGE: (f32, f32) = (0.5, 2.0);
+const SELECTED_COLOR: Color = Color::srgb(1.0, 1.0, 0.0); // Yellow
+const OPTION_COLOR: Color = Color::srgb(0.8, 0.8, 0.8);
+
+// Windowed, a borderless window covering the screen, or exclusive fullscreen
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DisplayMode {
+    #[default]
+    Windowed,
+    Borderless,
+    Fullscreen,
+}
+
+impl DisplayMode {
+    const ALL: [Self; 3] = [Self::Windowed, Self::Borderless, Self::Fullscreen];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Windowed => "Windowed",
+            Self::Borderless => "Borderless",
+            Self::Fullscreen => "Fullscreen",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|mode| mode.name().eq_ignore_ascii_case(name))
+    }
+
+    fn window_mode(self) -> WindowMode {
+        match self {
+            Self::Windowed => WindowMode::Windowed,
+            Self::Borderless => WindowMode::BorderlessFullscreen,
+            Self::Fullscreen => WindowMode::Fullscreen,
+        }
+    }
+}
+
+#[derive(Resource)]
+pub struct Settings {
+    // Scales the music and the effects
+    pub master_volume: f32,
+    pub music_volume: f32,
+    pub effects_volume: f32,
+    // Copied into the `GraphicsQuality` resource the drawing systems check
+    pub graphics: GraphicsQuality,
+    // Keeps the sky still instead of moving through the day
+    pub reduced_motion: bool,
+    // Streaks and afterimages when a player goes fast (see speed_lines.rs)
+    pub speed_lines: bool,
+    // Shows the sounds worth hearing on screen (see sound_indicators.rs)
+    pub sound_indicators: bool,
+    // Window size in logical pixels, while windowed
+    pub resolution: (u32, u32),
+    pub display: DisplayMode,
+    pub vsync: bool,
+    // Scales the HUD and the other `bevy_ui` screens
+    pub ui_scale: f32,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        let saved = read_save_file(SETTINGS_FILE).unwrap_or_default();
+        let mut settings = Self {
+            master_volume: 1.0,
+            music_volume: 0.5,
+            effects_volume: 1.0,
+            graphics: GraphicsQuality::default(),
+            reduced_motion: false,
+            speed_lines: true,
+            sound_indicators: false,
+            resolution: RESOLUTIONS[0],
+            display: DisplayMode::default(),
+            vsync: true,
+            ui_scale: 1.0,
+        };
+        // One `<setting> <value>` line each
+        for line in saved.lines() {
+            let Some((key, value)) = line.split_once(' ') else {
+                continue;
+            };
+            let value = value.trim();
+            match key {
+                "graphics" => settings.graphics = GraphicsQuality::from_name(value).unwrap_or_default(),
+                "reduced_motion" => settings.reduced_motion = value == "on",
+                "speed_lines" => settings.speed_lines = value != "off",
+                "sound_indicators" => settings.sound_indicators = value == "on",
+                "display" => settings.display = DisplayMode::from_name(value).unwrap_or_default(),
+                "vsync" => settings.vsync = value != "off",
+                "resolution" => {
+                    let size = value.split_once('x').and_then(|(width, height)| Some((width.parse().ok()?, height.parse().ok()?)));
+                    if let Some(size) = size.filter(|&(width, height)| width > 0 && height > 0) {
+                        settings.resolution = size;
+                    }
+                }
+                _ => {
+                    let Ok(value) = value.parse::<f32>() else {
+                        continue;
+                    };
+                    match key {
+                        "master_volume" => settings.master_volume = value.clamp(0.0, 1.0),
+                        "music_volume" => settings.music_volume = value.clamp(0.0, 1.0),
+                        "effects_volume" => settings.effects_volume = value.clamp(0.0, 1.0),
+                        "ui_scale" => settings.ui_scale = value.clamp(UI_SCALE_RANGE.0, UI_SCALE_RANGE.1),
+                        _ => {}
+                    }
+                }
+            }
+        }
+        settings
+    }
+}
+
+impl Settings {
+    // Moves a volume one step up (`steps` > 0) or down
+    pub fn step_volume(volume: &mut f32, steps: i32) {
+        *volume = ((*volume / VOLUME_STEP).round() + steps as f32).clamp(0.0, 1.0 / VOLUME_STEP) * VOLUME_STEP;
+    }
+
+    // The volumes sounds play at, with the master volume taken into account
+    pub fn music_level(&self) -> f32 {
+        self.master_volume * self.music_volume
+    }
+
+    pub fn effects_level(&self) -> f32 {
+        self.master_volume * self.effects_volume
+    }
+
+    fn save(&self) -> Result<(), RuntimeErrorEvent> {
+        let on_off = |on: bool| if on { "on" } else { "off" };
+        let contents = format!(
+            "master_volume {}\nmusic_volume {}\neffects_volume {}\ngraphics {}\nreduced_motion {}\nspeed_lines {}\nsound_indicators {}\nresolution {}x{}\ndisplay {}\nvsync {}\nui_scale {}",
+            self.master_volume,
+            self.music_volume,
+            self.effects_volume,
+            self.graphics.name(),
+            on_off(self.reduced_motion),
+            on_off(self.speed_lines),
+            on_off(self.sound_indicators),
+            self.resolution.0,
+            self.resolution.1,
+            self.display.name(),
+            on_off(self.vsync),
+            self.ui_scale,
+        );
+        write_save_file(SETTINGS_FILE, &contents, "settings")
+    }
+}
+
+// The rows of the Settings screen
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum SettingsRow {
+    Resolution,
+    Display,
+    VSync,
+    MasterVolume,
+    MusicVolume,
+    EffectsVolume,
+    UiScale,
+    Graphics,
+    ReducedMotion,
+    SpeedLines,
+    SoundIndicators,
+}
+
+impl SettingsRow {
+    const ALL: [Self; 11] = [
+        Self::Resolution,
+        Self::Display,
+        Self::VSync,
+        Self::MasterVolume,
+        Self::MusicVolume,
+        Self::EffectsVolume,
+        Self::UiScale,
+        Self::Graphics,
+        Self::ReducedMotion,
+        Self::SpeedLines,
+        Self::SoundIndicators,
+    ];
+
+    fn label(self, settings: &Settings) -> String {
+        let on_off = |on: bool| if on { "ON" } else { "OFF" };
+        match self {
+            Self::Resolution => format!("Resolution: {} x {}", settings.resolution.0, settings.resolution.1),
+            Self::Display => format!("Display: {}", settings.display.name()),
+            Self::VSync => format!("VSync: {}", on_off(settings.vsync)),
+            Self::MasterVolume => format!("Master volume: {:.0}%", settings.master_volume * 100.0),
+            Self::MusicVolume => format!("Music volume: {:.0}%", settings.music_volume * 100.0),
+            Self::EffectsVolume => format!("Effects volume: {:.0}%", settings.effects_volume * 100.0),
+            Self::UiScale => format!("UI scale: {:.0}%", settings.ui_scale * 100.0),
+            Self::Graphics => format!("Graphics: {}", settings.graphics.name()),
+            Self::ReducedMotion => format!("Reduced motion: {}", on_off(settings.reduced_motion)),
+            Self::SpeedLines => format!("Speed lines: {}", on_off(settings.speed_lines)),
+            Self::SoundIndicators => format!("Sound indicators: {}", on_off(settings.sound_indicators)),
+        }
+    }
+
+    // Moves the setting one step forward (`steps` > 0) or back; the lists wrap around
+    fn change(self, settings: &mut Settings, steps: i32) {
+        let cycle = |index: usize, len: usize| (index as i32 + steps).rem_euclid(len as i32) as usize;
+        match self {
+            Self::Resolution => {
+                // A size from the settings file that isn't on the list steps to the first one
+                let index = RESOLUTIONS.iter().position(|&size| size == settings.resolution);
+                settings.resolution = RESOLUTIONS[index.map_or(0, |index| cycle(index, RESOLUTIONS.len()))];
+            }
+            Self::Display => {
+                let index = DisplayMode::ALL.iter().position(|&mode| mode == settings.display).unwrap_or(0);
+                settings.display = DisplayMode::ALL[cycle(index, DisplayMode::ALL.len())];
+            }
+            Self::VSync => settings.vsync = !settings.vsync,
+            Self::MasterVolume => Settings::step_volume(&mut settings.master_volume, steps),
+            Self::MusicVolume => Settings::step_volume(&mut settings.music_volume, steps),
+            Self::EffectsVolume => Settings::step_volume(&mut settings.effects_volume, steps),
+            Self::UiScale => {
+                let scale = ((settings.ui_scale / UI_SCALE_STEP).round() + steps as f32) * UI_SCALE_STEP;
+                settings.ui_scale = scale.clamp(UI_SCALE_RANGE.0, UI_SCALE_RANGE.1);
+            }
+            Self::Graphics => {
+                let index = GraphicsQuality::ALL.iter().position(|&quality| quality == settings.graphics).unwrap_or(0);
+                settings.graphics = GraphicsQuality::ALL[cycle(index, GraphicsQuality::ALL.len())];
+            }
+            Self::ReducedMotion => settings.reduced_motion = !settings.reduced_motion,
+            Self::SpeedLines => settings.speed_lines = !settings.speed_lines,
+            Self::SoundIndicators => settings.sound_indicators = !settings.sound_indicators,
+        }
+    }
+}
+
+// The row picked on the Settings screen
+#[derive(Resource, Default)]
+struct SettingsScreen {
+    selected: usize,
+}
+
+#[derive(Component)]
+struct SettingsUI;
+
+#[derive(Component)]
+struct SettingsLine(usize);
+
+fn setup_settings_menu_line(mut commands: Commands) {
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                "E: Settings (display, volume, UI scale)",
+                TextStyle {
+                    font_size: 22.0,
+                    color: Color::srgb(0.8, 0.8, 0.8), // Light gray
+                    ..default()
+                },
+            ),
+            transform: Transform::from_translation(Vec3::new(0.0, 240.0, 10.0)),
+            ..default()
+        },
+        MainMenuUI,
+    ));
+}
+
+fn open_settings(keyboard_input: Res<ButtonInput<KeyCode>>, mut next_state: ResMut<NextState<AppState>>) {
+    if keyboard_input.just_pressed(KeyCode::KeyE) {
+        next_state.set(AppState::Settings);
+    }
+}
+
+fn settings_line(index: usize, settings: &Settings, screen: &SettingsScreen) -> (String, Color) {
+    let label = SettingsRow::ALL[index].label(settings);
+    if index == screen.selected {
+        (format!("> {} <", label), SELECTED_COLOR)
+    } else {
+        (label, OPTION_COLOR)
+    }
+}
+
+fn setup_settings_screen(mut commands: Commands, settings: Res<Settings>, mut screen: ResMut<SettingsScreen>) {
+    *screen = SettingsScreen::default();
+
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                "SETTINGS",
+                TextStyle {
+                    font_size: 60.0,
+                    color: Color::srgb(0.7, 0.7, 1.0), // Light blue
+                    ..default()
+                },
+            ),
+            transform: Transform::from_translation(Vec3::new(0.0, 260.0, 10.0)),
+            ..default()
+        },
+        SettingsUI,
+    ));
+
+    for index in 0..SettingsRow::ALL.len() {
+        let (line, color) = settings_line(index, &settings, &screen);
+        commands.spawn((
+            Text2dBundle {
+                text: Text::from_section(line, TextStyle { font_size: 30.0, color, ..default() }),
+                transform: Transform::from_translation(Vec3::new(0.0, 190.0 - index as f32 * 40.0, 10.0)),
+                ..default()
+            },
+            SettingsLine(index),
+            SettingsUI,
+        ));
+    }
+
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                "UP/DOWN: choose   LEFT/RIGHT or ENTER: change   ESC: back",
+                TextStyle {
+                    font_size: 24.0,
+                    color: Color::srgb(0.6, 0.6, 0.6), // Gray
+                    ..default()
+                },
+            ),
+            transform: Transform::from_translation(Vec3::new(0.0, -260.0, 10.0)),
+            ..default()
+        },
+        SettingsUI,
+    ));
+}
+
+fn handle_settings_input(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut screen: ResMut<SettingsScreen>,
+    mut settings: ResMut<Settings>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    let count = SettingsRow::ALL.len();
+    if keyboard_input.just_pressed(KeyCode::Escape) {
+        next_state.set(AppState::MainMenu);
+    } else if keyboard_input.any_just_pressed([KeyCode::ArrowUp, KeyCode::KeyW]) {
+        screen.selected = (screen.selected + count - 1) % count;
+    } else if keyboard_input.any_just_pressed([KeyCode::ArrowDown, KeyCode::KeyS]) {
+        screen.selected = (screen.selected + 1) % count;
+    } else if keyboard_input.any_just_pressed([KeyCode::ArrowLeft, KeyCode::KeyA]) {
+        SettingsRow::ALL[screen.selected].change(&mut settings, -1);
+    } else if keyboard_input.any_just_pressed([KeyCode::ArrowRight, KeyCode::KeyD, KeyCode::Enter]) {
+        SettingsRow::ALL[screen.selected].change(&mut settings, 1);
+    }
+}
+
+fn update_settings_lines(settings: Res<Settings>, screen: Res<SettingsScreen>, mut line_query: Query<(&SettingsLine, &mut Text)>) {
+    if !settings.is_changed() && !screen.is_changed() {
+        return;
+    }
+    for (line, mut text) in line_query.iter_mut() {
+        let (value, color) = settings_line(line.0, &settings, &screen);
+        text.sections[0].value = value;
+        text.sections[0].style.color = color;
+    }
+}
+
+fn save_settings(settings: Res<Settings>, mut errors: EventWriter<RuntimeErrorEvent>) {
+    if settings.is_changed() && !settings.is_added() {
+        if let Err(error) = settings.save() {
+            errors.send(error);
+        }
+    }
+}
+
+fn apply_graphics_setting(settings: Res<Settings>, mut graphics: ResMut<GraphicsQuality>) {
+    if settings.is_changed() && *graphics != settings.graphics {
+        *graphics = settings.graphics;
+    }
+}
+
+// Only what differs is set, so the window isn't touched for a volume change
+fn apply_window_settings(settings: Res<Settings>, mut window_query: Query<&mut Window, With<PrimaryWindow>>) {
+    if !settings.is_changed() {
+        return;
+    }
+    let Ok(mut window) = window_query.get_single_mut() else {
+        return;
+    };
+    let (width, height) = (settings.resolution.0 as f32, settings.resolution.1 as f32);
+    if window.resolution.width() != width || window.resolution.height() != height {
+        window.resolution.set(width, height);
+    }
+    let mode = settings.display.window_mode();
+    if window.mode != mode {
+        window.mode = mode;
+    }
+    let present_mode = if settings.vsync { PresentMode::AutoVsync } else { PresentMode::AutoNoVsync };
+    if window.present_mode != present_mode {
+        window.present_mode = present_mode;
+    }
+}
+
+fn apply_ui_scale(settings: Res<Settings>, mut ui_scale: ResMut<UiScale>) {
+    if settings.is_changed() && ui_scale.0 != settings.ui_scale {
+        ui_scale.0 = settings.ui_scale;
+    }
+}
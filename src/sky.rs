@@ -0,0 +1,170 @@
+// Sky: the gradient behind everything the world camera draws.
+//
+// A window-sized quad with its colors on the vertices follows the world
+// camera. Each Classic level's sky goes with its theme (grass, cave, ice or
+// night, see `theme`); party games and the menus use the grass one. Over a run the
+// sky slowly goes from dawn through day to dusk, following the run timer.
+// With reduced motion on (B on the main menu) it stays at day.
+
+use bevy::color::Mix;
+use bevy::prelude::*;
+use bevy::render::mesh::Mesh;
+use bevy::sprite::{MaterialMesh2dBundle, Mesh2dHandle};
+use bevy::transform::TransformSystem;
+use crate::prelude::*;
+use rustbevy_core::config::{WINDOW_HEIGHT, WINDOW_WIDTH};
+use rustbevy_core::theme::LevelTheme;
+
+use crate::platform::CurrentLevel;
+use crate::settings::Settings;
+
+pub struct SkyPlugin;
+
+impl Plugin for SkyPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_sky)
+            .add_systems(Update, update_sky_colors)
+            .add_systems(PostUpdate, follow_camera.before(TransformSystem::TransformPropagate));
+    }
+}
+
+// Behind the level and the menu's parallax layers
+const SKY_Z: f32 = -50.0;
+// Run time from dawn to dusk; the sky stays at dusk after that
+const DAY_SECONDS: f32 = 480.0;
+// Smaller color changes wait, so the mesh isn't rewritten every frame
+const MIN_COLOR_CHANGE: f32 = 0.002;
+
+// Top and bottom colors of the sky at one time of day
+#[derive(Clone, Copy)]
+struct Gradient {
+    top: Color,
+    bottom: Color,
+}
+
+struct SkyTheme {
+    dawn: Gradient,
+    day: Gradient,
+    dusk: Gradient,
+}
+
+const fn gradient(top: (f32, f32, f32), bottom: (f32, f32, f32)) -> Gradient {
+    Gradient { top: Color::srgb(top.0, top.1, top.2), bottom: Color::srgb(bottom.0, bottom.1, bottom.2) }
+}
+
+const GRASS_SKY: SkyTheme = SkyTheme {
+    dawn: gradient((0.35, 0.4, 0.62), (0.85, 0.62, 0.55)),
+    day: gradient((0.3, 0.55, 0.85), (0.7, 0.85, 0.95)),
+    dusk: gradient((0.2, 0.18, 0.4), (0.85, 0.45, 0.35)),
+};
+// Rock overhead, lighter toward the way out
+const CAVE_SKY: SkyTheme = SkyTheme {
+    dawn: gradient((0.12, 0.1, 0.1), (0.3, 0.22, 0.18)),
+    day: gradient((0.16, 0.13, 0.12), (0.38, 0.3, 0.24)),
+    dusk: gradient((0.08, 0.06, 0.06), (0.25, 0.16, 0.12)),
+};
+const ICE_SKY: SkyTheme = SkyTheme {
+    dawn: gradient((0.3, 0.38, 0.6), (0.75, 0.7, 0.8)),
+    day: gradient((0.5, 0.7, 0.9), (0.88, 0.94, 0.98)),
+    dusk: gradient((0.15, 0.18, 0.38), (0.6, 0.55, 0.75)),
+};
+// Night only goes from late evening to the small hours
+const NIGHT_SKY: SkyTheme = SkyTheme {
+    dawn: gradient((0.05, 0.06, 0.18), (0.2, 0.18, 0.35)),
+    day: gradient((0.04, 0.05, 0.16), (0.12, 0.14, 0.3)),
+    dusk: gradient((0.02, 0.02, 0.1), (0.1, 0.08, 0.22)),
+};
+
+#[derive(Component)]
+struct Sky {
+    // The colors the mesh has now, top then bottom
+    colors: [LinearRgba; 2],
+}
+
+fn sky_for(theme: LevelTheme) -> &'static SkyTheme {
+    match theme {
+        LevelTheme::Grass => &GRASS_SKY,
+        LevelTheme::Cave => &CAVE_SKY,
+        LevelTheme::Ice => &ICE_SKY,
+        LevelTheme::Night => &NIGHT_SKY,
+    }
+}
+
+// Dawn at the start of a run, day halfway through `DAY_SECONDS`, dusk at the end
+fn time_of_day(theme: &SkyTheme, seconds: f32) -> [LinearRgba; 2] {
+    let progress = (seconds / DAY_SECONDS).clamp(0.0, 1.0) * 2.0;
+    let (from, to, blend) = if progress < 1.0 { (theme.dawn, theme.day, progress) } else { (theme.day, theme.dusk, progress - 1.0) };
+    [
+        LinearRgba::from(from.top).mix(&LinearRgba::from(to.top), blend),
+        LinearRgba::from(from.bottom).mix(&LinearRgba::from(to.bottom), blend),
+    ]
+}
+
+// Vertex colors for the quad: its first two corners are the top ones
+fn vertex_colors([top, bottom]: [LinearRgba; 2]) -> Vec<[f32; 4]> {
+    vec![top.to_f32_array(), top.to_f32_array(), bottom.to_f32_array(), bottom.to_f32_array()]
+}
+
+fn setup_sky(mut commands: Commands, mut meshes: ResMut<Assets<Mesh>>, mut materials: ResMut<Assets<ColorMaterial>>) {
+    let theme = &GRASS_SKY;
+    let colors = [LinearRgba::from(theme.day.top), LinearRgba::from(theme.day.bottom)];
+    let mut mesh = Mesh::from(Rectangle::new(1.0, 1.0));
+    mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, vertex_colors(colors));
+    commands.spawn((
+        MaterialMesh2dBundle {
+            mesh: meshes.add(mesh).into(),
+            // White, so the vertex colors show as they are
+            material: materials.add(ColorMaterial::from(Color::WHITE)),
+            transform: Transform::from_xyz(0.0, 0.0, SKY_Z).with_scale(Vec3::new(WINDOW_WIDTH, WINDOW_HEIGHT, 1.0)),
+            ..default()
+        },
+        Sky { colors },
+    ));
+}
+
+fn update_sky_colors(
+    app_state: Res<State<AppState>>,
+    game_mode: Res<GameMode>,
+    game_state: Res<GameState>,
+    current_level: Res<CurrentLevel>,
+    settings: Res<Settings>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut sky_query: Query<(&mut Sky, &Mesh2dHandle)>,
+) {
+    let Ok((mut sky, mesh)) = sky_query.get_single_mut() else {
+        return;
+    };
+    let in_run = matches!(
+        app_state.get(),
+        AppState::Playing | AppState::Paused | AppState::ModifierDraft | AppState::DeathRecap | AppState::GameOver
+    );
+    let theme = if in_run && *game_mode == GameMode::Classic { sky_for(current_level.theme) } else { &GRASS_SKY };
+    let colors = if settings.reduced_motion || !in_run {
+        [LinearRgba::from(theme.day.top), LinearRgba::from(theme.day.bottom)]
+    } else {
+        time_of_day(theme, game_state.stats.seconds)
+    };
+
+    let changed = sky.colors.iter().zip(&colors).any(|(old, new)| {
+        old.to_f32_array().iter().zip(new.to_f32_array()).any(|(old, new)| (old - new).abs() > MIN_COLOR_CHANGE)
+    });
+    if !changed {
+        return;
+    }
+    if let Some(mesh) = meshes.get_mut(&mesh.0) {
+        mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, vertex_colors(colors));
+        sky.colors = colors;
+    }
+}
+
+// Keeps the sky filling the world camera's view, zoomed or not
+fn follow_camera(
+    camera_query: Query<(&Transform, &OrthographicProjection), (With<MainCamera>, Without<Sky>)>,
+    mut sky_query: Query<&mut Transform, With<Sky>>,
+) {
+    let (Ok((camera, projection)), Ok(mut sky)) = (camera_query.get_single(), sky_query.get_single_mut()) else {
+        return;
+    };
+    sky.translation = camera.translation.truncate().extend(SKY_Z);
+    sky.scale = Vec3::new(WINDOW_WIDTH * projection.scale, WINDOW_HEIGHT * projection.scale, 1.0);
+}
@@ -0,0 +1,447 @@
+// Sound effects and audio packs.
+//
+// An audio pack is a manifest in `assets/audio_packs/` (`*.audio.ron`) that
+// maps sound names to files and may name a soundtrack. Every pack in the folder
+// is loaded at startup and S on the main menu switches between them. Sounds a
+// pack leaves out fall back to the game's own. With the `hot_reload` feature a
+// pack picks up edits to its manifest while the game runs.
+//
+// Besides the sounds other systems ask for, players landing, footsteps,
+// Classic fruit being collected, Classic deaths and levels being reached play
+// sounds from here. Footsteps and landings sound like the platform underfoot
+// (stone, wood, metal or ice); a pack without a surface's sounds plays its
+// landing thud at that surface's pitch instead. Music and effects volumes come
+// from `Settings` (- / = and [ / ] on the main menu).
+//
+// The soundtrack plays on the music bus of the `AudioMixer`. Sounds that have
+// to be heard over it (deaths, 1-ups, level jingles; see `SoundType::duck`)
+// duck the whole bus with a volume envelope from `rustbevy_core::mixer`, and
+// it stays ducked while anything marked `DucksMusic` (dialogue) is around.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use bevy::prelude::*;
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, AsyncReadExt, LoadContext, LoadedFolder};
+use bevy::audio::{Volume, PlaybackSettings};
+use crate::prelude::*;
+use rustbevy_core::mixer::{BusEnvelope, Duck};
+use serde::Deserialize;
+
+use crate::loading::LoadingAssets;
+use crate::platform::surface_under;
+use crate::settings::Settings;
+
+const AUDIO_PACK_FOLDER: &str = "audio_packs";
+const DEFAULT_AUDIO_PACK: &str = "Classic";
+// Soundtrack loudness at full music volume
+const SOUNDTRACK_VOLUME: f32 = 0.6;
+// Touching down after less time in the air than this (running over a seam,
+// a tiny hop) makes no sound
+const MIN_AIRTIME_FOR_LAND: f32 = 0.15;
+// Time between footsteps while running, and the speed below which a player
+// counts as standing still
+const FOOTSTEP_SECONDS: f32 = 0.28;
+const MIN_FOOTSTEP_SPEED: f32 = 40.0;
+
+pub struct SoundPlugin;
+
+impl Plugin for SoundPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<AudioPack>()
+            .init_asset_loader::<AudioPackLoader>()
+            .init_resource::<AudioPacks>()
+            .init_resource::<AudioMixer>()
+            .add_event::<PlaySoundEvent>()
+            .add_systems(Startup, setup_audio)
+            .add_systems(Update, (
+                (collect_audio_packs, play_soundtrack, update_mixer, apply_music_volume).chain(),
+                (
+                    (play_landing_sounds, play_footsteps).run_if(in_state(AppState::Playing)),
+                    (play_collect_sound, play_death_sound, play_level_up_sound),
+                    play_sounds,
+                ).chain(),
+            ));
+    }
+}
+
+// What an audio pack manifest holds
+#[derive(Deserialize)]
+struct AudioPackManifest {
+    name: String,
+    sounds: HashMap<String, String>,
+    #[serde(default)]
+    soundtrack: Option<String>,
+}
+
+#[derive(Asset, TypePath)]
+pub struct AudioPack {
+    pub name: String,
+    sounds: HashMap<String, Handle<AudioSource>>,
+    soundtrack: Option<Handle<AudioSource>>,
+}
+
+#[derive(Debug)]
+pub enum AudioPackError {
+    Io(std::io::Error),
+    Manifest(ron::error::SpannedError),
+}
+
+impl fmt::Display for AudioPackError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AudioPackError::Io(error) => write!(f, "could not read audio pack: {error}"),
+            AudioPackError::Manifest(error) => write!(f, "bad audio pack manifest: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for AudioPackError {}
+
+#[derive(Default)]
+struct AudioPackLoader;
+
+impl AssetLoader for AudioPackLoader {
+    type Asset = AudioPack;
+    type Settings = ();
+    type Error = AudioPackError;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a (),
+        load_context: &'a mut LoadContext<'_>,
+    ) -> Result<AudioPack, AudioPackError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await.map_err(AudioPackError::Io)?;
+        let manifest: AudioPackManifest = ron::de::from_bytes(&bytes).map_err(AudioPackError::Manifest)?;
+        // Sound files are paths under `assets/`, like everything else
+        Ok(AudioPack {
+            name: manifest.name,
+            sounds: manifest
+                .sounds
+                .into_iter()
+                .map(|(name, path)| (name, load_context.load(path)))
+                .collect(),
+            soundtrack: manifest.soundtrack.map(|path| load_context.load(path)),
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["audio.ron"]
+    }
+}
+
+// The packs found in the pack folder and which one is picked
+#[derive(Resource)]
+pub struct AudioPacks {
+    folder: Handle<LoadedFolder>,
+    // Loaded packs, sorted by name
+    packs: Vec<(String, Handle<AudioPack>)>,
+    selected: String,
+    // A plugin supplied its own `GameAudio`, so packs stay out of the way
+    disabled: bool,
+}
+
+impl Default for AudioPacks {
+    fn default() -> Self {
+        Self {
+            folder: Handle::default(),
+            packs: Vec::new(),
+            selected: DEFAULT_AUDIO_PACK.to_string(),
+            disabled: false,
+        }
+    }
+}
+
+impl AudioPacks {
+    // Name of the picked pack, if it has loaded
+    pub fn selected(&self) -> Option<&str> {
+        self.packs.iter().find(|(name, _)| *name == self.selected).map(|(name, _)| name.as_str())
+    }
+
+    pub fn select_next(&mut self) {
+        if self.packs.is_empty() {
+            return;
+        }
+        let index = self.packs.iter().position(|(name, _)| *name == self.selected);
+        let next = index.map_or(0, |index| (index + 1) % self.packs.len());
+        self.selected = self.packs[next].0.clone();
+    }
+
+    fn current<'a>(&self, assets: &'a Assets<AudioPack>) -> Option<&'a AudioPack> {
+        if self.disabled {
+            return None;
+        }
+        let (_, handle) = self.packs.iter().find(|(name, _)| *name == self.selected)?;
+        assets.get(handle)
+    }
+}
+
+// The looping music of the picked pack
+#[derive(Component)]
+struct Soundtrack;
+
+// Gains of the audio buses, on top of the volume settings
+#[derive(Resource, Default)]
+pub struct AudioMixer {
+    pub music: BusEnvelope,
+}
+
+impl AudioMixer {
+    fn music_volume(&self, settings: &Settings) -> f32 {
+        SOUNDTRACK_VOLUME * settings.music_level() * self.music.gain()
+    }
+}
+
+fn setup_audio(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    existing_audio: Option<Res<GameAudio>>,
+    mut audio_packs: ResMut<AudioPacks>,
+    mut loading: ResMut<LoadingAssets>,
+) {
+    // A plugin may have provided its own sounds already
+    if existing_audio.is_some() {
+        audio_packs.disabled = true;
+        return;
+    }
+
+    // Load audio files from the assets folder
+    let game_audio = GameAudio {
+        jump_sound: asset_server.load("jump.wav"),
+        collect_sound: asset_server.load("collect.wav"),
+        death_sound: asset_server.load("death.wav"),
+    };
+
+    loading.track(game_audio.jump_sound.clone());
+    loading.track(game_audio.collect_sound.clone());
+    loading.track(game_audio.death_sound.clone());
+    commands.insert_resource(game_audio);
+    audio_packs.folder = asset_server.load_folder(AUDIO_PACK_FOLDER);
+    loading.track(audio_packs.folder.clone());
+}
+
+// Keeps the pack list up to date as packs load or change on disk
+fn collect_audio_packs(
+    mut folder_events: EventReader<AssetEvent<LoadedFolder>>,
+    mut pack_events: EventReader<AssetEvent<AudioPack>>,
+    folders: Res<Assets<LoadedFolder>>,
+    packs: Res<Assets<AudioPack>>,
+    mut audio_packs: ResMut<AudioPacks>,
+) {
+    let folder_changed = folder_events.read().count() > 0;
+    let packs_changed = pack_events.read().count() > 0;
+    if !folder_changed && !packs_changed {
+        return;
+    }
+    let Some(folder) = folders.get(&audio_packs.folder) else {
+        return;
+    };
+    let mut loaded: Vec<(String, Handle<AudioPack>)> = folder
+        .handles
+        .iter()
+        .filter_map(|handle| handle.clone().try_typed::<AudioPack>().ok())
+        .filter_map(|handle| packs.get(&handle).map(|pack| (pack.name.clone(), handle)))
+        .collect();
+    loaded.sort_by(|a, b| a.0.cmp(&b.0));
+    audio_packs.packs = loaded;
+    if audio_packs.selected().is_none() {
+        if let Some((name, _)) = audio_packs.packs.first() {
+            audio_packs.selected = name.clone();
+        }
+    }
+}
+
+// Restarts the soundtrack whenever the picked pack changes
+fn play_soundtrack(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    mixer: Res<AudioMixer>,
+    audio_packs: Res<AudioPacks>,
+    packs: Res<Assets<AudioPack>>,
+    soundtrack_query: Query<Entity, With<Soundtrack>>,
+) {
+    if !audio_packs.is_changed() {
+        return;
+    }
+    for entity in soundtrack_query.iter() {
+        commands.entity(entity).despawn();
+    }
+    if let Some(soundtrack) = audio_packs.current(&packs).and_then(|pack| pack.soundtrack.clone()) {
+        commands.spawn((
+            AudioBundle {
+                source: soundtrack,
+                settings: PlaybackSettings {
+                    mode: bevy::audio::PlaybackMode::Loop,
+                    volume: Volume::new(mixer.music_volume(&settings)),
+                    ..default()
+                },
+            },
+            Soundtrack,
+        ));
+    }
+}
+
+// Moves the bus envelopes along in real time, so a duck still lets go while
+// the game is paused
+fn update_mixer(time: Res<Time<Real>>, mut mixer: ResMut<AudioMixer>, dialogue_query: Query<(), With<DucksMusic>>) {
+    let dialogue = !dialogue_query.is_empty();
+    if !dialogue && !mixer.music.ducked() {
+        return;
+    }
+    if dialogue {
+        mixer.music.duck(Duck::DIALOGUE);
+    }
+    mixer.music.update(time.delta_seconds());
+}
+
+fn apply_music_volume(settings: Res<Settings>, mixer: Res<AudioMixer>, soundtrack_query: Query<&AudioSink, With<Soundtrack>>) {
+    if !settings.is_changed() && !mixer.is_changed() {
+        return;
+    }
+    for sink in soundtrack_query.iter() {
+        sink.set_volume(mixer.music_volume(&settings));
+    }
+}
+
+// How a surface's stand-in sound is pitched when a pack has none for it
+fn surface_speed(surface: Surface) -> f32 {
+    match surface {
+        Surface::Stone => 1.0,
+        Surface::Wood => 0.85,
+        Surface::Metal => 1.25,
+        Surface::Ice => 1.4,
+    }
+}
+
+// Plays the landing thud when a player comes down after some time in the air
+fn play_landing_sounds(
+    time: Res<Time>,
+    player_query: Query<(Entity, &Transform, &Grounded, &Collider), With<Player>>,
+    platform_query: Query<(&Transform, &Platform, Option<&PlatformSurface>), Without<Player>>,
+    mut airtime: Local<HashMap<Entity, f32>>,
+    mut sound_events: EventWriter<PlaySoundEvent>,
+) {
+    airtime.retain(|entity, _| player_query.contains(*entity));
+    for (entity, transform, grounded, collider) in player_query.iter() {
+        let seconds = airtime.entry(entity).or_default();
+        if !grounded.0 {
+            *seconds += time.delta_seconds();
+            continue;
+        }
+        if *seconds >= MIN_AIRTIME_FOR_LAND {
+            let surface = surface_under(transform.translation.truncate(), collider.size, platform_query.iter());
+            sound_events.send(PlaySoundEvent { sound_type: SoundType::LandOn(surface), position: None });
+        }
+        *seconds = 0.0;
+    }
+}
+
+// Plays a footstep every so often while a player runs along a platform
+fn play_footsteps(
+    time: Res<Time>,
+    player_query: Query<(Entity, &Transform, &Velocity, &Grounded, &Collider), With<Player>>,
+    platform_query: Query<(&Transform, &Platform, Option<&PlatformSurface>), Without<Player>>,
+    mut until_step: Local<HashMap<Entity, f32>>,
+    mut sound_events: EventWriter<PlaySoundEvent>,
+) {
+    until_step.retain(|entity, _| player_query.contains(*entity));
+    for (entity, transform, velocity, grounded, collider) in player_query.iter() {
+        let seconds = until_step.entry(entity).or_insert(FOOTSTEP_SECONDS / 2.0);
+        // The first step comes soon after starting to run
+        if !grounded.0 || velocity.x.abs() < MIN_FOOTSTEP_SPEED {
+            *seconds = FOOTSTEP_SECONDS / 2.0;
+            continue;
+        }
+        *seconds -= time.delta_seconds();
+        if *seconds <= 0.0 {
+            *seconds += FOOTSTEP_SECONDS;
+            let surface = surface_under(transform.translation.truncate(), collider.size, platform_query.iter());
+            sound_events.send(PlaySoundEvent { sound_type: SoundType::Footstep(surface), position: None });
+        }
+    }
+}
+
+fn play_collect_sound(mut fruit_events: EventReader<FruitCollectedEvent>, mut sound_events: EventWriter<PlaySoundEvent>) {
+    for _ in fruit_events.read() {
+        sound_events.send(PlaySoundEvent { sound_type: SoundType::Collect, position: None });
+    }
+}
+
+fn play_death_sound(mut death_events: EventReader<PlayerDiedEvent>, mut sound_events: EventWriter<PlaySoundEvent>) {
+    for _ in death_events.read() {
+        sound_events.send(PlaySoundEvent { sound_type: SoundType::Death, position: None });
+    }
+}
+
+fn play_level_up_sound(mut level_events: EventReader<LevelCompletedEvent>, mut sound_events: EventWriter<PlaySoundEvent>) {
+    for _ in level_events.read() {
+        sound_events.send(PlaySoundEvent { sound_type: SoundType::LevelUp, position: None });
+    }
+}
+
+fn play_sounds(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    mut sound_events: EventReader<PlaySoundEvent>,
+    game_audio: Res<GameAudio>,
+    audio_packs: Res<AudioPacks>,
+    packs: Res<Assets<AudioPack>>,
+    mut mixer: ResMut<AudioMixer>,
+) {
+    let pack = audio_packs.current(&packs);
+    let pack_sound = |sound_type: SoundType| pack.and_then(|pack| pack.sounds.get(sound_type.name())).cloned();
+    for event in sound_events.read() {
+        let (game_sound, volume, pitch_text) = match event.sound_type {
+            SoundType::Jump => (game_audio.jump_sound.clone(), 0.5, "Jump sound!"),
+            SoundType::Collect => (game_audio.collect_sound.clone(), 0.6, "Collect sound!"),
+            SoundType::Death => (game_audio.death_sound.clone(), 0.4, "Death sound!"),
+            SoundType::Land => (game_audio.jump_sound.clone(), 0.3, "Land sound!"),
+            SoundType::LandOn(_) => (game_audio.jump_sound.clone(), 0.3, "Land sound!"),
+            SoundType::Footstep(_) => (game_audio.jump_sound.clone(), 0.15, "Step!"),
+            SoundType::LevelUp => (game_audio.collect_sound.clone(), 0.5, "Level up!"),
+            SoundType::Squeak => (game_audio.jump_sound.clone(), 0.3, "Squeak!"),
+            SoundType::ExtraLife => (game_audio.collect_sound.clone(), 0.6, "Extra life!"),
+            SoundType::Crack => (game_audio.jump_sound.clone(), 0.4, "Crack!"),
+        };
+        if let Some(duck) = event.sound_type.duck() {
+            mixer.music.duck(duck);
+        }
+        let mut speed = 1.0;
+        let audio_source = match pack_sound(event.sound_type) {
+            Some(source) => source,
+            // Without a sound of its own: the land, jump or collect sound, sped
+            // up or slowed down
+            None => {
+                let (stand_in, stand_in_speed) = match event.sound_type {
+                    SoundType::Squeak => (Some(SoundType::Jump), 2.0),
+                    SoundType::Land => (Some(SoundType::Jump), 0.5),
+                    SoundType::LandOn(surface) => (Some(SoundType::Land), surface_speed(surface)),
+                    SoundType::Footstep(surface) => (Some(SoundType::Land), 1.5 * surface_speed(surface)),
+                    SoundType::LevelUp => (Some(SoundType::Collect), 1.5),
+                    SoundType::ExtraLife => (Some(SoundType::Collect), 2.0),
+                    SoundType::Crack => (Some(SoundType::Land), 0.7),
+                    _ => (None, 1.0),
+                };
+                speed = stand_in_speed;
+                stand_in.and_then(pack_sound).unwrap_or(game_sound)
+            }
+        };
+
+        // Spawn AudioBundle to play the sound
+        commands.spawn(AudioBundle {
+            source: audio_source,
+            settings: PlaybackSettings {
+                mode: bevy::audio::PlaybackMode::Despawn,
+                volume: Volume::new(volume * settings.effects_level()),
+                speed,
+                ..default()
+            },
+        });
+
+        // Also print to console for debugging
+        println!("🎵 {}", pitch_text);
+    }
+}
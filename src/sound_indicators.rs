@@ -0,0 +1,146 @@
+// Sound indicators, for players who can't hear the game. With "Sound
+// indicators" on in Settings, the sounds that tell you something (the
+// companion's warning squeak, a crumbling platform cracking, level and
+// extra-life jingles) flash a caption near the bottom of the screen. Sounds
+// that come from somewhere (see `PlaySoundEvent::position`) also send out a
+// ring there, or, when that's off screen, at the edge of the screen in its
+// direction. Like the audio, it listens to `PlaySoundEvent`, so any sound a
+// plugin plays shows up too.
+
+use bevy::prelude::*;
+use crate::prelude::*;
+use rustbevy_core::config::WINDOW_HEIGHT;
+
+use crate::culling::camera_view;
+use crate::settings::Settings;
+
+pub struct SoundIndicatorsPlugin;
+
+impl Plugin for SoundIndicatorsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SoundRings>()
+            .add_systems(Update, (show_sound_indicators, fade_captions, draw_sound_rings).chain());
+    }
+}
+
+const CAPTION_SECONDS: f32 = 1.5;
+const CAPTION_BLINK_RATE: f32 = 8.0;
+const CAPTION_Y: f32 = -WINDOW_HEIGHT / 2.0 + 90.0;
+const RING_SECONDS: f32 = 0.8;
+// A ring grows from the first radius to the second over its life
+const RING_RADIUS: (f32, f32) = (20.0, 70.0);
+// How far inside the edge of the screen the ring for an off-screen sound sits
+const EDGE_MARGIN: f32 = 50.0;
+
+// The caption and color of the sounds worth showing; the player's own jumps,
+// landings, footsteps and pickups are on screen already
+fn indicator(sound_type: SoundType) -> Option<(&'static str, Color)> {
+    match sound_type {
+        SoundType::Squeak => Some(("[squeak] Danger nearby", Color::srgb(1.0, 0.85, 0.2))),
+        SoundType::Crack => Some(("[crack] Platform crumbling", Color::srgb(1.0, 0.55, 0.2))),
+        SoundType::LevelUp => Some(("[jingle] Level up", Color::srgb(0.5, 1.0, 0.5))),
+        SoundType::ExtraLife => Some(("[jingle] Extra life", Color::srgb(1.0, 0.6, 0.8))),
+        _ => None,
+    }
+}
+
+#[derive(Component)]
+struct SoundCaption(Timer);
+
+// A ring going out from where a sound came from
+struct SoundRing {
+    position: Vec2,
+    color: Color,
+    age: f32,
+}
+
+#[derive(Resource, Default)]
+struct SoundRings(Vec<SoundRing>);
+
+fn show_sound_indicators(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    mut sound_events: EventReader<PlaySoundEvent>,
+    mut rings: ResMut<SoundRings>,
+    caption_query: Query<Entity, With<SoundCaption>>,
+) {
+    for event in sound_events.read() {
+        if !settings.sound_indicators {
+            continue;
+        }
+        let Some((caption, color)) = indicator(event.sound_type) else {
+            continue;
+        };
+        // The newest caption replaces the one showing
+        for entity in caption_query.iter() {
+            commands.entity(entity).despawn();
+        }
+        commands.spawn((
+            Text2dBundle {
+                text: Text::from_section(caption, TextStyle { font_size: 28.0, color, ..default() }),
+                transform: Transform::from_translation(Vec3::new(0.0, CAPTION_Y, 20.0)),
+                ..default()
+            },
+            SoundCaption(Timer::from_seconds(CAPTION_SECONDS, TimerMode::Once)),
+            Transient::new(CAPTION_SECONDS),
+            ScreenSpace,
+        ));
+        if let Some(position) = event.position {
+            rings.0.push(SoundRing { position, color, age: 0.0 });
+        }
+    }
+}
+
+// Captions blink for the first half of their time, then fade
+fn fade_captions(time: Res<Time>, mut commands: Commands, mut caption_query: Query<(Entity, &mut SoundCaption, &mut Text)>) {
+    for (entity, mut caption, mut text) in caption_query.iter_mut() {
+        if caption.0.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        let remaining = caption.0.fraction_remaining();
+        let alpha = if remaining > 0.5 {
+            if ((caption.0.elapsed_secs() * CAPTION_BLINK_RATE) as u32).is_multiple_of(2) { 1.0 } else { 0.4 }
+        } else {
+            remaining * 2.0
+        };
+        for section in text.sections.iter_mut() {
+            section.style.color.set_alpha(alpha);
+        }
+    }
+}
+
+// Rings are drawn in the world, pulled in to the edge of the view when the
+// sound came from outside it
+fn draw_sound_rings(
+    time: Res<Time>,
+    settings: Res<Settings>,
+    mut rings: ResMut<SoundRings>,
+    camera_query: Query<(&GlobalTransform, &OrthographicProjection), With<MainCamera>>,
+    mut gizmos: Gizmos,
+) {
+    for ring in rings.0.iter_mut() {
+        ring.age += time.delta_seconds();
+    }
+    rings.0.retain(|ring| ring.age < RING_SECONDS);
+    let Ok((camera_transform, projection)) = camera_query.get_single() else {
+        return;
+    };
+    if !settings.sound_indicators {
+        rings.0.clear();
+        return;
+    }
+    let view = camera_view(camera_transform, projection).inflate(-EDGE_MARGIN);
+    for ring in &rings.0 {
+        let progress = ring.age / RING_SECONDS;
+        let radius = RING_RADIUS.0 + (RING_RADIUS.1 - RING_RADIUS.0) * progress;
+        let color = ring.color.with_alpha(1.0 - progress);
+        let shown_at = ring.position.clamp(view.min, view.max);
+        gizmos.circle_2d(shown_at, radius, color);
+        // Off screen: an arrow out of the ring toward the sound
+        if shown_at != ring.position {
+            let direction = (ring.position - shown_at).normalize_or_zero();
+            gizmos.arrow_2d(shown_at, shown_at + direction * (radius + 20.0), color);
+        }
+    }
+}
@@ -0,0 +1,81 @@
+//! Broad phase for the collision checks (see `rustbevy_core::spatial`).
+//!
+//! `SpatialIndex` holds two grids of entities, rebuilt from scratch whenever
+//! what's in them may have moved: the platforms every physics step, once the
+//! moving platforms have moved and before `check_collisions`, and the fruit,
+//! power-ups and enemies every frame, once the fleeing fruit has run and before
+//! anything checks for a touch. Player collisions, enemy sight lines, pickups
+//! and enemy and hazard hits then only test what's in the cells around them.
+
+use bevy::prelude::*;
+use crate::prelude::*;
+use rustbevy_core::enemy::ENEMY_SIZE;
+use rustbevy_core::physics::Body;
+use rustbevy_core::spatial::SpatialGrid;
+
+use crate::fruit::{check_fruit_collection, flee_from_player};
+use crate::physics::{apply_velocity, check_collisions};
+use crate::platform::move_platforms;
+use crate::player::check_player_death;
+
+pub struct SpatialPlugin;
+
+impl Plugin for SpatialPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SpatialIndex>()
+            .add_systems(FixedUpdate, index_platforms
+                .after(move_platforms)
+                .after(apply_velocity)
+                .before(check_collisions)
+                .run_if(in_state(AppState::Playing)))
+            .add_systems(Update, index_touchables
+                .after(flee_from_player)
+                .before(check_fruit_collection)
+                .before(check_player_death)
+                .run_if(in_state(AppState::Playing)));
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct SpatialIndex {
+    pub platforms: SpatialGrid<Entity>,
+    /// Fruit, power-ups, enemies and hazards: what a player can touch
+    pub touchables: SpatialGrid<Entity>,
+}
+
+impl SpatialIndex {
+    /// Platforms `body` could run into or stand on by the end of this physics
+    /// step: its box, grown by a body's size (how far resolving one platform can
+    /// push it into the next) and the distance it moves in `dt`
+    pub fn platforms_near(&self, body: &Body, dt: f32) -> Vec<Entity> {
+        let reach = |speed: f32| body.size * 1.5 + speed.abs() * dt;
+        self.platforms.query((body.x, body.y), (reach(body.velocity_x), reach(body.velocity_y)))
+    }
+
+    /// Fruit, power-ups, enemies and hazards within `reach` of `center`
+    pub fn touchables_near(&self, center: Vec2, reach: f32) -> Vec<Entity> {
+        self.touchables.query((center.x, center.y), (reach, reach))
+    }
+}
+
+fn index_platforms(mut index: ResMut<SpatialIndex>, platform_query: Query<(Entity, &Transform, &Platform)>) {
+    index.platforms.clear();
+    for (entity, transform, platform) in platform_query.iter() {
+        index.platforms.insert(entity, (transform.translation.x, transform.translation.y), (platform.width / 2.0, platform.spec(transform, false).half_extent_y()));
+    }
+}
+
+fn index_touchables(
+    mut index: ResMut<SpatialIndex>,
+    touchable_query: Query<(Entity, &Transform, Has<Enemy>, Option<&HazardArea>), Or<(With<Fruit>, With<Pickup>, With<Enemy>, With<HazardArea>)>>,
+) {
+    index.touchables.clear();
+    for (entity, transform, enemy, hazard) in touchable_query.iter() {
+        let half_extents = match hazard {
+            Some(hazard) => (hazard.0.width / 2.0, hazard.0.height / 2.0),
+            None if enemy => (ENEMY_SIZE / 2.0, ENEMY_SIZE / 2.0),
+            None => (0.0, 0.0),
+        };
+        index.touchables.insert(entity, (transform.translation.x, transform.translation.y), half_extents);
+    }
+}
@@ -0,0 +1,151 @@
+// Speed effects: while a player runs faster than they can on their own
+// (flung by a trap, pushed off a wall, downhill) or falls fast, thin streaks
+// rush past the edges of the screen against the way they're going, more the
+// faster they go, and fading copies of the player's sprite trail behind them
+// as a cheap directional blur. Both are "Speed lines" on the Settings screen
+// and left out on the Low graphics preset, like the other particles.
+
+use bevy::prelude::*;
+use crate::prelude::*;
+use rustbevy_core::config::{MAX_FALL_SPEED, PLAYER_SPEED, WINDOW_HEIGHT, WINDOW_WIDTH};
+use rustbevy_core::rng::SeededRng;
+
+use crate::game::clock_seed;
+use crate::settings::Settings;
+
+pub struct SpeedLinesPlugin;
+
+impl Plugin for SpeedLinesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, (spawn_speed_effects, update_speed_lines, fade_afterimages).run_if(in_state(AppState::Playing)));
+    }
+}
+
+// Running faster than this, or falling faster than `FALL_THRESHOLD`, starts the effects
+const RUN_THRESHOLD: f32 = PLAYER_SPEED * 1.2;
+const FALL_THRESHOLD: f32 = MAX_FALL_SPEED / 2.0;
+// How much further past the thresholds the effects are at their strongest
+const RUN_RANGE: f32 = PLAYER_SPEED;
+const FALL_RANGE: f32 = MAX_FALL_SPEED / 2.0;
+// Streaks a second at full strength
+const LINES_PER_SECOND: f32 = 40.0;
+const LINE_SPEED: f32 = 1400.0;
+const LINE_SECONDS: f32 = 0.25;
+const LINE_LENGTH: (f32, f32) = (60.0, 140.0);
+const LINE_THICKNESS: f32 = 2.0;
+const LINE_COLOR: Color = Color::srgba(1.0, 1.0, 1.0, 0.35);
+// Streaks keep to the outer part of the screen, this share of the way out and beyond
+const LINE_INNER_EDGE: f32 = 0.55;
+// How opaque a fresh afterimage is and how long it takes to fade
+const AFTERIMAGE_ALPHA: f32 = 0.3;
+const AFTERIMAGE_SECONDS: f32 = 0.12;
+const LINE_Z: f32 = 15.0;
+
+// A streak drawn over the screen
+#[derive(Component)]
+struct SpeedLine {
+    velocity: Vec2,
+    lifetime: Timer,
+}
+
+// A fading copy of a fast player's sprite
+#[derive(Component)]
+struct Afterimage(Timer);
+
+// How strong the effects are for something moving at `velocity`: 0.0 at or
+// under the thresholds, 1.0 at full strength
+fn speed_strength(velocity: Vec2) -> f32 {
+    let run = (velocity.x.abs() - RUN_THRESHOLD) / RUN_RANGE;
+    let fall = (-velocity.y - FALL_THRESHOLD) / FALL_RANGE;
+    run.max(fall).clamp(0.0, 1.0)
+}
+
+fn spawn_speed_effects(
+    mut commands: Commands,
+    time: Res<Time>,
+    settings: Res<Settings>,
+    graphics: Res<GraphicsQuality>,
+    mut line_budget: Local<f32>,
+    player_query: Query<(&Transform, &Velocity, &Sprite), With<Player>>,
+) {
+    if !settings.speed_lines || !graphics.particles() {
+        return;
+    }
+    let mut rng = SeededRng::new(clock_seed());
+    // Streaks follow the fastest player, since they're on the whole screen
+    let mut fastest: Option<(f32, Vec2)> = None;
+    for (transform, velocity, sprite) in player_query.iter() {
+        let velocity = Vec2::new(velocity.x, velocity.y);
+        let strength = speed_strength(velocity);
+        if strength <= 0.0 {
+            continue;
+        }
+        if fastest.is_none_or(|(most, _)| strength > most) {
+            fastest = Some((strength, velocity));
+        }
+        let mut color = sprite.color;
+        color.set_alpha(color.alpha() * AFTERIMAGE_ALPHA * strength);
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite { color, ..sprite.clone() },
+                transform: transform.with_translation(transform.translation - Vec3::Z * 0.1),
+                ..default()
+            },
+            Afterimage(Timer::from_seconds(AFTERIMAGE_SECONDS, TimerMode::Once)),
+            Transient::new(AFTERIMAGE_SECONDS),
+            Budgeted::new(BudgetCategory::Particles, 0),
+        ));
+    }
+
+    let Some((strength, velocity)) = fastest else {
+        *line_budget = 0.0;
+        return;
+    };
+    *line_budget += LINES_PER_SECOND * strength * time.delta_seconds();
+    let direction = velocity.normalize();
+    while *line_budget >= 1.0 {
+        *line_budget -= 1.0;
+        let angle = rng.fraction() * std::f32::consts::TAU;
+        let distance = rng.range(LINE_INNER_EDGE, 1.0);
+        let position = Vec2::new(angle.cos() * WINDOW_WIDTH, angle.sin() * WINDOW_HEIGHT) / 2.0 * distance;
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: LINE_COLOR,
+                    custom_size: Some(Vec2::new(rng.range(LINE_LENGTH.0, LINE_LENGTH.1), LINE_THICKNESS)),
+                    ..default()
+                },
+                transform: Transform::from_translation(position.extend(LINE_Z)).with_rotation(Quat::from_rotation_z(direction.to_angle())),
+                ..default()
+            },
+            SpeedLine { velocity: -direction * LINE_SPEED, lifetime: Timer::from_seconds(LINE_SECONDS, TimerMode::Once) },
+            Transient::new(LINE_SECONDS),
+            Budgeted::new(BudgetCategory::Particles, 0),
+            ScreenSpace,
+        ));
+    }
+}
+
+fn update_speed_lines(time: Res<Time>, mut commands: Commands, mut line_query: Query<(Entity, &mut SpeedLine, &mut Transform, &mut Sprite)>) {
+    for (entity, mut line, mut transform, mut sprite) in line_query.iter_mut() {
+        if line.lifetime.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        transform.translation += (line.velocity * time.delta_seconds()).extend(0.0);
+        sprite.color.set_alpha(LINE_COLOR.alpha() * line.lifetime.fraction_remaining());
+    }
+}
+
+fn fade_afterimages(time: Res<Time>, mut commands: Commands, mut afterimage_query: Query<(Entity, &mut Afterimage, &mut Sprite)>) {
+    for (entity, mut afterimage, mut sprite) in afterimage_query.iter_mut() {
+        let alpha = sprite.color.alpha();
+        let remaining = afterimage.0.fraction_remaining();
+        if afterimage.0.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        // Fades by the share of its life this frame took
+        sprite.color.set_alpha(alpha * afterimage.0.fraction_remaining() / remaining.max(f32::EPSILON));
+    }
+}
@@ -0,0 +1,263 @@
+// Speedrun mode: a run timer on screen and split times for every level.
+//
+// L on the main menu (or `--speedrun` on the command line) turns it on. The
+// timer shows the Classic run time the categories count (see `categories`).
+// Each level finished on the way to the finish level is a split, shown under
+// the timer for a few seconds next to the best run's split for that level;
+// gold means the fastest that level has been done. When the run ends its
+// splits go on the splits leaderboard, kept in the save directory. Like the
+// categories, assisted runs and runs that didn't start at level 1 aren't
+// recorded.
+
+use bevy::prelude::*;
+use crate::prelude::*;
+use rustbevy_core::category::{format_time, FINISH_LEVEL};
+use rustbevy_core::config::WINDOW_HEIGHT;
+use rustbevy_core::splits::{format_delta, SplitBoard, SplitComparison, Splits};
+
+use crate::game::{read_save_file, setup_game_entities, write_save_file};
+use crate::menu::MainMenuUI;
+use crate::ui::{GameUI, Toasts};
+
+pub struct SpeedrunPlugin;
+
+impl Plugin for SpeedrunPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SpeedrunMode>()
+            .init_resource::<SplitLeaderboard>()
+            .init_resource::<RunSplits>()
+            .add_systems(OnEnter(AppState::MainMenu), setup_speedrun_menu_line)
+            .add_systems(OnEnter(AppState::Playing), setup_speedrun_hud.after(setup_game_entities))
+            .add_systems(OnEnter(AppState::GameOver), record_run_splits)
+            .add_systems(Update, (
+                toggle_speedrun_mode.run_if(in_state(AppState::MainMenu)),
+                (record_split, update_speedrun_hud)
+                    .chain()
+                    .run_if(in_state(AppState::Playing).and_then(resource_equals(GameMode::Classic)).and_then(speedrun_enabled)),
+            ));
+    }
+}
+
+const SPLITS_FILE: &str = "splits.txt";
+// How long a split stays on screen
+const SPLIT_SHOW_SECONDS: f32 = 4.0;
+const AHEAD_COLOR: Color = Color::srgb(0.3, 1.0, 0.4);
+const BEHIND_COLOR: Color = Color::srgb(1.0, 0.4, 0.35);
+const GOLD_COLOR: Color = Color::srgb(1.0, 0.85, 0.3);
+
+#[derive(Resource, Default)]
+pub struct SpeedrunMode(pub bool);
+
+fn speedrun_enabled(speedrun: Res<SpeedrunMode>) -> bool {
+    speedrun.0
+}
+
+#[derive(Resource)]
+struct SplitLeaderboard(SplitBoard);
+
+impl Default for SplitLeaderboard {
+    fn default() -> Self {
+        let saved = read_save_file(SPLITS_FILE).unwrap_or_default();
+        Self(SplitBoard::from_text(&saved))
+    }
+}
+
+// The current run's splits so far
+#[derive(Resource, Default)]
+struct RunSplits(Splits);
+
+#[derive(Component)]
+struct SpeedrunMenuText;
+
+#[derive(Component)]
+struct SpeedrunTimerText;
+
+// The latest split, until its timer runs out
+#[derive(Component)]
+struct SplitText(Timer);
+
+fn speedrun_menu_line(speedrun: &SpeedrunMode, leaderboard: &SplitLeaderboard) -> String {
+    let best = leaderboard
+        .0
+        .best()
+        .and_then(|best| best.total().map(|total| format!("best: level {} at {}", best.levels() + 1, format_time(total))))
+        .unwrap_or_else(|| "no splits yet".to_string());
+    format!("L: Speedrun timer and splits {} ({})", if speedrun.0 { "ON" } else { "OFF" }, best)
+}
+
+fn setup_speedrun_menu_line(mut commands: Commands, speedrun: Res<SpeedrunMode>, leaderboard: Res<SplitLeaderboard>) {
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                speedrun_menu_line(&speedrun, &leaderboard),
+                TextStyle {
+                    font_size: 22.0,
+                    color: GOLD_COLOR,
+                    ..default()
+                },
+            ),
+            transform: Transform::from_translation(Vec3::new(0.0, -125.0, 10.0)),
+            ..default()
+        },
+        MainMenuUI,
+        SpeedrunMenuText,
+    ));
+}
+
+fn toggle_speedrun_mode(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut speedrun: ResMut<SpeedrunMode>,
+    leaderboard: Res<SplitLeaderboard>,
+    mut text_query: Query<&mut Text, With<SpeedrunMenuText>>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::KeyL) {
+        return;
+    }
+    speedrun.0 = !speedrun.0;
+    if let Ok(mut text) = text_query.get_single_mut() {
+        text.sections[0].value = speedrun_menu_line(&speedrun, &leaderboard);
+    }
+}
+
+// A new Classic run starts with no splits and, in speedrun mode, the timer
+// under the title
+fn setup_speedrun_hud(
+    mut commands: Commands,
+    mut reset_events: EventReader<GameResetEvent>,
+    speedrun: Res<SpeedrunMode>,
+    mut run_splits: ResMut<RunSplits>,
+) {
+    let Some(event) = reset_events.read().last().copied() else {
+        return;
+    };
+    run_splits.0 = Splits::default();
+    if event.mode != GameMode::Classic || !speedrun.0 {
+        return;
+    }
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                format_time(0.0),
+                TextStyle {
+                    font_size: 36.0,
+                    color: Color::WHITE,
+                    ..default()
+                },
+            ),
+            transform: Transform::from_translation(Vec3::new(0.0, WINDOW_HEIGHT / 2.0 - 95.0, 10.0)),
+            ..default()
+        },
+        SpeedrunTimerText,
+        GameUI,
+    ));
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                "",
+                TextStyle {
+                    font_size: 24.0,
+                    color: Color::WHITE,
+                    ..default()
+                },
+            ),
+            transform: Transform::from_translation(Vec3::new(0.0, WINDOW_HEIGHT / 2.0 - 130.0, 10.0)),
+            ..default()
+        },
+        SplitText(Timer::from_seconds(SPLIT_SHOW_SECONDS, TimerMode::Once)),
+        GameUI,
+    ));
+}
+
+fn split_line(level: u32, seconds: f32, best: Option<f32>, comparison: SplitComparison) -> (String, Color) {
+    let mut line = format!("Level {}: {}", level, format_time(seconds));
+    if let (Some(best), Some(delta)) = (best, comparison.delta) {
+        line.push_str(&format!("  (best {}, {})", format_time(best), format_delta(delta)));
+    }
+    let color = match comparison.delta {
+        _ if comparison.gold => GOLD_COLOR,
+        Some(delta) if delta > 0.0 => BEHIND_COLOR,
+        Some(_) => AHEAD_COLOR,
+        None => Color::WHITE,
+    };
+    if comparison.gold {
+        line.push_str("  gold!");
+    }
+    (line, color)
+}
+
+// Splits every level finished until the finish level is reached
+fn record_split(
+    mut level_events: EventReader<LevelCompletedEvent>,
+    game_state: Res<GameState>,
+    leaderboard: Res<SplitLeaderboard>,
+    mut run_splits: ResMut<RunSplits>,
+    mut split_query: Query<(&mut Text, &mut SplitText)>,
+) {
+    for event in level_events.read() {
+        if game_state.stats.started_mid_run || event.level > FINISH_LEVEL {
+            continue;
+        }
+        let seconds = game_state.stats.seconds;
+        run_splits.0.push(seconds);
+        let Some(comparison) = leaderboard.0.compare(&run_splits.0) else {
+            continue;
+        };
+        let index = run_splits.0.levels() - 1;
+        let best = leaderboard.0.best().and_then(|best| best.times.get(index).copied());
+        let (line, color) = split_line(event.level - 1, seconds, best, comparison);
+        if let Ok((mut text, mut split)) = split_query.get_single_mut() {
+            text.sections[0].value = line;
+            text.sections[0].style.color = color;
+            split.0.reset();
+        }
+    }
+}
+
+fn update_speedrun_hud(
+    time: Res<Time>,
+    game_state: Res<GameState>,
+    mut timer_query: Query<&mut Text, (With<SpeedrunTimerText>, Without<SplitText>)>,
+    mut split_query: Query<(&mut Text, &mut SplitText)>,
+) {
+    if let Ok(mut text) = timer_query.get_single_mut() {
+        let value = format_time(game_state.stats.seconds);
+        if text.sections[0].value != value {
+            text.sections[0].value = value;
+        }
+    }
+    if let Ok((mut text, mut split)) = split_query.get_single_mut() {
+        if split.0.tick(time.delta()).just_finished() {
+            text.sections[0].value.clear();
+        }
+    }
+}
+
+// Puts the finished run on the splits leaderboard
+fn record_run_splits(
+    game_mode: Res<GameMode>,
+    speedrun: Res<SpeedrunMode>,
+    game_state: Res<GameState>,
+    assist_mode: Res<AssistMode>,
+    mut run_splits: ResMut<RunSplits>,
+    mut leaderboard: ResMut<SplitLeaderboard>,
+    mut toasts: ResMut<Toasts>,
+    mut errors: EventWriter<RuntimeErrorEvent>,
+) {
+    let splits = std::mem::take(&mut run_splits.0);
+    if *game_mode != GameMode::Classic || !speedrun.0 || splits.times.is_empty() {
+        return;
+    }
+    if assist_mode.0.any() || game_state.stats.started_mid_run {
+        toasts.push("Splits aren't recorded for assisted or continued runs");
+        return;
+    }
+    let levels = splits.levels();
+    match leaderboard.0.record(splits) {
+        Some(1) => toasts.push(format!("New best splits: {} levels", levels)),
+        Some(place) => toasts.push(format!("Splits #{} on the leaderboard", place)),
+        None => return,
+    }
+    if let Err(error) = write_save_file(SPLITS_FILE, &leaderboard.0.to_text(), "the splits") {
+        errors.send(error);
+    }
+}
@@ -0,0 +1,129 @@
+// Live run status for stream overlays.
+//
+// While streamer mode is on, http://127.0.0.1:7880/ answers with the current
+// run as JSON, e.g.
+//
+//     {"screen":"Playing","mode":"Classic","level":4,"score":1250,"lives":2,"level_seconds":12.3,"high_score":3100}
+//
+// so an OBS browser source or any other tool can show it. Requests are served
+// from a small background thread; the game only swaps in a fresh snapshot each
+// frame, so a slow client never holds up a frame.
+
+use std::io::{Read, Write};
+use std::net::{Ipv4Addr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use bevy::prelude::*;
+use crate::prelude::*;
+
+use crate::score::HighScore;
+use crate::streamer::StreamerMode;
+
+pub struct StatusServerPlugin;
+
+impl Plugin for StatusServerPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<StatusServer>()
+            .add_systems(Update, (run_status_server, publish_status).chain());
+    }
+}
+
+const STATUS_PORT: u16 = 7880;
+// How long the server thread naps when nobody is asking
+const IDLE_SLEEP: Duration = Duration::from_millis(50);
+const REQUEST_TIMEOUT: Duration = Duration::from_millis(200);
+
+#[derive(Resource, Default)]
+pub struct StatusServer {
+    // The JSON handed out to every request
+    snapshot: Arc<Mutex<String>>,
+    // Set to stop the server thread; None while it isn't running
+    stop: Option<Arc<AtomicBool>>,
+}
+
+impl StatusServer {
+    fn start(&mut self) -> std::io::Result<()> {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, STATUS_PORT))?;
+        listener.set_nonblocking(true)?;
+        let stop = Arc::new(AtomicBool::new(false));
+        let snapshot = Arc::clone(&self.snapshot);
+        let stopped = Arc::clone(&stop);
+        std::thread::spawn(move || {
+            while !stopped.load(Ordering::Relaxed) {
+                match listener.accept() {
+                    Ok((mut stream, _)) => serve(&mut stream, &snapshot),
+                    // Nobody waiting (or a failed accept): try again shortly
+                    Err(_) => std::thread::sleep(IDLE_SLEEP),
+                }
+            }
+        });
+        self.stop = Some(stop);
+        Ok(())
+    }
+
+    fn stop(&mut self) {
+        if let Some(stop) = self.stop.take() {
+            stop.store(true, Ordering::Relaxed);
+        }
+    }
+}
+
+// Answers any request with the latest snapshot
+fn serve(stream: &mut TcpStream, snapshot: &Mutex<String>) {
+    if stream.set_nonblocking(false).is_err() || stream.set_read_timeout(Some(REQUEST_TIMEOUT)).is_err() {
+        return;
+    }
+    // The request itself doesn't matter, but it has to be read before answering
+    let mut buffer = [0; 1024];
+    let _ = stream.read(&mut buffer);
+    let body = snapshot.lock().map(|snapshot| snapshot.clone()).unwrap_or_default();
+    let response = format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nAccess-Control-Allow-Origin: *\r\nCache-Control: no-store\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        body.len(),
+        body
+    );
+    let _ = stream.write_all(response.as_bytes());
+}
+
+// Starts or stops the server along with streamer mode
+fn run_status_server(streamer: Res<StreamerMode>, mut server: ResMut<StatusServer>, mut errors: EventWriter<RuntimeErrorEvent>) {
+    if !streamer.is_changed() {
+        return;
+    }
+    if !streamer.enabled {
+        server.stop();
+    } else if server.stop.is_none() {
+        if let Err(error) = server.start() {
+            errors.send(RuntimeErrorEvent::new("Status endpoint unavailable", format!("port {}: {}", STATUS_PORT, error)));
+        }
+    }
+}
+
+fn publish_status(
+    server: Res<StatusServer>,
+    app_state: Res<State<AppState>>,
+    game_mode: Res<GameMode>,
+    game_state: Res<GameState>,
+    difficulty: Res<Difficulty>,
+    high_score: Res<HighScore>,
+) {
+    if server.stop.is_none() {
+        return;
+    }
+    // Every field is a number or a fixed name, so nothing needs escaping
+    let json = format!(
+        "{{\"screen\":\"{:?}\",\"mode\":\"{}\",\"level\":{},\"score\":{},\"lives\":{},\"level_seconds\":{:.1},\"high_score\":{}}}",
+        app_state.get(),
+        game_mode.name(),
+        game_state.level,
+        game_state.score,
+        game_state.lives,
+        difficulty.0.level_seconds,
+        high_score.best,
+    );
+    if let Ok(mut snapshot) = server.snapshot.lock() {
+        *snapshot = json;
+    }
+}
@@ -0,0 +1,284 @@
+// Streamer mode.
+//
+// V on the main menu turns it on. While it is on, seeds are hidden wherever
+// the game would show them (so viewers can't spoil a level), the level timer
+// is drawn much bigger, and chat gets a say in the modifier drafts: a local
+// HTTP endpoint takes votes that a chat bot can post, e.g.
+//
+//     curl -X POST "http://127.0.0.1:7879/vote/2?user=somebody"
+//
+// A vote names a choice by its number (1-3) or its modifier key (`springy`,
+// ...). Each named user has one vote; voting again changes it. On the draft
+// screen ENTER takes the chat's favourite. The run status endpoint for
+// overlays (see `status_server`) is served while streamer mode is on too.
+
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::net::{Ipv4Addr, TcpListener, TcpStream};
+use std::time::Duration;
+
+use bevy::prelude::*;
+use crate::prelude::*;
+use rustbevy_core::modifiers::{Modifier, DRAFT_CHOICES};
+
+use crate::hud_layout::HudElement;
+use crate::menu::{MainMenuUI, ModifierDraft};
+use crate::ui::despawn_screen;
+
+pub struct StreamerPlugin;
+
+impl Plugin for StreamerPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<StreamerMode>()
+            .init_resource::<ChatVotes>()
+            .add_systems(OnEnter(AppState::MainMenu), setup_streamer_menu_line)
+            .add_systems(OnEnter(AppState::ModifierDraft), enter_vote_tally)
+            .add_systems(OnExit(AppState::ModifierDraft), despawn_screen::<VoteTallyText>)
+            .add_systems(Update, (
+                toggle_streamer_mode.run_if(in_state(AppState::MainMenu)),
+                run_vote_endpoint,
+                update_vote_tally.run_if(in_state(AppState::ModifierDraft)),
+                resize_timer,
+            ).chain());
+    }
+}
+
+// Localhost only; the lobby already uses 7777 for UDP
+const VOTE_PORT: u16 = 7879;
+const REQUEST_TIMEOUT: Duration = Duration::from_millis(50);
+const TIMER_FONT_SIZE: f32 = 30.0;
+const STREAMER_TIMER_FONT_SIZE: f32 = 64.0;
+
+#[derive(Resource, Default)]
+pub struct StreamerMode {
+    pub enabled: bool,
+}
+
+impl StreamerMode {
+    // What to show instead of a seed
+    pub fn seed_label(&self, seed: u64) -> String {
+        if self.enabled {
+            "hidden".to_string()
+        } else {
+            seed.to_string()
+        }
+    }
+}
+
+// Chat's votes on the modifier draft on screen
+#[derive(Resource, Default)]
+pub struct ChatVotes {
+    listener: Option<TcpListener>,
+    // Named voters, with their choice
+    named: HashMap<String, usize>,
+    anonymous: Vec<usize>,
+}
+
+impl ChatVotes {
+    fn clear(&mut self) {
+        self.named.clear();
+        self.anonymous.clear();
+    }
+
+    fn tally(&self) -> [u32; DRAFT_CHOICES] {
+        let mut tally = [0; DRAFT_CHOICES];
+        for &choice in self.named.values().chain(&self.anonymous) {
+            tally[choice] += 1;
+        }
+        tally
+    }
+
+    // The choice with the most votes; a tie goes to the lower number
+    pub fn leader(&self) -> Option<usize> {
+        let tally = self.tally();
+        let most = *tally.iter().max()?;
+        (most > 0).then(|| tally.iter().position(|&votes| votes == most)).flatten()
+    }
+}
+
+#[derive(Component)]
+struct StreamerMenuText;
+
+#[derive(Component)]
+struct VoteTallyText;
+
+fn streamer_menu_line(streamer: &StreamerMode) -> String {
+    let state = if streamer.enabled { "ON (seeds hidden, big timer, chat votes)" } else { "OFF" };
+    format!("V: Streamer mode {}", state)
+}
+
+fn setup_streamer_menu_line(mut commands: Commands, streamer: Res<StreamerMode>) {
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                streamer_menu_line(&streamer),
+                TextStyle {
+                    font_size: 22.0,
+                    color: Color::srgb(0.6, 0.4, 1.0), // Violet
+                    ..default()
+                },
+            ),
+            transform: Transform::from_translation(Vec3::new(0.0, 0.0, 10.0)),
+            ..default()
+        },
+        MainMenuUI,
+        StreamerMenuText,
+    ));
+}
+
+fn toggle_streamer_mode(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut streamer: ResMut<StreamerMode>,
+    mut text_query: Query<&mut Text, With<StreamerMenuText>>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::KeyV) {
+        return;
+    }
+    streamer.enabled = !streamer.enabled;
+    if let Ok(mut text) = text_query.get_single_mut() {
+        text.sections[0].value = streamer_menu_line(&streamer);
+    }
+}
+
+// The choice a vote path names, and who voted: `/vote/<choice>[?user=<name>]`
+fn parse_vote(path: &str, choices: &[Modifier]) -> Option<(usize, Option<String>)> {
+    let (path, query) = path.split_once('?').unwrap_or((path, ""));
+    let choice = path.strip_prefix("/vote/")?;
+    let index = match choice.parse::<usize>() {
+        Ok(number) => number.checked_sub(1).filter(|index| *index < choices.len())?,
+        Err(_) => {
+            let modifier = Modifier::from_key(&choice.to_lowercase())?;
+            choices.iter().position(|choice| *choice == modifier)?
+        }
+    };
+    let user = query
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("user="))
+        .filter(|user| !user.is_empty())
+        .map(str::to_lowercase);
+    Some((index, user))
+}
+
+// Reads one request and answers it; returns the vote it carried, if any
+fn answer_request(stream: &mut TcpStream, choices: Option<&[Modifier]>) -> Option<(usize, Option<String>)> {
+    stream.set_nonblocking(false).ok()?;
+    stream.set_read_timeout(Some(REQUEST_TIMEOUT)).ok()?;
+    let mut buffer = [0; 1024];
+    let read = stream.read(&mut buffer).ok()?;
+    let request = String::from_utf8_lossy(&buffer[..read]);
+    // "POST /vote/2?user=somebody HTTP/1.1"
+    let path = request.lines().next()?.split_whitespace().nth(1)?;
+
+    let (status, body, vote) = match choices {
+        None => ("409 Conflict", "No draft is open", None),
+        Some(choices) => match parse_vote(path, choices) {
+            Some(vote) => ("200 OK", "Vote counted", Some(vote)),
+            None => ("400 Bad Request", "Vote with /vote/<1-3 or modifier>?user=<name>", None),
+        },
+    };
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: text/plain\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+    // The bot may not wait for the answer; the vote counts either way
+    let _ = stream.write_all(response.as_bytes());
+    vote
+}
+
+// Opens the vote endpoint while streamer mode is on and counts incoming votes
+fn run_vote_endpoint(
+    streamer: Res<StreamerMode>,
+    app_state: Res<State<AppState>>,
+    modifier_draft: Res<ModifierDraft>,
+    mut votes: ResMut<ChatVotes>,
+    mut errors: EventWriter<RuntimeErrorEvent>,
+) {
+    if !streamer.enabled {
+        if votes.listener.is_some() {
+            votes.listener = None;
+        }
+        return;
+    }
+    if votes.listener.is_none() && streamer.is_changed() {
+        match TcpListener::bind((Ipv4Addr::LOCALHOST, VOTE_PORT)).and_then(|listener| listener.set_nonblocking(true).map(|_| listener)) {
+            Ok(listener) => votes.listener = Some(listener),
+            Err(error) => {
+                errors.send(RuntimeErrorEvent::new("Chat votes unavailable", format!("port {}: {}", VOTE_PORT, error)));
+            }
+        }
+    }
+
+    let drafting = *app_state.get() == AppState::ModifierDraft;
+    let choices = drafting.then_some(&modifier_draft.choices[..]);
+    let mut incoming = Vec::new();
+    if let Some(listener) = &votes.listener {
+        // Non-blocking, so this stops once nobody else is waiting
+        while let Ok((mut stream, _)) = listener.accept() {
+            incoming.extend(answer_request(&mut stream, choices));
+        }
+    }
+    for (choice, user) in incoming {
+        match user {
+            Some(user) => {
+                votes.named.insert(user, choice);
+            }
+            None => votes.anonymous.push(choice),
+        }
+    }
+}
+
+fn vote_tally_line(votes: &ChatVotes) -> String {
+    let tally: Vec<String> = votes
+        .tally()
+        .iter()
+        .enumerate()
+        .map(|(index, count)| format!("{}: {}", index + 1, count))
+        .collect();
+    format!("Chat votes   {}   (ENTER: take chat's pick)", tally.join("   "))
+}
+
+fn enter_vote_tally(mut commands: Commands, streamer: Res<StreamerMode>, mut votes: ResMut<ChatVotes>) {
+    votes.clear();
+    if !streamer.enabled {
+        return;
+    }
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                vote_tally_line(&votes),
+                TextStyle {
+                    font_size: 24.0,
+                    color: Color::srgb(0.6, 0.4, 1.0), // Violet
+                    ..default()
+                },
+            ),
+            transform: Transform::from_translation(Vec3::new(0.0, -200.0, 16.0)),
+            ..default()
+        },
+        VoteTallyText,
+    ));
+}
+
+fn update_vote_tally(votes: Res<ChatVotes>, mut text_query: Query<&mut Text, With<VoteTallyText>>) {
+    if !votes.is_changed() {
+        return;
+    }
+    if let Ok(mut text) = text_query.get_single_mut() {
+        text.sections[0].value = vote_tally_line(&votes);
+    }
+}
+
+// The level timer is easier to read on a stream when it's big
+fn resize_timer(streamer: Res<StreamerMode>, mut timer_query: Query<(Ref<HudElement>, &mut Text)>) {
+    let font_size = if streamer.enabled { STREAMER_TIMER_FONT_SIZE } else { TIMER_FONT_SIZE };
+    for (element, mut text) in timer_query.iter_mut() {
+        if *element != HudElement::Timer || !(streamer.is_changed() || element.is_added()) {
+            continue;
+        }
+        for section in text.sections.iter_mut() {
+            section.style.font_size = font_size;
+        }
+    }
+}
@@ -0,0 +1,119 @@
+//! Stress run: `cargo run --release -- --stress` plays a Classic run on a
+//! stress world (see `rustbevy_core::stress`): hundreds of platforms, enemies
+//! patrolling them and bonus fruit everywhere, so the physics, the broad phase
+//! (see `spatial`) and everything else that scales with the level is working
+//! far harder than on any real level.
+//!
+//! The run starts by itself. Every few seconds it prints the frame rate and
+//! the averages of the profiler markers (see `profiling`), and after
+//! `STRESS_SECONDS` a summary for the whole run, then quits. Lives are topped
+//! up so the enemies can't end it early, and every level of the run is the
+//! same stress world, so finishing one changes nothing. Standing still
+//! measures the world itself; playing measures it with a player in it.
+
+use bevy::diagnostic::DiagnosticsStore;
+use bevy::prelude::*;
+use crate::prelude::*;
+use rustbevy_core::config::PLAYER_SPAWN;
+use rustbevy_core::stress::stress_level;
+
+use crate::campaign::LevelProvider;
+use crate::level_loader::HandmadeLevel;
+use crate::profiling::MARKERS;
+
+pub struct StressPlugin;
+
+impl Plugin for StressPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, (
+            start_stress_run.run_if(in_state(AppState::MainMenu)),
+            (keep_lives, measure_stress_run).run_if(in_state(AppState::Playing)),
+        ).run_if(resource_exists::<StressTest>));
+    }
+}
+
+const STRESS_SEED: u64 = 1;
+// How long the run is measured, and how often it reports along the way
+const STRESS_SECONDS: f32 = 30.0;
+const REPORT_SECONDS: f32 = 5.0;
+const STRESS_LIVES: u32 = 3;
+
+#[derive(Resource)]
+pub struct StressTest {
+    level: HandmadeLevel,
+    started: bool,
+    // Real seconds and frames since the run started, and since the last report
+    seconds: f32,
+    frames: u32,
+    report_seconds: f32,
+    report_frames: u32,
+}
+
+/// `--stress` plays the stress run
+pub fn stress_argument() -> Option<StressTest> {
+    std::env::args().skip(1).any(|arg| arg == "--stress").then(|| StressTest {
+        level: HandmadeLevel { level: stress_level(STRESS_SEED), player_spawn: Vec2::from(PLAYER_SPAWN), theme: None },
+        started: false,
+        seconds: 0.0,
+        frames: 0,
+        report_seconds: 0.0,
+        report_frames: 0,
+    })
+}
+
+// Every level of a stress run is the stress world
+impl LevelProvider for StressTest {
+    fn level(&self, _number: u32) -> Option<HandmadeLevel> {
+        Some(self.level.clone())
+    }
+}
+
+fn start_stress_run(mut test: ResMut<StressTest>, mut game_mode: ResMut<GameMode>, mut next_state: ResMut<NextState<AppState>>) {
+    if test.started {
+        return;
+    }
+    test.started = true;
+    *game_mode = GameMode::Classic;
+    next_state.set(AppState::Playing);
+}
+
+fn keep_lives(mut game_state: ResMut<GameState>) {
+    if game_state.lives < STRESS_LIVES {
+        game_state.lives = STRESS_LIVES;
+    }
+}
+
+fn measure_stress_run(
+    time: Res<Time<Real>>,
+    diagnostics: Res<DiagnosticsStore>,
+    mut test: ResMut<StressTest>,
+    entity_query: Query<()>,
+    mut exit_events: EventWriter<AppExit>,
+) {
+    let dt = time.delta_seconds();
+    test.seconds += dt;
+    test.frames += 1;
+    test.report_seconds += dt;
+    test.report_frames += 1;
+    if test.report_seconds >= REPORT_SECONDS {
+        println!("stress: {}", report(test.report_frames, test.report_seconds, &diagnostics, entity_query.iter().count()));
+        test.report_seconds = 0.0;
+        test.report_frames = 0;
+    }
+    if test.seconds >= STRESS_SECONDS {
+        println!("stress: whole run, {}", report(test.frames, test.seconds, &diagnostics, entity_query.iter().count()));
+        exit_events.send(AppExit::Success);
+    }
+}
+
+// The frame rate over `frames` in `seconds`, each marker's recent average and
+// how many entities there are
+fn report(frames: u32, seconds: f32, diagnostics: &DiagnosticsStore, entities: usize) -> String {
+    let mut line = format!("{:.1} fps ({:.2} ms a frame), {} entities", frames as f32 / seconds, seconds * 1000.0 / frames as f32, entities);
+    for (name, path) in MARKERS {
+        if let Some(average) = diagnostics.get(&path).and_then(|diagnostic| diagnostic.average()) {
+            line.push_str(&format!(", {} {:.3} ms", name.to_lowercase(), average));
+        }
+    }
+    line
+}
@@ -0,0 +1,300 @@
+// Shareable run summary cards.
+//
+// When a Classic run ends (game over) or reaches the finish level, a card with
+// the level, score, time, seed and the run's mutators, modifiers and assists
+// (see `rustbevy_core::summary`) is drawn by its own camera into an offscreen
+// image, on a render layer the window's cameras don't draw. A few frames later
+// the render world copies the image back from the GPU and sends the pixels
+// over a channel; the game saves them as a PNG in the save directory's
+// `summaries` folder, and the card's line with the seed tells friends how to
+// play the same run.
+
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::Mutex;
+
+use bevy::prelude::*;
+use bevy::render::camera::{ClearColorConfig, RenderTarget};
+use bevy::render::extract_resource::{ExtractResource, ExtractResourcePlugin};
+use bevy::render::render_asset::{RenderAssetUsages, RenderAssets};
+use bevy::render::render_resource::{
+    BufferDescriptor, BufferUsages, CommandEncoderDescriptor, Extent3d, ImageCopyBuffer, ImageDataLayout, Maintain, MapMode,
+    TextureDimension, TextureFormat, TextureUsages,
+};
+use bevy::render::renderer::{RenderDevice, RenderQueue};
+use bevy::render::texture::GpuImage;
+use bevy::render::view::RenderLayers;
+use bevy::render::{Render, RenderApp, RenderSet};
+use crate::prelude::*;
+use rustbevy_core::category::FINISH_LEVEL;
+use rustbevy_core::summary::RunSummary;
+
+use crate::game::{save_path, unix_seconds};
+use crate::ui::Toasts;
+
+pub struct SummaryPlugin;
+
+impl Plugin for SummaryPlugin {
+    fn build(&self, app: &mut App) {
+        let (sender, receiver) = channel();
+        app.insert_resource(CardReadback { image: Handle::default(), capture: false, sender })
+            .insert_resource(CardPixels(Mutex::new(receiver)))
+            .init_resource::<PendingCard>()
+            .add_plugins(ExtractResourcePlugin::<CardReadback>::default())
+            .add_systems(Startup, create_card_image)
+            .add_systems(OnEnter(AppState::GameOver), card_for_game_over.run_if(resource_equals(GameMode::Classic)))
+            .add_systems(Update, (
+                card_for_finished_run.run_if(in_state(AppState::Playing).and_then(resource_equals(GameMode::Classic))),
+                capture_card,
+                save_card,
+            ).chain());
+        if let Some(render_app) = app.get_sub_app_mut(RenderApp) {
+            // After the frame's cameras have rendered
+            render_app.add_systems(Render, read_back_card.in_set(RenderSet::Cleanup));
+        }
+    }
+}
+
+const SUMMARY_DIR: &str = "summaries";
+const CARD_WIDTH: u32 = 640;
+const CARD_HEIGHT: u32 = 360;
+// Render layer only the card camera draws
+const CARD_LAYER: usize = 2;
+// Frames the card is drawn before it's read back, so its text is laid out
+const CAPTURE_DELAY_FRAMES: u32 = 3;
+const CARD_BORDER: Color = Color::srgb(1.0, 0.6, 0.0);
+const CARD_BACKGROUND: Color = Color::srgb(0.08, 0.1, 0.2);
+
+// The card image, and whether the render world should read it back this frame
+#[derive(Resource, Clone, ExtractResource)]
+struct CardReadback {
+    image: Handle<Image>,
+    capture: bool,
+    sender: Sender<Vec<u8>>,
+}
+
+// Pixels read back by the render world, tightly packed RGBA rows
+#[derive(Resource)]
+struct CardPixels(Mutex<Receiver<Vec<u8>>>);
+
+// The card being drawn, waiting for its pixels
+#[derive(Resource, Default)]
+struct PendingCard {
+    summary: Option<RunSummary>,
+    frames_left: u32,
+}
+
+// The card camera and everything on the card
+#[derive(Component)]
+struct SummaryCard;
+
+fn card_size() -> Extent3d {
+    Extent3d { width: CARD_WIDTH, height: CARD_HEIGHT, depth_or_array_layers: 1 }
+}
+
+fn create_card_image(mut images: ResMut<Assets<Image>>, mut readback: ResMut<CardReadback>) {
+    let mut image = Image::new_fill(
+        card_size(),
+        TextureDimension::D2,
+        &[0, 0, 0, 255],
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    );
+    image.texture_descriptor.usage = TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_SRC | TextureUsages::RENDER_ATTACHMENT;
+    readback.image = images.add(image);
+}
+
+fn run_summary(
+    game_state: &GameState,
+    game_rng: &GameRng,
+    mutator_mode: &MutatorMode,
+    run_modifiers: &RunModifiers,
+    assist_mode: &AssistMode,
+    finished: bool,
+) -> RunSummary {
+    RunSummary {
+        level: game_state.level,
+        score: game_state.score,
+        seconds: game_state.stats.seconds,
+        seed: game_rng.run_seed(),
+        finished,
+        mutators: mutator_mode.0.labels(),
+        modifiers: run_modifiers.0.picked.iter().map(|modifier| modifier.name()).collect(),
+        assists: assist_mode.0.labels(),
+    }
+}
+
+fn card_for_game_over(
+    mut commands: Commands,
+    game_state: Res<GameState>,
+    game_rng: Res<GameRng>,
+    mutator_mode: Res<MutatorMode>,
+    run_modifiers: Res<RunModifiers>,
+    assist_mode: Res<AssistMode>,
+    readback: Res<CardReadback>,
+    mut pending: ResMut<PendingCard>,
+) {
+    let summary = run_summary(&game_state, &game_rng, &mutator_mode, &run_modifiers, &assist_mode, false);
+    draw_card(&mut commands, &readback, &mut pending, summary);
+}
+
+fn card_for_finished_run(
+    mut commands: Commands,
+    mut level_events: EventReader<LevelCompletedEvent>,
+    game_state: Res<GameState>,
+    game_rng: Res<GameRng>,
+    mutator_mode: Res<MutatorMode>,
+    run_modifiers: Res<RunModifiers>,
+    assist_mode: Res<AssistMode>,
+    readback: Res<CardReadback>,
+    mut pending: ResMut<PendingCard>,
+) {
+    if level_events.read().any(|event| event.level == FINISH_LEVEL) {
+        let summary = run_summary(&game_state, &game_rng, &mutator_mode, &run_modifiers, &assist_mode, true);
+        draw_card(&mut commands, &readback, &mut pending, summary);
+    }
+}
+
+// Spawns the card and its camera; one card at a time
+fn draw_card(commands: &mut Commands, readback: &CardReadback, pending: &mut PendingCard, summary: RunSummary) {
+    if pending.summary.is_some() {
+        return;
+    }
+    let layer = RenderLayers::layer(CARD_LAYER);
+    commands.spawn((
+        Camera2dBundle {
+            camera: Camera {
+                order: -1,
+                target: RenderTarget::Image(readback.image.clone()),
+                clear_color: ClearColorConfig::Custom(CARD_BORDER),
+                ..default()
+            },
+            ..default()
+        },
+        layer.clone(),
+        SummaryCard,
+    ));
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: CARD_BACKGROUND,
+                custom_size: Some(Vec2::new(CARD_WIDTH as f32 - 16.0, CARD_HEIGHT as f32 - 16.0)),
+                ..default()
+            },
+            ..default()
+        },
+        layer.clone(),
+        Offscreen,
+        SummaryCard,
+    ));
+
+    let title_color = if summary.finished { Color::srgb(1.0, 0.85, 0.3) } else { Color::srgb(1.0, 0.2, 0.2) };
+    let mut texts = vec![(summary.title().to_string(), 56.0, title_color, 110.0)];
+    for (index, line) in summary.lines().into_iter().enumerate() {
+        let color = if index == 0 { Color::srgb(1.0, 1.0, 0.0) } else { Color::srgb(0.85, 0.85, 0.85) };
+        texts.push((line, if index == 0 { 28.0 } else { 20.0 }, color, 40.0 - index as f32 * 34.0));
+    }
+    texts.push((summary.seed_hint(), 18.0, Color::srgb(0.5, 1.0, 0.6), -140.0));
+    for (text, font_size, color, y) in texts {
+        commands.spawn((
+            Text2dBundle {
+                text: Text::from_section(text, TextStyle { font_size, color, ..default() }),
+                transform: Transform::from_translation(Vec3::new(0.0, y, 1.0)),
+                ..default()
+            },
+            layer.clone(),
+            Offscreen,
+            SummaryCard,
+        ));
+    }
+
+    *pending = PendingCard { summary: Some(summary), frames_left: CAPTURE_DELAY_FRAMES };
+}
+
+// Asks the render world for the pixels once the card has been drawn a few times
+fn capture_card(mut pending: ResMut<PendingCard>, mut readback: ResMut<CardReadback>) {
+    let capture = pending.summary.is_some() && pending.frames_left == 1;
+    pending.frames_left = pending.frames_left.saturating_sub(1);
+    if readback.capture != capture {
+        readback.capture = capture;
+    }
+}
+
+fn save_card(
+    mut commands: Commands,
+    pixels: Res<CardPixels>,
+    mut pending: ResMut<PendingCard>,
+    card_query: Query<Entity, With<SummaryCard>>,
+    mut toasts: ResMut<Toasts>,
+    mut errors: EventWriter<RuntimeErrorEvent>,
+) {
+    let Some(data) = pixels.0.lock().ok().and_then(|receiver| receiver.try_recv().ok()) else {
+        return;
+    };
+    let Some(summary) = pending.summary.take() else {
+        return;
+    };
+    for entity in card_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    let timestamp = unix_seconds();
+    let path = save_path(SUMMARY_DIR).join(summary.file_name(timestamp));
+    let image = Image::new(card_size(), TextureDimension::D2, data, TextureFormat::Rgba8UnormSrgb, RenderAssetUsages::MAIN_WORLD);
+    let saved = std::fs::create_dir_all(save_path(SUMMARY_DIR))
+        .map_err(|error| error.to_string())
+        .and_then(|_| image.try_into_dynamic().map_err(|error| error.to_string()))
+        .and_then(|image| image.save(&path).map_err(|error| error.to_string()));
+    match saved {
+        Ok(()) => toasts.push(format!("Run summary saved to {} - share seed {} with friends", path.display(), summary.seed)),
+        Err(error) => {
+            errors.send(RuntimeErrorEvent::new("Could not save the run summary", format!("{}: {}", path.display(), error)));
+        }
+    }
+}
+
+// Copies the card image into a buffer, waits for it and sends the pixels to
+// the main world without the row padding the copy needs
+fn read_back_card(
+    readback: Res<CardReadback>,
+    gpu_images: Res<RenderAssets<GpuImage>>,
+    render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
+) {
+    if !readback.capture {
+        return;
+    }
+    let Some(gpu_image) = gpu_images.get(&readback.image) else {
+        return;
+    };
+    let row_bytes = CARD_WIDTH as usize * 4;
+    let padded_row_bytes = RenderDevice::align_copy_bytes_per_row(row_bytes);
+    let buffer = render_device.create_buffer(&BufferDescriptor {
+        label: Some("summary_card_readback"),
+        size: (padded_row_bytes * CARD_HEIGHT as usize) as u64,
+        usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    });
+    let mut encoder = render_device.create_command_encoder(&CommandEncoderDescriptor { label: Some("summary_card_readback") });
+    encoder.copy_texture_to_buffer(
+        gpu_image.texture.as_image_copy(),
+        ImageCopyBuffer {
+            buffer: &buffer,
+            layout: ImageDataLayout { offset: 0, bytes_per_row: Some(padded_row_bytes as u32), rows_per_image: None },
+        },
+        card_size(),
+    );
+    render_queue.submit([encoder.finish()]);
+
+    let slice = buffer.slice(..);
+    let (mapped_sender, mapped) = channel();
+    render_device.map_buffer(&slice, MapMode::Read, move |result| {
+        let _ = mapped_sender.send(result);
+    });
+    render_device.poll(Maintain::wait());
+    if !matches!(mapped.try_recv(), Ok(Ok(()))) {
+        return;
+    }
+    let pixels: Vec<u8> = slice.get_mapped_range().chunks(padded_row_bytes).flat_map(|row| &row[..row_bytes]).copied().collect();
+    buffer.unmap();
+    let _ = readback.sender.send(pixels);
+}
@@ -0,0 +1,168 @@
+// How a Classic level's theme looks (see `rustbevy_core::theme` for which
+// level gets which, and what ice does underfoot). The sky takes the theme's
+// colors (see `sky`), platforms are tinted toward its palette, and a sheet of
+// ambient light lies over the whole level: a faint chill on ice, darkness in
+// caves and at night with a pool of light around the player. Party games and
+// the menus are left untinted.
+
+use bevy::color::Mix;
+use bevy::prelude::*;
+use bevy::render::mesh::{Indices, PrimitiveTopology};
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::sprite::{MaterialMesh2dBundle, Mesh2dHandle};
+use bevy::transform::TransformSystem;
+use crate::prelude::*;
+use rustbevy_core::theme::LevelTheme;
+
+use crate::physics::interpolate_positions;
+use crate::platform::CurrentLevel;
+
+pub struct ThemePlugin;
+
+impl Plugin for ThemePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_lighting)
+            .add_systems(PostUpdate, update_lighting.after(interpolate_positions).before(TransformSystem::TransformPropagate));
+    }
+}
+
+// Over the level and its effects, under the HUD
+const LIGHTING_Z: f32 = 40.0;
+// The pool of light around the player in the dark themes: clear out to the
+// first radius, fading into the dark by the second
+const LIGHT_RADII: (f32, f32) = (90.0, 280.0);
+// Far enough out to cover the most zoomed-out view
+const DARK_RADIUS: f32 = 6000.0;
+const LIGHT_SEGMENTS: usize = 48;
+
+// The platform palette: a color platforms lean toward, and how far
+fn palette(theme: LevelTheme) -> (Color, f32) {
+    match theme {
+        LevelTheme::Grass => (Color::srgb(0.4, 0.6, 0.3), 0.15),
+        LevelTheme::Cave => (Color::srgb(0.35, 0.27, 0.2), 0.35),
+        LevelTheme::Ice => (Color::srgb(0.78, 0.9, 1.0), 0.35),
+        LevelTheme::Night => (Color::srgb(0.2, 0.22, 0.4), 0.35),
+    }
+}
+
+// The light over the level, and whether the player carries a pool of light
+fn ambience(theme: LevelTheme) -> (Color, bool) {
+    match theme {
+        LevelTheme::Grass => (Color::NONE, false),
+        LevelTheme::Cave => (Color::srgba(0.05, 0.03, 0.02, 0.55), true),
+        LevelTheme::Ice => (Color::srgba(0.7, 0.85, 1.0, 0.12), false),
+        LevelTheme::Night => (Color::srgba(0.02, 0.03, 0.12, 0.6), true),
+    }
+}
+
+pub fn theme_title(theme: LevelTheme) -> &'static str {
+    match theme {
+        LevelTheme::Grass => "Grass",
+        LevelTheme::Cave => "Cave",
+        LevelTheme::Ice => "Ice",
+        LevelTheme::Night => "Night",
+    }
+}
+
+// `color` leaned toward the theme's palette, its alpha kept
+pub fn themed_color(color: Color, theme: LevelTheme) -> Color {
+    let (tint, amount) = palette(theme);
+    color.mix(&tint.with_alpha(color.alpha()), amount)
+}
+
+// The ambient light sheet, and the theme its colors are for
+#[derive(Component)]
+struct Lighting {
+    theme: Option<LevelTheme>,
+}
+
+// Rings around the middle, out to `LIGHT_RADII` and `DARK_RADIUS`; the
+// middle vertex comes first, then each ring from the inside out
+fn lighting_mesh() -> Mesh {
+    let mut positions = vec![[0.0, 0.0, 0.0]];
+    for radius in [LIGHT_RADII.0, LIGHT_RADII.1, DARK_RADIUS] {
+        positions.extend((0..LIGHT_SEGMENTS).map(|segment| {
+            let angle = segment as f32 / LIGHT_SEGMENTS as f32 * std::f32::consts::TAU;
+            [angle.cos() * radius, angle.sin() * radius, 0.0]
+        }));
+    }
+    let ring = |ring: usize, segment: usize| (1 + ring * LIGHT_SEGMENTS + segment % LIGHT_SEGMENTS) as u32;
+    let mut indices = Vec::new();
+    for segment in 0..LIGHT_SEGMENTS {
+        indices.extend([0, ring(0, segment), ring(0, segment + 1)]);
+        for inner in 0..2 {
+            let (a, b) = (ring(inner, segment), ring(inner, segment + 1));
+            let (c, d) = (ring(inner + 1, segment), ring(inner + 1, segment + 1));
+            indices.extend([a, c, d, a, d, b]);
+        }
+    }
+    let count = positions.len();
+    let mut mesh = Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::default());
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, vec![[0.0, 0.0, 1.0]; count]);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, vec![[0.0, 0.0]; count]);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, lighting_colors(LevelTheme::Grass));
+    mesh.insert_indices(Indices::U32(indices));
+    mesh
+}
+
+// Vertex colors for `lighting_mesh`: in the dark themes the middle and the
+// first ring are clear, the rest the theme's ambient light
+fn lighting_colors(theme: LevelTheme) -> Vec<[f32; 4]> {
+    let (ambient, light) = ambience(theme);
+    // Clear is the ambient color with none of it showing, so the fade into
+    // the dark doesn't pass through another color
+    let inner = if light { ambient.with_alpha(0.0) } else { ambient };
+    let mut colors = vec![LinearRgba::from(inner).to_f32_array(); 1 + LIGHT_SEGMENTS];
+    colors.extend(vec![LinearRgba::from(ambient).to_f32_array(); LIGHT_SEGMENTS * 2]);
+    colors
+}
+
+fn setup_lighting(mut commands: Commands, mut meshes: ResMut<Assets<Mesh>>, mut materials: ResMut<Assets<ColorMaterial>>) {
+    commands.spawn((
+        MaterialMesh2dBundle {
+            mesh: meshes.add(lighting_mesh()).into(),
+            // White, so the vertex colors show as they are
+            material: materials.add(ColorMaterial::from(Color::WHITE)),
+            transform: Transform::from_xyz(0.0, 0.0, LIGHTING_Z),
+            visibility: Visibility::Hidden,
+            ..default()
+        },
+        Lighting { theme: None },
+    ));
+}
+
+// Shows the current level's light during a Classic run, centred on the
+// player (on the camera while there's no player to follow)
+fn update_lighting(
+    app_state: Res<State<AppState>>,
+    game_mode: Res<GameMode>,
+    current_level: Res<CurrentLevel>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    player_query: Query<&Transform, (With<LeadPlayer>, Without<Lighting>)>,
+    camera_query: Query<&Transform, (With<MainCamera>, Without<Lighting>)>,
+    mut lighting_query: Query<(&mut Lighting, &mut Transform, &mut Visibility, &Mesh2dHandle)>,
+) {
+    let Ok((mut lighting, mut transform, mut visibility, mesh)) = lighting_query.get_single_mut() else {
+        return;
+    };
+    let in_run = matches!(
+        app_state.get(),
+        AppState::Playing | AppState::Paused | AppState::ModifierDraft | AppState::DeathRecap | AppState::GameOver
+    );
+    let shown = in_run && *game_mode == GameMode::Classic && current_level.theme != LevelTheme::Grass;
+    visibility.set_if_neq(if shown { Visibility::Inherited } else { Visibility::Hidden });
+    if !shown {
+        return;
+    }
+    if lighting.theme != Some(current_level.theme) {
+        if let Some(mesh) = meshes.get_mut(&mesh.0) {
+            mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, lighting_colors(current_level.theme));
+            lighting.theme = Some(current_level.theme);
+        }
+    }
+    let centre = player_query.get_single().or(camera_query.get_single()).map(|centre| centre.translation.truncate());
+    if let Ok(centre) = centre {
+        transform.translation = centre.extend(LIGHTING_Z);
+    }
+}
@@ -0,0 +1,247 @@
+// The endless tower: one screen wide, built upward forever while the screen
+// scrolls up after the player.
+//
+// `rustbevy_core::tower` lays out the platforms a chunk of rows at a time;
+// the next chunk is spawned before the top of the screen reaches the rows
+// built so far, and platforms are despawned once they've scrolled off the
+// bottom. The screen starts scrolling once the player leaves the floor, gets
+// faster the higher they climb and catches up when they get near its top.
+// Dropping below the screen costs a life and puts the player back on a
+// platform on screen. The score is the height climbed; the best climb is kept
+// in the save directory.
+
+use bevy::prelude::*;
+use crate::prelude::*;
+use rustbevy_core::config::{PLAYER_SIZE, WINDOW_HEIGHT};
+use rustbevy_core::physics::Tuning;
+use rustbevy_core::reachability::JumpProfile;
+use rustbevy_core::tower::{self, TowerBuilder, TOWER_FLOOR};
+
+use crate::game::{read_save_file, setup_game_entities, write_save_file};
+use crate::platform::spawn_platforms;
+use crate::player::{spawn_coop_partner, spawn_player};
+use crate::pool::EntityPool;
+use crate::ui::{despawn_screen, setup_tower_ui};
+
+pub struct TowerPlugin;
+
+impl Plugin for TowerPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TowerBest>()
+            .add_systems(OnEnter(AppState::Playing), setup_tower.after(setup_game_entities))
+            .add_systems(OnEnter(AppState::GameOver), enter_tower_results.run_if(resource_equals(GameMode::Tower)))
+            .add_systems(OnExit(AppState::GameOver), despawn_screen::<TowerResultsUI>)
+            .add_systems(Update, (scroll_tower, build_tower, despawn_scrolled_off, fall_off_screen, score_climb)
+                .chain()
+                .run_if(in_state(AppState::Playing).and_then(resource_equals(GameMode::Tower)).and_then(resource_exists::<Tower>)));
+    }
+}
+
+const TOWER_BEST_FILE: &str = "tower_best.txt";
+// The screen starts scrolling once the player has climbed this far
+const SCROLL_START_HEIGHT: f32 = 40.0;
+// The screen scrolls faster to keep the player at least this far below its top
+const CATCH_UP_MARGIN: f32 = WINDOW_HEIGHT / 3.0;
+// Rows are built this far above the top of the screen
+const BUILD_AHEAD: f32 = WINDOW_HEIGHT / 2.0;
+// A respawned player is put on a platform at least this far above the bottom of the screen
+const RESPAWN_CLEARANCE: f32 = WINDOW_HEIGHT / 4.0;
+
+// The tower being climbed
+#[derive(Resource)]
+pub struct Tower {
+    builder: TowerBuilder,
+    // The highest the player's feet have been above the floor
+    highest: f32,
+}
+
+// The best climb, as a score
+#[derive(Resource)]
+pub struct TowerBest {
+    pub best: u32,
+    // The current climb has beaten it
+    pub beaten: bool,
+}
+
+impl Default for TowerBest {
+    fn default() -> Self {
+        let saved = read_save_file(TOWER_BEST_FILE).unwrap_or_default();
+        Self {
+            best: saved.trim().parse().unwrap_or(0),
+            beaten: false,
+        }
+    }
+}
+
+#[derive(Component)]
+struct TowerResultsUI;
+
+// A new climb: the floor, the player on it, the camera at the bottom and the
+// first rows above
+fn setup_tower(
+    mut commands: Commands,
+    mut reset_events: EventReader<GameResetEvent>,
+    mut game_rng: ResMut<GameRng>,
+    mut best: ResMut<TowerBest>,
+    mut pool: ResMut<EntityPool>,
+    mut camera_query: Query<&mut Transform, With<MainCamera>>,
+) {
+    if reset_events.read().last().is_none_or(|event| event.mode != GameMode::Tower) {
+        return;
+    }
+    let seed = game_rng.start_run();
+    let mut builder = TowerBuilder::new(seed, JumpProfile::new(&Tuning::default(), PLAYER_SIZE));
+    spawn_platforms(&mut commands, &mut pool, &[TOWER_FLOOR]);
+    spawn_platforms(&mut commands, &mut pool, &builder.next_chunk());
+    spawn_player(&mut commands, PLAYER_SIZE, Vec2::new(0.0, TOWER_FLOOR.y + (TOWER_FLOOR.height + PLAYER_SIZE) / 2.0));
+    for mut transform in camera_query.iter_mut() {
+        transform.translation.x = 0.0;
+        transform.translation.y = 0.0;
+    }
+    commands.insert_resource(Tower { builder, highest: 0.0 });
+    best.beaten = false;
+    setup_tower_ui(commands, best.best);
+}
+
+fn scroll_tower(
+    time: Res<Time>,
+    mut tower: ResMut<Tower>,
+    player_query: Query<(&Transform, &Collider), With<Player>>,
+    mut camera_query: Query<&mut Transform, (With<MainCamera>, Without<Player>)>,
+) {
+    let Ok(mut camera) = camera_query.get_single_mut() else {
+        return;
+    };
+    // The screen follows whoever is highest
+    let player = player_query.iter().max_by(|(a, _), (b, _)| a.translation.y.total_cmp(&b.translation.y));
+    if let Some((transform, collider)) = player {
+        let feet = transform.translation.y - collider.size / 2.0 - (TOWER_FLOOR.y + TOWER_FLOOR.height / 2.0);
+        tower.highest = tower.highest.max(feet);
+    }
+    if tower.highest >= SCROLL_START_HEIGHT {
+        camera.translation.y += tower::scroll_speed(tower.highest) * time.delta_seconds();
+    }
+    if let Some((transform, _)) = player {
+        let ceiling = camera.translation.y + WINDOW_HEIGHT / 2.0 - CATCH_UP_MARGIN;
+        camera.translation.y += (transform.translation.y - ceiling).max(0.0);
+    }
+    camera.translation.x = 0.0;
+}
+
+fn build_tower(mut commands: Commands, mut tower: ResMut<Tower>, mut pool: ResMut<EntityPool>, camera_query: Query<&Transform, With<MainCamera>>) {
+    let Ok(camera) = camera_query.get_single() else {
+        return;
+    };
+    while tower.builder.built_up_to() < camera.translation.y + WINDOW_HEIGHT / 2.0 + BUILD_AHEAD {
+        let chunk = tower.builder.next_chunk();
+        spawn_platforms(&mut commands, &mut pool, &chunk);
+    }
+}
+
+fn despawn_scrolled_off(
+    mut commands: Commands,
+    platform_query: Query<(Entity, &Transform, &Platform)>,
+    camera_query: Query<&Transform, With<MainCamera>>,
+) {
+    let Ok(camera) = camera_query.get_single() else {
+        return;
+    };
+    let bottom = camera.translation.y - WINDOW_HEIGHT / 2.0;
+    for (entity, transform, platform) in platform_query.iter() {
+        if transform.translation.y + platform.height / 2.0 < bottom {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+// Below the bottom of the screen is a fall: a life lost, and the player back
+// on the lowest platform well clear of the bottom, or the end of the climb
+fn fall_off_screen(
+    mut commands: Commands,
+    mut game_state: ResMut<GameState>,
+    assist_mode: Res<AssistMode>,
+    player_query: Query<(Entity, &Transform, &Collider, Has<CoopPartner>), With<Player>>,
+    platform_query: Query<(&Transform, &Platform), Without<Player>>,
+    camera_query: Query<&Transform, (With<MainCamera>, Without<Player>)>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut death_events: EventWriter<PlayerDiedEvent>,
+    mut particle_events: EventWriter<SpawnParticles>,
+) {
+    let Ok(camera) = camera_query.get_single() else {
+        return;
+    };
+    let bottom = camera.translation.y - WINDOW_HEIGHT / 2.0;
+    for (player, transform, collider, partner) in player_query.iter() {
+        if transform.translation.y + collider.size / 2.0 >= bottom || game_state.lives == 0 {
+            continue;
+        }
+        if !assist_mode.0.infinite_lives {
+            game_state.lives = game_state.lives.saturating_sub(1);
+        }
+        death_events.send(PlayerDiedEvent { player, lives_left: game_state.lives });
+        particle_events.send(SpawnParticles { effect: ParticleEffect::DeathExplosion, position: Vec2::new(transform.translation.x, bottom) });
+        commands.entity(player).despawn();
+        if game_state.lives == 0 {
+            next_state.set(AppState::GameOver);
+            continue;
+        }
+        let landing = platform_query
+            .iter()
+            .map(|(transform, platform)| Vec2::new(transform.translation.x, transform.translation.y + platform.height / 2.0))
+            .filter(|top| top.y >= bottom + RESPAWN_CLEARANCE)
+            .min_by(|a, b| a.y.total_cmp(&b.y));
+        let spawn = landing.unwrap_or(Vec2::new(0.0, camera.translation.y)) + Vec2::new(0.0, collider.size / 2.0 + 1.0);
+        if partner {
+            spawn_coop_partner(&mut commands, collider.size, spawn);
+        } else {
+            spawn_player(&mut commands, collider.size, spawn);
+        }
+    }
+}
+
+fn score_climb(
+    tower: Res<Tower>,
+    mut game_state: ResMut<GameState>,
+    assist_mode: Res<AssistMode>,
+    mut best: ResMut<TowerBest>,
+    mut errors: EventWriter<RuntimeErrorEvent>,
+) {
+    let score = tower::height_score(tower.highest);
+    if score == game_state.score {
+        return;
+    }
+    game_state.score = score;
+    // Assisted climbs don't count towards the best
+    if assist_mode.0.any() || score <= best.best {
+        return;
+    }
+    best.best = score;
+    best.beaten = true;
+    if let Err(error) = write_save_file(TOWER_BEST_FILE, &best.best.to_string(), "the best tower climb") {
+        errors.send(error);
+    }
+}
+
+fn enter_tower_results(mut commands: Commands, tower: Res<Tower>, game_state: Res<GameState>, best: Res<TowerBest>) {
+    let lines = [
+        ("GAME OVER".to_string(), 80.0, Color::srgb(1.0, 0.0, 0.0), 150.0), // Red
+        (
+            if best.beaten { "NEW BEST CLIMB!".to_string() } else { format!("Best: {}", best.best) },
+            30.0,
+            Color::srgb(1.0, 0.85, 0.3), // Gold
+            95.0,
+        ),
+        (format!("Climbed: {:.0}m   Score: {}", tower.highest / 100.0, game_state.score), 40.0, Color::srgb(1.0, 1.0, 0.0), 50.0), // Yellow
+        ("R: Restart   ESC: Main Menu   Q: Quit Game".to_string(), 30.0, Color::srgb(1.0, 1.0, 1.0), -50.0), // White
+    ];
+    for (line, font_size, color, y) in lines {
+        commands.spawn((
+            Text2dBundle {
+                text: Text::from_section(line, TextStyle { font_size, color, ..default() }),
+                transform: Transform::from_translation(Vec3::new(0.0, y, 10.0)),
+                ..default()
+            },
+            TowerResultsUI,
+        ));
+    }
+}
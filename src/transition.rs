@@ -0,0 +1,279 @@
+//! Level transitions.
+//!
+//! Finishing a level starts `AppState::LevelTransition`, which `TransitionTimer`
+//! moves through three phases: the finished level fades to black, the next
+//! level's number, name and flavor text (see `rustbevy_core::naming`) are shown
+//! while the finished level is cleared away and the next one worked out, and a
+//! short countdown runs as the new level fades in, after
+//! which the player has control again (or a modifier draft opens, when one is
+//! due). Working out a generated level (laying out platforms until the fruit
+//! can be reached, then placing enemies, moving platforms and the rest) takes
+//! long enough to hitch a frame, so it runs as a task on the
+//! `AsyncComputeTaskPool` meanwhile. Clearing a big level's hundreds of
+//! entities at once hitches too, so behind the title they go a batch a frame
+//! (platforms and fruit into the `EntityPool`, for the next level to reuse),
+//! and the next level only spawns once the last of them is gone. The title
+//! stays up a moment even when the level is ready sooner, so it doesn't just
+//! flicker past.
+
+use bevy::diagnostic::Diagnostics;
+use bevy::prelude::*;
+use bevy::tasks::{block_on, futures_lite::future, AsyncComputeTaskPool, Task};
+use crate::prelude::*;
+use rustbevy_core::config::{WINDOW_HEIGHT, WINDOW_WIDTH};
+use rustbevy_core::difficulty::DifficultyCurve;
+use rustbevy_core::generation;
+use rustbevy_core::modifiers;
+use rustbevy_core::naming::{flavor_text, level_name};
+use rustbevy_core::reachability::JumpProfile;
+
+use crate::level_loader::HandmadeLevel;
+use crate::menu::ModifierDraft;
+use crate::platform::{plan_classic_level, spawn_planned_level, PlannedLevel};
+use crate::pool::LevelTeardown;
+use crate::profiling::{self, timed};
+use crate::ui::despawn_screen;
+
+pub struct TransitionPlugin;
+
+impl Plugin for TransitionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PendingLevel>()
+            .init_resource::<TransitionTimer>()
+            .add_systems(OnEnter(AppState::LevelTransition), setup_transition_screen)
+            .add_systems(OnExit(AppState::LevelTransition), despawn_screen::<TransitionUI>)
+            .add_systems(Update, (advance_transition, animate_transition).chain().run_if(in_state(AppState::LevelTransition)));
+    }
+}
+
+// Real seconds the finished level takes to fade to black
+const FADE_OUT_SECONDS: f32 = 0.3;
+// Real seconds the title is up for at least, long enough to read the flavor
+// text
+const MIN_TITLE_SECONDS: f32 = 0.8;
+// The countdown runs from this, a number every `COUNTDOWN_STEP_SECONDS`
+const COUNTDOWN_FROM: u32 = 3;
+const COUNTDOWN_STEP_SECONDS: f32 = 0.4;
+// Entities of the finished level cleared away each frame of the title
+const CLEARED_PER_FRAME: usize = 64;
+
+/// The next level, while it's being worked out
+#[derive(Resource, Default)]
+pub struct PendingLevel {
+    // And how many milliseconds it took
+    task: Option<Task<(PlannedLevel, f64)>>,
+}
+
+impl PendingLevel {
+    /// Starts working out level `level` of the run (`handmade` if it has a
+    /// hand-made one) off the main thread
+    pub fn generate(&mut self, handmade: Option<HandmadeLevel>, run_seed: u64, level: u32, difficulty: f32, profile: JumpProfile, curve: DifficultyCurve) {
+        let task = AsyncComputeTaskPool::get().spawn(async move { timed(|| plan_classic_level(handmade, run_seed, level, difficulty, profile, &curve)) });
+        self.task = Some(task);
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TransitionPhase {
+    /// The finished level fading to black
+    #[default]
+    FadeOut,
+    /// "Level N" and its name on black, until the finished level is cleared
+    /// away and the next one is ready
+    Title,
+    /// The next level fading in under the countdown
+    Countdown,
+}
+
+impl TransitionPhase {
+    fn seconds(self) -> f32 {
+        match self {
+            Self::FadeOut => FADE_OUT_SECONDS,
+            Self::Title => MIN_TITLE_SECONDS,
+            Self::Countdown => COUNTDOWN_STEP_SECONDS * COUNTDOWN_FROM as f32,
+        }
+    }
+}
+
+/// Where the transition is, timed in real seconds
+#[derive(Resource)]
+pub struct TransitionTimer {
+    pub phase: TransitionPhase,
+    timer: Timer,
+}
+
+impl Default for TransitionTimer {
+    fn default() -> Self {
+        Self::starting(TransitionPhase::default())
+    }
+}
+
+impl TransitionTimer {
+    fn starting(phase: TransitionPhase) -> Self {
+        Self { phase, timer: Timer::from_seconds(phase.seconds(), TimerMode::Once) }
+    }
+}
+
+#[derive(Component)]
+struct TransitionUI;
+
+#[derive(Component)]
+struct TransitionOverlay;
+
+#[derive(Component)]
+struct TransitionText;
+
+// The level's name and flavor text, under the title
+#[derive(Component)]
+struct TransitionSubtitle;
+
+fn setup_transition_screen(mut commands: Commands, mut transition: ResMut<TransitionTimer>) {
+    *transition = TransitionTimer::default();
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: Color::BLACK.with_alpha(0.0),
+                custom_size: Some(Vec2::new(WINDOW_WIDTH, WINDOW_HEIGHT)),
+                ..default()
+            },
+            transform: Transform::from_translation(Vec3::new(0.0, 0.0, 15.0)),
+            ..default()
+        },
+        TransitionOverlay,
+        TransitionUI,
+        ScreenSpace,
+    ));
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                "",
+                TextStyle {
+                    font_size: 72.0,
+                    color: Color::WHITE,
+                    ..default()
+                },
+            ),
+            transform: Transform::from_translation(Vec3::new(0.0, 0.0, 16.0)),
+            ..default()
+        },
+        TransitionText,
+        TransitionUI,
+    ));
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                "",
+                TextStyle {
+                    font_size: 26.0,
+                    color: Color::srgb(0.8, 0.8, 0.8),
+                    ..default()
+                },
+            )
+            .with_justify(JustifyText::Center),
+            transform: Transform::from_translation(Vec3::new(0.0, -80.0, 16.0)),
+            ..default()
+        },
+        TransitionSubtitle,
+        TransitionUI,
+    ));
+}
+
+// Moves on to the next phase once this one's time is up: clears the finished
+// level away behind the title, spawns the next one once it's gone and the
+// next is ready and puts the player at its start, and hands back control
+// after the countdown
+fn advance_transition(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    mut transition: ResMut<TransitionTimer>,
+    mut pending: ResMut<PendingLevel>,
+    mut teardown: LevelTeardown,
+    mut player_query: Query<(Entity, &mut Transform, &mut Velocity, &mut Grounded, &mut WallContact, &mut JumpState), With<Player>>,
+    mut modifier_draft: ResMut<ModifierDraft>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut diagnostics: Diagnostics,
+) {
+    transition.timer.tick(time.delta());
+    // The new level's entities would be caught up in the clearing, so it
+    // waits for the old ones to be gone
+    let clearing = transition.phase == TransitionPhase::Title && !teardown.is_clear();
+    let cleared = !clearing || {
+        let (cleared, took) = timed(|| info_span!("clear_level").in_scope(|| teardown.clear(&mut commands, CLEARED_PER_FRAME)));
+        diagnostics.add_measurement(&profiling::STREAMING, || took);
+        cleared
+    };
+    if !transition.timer.finished() || !cleared {
+        return;
+    }
+    match transition.phase {
+        TransitionPhase::FadeOut => *transition = TransitionTimer::starting(TransitionPhase::Title),
+        TransitionPhase::Title => {
+            let Some(task) = pending.task.as_mut() else {
+                next_state.set(AppState::Playing);
+                return;
+            };
+            let Some((planned, planned_in)) = block_on(future::poll_once(task)) else {
+                return;
+            };
+            pending.task = None;
+            diagnostics.add_measurement(&profiling::GENERATION, || planned_in);
+
+            let (player_spawn, took) = timed(|| info_span!("spawn_level").in_scope(|| spawn_planned_level(&mut commands, &mut teardown.pool, &planned)));
+            diagnostics.add_measurement(&profiling::STREAMING, || took);
+            // Starts the level afresh, as a respawn does in the core
+            // simulation, so a replay of the run checks out
+            for (player, mut transform, mut velocity, mut grounded, mut wall_contact, mut jump_state) in player_query.iter_mut() {
+                transform.translation = player_spawn.extend(0.0);
+                *velocity = Velocity { x: 0.0, y: 0.0 };
+                grounded.0 = false;
+                *wall_contact = WallContact::default();
+                *jump_state = JumpState::default();
+                commands.entity(player).remove::<(StandingOn, Climbing)>();
+            }
+
+            // Every few levels the run pauses to draft a modifier
+            if modifiers::draft_offered(planned.level) {
+                modifier_draft.choices = modifiers::draft_choices(generation::draft_seed(planned.run_seed, planned.level)).to_vec();
+                next_state.set(AppState::ModifierDraft);
+            } else {
+                *transition = TransitionTimer::starting(TransitionPhase::Countdown);
+            }
+        }
+        TransitionPhase::Countdown => next_state.set(AppState::Playing),
+    }
+}
+
+fn animate_transition(
+    transition: Res<TransitionTimer>,
+    game_state: Res<GameState>,
+    game_rng: Res<GameRng>,
+    mut overlay_query: Query<&mut Sprite, With<TransitionOverlay>>,
+    mut text_query: Query<&mut Text, (With<TransitionText>, Without<TransitionSubtitle>)>,
+    mut subtitle_query: Query<&mut Text, With<TransitionSubtitle>>,
+) {
+    let progress = transition.timer.fraction();
+    let (darkness, line, subtitle) = match transition.phase {
+        TransitionPhase::FadeOut => (progress, String::new(), String::new()),
+        TransitionPhase::Title => {
+            let (run_seed, level) = (game_rng.run_seed(), game_state.level);
+            (1.0, format!("Level {}", level), format!("{}\n{}", level_name(run_seed, level), flavor_text(run_seed, level)))
+        }
+        TransitionPhase::Countdown => {
+            let count = (transition.timer.remaining_secs() / COUNTDOWN_STEP_SECONDS).ceil().max(1.0) as u32;
+            (1.0 - progress, count.to_string(), String::new())
+        }
+    };
+    for mut sprite in overlay_query.iter_mut() {
+        sprite.color.set_alpha(darkness);
+    }
+    for mut text in text_query.iter_mut() {
+        if text.sections[0].value != line {
+            text.sections[0].value = line.clone();
+        }
+    }
+    for mut text in subtitle_query.iter_mut() {
+        if text.sections[0].value != subtitle {
+            text.sections[0].value = subtitle.clone();
+        }
+    }
+}
@@ -0,0 +1,94 @@
+//! Trap platforms in Classic runs (see `rustbevy_core::trap`). They react to
+//! the `PlatformContactEvent`s the collision step sends: a tilting platform
+//! leans, tips over and levels out again, and a launching one hops the player
+//! off sideways. The sideways push lives on the player as `Flung` and fades
+//! out like a party bump.
+
+use bevy::prelude::*;
+use crate::prelude::*;
+use rustbevy_core::trap::fling;
+
+use crate::physics::{apply_gravity, check_collisions, player_body, player_movement, store_player_body};
+
+pub struct TrapPlugin;
+
+impl Plugin for TrapPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, stop_flinging)
+            .add_systems(FixedUpdate, (
+                apply_fling.after(player_movement).before(apply_gravity),
+                react_to_platform_contact.after(check_collisions),
+            ).run_if(in_state(AppState::Playing).and_then(resource_equals(GameMode::Classic))));
+    }
+}
+
+// Sideways push left over from a launching trap
+#[derive(Component)]
+struct Flung(f32);
+
+fn apply_fling(
+    time: Res<Time>,
+    mut player_query: Query<(&mut Flung, &mut Transform, &mut Velocity, &mut Grounded, &Collider), With<Player>>,
+) {
+    for (mut flung, mut transform, mut velocity, mut grounded, collider) in player_query.iter_mut() {
+        let mut body = player_body(&transform, &velocity, &grounded, collider);
+        fling(&mut body, &mut flung.0, time.delta_seconds());
+        store_player_body(&body, &mut transform, &mut velocity, &mut grounded);
+    }
+}
+
+// Lets traps react to whoever stands on them, then moves them along their
+// timers. A tipped platform is drawn leaning and no longer collides (see
+// `check_collisions`).
+pub(crate) fn react_to_platform_contact(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut contact_events: EventReader<PlatformContactEvent>,
+    mut trap_query: Query<(&mut Trap, &mut Transform, &Platform), Without<Player>>,
+    mut player_query: Query<(&mut Transform, &mut Velocity, &mut Grounded, &Collider), With<Player>>,
+    mut sound_events: EventWriter<PlaySoundEvent>,
+    mut particle_events: EventWriter<SpawnParticles>,
+) {
+    for contact in contact_events.read() {
+        let (Ok((mut trap, transform, platform)), Ok((mut player_transform, mut velocity, mut grounded, collider))) =
+            (trap_query.get_mut(contact.platform), player_query.get_mut(contact.player))
+        else {
+            continue;
+        };
+        let spec = platform.spec(&transform, false);
+        let mut body = player_body(&player_transform, &velocity, &grounded, collider);
+        if let Some(speed) = trap.0.contact(&mut body, &spec) {
+            store_player_body(&body, &mut player_transform, &mut velocity, &mut grounded);
+            commands.entity(contact.player).insert(Flung(speed));
+            sound_events.send(PlaySoundEvent { sound_type: SoundType::Jump, position: None });
+        }
+    }
+
+    for (mut trap, mut transform, _) in trap_query.iter_mut() {
+        let was_solid = trap.0.solid();
+        trap.0.update(time.delta_seconds());
+        if was_solid && !trap.0.solid() {
+            particle_events.send(SpawnParticles { effect: ParticleEffect::LandingDust, position: transform.translation.truncate() });
+        }
+        let rotation = Quat::from_rotation_z(trap.0.angle());
+        if transform.rotation != rotation {
+            transform.rotation = rotation;
+        }
+    }
+}
+
+// A push doesn't outlive a death or the level
+fn stop_flinging(
+    mut commands: Commands,
+    mut died_events: EventReader<PlayerDiedEvent>,
+    mut completed_events: EventReader<LevelCompletedEvent>,
+    flung_query: Query<Entity, With<Flung>>,
+) {
+    let died: Vec<Entity> = died_events.read().map(|died| died.player).collect();
+    let completed = completed_events.read().count() > 0;
+    for player in flung_query.iter() {
+        if completed || died.contains(&player) {
+            commands.entity(player).remove::<Flung>();
+        }
+    }
+}
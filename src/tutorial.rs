@@ -0,0 +1,218 @@
+// The tutorial: level 0 of the authored levels (`assets/levels/level_0.ron`),
+// played with a prompt at the bottom of the screen.
+//
+// W on the main menu starts a Classic run on it, whichever campaign is
+// picked. The prompt asks for one thing at a time (walk, jump, climb, drop
+// through, throw a seed, grab the fruit) and only moves on once the player has
+// done it: most steps are trigger zones the player has to get to, laid out to
+// match the level file. Grabbing the fruit finishes the tutorial, and the run
+// carries on with level 1 of the picked campaign.
+
+use bevy::prelude::*;
+use crate::prelude::*;
+use rustbevy_core::zones::Zone;
+
+use crate::chat::chat_closed;
+use crate::favorites::{Favorite, PendingFavorite};
+use crate::fruit::build_next_level;
+use crate::game::{clock_seed, reset_run, setup_game_entities};
+use crate::keybindings::keys_label;
+use crate::level_loader::HandmadeLevels;
+use crate::ui::{centred_column, GameUI, Toasts};
+
+pub struct TutorialPlugin;
+
+impl Plugin for TutorialPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Tutorial>()
+            .add_systems(OnEnter(AppState::MainMenu), end_tutorial)
+            .add_systems(OnEnter(AppState::Playing), end_tutorial_on_new_run.after(reset_run).before(setup_game_entities))
+            .add_systems(Update, (
+                start_tutorial.run_if(in_state(AppState::MainMenu).and_then(chat_closed)),
+                (advance_tutorial, finish_tutorial.before(build_next_level))
+                    .run_if(in_state(AppState::Playing).and_then(tutorial_active)),
+                update_prompt,
+            ));
+    }
+}
+
+// The authored level the tutorial is
+pub const TUTORIAL_LEVEL: u32 = 0;
+
+const PROMPT_COLOR: Color = Color::srgb(1.0, 1.0, 0.6); // Pale yellow
+
+// What the player has to do to get past a step
+enum Goal {
+    // Get somewhere in the level
+    Reach(Zone),
+    // Throw a seed
+    Throw,
+    // Grab the fruit, which finishes the level
+    Finish,
+}
+
+struct Step {
+    prompt: fn(&InputMap) -> String,
+    goal: Goal,
+}
+
+// Standing on the step up from the floor (see the level file)
+const ON_STEP: Zone = Zone { x: 560.0, y: 150.0, width: 240.0, height: 100.0 };
+
+const STEPS: [Step; 6] = [
+    Step {
+        prompt: |input_map| format!(
+            "Press {} / {} to move - walk over to the step",
+            keys_label(input_map.keys(InputAction::MoveLeft)),
+            keys_label(input_map.keys(InputAction::MoveRight))
+        ),
+        goal: Goal::Reach(Zone { x: 275.0, y: 60.0, width: 250.0, height: 100.0 }),
+    },
+    Step {
+        prompt: |input_map| format!("Press {} to jump up onto the step", keys_label(input_map.keys(InputAction::Jump))),
+        goal: Goal::Reach(ON_STEP),
+    },
+    Step {
+        prompt: |input_map| format!("Stand at the ladder and hold {} to climb it", keys_label(input_map.keys(InputAction::Up))),
+        goal: Goal::Reach(Zone { x: 620.0, y: 400.0, width: 400.0, height: 80.0 }),
+    },
+    Step {
+        prompt: |input_map| format!(
+            "Hold {} and press {} to drop back through the ledge",
+            keys_label(input_map.keys(InputAction::Down)),
+            keys_label(input_map.keys(InputAction::Jump))
+        ),
+        goal: Goal::Reach(ON_STEP),
+    },
+    Step { prompt: |_| "Press F to throw a seed - seeds stun enemies".to_string(), goal: Goal::Throw },
+    Step { prompt: |_| "Jump over to the fruit and grab it to finish".to_string(), goal: Goal::Finish },
+];
+
+// Whether the current run is the tutorial, and how far into it the player is
+#[derive(Resource, Default)]
+pub struct Tutorial {
+    pub active: bool,
+    // Index into `STEPS`
+    step: usize,
+}
+
+fn tutorial_active(tutorial: Res<Tutorial>) -> bool {
+    tutorial.active
+}
+
+#[derive(Component)]
+struct TutorialUI;
+
+#[derive(Component)]
+struct PromptText;
+
+fn start_tutorial(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    handmade_levels: Res<HandmadeLevels>,
+    difficulty: Res<Difficulty>,
+    curve: Res<DifficultyCurve>,
+    mut tutorial: ResMut<Tutorial>,
+    mut game_mode: ResMut<GameMode>,
+    mut coop_mode: ResMut<CoopMode>,
+    mut game_rng: ResMut<GameRng>,
+    mut pending_favorite: ResMut<PendingFavorite>,
+    mut toasts: ResMut<Toasts>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::KeyW) {
+        return;
+    }
+    // Its steps only make sense on its own layout
+    if handmade_levels.get(TUTORIAL_LEVEL).is_none() {
+        toasts.push("The tutorial level (assets/levels/level_0.ron) is missing");
+        return;
+    }
+    *tutorial = Tutorial { active: true, step: 0 };
+    *game_mode = GameMode::Classic;
+    coop_mode.0 = false;
+    game_rng.stop_sharing();
+    pending_favorite.0 = Some(Favorite {
+        run_seed: clock_seed(),
+        level: TUTORIAL_LEVEL,
+        difficulty: difficulty.0.difficulty(&curve.0, 1),
+        mode: GameMode::Classic,
+    });
+    next_state.set(AppState::Playing);
+}
+
+fn end_tutorial(mut tutorial: ResMut<Tutorial>) {
+    tutorial.active = false;
+}
+
+// Any other run starting (a restart from the game over screen, say) is a
+// normal one, and has to get its levels from the picked campaign
+fn end_tutorial_on_new_run(mut reset_events: EventReader<GameResetEvent>, pending_favorite: Res<PendingFavorite>, mut tutorial: ResMut<Tutorial>) {
+    if reset_events.read().count() == 0 {
+        return;
+    }
+    if pending_favorite.0.as_ref().is_none_or(|favorite| favorite.level != TUTORIAL_LEVEL) {
+        tutorial.active = false;
+    }
+}
+
+fn advance_tutorial(
+    mut tutorial: ResMut<Tutorial>,
+    player_query: Query<&Transform, With<Player>>,
+    thrown_query: Query<(), Added<ThrownSeed>>,
+    mut sound_events: EventWriter<PlaySoundEvent>,
+) {
+    let Some(step) = STEPS.get(tutorial.step) else {
+        return;
+    };
+    let done = match step.goal {
+        Goal::Reach(zone) => player_query.iter().any(|transform| zone.contains((transform.translation.x, transform.translation.y))),
+        Goal::Throw => !thrown_query.is_empty(),
+        Goal::Finish => false,
+    };
+    if done {
+        tutorial.step += 1;
+        sound_events.send(PlaySoundEvent { sound_type: SoundType::Collect, position: None });
+    }
+}
+
+fn finish_tutorial(mut completed_events: EventReader<LevelCompletedEvent>, mut tutorial: ResMut<Tutorial>, mut toasts: ResMut<Toasts>) {
+    if completed_events.read().count() == 0 {
+        return;
+    }
+    tutorial.active = false;
+    toasts.push("Tutorial complete! On to level 1");
+}
+
+// Shows the current step's prompt for as long as the tutorial runs
+fn update_prompt(
+    mut commands: Commands,
+    tutorial: Res<Tutorial>,
+    app_state: Res<State<AppState>>,
+    input_map: Res<InputMap>,
+    ui_query: Query<Entity, With<TutorialUI>>,
+    mut text_query: Query<&mut Text, With<PromptText>>,
+) {
+    let shown = tutorial.active && *app_state.get() == AppState::Playing;
+    let Some(step) = STEPS.get(tutorial.step).filter(|_| shown) else {
+        for entity in ui_query.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+        return;
+    };
+    let prompt = (step.prompt)(&input_map);
+    let Ok(mut text) = text_query.get_single_mut() else {
+        if ui_query.is_empty() {
+            commands.spawn((centred_column(false, 80.0), TutorialUI, GameUI)).with_children(|parent| {
+                parent.spawn((
+                    TextBundle::from_section(prompt, TextStyle { font_size: 30.0, color: PROMPT_COLOR, ..default() }),
+                    PromptText,
+                    GameUI,
+                ));
+            });
+        }
+        return;
+    };
+    if text.sections[0].value != prompt {
+        text.sections[0].value = prompt;
+    }
+}
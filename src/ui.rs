@@ -0,0 +1,540 @@
+// In-game HUD, toast banners and screen cleanup.
+//
+// The HUD is made of `bevy_ui` nodes, so it follows the window when it's
+// resized: the readouts are anchored to its corners by `hud_layout`, the title
+// and status lines are centred along its top and bottom edges.
+
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use bevy::render::texture::ImageSampler;
+use crate::prelude::*;
+use rustbevy_core::assists::Assists;
+use rustbevy_core::config::{WINDOW_HEIGHT, WINDOW_WIDTH};
+use rustbevy_core::mutators::Mutators;
+use rustbevy_core::projectile::MAX_SEEDS;
+
+use crate::hud_layout::HudElement;
+use crate::player::{DarknessOverlay, DARKNESS_VIEW_RADIUS};
+use crate::projectile::Seeds;
+use crate::rewind::RewindBuffer;
+use crate::score::HighScore;
+
+pub struct UiPlugin;
+
+impl Plugin for UiPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Toasts>()
+            .add_systems(Startup, (setup_toast_banner, setup_heart_icon))
+            .add_systems(Update, ((update_ui, update_lives, update_continues, update_seeds, update_fruit_goal, update_rewind_meter).run_if(in_state(AppState::Playing)), update_toasts));
+    }
+}
+
+const TOAST_SECONDS: f32 = 5.0;
+
+// Lives are drawn as pixel-art hearts, up to `MAX_HEARTS` of them
+const HEART_PIXELS: [&str; 9] = [
+    ".###...###.",
+    "#####.#####",
+    "###########",
+    "###########",
+    ".#########.",
+    "..#######..",
+    "...#####...",
+    "....###....",
+    ".....#.....",
+];
+const HEART_SIZE: Vec2 = Vec2::new(33.0, 27.0);
+const HEART_GAP: f32 = 6.0;
+const HEART_COLOR: Color = Color::srgb(1.0, 0.2, 0.3);
+const MAX_HEARTS: u32 = 10;
+const REWIND_METER_SEGMENTS: usize = 10;
+
+#[derive(Resource)]
+struct HeartIcon(Handle<Image>);
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum LivesShown {
+    Hearts(u32),
+    Infinite,
+}
+
+// UI Components
+#[derive(Component, Default)]
+struct LivesText {
+    // What the hearts show, once drawn
+    shown: Option<LivesShown>,
+}
+
+#[derive(Component)]
+struct LevelText;
+
+#[derive(Component)]
+struct ScoreText;
+
+#[derive(Component)]
+struct HighScoreText;
+
+#[derive(Component)]
+struct TimerText;
+
+#[derive(Component)]
+struct SeedsText;
+
+#[derive(Component)]
+struct FruitsText;
+
+#[derive(Component)]
+struct RewindText;
+
+#[derive(Component)]
+struct ContinuesText;
+
+#[derive(Component)]
+pub struct StatusText;
+
+#[derive(Component)]
+pub struct GameUI;
+
+#[derive(Component)]
+pub struct AdaptiveDifficultyText;
+
+#[derive(Component)]
+pub struct ToastText;
+
+// Banner messages shown one at a time at the top of the screen
+#[derive(Resource, Default)]
+pub struct Toasts {
+    queue: VecDeque<String>,
+    current: Option<(String, Timer)>,
+}
+
+impl Toasts {
+    pub fn push(&mut self, text: impl Into<String>) {
+        self.queue.push_back(text.into());
+    }
+
+    pub fn current(&self) -> Option<&str> {
+        self.current.as_ref().map(|(text, _)| text.as_str())
+    }
+
+    pub fn contains(&self, text: &str) -> bool {
+        self.current() == Some(text) || self.queue.iter().any(|queued| queued == text)
+    }
+
+    pub fn dismiss(&mut self) {
+        self.current = None;
+    }
+
+    // Drops the current banner and everything queued after it
+    pub fn clear(&mut self) {
+        self.current = None;
+        self.queue.clear();
+    }
+}
+
+// A readout's text node; `HudLayout` anchors it to its corner
+fn hud_text(value: impl Into<String>, font_size: f32, color: Color) -> TextBundle {
+    TextBundle::from_section(value, TextStyle { font_size, color, ..default() }).with_style(Style {
+        position_type: PositionType::Absolute,
+        ..default()
+    })
+}
+
+// A full-width row of centred text lines, `edge` pixels in from the top or
+// bottom of the window
+pub fn centred_column(top: bool, edge: f32) -> NodeBundle {
+    NodeBundle {
+        style: Style {
+            position_type: PositionType::Absolute,
+            top: if top { Val::Px(edge) } else { Val::Auto },
+            bottom: if top { Val::Auto } else { Val::Px(edge) },
+            width: Val::Percent(100.0),
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            row_gap: Val::Px(4.0),
+            ..default()
+        },
+        ..default()
+    }
+}
+
+fn spawn_lives(commands: &mut Commands) {
+    commands.spawn((
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                flex_direction: FlexDirection::Row,
+                align_items: AlignItems::Center,
+                column_gap: Val::Px(HEART_GAP),
+                ..default()
+            },
+            ..default()
+        },
+        LivesText::default(),
+        HudElement::Lives,
+        GameUI,
+    ));
+}
+
+pub fn setup_game_ui(mut commands: Commands, assists: Assists, mutators: Mutators, high_score: u32, continues: u32) {
+    // Lives as hearts, filled in by `update_lives`
+    spawn_lives(&mut commands);
+
+    commands.spawn((
+        hud_text("Level: 1", 50.0, Color::srgb(0.0, 1.0, 1.0)), // Bright cyan
+        LevelText,
+        HudElement::Level,
+        GameUI,
+    ));
+
+    commands.spawn((
+        hud_text("Score: 0", 30.0, Color::srgb(1.0, 1.0, 1.0)), // White
+        ScoreText,
+        HudElement::Score,
+        GameUI,
+    ));
+
+    commands.spawn((
+        hud_text(format!("High Score: {}", high_score), 24.0, Color::srgb(1.0, 0.85, 0.3)), // Gold
+        HighScoreText,
+        HudElement::HighScore,
+        GameUI,
+    ));
+
+    // Time spent on the current level
+    commands.spawn((
+        hud_text("Time: 0.0s", 30.0, Color::srgb(1.0, 1.0, 1.0)), // White
+        TimerText,
+        HudElement::Timer,
+        GameUI,
+    ));
+
+    // Seeds left to throw
+    commands.spawn((
+        hud_text(format!("Seeds: {}/{}", MAX_SEEDS, MAX_SEEDS), 24.0, Color::srgb(0.85, 0.7, 0.3)), // Husk brown
+        SeedsText,
+        HudElement::Seeds,
+        GameUI,
+    ));
+
+    // Fruit collected toward the level's goal
+    commands.spawn((
+        hud_text("Fruits: 0/1", 24.0, Color::srgb(1.0, 0.5, 0.0)), // Orange, like the fruit
+        FruitsText,
+        HudElement::Fruits,
+        GameUI,
+    ));
+
+    // What's left of the rewind meter
+    commands.spawn((
+        hud_text(rewind_meter(1.0), 24.0, Color::srgb(0.5, 0.8, 1.0)), // Pale blue
+        RewindText,
+        HudElement::Rewind,
+        GameUI,
+    ));
+
+    // Continues left for when the lives run out
+    commands.spawn((
+        hud_text(format!("Continues: {}", continues), 24.0, Color::srgb(0.8, 0.8, 0.8)), // Light gray
+        ContinuesText,
+        HudElement::Continues,
+        GameUI,
+    ));
+
+    // Active status effects along the bottom (empty while there are none)
+    commands.spawn((centred_column(false, 25.0), GameUI)).with_children(|parent| {
+        parent.spawn((
+            TextBundle::from_section(
+                "",
+                TextStyle {
+                    font_size: 26.0,
+                    color: Color::srgb(0.8, 0.4, 1.0), // Purple
+                    ..default()
+                },
+            ),
+            StatusText,
+            GameUI,
+        ));
+    });
+
+    // Darkness debuff panels, hidden until a cursed fruit brings the dark
+    for panel in [DarknessOverlay::Left, DarknessOverlay::Right, DarknessOverlay::Top, DarknessOverlay::Bottom] {
+        let size = match panel {
+            DarknessOverlay::Left | DarknessOverlay::Right => Vec2::new(WINDOW_WIDTH * 2.0, WINDOW_HEIGHT * 2.0),
+            DarknessOverlay::Top | DarknessOverlay::Bottom => Vec2::new(DARKNESS_VIEW_RADIUS * 2.0, WINDOW_HEIGHT * 2.0),
+        };
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: Color::BLACK,
+                    custom_size: Some(size),
+                    ..default()
+                },
+                transform: Transform::from_translation(Vec3::new(0.0, 0.0, 9.0)),
+                visibility: Visibility::Hidden,
+                ..default()
+            },
+            panel,
+            GameUI,
+        ));
+    }
+
+    // Game title in center top, with the run's assists and mutators under it
+    let mut lines = vec![("BEVY PLATFORMER".to_string(), 40.0, Color::srgb(1.0, 0.5, 0.0))]; // Orange
+    // Assisted runs are marked for the whole run
+    if assists.any() {
+        lines.push((format!("Assists: {}", assists.labels().join(", ")), 22.0, Color::srgb(1.0, 0.7, 0.4))); // Light orange
+    }
+    if mutators.any() {
+        lines.push((format!("Mutators: {}", mutators.labels().join(", ")), 22.0, Color::srgb(0.8, 0.6, 1.0))); // Lavender
+    }
+    commands.spawn((centred_column(true, 25.0), GameUI)).with_children(|parent| {
+        for (line, font_size, color) in lines {
+            parent.spawn((TextBundle::from_section(line, TextStyle { font_size, color, ..default() }), GameUI));
+        }
+    });
+}
+
+fn setup_heart_icon(mut commands: Commands, mut images: ResMut<Assets<Image>>) {
+    let width = HEART_PIXELS[0].len() as u32;
+    let height = HEART_PIXELS.len() as u32;
+    let data = HEART_PIXELS
+        .iter()
+        .flat_map(|row| row.chars())
+        .flat_map(|pixel| if pixel == '#' { [255; 4] } else { [0; 4] })
+        .collect();
+    let mut image = Image::new(
+        Extent3d { width, height, depth_or_array_layers: 1 },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    );
+    // Keep the pixel art crisp when scaled up
+    image.sampler = ImageSampler::nearest();
+    commands.insert_resource(HeartIcon(images.add(image)));
+}
+
+// Redraws the hearts when the number of lives changes
+fn update_lives(
+    mut commands: Commands,
+    game_state: Res<GameState>,
+    assist_mode: Res<AssistMode>,
+    heart_icon: Res<HeartIcon>,
+    mut lives_query: Query<(Entity, &mut LivesText)>,
+) {
+    let wanted = if assist_mode.0.infinite_lives { LivesShown::Infinite } else { LivesShown::Hearts(game_state.lives) };
+    for (entity, mut lives) in lives_query.iter_mut() {
+        if lives.shown == Some(wanted) {
+            continue;
+        }
+        lives.shown = Some(wanted);
+        let (hearts, note) = match wanted {
+            LivesShown::Infinite => (1, Some("x infinite".to_string())),
+            LivesShown::Hearts(count) if count > MAX_HEARTS => (1, Some(format!("x {}", count))),
+            LivesShown::Hearts(count) => (count, None),
+        };
+        commands.entity(entity).despawn_descendants().with_children(|parent| {
+            for _ in 0..hearts {
+                parent.spawn((
+                    ImageBundle {
+                        style: Style {
+                            width: Val::Px(HEART_SIZE.x),
+                            height: Val::Px(HEART_SIZE.y),
+                            ..default()
+                        },
+                        image: UiImage::new(heart_icon.0.clone()).with_color(HEART_COLOR),
+                        ..default()
+                    },
+                    GameUI,
+                ));
+            }
+            if let Some(note) = note {
+                parent.spawn((
+                    TextBundle::from_section(
+                        note,
+                        TextStyle {
+                            font_size: 36.0,
+                            color: Color::srgb(1.0, 1.0, 0.0), // Bright yellow
+                            ..default()
+                        },
+                    ),
+                    GameUI,
+                ));
+            }
+        });
+    }
+}
+
+fn update_ui(
+    game_state: Res<GameState>,
+    mut level_query: Query<&mut Text, (With<LevelText>, Without<ScoreText>)>,
+    mut score_query: Query<&mut Text, (With<ScoreText>, Without<LevelText>)>,
+    high_score: Res<HighScore>,
+    mut high_score_query: Query<&mut Text, (With<HighScoreText>, Without<ScoreText>, Without<LevelText>)>,
+    difficulty: Res<Difficulty>,
+    mut timer_query: Query<&mut Text, (With<TimerText>, Without<HighScoreText>, Without<ScoreText>, Without<LevelText>)>,
+) {
+    // Only update if the game state has changed
+    if game_state.is_changed() {
+        // Update level text
+        if let Ok(mut text) = level_query.get_single_mut() {
+            text.sections[0].value = format!("Level: {}", game_state.level);
+        }
+
+        if let Ok(mut text) = score_query.get_single_mut() {
+            text.sections[0].value = match game_state.combo.multiplier() {
+                1 => format!("Score: {}", game_state.score),
+                multiplier => format!("Score: {}  x{}", game_state.score, multiplier),
+            };
+        }
+    }
+
+    if high_score.is_changed() {
+        if let Ok(mut text) = high_score_query.get_single_mut() {
+            text.sections[0].value = format!("High Score: {}", high_score.best);
+        }
+    }
+
+    if let Ok(mut text) = timer_query.get_single_mut() {
+        let value = format!("Time: {:.1}s", difficulty.0.level_seconds);
+        if text.sections[0].value != value {
+            text.sections[0].value = value;
+        }
+    }
+}
+
+fn update_continues(game_state: Res<GameState>, mut continues_query: Query<&mut Text, With<ContinuesText>>) {
+    if !game_state.is_changed() {
+        return;
+    }
+    let value = format!("Continues: {}", game_state.continues);
+    for mut text in continues_query.iter_mut() {
+        if text.sections[0].value != value {
+            text.sections[0].value = value.clone();
+        }
+    }
+}
+
+// The seed count, with a bar of dots filling up while the next one grows back
+fn update_seeds(seeds: Res<Seeds>, mut seeds_query: Query<&mut Text, With<SeedsText>>) {
+    if !seeds.is_changed() {
+        return;
+    }
+    let pouch = seeds.0;
+    let mut value = format!("Seeds: {}/{}", pouch.seeds, MAX_SEEDS);
+    if pouch.seeds < MAX_SEEDS {
+        value.push(' ');
+        value.push_str(&".".repeat((pouch.regrow_progress() * 4.0) as usize));
+    }
+    for mut text in seeds_query.iter_mut() {
+        if text.sections[0].value != value {
+            text.sections[0].value = value.clone();
+        }
+    }
+}
+
+// A bar of `REWIND_METER_SEGMENTS` blocks, as full as the meter
+fn rewind_meter(meter: f32) -> String {
+    let filled = (meter * REWIND_METER_SEGMENTS as f32).round() as usize;
+    format!("Rewind [{}{}]", "#".repeat(filled), "-".repeat(REWIND_METER_SEGMENTS - filled))
+}
+
+fn update_rewind_meter(rewind: Res<RewindBuffer>, mut rewind_query: Query<&mut Text, With<RewindText>>) {
+    if !rewind.is_changed() {
+        return;
+    }
+    let value = rewind_meter(rewind.0.meter());
+    for mut text in rewind_query.iter_mut() {
+        if text.sections[0].value != value {
+            text.sections[0].value = value.clone();
+        }
+    }
+}
+
+fn update_fruit_goal(fruit_goal: Res<FruitGoal>, mut fruits_query: Query<&mut Text, With<FruitsText>>) {
+    let value = format!("Fruits: {}/{}", fruit_goal.collected.min(fruit_goal.target), fruit_goal.target);
+    for mut text in fruits_query.iter_mut() {
+        if text.sections[0].value != value {
+            text.sections[0].value = value.clone();
+        }
+    }
+}
+
+// The tower's HUD: lives, the score for the height climbed and the best climb
+pub fn setup_tower_ui(mut commands: Commands, best: u32) {
+    spawn_lives(&mut commands);
+
+    commands.spawn((
+        hud_text("Score: 0", 30.0, Color::srgb(1.0, 1.0, 1.0)), // White
+        ScoreText,
+        HudElement::Score,
+        GameUI,
+    ));
+
+    commands.spawn((
+        hud_text(format!("Best: {}", best), 24.0, Color::srgb(1.0, 0.85, 0.3)), // Gold
+        HudElement::HighScore,
+        GameUI,
+    ));
+
+    commands.spawn((centred_column(true, 25.0), GameUI)).with_children(|parent| {
+        parent.spawn((
+            TextBundle::from_section("ENDLESS TOWER", TextStyle { font_size: 40.0, color: Color::srgb(1.0, 0.5, 0.0), ..default() }), // Orange
+            GameUI,
+        ));
+    });
+}
+
+// Despawns a screen's entities when its state is left
+pub fn despawn_screen<T: Component>(mut commands: Commands, query: Query<Entity, With<T>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
+fn setup_toast_banner(mut commands: Commands) {
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font_size: 22.0,
+                color: Color::srgb(1.0, 1.0, 0.6),
+                ..default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(60.0),
+            width: Val::Percent(100.0),
+            justify_content: JustifyContent::Center,
+            ..default()
+        })
+        .with_text_justify(JustifyText::Center),
+        ToastText,
+    ));
+}
+
+pub fn update_toasts(time: Res<Time>, mut toasts: ResMut<Toasts>, mut toast_query: Query<&mut Text, With<ToastText>>) {
+    if let Some((_, timer)) = &mut toasts.current {
+        if timer.tick(time.delta()).finished() {
+            toasts.current = None;
+        }
+    }
+    if toasts.current.is_none() {
+        if let Some(text) = toasts.queue.pop_front() {
+            toasts.current = Some((text, Timer::from_seconds(TOAST_SECONDS, TimerMode::Once)));
+        }
+    }
+
+    let Ok(mut text) = toast_query.get_single_mut() else {
+        return;
+    };
+    let banner = toasts.current().unwrap_or_default();
+    if text.sections[0].value != banner {
+        text.sections[0].value = banner.to_string();
+    }
+}
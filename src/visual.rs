@@ -0,0 +1,285 @@
+// Visual regression harness.
+//
+// `cargo run -- --visual-test` plays through a fixed list of Classic levels
+// (seed, level and camera position each) and compares a screenshot of each one
+// against the golden PNG in `tests/goldens`, within the tolerance in
+// `rustbevy_core::visual`. Game time is paused before a level is built, so
+// nothing moves, and the save directory is swapped for an empty one under
+// `target/visual`, so saved settings and scores can't change the frames. A
+// frame that fails is written next to it with a diff image, and the game exits
+// with an error once every case has run. `--update-goldens` writes new goldens
+// instead, after a rendering change that is meant to change the frames.
+
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::Mutex;
+
+use bevy::prelude::*;
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use bevy::render::texture::{CompressedImageFormats, ImageSampler, ImageType};
+use bevy::render::view::screenshot::ScreenshotManager;
+use bevy::window::PrimaryWindow;
+use crate::prelude::*;
+use rustbevy_core::visual::{compare_frames, diff_frame, Frame};
+
+use crate::favorites::{Favorite, PendingFavorite};
+use crate::ui::{GameUI, Toasts};
+
+pub struct VisualTestPlugin;
+
+impl Plugin for VisualTestPlugin {
+    fn build(&self, app: &mut App) {
+        let (sender, receiver) = channel();
+        app.insert_resource(CapturedFrames { sender, receiver: Mutex::new(receiver) })
+            .add_systems(Update, (
+                start_case.run_if(in_state(AppState::MainMenu)),
+                (capture_case, check_case).chain().run_if(in_state(AppState::Playing)),
+            ).run_if(resource_exists::<VisualTest>))
+            .add_systems(PostUpdate, hold_camera
+                .before(TransformSystem::TransformPropagate)
+                .run_if(resource_exists::<VisualTest>.and_then(in_state(AppState::Playing))));
+    }
+}
+
+const GOLDEN_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/goldens");
+const OUTPUT_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/target/visual");
+// Frames a level is drawn before it's captured, so text and sprites are laid out
+const SETTLE_FRAMES: u32 = 10;
+
+struct VisualCase {
+    name: &'static str,
+    seed: u64,
+    level: u32,
+    camera: Vec2,
+}
+
+const CASES: [VisualCase; 4] = [
+    VisualCase { name: "level_1", seed: 1, level: 1, camera: Vec2::ZERO },
+    VisualCase { name: "level_6", seed: 2024, level: 6, camera: Vec2::ZERO },
+    VisualCase { name: "level_12_corner", seed: 77, level: 12, camera: Vec2::new(-640.0, -360.0) },
+    VisualCase { name: "level_20", seed: 5, level: 20, camera: Vec2::new(320.0, 180.0) },
+];
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Phase {
+    // On the main menu, ready for the next case
+    Idle,
+    Settling(u32),
+    // Waiting for the screenshot
+    Capturing,
+    // Every case has run
+    Done,
+}
+
+#[derive(Resource)]
+pub struct VisualTest {
+    update_goldens: bool,
+    case: usize,
+    phase: Phase,
+    failures: Vec<String>,
+}
+
+#[derive(Resource)]
+struct CapturedFrames {
+    sender: Sender<Image>,
+    receiver: Mutex<Receiver<Image>>,
+}
+
+// `--visual-test` runs the harness, `--update-goldens` runs it writing goldens
+pub fn visual_test_argument() -> Option<VisualTest> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let update_goldens = args.iter().any(|arg| arg == "--update-goldens");
+    (update_goldens || args.iter().any(|arg| arg == "--visual-test")).then(|| VisualTest {
+        update_goldens,
+        case: 0,
+        phase: Phase::Idle,
+        failures: Vec::new(),
+    })
+}
+
+// Points the save directory at an empty folder for the harness. Called before
+// the app is built, since resources load their save files when they're made.
+pub fn isolate_save_directory() {
+    let save_dir = Path::new(OUTPUT_DIR).join("save");
+    let _ = std::fs::remove_dir_all(&save_dir);
+    std::env::set_var("APPDATA", save_dir);
+}
+
+fn image_to_frame(image: Image) -> Result<Frame, String> {
+    let rgba = image.try_into_dynamic().map_err(|error| error.to_string())?.to_rgba8();
+    Ok(Frame { width: rgba.width(), height: rgba.height(), rgba: rgba.into_raw() })
+}
+
+fn load_frame(path: &Path) -> Result<Frame, String> {
+    let bytes = std::fs::read(path).map_err(|error| error.to_string())?;
+    let image = Image::from_buffer(
+        &bytes,
+        ImageType::Extension("png"),
+        CompressedImageFormats::NONE,
+        true,
+        ImageSampler::Default,
+        RenderAssetUsages::MAIN_WORLD,
+    )
+    .map_err(|error| error.to_string())?;
+    image_to_frame(image)
+}
+
+fn save_frame(frame: &Frame, path: &Path) -> Result<(), String> {
+    let image = Image::new(
+        Extent3d { width: frame.width, height: frame.height, depth_or_array_layers: 1 },
+        TextureDimension::D2,
+        frame.rgba.clone(),
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::MAIN_WORLD,
+    );
+    path.parent().map_or(Ok(()), std::fs::create_dir_all).map_err(|error| error.to_string())?;
+    image.try_into_dynamic().map_err(|error| error.to_string())?.save(path).map_err(|error| error.to_string())
+}
+
+fn golden_path(case: &VisualCase) -> PathBuf {
+    Path::new(GOLDEN_DIR).join(format!("{}.png", case.name))
+}
+
+// Starts the next case's level like a favorite level, or exits after the last
+fn start_case(
+    mut test: ResMut<VisualTest>,
+    mut time: ResMut<Time<Virtual>>,
+    curve: Res<DifficultyCurve>,
+    mut game_mode: ResMut<GameMode>,
+    mut game_rng: ResMut<GameRng>,
+    mut pending_favorite: ResMut<PendingFavorite>,
+    mut toasts: ResMut<Toasts>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut exit_events: EventWriter<AppExit>,
+) {
+    if test.phase != Phase::Idle {
+        return;
+    }
+    let Some(case) = CASES.get(test.case) else {
+        if test.failures.is_empty() {
+            println!("visual: all {} cases passed", CASES.len());
+            exit_events.send(AppExit::Success);
+        } else {
+            println!("visual: {} of {} cases failed: {}", test.failures.len(), CASES.len(), test.failures.join(", "));
+            exit_events.send(AppExit::error());
+        }
+        test.phase = Phase::Done;
+        return;
+    };
+
+    time.pause();
+    toasts.clear();
+    *game_mode = GameMode::Classic;
+    game_rng.stop_sharing();
+    pending_favorite.0 = Some(Favorite {
+        run_seed: case.seed,
+        level: case.level,
+        difficulty: curve.0.base(case.level),
+        mode: GameMode::Classic,
+    });
+    test.phase = Phase::Settling(SETTLE_FRAMES);
+    next_state.set(AppState::Playing);
+}
+
+fn hold_camera(test: Res<VisualTest>, mut camera_query: Query<&mut Transform, With<MainCamera>>) {
+    let Some(case) = CASES.get(test.case) else {
+        return;
+    };
+    for mut transform in camera_query.iter_mut() {
+        transform.translation.x = case.camera.x;
+        transform.translation.y = case.camera.y;
+    }
+}
+
+fn capture_case(
+    mut test: ResMut<VisualTest>,
+    mut toasts: ResMut<Toasts>,
+    frames: Res<CapturedFrames>,
+    window_query: Query<Entity, With<PrimaryWindow>>,
+    mut screenshots: ResMut<ScreenshotManager>,
+) {
+    // Banners depend on what happened before the level, not on the level
+    toasts.clear();
+    let Phase::Settling(frames_left) = test.phase else {
+        return;
+    };
+    if frames_left > 0 {
+        test.phase = Phase::Settling(frames_left - 1);
+        return;
+    }
+    let Ok(window) = window_query.get_single() else {
+        return;
+    };
+    let sender = frames.sender.clone();
+    if screenshots.take_screenshot(window, move |image| {
+        let _ = sender.send(image);
+    })
+    .is_ok()
+    {
+        test.phase = Phase::Capturing;
+    }
+}
+
+// Compares the captured frame with its golden (or replaces the golden), then
+// clears the level and goes back to the menu for the next case
+fn check_case(
+    mut commands: Commands,
+    mut test: ResMut<VisualTest>,
+    frames: Res<CapturedFrames>,
+    mut next_state: ResMut<NextState<AppState>>,
+    run_query: Query<Entity, Or<(With<Player>, With<Platform>, With<Pickup>, With<Enemy>, With<Checkpoint>, With<ExitDoor>, With<Key>, With<Ladder>, With<HazardArea>, With<WindZone>, With<PortalEnd>, With<ThrownSeed>, With<GameUI>)>>,
+) {
+    if test.phase != Phase::Capturing {
+        return;
+    }
+    let Some(image) = frames.receiver.lock().ok().and_then(|receiver| receiver.try_recv().ok()) else {
+        return;
+    };
+    let case = &CASES[test.case];
+    if let Err(error) = check_frame(case, image, test.update_goldens) {
+        println!("visual: {} FAILED: {}", case.name, error);
+        test.failures.push(case.name.to_string());
+    }
+
+    for entity in run_query.iter() {
+        commands.entity(entity).despawn();
+    }
+    test.case += 1;
+    test.phase = Phase::Idle;
+    next_state.set(AppState::MainMenu);
+}
+
+fn check_frame(case: &VisualCase, image: Image, update_goldens: bool) -> Result<(), String> {
+    let actual = image_to_frame(image)?;
+    let golden_path = golden_path(case);
+    if update_goldens {
+        save_frame(&actual, &golden_path)?;
+        println!("visual: {} golden written to {}", case.name, golden_path.display());
+        return Ok(());
+    }
+
+    let golden = load_frame(&golden_path).map_err(|error| format!("no golden at {} ({}); run with --update-goldens", golden_path.display(), error))?;
+    let actual_path = Path::new(OUTPUT_DIR).join(format!("{}.png", case.name));
+    let Some(diff) = compare_frames(&golden, &actual) else {
+        save_frame(&actual, &actual_path)?;
+        return Err(format!(
+            "frame is {}x{}, golden is {}x{} (frame saved to {})",
+            actual.width,
+            actual.height,
+            golden.width,
+            golden.height,
+            actual_path.display()
+        ));
+    };
+    let percent = diff.differing_fraction() * 100.0;
+    if diff.passes() {
+        println!("visual: {} ok ({:.3}% of pixels differ, largest difference {})", case.name, percent, diff.max_delta);
+        return Ok(());
+    }
+
+    let diff_path = Path::new(OUTPUT_DIR).join(format!("{}_diff.png", case.name));
+    save_frame(&actual, &actual_path)?;
+    save_frame(&diff_frame(&golden, &actual), &diff_path)?;
+    Err(format!("{:.3}% of pixels differ (frame {}, diff {})", percent, actual_path.display(), diff_path.display()))
+}
@@ -0,0 +1,131 @@
+//! Wind zones and conveyor platforms in Classic levels (see
+//! `rustbevy_core::wind` for where generated levels put them). Every physics
+//! step, between the player's own movement and the collision check, a player
+//! standing on a conveyor is carried along it and one inside a wind zone
+//! drifts with it. Climbing a ladder shelters the player from both.
+//!
+//! Wind is a faint box with small arrows of air drifting through it the way it
+//! blows; a conveyor has chevrons running along its face. With reduced motion
+//! on, the arrows and chevrons hold still. They're drawn by `WindVisualsPlugin`,
+//! so the headless simulation can push players without drawing anything.
+
+use bevy::prelude::*;
+use crate::prelude::*;
+use rustbevy_core::wind::{blow, convey, Wind};
+
+use crate::physics::{check_collisions, player_body, player_movement};
+use crate::settings::Settings;
+
+pub struct WindPlugin;
+
+impl Plugin for WindPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(FixedUpdate, push_players
+            .after(player_movement)
+            .before(check_collisions)
+            .run_if(in_state(AppState::Playing).and_then(resource_equals(GameMode::Classic))));
+    }
+}
+
+/// The drifting arrows of wind zones and the chevrons of conveyors
+pub struct WindVisualsPlugin;
+
+impl Plugin for WindVisualsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, (draw_wind, draw_conveyors)
+            .run_if(in_state(AppState::Playing).and_then(resource_equals(GameMode::Classic))));
+    }
+}
+
+const WIND_COLOR: Color = Color::srgba(0.75, 0.9, 1.0, 0.08);
+const WIND_ARROW_COLOR: Color = Color::srgba(0.85, 0.95, 1.0, 0.45);
+const WIND_ARROW_LENGTH: f32 = 36.0;
+// Room each drifting arrow gets in a wind zone, across and down
+const WIND_ARROW_SPACING: Vec2 = Vec2::new(150.0, 70.0);
+const CHEVRON_COLOR: Color = Color::srgba(0.0, 0.0, 0.0, 0.35);
+const CHEVRON_SPACING: f32 = 24.0;
+const CHEVRON_SIZE: f32 = 5.0;
+
+pub fn spawn_wind(commands: &mut Commands, wind: Wind) {
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite { color: WIND_COLOR, custom_size: Some(Vec2::new(wind.width, wind.height)), ..default() },
+            transform: Transform::from_translation(Vec3::new(wind.x, wind.y, -1.0)),
+            ..default()
+        },
+        WindZone(wind),
+    ));
+}
+
+fn push_players(
+    time: Res<Time>,
+    wind_query: Query<&WindZone>,
+    conveyor_query: Query<&ConveyorPlatform>,
+    mut player_query: Query<(&mut Transform, &Velocity, &Grounded, &Collider, Option<&StandingOn>), (With<Player>, Without<Climbing>)>,
+) {
+    let winds: Vec<Wind> = wind_query.iter().map(|zone| zone.0).collect();
+    for (mut transform, velocity, grounded, collider, standing_on) in player_query.iter_mut() {
+        let mut body = player_body(&transform, velocity, grounded, collider);
+        if let Some(conveyor) = standing_on.and_then(|standing_on| conveyor_query.get(standing_on.0).ok()) {
+            convey(&mut body, &conveyor.0, time.delta_seconds());
+        }
+        blow(&mut body, &winds, time.delta_seconds());
+        transform.translation.x = body.x;
+    }
+}
+
+// How far along a repeating pattern `spacing` long something moving at
+// `speed` is
+fn drift(time: &Time, settings: &Settings, speed: f32, spacing: f32) -> f32 {
+    if settings.reduced_motion {
+        0.0
+    } else {
+        (time.elapsed_seconds() * speed).rem_euclid(spacing)
+    }
+}
+
+// Rows of arrows drifting downwind, wrapping around at the far side of the box
+fn draw_wind(time: Res<Time>, settings: Res<Settings>, mut gizmos: Gizmos, wind_query: Query<&WindZone>) {
+    for zone in wind_query.iter() {
+        let wind = zone.0;
+        let direction = wind.push.signum();
+        let columns = (wind.width / WIND_ARROW_SPACING.x).floor().max(1.0) as usize;
+        let rows = (wind.height / WIND_ARROW_SPACING.y).floor().max(1.0) as usize;
+        let span = wind.width - WIND_ARROW_LENGTH;
+        let left = wind.x - wind.width / 2.0;
+        for row in 0..rows {
+            let y = wind.y - wind.height / 2.0 + (row as f32 + 0.5) * wind.height / rows as f32;
+            // Every other row is staggered, so the arrows don't line up in columns
+            let stagger = if row % 2 == 1 { span / columns as f32 / 2.0 } else { 0.0 };
+            for column in 0..columns {
+                let along = (column as f32 * span / columns as f32 + stagger + drift(&time, &settings, wind.push.abs(), span)).rem_euclid(span);
+                let tail = if direction > 0.0 { left + along } else { left + wind.width - along };
+                gizmos.arrow_2d(Vec2::new(tail, y), Vec2::new(tail + direction * WIND_ARROW_LENGTH, y), WIND_ARROW_COLOR);
+            }
+        }
+    }
+}
+
+// Chevrons along each belt's face, pointing and running the way it carries
+fn draw_conveyors(
+    time: Res<Time>,
+    settings: Res<Settings>,
+    mut gizmos: Gizmos,
+    conveyor_query: Query<(&ConveyorPlatform, &Transform, &Platform)>,
+) {
+    for (conveyor, transform, platform) in conveyor_query.iter() {
+        let direction = conveyor.0.speed.signum();
+        let centre = transform.translation.truncate();
+        let usable = platform.width - CHEVRON_SIZE * 2.0;
+        let count = (usable / CHEVRON_SPACING).floor() as usize;
+        let offset = drift(&time, &settings, conveyor.0.speed.abs(), CHEVRON_SPACING);
+        for index in 0..count {
+            let along = (index as f32 * CHEVRON_SPACING + offset).rem_euclid(count as f32 * CHEVRON_SPACING);
+            let x = centre.x - usable / 2.0 + if direction > 0.0 { along } else { usable - along };
+            let tip = Vec2::new(x + direction * CHEVRON_SIZE, centre.y);
+            for side in [-1.0, 1.0] {
+                gizmos.line_2d(Vec2::new(x - direction * CHEVRON_SIZE, centre.y + side * CHEVRON_SIZE), tip, CHEVRON_COLOR);
+            }
+        }
+    }
+}
@@ -0,0 +1,163 @@
+// Juicy fruit, and the game's first custom shader.
+//
+// Fruit (the level's and the bonus fruit) is drawn with `WobbleMaterial` and
+// `assets/shaders/wobble.wgsl` instead of a plain sprite: it squashes,
+// stretches and ripples at the edges as a player comes near, and pops when it
+// is collected, a copy of it flattening out and fading where it was. At rest
+// it looks just as the sprite did. Fruit is still spawned as a sprite (see
+// `fruit::spawn_fruit`), so the pool and everything else see the same entity;
+// `dress_fruit` swaps the sprite for the material before it's first drawn.
+//
+// Another cosmetic effect can follow the same pattern: a `Material2d` holding
+// the shader's uniforms, the shader under `assets/shaders`, and systems
+// feeding it from the game. There are no springs in the game yet; the
+// material would suit them as it is.
+
+use bevy::prelude::*;
+use bevy::render::render_resource::{AsBindGroup, ShaderRef};
+use bevy::sprite::{Material2d, Material2dPlugin, MaterialMesh2dBundle, Mesh2dHandle};
+use crate::prelude::*;
+use rustbevy_core::config::FRUIT_SIZE;
+
+use crate::fruit::fruit_color;
+
+pub struct WobblePlugin;
+
+impl Plugin for WobblePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(Material2dPlugin::<WobbleMaterial>::default())
+            .init_resource::<WobbleMesh>()
+            // Once the frame's fruit has been spawned or collected
+            .add_systems(PostUpdate, (dress_fruit, wobble_near_players, pop_collected_fruit, animate_pops).chain());
+    }
+}
+
+const SHADER_PATH: &str = "shaders/wobble.wgsl";
+// How far from a fruit's edge a player starts it wobbling
+const PROXIMITY_RANGE: f32 = 120.0;
+const POP_SECONDS: f32 = 0.3;
+
+// The uniforms of `wobble.wgsl`
+#[derive(Asset, TypePath, AsBindGroup, Clone)]
+pub struct WobbleMaterial {
+    #[uniform(0)]
+    pub color: LinearRgba,
+    // The size at rest
+    #[uniform(0)]
+    pub size: Vec2,
+    // 0 with no player near, 1 with one touching
+    #[uniform(0)]
+    pub proximity: f32,
+    // How far through the collection pop, from 0 to 1 (gone)
+    #[uniform(0)]
+    pub pop: f32,
+    // Game seconds, so the wobble stops when the game does
+    #[uniform(0)]
+    pub time: f32,
+    // Keeps fruit side by side from wobbling in step
+    #[uniform(0)]
+    pub phase: f32,
+}
+
+impl WobbleMaterial {
+    pub fn new(color: Color, size: Vec2, phase: f32) -> Self {
+        Self { color: color.into(), size, proximity: 0.0, pop: 0.0, time: 0.0, phase }
+    }
+}
+
+impl Material2d for WobbleMaterial {
+    fn vertex_shader() -> ShaderRef {
+        SHADER_PATH.into()
+    }
+
+    fn fragment_shader() -> ShaderRef {
+        SHADER_PATH.into()
+    }
+}
+
+// The quad every wobbling thing is drawn on; the shader sizes it
+#[derive(Resource)]
+pub struct WobbleMesh(pub Mesh2dHandle);
+
+impl FromWorld for WobbleMesh {
+    fn from_world(world: &mut World) -> Self {
+        let mut meshes = world.resource_mut::<Assets<Mesh>>();
+        Self(meshes.add(Rectangle::new(1.0, 1.0)).into())
+    }
+}
+
+// A collected fruit's pop
+#[derive(Component)]
+struct FruitPop;
+
+// Gives newly spawned fruit (new or out of the pool) a material of its own
+// in place of its sprite
+fn dress_fruit(
+    mut commands: Commands,
+    mesh: Res<WobbleMesh>,
+    mut materials: ResMut<Assets<WobbleMaterial>>,
+    fruit_query: Query<(Entity, &Sprite, &Pickup), Added<Pickup>>,
+) {
+    for (entity, sprite, pickup) in fruit_query.iter() {
+        if pickup.0 != Collectible::Fruit {
+            continue;
+        }
+        let size = sprite.custom_size.unwrap_or(Vec2::splat(FRUIT_SIZE));
+        let material = materials.add(WobbleMaterial::new(sprite.color, size, entity.index() as f32));
+        commands.entity(entity).remove::<Sprite>().insert((mesh.0.clone(), material));
+    }
+}
+
+// Wobbles each fruit by how close the nearest player is. Fruit nobody is near
+// is left alone, so its material doesn't change every frame.
+fn wobble_near_players(
+    time: Res<Time>,
+    mut materials: ResMut<Assets<WobbleMaterial>>,
+    player_query: Query<&Transform, With<Player>>,
+    fruit_query: Query<(&Transform, &Handle<WobbleMaterial>), (With<Pickup>, Without<Player>)>,
+) {
+    for (transform, handle) in fruit_query.iter() {
+        let position = transform.translation.truncate();
+        let nearest = player_query.iter().map(|player| player.translation.truncate().distance(position)).fold(f32::INFINITY, f32::min);
+        let proximity = 1.0 - ((nearest - FRUIT_SIZE) / PROXIMITY_RANGE).clamp(0.0, 1.0);
+        let resting = materials.get(handle).is_some_and(|material| material.proximity == 0.0);
+        if proximity == 0.0 && resting {
+            continue;
+        }
+        if let Some(material) = materials.get_mut(handle) {
+            material.proximity = proximity;
+            material.time = time.elapsed_seconds_wrapped();
+        }
+    }
+}
+
+fn pop_collected_fruit(
+    mut commands: Commands,
+    mesh: Res<WobbleMesh>,
+    mut materials: ResMut<Assets<WobbleMaterial>>,
+    mut fruit_events: EventReader<FruitCollectedEvent>,
+) {
+    for (index, fruit) in fruit_events.read().enumerate() {
+        let mut material = WobbleMaterial::new(fruit_color(fruit.kind), Vec2::splat(FRUIT_SIZE), index as f32);
+        material.proximity = 1.0;
+        commands.spawn((
+            MaterialMesh2dBundle {
+                mesh: mesh.0.clone(),
+                material: materials.add(material),
+                transform: Transform::from_translation(fruit.position.extend(0.0)),
+                ..default()
+            },
+            FruitPop,
+            Transient::new(POP_SECONDS),
+        ));
+    }
+}
+
+fn animate_pops(time: Res<Time>, mut materials: ResMut<Assets<WobbleMaterial>>, pop_query: Query<(&Transient, &Handle<WobbleMaterial>), With<FruitPop>>) {
+    for (transient, handle) in pop_query.iter() {
+        if let Some(material) = materials.get_mut(handle) {
+            material.pop = (transient.age / transient.max_seconds).min(1.0);
+            material.time = time.elapsed_seconds_wrapped();
+        }
+    }
+}
@@ -0,0 +1,283 @@
+// Gameplay invariants, checked against the headless simulation:
+// `cargo test --features headless`
+
+use bevy::prelude::*;
+use bevy_platformer::headless::*;
+use bevy_platformer::prelude::*;
+use bevy_platformer::exit::spawn_exit;
+use bevy_platformer::replay::{ReplayPlayback, ReplayRecorder};
+use rustbevy_core::config::{MAX_FALL_SPEED, PHYSICS_HZ, PLAYER_SPAWN};
+use rustbevy_core::exit::ExitDoor as DoorSpec;
+use rustbevy_core::replay::Replay;
+use rustbevy_core::world::WorldBounds;
+
+const SEEDS: [u64; 3] = [1, 42, 2024];
+
+fn seeded_run(seed: u64) -> App {
+    let mut app = headless_app();
+    app.insert_resource(GameRng::from_seed(seed));
+    start_classic_run(&mut app);
+    app
+}
+
+fn game_state(app: &App) -> &GameState {
+    app.world().resource::<GameState>()
+}
+
+// The solid platforms the player can't be inside: not one-way, and not a
+// crumbled platform or tipped trap
+fn solid_platforms(app: &mut App) -> Vec<PlatformSpec> {
+    let mut query = app.world_mut().query_filtered::<(&Transform, &Platform, Option<&Crumbling>, Option<&Trap>), Without<OneWay>>();
+    query
+        .iter(app.world())
+        .filter(|(_, _, crumbling, trap)| crumbling.is_none_or(|crumbling| crumbling.0.solid()) && trap.is_none_or(|trap| trap.0.solid()))
+        .map(|(transform, platform, _, _)| platform.spec(transform, false))
+        .collect()
+}
+
+fn player_box(app: &mut App) -> Option<(Vec2, f32)> {
+    let mut query = app.world_mut().query_filtered::<(&Transform, &Collider), With<Player>>();
+    query.iter(app.world()).next().map(|(transform, collider)| (transform.translation.truncate(), collider.size))
+}
+
+// Further into a platform than this counts as inside it
+const OVERLAP_TOLERANCE: f32 = 0.5;
+
+fn assert_not_inside_platform(app: &mut App, step: u32) {
+    let Some((player, size)) = player_box(app) else {
+        return;
+    };
+    for platform in solid_platforms(app) {
+        // The part of a ramp under the player
+        let position = Vec2::new(platform.x, platform.top_at(player.x) - platform.height / 2.0);
+        let platform_size = Vec2::new(platform.width, platform.height);
+        let reach = (platform_size + Vec2::splat(size)) / 2.0 - Vec2::splat(OVERLAP_TOLERANCE);
+        let offset = (player - position).abs();
+        assert!(
+            offset.x >= reach.x || offset.y >= reach.y,
+            "step {}: player at {} is inside the platform at {} ({}x{})",
+            step,
+            player,
+            position,
+            platform_size.x,
+            platform_size.y
+        );
+    }
+}
+
+// Runs one way, then the other, hopping and dropping through as it goes
+fn scripted_keys(step: u32) -> (KeyCode, bool, bool) {
+    let direction = if (step / 240).is_multiple_of(2) { KeyCode::ArrowRight } else { KeyCode::ArrowLeft };
+    let jump = step.is_multiple_of(45);
+    let drop = step % 300 == 150;
+    (direction, jump, drop)
+}
+
+// Presses and releases this step's keys; `held` is the direction held so far
+fn play_scripted_step(app: &mut App, held: &mut Option<KeyCode>, step: u32) {
+    let (direction, jump, drop) = scripted_keys(step);
+    if *held != Some(direction) {
+        if let Some(key) = *held {
+            release(app, key);
+        }
+        press(app, direction);
+        *held = Some(direction);
+    }
+    if jump {
+        press(app, KeyCode::Space);
+    } else if step % 45 == 10 {
+        release(app, KeyCode::Space);
+    }
+    if drop {
+        press(app, KeyCode::ArrowDown);
+    } else if step % 300 == 160 {
+        release(app, KeyCode::ArrowDown);
+    }
+    app.update();
+}
+
+#[test]
+fn player_never_tunnels_through_platforms() {
+    for seed in SEEDS {
+        let mut app = seeded_run(seed);
+        let mut held = None;
+        for step in 0..3600 {
+            play_scripted_step(&mut app, &mut held, step);
+            assert_not_inside_platform(&mut app, step);
+        }
+    }
+}
+
+#[test]
+fn the_fastest_fall_still_lands() {
+    // As fast as anything falls
+    let speed = MAX_FALL_SPEED;
+    let fall_per_step = speed / PHYSICS_HZ;
+    let mut app = seeded_run(1);
+    // Onto the starting platform
+    step(&mut app, 60);
+    let player = find_player(&mut app).expect("the run has a player");
+    let (start, _) = player_box(&mut app).unwrap();
+    // Every part of a step, so one of the drops ends a step as deep in the
+    // platform as a fall can
+    for phase in 0..8 {
+        let height = fall_per_step * (1.0 + phase as f32 / 8.0);
+        teleport(&mut app, player, start + Vec2::new(0.0, height));
+        app.world_mut().get_mut::<Velocity>(player).unwrap().y = -speed;
+        for step in 0..30 {
+            app.update();
+            assert_not_inside_platform(&mut app, step);
+        }
+        let (landed, _) = player_box(&mut app).unwrap();
+        assert!((landed.y - start.y).abs() < 1.0, "dropped from {} above, landed at {} (started at {})", height, landed, start);
+        assert!(app.world().get::<Grounded>(player).unwrap().0);
+    }
+}
+
+// Puts the player on the level's fruit; it's collected on the next step
+fn collect_fruit(app: &mut App) {
+    let fruit = app
+        .world_mut()
+        .query_filtered::<&Transform, With<Fruit>>()
+        .iter(app.world())
+        .next()
+        .map(|transform| transform.translation.truncate())
+        .expect("the level has a fruit");
+    let player = find_player(app).expect("the run has a player");
+    teleport(app, player, fruit);
+}
+
+#[test]
+fn collecting_the_fruit_moves_on_a_level() {
+    for seed in SEEDS {
+        let mut app = seeded_run(seed);
+        assert_eq!(game_state(&app).level, 1);
+        collect_fruit(&mut app);
+        step(&mut app, 2);
+        assert_eq!(game_state(&app).level, 2, "seed {}", seed);
+        assert!(game_state(&app).score > 0);
+        // The next level is generated in the background, behind the transition
+        // screen, and play resumes after its fade and countdown
+        step(&mut app, 150);
+        assert_eq!(*app.world().resource::<State<AppState>>().get(), AppState::Playing);
+        assert!(app.world_mut().query_filtered::<(), With<Fruit>>().iter(app.world()).next().is_some());
+    }
+}
+
+#[test]
+fn clearing_a_level_leaves_nothing_behind() {
+    // Seed 123's first level has checkpoints, whose pennants hang off their
+    // poles
+    let mut app = seeded_run(123);
+    assert!(app.world_mut().query_filtered::<(), With<Checkpoint>>().iter(app.world()).next().is_some());
+    // And an open door, with its lock hanging off it, around the player, who
+    // goes through it on the next step
+    let (player, _) = player_box(&mut app).unwrap();
+    spawn_exit(&mut app.world_mut().commands(), &DoorSpec { door: player.into(), keys: Vec::new() });
+    app.world_mut().flush();
+    step(&mut app, 150);
+    assert_eq!(game_state(&app).level, 2);
+    let mut query = app.world_mut().query::<(Entity, &Parent)>();
+    for (entity, parent) in query.iter(app.world()) {
+        assert!(app.world().get_entity(parent.get()).is_some(), "{} was left behind when its parent went", entity);
+    }
+}
+
+#[test]
+fn falling_out_of_the_world_costs_a_life() {
+    let mut app = seeded_run(7);
+    let lives = game_state(&app).lives;
+    let player = find_player(&mut app).expect("the run has a player");
+    teleport(&mut app, player, Vec2::new(0.0, -WorldBounds::CLASSIC.half_height() - 100.0));
+    step(&mut app, 2);
+    assert_eq!(game_state(&app).lives, lives - 1);
+    // Respawned, with the level still going
+    assert!(find_player(&mut app).is_some_and(|respawned| respawned != player));
+    assert_eq!(*app.world().resource::<State<AppState>>().get(), AppState::Playing);
+}
+
+#[test]
+fn the_last_life_ends_the_run() {
+    let mut app = seeded_run(7);
+    app.world_mut().resource_mut::<GameState>().lives = 1;
+    let player = find_player(&mut app).expect("the run has a player");
+    teleport(&mut app, player, Vec2::new(0.0, -WorldBounds::CLASSIC.half_height() - 100.0));
+    step(&mut app, 2);
+    assert_eq!(game_state(&app).lives, 0);
+    assert_eq!(*app.world().resource::<State<AppState>>().get(), AppState::GameOver);
+}
+
+#[test]
+fn a_replay_plays_the_run_back_exactly() {
+    for seed in SEEDS {
+        let mut app = seeded_run(seed);
+        // Enough lives that the scripted keys don't end the run
+        app.world_mut().resource_mut::<GameState>().lives = 100;
+        let mut held = None;
+        for step in 0..1800 {
+            play_scripted_step(&mut app, &mut held, step);
+        }
+        let replay = app.world().resource::<ReplayRecorder>().replay().cloned().expect("the run is recorded");
+        assert!(replay.inputs.len() > 1000, "seed {}: only {} steps recorded", seed, replay.inputs.len());
+        // Through the replay file format, as a saved replay would be
+        let replay = Replay::parse(&replay.to_text()).unwrap();
+
+        let mut playback = headless_app();
+        playback.insert_resource(GameRng::from_seed(seed));
+        playback.insert_resource(ReplayPlayback::new(replay));
+        start_classic_run(&mut playback);
+        playback.world_mut().resource_mut::<GameState>().lives = 100;
+        for _ in 0..3600 {
+            if !playback.world().contains_resource::<ReplayPlayback>() {
+                break;
+            }
+            playback.update();
+        }
+        assert!(!playback.world().contains_resource::<ReplayPlayback>(), "seed {}: the replay never finished", seed);
+
+        let (recorded, _) = player_box(&mut app).unwrap();
+        let (replayed, _) = player_box(&mut playback).unwrap();
+        assert_eq!(recorded, replayed, "seed {}", seed);
+        assert_eq!(game_state(&app).level, game_state(&playback).level);
+        assert_eq!(game_state(&app).lives, game_state(&playback).lives);
+        assert_eq!(game_state(&app).score, game_state(&playback).score);
+    }
+}
+
+#[test]
+fn a_recorded_run_checks_out_in_the_core_simulation() {
+    // Seed 50's scripted run makes it to level 2, comes back at a checkpoint
+    // there and runs out of lives
+    let seed = 50;
+    let mut app = seeded_run(seed);
+    let spawn = Vec2::from(PLAYER_SPAWN);
+    let mut lives = game_state(&app).lives;
+    let mut checkpoint_respawns = 0;
+    let mut held = None;
+    // Saving the replay at game over takes it from the recorder
+    let mut recorded = None;
+    for step in 0..3600 {
+        if *app.world().resource::<State<AppState>>().get() == AppState::GameOver {
+            break;
+        }
+        play_scripted_step(&mut app, &mut held, step);
+        recorded = app.world().resource::<ReplayRecorder>().replay().cloned().or(recorded);
+        let now = game_state(&app).lives;
+        if now < lives && now > 0 && app.world().resource::<RespawnPoint>().0 != spawn {
+            checkpoint_respawns += 1;
+        }
+        lives = now;
+    }
+    assert_eq!(*app.world().resource::<State<AppState>>().get(), AppState::GameOver, "the run never ended");
+    assert!(game_state(&app).level >= 2, "the run never left level 1");
+    assert!(checkpoint_respawns > 0, "the run never came back at a checkpoint");
+
+    // Through the replay file format, as `validate_run` reads it
+    let replay = recorded.expect("the run is recorded");
+    let replay = Replay::parse(&replay.to_text()).unwrap();
+    let outcome = replay.simulate();
+    assert!(outcome.game_over);
+    assert_eq!(outcome.level, game_state(&app).level);
+    assert_eq!(outcome.lives, game_state(&app).lives);
+    assert_eq!(outcome.ticks, replay.inputs.len() as u64);
+}